@@ -0,0 +1,64 @@
+use anyhow::Result;
+use bollard::Docker;
+
+use cratebay_core::builder;
+use cratebay_core::container::format_bytes_human;
+
+use super::{confirm, print_removal, print_structured, OutputFormat};
+
+/// Show how much build cache the engine holds.
+pub async fn du(docker: &Docker, format: &OutputFormat) -> Result<()> {
+    let usage = builder::cache_usage(docker).await?;
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&usage, format);
+    }
+
+    if usage.records == 0 {
+        println!("No build cache");
+        return Ok(());
+    }
+    println!("{:<20} {:>8} SIZE", "TYPE", "RECORDS");
+    for t in &usage.types {
+        println!(
+            "{:<20} {:>8} {}",
+            t.kind,
+            t.records,
+            format_bytes_human(t.size_bytes)
+        );
+    }
+    println!();
+    println!(
+        "Total: {} in {} records ({} in use)",
+        format_bytes_human(usage.total_bytes),
+        usage.records,
+        usage.in_use
+    );
+    println!(
+        "Reclaimable: {}",
+        format_bytes_human(usage.reclaimable_bytes)
+    );
+    Ok(())
+}
+
+/// Remove unused build cache.
+pub async fn prune(
+    docker: &Docker,
+    all: bool,
+    dry_run: bool,
+    yes: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    if dry_run {
+        let report = builder::preview_prune(docker, all).await?;
+        return print_removal(&report, format);
+    }
+    let prompt = if all {
+        "Remove all unused build cache?"
+    } else {
+        "Remove unused build cache that no other record shares?"
+    };
+    confirm(prompt, yes)?;
+
+    let report = builder::prune(all).await?;
+    print_removal(&report, format)
+}
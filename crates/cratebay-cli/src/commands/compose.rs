@@ -0,0 +1,87 @@
+use anyhow::Result;
+
+use cratebay_core::compose;
+use cratebay_core::models::{ComposeDiagnostic, LintSeverity};
+
+use super::{print_structured, OutputFormat};
+
+/// `file:line: severity: message [path]`, like `image build` lint output.
+fn diagnostic_line(file: &str, d: &ComposeDiagnostic) -> String {
+    let location = match d.line {
+        Some(line) => format!("{}:{}", file, line),
+        None => file.to_string(),
+    };
+    let path = if d.path.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", d.path)
+    };
+    format!(
+        "{}: {}: {}{}",
+        location,
+        d.severity.as_str(),
+        d.message,
+        path
+    )
+}
+
+fn error_count(diagnostics: &[ComposeDiagnostic]) -> usize {
+    diagnostics
+        .iter()
+        .filter(|d| d.severity == LintSeverity::Error)
+        .count()
+}
+
+/// Check a compose file. Fails when it has errors.
+pub fn validate(file: Option<&str>, format: &OutputFormat) -> Result<()> {
+    let path = compose::resolve_file(file, &std::env::current_dir()?)?;
+    let report = compose::validate_file(&path)?;
+    if matches!(format, OutputFormat::Table) {
+        for d in &report.diagnostics {
+            println!("{}", diagnostic_line(&report.file, d));
+        }
+    } else {
+        print_structured(&report, format)?;
+    }
+
+    let errors = error_count(&report.diagnostics);
+    if errors > 0 {
+        anyhow::bail!("{} has {} error(s)", report.file, errors);
+    }
+    if matches!(format, OutputFormat::Table) {
+        println!(
+            "{} is valid ({} services)",
+            report.file,
+            report.services.len()
+        );
+    }
+    Ok(())
+}
+
+/// Convert a compose file to Kubernetes manifests, on stdout or to `out`.
+pub fn convert_k8s(file: Option<&str>, out: Option<&str>, format: &OutputFormat) -> Result<()> {
+    let path = compose::resolve_file(file, &std::env::current_dir()?)?;
+    let conversion = compose::convert_kubernetes_file(&path)?;
+    for d in &conversion.diagnostics {
+        eprintln!("{}", diagnostic_line(&conversion.file, d));
+    }
+    let errors = error_count(&conversion.diagnostics);
+    if errors > 0 {
+        anyhow::bail!(
+            "{} has {} error(s); nothing converted",
+            conversion.file,
+            errors
+        );
+    }
+
+    if let Some(out) = out {
+        std::fs::write(out, &conversion.manifest)?;
+        eprintln!("Wrote {} objects to {}", conversion.resources.len(), out);
+    }
+    match format {
+        OutputFormat::Table if out.is_none() => print!("{}", conversion.manifest),
+        OutputFormat::Table => {}
+        _ => print_structured(&conversion, format)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+//! `config.toml` commands.
+
+use anyhow::Result;
+
+use cratebay_core::config::{Config, CONFIG_KEYS};
+
+use super::{print_structured, OutputFormat};
+
+/// Print a single value (nothing if unset).
+pub fn get(key: &str) -> Result<()> {
+    if let Some(value) = Config::load()?.get(key)? {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Set a single value and write the file.
+pub fn set(key: &str, value: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    config.set(key, value)?;
+    config.save()?;
+    match config.get(key)? {
+        Some(value) => println!("{} = {}", key, value),
+        None => println!("{} unset", key),
+    }
+    Ok(())
+}
+
+/// Show every key.
+pub fn list(format: &OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&config, format);
+    }
+    for key in CONFIG_KEYS {
+        println!("{:<18} {}", key, config.get(key)?.unwrap_or_default());
+    }
+    Ok(())
+}
+
+/// Print the config file location.
+pub fn path() {
+    println!("{}", Config::path().display());
+}
@@ -4,14 +4,37 @@ use bollard::errors::Error as BollardError;
 use bollard::Docker;
 use futures_util::StreamExt;
 
+use cratebay_core::bulk::{self, BulkReport};
 use cratebay_core::container;
-use cratebay_core::models::{ContainerCreateRequest, LogOptions};
-use cratebay_core::{validation, AppError};
+use cratebay_core::models::{
+    AuditAction, ContainerCommitRequest, ContainerCreateRequest, ContainerListFilters,
+    ContainerStatus, LogOptions,
+};
+use cratebay_core::prune;
+use cratebay_core::{audit, checkpoint, validation, AppError};
 
-use super::{print_structured, OutputFormat};
+use super::{confirm, print_removal, print_structured, OutputFormat};
 
-pub async fn list(docker: &Docker, all: bool, format: &OutputFormat) -> Result<()> {
-    let containers = container::list(docker, all, None).await?;
+pub async fn list(
+    docker: &Docker,
+    all: bool,
+    latest: bool,
+    quiet: bool,
+    filters: &[String],
+    format: &OutputFormat,
+) -> Result<()> {
+    let mut filters = container::parse_list_filters(filters)?;
+    if latest {
+        filters.limit = Some(1);
+    }
+    let containers = container::list(docker, all || latest, Some(filters)).await?;
+
+    if quiet {
+        for c in containers {
+            println!("{}", c.short_id);
+        }
+        return Ok(());
+    }
 
     match format {
         OutputFormat::Table => {
@@ -37,12 +60,17 @@ pub async fn create(
     working_dir: Option<String>,
     env: Vec<String>,
     no_start: bool,
+    idempotency_key: Option<String>,
+    platform: Option<String>,
     format: &OutputFormat,
 ) -> Result<()> {
     validation::validate_container_name(&name)?;
     if let (Some(cpu), Some(mem)) = (cpu_cores, memory_mb) {
         validation::validate_resource_limits(cpu, mem)?;
     }
+    if let Some(warning) = container::platform_warning(docker, &image, platform.as_deref()).await {
+        eprintln!("Warning: {}", warning);
+    }
 
     let request = ContainerCreateRequest {
         name,
@@ -57,6 +85,8 @@ pub async fn create(
         auto_start: Some(!no_start),
         labels: None,
         template_id: None,
+        idempotency_key,
+        platform: platform.clone(),
     };
 
     let created = match container::create(docker, request.clone()).await {
@@ -64,11 +94,25 @@ pub async fn create(
         Err(e) if is_missing_image_error(&e) => {
             // Mimic `docker run` behavior: auto-pull missing image then retry.
             eprintln!("Image '{}' not found locally, pulling...", image);
-            container::image_pull(docker, &image, None, None).await?;
+            let cancel = super::ctrl_c_token();
+            container::image_pull(
+                docker,
+                &image,
+                None,
+                platform.as_deref(),
+                None,
+                Some(&cancel),
+            )
+            .await?;
             container::create(docker, request).await?
         }
         Err(e) => return Err(e.into()),
     };
+    audit::record(
+        &AuditAction::ContainerCreate,
+        &created.id,
+        Some(&created.name),
+    );
 
     match format {
         OutputFormat::Table => {
@@ -83,20 +127,259 @@ pub async fn create(
     }
 }
 
-pub async fn start(docker: &Docker, id: &str) -> Result<()> {
-    container::start(docker, id).await?;
-    println!("Started {}", id);
+/// Start containers: `ids`, or every created/exited one with `all`.
+pub async fn start(
+    docker: &Docker,
+    ids: Vec<String>,
+    all: bool,
+    parallel: usize,
+    format: &OutputFormat,
+) -> Result<()> {
+    let targets = bulk_targets(
+        docker,
+        ids,
+        all,
+        &[ContainerStatus::Created, ContainerStatus::Exited],
+    )
+    .await?;
+    run_bulk(
+        targets,
+        parallel,
+        "Started",
+        "start",
+        format,
+        |id| async move {
+            container::start(docker, &id).await?;
+            audit::record(&AuditAction::ContainerStart, &id, None);
+            Ok(())
+        },
+    )
+    .await
+}
+
+pub async fn open(docker: &Docker, id: &str, port: Option<u16>, print: bool) -> Result<()> {
+    let url = container::web_url(docker, id, port).await?;
+    if !print {
+        cratebay_core::browser::open_url(&url)?;
+    }
+    println!("{}", url);
+    Ok(())
+}
+
+/// Stop containers: `ids`, or every running/paused one with `all`.
+pub async fn stop(
+    docker: &Docker,
+    ids: Vec<String>,
+    timeout: Option<u32>,
+    all: bool,
+    parallel: usize,
+    format: &OutputFormat,
+) -> Result<()> {
+    let targets = bulk_targets(
+        docker,
+        ids,
+        all,
+        &[ContainerStatus::Running, ContainerStatus::Paused],
+    )
+    .await?;
+    run_bulk(
+        targets,
+        parallel,
+        "Stopped",
+        "stop",
+        format,
+        |id| async move {
+            container::stop(docker, &id, timeout).await?;
+            audit::record(&AuditAction::ContainerStop, &id, None);
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// Restart containers: `ids`, or every running one with `all`.
+pub async fn restart(
+    docker: &Docker,
+    ids: Vec<String>,
+    timeout: Option<u32>,
+    all: bool,
+    parallel: usize,
+    format: &OutputFormat,
+) -> Result<()> {
+    let targets = bulk_targets(docker, ids, all, &[ContainerStatus::Running]).await?;
+    run_bulk(
+        targets,
+        parallel,
+        "Restarted",
+        "restart",
+        format,
+        |id| async move {
+            container::restart(docker, &id, timeout).await?;
+            audit::record(&AuditAction::ContainerRestart, &id, None);
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// `ids`, or with `all` the names of containers in one of `statuses`.
+async fn bulk_targets(
+    docker: &Docker,
+    ids: Vec<String>,
+    all: bool,
+    statuses: &[ContainerStatus],
+) -> Result<Vec<String>> {
+    if !all {
+        return Ok(ids);
+    }
+    let filters = ContainerListFilters {
+        status: Some(statuses.to_vec()),
+        ..Default::default()
+    };
+    let containers = container::list(docker, true, Some(filters)).await?;
+    Ok(containers.into_iter().map(|c| c.name).collect())
+}
+
+/// Run `op` on every target, `parallel` at a time, and report each one.
+/// A single target fails with its own error, so exit codes stay specific;
+/// several fail together once all have been tried.
+async fn run_bulk<F, Fut>(
+    targets: Vec<String>,
+    parallel: usize,
+    done: &str,
+    verb: &str,
+    format: &OutputFormat,
+    op: F,
+) -> Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = Result<(), AppError>>,
+{
+    if targets.is_empty() {
+        if matches!(format, OutputFormat::Table) {
+            println!("No containers to {}", verb);
+        } else {
+            print_structured(&BulkReport::default(), format)?;
+        }
+        return Ok(());
+    }
+    if let [target] = targets.as_slice() {
+        if matches!(format, OutputFormat::Table) {
+            op(target.clone()).await?;
+            println!("{} {}", done, target);
+            return Ok(());
+        }
+    }
+
+    let report = bulk::run(targets, parallel, op).await;
+    if matches!(format, OutputFormat::Table) {
+        for item in &report.items {
+            match &item.error {
+                None => println!("{} {}", done, item.target),
+                Some(e) => eprintln!("Failed to {} {}: {}", verb, item.target, e),
+            }
+        }
+    } else {
+        print_structured(&report, format)?;
+    }
+    if !report.is_success() {
+        anyhow::bail!(
+            "{} of {} containers failed to {}",
+            report.failed,
+            report.items.len(),
+            verb
+        );
+    }
+    Ok(())
+}
+
+pub async fn pause(docker: &Docker, id: &str) -> Result<()> {
+    container::pause(docker, id).await?;
+    audit::record(&AuditAction::ContainerPause, id, None);
+    println!("Paused {}", id);
+    Ok(())
+}
+
+pub async fn unpause(docker: &Docker, id: &str) -> Result<()> {
+    container::unpause(docker, id).await?;
+    audit::record(&AuditAction::ContainerUnpause, id, None);
+    println!("Unpaused {}", id);
+    Ok(())
+}
+
+pub async fn rename(docker: &Docker, id: &str, new_name: &str) -> Result<()> {
+    container::rename(docker, id, new_name).await?;
+    audit::record(&AuditAction::ContainerRename, id, Some(new_name));
+    println!("Renamed {} to {}", id, new_name);
+    Ok(())
+}
+
+pub async fn checkpoint_create(container: &str, name: &str, leave_running: bool) -> Result<()> {
+    checkpoint::create(container, name, leave_running).await?;
+    audit::record(&AuditAction::ContainerCheckpoint, container, Some(name));
+    if leave_running {
+        println!("Checkpointed {} as {}", container, name);
+    } else {
+        println!("Checkpointed {} as {} and stopped it", container, name);
+    }
+    Ok(())
+}
+
+pub async fn checkpoint_list(container: &str, format: &OutputFormat) -> Result<()> {
+    let names = checkpoint::list(container).await?;
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&names, format);
+    }
+    if names.is_empty() {
+        println!("No checkpoints for {}", container);
+    }
+    for name in &names {
+        println!("{}", name);
+    }
+    Ok(())
+}
+
+pub async fn checkpoint_restore(container: &str, name: &str) -> Result<()> {
+    checkpoint::restore(container, name).await?;
+    audit::record(&AuditAction::ContainerRestore, container, Some(name));
+    println!("Restored {} from {}", container, name);
+    Ok(())
+}
+
+pub async fn checkpoint_delete(container: &str, name: &str) -> Result<()> {
+    checkpoint::remove(container, name).await?;
+    println!("Deleted checkpoint {} of {}", name, container);
     Ok(())
 }
 
-pub async fn stop(docker: &Docker, id: &str, timeout: Option<u32>) -> Result<()> {
-    container::stop(docker, id, timeout).await?;
-    println!("Stopped {}", id);
+pub async fn update(
+    docker: &Docker,
+    id: &str,
+    cpus: Option<u32>,
+    memory: Option<u64>,
+    memory_swap: Option<i64>,
+) -> Result<()> {
+    container::update_resources(docker, id, cpus, memory, memory_swap).await?;
+    audit::record(&AuditAction::ContainerUpdate, id, None);
+    println!("Updated {}", id);
     Ok(())
 }
 
-pub async fn delete(docker: &Docker, id: &str, force: bool) -> Result<()> {
+pub async fn delete(
+    docker: &Docker,
+    id: &str,
+    force: bool,
+    dry_run: bool,
+    yes: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    if dry_run {
+        let report = prune::preview_container_delete(docker, &[id.to_string()], force).await?;
+        return print_removal(&report, format);
+    }
+    confirm(&format!("Delete container {}?", id), yes)?;
     container::delete(docker, id, force).await?;
+    audit::record(&AuditAction::ContainerDelete, id, None);
     println!("Deleted {}", id);
     Ok(())
 }
@@ -189,6 +472,47 @@ pub async fn inspect(docker: &Docker, id: &str, format: &OutputFormat) -> Result
     }
 }
 
+pub async fn top(docker: &Docker, id: &str, format: &OutputFormat) -> Result<()> {
+    let processes = container::top(docker, id).await?;
+    match format {
+        OutputFormat::Table => {
+            println!("{:<8} {:<12} {:>6} COMMAND", "PID", "USER", "%CPU");
+            for p in processes {
+                let cpu = p
+                    .cpu_percent
+                    .map(|c| format!("{:.1}", c))
+                    .unwrap_or_else(|| "-".to_string());
+                println!("{:<8} {:<12} {:>6} {}", p.pid, p.user, cpu, p.command);
+            }
+            Ok(())
+        }
+        _ => print_structured(&processes, format),
+    }
+}
+
+pub async fn commit(docker: &Docker, id: &str, request: &ContainerCommitRequest) -> Result<()> {
+    let image_id = container::commit(docker, id, request).await?;
+    println!("{}", image_id);
+    Ok(())
+}
+
+pub async fn export(docker: &Docker, id: &str, output: &str, gzip: bool) -> Result<()> {
+    let gzip = gzip || output.ends_with(".gz") || output.ends_with(".tgz");
+    eprintln!("Exporting {} to {}", id, output);
+
+    let cb: container::SaveProgressCallback = std::sync::Arc::new(|written| {
+        eprint!("\r  {:.1} MB read", written as f64 / (1024.0 * 1024.0));
+    });
+    let size = container::export(docker, id, output, gzip, Some(cb)).await?;
+    eprintln!();
+    println!(
+        "Exported {} ({:.1} MB)",
+        output,
+        size as f64 / (1024.0 * 1024.0)
+    );
+    Ok(())
+}
+
 fn is_missing_image_error(err: &AppError) -> bool {
     match err {
         AppError::Docker(BollardError::DockerResponseServerError {
@@ -0,0 +1,122 @@
+//! `dev` commands: inner-loop helpers for containers under development.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use bollard::Docker;
+
+use cratebay_core::dev_watch::{self, WatchAction, WatchOptions, WatchReport};
+
+/// Changed paths named in a report line; the rest are counted.
+const SHOWN_PATHS: usize = 3;
+
+/// What `--signal`, `--exec` or neither select.
+pub fn action(signal: Option<String>, exec: Option<String>, timeout: Option<u32>) -> WatchAction {
+    match (signal, exec) {
+        (Some(signal), _) => WatchAction::Signal(signal),
+        // Run through a shell so pipes and `&&` work as typed.
+        (None, Some(cmd)) => WatchAction::Exec(vec!["sh".to_string(), "-c".to_string(), cmd]),
+        (None, None) => WatchAction::Restart { timeout },
+    }
+}
+
+/// Restart, signal or exec into `container` whenever `paths` (default: the
+/// current directory) change, until Ctrl-C.
+pub async fn watch(
+    docker: &Docker,
+    container: String,
+    paths: Vec<PathBuf>,
+    action: WatchAction,
+    debounce_ms: u64,
+    ignore: Vec<String>,
+    no_default_ignore: bool,
+) -> Result<()> {
+    let mut names: Vec<String> = if no_default_ignore {
+        Vec::new()
+    } else {
+        dev_watch::DEFAULT_IGNORE
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    };
+    names.extend(ignore);
+    let options = WatchOptions {
+        container,
+        paths: if paths.is_empty() {
+            vec![PathBuf::from(".")]
+        } else {
+            paths
+        },
+        action,
+        debounce: Duration::from_millis(debounce_ms),
+        ignore: names,
+    };
+
+    let what = match &options.action {
+        WatchAction::Restart { .. } => "restarting".to_string(),
+        WatchAction::Signal(signal) => format!("sending {} to", signal),
+        WatchAction::Exec(cmd) => format!("running `{}` in", cmd.last().map_or("", |c| c)),
+    };
+    let paths: Vec<String> = options
+        .paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    println!(
+        "Watching {}; {} {} on change (Ctrl-C to stop)",
+        paths.join(", "),
+        what,
+        options.container
+    );
+
+    dev_watch::run(docker, &options, &super::ctrl_c_token(), |report| {
+        print_report(&options, report)
+    })
+    .await?;
+    Ok(())
+}
+
+fn print_report(options: &WatchOptions, report: &WatchReport) {
+    let mut changed: Vec<String> = report
+        .changed
+        .iter()
+        .take(SHOWN_PATHS)
+        .map(|p| display_path(p))
+        .collect();
+    if report.changed.len() > SHOWN_PATHS {
+        changed.push(format!("+{} more", report.changed.len() - SHOWN_PATHS));
+    }
+    let changed = changed.join(", ");
+    let elapsed = format!("{:.1}s", report.elapsed.as_secs_f64());
+
+    match &report.outcome {
+        Ok(None) => {
+            let done = match &options.action {
+                WatchAction::Signal(signal) => format!("sent {} to", signal),
+                _ => "restarted".to_string(),
+            };
+            println!("{}: {} {} in {}", changed, done, options.container, elapsed);
+        }
+        Ok(Some(result)) => {
+            print!("{}", result.stdout);
+            eprint!("{}", result.stderr);
+            println!(
+                "{}: reload command exited with {} in {}",
+                changed, result.exit_code, elapsed
+            );
+        }
+        Err(e) => eprintln!("{}: {} failed: {}", changed, options.container, e),
+    }
+}
+
+/// `path` relative to the current directory when it is below it.
+fn display_path(path: &Path) -> String {
+    let cwd = std::env::current_dir().and_then(|d| d.canonicalize()).ok();
+    cwd.as_deref()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
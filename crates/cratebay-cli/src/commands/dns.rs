@@ -0,0 +1,73 @@
+//! `dns` commands: resolve containers by name from the host.
+
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use bollard::Docker;
+use tokio::net::UdpSocket;
+
+use cratebay_core::container;
+use cratebay_core::dns::{self, DnsRecords, DNS_DOMAIN};
+use cratebay_core::docker::{self, EngineMode};
+
+/// Serve `<container>.docker.cratebay` until Ctrl-C.
+pub async fn serve(docker: Arc<Docker>, port: Option<u16>) -> Result<()> {
+    let port = port.unwrap_or_else(dns::dns_port);
+    let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .with_context(|| format!("Cannot listen on 127.0.0.1:{}/udp", port))?;
+    let records = Arc::new(DnsRecords::new());
+    let count = records.refresh(&docker).await?;
+    println!(
+        "Resolving *.{} on 127.0.0.1:{} ({} containers with published ports)",
+        DNS_DOMAIN, port, count
+    );
+    if cfg!(target_os = "macos") {
+        println!(
+            "To use it system-wide: sudo mkdir -p /etc/resolver && printf 'nameserver 127.0.0.1\\nport {}\\n' | sudo tee /etc/resolver/{}",
+            port, DNS_DOMAIN
+        );
+    } else {
+        println!("Try: dig @127.0.0.1 -p {} <container>.{}", port, DNS_DOMAIN);
+    }
+    let engine = docker::selected_engine();
+    if engine.mode == EngineMode::Host {
+        eprintln!(
+            "Warning: containers run on {}; their published ports are not on 127.0.0.1",
+            engine
+        );
+    }
+    println!("Press Ctrl-C to stop.");
+
+    // Follow events, re-attaching when the stream drops; events missed
+    // in between are covered by a refresh on every attach.
+    let events = Arc::clone(&records);
+    let events_docker = Arc::clone(&docker);
+    tokio::spawn(async move {
+        loop {
+            events.invalidate();
+            let apply = Arc::clone(&events);
+            if let Err(e) =
+                container::watch_events(&events_docker, move |event| apply.apply_event(&event))
+                    .await
+            {
+                tracing::debug!("Docker event stream ended: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    });
+    let refresh = Arc::clone(&records);
+    tokio::spawn(async move {
+        loop {
+            refresh.stale().await;
+            if let Err(e) = refresh.refresh(&docker).await {
+                tracing::warn!("Refreshing DNS records failed: {}", e);
+            }
+        }
+    });
+
+    dns::serve(socket, &records, &super::ctrl_c_token()).await?;
+    Ok(())
+}
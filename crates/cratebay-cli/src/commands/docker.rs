@@ -0,0 +1,45 @@
+//! `docker context` commands.
+
+use anyhow::Result;
+
+use cratebay_core::docker_context;
+
+use super::{print_structured, OutputFormat};
+
+/// List contexts, marking the active one.
+pub fn context_list(format: &OutputFormat) -> Result<()> {
+    let contexts = docker_context::list()?;
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&contexts, format);
+    }
+    println!("{:<20} {:<36} DOCKER ENDPOINT", "NAME", "DESCRIPTION");
+    for context in contexts {
+        let name = if context.current {
+            format!("{} *", context.name)
+        } else {
+            context.name
+        };
+        println!(
+            "{:<20} {:<36} {}",
+            name,
+            context.description.unwrap_or_default(),
+            context.host.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Switch the active context.
+pub fn context_use(name: &str) -> Result<()> {
+    docker_context::use_context(name)?;
+    println!("Current context is now \"{}\"", name);
+    if std::env::var("DOCKER_CONTEXT").is_ok() {
+        eprintln!("Warning: DOCKER_CONTEXT is set and overrides the selected context");
+    }
+    if std::env::var("DOCKER_HOST").is_ok() {
+        eprintln!(
+            "Warning: DOCKER_HOST (or docker_host in config.toml) takes precedence over contexts"
+        );
+    }
+    Ok(())
+}
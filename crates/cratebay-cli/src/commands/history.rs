@@ -0,0 +1,46 @@
+use anyhow::Result;
+
+use cratebay_core::audit;
+use cratebay_core::models::AuditLogQuery;
+use cratebay_core::storage;
+
+use super::{print_structured, OutputFormat};
+
+/// Show recorded lifecycle operations, newest first.
+pub fn list(
+    target: Option<String>,
+    action: Option<String>,
+    since: Option<&str>,
+    limit: u32,
+    format: &OutputFormat,
+) -> Result<()> {
+    let query = AuditLogQuery {
+        action,
+        target,
+        since: since.map(audit::parse_since).transpose()?,
+        limit,
+    };
+    let conn = storage::init(&storage::default_db_path()?)?;
+    let entries = storage::query_audit_logs(&conn, &query)?;
+    match format {
+        OutputFormat::Table => {
+            println!("{:<20} {:<12} {:<22} TARGET", "TIME", "USER", "ACTION");
+            for e in entries {
+                let time = e
+                    .timestamp
+                    .replace('T', " ")
+                    .trim_end_matches('Z')
+                    .to_string();
+                match e.details {
+                    Some(details) => println!(
+                        "{:<20} {:<12} {:<22} {} ({})",
+                        time, e.user, e.action, e.target, details
+                    ),
+                    None => println!("{:<20} {:<12} {:<22} {}", time, e.user, e.action, e.target),
+                }
+            }
+            Ok(())
+        }
+        _ => print_structured(&entries, format),
+    }
+}
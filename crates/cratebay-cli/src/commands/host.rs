@@ -0,0 +1,67 @@
+//! `host` commands: remote Docker engines registered in `config.toml`.
+
+use anyhow::Result;
+
+use cratebay_core::config::{Config, HostSettings, LOCAL_HOST};
+
+use super::{print_structured, OutputFormat};
+
+/// Register a host.
+pub fn add(name: &str, settings: HostSettings) -> Result<()> {
+    let mut config = Config::load()?;
+    let url = settings.url.clone();
+    config.add_host(name, settings)?;
+    config.save()?;
+    println!("Added host \"{}\" ({})", name, url);
+    Ok(())
+}
+
+/// List registered hosts, marking the active one.
+pub fn list(format: &OutputFormat) -> Result<()> {
+    let hosts = Config::load()?.host_entries();
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&hosts, format);
+    }
+    println!("{:<20} {:<40} AUTH", "NAME", "URL");
+    for host in hosts {
+        let name = if host.active {
+            format!("{} *", host.name)
+        } else {
+            host.name
+        };
+        let auth = if host.settings.uses_tls() {
+            "tls"
+        } else if host.settings.identity_file.is_some() {
+            "ssh key"
+        } else {
+            ""
+        };
+        println!("{:<20} {:<40} {}", name, host.settings.url, auth);
+    }
+    Ok(())
+}
+
+/// Forget a host.
+pub fn remove(name: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    config.remove_host(name)?;
+    config.save()?;
+    println!("Removed host \"{}\"", name);
+    Ok(())
+}
+
+/// Select the default host.
+pub fn use_host(name: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    config.set("active_host", name)?;
+    config.save()?;
+    if name == LOCAL_HOST {
+        println!("No host selected; using DOCKER_HOST, the Docker context or the CrateBay runtime");
+    } else {
+        println!("Current host is now \"{}\"", name);
+    }
+    if std::env::var("CRATEBAY_HOST").is_ok_and(|v| !v.trim().is_empty()) {
+        eprintln!("Warning: CRATEBAY_HOST is set and overrides the selected host");
+    }
+    Ok(())
+}
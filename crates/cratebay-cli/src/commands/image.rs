@@ -1,10 +1,13 @@
 use anyhow::Result;
 use bollard::Docker;
 
-use cratebay_core::container;
-use cratebay_core::models::ImageSearchResult;
+use cratebay_core::models::{
+    AuditAction, DockerfileLint, ImageBuildRequest, ImageLayers, ImageSearchResponse,
+    ImageSearchResult, LintFinding, LintSeverity, RegistryAuth,
+};
+use cratebay_core::{audit, container, dockerfile_lint, prune, registry};
 
-use super::{print_structured, OutputFormat};
+use super::{confirm, print_removal, print_structured, OutputFormat};
 
 pub fn print_search_results(results: &[ImageSearchResult], format: &OutputFormat) -> Result<()> {
     match format {
@@ -30,6 +33,19 @@ pub fn print_search_results(results: &[ImageSearchResult], format: &OutputFormat
     }
 }
 
+/// Print merged multi-source results; failed sources are reported on stderr
+/// in table mode and under `errors` otherwise.
+pub fn print_search_response(response: &ImageSearchResponse, format: &OutputFormat) -> Result<()> {
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(response, format);
+    }
+    print_search_results(&response.results, format)?;
+    for e in &response.errors {
+        eprintln!("warning: {} search failed: {}", e.source, e.error);
+    }
+    Ok(())
+}
+
 pub async fn list(docker: &Docker, format: &OutputFormat) -> Result<()> {
     let images = container::image_list(docker).await?;
 
@@ -62,8 +78,16 @@ pub async fn search(
     print_search_results(&results, format)
 }
 
-pub async fn pull(docker: &Docker, image: &str) -> Result<()> {
-    eprintln!("Pulling image: {}", image);
+pub async fn pull(
+    docker: &Docker,
+    image: &str,
+    mirrors: &[String],
+    platform: Option<&str>,
+) -> Result<()> {
+    match platform {
+        Some(platform) => eprintln!("Pulling image: {} ({})", image, platform),
+        None => eprintln!("Pulling image: {}", image),
+    }
 
     let cb: container::PullProgressCallback = std::sync::Arc::new(|progress| {
         if progress.total_bytes > 0 {
@@ -74,12 +98,440 @@ pub async fn pull(docker: &Docker, image: &str) -> Result<()> {
         }
     });
 
-    container::image_pull(docker, image, None, Some(cb)).await?;
+    let cancel = super::ctrl_c_token();
+    if mirrors.is_empty() {
+        container::image_pull(docker, image, None, platform, Some(cb), Some(&cancel)).await?;
+    } else {
+        container::image_pull_with_mirrors(
+            docker,
+            image,
+            mirrors,
+            platform,
+            Some(cb),
+            Some(&cancel),
+        )
+        .await?;
+    }
     println!("Pulled {}", image);
     Ok(())
 }
 
-pub async fn delete(docker: &Docker, id: &str) -> Result<()> {
+pub async fn push(
+    docker: &Docker,
+    image: &str,
+    username: Option<String>,
+    password_stdin: bool,
+) -> Result<()> {
+    let auth = registry_auth(registry_host(image), username, password_stdin)?;
+
+    eprintln!("Pushing image: {}", image);
+    let cb: container::PullProgressCallback = std::sync::Arc::new(|progress| {
+        if progress.total_bytes > 0 {
+            let pct = (progress.current_bytes as f64 / progress.total_bytes as f64) * 100.0;
+            eprintln!("{:>6.1}% {}", pct, progress.status);
+        } else if !progress.status.is_empty() {
+            eprintln!("{}", progress.status);
+        }
+    });
+
+    container::image_push(docker, image, auth.as_ref(), Some(cb)).await?;
+    audit::record(&AuditAction::ImagePush, image, None);
+    println!("Pushed {}", image);
+    Ok(())
+}
+
+/// Build registry credentials from `--username` plus `--password-stdin` or
+/// `CRATEBAY_REGISTRY_PASSWORD`.
+fn registry_auth(
+    server_address: Option<String>,
+    username: Option<String>,
+    password_stdin: bool,
+) -> Result<Option<RegistryAuth>> {
+    let Some(username) = username else {
+        return Ok(None);
+    };
+    let password = if password_stdin {
+        let mut buf = String::new();
+        std::io::stdin().read_line(&mut buf)?;
+        buf.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        std::env::var("CRATEBAY_REGISTRY_PASSWORD").map_err(|_| {
+            anyhow::anyhow!(
+                "Password required: use --password-stdin or set CRATEBAY_REGISTRY_PASSWORD"
+            )
+        })?
+    };
+    Ok(Some(RegistryAuth {
+        username,
+        password,
+        server_address,
+    }))
+}
+
+pub async fn inspect_remote(
+    reference: &str,
+    platform: Option<&str>,
+    username: Option<String>,
+    password_stdin: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    let auth = registry_auth(registry_host(reference), username, password_stdin)?;
+    let manifest = registry::fetch_manifest(reference, platform, auth).await?;
+
+    match format {
+        OutputFormat::Table => {
+            println!("Reference:  {}", manifest.reference);
+            println!(
+                "Digest:     {}",
+                manifest.digest.as_deref().unwrap_or("<unknown>")
+            );
+            println!("Media type: {}", manifest.media_type);
+            println!("Artifact:   {}", manifest.artifact_type.as_str());
+            println!(
+                "Multi-arch: {}",
+                if manifest.multi_arch { "yes" } else { "no" }
+            );
+
+            println!("\nPLATFORMS");
+            for p in &manifest.platforms {
+                let marker = if manifest.platform.as_deref() == Some(p.platform.as_str()) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{} {:<20} {}", marker, p.platform, p.digest);
+            }
+
+            println!(
+                "\nLAYERS ({}, {} total)",
+                manifest.platform.as_deref().unwrap_or("unknown platform"),
+                container::format_bytes_human(manifest.total_size)
+            );
+            for layer in &manifest.layers {
+                println!(
+                    "  {:<75} {:>10}",
+                    layer.digest,
+                    container::format_bytes_human(layer.size)
+                );
+            }
+
+            if !manifest.labels.is_empty() {
+                println!("\nLABELS");
+                let mut labels: Vec<_> = manifest.labels.iter().collect();
+                labels.sort();
+                for (key, value) in labels {
+                    println!("  {}={}", key, value);
+                }
+            }
+
+            if !manifest.referrers.is_empty() {
+                println!("\nREFERRERS");
+                for referrer in &manifest.referrers {
+                    println!(
+                        "  {:<12} {:<75} {}",
+                        referrer.kind.as_str(),
+                        referrer.digest,
+                        referrer.artifact_type
+                    );
+                }
+            }
+            Ok(())
+        }
+        _ => print_structured(&manifest, format),
+    }
+}
+
+/// Layers of a local image with the instruction and size of each.
+pub async fn history(docker: &Docker, reference: &str, format: &OutputFormat) -> Result<()> {
+    let layers = container::image_history(docker, reference).await?;
+    print_layers(&layers, format)
+}
+
+/// Layers of an image in its registry, without pulling it.
+pub async fn history_remote(
+    reference: &str,
+    platform: Option<&str>,
+    username: Option<String>,
+    password_stdin: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    let auth = registry_auth(registry_host(reference), username, password_stdin)?;
+    let layers = registry::fetch_history(reference, platform, auth).await?;
+    print_layers(&layers, format)
+}
+
+fn print_layers(layers: &ImageLayers, format: &OutputFormat) -> Result<()> {
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(layers, format);
+    }
+    println!("{:>10} {:>6}  CREATED BY", "SIZE", "SHARE");
+    for layer in &layers.layers {
+        let share = if layers.total_size == 0 {
+            0.0
+        } else {
+            layer.size as f64 * 100.0 / layers.total_size as f64
+        };
+        let created_by: String = layer.created_by.chars().take(100).collect();
+        println!(
+            "{:>10} {:>5.1}%  {}",
+            container::format_bytes_human(layer.size),
+            share,
+            if created_by.is_empty() {
+                "<unknown>"
+            } else {
+                &created_by
+            }
+        );
+    }
+    println!(
+        "{:>10} total ({}{})",
+        container::format_bytes_human(layers.total_size),
+        if layers.compressed {
+            "compressed"
+        } else {
+            "unpacked"
+        },
+        layers
+            .platform
+            .as_deref()
+            .map(|p| format!(", {}", p))
+            .unwrap_or_default()
+    );
+    Ok(())
+}
+
+pub async fn info(
+    reference: &str,
+    username: Option<String>,
+    password_stdin: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    let auth = registry_auth(registry_host(reference), username, password_stdin)?;
+    let details = registry::fetch_image_details(reference, auth).await?;
+
+    match format {
+        OutputFormat::Table => {
+            let field = |label: &str, value: Option<String>| {
+                if let Some(value) = value {
+                    println!("{:<14}{}", format!("{}:", label), value);
+                }
+            };
+            field("Reference", Some(details.reference.clone()));
+            field("Title", details.title.clone());
+            field("Description", details.description.clone());
+            field("URL", details.url.clone());
+            field("Source", details.source_url.clone());
+            field("Docs", details.documentation.clone());
+            field("License", details.licenses.clone());
+            field("Stars", details.stars.map(|s| s.to_string()));
+            field("Pulls", details.pulls.map(|p| p.to_string()));
+            field("Last updated", details.last_updated.clone());
+            for warning in &details.warnings {
+                eprintln!("warning: {}", warning);
+            }
+            if let Some(readme) = &details.readme {
+                println!("\n{}", readme.trim_end());
+            }
+            Ok(())
+        }
+        _ => print_structured(&details, format),
+    }
+}
+
+pub async fn tags(
+    reference: &str,
+    options: &registry::TagListOptions,
+    format: &OutputFormat,
+) -> Result<()> {
+    let list = registry::list_tags(reference, options, None).await?;
+
+    match format {
+        OutputFormat::Table => {
+            for tag in &list.tags {
+                match list.artifact_types.get(tag) {
+                    Some(kind) => println!("{}  ({})", tag, kind.as_str()),
+                    None => println!("{}", tag),
+                }
+            }
+            if list.has_more {
+                eprintln!(
+                    "-- page {} ({} of {} tags); use --page {} or --all for more",
+                    list.page,
+                    list.tags.len(),
+                    list.total,
+                    list.page + 1
+                );
+            }
+            Ok(())
+        }
+        _ => print_structured(&list, format),
+    }
+}
+
+pub async fn catalog(
+    registry: &str,
+    options: &registry::CatalogOptions,
+    username: Option<String>,
+    password_stdin: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    let auth = registry_auth(
+        registry::parse_registry_host(registry).ok(),
+        username,
+        password_stdin,
+    )?;
+    let catalog = registry::list_catalog(registry, options, auth).await?;
+
+    match format {
+        OutputFormat::Table => {
+            for repository in &catalog.repositories {
+                println!("{}", repository);
+            }
+            if catalog.has_more {
+                eprintln!(
+                    "-- page {} ({} of {} repositories); use --page {} or --all for more",
+                    catalog.page,
+                    catalog.repositories.len(),
+                    catalog.total,
+                    catalog.page + 1
+                );
+            }
+            Ok(())
+        }
+        _ => print_structured(&catalog, format),
+    }
+}
+
+/// Registry host of an image reference (None for Docker Hub).
+fn registry_host(image: &str) -> Option<String> {
+    let (first, rest) = image.split_once('/')?;
+    let is_host = first.contains('.') || first.contains(':') || first == "localhost";
+    (is_host && !rest.is_empty()).then(|| first.to_string())
+}
+
+pub fn build_request(
+    context: String,
+    tags: Vec<String>,
+    file: Option<String>,
+    build_args: Vec<String>,
+    target: Option<String>,
+    no_cache: bool,
+    pull: bool,
+) -> Result<ImageBuildRequest> {
+    let build_args = build_args
+        .iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((key, value)) => Ok((key.to_string(), value.to_string())),
+            None => anyhow::bail!("Invalid --build-arg '{}': expected KEY=VALUE", arg),
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(ImageBuildRequest {
+        context_dir: context,
+        dockerfile: file,
+        tags,
+        build_args,
+        target,
+        no_cache,
+        pull,
+    })
+}
+
+/// `path:line: severity: message [rule]`, as compilers print diagnostics.
+fn lint_line(lint: &DockerfileLint, f: &LintFinding) -> String {
+    format!(
+        "{}:{}: {}: {} [{}]",
+        lint.dockerfile,
+        f.line,
+        f.severity.as_str(),
+        f.message,
+        f.rule
+    )
+}
+
+/// Lint the Dockerfile without building. Fails when it has errors.
+pub fn lint(request: &ImageBuildRequest, format: &OutputFormat) -> Result<()> {
+    let lint = dockerfile_lint::lint_build(request)?;
+    if matches!(format, OutputFormat::Table) {
+        if lint.findings.is_empty() {
+            println!("{}: no findings", lint.dockerfile);
+        }
+        for f in &lint.findings {
+            println!("{}", lint_line(&lint, f));
+        }
+    } else {
+        print_structured(&lint, format)?;
+    }
+    let errors = lint
+        .findings
+        .iter()
+        .filter(|f| f.severity == LintSeverity::Error)
+        .count();
+    if errors > 0 {
+        anyhow::bail!("{} has {} lint error(s)", lint.dockerfile, errors);
+    }
+    Ok(())
+}
+
+/// Build an image, printing lint findings for the Dockerfile first.
+pub async fn build(docker: &Docker, request: &ImageBuildRequest) -> Result<()> {
+    let lint = dockerfile_lint::lint_build(request)?;
+    for f in &lint.findings {
+        eprintln!("{}", lint_line(&lint, f));
+    }
+
+    let cb: container::BuildOutputCallback = std::sync::Arc::new(|line| eprintln!("{}", line));
+    let image_id = container::image_build(docker, request, Some(cb)).await?;
+
+    if request.tags.is_empty() {
+        println!("Built {}", image_id);
+    } else {
+        println!("Built {} ({})", request.tags.join(", "), image_id);
+    }
+    Ok(())
+}
+
+pub async fn save(docker: &Docker, images: &[String], output: &str, gzip: bool) -> Result<()> {
+    let gzip = gzip || output.ends_with(".gz") || output.ends_with(".tgz");
+    eprintln!("Saving {} to {}", images.join(", "), output);
+
+    let cb: container::SaveProgressCallback = std::sync::Arc::new(|written| {
+        eprint!("\r  {:.1} MB read", written as f64 / (1024.0 * 1024.0));
+    });
+    let size = container::image_save(docker, images, output, gzip, Some(cb)).await?;
+    eprintln!();
+    println!(
+        "Saved {} ({:.1} MB)",
+        output,
+        size as f64 / (1024.0 * 1024.0)
+    );
+    Ok(())
+}
+
+pub async fn import(
+    docker: &Docker,
+    file: &str,
+    reference: Option<&str>,
+    changes: &[String],
+) -> Result<()> {
+    eprintln!("Importing {}", file);
+    let image_id = container::image_import(docker, file, reference, changes).await?;
+    println!("{}", image_id);
+    Ok(())
+}
+
+pub async fn delete(
+    docker: &Docker,
+    id: &str,
+    dry_run: bool,
+    yes: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    if dry_run {
+        let report = prune::preview_image_delete(docker, id).await?;
+        return print_removal(&report, format);
+    }
+    confirm(&format!("Delete image {}?", id), yes)?;
     container::image_remove(docker, id, false).await?;
     println!("Deleted {}", id);
     Ok(())
@@ -1,10 +1,26 @@
+pub mod builder;
+pub mod compose;
+pub mod config;
 pub mod container;
+pub mod dev;
+pub mod dns;
+pub mod docker;
+pub mod history;
+pub mod host;
 pub mod image;
 pub mod mcp;
 pub mod runtime;
 pub mod system;
+pub mod tui;
+pub mod update;
+
+use std::io::{BufRead, IsTerminal, Write};
 
 use clap::ValueEnum;
+use cratebay_core::cancel::CancellationToken;
+use cratebay_core::container::format_bytes_human;
+use cratebay_core::error::AppError;
+use cratebay_core::prune::RemovalReport;
 use serde::Serialize;
 
 #[derive(Clone, Debug, ValueEnum)]
@@ -24,9 +40,89 @@ pub fn print_structured<T: Serialize + ?Sized>(
             Ok(())
         }
         OutputFormat::Yaml => {
-            println!("{}", serde_yaml::to_string(value)?);
+            println!("{}", serde_yaml_ng::to_string(value)?);
             Ok(())
         }
         OutputFormat::Table => anyhow::bail!("table output is not supported for this command"),
     }
 }
+
+/// Token cancelled by the first Ctrl-C, so pulls and downloads stop
+/// cleanly and remove partial files. A second Ctrl-C exits at once.
+pub fn ctrl_c_token() -> CancellationToken {
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            eprintln!("\nCancelling... (press Ctrl-C again to exit immediately)");
+            cancel.cancel();
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
+            }
+        }
+    });
+    token
+}
+
+/// Ask before a destructive action. Skipped with `--yes` and when stdin is
+/// not a terminal, so scripts keep working; declining cancels (exit 130).
+pub fn confirm(prompt: &str, yes: bool) -> anyhow::Result<()> {
+    if yes || !std::io::stdin().is_terminal() {
+        return Ok(());
+    }
+    eprint!("{} [y/N] ", prompt);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    if matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+        Ok(())
+    } else {
+        Err(AppError::Cancelled("Aborted".to_string()).into())
+    }
+}
+
+/// Print what a destructive command removed, or would remove with
+/// `--dry-run`.
+pub fn print_removal(report: &RemovalReport, format: &OutputFormat) -> anyhow::Result<()> {
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(report, format);
+    }
+    if report.items.is_empty() {
+        println!("Nothing to remove");
+        return Ok(());
+    }
+    println!(
+        "{}",
+        if report.dry_run {
+            "Would remove:"
+        } else {
+            "Removed:"
+        }
+    );
+    println!("{:<10} {:<14} {:<30} SIZE", "KIND", "ID", "NAME");
+    for item in &report.items {
+        println!(
+            "{:<10} {:<14} {:<30} {}",
+            item.kind,
+            if item.id.is_empty() { "-" } else { &item.id },
+            if item.name.is_empty() {
+                "-"
+            } else {
+                &item.name
+            },
+            item.size_bytes
+                .map(format_bytes_human)
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+    println!(
+        "Total {}: {}",
+        if report.dry_run {
+            "reclaimable"
+        } else {
+            "reclaimed"
+        },
+        format_bytes_human(report.reclaimed_bytes)
+    );
+    Ok(())
+}
@@ -1,48 +1,111 @@
 //! Runtime management commands.
 
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Result;
+use bollard::Docker;
+use ratatui::crossterm::terminal as tty;
+use serde::Serialize;
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+
+use cratebay_core::models::AuditAction;
+use cratebay_core::prune::{RemovalItem, RemovalReport, ResourceKind};
+use cratebay_core::runtime::ops::{self, RuntimeOp};
+use cratebay_core::runtime::{
+    self, balloon, clock, forward, BootConfig, RuntimeConfig, RuntimeState,
+};
+use cratebay_core::terminal::{self, TerminalEvent, TerminalSession, TerminalTarget};
+use cratebay_core::{audit, container, images};
 
-use cratebay_core::runtime::{self, RuntimeState};
+use super::{confirm, print_removal, print_structured, OutputFormat};
+
+/// Serializable view of `runtime status`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RuntimeStatusView {
+    state: String,
+    error: Option<String>,
+    docker_responsive: Option<bool>,
+    docker_version: Option<String>,
+    uptime_seconds: Option<u64>,
+    socket_path: String,
+    #[serde(skip_serializing_if = "BootConfig::is_empty")]
+    boot: BootConfig,
+}
 
 /// Show current runtime status.
-pub async fn status() -> Result<()> {
+pub async fn status(format: &OutputFormat) -> Result<()> {
     let runtime = runtime::create_runtime_manager();
     let state = runtime.get_state().await?;
 
-    let state_str = match &state {
-        RuntimeState::None => "not provisioned",
-        RuntimeState::Provisioned => "provisioned (stopped)",
-        RuntimeState::Starting => "starting",
-        RuntimeState::Ready => "ready",
-        RuntimeState::Stopping => "stopping",
-        RuntimeState::Stopped => "stopped",
-        RuntimeState::Error(msg) => {
-            println!("Runtime: error — {}", msg);
-            return Ok(());
-        }
+    let (state_str, error) = match &state {
+        RuntimeState::None => ("not provisioned", None),
+        RuntimeState::Provisioned => ("provisioned (stopped)", None),
+        RuntimeState::Starting => ("starting", None),
+        RuntimeState::Ready => ("ready", None),
+        RuntimeState::Stopping => ("stopping", None),
+        RuntimeState::Stopped => ("stopped", None),
+        RuntimeState::Error(msg) => ("error", Some(msg.clone())),
     };
 
-    println!("Runtime: {}", state_str);
+    let mut view = RuntimeStatusView {
+        state: state_str.to_string(),
+        error,
+        docker_responsive: None,
+        docker_version: None,
+        uptime_seconds: None,
+        socket_path: runtime.docker_socket_path().display().to_string(),
+        boot: RuntimeConfig::load().boot,
+    };
 
     // If ready, also show Docker info
     if state == RuntimeState::Ready {
         let health = runtime.health_check().await?;
-        if health.docker_responsive {
+        view.docker_responsive = Some(health.docker_responsive);
+        view.docker_version = health.docker_version;
+        view.uptime_seconds = health.uptime_seconds;
+    }
+
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&view, format);
+    }
+
+    if let Some(msg) = &view.error {
+        println!("Runtime: error — {}", msg);
+        return Ok(());
+    }
+
+    println!("Runtime: {}", view.state);
+    match view.docker_responsive {
+        Some(true) => {
             println!("Docker: responsive");
-            if let Some(ver) = &health.docker_version {
+            if let Some(ver) = &view.docker_version {
                 println!("Docker version: {}", ver);
             }
-        } else {
-            println!("Docker: not responsive");
-        }
-        if let Some(uptime) = health.uptime_seconds {
-            let mins = uptime / 60;
-            let secs = uptime % 60;
-            println!("Uptime: {}m {}s", mins, secs);
         }
+        Some(false) => println!("Docker: not responsive"),
+        None => {}
+    }
+    if let Some(uptime) = view.uptime_seconds {
+        let mins = uptime / 60;
+        let secs = uptime % 60;
+        println!("Uptime: {}m {}s", mins, secs);
     }
 
-    println!("Socket: {}", runtime.docker_socket_path().display());
+    println!("Socket: {}", view.socket_path);
+    if let Some(kernel) = &view.boot.kernel {
+        println!("Kernel: {}", kernel.display());
+    }
+    if let Some(initrd) = &view.boot.initrd {
+        println!("Initrd: {}", initrd.display());
+    }
+    if let Some(cmdline) = &view.boot.cmdline {
+        println!("Kernel cmdline: {}", cmdline);
+    }
 
     Ok(())
 }
@@ -50,6 +113,7 @@ pub async fn status() -> Result<()> {
 /// Start the built-in runtime (provision if needed).
 pub async fn start() -> Result<()> {
     let runtime = runtime::create_runtime_manager();
+    let _op = ops::begin(RuntimeOp::Start, ops::DEFAULT_OP_WAIT).await?;
 
     let state = runtime.get_state().await?;
     if state == RuntimeState::Ready {
@@ -77,6 +141,7 @@ pub async fn start() -> Result<()> {
 
     // Start
     runtime.start().await?;
+    audit::record(&AuditAction::RuntimeStart, "runtime", None);
     println!("Runtime started.");
 
     // Wait for Docker
@@ -101,6 +166,7 @@ pub async fn start() -> Result<()> {
 /// Stop the built-in runtime.
 pub async fn stop() -> Result<()> {
     let runtime = runtime::create_runtime_manager();
+    let _op = ops::begin(RuntimeOp::Stop, ops::DEFAULT_OP_WAIT).await?;
 
     let state = runtime.get_state().await?;
     match state {
@@ -113,6 +179,7 @@ pub async fn stop() -> Result<()> {
 
     println!("Stopping CrateBay runtime...");
     runtime.stop().await?;
+    audit::record(&AuditAction::RuntimeStop, "runtime", None);
     println!("Runtime stopped.");
     Ok(())
 }
@@ -120,6 +187,7 @@ pub async fn stop() -> Result<()> {
 /// Pre-download runtime image without starting.
 pub async fn provision() -> Result<()> {
     let runtime = runtime::create_runtime_manager();
+    let _op = ops::begin(RuntimeOp::Provision, ops::DEFAULT_OP_WAIT).await?;
 
     let state = runtime.get_state().await?;
     if state != RuntimeState::None {
@@ -141,3 +209,477 @@ pub async fn provision() -> Result<()> {
     println!("Provisioning complete.");
     Ok(())
 }
+
+/// Change the runtime CPU/memory allocation and GPU device.
+pub async fn set(
+    cpus: Option<u32>,
+    memory_mb: Option<u64>,
+    gpu: Option<bool>,
+    boot: BootOverrides,
+) -> Result<()> {
+    if cpus.is_none() && memory_mb.is_none() && gpu.is_none() && boot.is_empty() {
+        anyhow::bail!(
+            "Nothing to change: pass --cpus, --memory, --gpu, --cmdline, --kernel and/or --initrd"
+        );
+    }
+
+    let runtime = runtime::create_runtime_manager();
+    if gpu == Some(true) {
+        let hypervisor = runtime.hypervisor();
+        match hypervisor.gpu_available {
+            Some(true) => {}
+            Some(false) => anyhow::bail!(
+                "No GPU for the runtime: {} needs a DRM render node (/dev/dri/renderD*)",
+                hypervisor.backend
+            ),
+            None => anyhow::bail!(
+                "The {} runtime has no GPU setting; it already sees the host GPU",
+                hypervisor.backend
+            ),
+        }
+    }
+
+    let _op = ops::begin(RuntimeOp::UpdateResources, ops::DEFAULT_OP_WAIT).await?;
+    if cpus.is_some() || memory_mb.is_some() {
+        let config = runtime.update_resources(cpus, memory_mb).await?;
+        println!(
+            "Runtime configured with {} CPUs and {} MB memory. Applies on next start.",
+            config.cpu_cores, config.memory_mb
+        );
+    }
+    if let Some(gpu) = gpu {
+        let mut config = RuntimeConfig::load();
+        config.gpu = gpu;
+        config.save()?;
+        println!(
+            "Runtime GPU {}. Applies on next start.",
+            if gpu { "enabled" } else { "disabled" }
+        );
+    }
+    if !boot.is_empty() {
+        let mut config = RuntimeConfig::load();
+        boot.apply_to(&mut config.boot)?;
+        config.boot.validate()?;
+        config.save()?;
+        println!("Runtime boot settings updated. Applies on next start.");
+    }
+    Ok(())
+}
+
+/// Boot overrides passed to `runtime set`; an empty value clears one.
+#[derive(Debug, Default)]
+pub struct BootOverrides {
+    pub cmdline: Option<String>,
+    pub kernel: Option<String>,
+    pub initrd: Option<String>,
+}
+
+impl BootOverrides {
+    fn is_empty(&self) -> bool {
+        self.cmdline.is_none() && self.kernel.is_none() && self.initrd.is_none()
+    }
+
+    fn apply_to(self, boot: &mut BootConfig) -> Result<()> {
+        if let Some(cmdline) = self.cmdline {
+            let cmdline = cmdline.trim();
+            boot.cmdline = (!cmdline.is_empty()).then(|| cmdline.to_string());
+        }
+        if let Some(kernel) = self.kernel {
+            boot.kernel = boot_file(&kernel)?;
+        }
+        if let Some(initrd) = self.initrd {
+            boot.initrd = boot_file(&initrd)?;
+        }
+        Ok(())
+    }
+}
+
+/// Absolute path of a kernel or initrd override (`None` for "").
+fn boot_file(path: &str) -> Result<Option<std::path::PathBuf>> {
+    if path.is_empty() {
+        return Ok(None);
+    }
+    let path = std::fs::canonicalize(path)
+        .map_err(|e| anyhow::anyhow!("Cannot use {} as a boot file: {}", path, e))?;
+    Ok(Some(path))
+}
+
+/// Grow the runtime disk to `disk_gb` gigabytes.
+pub async fn resize(disk_gb: u32) -> Result<()> {
+    let runtime = runtime::create_runtime_manager();
+    let _op = ops::begin(RuntimeOp::ResizeDisk, ops::DEFAULT_OP_WAIT).await?;
+    runtime.resize_disk(disk_gb).await?;
+    println!(
+        "Runtime disk resized to {} GB. The filesystem grows on next start.",
+        disk_gb
+    );
+    Ok(())
+}
+
+/// Show live runtime VM metrics.
+pub async fn stats(docker: &Docker, format: &OutputFormat) -> Result<()> {
+    let m = runtime::metrics::collect(docker).await?;
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&m, format);
+    }
+
+    println!("CPU: {:.1}% of {} cores", m.cpu_percent, m.cpu_cores);
+    println!(
+        "Memory: {} / {} MB ({:.1}%)",
+        m.memory_used_mb,
+        m.memory_total_mb,
+        percent(m.memory_used_mb as f32, m.memory_total_mb as f32)
+    );
+    println!(
+        "Host memory: {} / {} MB allocated ({} MB returned by the balloon)",
+        m.memory_allocated_mb.saturating_sub(m.memory_balloon_mb),
+        m.memory_allocated_mb,
+        m.memory_balloon_mb
+    );
+    println!(
+        "Disk: {:.1} / {:.1} GB ({:.1}%)",
+        m.disk_used_gb,
+        m.disk_total_gb,
+        percent(m.disk_used_gb, m.disk_total_gb)
+    );
+    println!(
+        "Network: {} received, {} sent",
+        container::format_bytes_human(m.net_rx_bytes),
+        container::format_bytes_human(m.net_tx_bytes)
+    );
+    println!("Containers running: {}", m.container_count);
+    Ok(())
+}
+
+fn percent(used: f32, total: f32) -> f32 {
+    if total > 0.0 {
+        used / total * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Run a command inside the runtime VM. In table mode the process exits
+/// with the command's exit code.
+pub async fn exec(
+    docker: &Docker,
+    command: Vec<String>,
+    timeout_secs: Option<u64>,
+    format: &OutputFormat,
+) -> Result<()> {
+    let result =
+        container::runtime_exec(docker, command, timeout_secs.map(Duration::from_secs)).await?;
+    match format {
+        OutputFormat::Table => {
+            print!("{}", result.stdout);
+            eprint!("{}", result.stderr);
+            std::process::exit(result.exit_code as i32);
+        }
+        _ => print_structured(&result, format),
+    }
+}
+
+/// Interactive shell on the runtime VM, through the same privileged
+/// `nsenter` helper as `runtime exec`. Exits with the shell's exit code.
+pub async fn shell(docker: &Docker, shell: Option<String>) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!(
+            "cratebay runtime shell needs an interactive terminal; use `cratebay runtime exec` to run a command"
+        );
+    }
+    let (cols, rows) = tty::size().unwrap_or((80, 24));
+    let (exited_tx, mut exited) = mpsc::unbounded_channel();
+    let session = terminal::open(
+        docker,
+        TerminalTarget::Runtime,
+        shell,
+        cols,
+        rows,
+        move |event| match event {
+            TerminalEvent::Output(text) => {
+                let mut stdout = std::io::stdout().lock();
+                let _ = stdout.write_all(text.as_bytes());
+                let _ = stdout.flush();
+            }
+            TerminalEvent::Exit(code) => {
+                let _ = exited_tx.send(code);
+            }
+        },
+    )
+    .await?;
+
+    tty::enable_raw_mode()?;
+    let result = relay_shell(docker, &session, &mut exited).await;
+    let _ = tty::disable_raw_mode();
+    session.close(docker).await?;
+    // Exit here: a pending stdin read would keep the runtime from shutting down.
+    std::process::exit(result?.unwrap_or(1) as i32);
+}
+
+/// Forward stdin and window size changes to `session` until the shell exits.
+async fn relay_shell(
+    docker: &Docker,
+    session: &TerminalSession,
+    exited: &mut mpsc::UnboundedReceiver<Option<i64>>,
+) -> Result<Option<i64>> {
+    #[cfg(unix)]
+    let mut window_changes =
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::window_change())?;
+    let mut stdin = tokio::io::stdin();
+    let mut stdin_open = true;
+    let mut buf = [0u8; 1024];
+    loop {
+        let resized = async {
+            #[cfg(unix)]
+            window_changes.recv().await;
+            #[cfg(not(unix))]
+            std::future::pending::<()>().await;
+        };
+        tokio::select! {
+            code = exited.recv() => return Ok(code.flatten()),
+            read = stdin.read(&mut buf), if stdin_open => match read? {
+                0 => stdin_open = false,
+                n => session.write(&buf[..n]).await?,
+            },
+            _ = resized => {
+                if let Ok((cols, rows)) = tty::size() {
+                    session.resize(docker, cols, rows).await?;
+                }
+            }
+        }
+    }
+}
+
+pub async fn forward(docker: &Docker, ports: &[String]) -> Result<()> {
+    let forwards = ports
+        .iter()
+        .map(|spec| forward::parse(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    let forwarder = forward::Forwarder::bind(docker, &forwards).await?;
+    for (addr, guest_port) in forwarder.addrs() {
+        println!("Forwarding {} -> runtime:{}", addr, guest_port);
+    }
+    println!("Press Ctrl-C to stop.");
+    forwarder.run(&super::ctrl_c_token()).await?;
+    Ok(())
+}
+
+/// Move the runtime memory balloon to `target_mb`, or where the policy
+/// puts it.
+pub async fn balloon(docker: &Docker, target_mb: Option<u64>) -> Result<()> {
+    let runtime = runtime::create_runtime_manager();
+    match target_mb {
+        Some(target_mb) => {
+            let allocated_mb = RuntimeConfig::load().memory_mb;
+            if !(balloon::MIN_TARGET_MB.min(allocated_mb)..=allocated_mb).contains(&target_mb) {
+                anyhow::bail!(
+                    "Balloon target must be between {} and {} MB",
+                    balloon::MIN_TARGET_MB.min(allocated_mb),
+                    allocated_mb
+                );
+            }
+            runtime.set_balloon_target(target_mb).await?;
+            println!("Runtime balloon target set to {} MB.", target_mb);
+        }
+        None => match balloon::rebalance(runtime.as_ref(), docker).await? {
+            Some(target_mb) => println!("Runtime balloon target set to {} MB.", target_mb),
+            None => println!("Runtime balloon already matches guest usage."),
+        },
+    }
+    Ok(())
+}
+
+/// Attach the terminal to the runtime VM's serial console.
+pub async fn attach() -> Result<()> {
+    let console = runtime::console::Console::connect().await?;
+    eprintln!("Attached to the runtime console. Press Ctrl-] to detach.");
+    console.attach().await?;
+    eprintln!("\r\nDetached.");
+    Ok(())
+}
+
+/// Set the runtime clock to the host's.
+pub async fn sync_time(docker: &Docker) -> Result<()> {
+    let offset = clock::sync_guest_clock(docker).await?;
+    println!("Runtime clock set to host time (was off by {}s).", offset);
+    Ok(())
+}
+
+pub async fn rosetta_warm(docker: &Docker, images: &[String], format: &OutputFormat) -> Result<()> {
+    let results = runtime::rosetta::warm(docker, images).await?;
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&results, format);
+    }
+    if results.is_empty() {
+        println!("No amd64 images to warm.");
+        return Ok(());
+    }
+    for r in &results {
+        match &r.error {
+            None => println!(
+                "{}: warmed in {:.1}s",
+                r.image,
+                r.duration_ms as f64 / 1000.0
+            ),
+            Some(e) => println!("{}: failed: {}", r.image, e),
+        }
+    }
+    Ok(())
+}
+
+/// Serializable view of one `runtime kernel list` row.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KernelView {
+    id: String,
+    arch: String,
+    catalog_version: String,
+    status: images::ImageStatus,
+    /// `None` when installed from bundled assets or not installed.
+    installed_version: Option<String>,
+    active: bool,
+}
+
+/// List runtime kernel/initramfs images.
+pub fn kernel_list(format: &OutputFormat) -> Result<()> {
+    let active = runtime::common::runtime_os_image_id();
+    let rows: Vec<KernelView> = images::list_available_images()
+        .into_iter()
+        .filter(|e| e.id.starts_with("cratebay-runtime-") || e.id == active)
+        .map(|e| KernelView {
+            installed_version: images::installed_version(&e.id),
+            active: e.id == active,
+            id: e.id,
+            arch: e.arch,
+            catalog_version: e.version,
+            status: e.status,
+        })
+        .collect();
+
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&rows, format);
+    }
+
+    println!(
+        "{:<28} {:<8} {:<10} {:<16} {:<10}",
+        "ID", "ARCH", "CATALOG", "STATUS", "INSTALLED"
+    );
+    for row in rows {
+        let installed = match (&row.status, &row.installed_version) {
+            (_, Some(version)) => version.clone(),
+            (images::ImageStatus::Ready, None) => "bundled".to_string(),
+            _ => "-".to_string(),
+        };
+        println!(
+            "{:<28} {:<8} {:<10} {:<16} {:<10}{}",
+            row.id,
+            row.arch,
+            row.catalog_version,
+            format!("{:?}", row.status),
+            installed,
+            if row.active { " (active)" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+/// Download the catalog version of a runtime image.
+pub async fn kernel_update(id: Option<String>, force: bool) -> Result<()> {
+    let id = id.unwrap_or_else(|| runtime::common::runtime_os_image_id().to_string());
+    let entry =
+        images::find_image(&id).ok_or_else(|| anyhow::anyhow!("Unknown runtime image '{}'", id))?;
+
+    if !force && images::installed_version(&id).as_deref() == Some(entry.version.as_str()) {
+        println!("{} is already at version {}.", id, entry.version);
+        return Ok(());
+    }
+
+    println!("Downloading {} {}...", id, entry.version);
+    let cancel = super::ctrl_c_token();
+    let result = images::download_image(
+        &id,
+        Some(Arc::new(|p: images::DownloadProgress| {
+            if p.bytes_total > 0 {
+                eprint!(
+                    "\r  {} — {:.0}%",
+                    p.current_file,
+                    p.bytes_downloaded as f64 / p.bytes_total as f64 * 100.0
+                );
+            }
+        })),
+        Some(&cancel),
+    )
+    .await;
+    eprintln!(); // newline after progress
+    result?;
+    println!(
+        "{} updated to {}. Applies on next runtime start.",
+        id, entry.version
+    );
+    Ok(())
+}
+
+/// Share a host directory with the runtime VM.
+pub fn mount_add(host_path: &Path, tag: &str) -> Result<()> {
+    let mut config = RuntimeConfig::load();
+    let share = config.add_shared_dir(host_path, tag)?;
+    config.save()?;
+    audit::record(
+        &AuditAction::RuntimeMountAdd,
+        &share.tag,
+        Some(&share.host_path),
+    );
+    println!(
+        "Sharing {} as '{}'. Applies on next runtime start.",
+        share.host_path, share.tag
+    );
+    Ok(())
+}
+
+/// Stop sharing the directory mounted under `tag`.
+pub fn mount_remove(tag: &str, dry_run: bool, yes: bool, format: &OutputFormat) -> Result<()> {
+    let mut config = RuntimeConfig::load();
+    let share = config.remove_shared_dir(tag)?;
+    if dry_run {
+        let mut report = RemovalReport {
+            dry_run: true,
+            ..Default::default()
+        };
+        report.push(RemovalItem {
+            kind: ResourceKind::Mount,
+            id: share.tag,
+            name: share.host_path,
+            size_bytes: None,
+        });
+        return print_removal(&report, format);
+    }
+    confirm(
+        &format!("Stop sharing {} ('{}')?", share.host_path, share.tag),
+        yes,
+    )?;
+    config.save()?;
+    audit::record(
+        &AuditAction::RuntimeMountRemove,
+        &share.tag,
+        Some(&share.host_path),
+    );
+    println!(
+        "Stopped sharing {} ('{}'). Applies on next runtime start.",
+        share.host_path, share.tag
+    );
+    Ok(())
+}
+
+/// List host directories shared with the runtime VM.
+pub fn mount_list(format: &OutputFormat) -> Result<()> {
+    let config = RuntimeConfig::load();
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&config.shared_dirs, format);
+    }
+
+    println!("{:<20} HOST PATH", "TAG");
+    for share in &config.shared_dirs {
+        println!("{:<20} {}", share.tag, share.host_path);
+    }
+    Ok(())
+}
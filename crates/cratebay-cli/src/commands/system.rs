@@ -1,51 +1,238 @@
 use anyhow::Result;
+use bollard::Docker;
+use serde::Serialize;
 use std::time::Duration;
 
+use cratebay_core::docker::{self, EngineMode};
+use cratebay_core::models::{AuditAction, DockerStatus};
+use cratebay_core::prune::{self, PruneOptions, ResourceKind};
+use cratebay_core::runtime::{RuntimeManager, RuntimeState};
+use cratebay_core::{audit, status, storage};
+
+use super::{confirm, print_removal, print_structured, OutputFormat};
+
 const DOCKER_STATUS_RETRIES: usize = 3;
 const DOCKER_STATUS_RETRY_DELAY_MS: u64 = 250;
 
+/// Serializable view of `system info`.
+#[derive(Debug, Serialize)]
+struct SystemInfoView {
+    version: &'static str,
+    platform: &'static str,
+    arch: &'static str,
+    profile: String,
+    config_dir: String,
+    data_dir: String,
+}
+
 /// Show system information.
-pub fn info() -> Result<()> {
-    println!("CrateBay v{}", env!("CARGO_PKG_VERSION"));
-    println!("Platform: {}", std::env::consts::OS);
-    println!("Arch: {}", std::env::consts::ARCH);
+pub fn info(format: &OutputFormat) -> Result<()> {
+    let view = SystemInfoView {
+        version: env!("CARGO_PKG_VERSION"),
+        platform: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        profile: storage::profile().unwrap_or_else(|| storage::DEFAULT_PROFILE.to_string()),
+        config_dir: storage::config_dir().to_string_lossy().into_owned(),
+        data_dir: storage::data_dir().to_string_lossy().into_owned(),
+    };
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&view, format);
+    }
+
+    println!("CrateBay v{}", view.version);
+    println!("Platform: {}", view.platform);
+    println!("Arch: {}", view.arch);
+    println!("Profile: {}", view.profile);
+    println!("Config: {}", view.config_dir);
+    println!("Data: {}", view.data_dir);
     Ok(())
 }
 
-/// Show Docker connection status without starting the built-in runtime.
-pub async fn docker_status() -> Result<()> {
-    let mut docker = None;
+/// Connect to Docker with a few short retries, without starting the runtime.
+async fn try_connect_with_retries() -> Option<Docker> {
     for attempt in 0..DOCKER_STATUS_RETRIES {
-        docker = cratebay_core::docker::try_connect().await;
-        if docker.is_some() {
-            break;
+        if let Some(docker) = cratebay_core::docker::try_connect().await {
+            return Some(docker);
         }
         if attempt + 1 < DOCKER_STATUS_RETRIES {
             tokio::time::sleep(Duration::from_millis(DOCKER_STATUS_RETRY_DELAY_MS)).await;
         }
     }
+    None
+}
+
+/// Probe Docker and describe where the connection came from.
+async fn detect_docker() -> DockerStatus {
+    let docker = try_connect_with_retries().await;
+    let engine = docker::selected_engine();
+    let source = match engine.mode {
+        EngineMode::BuiltIn => "built-in",
+        _ => "external",
+    };
+    status::docker_status(docker.as_ref(), source, engine.endpoint).await
+}
+
+/// Show Docker connection status without starting the built-in runtime.
+pub async fn docker_status(format: &OutputFormat) -> Result<()> {
+    let d = detect_docker().await;
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&d, format);
+    }
 
-    let Some(docker) = docker else {
+    if !d.connected {
         println!("Docker: not connected");
         return Ok(());
-    };
+    }
 
-    let version = cratebay_core::docker::version(&docker).await.ok();
     println!("Docker: connected");
-    if let Some(v) = version {
-        if let Some(ver) = v.version {
-            println!("Version: {}", ver);
-        }
-        if let Some(api) = v.api_version {
-            println!("API: {}", api);
+    if let Some(ver) = &d.version {
+        println!("Version: {}", ver);
+    }
+    if let Some(api) = &d.api_version {
+        println!("API: {}", api);
+    }
+    if let Some(os) = &d.os {
+        println!("OS: {}", os);
+    }
+    if let Some(arch) = &d.arch {
+        println!("Arch: {}", arch);
+    }
+
+    Ok(())
+}
+
+/// Show a unified status snapshot without starting the built-in runtime.
+pub async fn status(runtime: &dyn RuntimeManager, format: &OutputFormat) -> Result<()> {
+    let docker_status = detect_docker().await;
+    let snapshot = status::collect(runtime, docker_status).await;
+
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&snapshot, format);
+    }
+
+    println!("CrateBay v{}", snapshot.version);
+    println!("Platform: {} ({})", snapshot.os, snapshot.arch);
+    match &snapshot.runtime_state {
+        RuntimeState::Error(msg) => println!("Runtime: error ({})", msg),
+        state => println!("Runtime: {:?}", state),
+    }
+    println!("Engine: {}", snapshot.engine);
+    println!(
+        "Hypervisor: {} ({})",
+        snapshot.hypervisor.backend,
+        if snapshot.hypervisor.available {
+            "available"
+        } else {
+            "unavailable"
         }
-        if let Some(os) = v.os {
-            println!("OS: {}", os);
+    );
+    if let Some(rosetta) = snapshot.hypervisor.rosetta_available {
+        println!(
+            "Rosetta: {}",
+            if rosetta { "available" } else { "unavailable" }
+        );
+    }
+    if let Some(gpu) = snapshot.hypervisor.gpu_available {
+        println!("GPU: {}", if gpu { "available" } else { "unavailable" });
+    }
+    let d = &snapshot.docker;
+    if d.connected {
+        println!(
+            "Docker: connected ({}, {})",
+            d.source,
+            d.socket_path.as_deref().unwrap_or("-")
+        );
+        if let Some(ver) = &d.version {
+            println!("Docker version: {}", ver);
         }
-        if let Some(arch) = v.arch {
-            println!("Arch: {}", arch);
+        if let Some(api) = &d.api_version {
+            println!("Docker API: {}", api);
         }
+    } else {
+        println!("Docker: not connected");
+    }
+    println!("Config dir: {}", snapshot.config_dir);
+    println!("Data dir: {}", snapshot.data_dir);
+    println!("Log dir: {}", snapshot.log_dir);
+    Ok(())
+}
+
+/// Stream Docker events until interrupted. JSON output is one object per line.
+pub async fn events(docker: &Docker, format: &OutputFormat) -> Result<()> {
+    let format = format.clone();
+    if matches!(format, OutputFormat::Table) {
+        println!("{:<20} {:<10} {:<16} NAME/ID", "TIME", "TYPE", "ACTION");
     }
 
+    cratebay_core::container::watch_events(docker, move |event| match format {
+        OutputFormat::Table => {
+            let time = chrono::DateTime::from_timestamp(event.time, 0)
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            let target = event.name.clone().unwrap_or_else(|| {
+                event
+                    .id
+                    .strip_prefix("sha256:")
+                    .unwrap_or(&event.id)
+                    .chars()
+                    .take(12)
+                    .collect()
+            });
+            println!(
+                "{:<20} {:<10} {:<16} {}",
+                time, event.kind, event.action, target
+            );
+        }
+        OutputFormat::Json => {
+            if let Ok(line) = serde_json::to_string(&event) {
+                println!("{}", line);
+            }
+        }
+        OutputFormat::Yaml => {
+            if let Ok(doc) = serde_yaml_ng::to_string(&event) {
+                println!("---\n{}", doc.trim_end());
+            }
+        }
+    })
+    .await?;
     Ok(())
 }
+
+/// Remove stopped containers, unused networks and dangling (or, with
+/// `all`, unused) images, plus anonymous unused volumes with `volumes`.
+pub async fn prune(
+    docker: &Docker,
+    options: PruneOptions,
+    dry_run: bool,
+    yes: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    if dry_run {
+        let report = prune::preview_system_prune(docker, options).await?;
+        return print_removal(&report, format);
+    }
+    let mut prompt = format!(
+        "Remove all stopped containers, unused networks and {} images",
+        if options.all_images {
+            "unused"
+        } else {
+            "dangling"
+        }
+    );
+    if options.volumes {
+        prompt.push_str(", and anonymous unused volumes");
+    }
+    confirm(&format!("{}?", prompt), yes)?;
+
+    let report = prune::system_prune(docker, options).await?;
+    for item in &report.items {
+        if item.kind == ResourceKind::Container {
+            audit::record(&AuditAction::ContainerDelete, &item.id, None);
+        }
+    }
+    print_removal(&report, format)
+}
@@ -0,0 +1,355 @@
+//! `tui`: a terminal dashboard of the engine's containers.
+//!
+//! A table of all containers, refreshed every two seconds, with keys to
+//! start, stop and restart the selected one and to read its recent logs.
+//! Actions go through the same core calls and history records as
+//! `cratebay container`, and run in the background so a slow stop does
+//! not freeze the screen.
+
+use std::io::IsTerminal;
+use std::time::Duration;
+
+use anyhow::Result;
+use bollard::Docker;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::mpsc;
+
+use cratebay_core::audit;
+use cratebay_core::container;
+use cratebay_core::models::{AuditAction, ContainerInfo, LogOptions};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Log lines fetched for the logs view.
+const LOG_TAIL: u32 = 200;
+
+const LOADING: &str = "Loading containers...";
+const LIST_HELP: &str = "↑/↓ select  s start  x stop  r restart  l logs  q quit";
+const LOGS_HELP: &str = "↑/↓ PgUp/PgDn scroll  l reload  Esc back  q quit";
+
+#[derive(Clone, Copy)]
+enum Action {
+    Start,
+    Stop,
+    Restart,
+}
+
+enum View {
+    List,
+    Logs {
+        id: String,
+        name: String,
+        lines: Vec<String>,
+        /// First visible line; clamped to the end when drawn.
+        scroll: usize,
+    },
+}
+
+enum Update {
+    Key(KeyEvent),
+    Redraw,
+    Containers(Result<Vec<ContainerInfo>, String>),
+    Status(String),
+    Logs {
+        id: String,
+        name: String,
+        lines: Result<Vec<String>, String>,
+    },
+}
+
+struct App {
+    containers: Vec<ContainerInfo>,
+    table: TableState,
+    view: View,
+    status: String,
+}
+
+/// Run the dashboard until `q`, Esc on the list, or Ctrl-C.
+pub async fn run(docker: &Docker) -> Result<()> {
+    if !std::io::stdout().is_terminal() {
+        anyhow::bail!("cratebay tui needs an interactive terminal");
+    }
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, docker).await;
+    ratatui::try_restore()?;
+    result
+}
+
+async fn event_loop(terminal: &mut DefaultTerminal, docker: &Docker) -> Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    // crossterm reads block, so keys come from a thread of their own.
+    let keys = tx.clone();
+    std::thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            let update = match event {
+                Event::Key(key) if key.kind == KeyEventKind::Press => Update::Key(key),
+                Event::Resize(..) => Update::Redraw,
+                _ => continue,
+            };
+            if keys.send(update).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut app = App {
+        containers: Vec::new(),
+        table: TableState::default(),
+        view: View::List,
+        status: LOADING.to_string(),
+    };
+    let mut ticker = tokio::time::interval(REFRESH_INTERVAL);
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        tokio::select! {
+            _ = ticker.tick() => refresh(docker.clone(), tx.clone()),
+            update = rx.recv() => match update {
+                Some(Update::Key(key)) => {
+                    if !app.handle_key(key, docker, &tx) {
+                        return Ok(());
+                    }
+                }
+                Some(update) => app.apply(update),
+                None => return Ok(()),
+            },
+        }
+    }
+}
+
+fn refresh(docker: Docker, tx: mpsc::UnboundedSender<Update>) {
+    tokio::spawn(async move {
+        let containers = container::list(&docker, true, None)
+            .await
+            .map_err(|e| e.to_string());
+        let _ = tx.send(Update::Containers(containers));
+    });
+}
+
+fn run_action(
+    action: Action,
+    docker: Docker,
+    target: &ContainerInfo,
+    tx: mpsc::UnboundedSender<Update>,
+) {
+    let (id, name) = (target.id.clone(), target.name.clone());
+    tokio::spawn(async move {
+        let (verb, done, audit_action) = match action {
+            Action::Start => ("start", "Started", AuditAction::ContainerStart),
+            Action::Stop => ("stop", "Stopped", AuditAction::ContainerStop),
+            Action::Restart => ("restart", "Restarted", AuditAction::ContainerRestart),
+        };
+        let result = match action {
+            Action::Start => container::start(&docker, &id).await,
+            Action::Stop => container::stop(&docker, &id, None).await,
+            Action::Restart => container::restart(&docker, &id, None).await,
+        };
+        let status = match result {
+            Ok(()) => {
+                audit::record(&audit_action, &id, None);
+                format!("{} {}", done, name)
+            }
+            Err(e) => format!("Failed to {} {}: {}", verb, name, e),
+        };
+        let _ = tx.send(Update::Status(status));
+        refresh(docker, tx);
+    });
+}
+
+fn load_logs(docker: Docker, id: String, name: String, tx: mpsc::UnboundedSender<Update>) {
+    tokio::spawn(async move {
+        let options = LogOptions {
+            tail: Some(LOG_TAIL),
+            ..Default::default()
+        };
+        let lines = container::logs(&docker, &id, Some(options))
+            .await
+            .map(|entries| {
+                entries
+                    .iter()
+                    .flat_map(|entry| entry.message.lines().map(str::to_string))
+                    .collect()
+            })
+            .map_err(|e| e.to_string());
+        let _ = tx.send(Update::Logs { id, name, lines });
+    });
+}
+
+impl App {
+    fn selected(&self) -> Option<&ContainerInfo> {
+        self.table.selected().and_then(|i| self.containers.get(i))
+    }
+
+    /// Handle a key press; `false` quits.
+    fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        docker: &Docker,
+        tx: &mpsc::UnboundedSender<Update>,
+    ) -> bool {
+        if key.code == KeyCode::Char('q')
+            || (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+        {
+            return false;
+        }
+
+        if let View::Logs {
+            id, name, scroll, ..
+        } = &mut self.view
+        {
+            match key.code {
+                KeyCode::Esc => self.view = View::List,
+                KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => *scroll += 1,
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(20),
+                KeyCode::PageDown => *scroll += 20,
+                KeyCode::Char('l') => {
+                    load_logs(docker.clone(), id.clone(), name.clone(), tx.clone())
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        let action = match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.table.select_next();
+                None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.table.select_previous();
+                None
+            }
+            KeyCode::Char('s') => Some(Action::Start),
+            KeyCode::Char('x') => Some(Action::Stop),
+            KeyCode::Char('r') => Some(Action::Restart),
+            KeyCode::Char('l') | KeyCode::Enter => {
+                if let Some(target) = self.selected().cloned() {
+                    self.status = format!("Loading logs of {}...", target.name);
+                    load_logs(docker.clone(), target.id, target.name, tx.clone());
+                }
+                None
+            }
+            _ => None,
+        };
+        if let (Some(action), Some(target)) = (action, self.selected().cloned()) {
+            let doing = match action {
+                Action::Start => "Starting",
+                Action::Stop => "Stopping",
+                Action::Restart => "Restarting",
+            };
+            self.status = format!("{} {}...", doing, target.name);
+            run_action(action, docker.clone(), &target, tx.clone());
+        }
+        true
+    }
+
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Key(_) | Update::Redraw => {}
+            Update::Containers(Ok(mut containers)) => {
+                containers.sort_by(|a, b| a.name.cmp(&b.name));
+                // Keep the selection on the same container across refreshes.
+                let selected = self.selected().map(|c| c.id.clone());
+                let index = selected
+                    .and_then(|id| containers.iter().position(|c| c.id == id))
+                    .or_else(|| {
+                        (!containers.is_empty())
+                            .then(|| self.table.selected().unwrap_or(0).min(containers.len() - 1))
+                    });
+                self.table.select(index);
+                if self.status == LOADING {
+                    self.status = String::new();
+                }
+                self.containers = containers;
+            }
+            Update::Containers(Err(e)) => self.status = format!("Listing containers failed: {}", e),
+            Update::Status(status) => self.status = status,
+            Update::Logs { id, name, lines } => match lines {
+                Ok(lines) => {
+                    self.status = String::new();
+                    self.view = View::Logs {
+                        id,
+                        name,
+                        scroll: lines.len(),
+                        lines,
+                    };
+                }
+                Err(e) => self.status = format!("Reading logs of {} failed: {}", name, e),
+            },
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status, help] = Layout::vertical([
+            Constraint::Min(3),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+
+        match &mut self.view {
+            View::List => {
+                let rows = self.containers.iter().map(|c| {
+                    let ports: Vec<String> = c
+                        .ports
+                        .iter()
+                        .map(|p| format!("{}->{}/{}", p.host_port, p.container_port, p.protocol))
+                        .collect();
+                    Row::new(vec![
+                        c.name.clone(),
+                        c.image.clone(),
+                        c.state.clone(),
+                        ports.join(", "),
+                        c.short_id.clone(),
+                    ])
+                });
+                let table = Table::new(
+                    rows,
+                    [
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(30),
+                        Constraint::Length(10),
+                        Constraint::Percentage(30),
+                        Constraint::Length(12),
+                    ],
+                )
+                .header(Row::new(["NAME", "IMAGE", "STATE", "PORTS", "ID"]).style(bold))
+                .block(
+                    Block::bordered()
+                        .title(format!(" CrateBay containers ({}) ", self.containers.len())),
+                )
+                .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+                frame.render_stateful_widget(table, main, &mut self.table);
+                frame.render_widget(Paragraph::new(LIST_HELP).style(bold), help);
+            }
+            View::Logs {
+                name,
+                lines,
+                scroll,
+                ..
+            } => {
+                // Two rows go to the border.
+                let visible = usize::from(main.height.saturating_sub(2));
+                *scroll = (*scroll).min(lines.len().saturating_sub(visible));
+                let text: Vec<&str> = lines
+                    .iter()
+                    .skip(*scroll)
+                    .take(visible)
+                    .map(String::as_str)
+                    .collect();
+                let logs = Paragraph::new(text.join("\n")).block(
+                    Block::bordered().title(format!(" Logs: {} (last {}) ", name, LOG_TAIL)),
+                );
+                frame.render_widget(logs, main);
+                frame.render_widget(Paragraph::new(LOGS_HELP).style(bold), help);
+            }
+        }
+        frame.render_widget(Paragraph::new(self.status.as_str()), status);
+    }
+}
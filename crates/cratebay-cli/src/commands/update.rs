@@ -0,0 +1,45 @@
+//! `cratebay update` — check for and install new releases.
+
+use anyhow::Result;
+
+use cratebay_core::update;
+
+use super::{ctrl_c_token, print_structured, OutputFormat};
+
+/// Report whether a newer release is available.
+pub async fn check(pre: bool, format: &OutputFormat) -> Result<()> {
+    let info = update::check(pre).await?;
+    if !matches!(format, OutputFormat::Table) {
+        return print_structured(&info, format);
+    }
+    if info.update_available {
+        println!(
+            "CrateBay {} is available (installed: {}).",
+            info.latest_version, info.current_version
+        );
+        println!("Release notes: {}", info.release_url);
+        println!("Run `cratebay update apply` to install it.");
+    } else {
+        println!("CrateBay {} is up to date.", info.current_version);
+    }
+    Ok(())
+}
+
+/// Download, verify and install the newest release over this binary.
+pub async fn apply(pre: bool) -> Result<()> {
+    let info = update::check(pre).await?;
+    if !info.update_available {
+        println!("CrateBay {} is up to date.", info.current_version);
+        return Ok(());
+    }
+    let exe = std::env::current_exe()?;
+    println!(
+        "Updating {} from {} to {}...",
+        exe.display(),
+        info.current_version,
+        info.latest_version
+    );
+    update::apply(&info, &exe, Some(&ctrl_c_token())).await?;
+    println!("Updated to CrateBay {}.", info.latest_version);
+    Ok(())
+}
@@ -0,0 +1,157 @@
+//! CLI failure classes and process exit codes.
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 1 | Any other failure |
+//! | 2 | Resource not found |
+//! | 3 | Daemon unreachable (reserved until the daemon lands, ADR-009) |
+//! | 4 | Docker unreachable |
+//! | 5 | Permission denied |
+//! | 6 | Quota exceeded (`max_*` keys in config.toml) |
+//! | 7 | The runtime must be stopped for the change |
+//! | 130 | Cancelled with Ctrl-C or declined at a confirmation prompt |
+//!
+//! `container exec` and `runtime exec` in table mode exit with the command's
+//! own exit code.
+
+use cratebay_core::error::{AppError, ErrorCode};
+use thiserror::Error;
+
+/// A failed CLI invocation, classified for scripting.
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{0:#}")]
+    NotFound(anyhow::Error),
+
+    #[error("Docker is unreachable: {0:#}")]
+    DockerUnreachable(anyhow::Error),
+
+    #[error("{0:#}")]
+    PermissionDenied(anyhow::Error),
+
+    #[error("{0:#}")]
+    QuotaExceeded(anyhow::Error),
+
+    #[error("{0:#}")]
+    RequiresRestart(anyhow::Error),
+
+    #[error("{0:#}")]
+    Cancelled(anyhow::Error),
+
+    #[error("{0:#}")]
+    Other(anyhow::Error),
+}
+
+impl CliError {
+    /// Process exit code for this failure class.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Other(_) => 1,
+            CliError::NotFound(_) => 2,
+            CliError::DockerUnreachable(_) => 4,
+            CliError::PermissionDenied(_) => 5,
+            CliError::QuotaExceeded(_) => 6,
+            CliError::RequiresRestart(_) => 7,
+            CliError::Cancelled(_) => 130,
+        }
+    }
+
+    /// Classify an error returned by a command.
+    pub fn classify(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<CliError>() {
+            Ok(cli) => return cli,
+            Err(err) => err,
+        };
+        let code = err.downcast_ref::<AppError>().map_or(1, app_error_code);
+        match code {
+            2 => CliError::NotFound(err),
+            4 => CliError::DockerUnreachable(err),
+            5 => CliError::PermissionDenied(err),
+            6 => CliError::QuotaExceeded(err),
+            7 => CliError::RequiresRestart(err),
+            130 => CliError::Cancelled(err),
+            _ => CliError::Other(err),
+        }
+    }
+}
+
+fn app_error_code(err: &AppError) -> i32 {
+    match err.code() {
+        ErrorCode::NotFound => 2,
+        ErrorCode::DockerUnreachable => 4,
+        ErrorCode::PermissionDenied => 5,
+        ErrorCode::QuotaExceeded => 6,
+        ErrorCode::RequiresRestart => 7,
+        ErrorCode::Cancelled => 130,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bollard::errors::Error as BollardError;
+
+    use super::*;
+
+    #[test]
+    fn maps_app_errors_to_exit_codes() {
+        let not_found = AppError::NotFound {
+            entity: "container".into(),
+            id: "abc".into(),
+        };
+        assert_eq!(CliError::classify(not_found.into()).exit_code(), 2);
+        assert_eq!(
+            CliError::classify(AppError::PermissionDenied("x".into()).into()).exit_code(),
+            5
+        );
+        assert_eq!(
+            CliError::classify(AppError::Validation("x".into()).into()).exit_code(),
+            1
+        );
+        let quota = AppError::QuotaExceeded {
+            resource: "max_cpus".into(),
+            requested: 8,
+            limit: 4,
+        };
+        assert_eq!(CliError::classify(quota.into()).exit_code(), 6);
+        assert_eq!(
+            CliError::classify(AppError::RequiresRestart("resizing its disk".into()).into())
+                .exit_code(),
+            7
+        );
+        assert_eq!(
+            CliError::classify(AppError::Cancelled("Pull of 'nginx'".into()).into()).exit_code(),
+            130
+        );
+        assert_eq!(CliError::classify(anyhow::anyhow!("plain")).exit_code(), 1);
+    }
+
+    #[test]
+    fn maps_docker_errors_to_exit_codes() {
+        let missing = BollardError::DockerResponseServerError {
+            status_code: 404,
+            message: "No such container".into(),
+        };
+        assert_eq!(
+            CliError::classify(AppError::Docker(missing).into()).exit_code(),
+            2
+        );
+        let socket = BollardError::SocketNotFoundError("/var/run/docker.sock".into());
+        assert_eq!(
+            CliError::classify(AppError::Docker(socket).into()).exit_code(),
+            4
+        );
+    }
+
+    #[test]
+    fn keeps_explicit_classification() {
+        let err: anyhow::Error =
+            CliError::DockerUnreachable(AppError::Runtime("timed out".into()).into()).into();
+        let err = CliError::classify(err);
+        assert_eq!(err.exit_code(), 4);
+        assert_eq!(
+            err.to_string(),
+            "Docker is unreachable: Runtime error: timed out"
+        );
+    }
+}
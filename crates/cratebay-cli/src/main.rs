@@ -1,10 +1,21 @@
 //! CrateBay CLI — command-line interface.
 
-use clap::{ArgAction, Parser, Subcommand};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use bollard::Docker;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 
 mod commands;
+mod error;
 
 use commands::OutputFormat;
+use cratebay_core::config::{Config, HostSettings, LOCAL_HOST};
+use cratebay_core::logging::LogOutput;
+use cratebay_core::models::ContainerCommitRequest;
+use cratebay_core::runtime::RuntimeManager;
+use cratebay_core::storage;
+use error::CliError;
 
 #[derive(Parser)]
 #[command(
@@ -19,10 +30,23 @@ struct Cli {
     /// Docker host (overrides auto-detection). Examples:
     /// - unix:///var/run/docker.sock
     /// - tcp://127.0.0.1:2375
-    #[arg(long, global = true)]
+    /// - ssh://user@build-server
+    #[arg(long, global = true, conflicts_with = "host")]
     docker_host: Option<String>,
 
-    /// Output format for structured commands.
+    /// Registered host to manage (see `cratebay host ls`)
+    #[arg(long, global = true)]
+    host: Option<String>,
+
+    /// Use the built-in runtime, ignoring hosts, DOCKER_HOST and Docker contexts
+    #[arg(long, global = true, conflicts_with_all = ["host", "docker_host"])]
+    local: bool,
+
+    /// Profile with its own config, data, runtime VM and ports (also CRATEBAY_PROFILE)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Output format for structured commands
     #[arg(long, global = true, default_value = "table")]
     format: OutputFormat,
 }
@@ -48,6 +72,90 @@ enum Commands {
     /// MCP server operations
     #[command(subcommand)]
     Mcp(McpCommands),
+
+    /// Read or change settings in config.toml
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// Check for and install CrateBay releases
+    #[command(subcommand)]
+    Update(UpdateCommands),
+
+    /// Docker CLI settings shared with CrateBay
+    #[command(subcommand)]
+    Docker(DockerCommands),
+
+    /// Register remote Docker engines and choose which one to manage
+    #[command(subcommand)]
+    Host(HostCommands),
+
+    /// Resolve <container>.docker.cratebay to container IPs from the host
+    #[command(subcommand)]
+    Dns(DnsCommands),
+
+    /// BuildKit build cache of the engine
+    #[command(subcommand)]
+    Builder(BuilderCommands),
+
+    /// Check compose files and convert them for Kubernetes
+    #[command(subcommand)]
+    Compose(ComposeCommands),
+
+    /// Inner-loop helpers for containers under development
+    #[command(subcommand)]
+    Dev(DevCommands),
+
+    /// Terminal dashboard: list containers, start, stop or restart them and read their logs
+    Tui,
+
+    /// Open a container's published web port in the default browser
+    Open {
+        /// Container ID or name
+        container: String,
+        /// Container or host port (default: the first well-known web port, e.g. 80, 3000, 8080)
+        port: Option<u16>,
+        /// Print the URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Show the history of lifecycle operations (create, start, stop, delete, push, mounts)
+    History {
+        /// Only operations on this target (container ID or name as given, image, mount tag, or "runtime")
+        #[arg(long)]
+        target: Option<String>,
+        /// Only this action, e.g. container.start
+        #[arg(long)]
+        action: Option<String>,
+        /// Only operations since an age (30m, 24h, 7d), RFC 3339 time or YYYY-MM-DD
+        #[arg(long)]
+        since: Option<String>,
+        /// Maximum number of entries
+        #[arg(long, short = 'n', default_value_t = 50)]
+        limit: u32,
+    },
+}
+
+/// Options shared by destructive commands.
+#[derive(clap::Args)]
+struct RemovalArgs {
+    /// Report what would be removed, and the space reclaimed, without removing it
+    #[arg(long)]
+    dry_run: bool,
+    /// Do not ask for confirmation
+    #[arg(long, short = 'y')]
+    yes: bool,
+}
+
+/// Options shared by commands that act on several containers at once.
+#[derive(clap::Args)]
+struct BulkArgs {
+    /// Every container the action applies to (stopped ones for start, running ones otherwise)
+    #[arg(long)]
+    all: bool,
+    /// Containers processed concurrently
+    #[arg(long, default_value_t = cratebay_core::bulk::DEFAULT_PARALLELISM)]
+    parallel: usize,
 }
 
 #[derive(Subcommand)]
@@ -56,8 +164,17 @@ enum ContainerCommands {
     #[command(alias = "ls")]
     List {
         /// Show all containers (including stopped)
-        #[arg(long)]
+        #[arg(long, short = 'a')]
         all: bool,
+        /// Show only the most recently created container (implies --all)
+        #[arg(long, short = 'l')]
+        latest: bool,
+        /// Print container IDs only
+        #[arg(long, short = 'q')]
+        quiet: bool,
+        /// Filter as key=value: name, label, status or ancestor (repeatable)
+        #[arg(long = "filter", short = 'f', value_name = "KEY=VALUE")]
+        filters: Vec<String>,
     },
 
     /// Create a container
@@ -85,17 +202,73 @@ enum ContainerCommands {
         /// Do not auto-start container after creation
         #[arg(long)]
         no_start: bool,
+        /// Key that makes retries safe: rerunning with the same key within
+        /// 24h returns the container it created
+        #[arg(long)]
+        idempotency_key: Option<String>,
+        /// Platform to run, e.g. linux/amd64 (emulated on an arm64 runtime)
+        #[arg(long)]
+        platform: Option<String>,
     },
 
-    /// Start a container
-    Start { id: String },
+    /// Start one or more containers
+    Start {
+        /// Container IDs or names
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        ids: Vec<String>,
+        #[command(flatten)]
+        bulk: BulkArgs,
+    },
 
-    /// Stop a container
+    /// Stop one or more containers
     Stop {
-        id: String,
+        /// Container IDs or names
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        ids: Vec<String>,
         /// Timeout in seconds before SIGKILL (default: 10)
         #[arg(long)]
         timeout: Option<u32>,
+        #[command(flatten)]
+        bulk: BulkArgs,
+    },
+
+    /// Restart one or more containers
+    Restart {
+        /// Container IDs or names
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        ids: Vec<String>,
+        /// Timeout in seconds before SIGKILL (default: 10)
+        #[arg(long)]
+        timeout: Option<u32>,
+        #[command(flatten)]
+        bulk: BulkArgs,
+    },
+
+    /// Pause all processes in a container
+    Pause { id: String },
+
+    /// Resume a paused container
+    Unpause { id: String },
+
+    /// Rename a container
+    Rename { id: String, new_name: String },
+
+    /// Freeze containers to disk and start them from there later (CRIU)
+    #[command(subcommand)]
+    Checkpoint(CheckpointCommands),
+
+    /// Change CPU/memory limits of a container
+    Update {
+        id: String,
+        /// CPU cores limit
+        #[arg(long)]
+        cpus: Option<u32>,
+        /// Memory limit in MB
+        #[arg(long)]
+        memory: Option<u64>,
+        /// Memory plus swap limit in MB, or -1 for unlimited swap (default: keep the current one)
+        #[arg(long, allow_hyphen_values = true)]
+        memory_swap: Option<i64>,
     },
 
     /// Delete a container
@@ -104,6 +277,8 @@ enum ContainerCommands {
         /// Force removal
         #[arg(long)]
         force: bool,
+        #[command(flatten)]
+        removal: RemovalArgs,
     },
 
     /// Execute a command inside a container
@@ -133,6 +308,41 @@ enum ContainerCommands {
 
     /// Inspect a container
     Inspect { id: String },
+
+    /// List processes running in a container
+    Top { id: String },
+
+    /// Create an image from a container's changes
+    Commit {
+        /// Container ID or name
+        id: String,
+        /// New image reference, e.g. myapp:snapshot
+        target: String,
+        /// Image author, e.g. "Jane <jane@example.com>"
+        #[arg(long, short = 'a')]
+        author: Option<String>,
+        /// Commit message
+        #[arg(long, short = 'm')]
+        message: Option<String>,
+        /// Dockerfile instruction to apply, e.g. "ENV APP_ENV=prod" (repeatable)
+        #[arg(long = "change", short = 'c', value_name = "INSTRUCTION")]
+        changes: Vec<String>,
+        /// Pause the container while committing
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        pause: bool,
+    },
+
+    /// Export a container's filesystem to a tar archive
+    Export {
+        /// Container ID or name
+        id: String,
+        /// Output file
+        #[arg(short = 'o', long)]
+        output: String,
+        /// Gzip-compress the archive (implied by a .gz/.tgz output name)
+        #[arg(long)]
+        gzip: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -142,17 +352,184 @@ enum ImageCommands {
 
     /// Search images from registry
     Search {
+        /// Search term (for ghcr: `<owner>` or `<owner>/<name filter>`)
         query: String,
         /// Max results
         #[arg(long)]
         limit: Option<u32>,
+        /// Registries to search, comma-separated: dockerhub, quay, ghcr, gitlab
+        /// (default: dockerhub,quay, searched concurrently)
+        #[arg(long, value_delimiter = ',')]
+        source: Vec<String>,
     },
 
     /// Pull an image
-    Pull { image: String },
+    Pull {
+        image: String,
+        /// Pull this platform's variant, e.g. linux/amd64 or linux/arm64
+        #[arg(long)]
+        platform: Option<String>,
+    },
+
+    /// Push an image to its registry
+    Push {
+        image: String,
+        /// Registry username
+        #[arg(long, short = 'u')]
+        username: Option<String>,
+        /// Read the registry password from stdin
+        #[arg(long)]
+        password_stdin: bool,
+    },
+
+    /// Inspect an image's manifest in its registry without pulling
+    InspectRemote {
+        /// Image reference, e.g. nginx:1.27 or ghcr.io/org/app@sha256:...
+        reference: String,
+        /// Platform whose layers/labels to show (os/arch[/variant]; default: host)
+        #[arg(long)]
+        platform: Option<String>,
+        /// Registry username
+        #[arg(long, short = 'u')]
+        username: Option<String>,
+        /// Read the registry password from stdin
+        #[arg(long)]
+        password_stdin: bool,
+    },
+
+    /// Show an image's layers with the instruction and size of each
+    History {
+        /// Image reference, e.g. nginx:1.27
+        reference: String,
+        /// Read the layers from the registry instead of the local image store
+        #[arg(long)]
+        remote: bool,
+        /// Platform of a remote multi-arch image (os/arch[/variant]; default: host)
+        #[arg(long, requires = "remote")]
+        platform: Option<String>,
+        /// Registry username (with --remote)
+        #[arg(long, short = 'u', requires = "remote")]
+        username: Option<String>,
+        /// Read the registry password from stdin
+        #[arg(long, requires = "remote")]
+        password_stdin: bool,
+    },
+
+    /// Show an image's description and README without pulling it
+    Info {
+        /// Image reference, e.g. nginx or quay.io/prometheus/prometheus
+        reference: String,
+        /// Registry username
+        #[arg(long, short = 'u')]
+        username: Option<String>,
+        /// Read the registry password from stdin
+        #[arg(long)]
+        password_stdin: bool,
+    },
+
+    /// List tags of a repository in its registry
+    Tags {
+        /// Repository, e.g. nginx or ghcr.io/org/app
+        reference: String,
+        /// Only show tags matching a glob, e.g. '1.2*' or '*-alpine'
+        #[arg(long)]
+        filter: Option<String>,
+        /// Page number (1-based)
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+        /// Tags per page
+        #[arg(long)]
+        limit: Option<u32>,
+        /// Show all tags instead of a single page
+        #[arg(long, conflicts_with = "page")]
+        all: bool,
+        /// Read each tag's manifest to mark Helm charts, SBOMs and other artifacts
+        #[arg(long)]
+        artifacts: bool,
+    },
+
+    /// List the repositories of a self-hosted registry (/v2/_catalog)
+    Catalog {
+        /// Registry host, e.g. harbor.example.com or localhost:5000
+        registry: String,
+        /// Only show repositories matching a glob, e.g. 'team/*'
+        #[arg(long)]
+        filter: Option<String>,
+        /// Page number (1-based)
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+        /// Repositories per page
+        #[arg(long)]
+        limit: Option<u32>,
+        /// Show all repositories instead of a single page
+        #[arg(long, conflicts_with = "page")]
+        all: bool,
+        /// Registry username
+        #[arg(long, short = 'u')]
+        username: Option<String>,
+        /// Read the registry password from stdin
+        #[arg(long)]
+        password_stdin: bool,
+    },
+
+    /// Build an image from a Dockerfile
+    Build {
+        /// Build context directory
+        #[arg(default_value = ".")]
+        context: String,
+        /// Image tag (name:tag). Can be repeated.
+        #[arg(short = 't', long = "tag", action = ArgAction::Append)]
+        tags: Vec<String>,
+        /// Dockerfile path (default: <context>/Dockerfile)
+        #[arg(short = 'f', long = "file")]
+        file: Option<String>,
+        /// Build-time variables (KEY=VALUE). Can be repeated.
+        #[arg(long = "build-arg", action = ArgAction::Append)]
+        build_args: Vec<String>,
+        /// Target build stage
+        #[arg(long)]
+        target: Option<String>,
+        /// Do not use cache when building
+        #[arg(long)]
+        no_cache: bool,
+        /// Always attempt to pull newer base images
+        #[arg(long)]
+        pull: bool,
+        /// Only lint the Dockerfile; exit non-zero if it has errors
+        #[arg(long)]
+        lint_only: bool,
+    },
+
+    /// Save images to a tar archive
+    Save {
+        /// Image references to save
+        #[arg(required = true)]
+        images: Vec<String>,
+        /// Output file
+        #[arg(short = 'o', long)]
+        output: String,
+        /// Gzip-compress the archive (implied by a .gz/.tgz output name)
+        #[arg(long)]
+        gzip: bool,
+    },
+
+    /// Create an image from a filesystem tar archive (e.g. from `container export`)
+    Import {
+        /// Archive path (.tar or .tar.gz)
+        file: String,
+        /// Image reference for the result, e.g. myapp:rootfs
+        reference: Option<String>,
+        /// Dockerfile instruction to apply, e.g. "CMD [\"/bin/sh\"]" (repeatable)
+        #[arg(long = "change", short = 'c', value_name = "INSTRUCTION")]
+        changes: Vec<String>,
+    },
 
     /// Delete a local image
-    Delete { id: String },
+    Delete {
+        id: String,
+        #[command(flatten)]
+        removal: RemovalArgs,
+    },
 }
 
 #[derive(Subcommand)]
@@ -165,6 +542,121 @@ enum RuntimeCommands {
     Stop,
     /// Pre-download runtime image without starting
     Provision,
+    /// Change runtime CPU/memory/GPU/boot settings (runtime must be stopped)
+    Set {
+        /// Number of CPU cores (1-16)
+        #[arg(long)]
+        cpus: Option<u32>,
+        /// Memory in MB (256-65536)
+        #[arg(long)]
+        memory: Option<u64>,
+        /// Attach a virtio-gpu device (true/false)
+        #[arg(long)]
+        gpu: Option<bool>,
+        /// Kernel command line replacing the image default ("" to reset)
+        #[arg(long, allow_hyphen_values = true)]
+        cmdline: Option<String>,
+        /// Boot this kernel instead of the image's ("" to reset)
+        #[arg(long)]
+        kernel: Option<String>,
+        /// Boot this initrd instead of the image's ("" to reset)
+        #[arg(long)]
+        initrd: Option<String>,
+    },
+    /// Grow the runtime disk (runtime must be stopped)
+    Resize {
+        /// New disk size in GB (shrinking is not supported)
+        #[arg(long)]
+        disk: u32,
+    },
+    /// Show live CPU, memory, disk and network usage of the runtime VM
+    Stats,
+    /// Run a command inside the runtime VM (starts the runtime if needed)
+    Exec {
+        /// Give up after this many seconds (default 300)
+        #[arg(long)]
+        timeout: Option<u64>,
+        /// Command to execute (after `--`)
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Forward localhost ports to ports on the runtime VM until Ctrl-C
+    Forward {
+        /// HOST_PORT:GUEST_PORT (or a single port), e.g. 8080:80
+        #[arg(required = true)]
+        ports: Vec<String>,
+    },
+    /// Return idle runtime VM memory to the host (one balloon policy round)
+    Balloon {
+        /// Guest memory to keep in MB instead of the policy's choice
+        #[arg(long)]
+        target: Option<u64>,
+    },
+    /// Open an interactive shell on the runtime VM (starts the runtime if needed)
+    Shell {
+        /// Command run with /bin/sh -c instead of a login shell
+        #[arg(long)]
+        shell: Option<String>,
+    },
+    /// Attach to the runtime VM's serial console (Ctrl-] detaches)
+    Attach,
+    /// Set the runtime VM clock to the host's (e.g. after sleep)
+    SyncTime,
+    /// Manage the runtime kernel/initramfs image
+    #[command(subcommand)]
+    Kernel(KernelCommands),
+    /// Manage host directories shared with the runtime VM (applies on next start)
+    #[command(subcommand)]
+    Mount(MountCommands),
+    /// Rosetta x86_64 translation (Apple Silicon)
+    #[command(subcommand)]
+    Rosetta(RosettaCommands),
+}
+
+#[derive(Subcommand)]
+enum RosettaCommands {
+    /// Pre-translate common binaries of amd64 images into the Rosetta cache
+    Warm {
+        /// Images to warm (defaults to every local amd64 image)
+        images: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MountCommands {
+    /// Share a host directory with the runtime VM
+    Add {
+        /// Host directory (relative paths and symlinks are resolved)
+        host_path: std::path::PathBuf,
+        /// VirtioFS tag used to mount it in the guest
+        #[arg(long)]
+        tag: String,
+    },
+    /// Stop sharing a directory
+    #[command(alias = "rm")]
+    Remove {
+        tag: String,
+        #[command(flatten)]
+        removal: RemovalArgs,
+    },
+    /// List shared directories
+    #[command(alias = "ls")]
+    List,
+}
+
+#[derive(Subcommand)]
+enum KernelCommands {
+    /// List runtime images and their installed versions
+    #[command(alias = "ls")]
+    List,
+    /// Download the catalog version of the runtime image (applies on next start)
+    Update {
+        /// Image id (defaults to the runtime image for this host)
+        id: Option<String>,
+        /// Download even if the installed version is current
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -184,15 +676,304 @@ enum SystemCommands {
 
     /// Show Docker connection status (does not start runtime)
     DockerStatus,
+
+    /// Show runtime, Docker and hypervisor status (does not start runtime)
+    Status,
+
+    /// Stream container/image/network/volume events
+    Events,
+
+    /// Remove stopped containers, unused networks and dangling images
+    Prune {
+        /// Remove all unused images, not just dangling ones
+        #[arg(long, short = 'a')]
+        all: bool,
+        /// Also remove anonymous volumes no container uses
+        #[arg(long)]
+        volumes: bool,
+        #[command(flatten)]
+        removal: RemovalArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print a config value
+    Get {
+        /// Key (docker_host, log_level, registry_mirrors, registry_proxy, max_*)
+        key: String,
+    },
+
+    /// Set a config value (empty string unsets; mirrors are comma-separated)
+    Set { key: String, value: String },
+
+    /// Show all config values
+    #[command(alias = "ls")]
+    List,
+
+    /// Print the config file path
+    Path,
+}
+
+#[derive(Subcommand)]
+enum UpdateCommands {
+    /// Check GitHub for a newer release
+    Check {
+        /// Include pre-releases
+        #[arg(long)]
+        pre: bool,
+    },
+
+    /// Download the newest release, verify its checksum and replace this binary
+    Apply {
+        /// Include pre-releases
+        #[arg(long)]
+        pre: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DockerCommands {
+    /// Switch between Docker engines (local, Colima, remote)
+    #[command(subcommand)]
+    Context(ContextCommands),
+}
+
+#[derive(Subcommand)]
+enum ContextCommands {
+    /// List Docker contexts (* marks the active one)
+    #[command(alias = "ls")]
+    List,
+
+    /// Make a context active for CrateBay and the Docker CLI ("default" selects the CrateBay runtime)
+    Use { name: String },
+}
+
+#[derive(Subcommand)]
+enum DnsCommands {
+    /// Answer DNS queries on 127.0.0.1 until Ctrl-C
+    Serve {
+        /// UDP port (default: CRATEBAY_DNS_PORT or 5354)
+        #[arg(long)]
+        port: Option<u16>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CheckpointCommands {
+    /// Checkpoint a running container (stops it unless --leave-running)
+    Create {
+        container: String,
+        /// Checkpoint name
+        name: String,
+        /// Keep the container running after the checkpoint
+        #[arg(long)]
+        leave_running: bool,
+    },
+    /// List a container's checkpoints
+    #[command(alias = "ls")]
+    List { container: String },
+    /// Start a stopped container from a checkpoint
+    Restore {
+        container: String,
+        /// Checkpoint name
+        name: String,
+    },
+    /// Delete a checkpoint
+    #[command(alias = "rm")]
+    Delete {
+        container: String,
+        /// Checkpoint name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum BuilderCommands {
+    /// Show build cache usage by record type
+    Du,
+    /// Remove build cache nothing uses
+    Prune {
+        /// Also remove records shared with other cache records
+        #[arg(long, short = 'a')]
+        all: bool,
+        #[command(flatten)]
+        removal: RemovalArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum ComposeCommands {
+    /// Check a compose file; exit non-zero if it has errors
+    Validate {
+        /// Compose file (default: compose.yaml, docker-compose.yml, ... in the current directory)
+        #[arg(value_name = "FILE", conflicts_with = "file")]
+        path: Option<String>,
+        /// Compose file, as with `docker compose -f`
+        #[arg(short = 'f', long)]
+        file: Option<String>,
+    },
+    /// Convert a compose file (kompose-style)
+    Convert {
+        /// Compose file (default: compose.yaml, docker-compose.yml, ... in the current directory)
+        #[arg(value_name = "FILE", conflicts_with = "file")]
+        path: Option<String>,
+        /// Compose file, as with `docker compose -f`
+        #[arg(short = 'f', long)]
+        file: Option<String>,
+        /// Output format of the conversion
+        #[arg(long, value_enum)]
+        to: ConvertTarget,
+        /// Write the manifests to this file instead of stdout
+        #[arg(long)]
+        out: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DevCommands {
+    /// Restart a container (or signal it, or run a reload command) when host files change
+    Watch {
+        /// Container ID or name
+        #[arg(long, short = 'c')]
+        container: String,
+        /// File or directory to watch, repeatable (default: the current directory)
+        #[arg(long = "path", short = 'p')]
+        paths: Vec<PathBuf>,
+        /// Send this signal instead of restarting, e.g. HUP
+        #[arg(long, conflicts_with = "exec")]
+        signal: Option<String>,
+        /// Run this command in the container (via sh -c) instead of restarting
+        #[arg(long)]
+        exec: Option<String>,
+        /// Seconds to wait for the container to stop when restarting (default: 10)
+        #[arg(long, conflicts_with_all = ["signal", "exec"])]
+        timeout: Option<u32>,
+        /// Quiet period before acting on a batch of changes
+        #[arg(long, default_value_t = 300)]
+        debounce_ms: u64,
+        /// Also ignore changes below directories with this name, repeatable
+        #[arg(long)]
+        ignore: Vec<String>,
+        /// Do not ignore .git, node_modules and target
+        #[arg(long)]
+        no_default_ignore: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ConvertTarget {
+    /// Kubernetes Deployments, Services and PersistentVolumeClaims
+    #[value(alias = "kubernetes")]
+    K8s,
+}
+
+#[derive(Subcommand)]
+enum HostCommands {
+    /// Register a host (replaces an existing one with the same name)
+    Add {
+        /// Name used with --host and `host use`
+        name: String,
+        /// ssh://[user@]host[:port], tcp://host:port, https://host:port, unix:// or npipe://
+        url: String,
+        /// SSH private key for ssh:// hosts
+        #[arg(long)]
+        identity_file: Option<PathBuf>,
+        /// CA certificate of the daemon; enables TLS for tcp:// hosts
+        #[arg(long)]
+        tls_ca: Option<PathBuf>,
+        /// Client certificate (with --tls-key)
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+        /// Client certificate key (with --tls-cert)
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+    },
+
+    /// List registered hosts (* marks the active one)
+    #[command(alias = "ls")]
+    List,
+
+    /// Forget a host
+    #[command(alias = "rm")]
+    Remove { name: String },
+
+    /// Make a host the default target ("local" goes back to DOCKER_HOST, contexts and the runtime)
+    Use { name: String },
 }
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt::init();
+async fn main() {
+    if let Err(e) = run().await {
+        let err = CliError::classify(e);
+        eprintln!("Error: {}", err);
+        std::process::exit(err.exit_code());
+    }
+}
 
+/// Connect to Docker (starting the built-in runtime if needed).
+async fn ensure_docker(runtime: &dyn RuntimeManager) -> Result<Arc<Docker>, CliError> {
+    cratebay_core::engine::ensure_docker(runtime, Default::default())
+        .await
+        .map_err(|e| CliError::DockerUnreachable(e.into()))
+}
+
+async fn run() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    if let Some(host) = cli.docker_host.as_ref() {
+    // Every config and data path depends on the profile, so set it first.
+    if let Some(profile) = &cli.profile {
+        storage::validate_profile(profile)?;
+        std::env::set_var(storage::PROFILE_ENV, profile);
+    }
+
+    let config = Config::load();
+    cratebay_core::logging::init(
+        config.as_ref().unwrap_or(&Config::default()),
+        "error",
+        LogOutput::Stdout,
+        None,
+    );
+
+    // `config` subcommands must still work with a broken config file.
+    let config = match config {
+        Ok(config) => config,
+        Err(e) if matches!(cli.command, Commands::Config(_)) => {
+            tracing::debug!("Config file error: {}", e);
+            Config::default()
+        }
+        Err(e) => {
+            eprintln!("Warning: ignoring config file: {}", e);
+            Config::default()
+        }
+    };
+
+    // --local > --host > CRATEBAY_HOST > active_host; a selected host wins
+    // over DOCKER_HOST
+    if cli.local {
+        std::env::set_var("CRATEBAY_HOST", LOCAL_HOST);
+        std::env::remove_var("DOCKER_HOST");
+        std::env::set_var(
+            "DOCKER_CONTEXT",
+            cratebay_core::docker_context::DEFAULT_CONTEXT,
+        );
+    } else if let Some(name) = &cli.host {
+        if name != LOCAL_HOST {
+            config.host(name)?;
+        }
+        std::env::set_var("CRATEBAY_HOST", name);
+    } else if cli.docker_host.is_some() {
+        // An explicit engine also overrides the saved host selection.
+        std::env::set_var("CRATEBAY_HOST", LOCAL_HOST);
+    }
+
+    // --docker-host > DOCKER_HOST > config.toml
+    if let Some(host) = cli
+        .docker_host
+        .clone()
+        .or_else(|| config.docker_host())
+        .filter(|_| !cli.local)
+    {
         std::env::set_var("DOCKER_HOST", host);
     }
 
@@ -201,11 +982,16 @@ async fn main() -> anyhow::Result<()> {
 
     match cli.command {
         Commands::Container(cmd) => {
-            let docker =
-                cratebay_core::engine::ensure_docker(runtime.as_ref(), Default::default()).await?;
+            let docker = ensure_docker(runtime.as_ref()).await?;
             match cmd {
-                ContainerCommands::List { all } => {
-                    commands::container::list(&docker, all, &cli.format).await?
+                ContainerCommands::List {
+                    all,
+                    latest,
+                    quiet,
+                    filters,
+                } => {
+                    commands::container::list(&docker, all, latest, quiet, &filters, &cli.format)
+                        .await?
                 }
                 ContainerCommands::Create {
                     name,
@@ -216,6 +1002,8 @@ async fn main() -> anyhow::Result<()> {
                     working_dir,
                     env,
                     no_start,
+                    idempotency_key,
+                    platform,
                 } => {
                     commands::container::create(
                         &docker,
@@ -227,16 +1015,80 @@ async fn main() -> anyhow::Result<()> {
                         working_dir,
                         env,
                         no_start,
+                        idempotency_key,
+                        platform,
+                        &cli.format,
+                    )
+                    .await?
+                }
+                ContainerCommands::Start { ids, bulk } => {
+                    commands::container::start(&docker, ids, bulk.all, bulk.parallel, &cli.format)
+                        .await?
+                }
+                ContainerCommands::Stop { ids, timeout, bulk } => {
+                    commands::container::stop(
+                        &docker,
+                        ids,
+                        timeout,
+                        bulk.all,
+                        bulk.parallel,
                         &cli.format,
                     )
                     .await?
                 }
-                ContainerCommands::Start { id } => commands::container::start(&docker, &id).await?,
-                ContainerCommands::Stop { id, timeout } => {
-                    commands::container::stop(&docker, &id, timeout).await?
+                ContainerCommands::Restart { ids, timeout, bulk } => {
+                    commands::container::restart(
+                        &docker,
+                        ids,
+                        timeout,
+                        bulk.all,
+                        bulk.parallel,
+                        &cli.format,
+                    )
+                    .await?
                 }
-                ContainerCommands::Delete { id, force } => {
-                    commands::container::delete(&docker, &id, force).await?
+                ContainerCommands::Pause { id } => commands::container::pause(&docker, &id).await?,
+                ContainerCommands::Unpause { id } => {
+                    commands::container::unpause(&docker, &id).await?
+                }
+                ContainerCommands::Rename { id, new_name } => {
+                    commands::container::rename(&docker, &id, &new_name).await?
+                }
+                ContainerCommands::Checkpoint(cmd) => match cmd {
+                    CheckpointCommands::Create {
+                        container,
+                        name,
+                        leave_running,
+                    } => {
+                        commands::container::checkpoint_create(&container, &name, leave_running)
+                            .await?
+                    }
+                    CheckpointCommands::List { container } => {
+                        commands::container::checkpoint_list(&container, &cli.format).await?
+                    }
+                    CheckpointCommands::Restore { container, name } => {
+                        commands::container::checkpoint_restore(&container, &name).await?
+                    }
+                    CheckpointCommands::Delete { container, name } => {
+                        commands::container::checkpoint_delete(&container, &name).await?
+                    }
+                },
+                ContainerCommands::Update {
+                    id,
+                    cpus,
+                    memory,
+                    memory_swap,
+                } => commands::container::update(&docker, &id, cpus, memory, memory_swap).await?,
+                ContainerCommands::Delete { id, force, removal } => {
+                    commands::container::delete(
+                        &docker,
+                        &id,
+                        force,
+                        removal.dry_run,
+                        removal.yes,
+                        &cli.format,
+                    )
+                    .await?
                 }
                 ContainerCommands::Exec {
                     id,
@@ -255,14 +1107,65 @@ async fn main() -> anyhow::Result<()> {
                 ContainerCommands::Inspect { id } => {
                     commands::container::inspect(&docker, &id, &cli.format).await?
                 }
+                ContainerCommands::Top { id } => {
+                    commands::container::top(&docker, &id, &cli.format).await?
+                }
+                ContainerCommands::Commit {
+                    id,
+                    target,
+                    author,
+                    message,
+                    changes,
+                    pause,
+                } => {
+                    let request = ContainerCommitRequest {
+                        target,
+                        author,
+                        message,
+                        changes,
+                        pause: Some(pause),
+                    };
+                    commands::container::commit(&docker, &id, &request).await?
+                }
+                ContainerCommands::Export { id, output, gzip } => {
+                    commands::container::export(&docker, &id, &output, gzip).await?
+                }
             }
         }
         Commands::Image(cmd) => {
             match cmd {
-                ImageCommands::Search { query, limit } => {
+                ImageCommands::Search {
+                    query,
+                    limit,
+                    source,
+                } => {
                     // Image search should not require starting the runtime. Prefer any
                     // already-available Docker, otherwise fall back to Docker Hub HTTP API.
-                    if let Some(docker) = cratebay_core::docker::try_connect().await {
+                    // Other registries, and several sources at once, are searched over HTTP.
+                    if source.len() != 1 {
+                        let sources: Vec<&str> = if source.is_empty() {
+                            cratebay_core::registry::DEFAULT_SEARCH_SOURCES.to_vec()
+                        } else {
+                            source.iter().map(String::as_str).collect()
+                        };
+                        let response = cratebay_core::registry::search_sources(
+                            &sources,
+                            &query,
+                            limit.map(u64::from),
+                        )
+                        .await?;
+                        commands::image::print_search_response(&response, &cli.format)?;
+                    } else if cratebay_core::registry::search_provider(&source[0])?.source()
+                        != "dockerhub"
+                    {
+                        let results = cratebay_core::registry::search_images(
+                            &source[0],
+                            &query,
+                            limit.map(u64::from),
+                        )
+                        .await?;
+                        commands::image::print_search_results(&results, &cli.format)?;
+                    } else if let Some(docker) = cratebay_core::docker::try_connect().await {
                         commands::image::search(&docker, &query, limit, &cli.format).await?;
                     } else {
                         let results = cratebay_core::container::image_search_dockerhub(
@@ -274,39 +1177,401 @@ async fn main() -> anyhow::Result<()> {
                     }
                 }
                 ImageCommands::List => {
-                    let docker =
-                        cratebay_core::engine::ensure_docker(runtime.as_ref(), Default::default())
-                            .await?;
+                    let docker = ensure_docker(runtime.as_ref()).await?;
                     commands::image::list(&docker, &cli.format).await?
                 }
-                ImageCommands::Pull { image } => {
-                    let docker =
-                        cratebay_core::engine::ensure_docker(runtime.as_ref(), Default::default())
-                            .await?;
-                    commands::image::pull(&docker, &image).await?
+                ImageCommands::Pull { image, platform } => {
+                    let docker = ensure_docker(runtime.as_ref()).await?;
+                    commands::image::pull(
+                        &docker,
+                        &image,
+                        &config.mirrors_for_image(&image),
+                        platform.as_deref(),
+                    )
+                    .await?
                 }
-                ImageCommands::Delete { id } => {
-                    let docker =
-                        cratebay_core::engine::ensure_docker(runtime.as_ref(), Default::default())
-                            .await?;
-                    commands::image::delete(&docker, &id).await?
+                ImageCommands::Push {
+                    image,
+                    username,
+                    password_stdin,
+                } => {
+                    let docker = ensure_docker(runtime.as_ref()).await?;
+                    commands::image::push(&docker, &image, username, password_stdin).await?
+                }
+                ImageCommands::History {
+                    reference,
+                    remote: true,
+                    platform,
+                    username,
+                    password_stdin,
+                } => {
+                    commands::image::history_remote(
+                        &reference,
+                        platform.as_deref(),
+                        username,
+                        password_stdin,
+                        &cli.format,
+                    )
+                    .await?
+                }
+                ImageCommands::History { reference, .. } => {
+                    let docker = ensure_docker(runtime.as_ref()).await?;
+                    commands::image::history(&docker, &reference, &cli.format).await?
+                }
+                ImageCommands::InspectRemote {
+                    reference,
+                    platform,
+                    username,
+                    password_stdin,
+                } => {
+                    // Talks to the registry directly; no runtime needed.
+                    commands::image::inspect_remote(
+                        &reference,
+                        platform.as_deref(),
+                        username,
+                        password_stdin,
+                        &cli.format,
+                    )
+                    .await?
+                }
+                ImageCommands::Info {
+                    reference,
+                    username,
+                    password_stdin,
+                } => {
+                    commands::image::info(&reference, username, password_stdin, &cli.format).await?
+                }
+                ImageCommands::Tags {
+                    reference,
+                    filter,
+                    page,
+                    limit,
+                    all,
+                    artifacts,
+                } => {
+                    let options = cratebay_core::registry::TagListOptions {
+                        filter,
+                        page: Some(page),
+                        page_size: limit,
+                        all,
+                        artifacts,
+                    };
+                    commands::image::tags(&reference, &options, &cli.format).await?
+                }
+                ImageCommands::Catalog {
+                    registry,
+                    filter,
+                    page,
+                    limit,
+                    all,
+                    username,
+                    password_stdin,
+                } => {
+                    let options = cratebay_core::registry::CatalogOptions {
+                        filter,
+                        page: Some(page),
+                        page_size: limit,
+                        all,
+                    };
+                    commands::image::catalog(
+                        &registry,
+                        &options,
+                        username,
+                        password_stdin,
+                        &cli.format,
+                    )
+                    .await?
+                }
+                ImageCommands::Build {
+                    context,
+                    tags,
+                    file,
+                    build_args,
+                    target,
+                    no_cache,
+                    pull,
+                    lint_only,
+                } => {
+                    let request = commands::image::build_request(
+                        context, tags, file, build_args, target, no_cache, pull,
+                    )?;
+                    if lint_only {
+                        commands::image::lint(&request, &cli.format)?
+                    } else {
+                        let docker = ensure_docker(runtime.as_ref()).await?;
+                        commands::image::build(&docker, &request).await?
+                    }
+                }
+                ImageCommands::Save {
+                    images,
+                    output,
+                    gzip,
+                } => {
+                    let docker = ensure_docker(runtime.as_ref()).await?;
+                    commands::image::save(&docker, &images, &output, gzip).await?
+                }
+                ImageCommands::Import {
+                    file,
+                    reference,
+                    changes,
+                } => {
+                    let docker = ensure_docker(runtime.as_ref()).await?;
+                    commands::image::import(&docker, &file, reference.as_deref(), &changes).await?
+                }
+                ImageCommands::Delete { id, removal } => {
+                    let docker = ensure_docker(runtime.as_ref()).await?;
+                    commands::image::delete(&docker, &id, removal.dry_run, removal.yes, &cli.format)
+                        .await?
                 }
             }
         }
         Commands::Runtime(cmd) => match cmd {
-            RuntimeCommands::Status => commands::runtime::status().await?,
+            RuntimeCommands::Status => commands::runtime::status(&cli.format).await?,
             RuntimeCommands::Start => commands::runtime::start().await?,
             RuntimeCommands::Stop => commands::runtime::stop().await?,
             RuntimeCommands::Provision => commands::runtime::provision().await?,
+            RuntimeCommands::Set {
+                cpus,
+                memory,
+                gpu,
+                cmdline,
+                kernel,
+                initrd,
+            } => {
+                let boot = commands::runtime::BootOverrides {
+                    cmdline,
+                    kernel,
+                    initrd,
+                };
+                commands::runtime::set(cpus, memory, gpu, boot).await?
+            }
+            RuntimeCommands::Resize { disk } => commands::runtime::resize(disk).await?,
+            RuntimeCommands::Stats => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                commands::runtime::stats(&docker, &cli.format).await?
+            }
+            RuntimeCommands::Exec { timeout, command } => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                commands::runtime::exec(&docker, command, timeout, &cli.format).await?
+            }
+            RuntimeCommands::Balloon { target } => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                commands::runtime::balloon(&docker, target).await?
+            }
+            RuntimeCommands::Shell { shell } => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                commands::runtime::shell(&docker, shell).await?
+            }
+            RuntimeCommands::Attach => commands::runtime::attach().await?,
+            RuntimeCommands::SyncTime => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                commands::runtime::sync_time(&docker).await?
+            }
+            RuntimeCommands::Forward { ports } => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                commands::runtime::forward(&docker, &ports).await?
+            }
+            RuntimeCommands::Mount(cmd) => match cmd {
+                MountCommands::Add { host_path, tag } => {
+                    commands::runtime::mount_add(&host_path, &tag)?
+                }
+                MountCommands::Remove { tag, removal } => commands::runtime::mount_remove(
+                    &tag,
+                    removal.dry_run,
+                    removal.yes,
+                    &cli.format,
+                )?,
+                MountCommands::List => commands::runtime::mount_list(&cli.format)?,
+            },
+            RuntimeCommands::Rosetta(cmd) => match cmd {
+                RosettaCommands::Warm { images } => {
+                    let docker = ensure_docker(runtime.as_ref()).await?;
+                    commands::runtime::rosetta_warm(&docker, &images, &cli.format).await?
+                }
+            },
+            RuntimeCommands::Kernel(cmd) => match cmd {
+                KernelCommands::List => commands::runtime::kernel_list(&cli.format)?,
+                KernelCommands::Update { id, force } => {
+                    commands::runtime::kernel_update(id, force).await?
+                }
+            },
         },
         Commands::System(cmd) => match cmd {
-            SystemCommands::Info => commands::system::info()?,
-            SystemCommands::DockerStatus => commands::system::docker_status().await?,
+            SystemCommands::Info => commands::system::info(&cli.format)?,
+            SystemCommands::DockerStatus => commands::system::docker_status(&cli.format).await?,
+            SystemCommands::Status => {
+                commands::system::status(runtime.as_ref(), &cli.format).await?
+            }
+            SystemCommands::Events => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                commands::system::events(&docker, &cli.format).await?
+            }
+            SystemCommands::Prune {
+                all,
+                volumes,
+                removal,
+            } => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                let options = cratebay_core::prune::PruneOptions {
+                    all_images: all,
+                    volumes,
+                };
+                commands::system::prune(&docker, options, removal.dry_run, removal.yes, &cli.format)
+                    .await?
+            }
         },
         Commands::Mcp(cmd) => match cmd {
             McpCommands::Export { target } => commands::mcp::export_config(&target)?,
         },
+        Commands::Config(cmd) => match cmd {
+            ConfigCommands::Get { key } => commands::config::get(&key)?,
+            ConfigCommands::Set { key, value } => commands::config::set(&key, &value)?,
+            ConfigCommands::List => commands::config::list(&cli.format)?,
+            ConfigCommands::Path => commands::config::path(),
+        },
+        Commands::Update(cmd) => match cmd {
+            UpdateCommands::Check { pre } => commands::update::check(pre, &cli.format).await?,
+            UpdateCommands::Apply { pre } => commands::update::apply(pre).await?,
+        },
+        Commands::Docker(DockerCommands::Context(cmd)) => match cmd {
+            ContextCommands::List => commands::docker::context_list(&cli.format)?,
+            ContextCommands::Use { name } => commands::docker::context_use(&name)?,
+        },
+        Commands::Dns(cmd) => match cmd {
+            DnsCommands::Serve { port } => {
+                let docker = ensure_docker(runtime.as_ref()).await?;
+                commands::dns::serve(docker, port).await?
+            }
+        },
+        Commands::Compose(cmd) => match cmd {
+            ComposeCommands::Validate { path, file } => {
+                commands::compose::validate(file.or(path).as_deref(), &cli.format)?
+            }
+            ComposeCommands::Convert {
+                path,
+                file,
+                to,
+                out,
+            } => match to {
+                ConvertTarget::K8s => commands::compose::convert_k8s(
+                    file.or(path).as_deref(),
+                    out.as_deref(),
+                    &cli.format,
+                )?,
+            },
+        },
+        Commands::Dev(DevCommands::Watch {
+            container,
+            paths,
+            signal,
+            exec,
+            timeout,
+            debounce_ms,
+            ignore,
+            no_default_ignore,
+        }) => {
+            let docker = ensure_docker(runtime.as_ref()).await?;
+            let action = commands::dev::action(signal, exec, timeout);
+            commands::dev::watch(
+                &docker,
+                container,
+                paths,
+                action,
+                debounce_ms,
+                ignore,
+                no_default_ignore,
+            )
+            .await?
+        }
+        Commands::Builder(cmd) => {
+            let docker = ensure_docker(runtime.as_ref()).await?;
+            match cmd {
+                BuilderCommands::Du => commands::builder::du(&docker, &cli.format).await?,
+                BuilderCommands::Prune { all, removal } => {
+                    commands::builder::prune(
+                        &docker,
+                        all,
+                        removal.dry_run,
+                        removal.yes,
+                        &cli.format,
+                    )
+                    .await?
+                }
+            }
+        }
+        Commands::Host(cmd) => match cmd {
+            HostCommands::Add {
+                name,
+                url,
+                identity_file,
+                tls_ca,
+                tls_cert,
+                tls_key,
+            } => commands::host::add(
+                &name,
+                HostSettings {
+                    url,
+                    identity_file,
+                    tls_ca,
+                    tls_cert,
+                    tls_key,
+                },
+            )?,
+            HostCommands::List => commands::host::list(&cli.format)?,
+            HostCommands::Remove { name } => commands::host::remove(&name)?,
+            HostCommands::Use { name } => commands::host::use_host(&name)?,
+        },
+        Commands::Tui => {
+            let docker = ensure_docker(runtime.as_ref()).await?;
+            commands::tui::run(&docker).await?
+        }
+        Commands::Open {
+            container,
+            port,
+            print,
+        } => {
+            let docker = ensure_docker(runtime.as_ref()).await?;
+            commands::container::open(&docker, &container, port, print).await?
+        }
+        Commands::History {
+            target,
+            action,
+            since,
+            limit,
+        } => commands::history::list(target, action, since.as_deref(), limit, &cli.format)?,
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn compose_file_can_be_positional() {
+        for args in [
+            &["cratebay", "compose", "validate", "app.yaml"][..],
+            &["cratebay", "compose", "validate", "-f", "app.yaml"],
+        ] {
+            let cli = Cli::try_parse_from(args).unwrap();
+            let Commands::Compose(ComposeCommands::Validate { path, file }) = cli.command else {
+                panic!("parsed as another command");
+            };
+            assert_eq!(file.or(path).as_deref(), Some("app.yaml"));
+        }
+        assert!(
+            Cli::try_parse_from(["cratebay", "compose", "validate", "a.yaml", "-f", "b.yaml"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn cli_definition_is_consistent() {
+        // Catches clashing flags (e.g. a global short option reused by a
+        // subcommand), which clap only reports when that subcommand runs.
+        Cli::command().debug_assert();
+    }
+}
@@ -7,6 +7,66 @@ use rusqlite::params;
 
 use crate::error::AppError;
 use crate::models::AuditAction;
+use crate::storage;
+
+/// Name recorded as the acting user: the OS login, or `"user"` if unknown.
+pub fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .unwrap_or_else(|| "user".to_string())
+}
+
+/// Log an action by [`current_user`] to the default database.
+///
+/// For callers without an open connection (the CLI). Failures are logged
+/// and swallowed so a locked or unwritable database never fails the
+/// operation being recorded.
+pub fn record(action: &AuditAction, target: &str, details: Option<&str>) {
+    let result = storage::default_db_path()
+        .and_then(|path| storage::init(&path))
+        .and_then(|conn| log_action(&conn, action, target, details, &current_user()));
+    if let Err(e) = result {
+        tracing::warn!("Failed to record {} in audit log: {}", action.as_str(), e);
+    }
+}
+
+/// Parse a `--since` value into the audit log timestamp format.
+///
+/// Accepts a relative age (`30m`, `24h`, `7d`), an RFC 3339 timestamp, or a
+/// date (`2026-03-01`, midnight UTC).
+pub fn parse_since(value: &str) -> Result<String, AppError> {
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+    let value = value.trim();
+    let invalid = || {
+        AppError::Validation(format!(
+            "Invalid time '{}': expected an age like 30m, 24h or 7d, an RFC 3339 timestamp, or YYYY-MM-DD",
+            value
+        ))
+    };
+
+    if let Some(unit) = value.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        let amount: i64 = value[..value.len() - 1].parse().map_err(|_| invalid())?;
+        let age = match unit {
+            's' => chrono::Duration::seconds(amount),
+            'm' => chrono::Duration::minutes(amount),
+            'h' => chrono::Duration::hours(amount),
+            'd' => chrono::Duration::days(amount),
+            'w' => chrono::Duration::weeks(amount),
+            _ => return Err(invalid()),
+        };
+        return Ok((chrono::Utc::now() - age).format(FORMAT).to_string());
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&chrono::Utc).format(FORMAT).to_string());
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(format!("{}T00:00:00Z", date.format("%Y-%m-%d")));
+    }
+    Err(invalid())
+}
 
 /// Log an audit event to the database.
 pub fn log_action(
@@ -108,4 +168,18 @@ mod tests {
             .unwrap();
         assert_eq!(count, actions.len() as u32);
     }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("2026-03-01").unwrap(), "2026-03-01T00:00:00Z");
+        assert_eq!(
+            parse_since("2026-03-01T10:00:00+02:00").unwrap(),
+            "2026-03-01T08:00:00Z"
+        );
+        let day_ago = parse_since("1d").unwrap();
+        let hour_ago = parse_since("1h").unwrap();
+        assert!(day_ago < hour_ago);
+        assert!(parse_since("3x").is_err());
+        assert!(parse_since("yesterday").is_err());
+    }
 }
@@ -0,0 +1,233 @@
+//! Launch the desktop app at login.
+//!
+//! Registers a per-user login item that starts the GUI with [`HIDDEN_ARG`]
+//! (minimized to the tray): a launchd agent on macOS, a `Run` registry
+//! value on Windows and an XDG autostart entry elsewhere.
+
+use std::path::Path;
+
+use crate::error::AppError;
+
+/// Command-line flag that starts the GUI hidden in the tray.
+pub const HIDDEN_ARG: &str = "--hidden";
+
+/// launchd label and plist name on macOS.
+const LAUNCH_AGENT_LABEL: &str = "com.cratebay.app";
+
+/// Whether the login item is registered.
+pub fn is_enabled() -> bool {
+    imp::is_enabled()
+}
+
+/// Register (`enabled`) or remove the login item that launches `exe`.
+/// Removing an item that does not exist succeeds.
+pub fn set_enabled(enabled: bool, exe: &Path) -> Result<(), AppError> {
+    if enabled {
+        imp::enable(exe)
+    } else {
+        imp::disable()
+    }
+}
+
+/// launchd agent plist starting `exe` hidden at login.
+pub fn launch_agent(exe: &Path) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>ProcessType</key>
+    <string>Interactive</string>
+</dict>
+</plist>
+"#,
+        LAUNCH_AGENT_LABEL,
+        xml_escape(&exe.to_string_lossy()),
+        HIDDEN_ARG
+    )
+}
+
+/// XDG autostart desktop entry starting `exe` hidden at login.
+pub fn desktop_entry(exe: &Path) -> String {
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=CrateBay\n\
+         Comment=Start CrateBay in the system tray\n\
+         Exec=\"{}\" {}\n\
+         Terminal=false\n\
+         X-GNOME-Autostart-enabled=true\n",
+        desktop_exec_escape(&exe.to_string_lossy()),
+        HIDDEN_ARG
+    )
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape a quoted argument of a desktop entry `Exec` key.
+fn desktop_exec_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Write a login item file, creating its directory.
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn write_login_item(path: &Path, contents: &str) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Remove a login item file if present.
+#[cfg_attr(target_os = "windows", allow(dead_code))]
+fn remove_login_item(path: &Path) -> Result<(), AppError> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn plist_path() -> Result<PathBuf, AppError> {
+        Ok(crate::storage::home_dir()?
+            .join("Library")
+            .join("LaunchAgents")
+            .join(format!("{}.plist", LAUNCH_AGENT_LABEL)))
+    }
+
+    pub fn is_enabled() -> bool {
+        plist_path().map(|p| p.exists()).unwrap_or(false)
+    }
+
+    pub fn enable(exe: &Path) -> Result<(), AppError> {
+        write_login_item(&plist_path()?, &launch_agent(exe))
+    }
+
+    pub fn disable() -> Result<(), AppError> {
+        remove_login_item(&plist_path()?)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use std::process::Command;
+
+    use super::*;
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    const VALUE_NAME: &str = "CrateBay";
+
+    fn reg(args: &[&str]) -> Result<bool, AppError> {
+        let output = Command::new("reg")
+            .args(args)
+            .output()
+            .map_err(|e| AppError::Runtime(format!("Failed to run reg.exe: {}", e)))?;
+        Ok(output.status.success())
+    }
+
+    pub fn is_enabled() -> bool {
+        reg(&["query", RUN_KEY, "/v", VALUE_NAME]).unwrap_or(false)
+    }
+
+    pub fn enable(exe: &Path) -> Result<(), AppError> {
+        let command = format!("\"{}\" {}", exe.display(), HIDDEN_ARG);
+        let args = [
+            "add", RUN_KEY, "/v", VALUE_NAME, "/t", "REG_SZ", "/d", &command, "/f",
+        ];
+        if !reg(&args)? {
+            return Err(AppError::Runtime(
+                "Failed to register CrateBay as a login item".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn disable() -> Result<(), AppError> {
+        if is_enabled() && !reg(&["delete", RUN_KEY, "/v", VALUE_NAME, "/f"])? {
+            return Err(AppError::Runtime(
+                "Failed to remove the CrateBay login item".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod imp {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn desktop_entry_path() -> Result<PathBuf, AppError> {
+        let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => crate::storage::home_dir()?.join(".config"),
+        };
+        Ok(config_home.join("autostart").join("cratebay.desktop"))
+    }
+
+    pub fn is_enabled() -> bool {
+        desktop_entry_path().map(|p| p.exists()).unwrap_or(false)
+    }
+
+    pub fn enable(exe: &Path) -> Result<(), AppError> {
+        write_login_item(&desktop_entry_path()?, &desktop_entry(exe))
+    }
+
+    pub fn disable() -> Result<(), AppError> {
+        remove_login_item(&desktop_entry_path()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_entry_quotes_the_executable() {
+        let entry = desktop_entry(Path::new("/opt/Crate Bay/cratebay-gui"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Exec=\"/opt/Crate Bay/cratebay-gui\" --hidden\n"));
+
+        let entry = desktop_entry(Path::new("/home/$user/cratebay"));
+        assert!(entry.contains(r#"Exec="/home/\$user/cratebay" --hidden"#));
+    }
+
+    #[test]
+    fn launch_agent_escapes_the_executable() {
+        let plist = launch_agent(Path::new("/Applications/A&B.app/Contents/MacOS/CrateBay"));
+        assert!(plist.contains("<string>com.cratebay.app</string>"));
+        assert!(
+            plist.contains("<string>/Applications/A&amp;B.app/Contents/MacOS/CrateBay</string>")
+        );
+        assert!(plist.contains("<string>--hidden</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+    }
+}
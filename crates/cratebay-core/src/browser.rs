@@ -0,0 +1,54 @@
+//! Open URLs in the user's default browser.
+
+use std::process::Command;
+
+use crate::error::AppError;
+
+/// Open an `http(s)` URL with the platform opener (`open`, `xdg-open`,
+/// `start`). Other schemes are rejected so callers cannot launch files or
+/// applications through this.
+pub fn open_url(url: &str) -> Result<(), AppError> {
+    let url = url.trim();
+    let valid = reqwest::Url::parse(url)
+        .map(|u| matches!(u.scheme(), "http" | "https") && u.host().is_some())
+        .unwrap_or(false);
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Only http(s) URLs can be opened: '{}'",
+            url
+        )));
+    }
+
+    let status = opener(url)
+        .status()
+        .map_err(|e| AppError::Runtime(format!("Failed to launch the default browser: {}", e)))?;
+    if !status.success() {
+        return Err(AppError::Runtime(format!(
+            "Browser opener exited with {} for {}",
+            status, url
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn opener(url: &str) -> Command {
+    let mut cmd = Command::new("open");
+    cmd.arg(url);
+    cmd
+}
+
+#[cfg(target_os = "windows")]
+fn opener(url: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    // The empty argument is `start`'s window title.
+    cmd.args(["/C", "start", ""]).arg(url);
+    cmd
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn opener(url: &str) -> Command {
+    let mut cmd = Command::new("xdg-open");
+    cmd.arg(url);
+    cmd
+}
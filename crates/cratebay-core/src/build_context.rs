@@ -0,0 +1,384 @@
+//! Docker build context packaging.
+//!
+//! Packs a build context directory into an in-memory tar archive for
+//! `POST /build`, honouring `.dockerignore` the same way the Docker CLI does:
+//!
+//! - `#` comments and blank lines are skipped
+//! - `*` / `?` match within a path segment, `**` matches any number of segments
+//! - a pattern matching a directory excludes everything below it
+//! - `!pattern` re-includes paths; the last matching rule wins
+//!
+//! Excluded directories are not walked unless a `!` rule could re-include
+//! something below them, so an ignored `node_modules` costs nothing.
+//!
+//! The Dockerfile is always sent, even when it is ignored.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+
+/// Archive name used when the Dockerfile lives outside the context directory.
+const EXTERNAL_DOCKERFILE_NAME: &str = ".cratebay.Dockerfile";
+
+/// A packed build context ready to send to the Docker daemon.
+#[derive(Debug)]
+pub struct BuildContext {
+    /// Uncompressed tar archive of the context.
+    pub tar: Vec<u8>,
+    /// Dockerfile path inside the archive (for the `dockerfile` build option).
+    pub dockerfile: String,
+}
+
+/// A single `.dockerignore` rule.
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    segments: Vec<String>,
+    exclude: bool,
+}
+
+/// Parsed `.dockerignore` rules.
+#[derive(Debug, Clone, Default)]
+pub struct DockerIgnore {
+    rules: Vec<IgnoreRule>,
+}
+
+impl DockerIgnore {
+    /// Parse `.dockerignore` content.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (exclude, pattern) = match line.strip_prefix('!') {
+                    Some(rest) => (false, rest.trim()),
+                    None => (true, line),
+                };
+                let segments: Vec<String> = normalize(pattern)
+                    .split('/')
+                    .filter(|s| !s.is_empty() && *s != ".")
+                    .map(str::to_string)
+                    .collect();
+                (!segments.is_empty()).then_some(IgnoreRule { segments, exclude })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Load `.dockerignore` from the context directory (empty if absent).
+    pub fn load(context_dir: &Path) -> Result<Self, AppError> {
+        match std::fs::read_to_string(context_dir.join(".dockerignore")) {
+            Ok(content) => Ok(Self::parse(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Whether `rel_path` (relative to the context, `/`-separated) is excluded.
+    pub fn is_excluded(&self, rel_path: &str) -> bool {
+        let path: Vec<&str> = rel_path.split('/').filter(|s| !s.is_empty()).collect();
+        let mut excluded = false;
+        for rule in &self.rules {
+            // A rule matching any ancestor directory applies to the path too.
+            let matched = (1..=path.len()).any(|len| match_segments(&rule.segments, &path[..len]));
+            if matched {
+                excluded = rule.exclude;
+            }
+        }
+        excluded
+    }
+
+    /// Whether a `!` rule could re-include something below the directory
+    /// `rel_dir`. When none can, an excluded directory is not walked.
+    pub fn may_reinclude_below(&self, rel_dir: &str) -> bool {
+        let dir: Vec<&str> = rel_dir.split('/').filter(|s| !s.is_empty()).collect();
+        self.rules
+            .iter()
+            .any(|rule| !rule.exclude && may_match_below(&rule.segments, &dir))
+    }
+}
+
+/// Pack `context_dir` into a tar archive.
+///
+/// `dockerfile` defaults to `<context_dir>/Dockerfile`. A Dockerfile outside
+/// the context is embedded under a reserved name.
+pub fn create_build_context(
+    context_dir: &Path,
+    dockerfile: Option<&Path>,
+) -> Result<BuildContext, AppError> {
+    if !context_dir.is_dir() {
+        return Err(AppError::Validation(format!(
+            "Build context is not a directory: {}",
+            context_dir.display()
+        )));
+    }
+    let context_dir = context_dir.canonicalize()?;
+
+    let dockerfile_path = match dockerfile {
+        Some(path) => path.to_path_buf(),
+        None => context_dir.join("Dockerfile"),
+    };
+    if !dockerfile_path.is_file() {
+        return Err(AppError::Validation(format!(
+            "Dockerfile not found: {}",
+            dockerfile_path.display()
+        )));
+    }
+    let dockerfile_path = dockerfile_path.canonicalize()?;
+    let dockerfile_in_context = dockerfile_path
+        .strip_prefix(&context_dir)
+        .ok()
+        .map(to_archive_path);
+
+    let ignore = DockerIgnore::load(&context_dir)?;
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.follow_symlinks(false);
+
+    let mut files = Vec::new();
+    collect_entries(&context_dir, &context_dir, &ignore, &mut files)?;
+    for (path, rel) in &files {
+        builder.append_path_with_name(path, rel)?;
+    }
+
+    let dockerfile_name = match dockerfile_in_context {
+        Some(rel) => {
+            if !files.iter().any(|(_, r)| *r == rel) {
+                builder.append_path_with_name(&dockerfile_path, &rel)?;
+            }
+            rel
+        }
+        None => {
+            builder.append_path_with_name(&dockerfile_path, EXTERNAL_DOCKERFILE_NAME)?;
+            EXTERNAL_DOCKERFILE_NAME.to_string()
+        }
+    };
+
+    Ok(BuildContext {
+        tar: builder.into_inner()?,
+        dockerfile: dockerfile_name,
+    })
+}
+
+/// Recursively collect non-ignored entries under `dir`, sorted for
+/// reproducible archives.
+fn collect_entries(
+    root: &Path,
+    dir: &Path,
+    ignore: &DockerIgnore,
+    out: &mut Vec<(PathBuf, String)>,
+) -> Result<(), AppError> {
+    let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let rel = match path.strip_prefix(root) {
+            Ok(rel) => to_archive_path(rel),
+            Err(_) => continue,
+        };
+        let file_type = entry.file_type()?;
+        let excluded = ignore.is_excluded(&rel);
+
+        if file_type.is_dir() {
+            // An excluded directory is still walked when a `!` rule may
+            // re-include something below it.
+            let descend = !excluded || ignore.may_reinclude_below(&rel);
+            if !excluded {
+                out.push((path.clone(), rel));
+            }
+            if descend {
+                collect_entries(root, &path, ignore, out)?;
+            }
+        } else if !excluded {
+            out.push((path, rel));
+        }
+    }
+    Ok(())
+}
+
+fn to_archive_path(rel: &Path) -> String {
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn normalize(pattern: &str) -> String {
+    let pattern = pattern.replace('\\', "/");
+    let pattern = pattern.trim_start_matches("./").trim_start_matches('/');
+    pattern.trim_end_matches('/').to_string()
+}
+
+/// Match pattern segments against path segments (`**` spans segments).
+fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| match_segments(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((seg, path_rest)) => wildcard_match(first, seg) && match_segments(rest, path_rest),
+            None => false,
+        },
+    }
+}
+
+/// Whether `pattern` could match a path strictly below the directory `dir`.
+/// Conservative: `**` is assumed to match.
+fn may_match_below(pattern: &[String], dir: &[&str]) -> bool {
+    match (pattern.split_first(), dir.split_first()) {
+        // The pattern ends at or above `dir`; it matches the directory itself.
+        (None, _) => false,
+        (Some((first, _)), _) if first == "**" => true,
+        (Some(_), None) => true,
+        (Some((first, rest)), Some((seg, dir_rest))) => {
+            wildcard_match(first, seg) && may_match_below(rest, dir_rest)
+        }
+    }
+}
+
+/// Match a single segment with `*` and `?` wildcards.
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((star_pi, star_ti)) = star {
+            pi = star_pi + 1;
+            ti = star_ti + 1;
+            star = Some((star_pi, star_ti + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn archive_names(tar_bytes: &[u8]) -> Vec<String> {
+        let mut archive = tar::Archive::new(tar_bytes);
+        archive
+            .entries()
+            .unwrap()
+            .map(|e| {
+                e.unwrap()
+                    .path()
+                    .unwrap()
+                    .to_string_lossy()
+                    .trim_end_matches('/')
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wildcard_matches_within_segment() {
+        assert!(wildcard_match("*.log", "build.log"));
+        assert!(wildcard_match("file?.txt", "file1.txt"));
+        assert!(!wildcard_match("*.log", "build.txt"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn dockerignore_excludes_directories_and_contents() {
+        let ignore = DockerIgnore::parse("# comment\n\nnode_modules\n/target/\n");
+        assert!(ignore.is_excluded("node_modules"));
+        assert!(ignore.is_excluded("node_modules/pkg/index.js"));
+        assert!(ignore.is_excluded("target/debug/app"));
+        assert!(!ignore.is_excluded("src/main.rs"));
+        assert!(!ignore.is_excluded("src/node_modules_helper.rs"));
+    }
+
+    #[test]
+    fn dockerignore_double_star_and_negation() {
+        let ignore = DockerIgnore::parse("**/*.log\n*.md\n!README.md\n");
+        assert!(ignore.is_excluded("app.log"));
+        assert!(ignore.is_excluded("logs/deep/app.log"));
+        assert!(ignore.is_excluded("CHANGELOG.md"));
+        assert!(!ignore.is_excluded("README.md"));
+        assert!(!ignore.is_excluded("docs/guide.md"));
+    }
+
+    #[test]
+    fn excluded_directories_are_walked_only_for_negations() {
+        let ignore = DockerIgnore::parse("node_modules\ntarget\n!node_modules/keep/*.js\n");
+        assert!(ignore.may_reinclude_below("node_modules"));
+        assert!(ignore.may_reinclude_below("node_modules/keep"));
+        assert!(!ignore.may_reinclude_below("node_modules/other"));
+        assert!(!ignore.may_reinclude_below("target"));
+        // `!node_modules` re-includes the directory itself, not below it.
+        assert!(!DockerIgnore::parse("vendor\n!vendor\n").may_reinclude_below("vendor"));
+        assert!(DockerIgnore::parse("vendor\n!**/*.md\n").may_reinclude_below("vendor"));
+    }
+
+    #[test]
+    fn build_context_skips_excluded_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Dockerfile"), "FROM alpine\n").unwrap();
+        std::fs::write(
+            dir.path().join(".dockerignore"),
+            "cache\nnode_modules\n!node_modules/keep.js\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("cache").join("deep")).unwrap();
+        std::fs::create_dir(dir.path().join("node_modules")).unwrap();
+        std::fs::write(dir.path().join("node_modules").join("keep.js"), "").unwrap();
+        std::fs::write(dir.path().join("node_modules").join("drop.js"), "").unwrap();
+
+        let names = archive_names(&create_build_context(dir.path(), None).unwrap().tar);
+        assert!(!names.iter().any(|n| n.starts_with("cache")));
+        assert!(names.contains(&"node_modules/keep.js".to_string()));
+        assert!(!names.contains(&"node_modules/drop.js".to_string()));
+    }
+
+    #[test]
+    fn build_context_respects_dockerignore() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Dockerfile"), "FROM alpine\n").unwrap();
+        std::fs::write(dir.path().join(".dockerignore"), "secret.txt\nDockerfile\n").unwrap();
+        std::fs::write(dir.path().join("app.py"), "print(1)\n").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "token\n").unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src").join("lib.py"), "").unwrap();
+
+        let ctx = create_build_context(dir.path(), None).unwrap();
+        assert_eq!(ctx.dockerfile, "Dockerfile");
+
+        let names = archive_names(&ctx.tar);
+        assert!(names.contains(&"app.py".to_string()));
+        assert!(names.contains(&"src/lib.py".to_string()));
+        // Ignored Dockerfile is still sent.
+        assert!(names.contains(&"Dockerfile".to_string()));
+        assert!(!names.contains(&"secret.txt".to_string()));
+    }
+
+    #[test]
+    fn build_context_embeds_external_dockerfile() {
+        let context = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let dockerfile = other.path().join("Dockerfile.dev");
+        std::fs::write(&dockerfile, "FROM alpine\n").unwrap();
+
+        let ctx = create_build_context(context.path(), Some(&dockerfile)).unwrap();
+        assert_eq!(ctx.dockerfile, EXTERNAL_DOCKERFILE_NAME);
+        assert!(archive_names(&ctx.tar).contains(&EXTERNAL_DOCKERFILE_NAME.to_string()));
+    }
+
+    #[test]
+    fn build_context_requires_dockerfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = create_build_context(dir.path(), None).unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+    }
+}
@@ -0,0 +1,233 @@
+//! Build cache usage and `builder prune`.
+//!
+//! BuildKit keeps its cache inside the engine: the Docker API reports it
+//! in `/system/df` and removes it with `POST /build/prune`. bollard has no
+//! method for the latter, so it goes through [`docker::engine_request`].
+//!
+//! Without `all`, a prune removes cache records nothing uses and no other
+//! record shares; with `all`, every unused record. Previews apply the same
+//! rule to the records `/system/df` lists, so they are close but not exact:
+//! BuildKit may release parents of removed records as well.
+
+use std::time::Duration;
+
+use bollard::models::BuildCache;
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+
+use crate::docker;
+use crate::error::AppError;
+use crate::prune::{short_id, RemovalItem, RemovalReport, ResourceKind};
+
+const BUILDER_PRUNE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Build cache held by the engine.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildCacheUsage {
+    pub total_bytes: u64,
+    /// What `builder prune --all` would free: records nothing uses.
+    pub reclaimable_bytes: u64,
+    pub records: usize,
+    pub in_use: usize,
+    /// Usage per record type (`regular`, `source.local`, `exec.cachemount`...),
+    /// largest first.
+    pub types: Vec<BuildCacheTypeUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildCacheTypeUsage {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub records: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BuildPruneResponse {
+    #[serde(default)]
+    caches_deleted: Option<Vec<String>>,
+    #[serde(default)]
+    space_reclaimed: Option<i64>,
+}
+
+async fn build_cache(docker: &Docker) -> Result<Vec<BuildCache>, AppError> {
+    Ok(docker.df().await?.build_cache.unwrap_or_default())
+}
+
+fn record_size(record: &BuildCache) -> u64 {
+    record.size.and_then(|s| u64::try_from(s).ok()).unwrap_or(0)
+}
+
+fn record_type(record: &BuildCache) -> String {
+    record
+        .typ
+        .map(|t| t.to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Whether a prune removes `record`.
+fn prunable(record: &BuildCache, all: bool) -> bool {
+    let in_use = record.in_use.unwrap_or(false);
+    let shared = record.shared.unwrap_or(false);
+    !in_use && (all || !shared)
+}
+
+/// Summarize the engine's build cache.
+pub async fn cache_usage(docker: &Docker) -> Result<BuildCacheUsage, AppError> {
+    Ok(summarize(&build_cache(docker).await?))
+}
+
+fn summarize(records: &[BuildCache]) -> BuildCacheUsage {
+    let mut usage = BuildCacheUsage {
+        records: records.len(),
+        ..Default::default()
+    };
+    for record in records {
+        let size = record_size(record);
+        usage.total_bytes += size;
+        if record.in_use.unwrap_or(false) {
+            usage.in_use += 1;
+        }
+        if prunable(record, true) {
+            usage.reclaimable_bytes += size;
+        }
+        let kind = record_type(record);
+        match usage.types.iter_mut().find(|t| t.kind == kind) {
+            Some(entry) => {
+                entry.records += 1;
+                entry.size_bytes += size;
+            }
+            None => usage.types.push(BuildCacheTypeUsage {
+                kind,
+                records: 1,
+                size_bytes: size,
+            }),
+        }
+    }
+    usage
+        .types
+        .sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.kind.cmp(&b.kind)));
+    usage
+}
+
+/// Preview `builder prune`.
+pub async fn preview_prune(docker: &Docker, all: bool) -> Result<RemovalReport, AppError> {
+    Ok(plan_prune(&build_cache(docker).await?, all))
+}
+
+fn plan_prune(records: &[BuildCache], all: bool) -> RemovalReport {
+    let mut report = RemovalReport {
+        dry_run: true,
+        ..Default::default()
+    };
+    for record in records.iter().filter(|r| prunable(r, all)) {
+        report.push(RemovalItem {
+            kind: ResourceKind::BuildCache,
+            id: short_id(record.id.as_deref().unwrap_or_default()),
+            name: record
+                .description
+                .clone()
+                .unwrap_or_else(|| record_type(record)),
+            size_bytes: Some(record_size(record)),
+        });
+    }
+    report
+}
+
+/// Remove unused build cache (`docker builder prune`).
+pub async fn prune(all: bool) -> Result<RemovalReport, AppError> {
+    let path = format!("/build/prune?all={}", all);
+    let (status, body) = tokio::time::timeout(
+        BUILDER_PRUNE_TIMEOUT,
+        docker::engine_request(hyper::Method::POST, &path, None),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Runtime(format!(
+            "Build cache prune timed out after {:?}",
+            BUILDER_PRUNE_TIMEOUT
+        ))
+    })??;
+    if !status.is_success() {
+        return Err(AppError::Runtime(format!(
+            "Build cache prune failed ({}): {}",
+            status,
+            docker::engine_error_message(&body)
+        )));
+    }
+
+    let response: BuildPruneResponse = serde_json::from_slice(&body)?;
+    let mut report = RemovalReport::default();
+    for id in response.caches_deleted.unwrap_or_default() {
+        report.items.push(RemovalItem {
+            kind: ResourceKind::BuildCache,
+            id: short_id(&id),
+            name: String::new(),
+            size_bytes: None,
+        });
+    }
+    report.reclaimed_bytes = response
+        .space_reclaimed
+        .and_then(|s| u64::try_from(s).ok())
+        .unwrap_or(0);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use bollard::models::BuildCacheTypeEnum;
+
+    use super::*;
+
+    fn record(
+        id: &str,
+        typ: BuildCacheTypeEnum,
+        size: i64,
+        in_use: bool,
+        shared: bool,
+    ) -> BuildCache {
+        BuildCache {
+            id: Some(id.to_string()),
+            typ: Some(typ),
+            size: Some(size),
+            in_use: Some(in_use),
+            shared: Some(shared),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn usage_groups_by_type_and_counts_unused_records() {
+        let records = [
+            record("a", BuildCacheTypeEnum::REGULAR, 100, true, false),
+            record("b", BuildCacheTypeEnum::REGULAR, 200, false, true),
+            record("c", BuildCacheTypeEnum::EXEC_CACHEMOUNT, 500, false, false),
+        ];
+        let usage = summarize(&records);
+        assert_eq!(usage.total_bytes, 800);
+        assert_eq!(usage.reclaimable_bytes, 700);
+        assert_eq!(usage.in_use, 1);
+        assert_eq!(usage.types[0].kind, "exec.cachemount");
+        assert_eq!(usage.types[1].records, 2);
+    }
+
+    #[test]
+    fn prune_keeps_shared_records_unless_all() {
+        let records = [
+            record("a", BuildCacheTypeEnum::REGULAR, 100, true, false),
+            record("b", BuildCacheTypeEnum::REGULAR, 200, false, true),
+            record("c", BuildCacheTypeEnum::SOURCE_LOCAL, 500, false, false),
+        ];
+        let report = plan_prune(&records, false);
+        assert!(report.dry_run);
+        assert_eq!(report.items.len(), 1);
+        assert_eq!(report.items[0].id, "c");
+        assert_eq!(report.reclaimed_bytes, 500);
+
+        assert_eq!(plan_prune(&records, true).reclaimed_bytes, 700);
+    }
+}
@@ -0,0 +1,132 @@
+//! Bounded-parallel bulk operations.
+//!
+//! Runs one operation per target with at most `parallelism` in flight and
+//! collects a per-target outcome instead of stopping at the first failure,
+//! so `cratebay container stop --all` reports every container it could not
+//! stop.
+
+use std::future::Future;
+
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+
+use crate::error::AppError;
+
+/// Operations in flight when the caller does not choose.
+pub const DEFAULT_PARALLELISM: usize = 4;
+
+/// Upper bound on `parallelism`, to keep the Docker daemon responsive.
+pub const MAX_PARALLELISM: usize = 32;
+
+/// Outcome for one target.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkItem {
+    pub target: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcomes in target order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkReport {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub items: Vec<BulkItem>,
+}
+
+impl BulkReport {
+    /// Whether every target succeeded.
+    pub fn is_success(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Run `op` for every target, `parallelism` (clamped to
+/// 1..=[`MAX_PARALLELISM`]) at a time.
+pub async fn run<F, Fut>(targets: Vec<String>, parallelism: usize, op: F) -> BulkReport
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<(), AppError>>,
+{
+    let items: Vec<BulkItem> = stream::iter(targets)
+        .map(|target| {
+            let outcome = op(target.clone());
+            async move {
+                match outcome.await {
+                    Ok(()) => BulkItem {
+                        target,
+                        ok: true,
+                        error: None,
+                    },
+                    Err(e) => BulkItem {
+                        target,
+                        ok: false,
+                        error: Some(e.to_string()),
+                    },
+                }
+            }
+        })
+        .buffered(parallelism.clamp(1, MAX_PARALLELISM))
+        .collect()
+        .await;
+    let succeeded = items.iter().filter(|item| item.ok).count();
+    BulkReport {
+        succeeded,
+        failed: items.len() - succeeded,
+        items,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn runs_bounded_and_reports_every_target() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let targets: Vec<String> = (0..10).map(|i| format!("c{}", i)).collect();
+
+        let report = run(targets, 3, |target| {
+            let running = Arc::clone(&running);
+            let peak = Arc::clone(&peak);
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                if target == "c4" {
+                    return Err(AppError::NotFound {
+                        entity: "container".to_string(),
+                        id: target,
+                    });
+                }
+                Ok(())
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!((report.succeeded, report.failed), (9, 1));
+        assert!(!report.is_success());
+        let order: Vec<_> = report.items.iter().map(|i| i.target.as_str()).collect();
+        assert_eq!(order[..3], ["c0", "c1", "c2"]);
+        assert!(report.items[4]
+            .error
+            .as_deref()
+            .is_some_and(|e| e.contains("c4")));
+    }
+
+    #[tokio::test]
+    async fn zero_parallelism_still_makes_progress() {
+        let report = run(vec!["a".to_string()], 0, |_| async { Ok(()) }).await;
+        assert!(report.is_success());
+    }
+}
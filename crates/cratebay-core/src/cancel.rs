@@ -0,0 +1,54 @@
+//! Cooperative cancellation for image pulls and downloads.
+//!
+//! Long-running operations take an optional [`CancellationToken`], cancelled
+//! by Ctrl-C in the CLI or by a task's cancel button in the GUI. A cancelled
+//! operation stops reading its stream, removes what it had written and fails
+//! with [`AppError::Cancelled`].
+
+use std::future::Future;
+
+pub use tokio_util::sync::CancellationToken;
+
+use crate::error::AppError;
+
+/// Await `future` unless `cancel` fires first. `what` names the operation
+/// in the error, e.g. `Pull of 'nginx:latest'`.
+pub async fn run<T>(
+    cancel: Option<&CancellationToken>,
+    what: &str,
+    future: impl Future<Output = T>,
+) -> Result<T, AppError> {
+    let Some(cancel) = cancel else {
+        return Ok(future.await);
+    };
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => Err(AppError::Cancelled(what.to_string())),
+        value = future => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn run_stops_at_cancellation() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let err = run(
+            Some(&token),
+            "Pull of 'nginx'",
+            std::future::pending::<()>(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::Cancelled(_)));
+        assert_eq!(err.to_string(), "Pull of 'nginx' cancelled");
+    }
+
+    #[tokio::test]
+    async fn run_without_token_awaits_the_future() {
+        assert_eq!(run(None, "Pull", async { 7 }).await.unwrap(), 7);
+    }
+}
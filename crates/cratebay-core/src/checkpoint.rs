@@ -0,0 +1,192 @@
+//! Container checkpoint/restore (CRIU).
+//!
+//! Docker's checkpoint API freezes a running container's processes to disk
+//! with CRIU and starts the container again from that state later, also
+//! after the engine or the runtime VM restarted. It needs a Linux engine
+//! with experimental features enabled and `criu` installed; the built-in
+//! runtime images ship both. bollard has no
+//! methods for it, so requests go through [`docker::engine_request`].
+//!
+//! Checkpoints are kept in the engine next to the container and are
+//! removed with it.
+
+use std::time::Duration;
+
+use hyper::{Method, StatusCode};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::docker;
+use crate::error::AppError;
+
+/// Dumping or restoring a large process tree can take a while.
+const CHECKPOINT_TIMEOUT: Duration = Duration::from_secs(300);
+
+const ENGINE_HINT: &str = "checkpoints need an engine with experimental features enabled \
+     (`\"experimental\": true` in daemon.json) and criu installed";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CheckpointSummary {
+    name: String,
+}
+
+/// Checkpoint and container names go into URL paths; allow what Docker
+/// allows for names (IDs are a subset).
+fn validate_name(kind: &str, name: &str) -> Result<(), AppError> {
+    let valid = name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!(
+            "Invalid {} name '{}': use letters, digits, '_', '.' and '-'",
+            kind, name
+        )))
+    }
+}
+
+/// Map a failed checkpoint request to an error.
+fn request_error(
+    status: StatusCode,
+    message: String,
+    container: &str,
+    name: Option<&str>,
+) -> AppError {
+    let lower = message.to_ascii_lowercase();
+    if status == StatusCode::NOT_FOUND && lower.contains("no such container") {
+        return AppError::NotFound {
+            entity: "container".to_string(),
+            id: container.to_string(),
+        };
+    }
+    if let Some(name) =
+        name.filter(|_| lower.contains("no such file") || status == StatusCode::NOT_FOUND)
+    {
+        return AppError::NotFound {
+            entity: "checkpoint".to_string(),
+            id: format!("{}/{}", container, name),
+        };
+    }
+    if lower.contains("experimental") || lower.contains("criu") {
+        return AppError::Validation(format!("{}; {}", message, ENGINE_HINT));
+    }
+    AppError::Runtime(format!(
+        "Checkpoint request failed ({}): {}",
+        status, message
+    ))
+}
+
+async fn request(
+    method: Method,
+    path: &str,
+    body: Option<serde_json::Value>,
+    container: &str,
+    name: Option<&str>,
+) -> Result<bytes::Bytes, AppError> {
+    let (status, body) = tokio::time::timeout(
+        CHECKPOINT_TIMEOUT,
+        docker::engine_request(method, path, body),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Runtime(format!(
+            "Checkpoint request timed out after {:?}",
+            CHECKPOINT_TIMEOUT
+        ))
+    })??;
+    if status.is_success() {
+        Ok(body)
+    } else {
+        Err(request_error(
+            status,
+            docker::engine_error_message(&body),
+            container,
+            name,
+        ))
+    }
+}
+
+/// Checkpoint a running container. It stops unless `leave_running`.
+pub async fn create(container: &str, name: &str, leave_running: bool) -> Result<(), AppError> {
+    validate_name("container", container)?;
+    validate_name("checkpoint", name)?;
+    let body = json!({ "CheckpointID": name, "Exit": !leave_running });
+    let path = format!("/containers/{}/checkpoints", container);
+    request(Method::POST, &path, Some(body), container, None).await?;
+    Ok(())
+}
+
+/// Names of a container's checkpoints.
+pub async fn list(container: &str) -> Result<Vec<String>, AppError> {
+    validate_name("container", container)?;
+    let path = format!("/containers/{}/checkpoints", container);
+    let body = request(Method::GET, &path, None, container, None).await?;
+    // The engine answers `null` when there are none.
+    let checkpoints: Option<Vec<CheckpointSummary>> = serde_json::from_slice(&body)?;
+    Ok(checkpoints
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| c.name)
+        .collect())
+}
+
+/// Delete a checkpoint.
+pub async fn remove(container: &str, name: &str) -> Result<(), AppError> {
+    validate_name("container", container)?;
+    validate_name("checkpoint", name)?;
+    let path = format!("/containers/{}/checkpoints/{}", container, name);
+    request(Method::DELETE, &path, None, container, Some(name)).await?;
+    Ok(())
+}
+
+/// Start a stopped container from a checkpoint.
+pub async fn restore(container: &str, name: &str) -> Result<(), AppError> {
+    validate_name("container", container)?;
+    validate_name("checkpoint", name)?;
+    let path = format!("/containers/{}/start?checkpoint={}", container, name);
+    request(Method::POST, &path, None, container, Some(name)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_must_be_safe_in_url_paths() {
+        assert!(validate_name("checkpoint", "before-upgrade.1").is_ok());
+        assert!(validate_name("checkpoint", "../x").is_err());
+        assert!(validate_name("container", "web?all=1").is_err());
+        assert!(validate_name("container", "").is_err());
+    }
+
+    #[test]
+    fn engine_errors_map_to_app_errors() {
+        let err = request_error(
+            StatusCode::NOT_FOUND,
+            "No such container: web".to_string(),
+            "web",
+            Some("cp1"),
+        );
+        assert!(matches!(err, AppError::NotFound { ref entity, .. } if entity == "container"));
+
+        let err = request_error(
+            StatusCode::NOT_FOUND,
+            "checkpoint cp1 does not exist".to_string(),
+            "web",
+            Some("cp1"),
+        );
+        assert!(matches!(err, AppError::NotFound { ref id, .. } if id == "web/cp1"));
+
+        let err = request_error(
+            StatusCode::BAD_REQUEST,
+            "checkpoint is only supported in experimental mode".to_string(),
+            "web",
+            None,
+        );
+        assert!(matches!(err, AppError::Validation(ref m) if m.contains("daemon.json")));
+    }
+}
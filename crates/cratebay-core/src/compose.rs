@@ -0,0 +1,1711 @@
+//! Compose file validation and conversion to Kubernetes manifests.
+//!
+//! Compose projects are run by `docker compose` against the selected
+//! engine; this module only reads compose files. [`validate_file`] checks
+//! them against the parts of the Compose spec that matter before `up`
+//! (unknown keys, port syntax, references to undeclared services, networks
+//! and volumes, host ports published twice), and [`convert_kubernetes_file`]
+//! turns services into Deployments, Services and PersistentVolumeClaims the
+//! way kompose does, for `kubectl apply`.
+//!
+//! Values are interpolated first, as `docker compose` does: `$VAR`,
+//! `${VAR}`, `${VAR:-default}`, `${VAR:?error}`, `${VAR:+alt}` and `$$`,
+//! from the process environment over a `.env` file next to the compose
+//! file. Diagnostics carry a dotted path (`services.web.ports[0]`) and the
+//! line of that node, found by following the path through the indentation
+//! of the source; flow collections (`[a, b]`) point at their parent key.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value as Json};
+use serde_yaml_ng::{Mapping, Value};
+
+use crate::dockerfile_lint;
+use crate::error::AppError;
+use crate::models::{ComposeConversion, ComposeDiagnostic, ComposeValidation, LintSeverity};
+
+/// File names `docker compose` looks for, in order.
+pub const COMPOSE_FILE_NAMES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// Size requested for PersistentVolumeClaims made from named volumes.
+const DEFAULT_VOLUME_SIZE: &str = "1Gi";
+
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "version", "name", "include", "services", "networks", "volumes", "configs", "secrets",
+];
+
+const SERVICE_KEYS: &[&str] = &[
+    "annotations",
+    "attach",
+    "blkio_config",
+    "build",
+    "cap_add",
+    "cap_drop",
+    "cgroup",
+    "cgroup_parent",
+    "command",
+    "configs",
+    "container_name",
+    "cpu_count",
+    "cpu_percent",
+    "cpu_period",
+    "cpu_quota",
+    "cpu_rt_period",
+    "cpu_rt_runtime",
+    "cpu_shares",
+    "cpus",
+    "cpuset",
+    "credential_spec",
+    "depends_on",
+    "deploy",
+    "develop",
+    "device_cgroup_rules",
+    "devices",
+    "dns",
+    "dns_opt",
+    "dns_search",
+    "domainname",
+    "entrypoint",
+    "env_file",
+    "environment",
+    "expose",
+    "extends",
+    "external_links",
+    "extra_hosts",
+    "gpus",
+    "group_add",
+    "healthcheck",
+    "hostname",
+    "image",
+    "init",
+    "ipc",
+    "isolation",
+    "labels",
+    "links",
+    "logging",
+    "mac_address",
+    "mem_limit",
+    "mem_reservation",
+    "mem_swappiness",
+    "memswap_limit",
+    "network_mode",
+    "networks",
+    "oom_kill_disable",
+    "oom_score_adj",
+    "pid",
+    "pids_limit",
+    "platform",
+    "ports",
+    "post_start",
+    "pre_stop",
+    "privileged",
+    "profiles",
+    "pull_policy",
+    "read_only",
+    "restart",
+    "runtime",
+    "scale",
+    "secrets",
+    "security_opt",
+    "shm_size",
+    "stdin_open",
+    "stop_grace_period",
+    "stop_signal",
+    "storage_opt",
+    "sysctls",
+    "tmpfs",
+    "tty",
+    "ulimits",
+    "user",
+    "userns_mode",
+    "uts",
+    "volumes",
+    "volumes_from",
+    "working_dir",
+];
+
+/// Service keys with no Kubernetes counterpart in the conversion.
+const UNCONVERTED_KEYS: &[&str] = &[
+    "configs",
+    "devices",
+    "env_file",
+    "extends",
+    "extra_hosts",
+    "healthcheck",
+    "ipc",
+    "links",
+    "logging",
+    "network_mode",
+    "pid",
+    "secrets",
+    "sysctls",
+    "ulimits",
+    "volumes_from",
+];
+
+const DEPENDS_ON_CONDITIONS: &[&str] = &[
+    "service_started",
+    "service_healthy",
+    "service_completed_successfully",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Seg {
+    Key(String),
+    Index(usize),
+}
+
+fn key(k: &str) -> Seg {
+    Seg::Key(k.to_string())
+}
+
+fn path_string(path: &[Seg]) -> String {
+    let mut out = String::new();
+    for seg in path {
+        match seg {
+            Seg::Key(k) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(k);
+            }
+            Seg::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
+
+fn joined(path: &[Seg], seg: Seg) -> Vec<Seg> {
+    let mut path = path.to_vec();
+    path.push(seg);
+    path
+}
+
+// ---------------------------------------------------------------------------
+// Source lines
+// ---------------------------------------------------------------------------
+
+/// A block-structure entry of the source: a key line or a `-` list item.
+/// `- key: value` yields two entries on the same line, the key indented
+/// past the dash.
+struct Entry<'a> {
+    line: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+fn outline(source: &str) -> Vec<Entry<'_>> {
+    let mut entries = Vec::new();
+    for (i, raw) in source.lines().enumerate() {
+        let mut text = raw.trim_start();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let mut indent = raw.len() - text.len();
+        while text == "-" || text.starts_with("- ") {
+            entries.push(Entry {
+                line: i + 1,
+                indent,
+                text: "-",
+            });
+            let rest = text[1..].trim_start();
+            indent += text.len() - rest.len();
+            text = rest;
+        }
+        if !text.is_empty() {
+            entries.push(Entry {
+                line: i + 1,
+                indent,
+                text,
+            });
+        }
+    }
+    entries
+}
+
+/// Mapping key of an outline entry, unquoted.
+fn entry_key(text: &str) -> Option<&str> {
+    let key = match text.split_once(": ") {
+        Some((key, _)) => key,
+        None => text.strip_suffix(':')?,
+    };
+    Some(key.trim().trim_matches(['"', '\'']))
+}
+
+/// 1-based line of the node at `path`, or of its closest ancestor found.
+fn locate(entries: &[Entry], path: &[Seg]) -> Option<usize> {
+    let mut range = 0..entries.len();
+    let mut found = None;
+    for seg in path {
+        let block = &entries[range.clone()];
+        let Some(base) = block.first().map(|e| e.indent) else {
+            break;
+        };
+        let mut children = block.iter().enumerate().filter(|(_, e)| e.indent == base);
+        let hit = match seg {
+            Seg::Key(k) => children.find(|(_, e)| entry_key(e.text) == Some(k)),
+            Seg::Index(n) => children.filter(|(_, e)| e.text == "-").nth(*n),
+        };
+        let Some((offset, entry)) = hit else {
+            break;
+        };
+        found = Some(entry.line);
+
+        // The node's block: deeper entries, plus a list under a key at the
+        // key's own indentation.
+        let pos = range.start + offset;
+        let mut end = pos + 1;
+        while end < range.end {
+            let next = &entries[end];
+            let nested = next.indent > entry.indent
+                || (entry.text != "-" && next.indent == entry.indent && next.text == "-");
+            if !nested {
+                break;
+            }
+            end += 1;
+        }
+        range = pos + 1..end;
+    }
+    found
+}
+
+// ---------------------------------------------------------------------------
+// Interpolation
+// ---------------------------------------------------------------------------
+
+/// Interpolate `$VAR` forms in `s`. Returns the result and the variables
+/// that were unset and defaulted to blank.
+fn interpolate(s: &str, env: &HashMap<String, String>) -> Result<(String, Vec<String>), String> {
+    let mut out = String::new();
+    let mut unset = Vec::new();
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        if let Some(tail) = after.strip_prefix('$') {
+            out.push('$');
+            rest = tail;
+        } else if let Some(braced) = after.strip_prefix('{') {
+            let close = braced
+                .find('}')
+                .ok_or_else(|| format!("Unterminated `${{` in \"{}\"", s))?;
+            out.push_str(&substitute(&braced[..close], env, &mut unset)?);
+            rest = &braced[close + 1..];
+        } else {
+            let len = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            let name = &after[..len];
+            if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+                out.push('$');
+            } else {
+                match env.get(name) {
+                    Some(value) => out.push_str(value),
+                    None => unset.push(name.to_string()),
+                }
+            }
+            rest = &after[len..];
+        }
+    }
+    out.push_str(rest);
+    Ok((out, unset))
+}
+
+/// Value of the inside of `${...}`.
+fn substitute(
+    expr: &str,
+    env: &HashMap<String, String>,
+    unset: &mut Vec<String>,
+) -> Result<String, String> {
+    let len = expr
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(expr.len());
+    let (name, op) = expr.split_at(len);
+    if name.is_empty() {
+        return Err(format!("Invalid interpolation `${{{}}}`", expr));
+    }
+    let value = env.get(name);
+    let non_empty = value.filter(|v| !v.is_empty());
+    let (colon, op) = match op.strip_prefix(':') {
+        Some(op) => (true, op),
+        None => (false, op),
+    };
+    let present = if colon { non_empty } else { value };
+    let Some(first) = op.chars().next() else {
+        return match value {
+            Some(v) if !colon => Ok(v.clone()),
+            _ => {
+                unset.push(name.to_string());
+                Ok(String::new())
+            }
+        };
+    };
+    let arg = &op[1..];
+    match first {
+        '-' => Ok(present.cloned().unwrap_or_else(|| arg.to_string())),
+        '+' => Ok(if present.is_some() {
+            arg.to_string()
+        } else {
+            String::new()
+        }),
+        '?' => present.cloned().ok_or_else(|| {
+            if arg.is_empty() {
+                format!("Required variable {} is missing a value", name)
+            } else {
+                format!("Required variable {} is missing a value: {}", name, arg)
+            }
+        }),
+        _ => Err(format!("Invalid interpolation `${{{}}}`", expr)),
+    }
+}
+
+/// `KEY=VALUE` lines of a `.env` file.
+fn parse_env_file(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| {
+            let l = l.strip_prefix("export ").unwrap_or(l);
+            let (key, value) = l.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+struct Checker<'a> {
+    entries: Vec<Entry<'a>>,
+    diagnostics: Vec<ComposeDiagnostic>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, severity: LintSeverity, path: &[Seg], message: impl Into<String>) {
+        self.diagnostics.push(ComposeDiagnostic {
+            severity,
+            path: path_string(path),
+            line: locate(&self.entries, path),
+            message: message.into(),
+        });
+    }
+
+    fn error(&mut self, path: &[Seg], message: impl Into<String>) {
+        self.report(LintSeverity::Error, path, message);
+    }
+
+    fn warning(&mut self, path: &[Seg], message: impl Into<String>) {
+        self.report(LintSeverity::Warning, path, message);
+    }
+
+    fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.severity == LintSeverity::Error)
+    }
+
+    fn interpolate_value(
+        &mut self,
+        value: &mut Value,
+        path: &mut Vec<Seg>,
+        env: &HashMap<String, String>,
+    ) {
+        match value {
+            Value::String(s) => match interpolate(s, env) {
+                Ok((result, unset)) => {
+                    for name in unset {
+                        self.warning(
+                            path,
+                            format!("Variable {} is not set; using a blank string", name),
+                        );
+                    }
+                    *s = result;
+                }
+                Err(message) => self.error(path, message),
+            },
+            Value::Sequence(items) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    path.push(Seg::Index(i));
+                    self.interpolate_value(item, path, env);
+                    path.pop();
+                }
+            }
+            Value::Mapping(map) => {
+                for (k, v) in map.iter_mut() {
+                    path.push(key(&scalar_string(k).unwrap_or_default()));
+                    self.interpolate_value(v, path, env);
+                    path.pop();
+                }
+            }
+            Value::Tagged(tagged) => self.interpolate_value(&mut tagged.value, path, env),
+            _ => {}
+        }
+    }
+}
+
+/// A scalar as a string (numbers and booleans included).
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Names of a list (`[a, b]`) or the keys of a mapping (`{a: ..., b: ...}`).
+fn names(value: &Value) -> Vec<(Seg, String)> {
+    match value {
+        Value::Sequence(items) => items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| scalar_string(v).map(|s| (Seg::Index(i), s)))
+            .collect(),
+        Value::Mapping(map) => map
+            .keys()
+            .filter_map(scalar_string)
+            .map(|k| (key(&k), k))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn declared(doc: &Mapping, section: &str) -> HashSet<String> {
+    doc.get(section)
+        .map(|v| names(v).into_iter().map(|(_, n)| n).collect())
+        .unwrap_or_default()
+}
+
+fn valid_service_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// A port or a `start-end` range.
+fn parse_port_range(s: &str) -> Option<(u16, u16)> {
+    let (start, end) = match s.split_once('-') {
+        Some((a, b)) => (a.parse().ok()?, b.parse().ok()?),
+        None => {
+            let p = s.parse().ok()?;
+            (p, p)
+        }
+    };
+    (start > 0 && start <= end).then_some((start, end))
+}
+
+/// Short port syntax `[[ip:]published:]target[/protocol]`.
+#[derive(Debug, PartialEq)]
+struct PortSpec {
+    host_ip: String,
+    published: Option<(u16, u16)>,
+    target: (u16, u16),
+    protocol: String,
+}
+
+fn parse_port(spec: &str) -> Result<PortSpec, String> {
+    let invalid = || format!("Invalid port `{}`", spec);
+    let (rest, protocol) = match spec.rsplit_once('/') {
+        Some((rest, proto)) => (rest, proto.to_ascii_lowercase()),
+        None => (spec, "tcp".to_string()),
+    };
+    if !matches!(protocol.as_str(), "tcp" | "udp" | "sctp") {
+        return Err(format!(
+            "Unknown protocol `{}` in port `{}`",
+            protocol, spec
+        ));
+    }
+    // `[::1]:8080:80` keeps the IPv6 address whole.
+    let (host_ip, rest) = match rest.strip_prefix('[') {
+        Some(v6) => {
+            let (ip, tail) = v6.split_once("]:").ok_or_else(invalid)?;
+            (ip.to_string(), tail)
+        }
+        None => (String::new(), rest),
+    };
+    let parts: Vec<&str> = rest.rsplitn(3, ':').collect();
+    let target = parse_port_range(parts[0]).ok_or_else(invalid)?;
+    let published = match parts.get(1) {
+        Some(p) if !p.is_empty() => Some(parse_port_range(p).ok_or_else(invalid)?),
+        _ => None,
+    };
+    let host_ip = match parts.get(2) {
+        Some(ip) if host_ip.is_empty() => ip.to_string(),
+        Some(_) => return Err(invalid()),
+        None => host_ip,
+    };
+    if let Some((start, end)) = published {
+        if target.0 != target.1 && end - start != target.1 - target.0 {
+            return Err(format!("Port ranges of different sizes in `{}`", spec));
+        }
+    }
+    Ok(PortSpec {
+        host_ip,
+        published,
+        target,
+        protocol,
+    })
+}
+
+/// Port entry of a service, short or long syntax.
+fn service_port(value: &Value) -> Result<PortSpec, String> {
+    match value {
+        Value::Number(n) => parse_port(&n.to_string()),
+        Value::String(s) => parse_port(s),
+        Value::Mapping(m) => {
+            let port = |k: &str| m.get(k).and_then(scalar_string);
+            let target = port("target")
+                .and_then(|t| parse_port_range(&t))
+                .ok_or_else(|| "Long port syntax needs a numeric `target`".to_string())?;
+            let published = match port("published") {
+                Some(p) => {
+                    Some(parse_port_range(&p).ok_or_else(|| format!("Invalid port `{}`", p))?)
+                }
+                None => None,
+            };
+            Ok(PortSpec {
+                host_ip: port("host_ip").unwrap_or_default(),
+                published,
+                target,
+                protocol: port("protocol").unwrap_or_else(|| "tcp".to_string()),
+            })
+        }
+        _ => Err("A port must be a string, number or mapping".to_string()),
+    }
+}
+
+/// A volume mount of a service, short or long syntax.
+#[derive(Debug, PartialEq)]
+struct VolumeMount {
+    kind: String,
+    source: Option<String>,
+    target: String,
+    read_only: bool,
+}
+
+fn is_host_path(source: &str) -> bool {
+    source.starts_with(['/', '.', '~', '$'])
+        || source.as_bytes().get(1) == Some(&b':')
+        || source.starts_with("\\\\")
+}
+
+fn volume_mount(value: &Value) -> Result<VolumeMount, String> {
+    match value {
+        Value::String(s) => {
+            // `C:\data:/data` keeps the drive letter with the source.
+            let (source, rest) = match s.as_bytes().get(1) {
+                Some(b':') if s.len() > 2 => {
+                    let (src, rest) = s[2..].split_once(':').ok_or("Missing mount target")?;
+                    (Some(format!("{}{}", &s[..2], src)), rest.to_string())
+                }
+                _ => match s.split_once(':') {
+                    Some((src, rest)) => (Some(src.to_string()), rest.to_string()),
+                    None => (None, s.clone()),
+                },
+            };
+            let (target, mode) = match rest.split_once(':') {
+                Some((target, mode)) => (target.to_string(), mode.to_string()),
+                None => (rest, String::new()),
+            };
+            let kind = match &source {
+                None => "volume",
+                Some(src) if is_host_path(src) => "bind",
+                Some(_) => "volume",
+            };
+            Ok(VolumeMount {
+                kind: kind.to_string(),
+                source,
+                target,
+                read_only: mode.split(',').any(|m| m == "ro"),
+            })
+        }
+        Value::Mapping(m) => {
+            let field = |k: &str| m.get(k).and_then(scalar_string);
+            let kind = field("type").unwrap_or_else(|| "volume".to_string());
+            if !matches!(
+                kind.as_str(),
+                "volume" | "bind" | "tmpfs" | "npipe" | "cluster" | "image"
+            ) {
+                return Err(format!("Unknown mount type `{}`", kind));
+            }
+            Ok(VolumeMount {
+                kind,
+                source: field("source"),
+                target: field("target").ok_or("Long volume syntax needs a `target`")?,
+                read_only: m.get("read_only").and_then(Value::as_bool).unwrap_or(false),
+            })
+        }
+        _ => Err("A volume must be a string or mapping".to_string()),
+    }
+}
+
+fn valid_restart(policy: &str) -> bool {
+    match policy.split_once(':') {
+        Some(("on-failure", n)) => n.parse::<u32>().is_ok(),
+        Some(_) => false,
+        None => matches!(policy, "no" | "always" | "on-failure" | "unless-stopped"),
+    }
+}
+
+fn replicas(service: &Mapping) -> Option<u64> {
+    service
+        .get("deploy")
+        .and_then(|d| d.get("replicas"))
+        .or_else(|| service.get("scale"))
+        .and_then(Value::as_u64)
+}
+
+fn check_document(checker: &mut Checker, doc: &Value) {
+    let Some(doc) = doc.as_mapping() else {
+        checker.error(&[], "A compose file must be a mapping");
+        return;
+    };
+    for k in doc.keys() {
+        let name = scalar_string(k).unwrap_or_default();
+        if name == "version" {
+            checker.report(
+                LintSeverity::Info,
+                &[key("version")],
+                "`version` is obsolete and ignored",
+            );
+        } else if !TOP_LEVEL_KEYS.contains(&name.as_str()) && !name.starts_with("x-") {
+            checker.error(&[key(&name)], format!("Unknown top-level key `{}`", name));
+        }
+    }
+
+    let services = match doc.get("services") {
+        Some(Value::Mapping(services)) if !services.is_empty() => services,
+        Some(Value::Mapping(_)) | None if doc.contains_key("include") => return,
+        Some(_) => {
+            checker.error(&[key("services")], "`services` must be a non-empty mapping");
+            return;
+        }
+        None => {
+            checker.error(&[], "The compose file defines no services");
+            return;
+        }
+    };
+    let service_names: HashSet<String> = services.keys().filter_map(scalar_string).collect();
+    let networks = declared(doc, "networks");
+    let volumes = declared(doc, "volumes");
+    let mut published: HashMap<(String, u16, String), String> = HashMap::new();
+
+    for (name, service) in services {
+        let name = scalar_string(name).unwrap_or_default();
+        let path = vec![key("services"), key(&name)];
+        if !valid_service_name(&name) {
+            checker.error(
+                &path,
+                format!(
+                    "Invalid service name `{}`: use letters, digits, `_`, `.` and `-`",
+                    name
+                ),
+            );
+        }
+        let Some(service) = service.as_mapping() else {
+            checker.error(&path, format!("Service `{}` must be a mapping", name));
+            continue;
+        };
+        for k in service.keys() {
+            let k = scalar_string(k).unwrap_or_default();
+            if !SERVICE_KEYS.contains(&k.as_str()) && !k.starts_with("x-") {
+                checker.error(
+                    &joined(&path, key(&k)),
+                    format!("Unknown service key `{}`", k),
+                );
+            }
+        }
+
+        match service.get("image") {
+            Some(Value::String(image)) => {
+                if let Some(reason) = dockerfile_lint::unpinned(image) {
+                    checker.warning(
+                        &joined(&path, key("image")),
+                        format!("`{}` {}; pin a version", image, reason),
+                    );
+                }
+            }
+            Some(_) => checker.error(&joined(&path, key("image")), "`image` must be a string"),
+            None if !service.contains_key("build") && !service.contains_key("extends") => {
+                checker.error(
+                    &path,
+                    format!("Service `{}` has neither `image` nor `build`", name),
+                );
+            }
+            None => {}
+        }
+
+        if let Some(restart) = service.get("restart") {
+            let policy = scalar_string(restart).unwrap_or_default();
+            if !valid_restart(&policy) {
+                checker.error(
+                    &joined(&path, key("restart")),
+                    format!(
+                        "Invalid restart policy `{}` (no, always, on-failure[:N], unless-stopped)",
+                        policy
+                    ),
+                );
+            }
+        }
+
+        if service.contains_key("container_name") && replicas(service).unwrap_or(1) > 1 {
+            checker.error(
+                &joined(&path, key("container_name")),
+                "`container_name` must be unique, so the service cannot have more than one replica",
+            );
+        }
+
+        if let Some(ports) = service.get("ports") {
+            let ports_path = joined(&path, key("ports"));
+            let Some(items) = ports.as_sequence() else {
+                checker.error(&ports_path, "`ports` must be a list");
+                continue;
+            };
+            for (i, item) in items.iter().enumerate() {
+                let item_path = joined(&ports_path, Seg::Index(i));
+                match service_port(item) {
+                    Ok(spec) => {
+                        let Some((start, end)) = spec.published else {
+                            continue;
+                        };
+                        for port in start..=end {
+                            let slot = (spec.host_ip.clone(), port, spec.protocol.clone());
+                            match published.get(&slot) {
+                                Some(other) => checker.error(
+                                    &item_path,
+                                    format!(
+                                        "Host port {}/{} is already published by service `{}`",
+                                        port, spec.protocol, other
+                                    ),
+                                ),
+                                None => {
+                                    published.insert(slot, name.clone());
+                                }
+                            }
+                        }
+                    }
+                    Err(message) => checker.error(&item_path, message),
+                }
+            }
+        }
+
+        if let Some(expose) = service.get("expose") {
+            for (seg, port) in names(expose) {
+                let port = port.split('/').next().unwrap_or_default();
+                if parse_port_range(port).is_none() {
+                    checker.error(
+                        &joined(&joined(&path, key("expose")), seg),
+                        format!("Invalid port `{}`", port),
+                    );
+                }
+            }
+        }
+
+        if let Some(depends_on) = service.get("depends_on") {
+            let depends_path = joined(&path, key("depends_on"));
+            for (seg, dependency) in names(depends_on) {
+                let dep_path = joined(&depends_path, seg);
+                if dependency == name {
+                    checker.error(&dep_path, format!("Service `{}` depends on itself", name));
+                } else if !service_names.contains(&dependency) {
+                    checker.error(
+                        &dep_path,
+                        format!(
+                            "Service `{}` depends on undefined service `{}`",
+                            name, dependency
+                        ),
+                    );
+                }
+                let condition = depends_on
+                    .get(&dependency)
+                    .and_then(|d| d.get("condition"))
+                    .and_then(scalar_string);
+                if let Some(condition) = condition {
+                    if !DEPENDS_ON_CONDITIONS.contains(&condition.as_str()) {
+                        checker.error(
+                            &joined(&dep_path, key("condition")),
+                            format!(
+                                "Unknown condition `{}` ({})",
+                                condition,
+                                DEPENDS_ON_CONDITIONS.join(", ")
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        if let Some(service_networks) = service.get("networks") {
+            for (seg, network) in names(service_networks) {
+                if network != "default" && !networks.contains(&network) {
+                    checker.error(
+                        &joined(&joined(&path, key("networks")), seg),
+                        format!(
+                            "Service `{}` uses undefined network `{}`; declare it under top-level `networks`",
+                            name, network
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Some(mounts) = service.get("volumes") {
+            let volumes_path = joined(&path, key("volumes"));
+            let Some(items) = mounts.as_sequence() else {
+                checker.error(&volumes_path, "`volumes` must be a list");
+                continue;
+            };
+            for (i, item) in items.iter().enumerate() {
+                let item_path = joined(&volumes_path, Seg::Index(i));
+                match volume_mount(item) {
+                    Ok(mount) => {
+                        if !mount.target.starts_with('/') && mount.kind != "npipe" {
+                            checker.error(
+                                &item_path,
+                                format!("Mount target `{}` must be an absolute path", mount.target),
+                            );
+                        }
+                        if let Some(source) = mount.source.filter(|_| mount.kind == "volume") {
+                            if !volumes.contains(&source) {
+                                checker.error(
+                                    &item_path,
+                                    format!(
+                                        "Service `{}` uses undefined volume `{}`; declare it under top-level `volumes`",
+                                        name, source
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                    Err(message) => checker.error(&item_path, message),
+                }
+            }
+        }
+
+        if let Some(Value::Mapping(environment)) = service.get("environment") {
+            for (k, v) in environment {
+                if matches!(v, Value::Sequence(_) | Value::Mapping(_)) {
+                    let k = scalar_string(k).unwrap_or_default();
+                    checker.error(
+                        &joined(&joined(&path, key("environment")), key(&k)),
+                        format!("Environment value of `{}` must be a scalar", k),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// A parsed, interpolated compose file and its diagnostics so far.
+struct Loaded<'a> {
+    checker: Checker<'a>,
+    document: Option<Value>,
+    env: HashMap<String, String>,
+}
+
+fn load<'a>(source: &'a str, env: HashMap<String, String>) -> Loaded<'a> {
+    let mut checker = Checker {
+        entries: outline(source),
+        diagnostics: Vec::new(),
+    };
+    let mut document = match serde_yaml_ng::from_str::<Value>(source) {
+        Ok(document) => document,
+        Err(e) => {
+            checker.diagnostics.push(ComposeDiagnostic {
+                severity: LintSeverity::Error,
+                path: String::new(),
+                line: e.location().map(|l| l.line()),
+                message: format!("Invalid YAML: {}", e),
+            });
+            return Loaded {
+                checker,
+                document: None,
+                env,
+            };
+        }
+    };
+    if let Err(e) = document.apply_merge() {
+        checker.error(&[], format!("Invalid YAML merge key: {}", e));
+    }
+    checker.interpolate_value(&mut document, &mut Vec::new(), &env);
+    check_document(&mut checker, &document);
+    Loaded {
+        checker,
+        document: Some(document),
+        env,
+    }
+}
+
+/// The environment for interpolating `path`: its `.env` overlaid with the
+/// process environment.
+fn environment_for(path: &Path) -> HashMap<String, String> {
+    let mut env = path
+        .parent()
+        .map(|dir| dir.join(".env"))
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .map(|content| parse_env_file(&content))
+        .unwrap_or_default();
+    env.extend(std::env::vars());
+    env
+}
+
+/// The compose file to use: `file` when given, else the first of
+/// [`COMPOSE_FILE_NAMES`] in `dir`.
+pub fn resolve_file(file: Option<&str>, dir: &Path) -> Result<PathBuf, AppError> {
+    if let Some(file) = file {
+        return Ok(PathBuf::from(file));
+    }
+    COMPOSE_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|p| p.is_file())
+        .ok_or_else(|| {
+            AppError::Validation(format!(
+                "No compose file in {} (looked for {})",
+                dir.display(),
+                COMPOSE_FILE_NAMES.join(", ")
+            ))
+        })
+}
+
+fn read_file(path: &Path) -> Result<String, AppError> {
+    std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            AppError::Validation(format!("Compose file not found: {}", path.display()))
+        }
+        _ => AppError::Io(e),
+    })
+}
+
+fn sorted(mut diagnostics: Vec<ComposeDiagnostic>) -> Vec<ComposeDiagnostic> {
+    diagnostics.sort_by_key(|d| d.line.unwrap_or(0));
+    diagnostics
+}
+
+/// Validate compose `source`.
+fn validate(source: &str, env: HashMap<String, String>) -> (Vec<String>, Vec<ComposeDiagnostic>) {
+    let loaded = load(source, env);
+    let services = loaded
+        .document
+        .as_ref()
+        .and_then(|d| d.get("services"))
+        .map(|s| names(s).into_iter().map(|(_, n)| n).collect())
+        .unwrap_or_default();
+    (services, sorted(loaded.checker.diagnostics))
+}
+
+/// Validate the compose file at `path`.
+pub fn validate_file(path: &Path) -> Result<ComposeValidation, AppError> {
+    let source = read_file(path)?;
+    let (services, diagnostics) = validate(&source, environment_for(path));
+    Ok(ComposeValidation {
+        file: path.display().to_string(),
+        services,
+        diagnostics,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Kubernetes conversion
+// ---------------------------------------------------------------------------
+
+/// A Kubernetes object name (RFC 1123 label) for a compose name.
+fn k8s_name(name: &str) -> String {
+    let mapped: String = name
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let trimmed = mapped.trim_matches('-');
+    trimmed[..trimmed.len().min(63)]
+        .trim_end_matches('-')
+        .to_string()
+}
+
+/// A compose byte size (`512m`, `1gb`, `1024`) as a Kubernetes quantity.
+fn k8s_memory(size: &str) -> Option<String> {
+    let size = size.trim().to_ascii_lowercase();
+    let digits = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(digits);
+    number.parse::<u64>().ok()?;
+    let suffix = match unit.trim_end_matches('b') {
+        "" => "",
+        "k" => "Ki",
+        "m" => "Mi",
+        "g" => "Gi",
+        "t" => "Ti",
+        _ => return None,
+    };
+    Some(format!("{}{}", number, suffix))
+}
+
+/// Split a command string into words the way compose does (shell quoting,
+/// no expansion).
+fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => word.push(c),
+            (_, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+                in_word = true;
+            }
+            (_, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+fn command_words(value: &Value) -> Option<Vec<String>> {
+    match value {
+        Value::String(s) => Some(split_command(s)),
+        Value::Sequence(items) => Some(items.iter().filter_map(scalar_string).collect()),
+        _ => None,
+    }
+}
+
+/// Environment entries of a service as `(name, value)`; `None` values
+/// take the host's.
+fn environment_entries(value: &Value) -> Vec<(String, Option<String>)> {
+    match value {
+        Value::Sequence(items) => items
+            .iter()
+            .filter_map(scalar_string)
+            .map(|entry| match entry.split_once('=') {
+                Some((k, v)) => (k.to_string(), Some(v.to_string())),
+                None => (entry, None),
+            })
+            .collect(),
+        Value::Mapping(map) => map
+            .iter()
+            .filter_map(|(k, v)| Some((scalar_string(k)?, scalar_string(v))))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+struct Converter<'a, 'b> {
+    checker: &'a mut Checker<'b>,
+    env: &'a HashMap<String, String>,
+    claims: Vec<String>,
+}
+
+impl Converter<'_, '_> {
+    /// Deployment and Service of one compose service.
+    fn service(&mut self, name: &str, service: &Mapping) -> Vec<Json> {
+        let path = vec![key("services"), key(name)];
+        let object_name = k8s_name(name);
+        let labels = json!({ "app.kubernetes.io/name": object_name });
+
+        for k in service.keys().filter_map(scalar_string) {
+            if UNCONVERTED_KEYS.contains(&k.as_str()) {
+                self.checker
+                    .warning(&joined(&path, key(&k)), format!("`{}` is not converted", k));
+            }
+        }
+        for k in ["depends_on", "networks"] {
+            if service.contains_key(k) {
+                self.checker.report(
+                    LintSeverity::Info,
+                    &joined(&path, key(k)),
+                    format!("`{}` has no Kubernetes counterpart and is dropped", k),
+                );
+            }
+        }
+
+        let image = match service.get("image").and_then(scalar_string) {
+            Some(image) => image,
+            None => {
+                self.checker.warning(
+                    &path,
+                    format!(
+                        "Service `{}` has no `image`; build and push it, then set `image`",
+                        name
+                    ),
+                );
+                name.to_string()
+            }
+        };
+        let mut container = json!({ "name": object_name, "image": image });
+        if let Some(entrypoint) = service.get("entrypoint").and_then(command_words) {
+            container["command"] = json!(entrypoint);
+        }
+        if let Some(command) = service.get("command").and_then(command_words) {
+            container["args"] = json!(command);
+        }
+        if let Some(dir) = service.get("working_dir").and_then(scalar_string) {
+            container["workingDir"] = json!(dir);
+        }
+        for (compose_key, k8s_key) in [("stdin_open", "stdin"), ("tty", "tty")] {
+            if let Some(flag) = service.get(compose_key).and_then(Value::as_bool) {
+                container[k8s_key] = json!(flag);
+            }
+        }
+
+        let mut env = Vec::new();
+        for (k, v) in service
+            .get("environment")
+            .map(environment_entries)
+            .unwrap_or_default()
+        {
+            match v.or_else(|| self.env.get(&k).cloned()) {
+                Some(value) => env.push(json!({ "name": k, "value": value })),
+                None => self.checker.warning(
+                    &joined(&joined(&path, key("environment")), key(&k)),
+                    format!(
+                        "`{}` takes its value from the host, where it is unset; dropped",
+                        k
+                    ),
+                ),
+            }
+        }
+        if !env.is_empty() {
+            container["env"] = json!(env);
+        }
+
+        let mut container_ports = Vec::new();
+        let mut service_ports = Vec::new();
+        let port_items = service
+            .get("ports")
+            .and_then(Value::as_sequence)
+            .cloned()
+            .unwrap_or_default();
+        let exposed = service.get("expose").map(names).unwrap_or_default();
+        let specs = port_items
+            .iter()
+            .filter_map(|p| service_port(p).ok())
+            .chain(exposed.iter().filter_map(|(_, p)| parse_port(p).ok()));
+        for spec in specs {
+            if spec.target.0 != spec.target.1 {
+                self.checker.warning(
+                    &joined(&path, key("ports")),
+                    format!(
+                        "Port range {}-{} is not converted",
+                        spec.target.0, spec.target.1
+                    ),
+                );
+                continue;
+            }
+            let target = spec.target.0;
+            let port = spec.published.map(|(p, _)| p).unwrap_or(target);
+            let protocol = spec.protocol.to_ascii_uppercase();
+            let port_name = format!("{}-{}", port, spec.protocol);
+            if service_ports.iter().any(|p: &Json| p["name"] == port_name) {
+                continue;
+            }
+            if !container_ports
+                .iter()
+                .any(|p: &Json| p["containerPort"] == target && p["protocol"] == protocol)
+            {
+                container_ports.push(json!({ "containerPort": target, "protocol": protocol }));
+            }
+            service_ports.push(json!({
+                "name": port_name,
+                "port": port,
+                "targetPort": target,
+                "protocol": protocol,
+            }));
+        }
+        if !container_ports.is_empty() {
+            container["ports"] = json!(container_ports);
+        }
+
+        let mut mounts = Vec::new();
+        let mut pod_volumes = Vec::new();
+        let volume_items = service
+            .get("volumes")
+            .and_then(Value::as_sequence)
+            .cloned()
+            .unwrap_or_default();
+        for (i, item) in volume_items.iter().enumerate() {
+            let Ok(mount) = volume_mount(item) else {
+                continue;
+            };
+            let volume_name = match (mount.kind.as_str(), &mount.source) {
+                ("volume", Some(source)) => {
+                    let claim = k8s_name(source);
+                    if !self.claims.contains(&claim) {
+                        self.claims.push(claim.clone());
+                    }
+                    pod_volumes.push(json!({
+                        "name": claim,
+                        "persistentVolumeClaim": { "claimName": claim },
+                    }));
+                    claim
+                }
+                ("volume", None) | ("tmpfs", _) => {
+                    let volume_name = format!("{}-tmp{}", object_name, i);
+                    let empty_dir = if mount.kind == "tmpfs" {
+                        json!({ "medium": "Memory" })
+                    } else {
+                        json!({})
+                    };
+                    pod_volumes.push(json!({ "name": volume_name, "emptyDir": empty_dir }));
+                    volume_name
+                }
+                _ => {
+                    self.checker.warning(
+                        &joined(&joined(&path, key("volumes")), Seg::Index(i)),
+                        format!(
+                            "{} mount of `{}` is not converted; host paths do not carry over to a cluster",
+                            mount.kind,
+                            mount.source.unwrap_or_default()
+                        ),
+                    );
+                    continue;
+                }
+            };
+            let mut volume_mount = json!({ "name": volume_name, "mountPath": mount.target });
+            if mount.read_only {
+                volume_mount["readOnly"] = json!(true);
+            }
+            mounts.push(volume_mount);
+        }
+        if let Some(tmpfs) = service.get("tmpfs") {
+            let targets: Vec<String> = match tmpfs {
+                Value::Sequence(items) => items.iter().filter_map(scalar_string).collect(),
+                other => scalar_string(other).into_iter().collect(),
+            };
+            for (i, target) in targets.iter().enumerate() {
+                let volume_name = format!("{}-tmpfs{}", object_name, i);
+                let target = target.split(':').next().unwrap_or_default();
+                pod_volumes
+                    .push(json!({ "name": volume_name, "emptyDir": { "medium": "Memory" } }));
+                mounts.push(json!({ "name": volume_name, "mountPath": target }));
+            }
+        }
+        if !mounts.is_empty() {
+            container["volumeMounts"] = json!(mounts);
+        }
+
+        let mut security = serde_json::Map::new();
+        if service.get("privileged").and_then(Value::as_bool) == Some(true) {
+            security.insert("privileged".into(), json!(true));
+        }
+        if service.get("read_only").and_then(Value::as_bool) == Some(true) {
+            security.insert("readOnlyRootFilesystem".into(), json!(true));
+        }
+        let mut capabilities = serde_json::Map::new();
+        for (compose_key, k8s_key) in [("cap_add", "add"), ("cap_drop", "drop")] {
+            if let Some(caps) = service.get(compose_key) {
+                let caps: Vec<String> = names(caps).into_iter().map(|(_, c)| c).collect();
+                capabilities.insert(k8s_key.into(), json!(caps));
+            }
+        }
+        if !capabilities.is_empty() {
+            security.insert("capabilities".into(), Json::Object(capabilities));
+        }
+        if let Some(user) = service.get("user").and_then(scalar_string) {
+            let (uid, gid) = match user.split_once(':') {
+                Some((uid, gid)) => (uid.to_string(), Some(gid.to_string())),
+                None => (user.clone(), None),
+            };
+            match uid.parse::<u64>() {
+                Ok(uid) => {
+                    security.insert("runAsUser".into(), json!(uid));
+                    if let Some(gid) = gid.and_then(|g| g.parse::<u64>().ok()) {
+                        security.insert("runAsGroup".into(), json!(gid));
+                    }
+                }
+                Err(_) => self.checker.warning(
+                    &joined(&path, key("user")),
+                    format!(
+                        "User `{}` is not converted; Kubernetes needs a numeric UID",
+                        user
+                    ),
+                ),
+            }
+        }
+        if !security.is_empty() {
+            container["securityContext"] = Json::Object(security);
+        }
+
+        let mut limits = serde_json::Map::new();
+        let mut requests = serde_json::Map::new();
+        let deploy_resources = service.get("deploy").and_then(|d| d.get("resources"));
+        for (section, target) in [("limits", &mut limits), ("reservations", &mut requests)] {
+            let Some(resources) = deploy_resources.and_then(|r| r.get(section)) else {
+                continue;
+            };
+            if let Some(cpus) = resources.get("cpus").and_then(scalar_string) {
+                target.insert("cpu".into(), json!(cpus));
+            }
+            if let Some(memory) = resources.get("memory").and_then(scalar_string) {
+                if let Some(quantity) = k8s_memory(&memory) {
+                    target.insert("memory".into(), json!(quantity));
+                }
+            }
+        }
+        if let Some(cpus) = service.get("cpus").and_then(scalar_string) {
+            limits.entry("cpu").or_insert(json!(cpus));
+        }
+        for (compose_key, target) in [
+            ("mem_limit", &mut limits),
+            ("mem_reservation", &mut requests),
+        ] {
+            if let Some(quantity) = service
+                .get(compose_key)
+                .and_then(scalar_string)
+                .and_then(|m| k8s_memory(&m))
+            {
+                target.entry("memory").or_insert(json!(quantity));
+            }
+        }
+        if !limits.is_empty() || !requests.is_empty() {
+            let mut resources = serde_json::Map::new();
+            if !limits.is_empty() {
+                resources.insert("limits".into(), Json::Object(limits));
+            }
+            if !requests.is_empty() {
+                resources.insert("requests".into(), Json::Object(requests));
+            }
+            container["resources"] = Json::Object(resources);
+        }
+
+        if matches!(
+            service.get("restart").and_then(scalar_string).as_deref(),
+            Some("no") | Some("on-failure")
+        ) {
+            self.checker.warning(
+                &joined(&path, key("restart")),
+                "Deployments always restart their pods; the restart policy is dropped",
+            );
+        }
+
+        let mut pod_spec = json!({ "containers": [container] });
+        if !pod_volumes.is_empty() {
+            pod_spec["volumes"] = json!(pod_volumes);
+        }
+        if let Some(hostname) = service.get("hostname").and_then(scalar_string) {
+            pod_spec["hostname"] = json!(hostname);
+        }
+
+        let mut objects = vec![json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": object_name, "labels": labels },
+            "spec": {
+                "replicas": replicas(service).unwrap_or(1),
+                "selector": { "matchLabels": labels },
+                "template": {
+                    "metadata": { "labels": labels },
+                    "spec": pod_spec,
+                },
+            },
+        })];
+        if !service_ports.is_empty() {
+            objects.push(json!({
+                "apiVersion": "v1",
+                "kind": "Service",
+                "metadata": { "name": object_name, "labels": labels },
+                "spec": { "selector": labels, "ports": service_ports },
+            }));
+        }
+        objects
+    }
+}
+
+fn persistent_volume_claim(name: &str) -> Json {
+    json!({
+        "apiVersion": "v1",
+        "kind": "PersistentVolumeClaim",
+        "metadata": { "name": name },
+        "spec": {
+            "accessModes": ["ReadWriteOnce"],
+            "resources": { "requests": { "storage": DEFAULT_VOLUME_SIZE } },
+        },
+    })
+}
+
+/// Convert compose `source` to Kubernetes objects. Nothing is converted
+/// when the file has errors.
+fn convert_kubernetes(
+    source: &str,
+    env: HashMap<String, String>,
+) -> Result<(Vec<Json>, Vec<ComposeDiagnostic>), AppError> {
+    let mut loaded = load(source, env);
+    let services = loaded
+        .document
+        .as_ref()
+        .and_then(|d| d.get("services"))
+        .and_then(Value::as_mapping);
+    let Some(services) = services.filter(|_| !loaded.checker.has_errors()) else {
+        return Ok((Vec::new(), sorted(loaded.checker.diagnostics)));
+    };
+
+    let mut converter = Converter {
+        checker: &mut loaded.checker,
+        env: &loaded.env,
+        claims: Vec::new(),
+    };
+    let mut objects = Vec::new();
+    for (name, service) in services {
+        let (Some(name), Some(service)) = (scalar_string(name), service.as_mapping()) else {
+            continue;
+        };
+        objects.extend(converter.service(&name, service));
+    }
+    let claims: Vec<Json> = converter
+        .claims
+        .iter()
+        .map(|c| persistent_volume_claim(c))
+        .collect();
+    objects.splice(0..0, claims);
+    Ok((objects, sorted(loaded.checker.diagnostics)))
+}
+
+/// Convert the compose file at `path` to Kubernetes manifests.
+pub fn convert_kubernetes_file(path: &Path) -> Result<ComposeConversion, AppError> {
+    let source = read_file(path)?;
+    let (objects, diagnostics) = convert_kubernetes(&source, environment_for(path))?;
+    let mut manifest = String::new();
+    for object in &objects {
+        if !manifest.is_empty() {
+            manifest.push_str("---\n");
+        }
+        manifest.push_str(
+            &serde_yaml_ng::to_string(object)
+                .map_err(|e| AppError::Runtime(format!("Writing the manifest failed: {}", e)))?,
+        );
+    }
+    Ok(ComposeConversion {
+        file: path.display().to_string(),
+        resources: objects
+            .iter()
+            .map(|o| {
+                format!(
+                    "{}/{}",
+                    o["kind"].as_str().unwrap_or_default(),
+                    o["metadata"]["name"].as_str().unwrap_or_default()
+                )
+            })
+            .collect(),
+        manifest,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+name: shop
+services:
+  web:
+    image: nginx:1.27
+    ports:
+      - \"${WEB_PORT:-8080}:80\"
+      - target: 443
+        published: 8443
+    depends_on:
+      - api
+    volumes:
+      - ./site:/usr/share/nginx/html:ro
+  api:
+    image: ghcr.io/acme/api:2.1
+    command: serve --port 3000 \"--name=my api\"
+    environment:
+      DATABASE_URL: postgres://db/shop
+      SECRET: $API_SECRET
+    expose:
+      - 3000
+    volumes:
+      - data:/var/lib/api
+    user: \"1000:1000\"
+    deploy:
+      replicas: 2
+      resources:
+        limits:
+          cpus: \"0.5\"
+          memory: 512m
+volumes:
+  data:
+";
+
+    fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn interpolation_follows_compose_rules() {
+        let env = env(&[("A", "1"), ("EMPTY", "")]);
+        let run = |s: &str| interpolate(s, &env);
+        assert_eq!(run("$A-${A}-$$A").unwrap().0, "1-1-$A");
+        assert_eq!(run("${EMPTY:-d} ${EMPTY-d}").unwrap().0, "d ");
+        assert_eq!(run("${A:+set} ${B:+set}").unwrap().0, "set ");
+        assert_eq!(
+            run("x${B}y").unwrap(),
+            ("xy".to_string(), vec!["B".to_string()])
+        );
+        assert!(run("${B:?is required}")
+            .unwrap_err()
+            .contains("is required"));
+        assert!(run("${A").is_err());
+    }
+
+    #[test]
+    fn ports_parse_short_syntax() {
+        let spec = parse_port("127.0.0.1:8000-8001:80-81/udp").unwrap();
+        assert_eq!(spec.host_ip, "127.0.0.1");
+        assert_eq!(spec.published, Some((8000, 8001)));
+        assert_eq!(spec.target, (80, 81));
+        assert_eq!(spec.protocol, "udp");
+        assert_eq!(parse_port("[::1]:53:53").unwrap().host_ip, "::1");
+        assert_eq!(parse_port("3000").unwrap().published, None);
+        assert!(parse_port("80:http").is_err());
+        assert!(parse_port("8000-8002:80-81").is_err());
+    }
+
+    #[test]
+    fn diagnostics_point_at_the_offending_line() {
+        let source = "\
+services:
+  web:
+    image: nginx
+    ports:
+      - \"8080:80\"
+      - \"80:bad\"
+    depends_on: [cache]
+    volumes:
+      - logs:/var/log
+  worker:
+    build: .
+    ports:
+      - 8080:81
+    restrat: always
+";
+        let (services, diagnostics) = validate(source, HashMap::new());
+        assert_eq!(services, ["web", "worker"]);
+        let found: Vec<(Option<usize>, LintSeverity)> =
+            diagnostics.iter().map(|d| (d.line, d.severity)).collect();
+        assert_eq!(
+            found,
+            [
+                (Some(3), LintSeverity::Warning),
+                (Some(6), LintSeverity::Error),
+                (Some(7), LintSeverity::Error),
+                (Some(9), LintSeverity::Error),
+                (Some(13), LintSeverity::Error),
+                (Some(14), LintSeverity::Error),
+            ]
+        );
+        assert_eq!(diagnostics[4].path, "services.worker.ports[0]");
+        assert!(diagnostics[4].message.contains("`web`"));
+    }
+
+    #[test]
+    fn sample_validates_cleanly_apart_from_unset_variables() {
+        let (_, diagnostics) = validate(SAMPLE, HashMap::new());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, "services.api.environment.SECRET");
+        assert_eq!(diagnostics[0].line, Some(18));
+        assert_eq!(diagnostics[0].severity, LintSeverity::Warning);
+    }
+
+    #[test]
+    fn conversion_builds_deployments_services_and_claims() {
+        let (objects, diagnostics) =
+            convert_kubernetes(SAMPLE, env(&[("API_SECRET", "s3"), ("WEB_PORT", "9090")])).unwrap();
+        let kinds: Vec<String> = objects
+            .iter()
+            .map(|o| {
+                format!(
+                    "{}/{}",
+                    o["kind"].as_str().unwrap(),
+                    o["metadata"]["name"].as_str().unwrap()
+                )
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "PersistentVolumeClaim/data",
+                "Deployment/web",
+                "Service/web",
+                "Deployment/api",
+                "Service/api",
+            ]
+        );
+
+        let web_ports = &objects[2]["spec"]["ports"];
+        assert_eq!(web_ports[0]["port"], 9090);
+        assert_eq!(web_ports[0]["targetPort"], 80);
+        assert_eq!(web_ports[1]["port"], 8443);
+
+        let api = &objects[3]["spec"];
+        assert_eq!(api["replicas"], 2);
+        let container = &api["template"]["spec"]["containers"][0];
+        assert_eq!(
+            container["args"],
+            json!(["serve", "--port", "3000", "--name=my api"])
+        );
+        assert_eq!(
+            container["env"][1],
+            json!({ "name": "SECRET", "value": "s3" })
+        );
+        assert_eq!(container["resources"]["limits"]["memory"], "512Mi");
+        assert_eq!(container["securityContext"]["runAsGroup"], 1000);
+        assert_eq!(container["volumeMounts"][0]["name"], "data");
+
+        // The bind mount and depends_on are reported, not converted.
+        let paths: Vec<&str> = diagnostics.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["services.web.depends_on", "services.web.volumes[0]"]
+        );
+    }
+
+    #[test]
+    fn conversion_stops_on_errors() {
+        let (objects, diagnostics) =
+            convert_kubernetes("services:\n  web:\n    ports: [\"x\"]\n", HashMap::new()).unwrap();
+        assert!(objects.is_empty());
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.severity == LintSeverity::Error));
+    }
+}
@@ -0,0 +1,989 @@
+//! User configuration file (`config_dir()/config.toml`).
+//!
+//! Shared by the CLI and the GUI backend. Precedence, highest first:
+//! command-line flags, environment variables, this file, built-in defaults.
+//!
+//! ```toml
+//! docker_host = "unix:///var/run/docker.sock"
+//! log_level = "debug"
+//! log_format = "json"
+//! registry_mirrors = ["https://mirror.gcr.io"]
+//! registry_proxy = "http://proxy.corp.example:3128"
+//! max_cpus = 8
+//! max_memory_mb = 16384
+//! max_disk_gb = 100
+//! default_vm_cpus = 4
+//! default_vm_memory_mb = 4096
+//! default_vm_disk_gb = 40
+//! theme = "system"
+//! telemetry = false
+//! daemon_address = "unix:///Users/me/.cratebay/cratebayd.sock"
+//! active_host = "lab"
+//!
+//! [hosts.lab]
+//! url = "ssh://me@lab.example"
+//! identity_file = "/Users/me/.ssh/lab_ed25519"
+//!
+//! [hosts.ci]
+//! url = "tcp://ci.example:2376"
+//! tls_ca = "/Users/me/.cratebay/certs/ci/ca.pem"
+//! tls_cert = "/Users/me/.cratebay/certs/ci/cert.pem"
+//! tls_key = "/Users/me/.cratebay/certs/ci/key.pem"
+//!
+//! [log_levels]
+//! "cratebay_core::registry" = "debug"
+//! bollard = "warn"
+//!
+//! [registries."ghcr.io"]
+//! mirrors = ["https://ghcr.mirror.example"]
+//! proxy = "socks5h://127.0.0.1:1080"
+//!
+//! [registries."harbor.corp.example"]
+//! ca_file = "/etc/ssl/corp-root.pem"
+//!
+//! [registries."nexus.lan:8082"]
+//! insecure = true
+//! ```
+//!
+//! Runtime VM sizes are not stored here; they live in the runtime config
+//! managed by `cratebay runtime set`. The `default_vm_*` keys only seed that
+//! config the first time it is created. The `max_*` keys are quotas checked
+//! whenever the VM is resized and when containers are created, so shared
+//! machines can cap what CrateBay takes.
+//!
+//! `log_format = "json"` writes one JSON object per line, for service
+//! managers and log collectors (`CRATEBAY_LOG_FORMAT` overrides it).
+//! `[log_levels]` overrides the level of individual modules on top of
+//! `log_level`; `RUST_LOG` replaces both.
+//!
+//! `[hosts.<name>]` registers remote Docker engines managed with
+//! `cratebay host`. `active_host` (or `CRATEBAY_HOST`, or `--host`) selects
+//! one in place of `DOCKER_HOST` and the built-in runtime.
+//!
+//! `theme` and `telemetry` are desktop preferences, edited from the GUI
+//! settings page. `daemon_address` is reserved for the CrateBay daemon
+//! (ADR-009).
+//!
+//! Registry traffic (search, tags, manifests) uses the proxy of the
+//! registry's `[registries.<host>]` entry, then `CRATEBAY_RUNTIME_HTTP_PROXY`,
+//! then `registry_proxy`. Pulls try the registry's mirrors first. Docker Hub
+//! mirrors and `registry_proxy` are also written into the runtime VM's
+//! `daemon.json`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::storage;
+
+/// File name inside [`storage::config_dir`].
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Keys accepted by [`Config::get`] and [`Config::set`].
+pub const CONFIG_KEYS: &[&str] = &[
+    "docker_host",
+    "log_level",
+    "log_format",
+    "registry_mirrors",
+    "registry_proxy",
+    "max_cpus",
+    "max_memory_mb",
+    "max_disk_gb",
+    "default_vm_cpus",
+    "default_vm_memory_mb",
+    "default_vm_disk_gb",
+    "theme",
+    "telemetry",
+    "daemon_address",
+    "active_host",
+];
+
+const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+const LOG_FORMATS: &[&str] = &["text", "json"];
+const DOCKER_HOST_SCHEMES: &[&str] = &[
+    "unix://", "tcp://", "npipe://", "http://", "https://", "ssh://",
+];
+const PROXY_SCHEMES: &[&str] = &["http://", "https://", "socks5://", "socks5h://"];
+const DAEMON_ADDRESS_SCHEMES: &[&str] = &["unix://", "tcp://", "npipe://"];
+const THEMES: &[&str] = &["dark", "light", "system"];
+/// Host name that selects no registered host (`cratebay host use local`).
+pub const LOCAL_HOST: &str = "local";
+const HOST_URL_SCHEMES: &[&str] = &[
+    "unix://", "tcp://", "npipe://", "http://", "https://", "ssh://",
+];
+
+/// Host names that all mean Docker Hub; its settings live under `docker.io`.
+const DOCKER_HUB_HOSTS: &[&str] = &[
+    "docker.io",
+    "registry-1.docker.io",
+    "index.docker.io",
+    "hub.docker.com",
+];
+
+/// Parsed `config.toml`. Every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Docker endpoint used when `DOCKER_HOST` is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docker_host: Option<String>,
+    /// Log filter used when `RUST_LOG` is not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
+    /// Log line format: `text` (default) or `json`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_format: Option<String>,
+    /// Per-module levels applied on top of `log_level`, keyed by tracing
+    /// target (`cratebay_core::registry`, `bollard`, ...). Only editable in
+    /// the file.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub log_levels: BTreeMap<String, String>,
+    /// Docker Hub mirrors tried in order before pulling directly.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub registry_mirrors: Vec<String>,
+    /// Proxy for registry traffic (`http`, `https`, `socks5` or `socks5h` URL).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry_proxy: Option<String>,
+    /// Per-registry settings keyed by host (`docker.io`, `ghcr.io`, ...).
+    /// Only editable in the file.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub registries: BTreeMap<String, RegistrySettings>,
+    /// Upper bound for runtime VM CPUs and for CPUs reserved by running
+    /// containers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cpus: Option<u32>,
+    /// Upper bound for runtime VM memory and for memory reserved by running
+    /// containers, in MB.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_memory_mb: Option<u64>,
+    /// Upper bound for the runtime VM disk, in GB.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_disk_gb: Option<u32>,
+    /// CPUs of a newly created runtime VM (default 2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_vm_cpus: Option<u32>,
+    /// Memory of a newly created runtime VM in MB (default 2048).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_vm_memory_mb: Option<u64>,
+    /// Disk of a newly created runtime VM in GB (default 20).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_vm_disk_gb: Option<u32>,
+    /// GUI color theme: `dark`, `light` or `system`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Anonymous usage statistics opt-in (off unless set to `true`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub telemetry: Option<bool>,
+    /// Address of the CrateBay daemon (`unix://`, `tcp://` or `npipe://`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daemon_address: Option<String>,
+    /// Name of the `[hosts]` entry used by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_host: Option<String>,
+    /// Remote Docker engines keyed by name, managed with `cratebay host`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub hosts: BTreeMap<String, HostSettings>,
+}
+
+/// A `[hosts.<name>]` table.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HostSettings {
+    /// Docker endpoint: `ssh://[user@]host[:port]`, `tcp://host:port`,
+    /// `https://`, `unix://` or `npipe://`.
+    pub url: String,
+    /// SSH private key for `ssh://` hosts (default: the ssh client's own).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identity_file: Option<PathBuf>,
+    /// CA bundle that signed the daemon's certificate; enables TLS for
+    /// `tcp://` hosts and is required for `https://` ones.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_ca: Option<PathBuf>,
+    /// Client certificate presented to the daemon (with `tls_key`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_cert: Option<PathBuf>,
+    /// Private key of `tls_cert`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls_key: Option<PathBuf>,
+}
+
+/// A registered host as listed by `cratebay host ls`; the settings keep
+/// their config.toml names.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HostEntry {
+    pub name: String,
+    /// Whether Docker commands currently target this host.
+    pub active: bool,
+    #[serde(flatten)]
+    pub settings: HostSettings,
+}
+
+impl HostSettings {
+    fn validate(&self, name: &str) -> Result<(), AppError> {
+        let invalid = |reason: String| AppError::Validation(format!("host '{}': {}", name, reason));
+        if name == LOCAL_HOST {
+            return Err(invalid(format!("'{}' is reserved", LOCAL_HOST)));
+        }
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        {
+            return Err(invalid(
+                "names may only contain letters, digits, '-', '_' and '.'".to_string(),
+            ));
+        }
+        if !HOST_URL_SCHEMES.iter().any(|s| self.url.starts_with(s)) {
+            return Err(invalid(format!(
+                "url must start with one of {}: {}",
+                HOST_URL_SCHEMES.join(", "),
+                self.url
+            )));
+        }
+        let ssh = self.url.starts_with("ssh://");
+        let tls_capable = self.url.starts_with("tcp://") || self.url.starts_with("https://");
+        if self.identity_file.is_some() && !ssh {
+            return Err(invalid(
+                "identity_file only applies to ssh:// hosts".to_string(),
+            ));
+        }
+        let tls = self.tls_ca.is_some() || self.tls_cert.is_some() || self.tls_key.is_some();
+        if tls && !tls_capable {
+            return Err(invalid(
+                "tls_* settings only apply to tcp:// and https:// hosts".to_string(),
+            ));
+        }
+        if self.tls_ca.is_none() && (tls || self.url.starts_with("https://")) {
+            return Err(invalid(
+                "TLS needs tls_ca (system roots are not used)".to_string(),
+            ));
+        }
+        if self.tls_cert.is_some() != self.tls_key.is_some() {
+            return Err(invalid(
+                "tls_cert and tls_key must be set together".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the connection uses TLS.
+    pub fn uses_tls(&self) -> bool {
+        self.tls_ca.is_some()
+    }
+}
+
+/// A `[registries.<host>]` table.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RegistrySettings {
+    /// Mirrors standing in for this registry, tried in order before it.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<String>,
+    /// Proxy for this registry, overriding `registry_proxy`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Talk plain HTTP to this registry.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub insecure: bool,
+    /// PEM bundle of extra CA certificates trusted for this registry
+    /// (self-signed or corporate roots), on top of the system roots.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<PathBuf>,
+}
+
+/// Resources capped by the `max_*` config keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaResource {
+    Cpus,
+    MemoryMb,
+    DiskGb,
+}
+
+impl QuotaResource {
+    fn key(self) -> &'static str {
+        match self {
+            QuotaResource::Cpus => "max_cpus",
+            QuotaResource::MemoryMb => "max_memory_mb",
+            QuotaResource::DiskGb => "max_disk_gb",
+        }
+    }
+}
+
+impl Config {
+    /// Location of the config file.
+    pub fn path() -> PathBuf {
+        storage::config_dir().join(CONFIG_FILE_NAME)
+    }
+
+    /// Load the config file (defaults if absent).
+    pub fn load() -> Result<Self, AppError> {
+        Self::load_from(&Self::path())
+    }
+
+    /// Load a config file from `path` (defaults if absent).
+    pub fn load_from(path: &Path) -> Result<Self, AppError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let config: Self = toml::from_str(&content)
+            .map_err(|e| AppError::Validation(format!("Invalid {}: {}", path.display(), e)))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Load the config file, logging and falling back to defaults on error.
+    pub fn load_or_default() -> Self {
+        Self::load().unwrap_or_else(|e| {
+            tracing::warn!("Ignoring config file: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Persist to the default location.
+    pub fn save(&self) -> Result<(), AppError> {
+        self.save_to(&Self::path())
+    }
+
+    /// Persist to `path` atomically.
+    pub fn save_to(&self, path: &Path) -> Result<(), AppError> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| AppError::Validation(format!("Failed to encode config: {}", e)))?;
+        storage::write_atomic(path, content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Read a single key as a string (mirrors are comma-separated).
+    pub fn get(&self, key: &str) -> Result<Option<String>, AppError> {
+        match key {
+            "docker_host" => Ok(self.docker_host.clone()),
+            "log_level" => Ok(self.log_level.clone()),
+            "log_format" => Ok(self.log_format.clone()),
+            "registry_mirrors" => {
+                Ok((!self.registry_mirrors.is_empty()).then(|| self.registry_mirrors.join(",")))
+            }
+            "registry_proxy" => Ok(self.registry_proxy.clone()),
+            "max_cpus" => Ok(self.max_cpus.map(|v| v.to_string())),
+            "max_memory_mb" => Ok(self.max_memory_mb.map(|v| v.to_string())),
+            "max_disk_gb" => Ok(self.max_disk_gb.map(|v| v.to_string())),
+            "default_vm_cpus" => Ok(self.default_vm_cpus.map(|v| v.to_string())),
+            "default_vm_memory_mb" => Ok(self.default_vm_memory_mb.map(|v| v.to_string())),
+            "default_vm_disk_gb" => Ok(self.default_vm_disk_gb.map(|v| v.to_string())),
+            "theme" => Ok(self.theme.clone()),
+            "telemetry" => Ok(self.telemetry.map(|v| v.to_string())),
+            "daemon_address" => Ok(self.daemon_address.clone()),
+            "active_host" => Ok(self.active_host.clone()),
+            other => Err(unknown_key(other)),
+        }
+    }
+
+    /// Set a single key. An empty value unsets it. Leaves `self` untouched
+    /// when the new value is invalid.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), AppError> {
+        let value = value.trim();
+        let optional = (!value.is_empty()).then(|| value.to_string());
+        let mut next = self.clone();
+        match key {
+            "docker_host" => next.docker_host = optional,
+            "log_level" => next.log_level = optional.map(|v| v.to_ascii_lowercase()),
+            "log_format" => next.log_format = optional.map(|v| v.to_ascii_lowercase()),
+            "registry_mirrors" => {
+                next.registry_mirrors = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|m| !m.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            "registry_proxy" => next.registry_proxy = optional,
+            "max_cpus" => next.max_cpus = parse_quota(key, optional)?,
+            "max_memory_mb" => next.max_memory_mb = parse_quota(key, optional)?,
+            "max_disk_gb" => next.max_disk_gb = parse_quota(key, optional)?,
+            "default_vm_cpus" => next.default_vm_cpus = parse_quota(key, optional)?,
+            "default_vm_memory_mb" => next.default_vm_memory_mb = parse_quota(key, optional)?,
+            "default_vm_disk_gb" => next.default_vm_disk_gb = parse_quota(key, optional)?,
+            "theme" => next.theme = optional.map(|v| v.to_ascii_lowercase()),
+            "telemetry" => next.telemetry = parse_bool(key, optional)?,
+            "daemon_address" => next.daemon_address = optional,
+            "active_host" => next.active_host = optional.filter(|name| name != LOCAL_HOST),
+            other => return Err(unknown_key(other)),
+        }
+        next.validate()?;
+        *self = next;
+        Ok(())
+    }
+
+    /// Effective Docker endpoint: `DOCKER_HOST` first, then the config file.
+    pub fn docker_host(&self) -> Option<String> {
+        env_non_empty("DOCKER_HOST").or_else(|| self.docker_host.clone())
+    }
+
+    /// Effective log filter: `RUST_LOG` first, then the config file.
+    pub fn log_level(&self) -> Option<String> {
+        env_non_empty("RUST_LOG").or_else(|| self.log_level.clone())
+    }
+
+    /// Effective filter directives: `RUST_LOG` as is, otherwise `log_level`
+    /// (or `default`) followed by the `[log_levels]` overrides.
+    pub fn log_filter(&self, default: &str) -> String {
+        env_non_empty("RUST_LOG").unwrap_or_else(|| self.file_log_filter(default))
+    }
+
+    fn file_log_filter(&self, default: &str) -> String {
+        let base = self.log_level.as_deref().unwrap_or(default);
+        std::iter::once(base.to_string())
+            .chain(
+                self.log_levels
+                    .iter()
+                    .map(|(target, level)| format!("{}={}", target, level)),
+            )
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Effective log format: `CRATEBAY_LOG_FORMAT` first, then the config
+    /// file.
+    pub fn log_format(&self) -> Option<String> {
+        env_non_empty("CRATEBAY_LOG_FORMAT").or_else(|| self.log_format.clone())
+    }
+
+    /// Name of the selected `[hosts]` entry: `CRATEBAY_HOST` first, then
+    /// `active_host`. [`LOCAL_HOST`] selects none.
+    pub fn active_host(&self) -> Option<String> {
+        env_non_empty("CRATEBAY_HOST")
+            .or_else(|| self.active_host.clone())
+            .filter(|name| name != LOCAL_HOST)
+    }
+
+    /// The `[hosts]` entry called `name`.
+    pub fn host(&self, name: &str) -> Result<&HostSettings, AppError> {
+        self.hosts.get(name).ok_or_else(|| AppError::NotFound {
+            entity: "host".to_string(),
+            id: name.to_string(),
+        })
+    }
+
+    /// Register (or replace) the host `name`.
+    pub fn add_host(&mut self, name: &str, settings: HostSettings) -> Result<(), AppError> {
+        settings.validate(name)?;
+        self.hosts.insert(name.to_string(), settings);
+        Ok(())
+    }
+
+    /// Forget the host `name`, deselecting it if it was active.
+    pub fn remove_host(&mut self, name: &str) -> Result<(), AppError> {
+        self.host(name)?;
+        self.hosts.remove(name);
+        if self.active_host.as_deref() == Some(name) {
+            self.active_host = None;
+        }
+        Ok(())
+    }
+
+    /// Registered hosts by name, marking the active one.
+    pub fn host_entries(&self) -> Vec<HostEntry> {
+        let active = self.active_host();
+        self.hosts
+            .iter()
+            .map(|(name, settings)| HostEntry {
+                name: name.clone(),
+                active: active.as_deref() == Some(name.as_str()),
+                settings: settings.clone(),
+            })
+            .collect()
+    }
+
+    /// Settings for `registry` (any Docker Hub alias resolves to `docker.io`).
+    pub fn registry(&self, registry: &str) -> Option<&RegistrySettings> {
+        self.registries.get(&canonical_registry(registry))
+    }
+
+    /// Mirrors to try when pulling from `registry`: its own entry, falling
+    /// back to `registry_mirrors` for Docker Hub.
+    pub fn registry_mirrors_for(&self, registry: &str) -> Vec<String> {
+        match self.registry(registry) {
+            Some(settings) if !settings.mirrors.is_empty() => settings.mirrors.clone(),
+            _ if canonical_registry(registry) == "docker.io" => self.registry_mirrors.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Mirrors to try when pulling `image` (a reference such as `nginx` or
+    /// `ghcr.io/org/app:1`).
+    pub fn mirrors_for_image(&self, image: &str) -> Vec<String> {
+        crate::registry::ImageReference::parse(image)
+            .map(|reference| self.registry_mirrors_for(&reference.registry))
+            .unwrap_or_default()
+    }
+
+    /// Proxy for traffic to `registry`: its own entry, then `registry_proxy`.
+    pub fn registry_proxy_for(&self, registry: &str) -> Option<String> {
+        self.registry(registry)
+            .and_then(|settings| settings.proxy.clone())
+            .or_else(|| self.registry_proxy.clone())
+    }
+
+    /// Fail with [`AppError::QuotaExceeded`] when `requested` is above the
+    /// configured limit for `resource`.
+    pub fn check_quota(&self, resource: QuotaResource, requested: u64) -> Result<(), AppError> {
+        let limit = match resource {
+            QuotaResource::Cpus => self.max_cpus.map(u64::from),
+            QuotaResource::MemoryMb => self.max_memory_mb,
+            QuotaResource::DiskGb => self.max_disk_gb.map(u64::from),
+        };
+        match limit {
+            Some(limit) if requested > limit => Err(AppError::QuotaExceeded {
+                resource: resource.key().to_string(),
+                requested,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        if let Some(host) = &self.docker_host {
+            if !DOCKER_HOST_SCHEMES.iter().any(|s| host.starts_with(s)) {
+                return Err(AppError::Validation(format!(
+                    "docker_host must start with one of {}: {}",
+                    DOCKER_HOST_SCHEMES.join(", "),
+                    host
+                )));
+            }
+        }
+        if let Some(level) = &self.log_level {
+            if !LOG_LEVELS.contains(&level.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "log_level must be one of {}: {}",
+                    LOG_LEVELS.join(", "),
+                    level
+                )));
+            }
+        }
+        if let Some(format) = &self.log_format {
+            if !LOG_FORMATS.contains(&format.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "log_format must be one of {}: {}",
+                    LOG_FORMATS.join(", "),
+                    format
+                )));
+            }
+        }
+        for (target, level) in &self.log_levels {
+            if target.is_empty() || target.contains(['=', ',', ' ']) {
+                return Err(AppError::Validation(format!(
+                    "log_levels key must be a module path: '{}'",
+                    target
+                )));
+            }
+            if !LOG_LEVELS.contains(&level.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "log_levels.{} must be one of {}: {}",
+                    target,
+                    LOG_LEVELS.join(", "),
+                    level
+                )));
+            }
+        }
+        let mirrors = self
+            .registry_mirrors
+            .iter()
+            .chain(self.registries.values().flat_map(|r| r.mirrors.iter()));
+        for mirror in mirrors {
+            if !mirror.starts_with("http://") && !mirror.starts_with("https://") {
+                return Err(AppError::Validation(format!(
+                    "registry mirror must be an http(s) URL: {}",
+                    mirror
+                )));
+            }
+        }
+        if let Some((host, _)) = self
+            .registries
+            .iter()
+            .find(|(_, r)| r.insecure && r.ca_file.is_some())
+        {
+            return Err(AppError::Validation(format!(
+                "registry {} sets both insecure and ca_file",
+                host
+            )));
+        }
+        let proxies = self
+            .registry_proxy
+            .iter()
+            .chain(self.registries.values().filter_map(|r| r.proxy.as_ref()));
+        for proxy in proxies {
+            if !PROXY_SCHEMES.iter().any(|s| proxy.starts_with(s)) {
+                return Err(AppError::Validation(format!(
+                    "registry proxy must start with one of {}: {}",
+                    PROXY_SCHEMES.join(", "),
+                    proxy
+                )));
+            }
+        }
+        if self.max_cpus == Some(0) || self.max_memory_mb == Some(0) || self.max_disk_gb == Some(0)
+        {
+            return Err(AppError::Validation(
+                "max_cpus, max_memory_mb and max_disk_gb must be greater than 0".to_string(),
+            ));
+        }
+        let defaults = [
+            (QuotaResource::Cpus, self.default_vm_cpus.map(u64::from)),
+            (QuotaResource::MemoryMb, self.default_vm_memory_mb),
+            (
+                QuotaResource::DiskGb,
+                self.default_vm_disk_gb.map(u64::from),
+            ),
+        ];
+        for (resource, value) in defaults {
+            match value {
+                Some(0) => {
+                    return Err(AppError::Validation(
+                        "default_vm_cpus, default_vm_memory_mb and default_vm_disk_gb must be greater than 0"
+                            .to_string(),
+                    ))
+                }
+                Some(value) => self.check_quota(resource, value)?,
+                None => {}
+            }
+        }
+        if let Some(theme) = &self.theme {
+            if !THEMES.contains(&theme.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "theme must be one of {}: {}",
+                    THEMES.join(", "),
+                    theme
+                )));
+            }
+        }
+        for (name, host) in &self.hosts {
+            host.validate(name)?;
+        }
+        if let Some(name) = &self.active_host {
+            self.host(name)?;
+        }
+        if let Some(address) = &self.daemon_address {
+            if !DAEMON_ADDRESS_SCHEMES
+                .iter()
+                .any(|s| address.starts_with(s))
+            {
+                return Err(AppError::Validation(format!(
+                    "daemon_address must start with one of {}: {}",
+                    DAEMON_ADDRESS_SCHEMES.join(", "),
+                    address
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lower-cased registry host, with Docker Hub aliases mapped to `docker.io`.
+pub fn canonical_registry(registry: &str) -> String {
+    let host = registry.trim().trim_end_matches('/').to_ascii_lowercase();
+    if DOCKER_HUB_HOSTS.contains(&host.as_str()) {
+        "docker.io".to_string()
+    } else {
+        host
+    }
+}
+
+fn unknown_key(key: &str) -> AppError {
+    AppError::Validation(format!(
+        "Unknown config key '{}' (expected one of: {})",
+        key,
+        CONFIG_KEYS.join(", ")
+    ))
+}
+
+fn parse_quota<T: std::str::FromStr>(
+    key: &str,
+    value: Option<String>,
+) -> Result<Option<T>, AppError> {
+    value
+        .map(|v| {
+            v.parse().map_err(|_| {
+                AppError::Validation(format!("{} must be a positive integer: {}", key, v))
+            })
+        })
+        .transpose()
+}
+
+/// Parse a boolean setting (`true`/`on`/`yes`/`1` or `false`/`off`/`no`/`0`).
+pub fn parse_bool(key: &str, value: Option<String>) -> Result<Option<bool>, AppError> {
+    value
+        .map(|v| match v.to_ascii_lowercase().as_str() {
+            "true" | "on" | "yes" | "1" => Ok(true),
+            "false" | "off" | "no" | "0" => Ok(false),
+            _ => Err(AppError::Validation(format!(
+                "{} must be true or false: {}",
+                key, v
+            ))),
+        })
+        .transpose()
+}
+
+fn env_non_empty(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_file_yields_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::load_from(&dir.path().join(CONFIG_FILE_NAME)).unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn set_save_and_reload_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+
+        let mut config = Config::default();
+        config.set("docker_host", "tcp://127.0.0.1:2375").unwrap();
+        config.set("log_level", "DEBUG").unwrap();
+        config
+            .set("registry_mirrors", "https://a.example, https://b.example")
+            .unwrap();
+        config.save_to(&path).unwrap();
+
+        let loaded = Config::load_from(&path).unwrap();
+        assert_eq!(loaded, config);
+        assert_eq!(loaded.get("log_level").unwrap().as_deref(), Some("debug"));
+        assert_eq!(
+            loaded.get("registry_mirrors").unwrap().as_deref(),
+            Some("https://a.example,https://b.example")
+        );
+    }
+
+    #[test]
+    fn empty_value_unsets_key() {
+        let mut config = Config::default();
+        config
+            .set("docker_host", "unix:///tmp/docker.sock")
+            .unwrap();
+        config.set("docker_host", "").unwrap();
+        assert!(config.docker_host.is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_values() {
+        let mut config = Config::default();
+        assert!(matches!(
+            config.set("grpc_addr", "x"),
+            Err(AppError::Validation(_))
+        ));
+        assert!(config.set("log_level", "loud").is_err());
+        assert!(config.set("docker_host", "/var/run/docker.sock").is_err());
+        assert!(config.set("registry_mirrors", "mirror.example").is_err());
+    }
+
+    #[test]
+    fn quotas_round_trip_and_enforce_limits() {
+        let mut config = Config::default();
+        assert!(config.check_quota(QuotaResource::Cpus, 64).is_ok());
+
+        config.set("max_cpus", "4").unwrap();
+        config.set("max_disk_gb", "100").unwrap();
+        assert_eq!(config.get("max_cpus").unwrap().as_deref(), Some("4"));
+        assert!(config.check_quota(QuotaResource::Cpus, 4).is_ok());
+        assert!(matches!(
+            config.check_quota(QuotaResource::Cpus, 6),
+            Err(AppError::QuotaExceeded {
+                requested: 6,
+                limit: 4,
+                ..
+            })
+        ));
+        assert!(config.check_quota(QuotaResource::MemoryMb, 1 << 20).is_ok());
+
+        assert!(config.set("max_memory_mb", "lots").is_err());
+        assert!(config.set("max_disk_gb", "0").is_err());
+        assert_eq!(config.max_disk_gb, Some(100));
+    }
+
+    #[test]
+    fn registry_settings_resolve_per_host() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(
+            &path,
+            r#"registry_mirrors = ["https://hub.mirror.example"]
+registry_proxy = "http://proxy.example:3128"
+
+[registries."ghcr.io"]
+mirrors = ["https://ghcr.mirror.example"]
+proxy = "socks5h://127.0.0.1:1080"
+
+[registries."nexus.lan:8082"]
+insecure = true
+"#,
+        )
+        .unwrap();
+        let config = Config::load_from(&path).unwrap();
+
+        assert_eq!(
+            config.registry_mirrors_for("registry-1.docker.io"),
+            ["https://hub.mirror.example"]
+        );
+        assert_eq!(
+            config.registry_mirrors_for("GHCR.io"),
+            ["https://ghcr.mirror.example"]
+        );
+        assert!(config.registry_mirrors_for("quay.io").is_empty());
+        assert!(config.registry("nexus.lan:8082").unwrap().insecure);
+        assert!(!config.registry("ghcr.io").unwrap().insecure);
+        assert_eq!(
+            config.mirrors_for_image("ghcr.io/org/app:1"),
+            ["https://ghcr.mirror.example"]
+        );
+        assert_eq!(
+            config.mirrors_for_image("nginx"),
+            ["https://hub.mirror.example"]
+        );
+        assert_eq!(
+            config.registry_proxy_for("ghcr.io").as_deref(),
+            Some("socks5h://127.0.0.1:1080")
+        );
+        assert_eq!(
+            config.registry_proxy_for("quay.io").as_deref(),
+            Some("http://proxy.example:3128")
+        );
+
+        let mut config = config;
+        assert!(config.set("registry_proxy", "proxy.example:3128").is_err());
+        config.set("registry_proxy", "").unwrap();
+        assert!(config.registry_proxy_for("quay.io").is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_fields_in_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "docker_hots = \"unix:///x\"\n").unwrap();
+        assert!(matches!(
+            Config::load_from(&path),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn validates_preference_keys() {
+        let mut config = Config::default();
+        config.set("theme", "Light").unwrap();
+        config.set("telemetry", "on").unwrap();
+        config
+            .set("daemon_address", "unix:///tmp/cratebayd.sock")
+            .unwrap();
+        assert_eq!(config.get("theme").unwrap().as_deref(), Some("light"));
+        assert_eq!(config.get("telemetry").unwrap().as_deref(), Some("true"));
+
+        assert!(config.set("theme", "blue").is_err());
+        assert!(config.set("telemetry", "maybe").is_err());
+        assert!(config.set("daemon_address", "localhost:9000").is_err());
+        assert_eq!(config.theme.as_deref(), Some("light"));
+    }
+
+    #[test]
+    fn hosts_validate_transport_settings() {
+        let mut config = Config::default();
+        config.hosts.insert(
+            "lab".to_string(),
+            HostSettings {
+                url: "ssh://me@lab.example".to_string(),
+                identity_file: Some(PathBuf::from("/home/me/.ssh/lab")),
+                ..Default::default()
+            },
+        );
+        config.hosts.insert(
+            "ci".to_string(),
+            HostSettings {
+                url: "tcp://ci.example:2376".to_string(),
+                tls_ca: Some(PathBuf::from("ca.pem")),
+                tls_cert: Some(PathBuf::from("cert.pem")),
+                tls_key: Some(PathBuf::from("key.pem")),
+                ..Default::default()
+            },
+        );
+        config.set("active_host", "ci").unwrap();
+        assert!(config.host("ci").unwrap().uses_tls());
+        assert_eq!(
+            config
+                .host_entries()
+                .iter()
+                .map(|h| (h.name.as_str(), h.active))
+                .collect::<Vec<_>>(),
+            [("ci", true), ("lab", false)]
+        );
+        assert!(matches!(
+            config.set("active_host", "prod"),
+            Err(AppError::NotFound { .. })
+        ));
+
+        let mut bad = config.clone();
+        bad.hosts.get_mut("ci").unwrap().tls_key = None;
+        assert!(bad.validate().is_err());
+        let mut bad = config.clone();
+        bad.hosts.get_mut("lab").unwrap().tls_ca = Some(PathBuf::from("ca.pem"));
+        assert!(bad.validate().is_err());
+        let mut bad = config.clone();
+        bad.hosts.get_mut("ci").unwrap().identity_file = Some(PathBuf::from("id"));
+        assert!(bad.validate().is_err());
+        let ssh = HostSettings {
+            url: "ssh://x".to_string(),
+            ..Default::default()
+        };
+        assert!(config.add_host("bad name", ssh.clone()).is_err());
+        assert!(config.add_host(LOCAL_HOST, ssh.clone()).is_err());
+        config.add_host("x", ssh).unwrap();
+        config.remove_host("ci").unwrap();
+        assert_eq!(config.active_host, None);
+        assert!(config.remove_host("ci").is_err());
+    }
+
+    #[test]
+    fn log_levels_extend_the_base_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(
+            &path,
+            "log_level = \"warn\"\nlog_format = \"json\"\n\n[log_levels]\n\"cratebay_core::registry\" = \"debug\"\nbollard = \"error\"\n",
+        )
+        .unwrap();
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.get("log_format").unwrap().as_deref(), Some("json"));
+        assert_eq!(
+            config.file_log_filter("info"),
+            "warn,bollard=error,cratebay_core::registry=debug"
+        );
+        assert_eq!(
+            Config::default().file_log_filter("cratebay=info"),
+            "cratebay=info"
+        );
+
+        std::fs::write(&path, "[log_levels]\nbollard = \"loud\"\n").unwrap();
+        assert!(Config::load_from(&path).is_err());
+        std::fs::write(&path, "[log_levels]\n\"a=b\" = \"info\"\n").unwrap();
+        assert!(Config::load_from(&path).is_err());
+        assert!(Config::default().set("log_format", "xml").is_err());
+    }
+
+    #[test]
+    fn default_vm_sizes_respect_quotas() {
+        let mut config = Config::default();
+        config.set("max_cpus", "4").unwrap();
+        config.set("default_vm_cpus", "4").unwrap();
+        assert!(matches!(
+            config.set("default_vm_cpus", "8"),
+            Err(AppError::QuotaExceeded { .. })
+        ));
+        assert!(config.set("default_vm_memory_mb", "0").is_err());
+        assert_eq!(config.default_vm_cpus, Some(4));
+    }
+}
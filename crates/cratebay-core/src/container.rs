@@ -4,10 +4,14 @@
 
 use bollard::container::{
     Config, CreateContainerOptions, InspectContainerOptions, ListContainersOptions, LogsOptions,
-    RemoveContainerOptions, StatsOptions, StopContainerOptions,
+    RemoveContainerOptions, RenameContainerOptions, RestartContainerOptions, StatsOptions,
+    StopContainerOptions, UpdateContainerOptions,
 };
 use bollard::exec::{CreateExecOptions, StartExecResults};
-use bollard::image::{ListImagesOptions, RemoveImageOptions, SearchImagesOptions, TagImageOptions};
+use bollard::image::{
+    CommitContainerOptions, ListImagesOptions, RemoveImageOptions, SearchImagesOptions,
+    TagImageOptions,
+};
 use bollard::Docker;
 use futures_util::StreamExt;
 use serde::Deserialize;
@@ -16,26 +20,64 @@ use std::error::Error as _;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::cancel::{self, CancellationToken};
+use crate::config::QuotaResource;
 use crate::error::AppError;
 use crate::models::{
-    ContainerCreateRequest, ContainerDetail, ContainerInfo, ContainerListFilters, ContainerState,
-    ContainerStats, ContainerStatus, ExecResult, ExecStreamChunk, ImageInspectInfo,
-    ImageSearchResult, LocalImageInfo, LogEntry, LogOptions, PortMapping,
+    ContainerCommitRequest, ContainerCreateRequest, ContainerDetail, ContainerInfo,
+    ContainerListFilters, ContainerProcess, ContainerState, ContainerStats, ContainerStatus,
+    DockerEvent, ExecResult, ExecStreamChunk, ImageBuildRequest, ImageInspectInfo, ImageLayer,
+    ImageLayers, ImageSearchResult, LocalImageInfo, LogEntry, LogOptions, PortMapping,
+    RegistryAuth, RunValidationIssue, RunValidationReport, VolumeMount,
 };
 
 const DOCKER_LIST_TIMEOUT: Duration = Duration::from_secs(8);
 const DOCKER_CREATE_TIMEOUT: Duration = Duration::from_secs(60);
 const DOCKER_START_TIMEOUT: Duration = Duration::from_secs(60);
 const DOCKER_STOP_TIMEOUT: Duration = Duration::from_secs(30);
+const DOCKER_PAUSE_TIMEOUT: Duration = Duration::from_secs(15);
+const DOCKER_UPDATE_TIMEOUT: Duration = Duration::from_secs(15);
 const DOCKER_DELETE_TIMEOUT: Duration = Duration::from_secs(30);
 const DOCKER_INSPECT_TIMEOUT: Duration = Duration::from_secs(8);
 const DOCKER_STATS_TIMEOUT: Duration = Duration::from_secs(8);
+const DOCKER_TOP_TIMEOUT: Duration = Duration::from_secs(8);
 const DOCKER_EXEC_SETUP_TIMEOUT: Duration = Duration::from_secs(12);
+const DOCKER_RUNTIME_EXEC_TIMEOUT: Duration = Duration::from_secs(300);
 const DOCKER_LOGS_TIMEOUT: Duration = Duration::from_secs(12);
 const DOCKER_IMAGE_LIST_TIMEOUT: Duration = Duration::from_secs(12);
 const DOCKER_IMAGE_INSPECT_TIMEOUT: Duration = Duration::from_secs(12);
 const DOCKER_IMAGE_REMOVE_TIMEOUT: Duration = Duration::from_secs(12);
 const DOCKER_IMAGE_TAG_TIMEOUT: Duration = Duration::from_secs(12);
+const DOCKER_COMMIT_TIMEOUT: Duration = Duration::from_secs(300);
+/// Instructions Docker accepts in `commit --change`.
+const COMMIT_CHANGE_INSTRUCTIONS: &[&str] = &[
+    "CMD",
+    "ENTRYPOINT",
+    "ENV",
+    "EXPOSE",
+    "LABEL",
+    "ONBUILD",
+    "STOPSIGNAL",
+    "USER",
+    "VOLUME",
+    "WORKDIR",
+];
+/// Label holding a create request's idempotency key.
+pub const IDEMPOTENCY_KEY_LABEL: &str = "com.cratebay.idempotency_key";
+
+/// How long a replayed idempotency key returns the container it created.
+pub const IDEMPOTENCY_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Helper image for [`runtime_exec`]; busybox ships `nsenter`.
+const RUNTIME_EXEC_IMAGE: &str = "alpine:3.20";
+
+/// Label on CrateBay's own helper containers; its value names the helper.
+/// They are left out of container lists and [`watch_events`].
+pub const INTERNAL_LABEL: &str = "com.cratebay.internal";
+
+fn is_internal(labels: Option<&HashMap<String, String>>) -> bool {
+    labels.is_some_and(|l| l.contains_key(INTERNAL_LABEL))
+}
 
 /// List all containers, optionally filtered.
 pub async fn list(
@@ -43,32 +85,16 @@ pub async fn list(
     all: bool,
     filters: Option<ContainerListFilters>,
 ) -> Result<Vec<ContainerInfo>, AppError> {
-    let mut list_filters = HashMap::new();
-    if let Some(ref f) = filters {
-        if let Some(ref statuses) = f.status {
-            let status_strings: Vec<String> = statuses
-                .iter()
-                .map(|s| {
-                    format!("{}", serde_json::to_value(s).unwrap_or_default())
-                        .trim_matches('"')
-                        .to_string()
-                })
-                .collect();
-            list_filters.insert("status".to_string(), status_strings);
-        }
-        if let Some(ref name) = f.name {
-            list_filters.insert("name".to_string(), vec![name.clone()]);
-        }
-        if let Some(ref label) = f.label {
-            let label_filters: Vec<String> =
-                label.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
-            list_filters.insert("label".to_string(), label_filters);
-        }
-    }
-
+    // The limit is applied after helper containers and the image filter are
+    // dropped client-side, so it is not sent to Docker; Docker's own `limit`
+    // also includes stopped containers.
+    let limit = filters.as_ref().and_then(|f| f.limit);
     let options = Some(ListContainersOptions {
-        all,
-        filters: list_filters,
+        all: all || limit.is_some(),
+        filters: filters
+            .as_ref()
+            .map(docker_list_filters)
+            .unwrap_or_default(),
         ..Default::default()
     });
 
@@ -81,85 +107,680 @@ pub async fn list(
             ))
         })??;
 
-    let mut results: Vec<ContainerInfo> = containers
+    Ok(visible_containers(containers, filters.as_ref()))
+}
+
+/// Drop helper containers, apply the client-side image filter (the Docker
+/// API has no image substring match), then the limit. Docker lists the
+/// newest first.
+fn visible_containers(
+    containers: Vec<bollard::models::ContainerSummary>,
+    filters: Option<&ContainerListFilters>,
+) -> Vec<ContainerInfo> {
+    let image_filter = filters.and_then(|f| f.image.as_deref());
+    let limit = filters
+        .and_then(|f| f.limit)
+        .map_or(usize::MAX, |n| n as usize);
+    containers
         .into_iter()
-        .map(|c| {
-            let id = c.id.unwrap_or_default();
-            let short_id = id.chars().take(12).collect();
-            let names = c.names.unwrap_or_default();
-            let name = names
-                .first()
-                .map(|n| n.trim_start_matches('/').to_string())
-                .unwrap_or_default();
-
-            let status = match c.state.as_deref() {
-                Some("running") => ContainerStatus::Running,
-                Some("exited") => ContainerStatus::Exited,
-                Some("created") => ContainerStatus::Created,
-                Some("restarting") => ContainerStatus::Restarting,
-                Some("removing") => ContainerStatus::Removing,
-                Some("paused") => ContainerStatus::Paused,
-                Some("dead") => ContainerStatus::Dead,
-                _ => ContainerStatus::Stopped,
-            };
+        .filter(|c| !is_internal(c.labels.as_ref()))
+        .map(container_info)
+        .filter(|c| image_filter.is_none_or(|image| c.image.contains(image)))
+        .take(limit)
+        .collect()
+}
 
-            let ports = c
-                .ports
-                .unwrap_or_default()
-                .into_iter()
-                .filter_map(|p| {
-                    Some(PortMapping {
-                        host_port: p.public_port?,
-                        container_port: p.private_port,
-                        protocol: p
-                            .typ
-                            .map(|t| t.to_string())
-                            .unwrap_or_else(|| "tcp".to_string()),
-                    })
-                })
-                .collect();
+/// List the given containers, running or not. Removed ones are absent.
+pub(crate) async fn list_ids(
+    docker: &Docker,
+    ids: &[String],
+) -> Result<Vec<ContainerInfo>, AppError> {
+    let options = Some(ListContainersOptions {
+        all: true,
+        filters: HashMap::from([("id".to_string(), ids.to_vec())]),
+        ..Default::default()
+    });
+    let containers = tokio::time::timeout(DOCKER_LIST_TIMEOUT, docker.list_containers(options))
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Docker container list timed out after {:?}",
+                DOCKER_LIST_TIMEOUT
+            ))
+        })??;
+    Ok(containers
+        .into_iter()
+        .filter(|c| !is_internal(c.labels.as_ref()))
+        .map(container_info)
+        .collect())
+}
 
-            let labels = c.labels.unwrap_or_default();
+/// Convert a Docker container summary to [`ContainerInfo`].
+pub(crate) fn container_info(c: bollard::models::ContainerSummary) -> ContainerInfo {
+    let id = c.id.unwrap_or_default();
+    let short_id = id.chars().take(12).collect();
+    let names = c.names.unwrap_or_default();
+    let name = names
+        .first()
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_default();
 
-            // Extract resource limits from labels (if set by CrateBay)
-            let cpu_cores = labels
-                .get("com.cratebay.cpu_cores")
-                .and_then(|v| v.parse().ok());
-            let memory_mb = labels
-                .get("com.cratebay.memory_mb")
-                .and_then(|v| v.parse().ok());
-
-            ContainerInfo {
-                id,
-                short_id,
-                name,
-                image: c.image.unwrap_or_default(),
-                status,
-                state: c.state.unwrap_or_default(),
-                created_at: c
-                    .created
-                    .map(|t| {
-                        chrono::DateTime::from_timestamp(t, 0)
-                            .map(|dt| dt.to_rfc3339())
-                            .unwrap_or_default()
-                    })
-                    .unwrap_or_default(),
-                ports,
-                labels,
-                cpu_cores,
-                memory_mb,
-            }
+    let status = match c.state.as_deref() {
+        Some("running") => ContainerStatus::Running,
+        Some("exited") => ContainerStatus::Exited,
+        Some("created") => ContainerStatus::Created,
+        Some("restarting") => ContainerStatus::Restarting,
+        Some("removing") => ContainerStatus::Removing,
+        Some("paused") => ContainerStatus::Paused,
+        Some("dead") => ContainerStatus::Dead,
+        _ => ContainerStatus::Stopped,
+    };
+
+    let ports = c
+        .ports
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|p| {
+            Some(PortMapping {
+                host_port: p.public_port?,
+                container_port: p.private_port,
+                protocol: p
+                    .typ
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "tcp".to_string()),
+            })
         })
         .collect();
 
-    // Apply client-side image filter (Docker API doesn't support image substring match)
-    if let Some(ref f) = filters {
-        if let Some(ref image_filter) = f.image {
-            results.retain(|c| c.image.contains(image_filter.as_str()));
+    let labels = c.labels.unwrap_or_default();
+
+    // Extract resource limits from labels (if set by CrateBay)
+    let cpu_cores = labels
+        .get("com.cratebay.cpu_cores")
+        .and_then(|v| v.parse().ok());
+    let memory_mb = labels
+        .get("com.cratebay.memory_mb")
+        .and_then(|v| v.parse().ok());
+
+    ContainerInfo {
+        id,
+        short_id,
+        name,
+        image: c.image.unwrap_or_default(),
+        status,
+        state: c.state.unwrap_or_default(),
+        created_at: c
+            .created
+            .map(|t| {
+                chrono::DateTime::from_timestamp(t, 0)
+                    .map(|dt| dt.to_rfc3339())
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default(),
+        ports,
+        labels,
+        cpu_cores,
+        memory_mb,
+    }
+}
+
+/// Containers inspected at once when summing reservations.
+const RESERVATION_INSPECT_CONCURRENCY: usize = 8;
+
+/// Check a new container's CPU/memory reservation against the `max_cpus` /
+/// `max_memory_mb` quotas.
+async fn check_container_quotas(
+    docker: &Docker,
+    request: &ContainerCreateRequest,
+) -> Result<(), AppError> {
+    check_reservation_quotas(docker, None, request.cpu_cores, request.memory_mb).await
+}
+
+/// Check `cpus` / `memory_mb` for one container, plus what every other
+/// container already reserves, against the `max_cpus` / `max_memory_mb`
+/// quotas; `None` leaves that resource unchecked. `exclude` is the
+/// container being checked, if it exists. Containers without explicit
+/// limits reserve nothing.
+async fn check_reservation_quotas(
+    docker: &Docker,
+    exclude: Option<&str>,
+    cpus: Option<u32>,
+    memory_mb: Option<u64>,
+) -> Result<(), AppError> {
+    let quotas = crate::config::Config::load_or_default();
+    let cpus = cpus.filter(|_| quotas.max_cpus.is_some());
+    let memory_mb = memory_mb.filter(|_| quotas.max_memory_mb.is_some());
+    if cpus.is_none() && memory_mb.is_none() {
+        return Ok(());
+    }
+
+    let exclude = match exclude {
+        Some(id) => Some(inspect(docker, id).await?.info.id),
+        None => None,
+    };
+    let (reserved_cpus, reserved_memory_mb) =
+        reserved_resources(docker, exclude.as_deref()).await?;
+    if let Some(cpus) = cpus {
+        quotas.check_quota(QuotaResource::Cpus, reserved_cpus + u64::from(cpus))?;
+    }
+    if let Some(memory_mb) = memory_mb {
+        quotas.check_quota(QuotaResource::MemoryMb, reserved_memory_mb + memory_mb)?;
+    }
+    Ok(())
+}
+
+/// CPU cores and memory (MB) reserved by every container except `exclude`
+/// (a full ID). Stopped containers count: starting them takes their
+/// reservation back. Limits come from inspect, so changes made by
+/// [`update_resources`] after creation are seen.
+async fn reserved_resources(
+    docker: &Docker,
+    exclude: Option<&str>,
+) -> Result<(u64, u64), AppError> {
+    let ids: Vec<String> = list(docker, true, None)
+        .await?
+        .into_iter()
+        .map(|c| c.id)
+        .filter(|id| Some(id.as_str()) != exclude)
+        .collect();
+    // Owned IDs keep the future `Send` for callers that spawn it.
+    let inspected: Vec<Result<ContainerDetail, AppError>> = futures_util::stream::iter(ids)
+        .map(|id| async move { inspect(docker, &id).await })
+        .buffer_unordered(RESERVATION_INSPECT_CONCURRENCY)
+        .collect()
+        .await;
+    let mut containers = Vec::with_capacity(inspected.len());
+    for detail in inspected {
+        match detail {
+            Ok(detail) => containers.push(detail.info),
+            // Removed since the list.
+            Err(AppError::Docker(bollard::errors::Error::DockerResponseServerError {
+                status_code: 404,
+                ..
+            })) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(sum_reservations(&containers))
+}
+
+/// Total CPU cores and memory (MB) limits of `containers`.
+fn sum_reservations(containers: &[ContainerInfo]) -> (u64, u64) {
+    containers.iter().fold((0, 0), |(cpus, memory_mb), c| {
+        (
+            cpus + c.cpu_cores.map_or(0, u64::from),
+            memory_mb + c.memory_mb.unwrap_or(0),
+        )
+    })
+}
+
+/// Whole cores for a `NanoCpus` limit, rounded up so a fractional limit
+/// still reserves a core against `max_cpus`.
+fn nano_cpus_to_cores(nano_cpus: i64) -> u32 {
+    ((nano_cpus + 999_999_999) / 1_000_000_000) as u32
+}
+
+/// Check a container's own reservation against the quotas before it
+/// starts, in case the quotas were lowered or other containers grew since
+/// it was created.
+async fn check_start_quotas(docker: &Docker, id: &str) -> Result<(), AppError> {
+    let quotas = crate::config::Config::load_or_default();
+    if quotas.max_cpus.is_none() && quotas.max_memory_mb.is_none() {
+        return Ok(());
+    }
+    let detail = inspect(docker, id).await?;
+    check_reservation_quotas(
+        docker,
+        Some(&detail.info.id),
+        detail.info.cpu_cores,
+        detail.info.memory_mb,
+    )
+    .await
+}
+
+fn run_issue(field: &str, severity: &str, message: impl Into<String>) -> RunValidationIssue {
+    RunValidationIssue {
+        field: field.to_string(),
+        severity: severity.to_string(),
+        message: message.into(),
+    }
+}
+
+/// Message of an error without the variant prefix, for inline display.
+fn issue_message(error: AppError) -> String {
+    match error {
+        AppError::Validation(message) => message,
+        other => other.to_string(),
+    }
+}
+
+/// Dry-run `request` without creating anything: name, image, published
+/// ports, volume paths and resource limits are checked against the Docker
+/// host, so a wizard can show every problem at once.
+pub async fn validate_create(
+    docker: &Docker,
+    request: &ContainerCreateRequest,
+) -> Result<RunValidationReport, AppError> {
+    let mut issues = Vec::new();
+
+    match crate::validation::validate_container_name(&request.name) {
+        Err(e) => issues.push(run_issue("name", "error", issue_message(e))),
+        Ok(()) => {
+            if docker
+                .inspect_container(&request.name, None::<InspectContainerOptions>)
+                .await
+                .is_ok()
+            {
+                issues.push(run_issue(
+                    "name",
+                    "error",
+                    format!("A container named '{}' already exists", request.name),
+                ));
+            }
         }
     }
 
-    Ok(results)
+    let image = image_issues(docker, &request.image).await;
+    let image_ok = !image.iter().any(|i| i.severity == "error");
+    issues.extend(image);
+    if image_ok {
+        if let Some(warning) =
+            platform_warning(docker, &request.image, request.platform.as_deref()).await
+        {
+            issues.push(run_issue("platform", "warning", warning));
+        }
+    }
+
+    let ports = request.ports.clone().unwrap_or_default();
+    if let Some(port) = crate::ports::duplicate_host_port(&ports) {
+        issues.push(run_issue(
+            "ports",
+            "error",
+            format!("Host port {} is mapped more than once", port),
+        ));
+    }
+    for conflict in crate::ports::find_conflicts(docker, &ports, None).await? {
+        issues.push(run_issue("ports", "error", conflict.to_string()));
+    }
+
+    issues.extend(volume_issues(
+        request.volumes.as_deref().unwrap_or_default(),
+    ));
+
+    let info = docker.info().await?;
+    let host_cpus = info.ncpu.and_then(|n| u32::try_from(n).ok());
+    let host_memory_mb = info.mem_total.map(|b| (b.max(0) as u64) / 1024 / 1024);
+    issues.extend(resource_issues(
+        request.cpu_cores,
+        request.memory_mb,
+        host_cpus,
+        host_memory_mb,
+    ));
+    match check_container_quotas(docker, request).await {
+        Err(e @ AppError::QuotaExceeded { .. }) => {
+            let field = match &e {
+                AppError::QuotaExceeded { resource, .. } if resource == "max_cpus" => "cpuCores",
+                _ => "memoryMb",
+            };
+            issues.push(run_issue(field, "error", e.to_string()));
+        }
+        other => other?,
+    }
+
+    Ok(RunValidationReport {
+        ok: !issues.iter().any(|i| i.severity == "error"),
+        issues,
+    })
+}
+
+/// The image must exist locally or in its registry; a remote-only image
+/// is a warning because it will be pulled first.
+async fn image_issues(docker: &Docker, image: &str) -> Vec<RunValidationIssue> {
+    if image.trim().is_empty() {
+        return vec![run_issue("image", "error", "Image must not be empty")];
+    }
+    if image_exists(docker, image).await.unwrap_or(false) {
+        return Vec::new();
+    }
+    match crate::registry::fetch_manifest(image, None, None).await {
+        Ok(manifest) => vec![run_issue(
+            "image",
+            "warning",
+            format!(
+                "{} is not present locally and will be pulled",
+                manifest.reference
+            ),
+        )],
+        Err(AppError::NotFound { .. }) => vec![run_issue(
+            "image",
+            "error",
+            format!("Image '{}' was not found locally or in its registry", image),
+        )],
+        Err(e) => vec![run_issue(
+            "image",
+            "warning",
+            format!(
+                "Image '{}' is not present locally and could not be checked remotely: {}",
+                image,
+                issue_message(e)
+            ),
+        )],
+    }
+}
+
+/// Why `image` would not run natively on the runtime, if it would not:
+/// it only exists for another architecture, or `platform` asks for one.
+/// Emulated images run slowly, and fail with `exec format error` when the
+/// runtime has no binfmt handler for them. `None` when the image runs
+/// natively or its platforms cannot be determined.
+pub async fn platform_warning(
+    docker: &Docker,
+    image: &str,
+    platform: Option<&str>,
+) -> Option<String> {
+    let engine = engine_platform(docker).await;
+    let (available, multi_arch) = image_platforms(docker, image).await?;
+    let emulated = match run_platform(&engine, &available, multi_arch, platform) {
+        Ok(None) => return None,
+        Ok(Some(emulated)) => emulated,
+        Err(reason) => return Some(format!("'{}' {}", image, reason)),
+    };
+    let arch = platform_arch(&emulated);
+    Some(match emulator_registered(docker, arch).await {
+        Some(true) => format!(
+            "'{}' runs as {} under emulation on this {} runtime, which is much slower than native",
+            image, emulated, engine
+        ),
+        Some(false) => format!(
+            "'{}' is {} but this runtime is {} and has no emulator for it, so it will fail \
+             with 'exec format error'; {}",
+            image,
+            emulated,
+            engine,
+            emulator_hint(&engine, arch)
+        ),
+        None => format!(
+            "'{}' runs as {} on this {} runtime, which needs an emulator (binfmt handler)",
+            image, emulated, engine
+        ),
+    })
+}
+
+/// `linux/<arch>` of the Docker engine (the runtime VM for the built-in one).
+async fn engine_platform(docker: &Docker) -> String {
+    match docker.info().await.ok().and_then(|info| info.architecture) {
+        Some(arch) => format!("linux/{}", docker_arch(&arch)),
+        None => crate::registry::host_platform(),
+    }
+}
+
+/// Docker's name for a `uname -m` architecture.
+fn docker_arch(uname: &str) -> &str {
+    match uname {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "armv7l" => "arm",
+        "i686" | "i386" => "386",
+        other => other,
+    }
+}
+
+fn platform_arch(platform: &str) -> &str {
+    platform.split('/').nth(1).unwrap_or(platform)
+}
+
+/// Platforms `image` is available for: the local image's own, else those
+/// in its registry manifest. The flag tells whether it is a multi-arch
+/// index, from which Docker refuses to pick a foreign platform by itself.
+async fn image_platforms(docker: &Docker, image: &str) -> Option<(Vec<String>, bool)> {
+    if let Ok(local) = docker.inspect_image(image).await {
+        let os = local.os.unwrap_or_else(|| "linux".to_string());
+        let arch = local.architecture?;
+        let platform = match local.variant {
+            Some(variant) if !variant.is_empty() => format!("{}/{}/{}", os, arch, variant),
+            _ => format!("{}/{}", os, arch),
+        };
+        return Some((vec![platform], false));
+    }
+    let manifest = crate::registry::fetch_manifest(image, None, None)
+        .await
+        .ok()?;
+    let platforms = manifest.platforms.into_iter().map(|p| p.platform).collect();
+    Some((platforms, manifest.multi_arch))
+}
+
+/// Platform an image available as `available` runs as on an `engine`
+/// runtime when `requested` is passed: `Ok(None)` natively, `Ok(Some(_))`
+/// under emulation, `Err` with the reason when Docker has no variant to run.
+fn run_platform(
+    engine: &str,
+    available: &[String],
+    multi_arch: bool,
+    requested: Option<&str>,
+) -> Result<Option<String>, String> {
+    let engine_arch = platform_arch(engine);
+    let has_arch = |arch: &str| available.iter().any(|p| platform_arch(p) == arch);
+    if let Some(requested) = requested {
+        let arch = platform_arch(requested);
+        if arch == engine_arch {
+            return Ok(None);
+        }
+        if multi_arch && !has_arch(arch) {
+            return Err(format!(
+                "has no {} variant (available: {})",
+                requested,
+                available.join(", ")
+            ));
+        }
+        return Ok(Some(requested.to_string()));
+    }
+    match available.first() {
+        None => Ok(None),
+        Some(_) if has_arch(engine_arch) => Ok(None),
+        Some(first) if multi_arch => Err(format!(
+            "has no {} variant (available: {}); pass --platform {} to run it under emulation",
+            engine,
+            available.join(", "),
+            first
+        )),
+        Some(only) => Ok(Some(only.clone())),
+    }
+}
+
+/// Whether the runtime has a binfmt handler for `arch` (Rosetta or QEMU
+/// user mode); `None` if that cannot be checked.
+async fn emulator_registered(docker: &Docker, arch: &str) -> Option<bool> {
+    let qemu = match arch {
+        "amd64" => "x86_64",
+        "arm64" => "aarch64",
+        "386" => "i386",
+        other => other,
+    };
+    let script = format!(
+        "ls /proc/sys/fs/binfmt_misc | grep -qx -e 'qemu-{}'{}",
+        qemu,
+        if arch == "amd64" { " -e rosetta" } else { "" }
+    );
+    let cmd = vec!["/bin/sh".to_string(), "-c".to_string(), script];
+    let result = runtime_exec(docker, cmd, Some(Duration::from_secs(30)))
+        .await
+        .ok()?;
+    Some(result.exit_code == 0)
+}
+
+fn emulator_hint(engine: &str, arch: &str) -> String {
+    if cfg!(target_os = "macos") && platform_arch(engine) == "arm64" && arch == "amd64" {
+        "start the runtime with CRATEBAY_RUNTIME_ROSETTA=1 to run amd64 images through Rosetta"
+            .to_string()
+    } else {
+        format!(
+            "register one with `docker run --privileged --rm tonistiigi/binfmt --install {}`",
+            arch
+        )
+    }
+}
+
+/// Host paths must exist and container paths must be absolute.
+fn volume_issues(volumes: &[VolumeMount]) -> Vec<RunValidationIssue> {
+    let mut issues = Vec::new();
+    for volume in volumes {
+        if !std::path::Path::new(&volume.host_path).exists() {
+            issues.push(run_issue(
+                "volumes",
+                "error",
+                format!("Host path '{}' does not exist", volume.host_path),
+            ));
+        }
+        if !volume.container_path.starts_with('/') {
+            issues.push(run_issue(
+                "volumes",
+                "error",
+                format!(
+                    "Container path '{}' must be absolute",
+                    volume.container_path
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+/// CPU and memory limits must be valid and fit the Docker host.
+fn resource_issues(
+    cpu_cores: Option<u32>,
+    memory_mb: Option<u64>,
+    host_cpus: Option<u32>,
+    host_memory_mb: Option<u64>,
+) -> Vec<RunValidationIssue> {
+    let mut issues = Vec::new();
+    if let Some(cpu) = cpu_cores {
+        if let Err(e) = crate::validation::validate_cpu_cores(cpu) {
+            issues.push(run_issue("cpuCores", "error", issue_message(e)));
+        } else if let Some(host) = host_cpus.filter(|host| cpu > *host) {
+            issues.push(run_issue(
+                "cpuCores",
+                "error",
+                format!("{} CPUs requested but the runtime has {}", cpu, host),
+            ));
+        }
+    }
+    if let Some(memory) = memory_mb {
+        if let Err(e) = crate::validation::validate_memory_mb(memory) {
+            issues.push(run_issue("memoryMb", "error", issue_message(e)));
+        } else if let Some(host) = host_memory_mb.filter(|host| memory > *host) {
+            issues.push(run_issue(
+                "memoryMb",
+                "error",
+                format!("{} MB requested but the runtime has {} MB", memory, host),
+            ));
+        } else if let Some(host) = host_memory_mb.filter(|host| memory * 10 > host * 8) {
+            issues.push(run_issue(
+                "memoryMb",
+                "warning",
+                format!(
+                    "{} MB is more than 80% of the runtime's {} MB",
+                    memory, host
+                ),
+            ));
+        }
+    }
+    issues
+}
+
+/// Map [`ContainerListFilters`] onto Docker's server-side list filters.
+/// `image` is not included; it is a substring match applied client-side.
+fn docker_list_filters(f: &ContainerListFilters) -> HashMap<String, Vec<String>> {
+    let mut list_filters = HashMap::new();
+    if let Some(ref statuses) = f.status {
+        let status_strings: Vec<String> = statuses
+            .iter()
+            .map(|s| {
+                format!("{}", serde_json::to_value(s).unwrap_or_default())
+                    .trim_matches('"')
+                    .to_string()
+            })
+            .collect();
+        list_filters.insert("status".to_string(), status_strings);
+    }
+    if let Some(ref name) = f.name {
+        list_filters.insert("name".to_string(), vec![name.clone()]);
+    }
+    if let Some(ref label) = f.label {
+        let label_filters: Vec<String> = label
+            .iter()
+            .map(|(k, v)| {
+                if v.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}={}", k, v)
+                }
+            })
+            .collect();
+        list_filters.insert("label".to_string(), label_filters);
+    }
+    if let Some(ref ancestor) = f.ancestor {
+        list_filters.insert("ancestor".to_string(), vec![ancestor.clone()]);
+    }
+    list_filters
+}
+
+/// Parse `docker ps`-style `key=value` filters (`name`, `label`, `status`,
+/// `ancestor`). `label` may repeat; the other keys may appear once.
+pub fn parse_list_filters(specs: &[String]) -> Result<ContainerListFilters, AppError> {
+    let mut filters = ContainerListFilters::default();
+    for spec in specs {
+        let (key, value) = spec
+            .split_once('=')
+            .filter(|(_, v)| !v.is_empty())
+            .ok_or_else(|| {
+                AppError::Validation(format!("Filter must be key=value, got '{}'", spec))
+            })?;
+        let duplicate = || AppError::Validation(format!("Filter '{}' given more than once", key));
+        match key {
+            "name" => {
+                if filters.name.replace(value.to_string()).is_some() {
+                    return Err(duplicate());
+                }
+            }
+            "ancestor" => {
+                if filters.ancestor.replace(value.to_string()).is_some() {
+                    return Err(duplicate());
+                }
+            }
+            "label" => {
+                let (k, v) = value.split_once('=').unwrap_or((value, ""));
+                filters
+                    .label
+                    .get_or_insert_with(HashMap::new)
+                    .insert(k.to_string(), v.to_string());
+            }
+            "status" => {
+                let status = match value {
+                    "created" => ContainerStatus::Created,
+                    "restarting" => ContainerStatus::Restarting,
+                    "running" => ContainerStatus::Running,
+                    "removing" => ContainerStatus::Removing,
+                    "paused" => ContainerStatus::Paused,
+                    "exited" => ContainerStatus::Exited,
+                    "dead" => ContainerStatus::Dead,
+                    other => {
+                        return Err(AppError::Validation(format!(
+                            "Unknown status '{}' (expected created, restarting, running, removing, paused, exited or dead)",
+                            other
+                        )))
+                    }
+                };
+                filters.status.get_or_insert_with(Vec::new).push(status);
+            }
+            other => {
+                return Err(AppError::Validation(format!(
+                    "Unknown filter '{}' (expected name, label, status or ancestor)",
+                    other
+                )))
+            }
+        }
+    }
+    Ok(filters)
 }
 
 /// Create a new container from a request.
@@ -170,6 +791,16 @@ pub async fn create(
     docker: &Docker,
     request: ContainerCreateRequest,
 ) -> Result<ContainerInfo, AppError> {
+    if let Some(key) = &request.idempotency_key {
+        crate::validation::validate_idempotency_key(key)?;
+        if let Some(existing) = find_idempotent(docker, key, &request.name).await? {
+            return Ok(existing);
+        }
+    }
+    check_container_quotas(docker, &request).await?;
+    let ports = request.ports.clone().unwrap_or_default();
+    crate::ports::ensure_available(docker, &ports, None).await?;
+
     let mut labels: HashMap<String, String> = request.labels.clone().unwrap_or_default();
     labels.insert("com.cratebay.managed".to_string(), "true".to_string());
     if let Some(cpu) = request.cpu_cores {
@@ -181,10 +812,29 @@ pub async fn create(
     if let Some(ref template_id) = request.template_id {
         labels.insert("com.cratebay.template_id".to_string(), template_id.clone());
     }
+    if let Some(ref key) = request.idempotency_key {
+        labels.insert(IDEMPOTENCY_KEY_LABEL.to_string(), key.clone());
+    }
+
+    let mut exposed_ports = HashMap::new();
+    let mut port_bindings: bollard::models::PortMap = HashMap::new();
+    for port in &ports {
+        let key = format!("{}/{}", port.container_port, port.protocol.to_lowercase());
+        exposed_ports.insert(key.clone(), HashMap::new());
+        port_bindings
+            .entry(key)
+            .or_insert_with(|| Some(Vec::new()))
+            .get_or_insert_with(Vec::new)
+            .push(bollard::models::PortBinding {
+                host_ip: None,
+                host_port: Some(port.host_port.to_string()),
+            });
+    }
 
     let host_config = bollard::models::HostConfig {
         memory: request.memory_mb.map(|m| (m * 1024 * 1024) as i64),
         nano_cpus: request.cpu_cores.map(|c| (c as i64) * 1_000_000_000),
+        port_bindings: (!port_bindings.is_empty()).then_some(port_bindings),
         ..Default::default()
     };
 
@@ -201,6 +851,7 @@ pub async fn create(
         image: Some(request.image.clone()),
         cmd,
         env: request.env.clone(),
+        exposed_ports: (!exposed_ports.is_empty()).then_some(exposed_ports),
         host_config: Some(host_config),
         labels: Some(labels),
         working_dir: request.working_dir.clone(),
@@ -211,20 +862,37 @@ pub async fn create(
 
     let options = CreateContainerOptions {
         name: request.name.as_str(),
-        platform: None,
+        platform: request.platform.as_deref(),
     };
 
-    let response = tokio::time::timeout(
+    let response = match tokio::time::timeout(
         DOCKER_CREATE_TIMEOUT,
         docker.create_container(Some(options), config),
     )
     .await
-    .map_err(|_| {
-        AppError::Runtime(format!(
-            "Docker container create timed out after {:?}",
-            DOCKER_CREATE_TIMEOUT
-        ))
-    })??;
+    {
+        Ok(Ok(response)) => response,
+        // A concurrent retry with the same key won the race for the name.
+        Ok(Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 409, ..
+        })) if request.idempotency_key.is_some() => {
+            let key = request.idempotency_key.as_deref().unwrap_or_default();
+            if let Some(existing) = find_idempotent(docker, key, &request.name).await? {
+                return Ok(existing);
+            }
+            return Err(AppError::Validation(format!(
+                "A container named '{}' already exists",
+                request.name
+            )));
+        }
+        Ok(Err(e)) => return Err(e.into()),
+        Err(_) => {
+            return Err(AppError::Runtime(format!(
+                "Docker container create timed out after {:?}",
+                DOCKER_CREATE_TIMEOUT
+            )))
+        }
+    };
 
     // Auto-start if requested (default: true)
     // If start fails (e.g. OCI shim error for images without /bin/sh),
@@ -257,8 +925,74 @@ pub async fn create(
         .map(|detail| detail.info)
 }
 
+/// The container created for `key` within [`IDEMPOTENCY_WINDOW`], if any.
+/// Reusing a key for a different container name is an error.
+async fn find_idempotent(
+    docker: &Docker,
+    key: &str,
+    name: &str,
+) -> Result<Option<ContainerInfo>, AppError> {
+    let mut filters = HashMap::new();
+    filters.insert(
+        "label".to_string(),
+        vec![format!("{}={}", IDEMPOTENCY_KEY_LABEL, key)],
+    );
+    let summaries = tokio::time::timeout(
+        DOCKER_LIST_TIMEOUT,
+        docker.list_containers(Some(ListContainersOptions {
+            all: true,
+            filters,
+            ..Default::default()
+        })),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Runtime(format!(
+            "Docker container list timed out after {:?}",
+            DOCKER_LIST_TIMEOUT
+        ))
+    })??;
+    let now = chrono::Utc::now().timestamp();
+    let Some(summary) = summaries.into_iter().find(|s| {
+        s.created
+            .is_some_and(|created| within_idempotency_window(created, now))
+    }) else {
+        return Ok(None);
+    };
+    let existing = summary
+        .names
+        .as_ref()
+        .and_then(|names| names.first())
+        .map(|n| n.trim_start_matches('/').to_string())
+        .unwrap_or_default();
+    if existing != name {
+        return Err(AppError::Validation(format!(
+            "Idempotency key '{}' was already used to create container '{}'",
+            key, existing
+        )));
+    }
+    let id = summary.id.unwrap_or_default();
+    tracing::info!(
+        "Idempotency key {} replayed; returning container {}",
+        key,
+        id
+    );
+    inspect(docker, &id).await.map(|detail| Some(detail.info))
+}
+
+/// Whether a container created at `created` (Unix seconds) still answers
+/// replays of its idempotency key at `now`.
+fn within_idempotency_window(created: i64, now: i64) -> bool {
+    now.saturating_sub(created) <= IDEMPOTENCY_WINDOW.as_secs() as i64
+}
+
 /// Start a stopped container.
+///
+/// Its published host ports are checked first so a taken port fails with
+/// the process or container holding it (see [`crate::ports`]).
 pub async fn start(docker: &Docker, id: &str) -> Result<(), AppError> {
+    check_start_quotas(docker, id).await?;
+    crate::ports::ensure_available_for_container(docker, id).await?;
     match tokio::time::timeout(
         DOCKER_START_TIMEOUT,
         docker.start_container::<String>(id, None),
@@ -306,6 +1040,170 @@ pub async fn stop(docker: &Docker, id: &str, timeout: Option<u32>) -> Result<(),
     }
 }
 
+/// Restart a container, giving it `timeout` seconds (default 10) to stop
+/// before it is killed.
+pub async fn restart(docker: &Docker, id: &str, timeout: Option<u32>) -> Result<(), AppError> {
+    let grace = timeout.unwrap_or(10);
+    let options = Some(RestartContainerOptions { t: grace as isize });
+    // The daemon waits up to `grace` seconds for the stop, then starts again.
+    let limit = Duration::from_secs(u64::from(grace)) + DOCKER_START_TIMEOUT;
+    tokio::time::timeout(limit, docker.restart_container(id, options))
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Docker container restart timed out after {:?}",
+                limit
+            ))
+        })??;
+    Ok(())
+}
+
+/// Pause all processes in a running container.
+pub async fn pause(docker: &Docker, id: &str) -> Result<(), AppError> {
+    tokio::time::timeout(DOCKER_PAUSE_TIMEOUT, docker.pause_container(id))
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Docker container pause timed out after {:?}",
+                DOCKER_PAUSE_TIMEOUT
+            ))
+        })??;
+    Ok(())
+}
+
+/// Resume a paused container.
+pub async fn unpause(docker: &Docker, id: &str) -> Result<(), AppError> {
+    tokio::time::timeout(DOCKER_PAUSE_TIMEOUT, docker.unpause_container(id))
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Docker container unpause timed out after {:?}",
+                DOCKER_PAUSE_TIMEOUT
+            ))
+        })??;
+    Ok(())
+}
+
+/// Rename a container.
+pub async fn rename(docker: &Docker, id: &str, new_name: &str) -> Result<(), AppError> {
+    crate::validation::validate_container_name(new_name)?;
+    let options = RenameContainerOptions { name: new_name };
+    tokio::time::timeout(DOCKER_UPDATE_TIMEOUT, docker.rename_container(id, options))
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Docker container rename timed out after {:?}",
+                DOCKER_UPDATE_TIMEOUT
+            ))
+        })??;
+    Ok(())
+}
+
+/// Change CPU/memory limits of a container (running or stopped).
+///
+/// `memory_swap_mb` is Docker's memory-plus-swap limit in MB, `-1` for
+/// unlimited swap. Without it the current limit is kept; Docker then
+/// rejects a memory limit above it, which is reported before updating.
+///
+/// The `com.cratebay.*` limit labels are fixed at creation, so [`inspect`]
+/// reports the live HostConfig values after an update.
+pub async fn update_resources(
+    docker: &Docker,
+    id: &str,
+    cpu_cores: Option<u32>,
+    memory_mb: Option<u64>,
+    memory_swap_mb: Option<i64>,
+) -> Result<(), AppError> {
+    if cpu_cores.is_none() && memory_mb.is_none() && memory_swap_mb.is_none() {
+        return Err(AppError::Validation(
+            "Nothing to update: specify CPU cores, memory and/or memory+swap".to_string(),
+        ));
+    }
+    if let Some(cpu) = cpu_cores {
+        crate::validation::validate_cpu_cores(cpu)?;
+    }
+    if let Some(mem) = memory_mb {
+        crate::validation::validate_memory_mb(mem)?;
+    }
+    if memory_mb.is_some() || memory_swap_mb.is_some() {
+        let current = tokio::time::timeout(
+            DOCKER_INSPECT_TIMEOUT,
+            docker.inspect_container(id, None::<InspectContainerOptions>),
+        )
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Docker container inspect timed out after {:?}",
+                DOCKER_INSPECT_TIMEOUT
+            ))
+        })??
+        .host_config
+        .unwrap_or_default();
+        check_memory_swap(
+            memory_mb.map_or(current.memory, |m| Some((m * 1024 * 1024) as i64)),
+            memory_swap_mb.map_or(current.memory_swap, |s| {
+                Some(if s < 0 { s } else { s * 1024 * 1024 })
+            }),
+            memory_swap_mb.is_some(),
+        )?;
+    }
+    check_reservation_quotas(docker, Some(id), cpu_cores, memory_mb).await?;
+
+    let options = UpdateContainerOptions::<String> {
+        nano_cpus: cpu_cores.map(|c| (c as i64) * 1_000_000_000),
+        memory: memory_mb.map(|m| (m * 1024 * 1024) as i64),
+        memory_swap: memory_swap_mb.map(|s| if s < 0 { -1 } else { s * 1024 * 1024 }),
+        ..Default::default()
+    };
+    tokio::time::timeout(DOCKER_UPDATE_TIMEOUT, docker.update_container(id, options))
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Docker container update timed out after {:?}",
+                DOCKER_UPDATE_TIMEOUT
+            ))
+        })??;
+    Ok(())
+}
+
+/// Check a memory limit against a memory-plus-swap limit, both in bytes as
+/// Docker stores them (`None` or `0` unset, `-1` unlimited swap).
+/// `swap_given` says whether the swap limit is the caller's or the current
+/// one, for the message.
+fn check_memory_swap(
+    memory: Option<i64>,
+    memory_swap: Option<i64>,
+    swap_given: bool,
+) -> Result<(), AppError> {
+    const MB: i64 = 1024 * 1024;
+    match memory_swap {
+        Some(swap) if swap < -1 || (swap == 0 && swap_given) => Err(AppError::Validation(
+            "Memory+swap must be a size in MB, or -1 for unlimited swap".to_string(),
+        )),
+        Some(swap) if swap > 0 && memory.is_none_or(|m| m <= 0) => Err(AppError::Validation(
+            "Memory+swap needs a memory limit; set one as well".to_string(),
+        )),
+        Some(swap) if swap > 0 && memory.is_some_and(|m| m > swap) => {
+            let memory = memory.unwrap_or_default() / MB;
+            Err(AppError::Validation(if swap_given {
+                format!(
+                    "Memory+swap ({} MB) must be at least the memory limit ({} MB)",
+                    swap / MB,
+                    memory
+                )
+            } else {
+                format!(
+                    "Memory limit {} MB exceeds the container's memory+swap limit of {} MB; \
+                     set memory+swap as well (-1 for unlimited swap)",
+                    memory,
+                    swap / MB
+                )
+            }))
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Remove a container. Must be stopped first unless force=true.
 pub async fn delete(docker: &Docker, id: &str, force: bool) -> Result<(), AppError> {
     let options = Some(RemoveContainerOptions {
@@ -353,15 +1251,26 @@ pub async fn inspect(docker: &Docker, id: &str) -> Result<ContainerDetail, AppEr
 
     let labels = config.and_then(|c| c.labels.clone()).unwrap_or_default();
 
-    let cpu_cores = labels
-        .get("com.cratebay.cpu_cores")
-        .and_then(|v| v.parse().ok())
-        .or_else(|| host_config.and_then(|h| h.nano_cpus.map(|n| (n / 1_000_000_000) as u32)));
+    // Live HostConfig limits win over creation labels (they change on update).
+    let cpu_cores = host_config
+        .and_then(|h| h.nano_cpus)
+        .filter(|n| *n > 0)
+        .map(nano_cpus_to_cores)
+        .or_else(|| {
+            labels
+                .get("com.cratebay.cpu_cores")
+                .and_then(|v| v.parse().ok())
+        });
 
-    let memory_mb = labels
-        .get("com.cratebay.memory_mb")
-        .and_then(|v| v.parse().ok())
-        .or_else(|| host_config.and_then(|h| h.memory.map(|m| (m / 1024 / 1024) as u64)));
+    let memory_mb = host_config
+        .and_then(|h| h.memory)
+        .filter(|m| *m > 0)
+        .map(|m| (m / 1024 / 1024) as u64)
+        .or_else(|| {
+            labels
+                .get("com.cratebay.memory_mb")
+                .and_then(|v| v.parse().ok())
+        });
 
     let running = state.and_then(|s| s.running).unwrap_or(false);
     let status_str = state
@@ -428,6 +1337,67 @@ pub async fn inspect(docker: &Docker, id: &str) -> Result<ContainerDetail, AppEr
     })
 }
 
+/// Container ports tried, in order, when `open` is given no port.
+const WEB_PORTS: &[u16] = &[80, 443, 3000, 5173, 8000, 8080, 8443, 8888, 4200, 5000];
+
+/// Container ports served over HTTPS.
+const HTTPS_PORTS: &[u16] = &[443, 8443];
+
+/// Pick the published TCP port for a web UI. `wanted` matches a container
+/// port first, then a host port; without it the first of [`WEB_PORTS`]
+/// wins, then the lowest container port.
+fn select_web_port(ports: &[PortMapping], wanted: Option<u16>) -> Option<&PortMapping> {
+    let tcp = || {
+        ports
+            .iter()
+            .filter(|p| p.protocol.eq_ignore_ascii_case("tcp"))
+    };
+    match wanted {
+        Some(port) => tcp()
+            .find(|p| p.container_port == port)
+            .or_else(|| tcp().find(|p| p.host_port == port)),
+        None => WEB_PORTS
+            .iter()
+            .find_map(|web| tcp().find(|p| p.container_port == *web))
+            .or_else(|| tcp().min_by_key(|p| p.container_port)),
+    }
+}
+
+/// Host URL of a running container's web UI, e.g. `http://localhost:8080`.
+///
+/// `port` selects a container (or host) port; otherwise a well-known web
+/// port is preferred. Fails with `NotFound` when nothing suitable is
+/// published.
+pub async fn web_url(docker: &Docker, id: &str, port: Option<u16>) -> Result<String, AppError> {
+    let detail = inspect(docker, id).await?;
+    if !detail.state.running {
+        return Err(AppError::Validation(format!(
+            "Container '{}' is not running",
+            id
+        )));
+    }
+    // Inspect does not report ports; the list entry does.
+    let ports = list(docker, false, None)
+        .await?
+        .into_iter()
+        .find(|c| c.id == detail.info.id)
+        .map(|c| c.ports)
+        .unwrap_or_default();
+    let mapping = select_web_port(&ports, port).ok_or_else(|| AppError::NotFound {
+        entity: "published port".to_string(),
+        id: match port {
+            Some(port) => format!("{}/tcp on {}", port, id),
+            None => format!("any TCP port on {}", id),
+        },
+    })?;
+    let scheme = if HTTPS_PORTS.contains(&mapping.container_port) {
+        "https"
+    } else {
+        "http"
+    };
+    Ok(format!("{}://localhost:{}", scheme, mapping.host_port))
+}
+
 /// Get real-time resource usage for a container.
 pub async fn stats(docker: &Docker, id: &str) -> Result<ContainerStats, AppError> {
     let mut stream = docker.stats(
@@ -498,6 +1468,48 @@ pub async fn stats(docker: &Docker, id: &str) -> Result<ContainerStats, AppError
     })
 }
 
+/// List processes running in a container (`docker top`).
+pub async fn top(docker: &Docker, id: &str) -> Result<Vec<ContainerProcess>, AppError> {
+    let response =
+        tokio::time::timeout(DOCKER_TOP_TIMEOUT, docker.top_processes::<String>(id, None))
+            .await
+            .map_err(|_| {
+                AppError::Runtime(format!(
+                    "Docker container top timed out after {:?}",
+                    DOCKER_TOP_TIMEOUT
+                ))
+            })??;
+
+    Ok(parse_top(
+        &response.titles.unwrap_or_default(),
+        response.processes.unwrap_or_default(),
+    ))
+}
+
+/// Map `ps` rows onto [`ContainerProcess`] by column title, so both the
+/// default `ps -ef` layout and `ps aux`-style output are understood.
+fn parse_top(titles: &[String], rows: Vec<Vec<String>>) -> Vec<ContainerProcess> {
+    let column = |names: &[&str]| titles.iter().position(|t| names.contains(&t.as_str()));
+    let pid = column(&["PID"]);
+    let ppid = column(&["PPID"]);
+    let user = column(&["UID", "USER"]);
+    let cpu = column(&["%CPU", "C"]);
+    let command = column(&["CMD", "COMMAND"]);
+
+    rows.into_iter()
+        .map(|row| {
+            let field = |idx: Option<usize>| idx.and_then(|i| row.get(i)).map(|v| v.trim());
+            ContainerProcess {
+                pid: field(pid).and_then(|v| v.parse().ok()).unwrap_or_default(),
+                ppid: field(ppid).and_then(|v| v.parse().ok()),
+                user: field(user).unwrap_or_default().to_string(),
+                cpu_percent: field(cpu).and_then(|v| v.parse().ok()),
+                command: field(command).unwrap_or_default().to_string(),
+            }
+        })
+        .collect()
+}
+
 /// Execute a command inside a running container and return the complete result.
 pub async fn exec(
     docker: &Docker,
@@ -575,6 +1587,237 @@ pub async fn exec(
     })
 }
 
+/// Run a command on the Docker host (the built-in runtime VM) and capture
+/// its output.
+///
+/// The runtime image has no sshd or guest agent, so this starts a
+/// short-lived privileged helper container in the host PID namespace and
+/// enters PID 1's namespaces with `nsenter`. The helper is always removed.
+pub async fn runtime_exec(
+    docker: &Docker,
+    cmd: Vec<String>,
+    timeout: Option<Duration>,
+) -> Result<ExecResult, AppError> {
+    if cmd.is_empty() {
+        return Err(AppError::Validation(
+            "Command must not be empty".to_string(),
+        ));
+    }
+    let timeout = timeout.unwrap_or(DOCKER_RUNTIME_EXEC_TIMEOUT);
+    ensure_image(docker, RUNTIME_EXEC_IMAGE).await?;
+    let config = runtime_helper_config(host_namespace_cmd(cmd));
+    run_helper(docker, "runtime-exec", config, None, timeout).await
+}
+
+/// Prefix `cmd` with `nsenter` into PID 1's namespaces, so a helper from
+/// [`runtime_helper_config`] runs it on the Docker host itself.
+pub(crate) fn host_namespace_cmd(cmd: Vec<String>) -> Vec<String> {
+    let mut nsenter: Vec<String> = ["nsenter", "-t", "1", "-m", "-u", "-i", "-n", "-p", "--"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    nsenter.extend(cmd);
+    nsenter
+}
+
+/// Privileged helper container in the host PID and network namespaces.
+pub(crate) fn runtime_helper_config(cmd: Vec<String>) -> Config<String> {
+    Config {
+        image: Some(RUNTIME_EXEC_IMAGE.to_string()),
+        cmd: Some(cmd),
+        host_config: Some(bollard::models::HostConfig {
+            privileged: Some(true),
+            pid_mode: Some("host".to_string()),
+            network_mode: Some("host".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// Start a long-lived runtime helper (see [`runtime_helper_config`]) that
+/// idles until removed, for callers that exec into it repeatedly. Returns
+/// the container name; the caller removes it with [`delete`].
+pub(crate) async fn start_runtime_helper(docker: &Docker, kind: &str) -> Result<String, AppError> {
+    let name = format!(
+        "cratebay-{}-{}",
+        kind,
+        &uuid::Uuid::new_v4().simple().to_string()[..12]
+    );
+    create_runtime_helper(docker, &name, kind, false).await?;
+    Ok(name)
+}
+
+/// Make sure the idle runtime helper `cratebay-<kind>` is running and
+/// return its name. Unlike [`start_runtime_helper`] it is shared: every
+/// caller (and process) execs into the same container, which goes away
+/// when it stops, e.g. with the runtime VM.
+pub(crate) async fn shared_runtime_helper(docker: &Docker, kind: &str) -> Result<String, AppError> {
+    let name = format!("cratebay-{}", kind);
+    let state = tokio::time::timeout(
+        DOCKER_INSPECT_TIMEOUT,
+        docker.inspect_container(&name, None),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Runtime(format!(
+            "Docker container inspect timed out after {:?}",
+            DOCKER_INSPECT_TIMEOUT
+        ))
+    })?;
+    match state {
+        Ok(c) if c.state.as_ref().and_then(|s| s.running).unwrap_or(false) => return Ok(name),
+        // Left over from an engine restart; start over.
+        Ok(_) => delete(docker, &name, true).await?,
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => {}
+        Err(e) => return Err(e.into()),
+    }
+    match create_runtime_helper(docker, &name, kind, true).await {
+        // Another caller created it first.
+        Err(AppError::Docker(bollard::errors::Error::DockerResponseServerError {
+            status_code: 409,
+            ..
+        })) => Ok(name),
+        result => result.map(|_| name),
+    }
+}
+
+async fn create_runtime_helper(
+    docker: &Docker,
+    name: &str,
+    kind: &str,
+    auto_remove: bool,
+) -> Result<(), AppError> {
+    ensure_image(docker, RUNTIME_EXEC_IMAGE).await?;
+    let mut config = runtime_helper_config(vec![
+        "tail".to_string(),
+        "-f".to_string(),
+        "/dev/null".to_string(),
+    ]);
+    config.labels = Some(HashMap::from([(
+        INTERNAL_LABEL.to_string(),
+        kind.to_string(),
+    )]));
+    if let Some(host_config) = config.host_config.as_mut() {
+        host_config.auto_remove = Some(auto_remove);
+    }
+    let options = CreateContainerOptions {
+        name,
+        platform: None,
+    };
+    tokio::time::timeout(
+        DOCKER_CREATE_TIMEOUT,
+        docker.create_container(Some(options), config),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Runtime(format!(
+            "Docker container create timed out after {:?}",
+            DOCKER_CREATE_TIMEOUT
+        ))
+    })??;
+    if let Err(e) = docker.start_container::<String>(name, None).await {
+        let _ = delete(docker, name, true).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Create a throwaway helper container from `config`, run it to completion
+/// and remove it. `kind` names the helper (`cratebay-<kind>-<id>`) and is
+/// stored in the [`INTERNAL_LABEL`] label.
+pub(crate) async fn run_helper(
+    docker: &Docker,
+    kind: &str,
+    mut config: Config<String>,
+    platform: Option<&str>,
+    timeout: Duration,
+) -> Result<ExecResult, AppError> {
+    let name = format!(
+        "cratebay-{}-{}",
+        kind,
+        &uuid::Uuid::new_v4().simple().to_string()[..12]
+    );
+    config.labels = Some(HashMap::from([(
+        INTERNAL_LABEL.to_string(),
+        kind.to_string(),
+    )]));
+    let options = CreateContainerOptions {
+        name: name.as_str(),
+        platform,
+    };
+    tokio::time::timeout(
+        DOCKER_CREATE_TIMEOUT,
+        docker.create_container(Some(options), config),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Runtime(format!(
+            "Docker container create timed out after {:?}",
+            DOCKER_CREATE_TIMEOUT
+        ))
+    })??;
+
+    let result = run_helper_to_completion(docker, &name, timeout).await;
+
+    let remove = RemoveContainerOptions {
+        force: true,
+        ..Default::default()
+    };
+    if let Err(e) = docker.remove_container(&name, Some(remove)).await {
+        tracing::warn!("Failed to remove {} helper {}: {}", kind, name, e);
+    }
+    result
+}
+
+/// Start a created container, wait for it to exit and collect its output.
+async fn run_helper_to_completion(
+    docker: &Docker,
+    name: &str,
+    timeout: Duration,
+) -> Result<ExecResult, AppError> {
+    tokio::time::timeout(timeout, async {
+        docker.start_container::<String>(name, None).await?;
+
+        let exit_code = match docker.wait_container::<String>(name, None).next().await {
+            Some(Ok(response)) => response.status_code,
+            Some(Err(bollard::errors::Error::DockerContainerWaitError { code, .. })) => code,
+            Some(Err(e)) => return Err(e.into()),
+            None => -1,
+        };
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        let log_options = LogsOptions::<String> {
+            stdout: true,
+            stderr: true,
+            ..Default::default()
+        };
+        let mut stream = docker.logs(name, Some(log_options));
+        while let Some(chunk) = stream.next().await {
+            match chunk? {
+                bollard::container::LogOutput::StdOut { message } => {
+                    stdout.push_str(&String::from_utf8_lossy(&message));
+                }
+                bollard::container::LogOutput::StdErr { message } => {
+                    stderr.push_str(&String::from_utf8_lossy(&message));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(ExecResult {
+            exit_code,
+            stdout,
+            stderr,
+        })
+    })
+    .await
+    .map_err(|_| AppError::Runtime(format!("Helper container timed out after {:?}", timeout)))?
+}
+
 /// Execute a command inside a running container with a custom timeout.
 ///
 /// Unlike [`exec`], this function allows the caller to specify a total timeout
@@ -820,6 +2063,57 @@ pub async fn exec_stream(
     Ok(exit_code)
 }
 
+/// Object types forwarded by [`watch_events`].
+const WATCHED_EVENT_TYPES: &[&str] = &["container", "image", "network", "volume"];
+
+/// Stream Docker container/image/network/volume events to `on_event`.
+/// Events of CrateBay's helper containers ([`INTERNAL_LABEL`]), including
+/// execs into them, are dropped.
+///
+/// Runs until the event stream ends (daemon shutdown or connection loss),
+/// which is reported as an error so callers can reconnect.
+pub async fn watch_events(
+    docker: &Docker,
+    on_event: impl Fn(DockerEvent) + Send + 'static,
+) -> Result<(), AppError> {
+    let mut filters = HashMap::new();
+    filters.insert(
+        "type".to_string(),
+        WATCHED_EVENT_TYPES.iter().map(|t| t.to_string()).collect(),
+    );
+    let options = bollard::system::EventsOptions::<String> {
+        filters,
+        ..Default::default()
+    };
+
+    let mut stream = docker.events(Some(options));
+    while let Some(item) = stream.next().await {
+        let message = item?;
+        let kind = message.typ.map(|t| t.to_string()).unwrap_or_default();
+        if !WATCHED_EVENT_TYPES.contains(&kind.as_str()) {
+            continue;
+        }
+        let actor = message.actor.unwrap_or_default();
+        let attributes = actor.attributes.unwrap_or_default();
+        // Container events carry the container's labels as attributes.
+        if attributes.contains_key(INTERNAL_LABEL) {
+            continue;
+        }
+        on_event(DockerEvent {
+            kind,
+            action: message.action.unwrap_or_default(),
+            id: actor.id.unwrap_or_default(),
+            name: attributes.get("name").cloned(),
+            time: message.time.unwrap_or_default(),
+            attributes,
+        });
+    }
+
+    Err(AppError::Runtime(
+        "Docker event stream closed by the daemon".to_string(),
+    ))
+}
+
 /// Get container logs.
 pub async fn logs(
     docker: &Docker,
@@ -957,48 +2251,402 @@ pub async fn image_load_from_tar(docker: &Docker, tar_path: &str) -> Result<Vec<
     use bollard::image::ImportImageOptions;
     use bytes::Bytes;
 
-    let path = std::path::Path::new(tar_path);
-    if !path.exists() {
-        return Err(AppError::Validation(format!(
-            "Image tar file not found: {}",
-            tar_path
-        )));
+    let path = std::path::Path::new(tar_path);
+    if !path.exists() {
+        return Err(AppError::Validation(format!(
+            "Image tar file not found: {}",
+            tar_path
+        )));
+    }
+
+    // Read the file into memory
+    let file_bytes = tokio::fs::read(tar_path).await.map_err(|e| {
+        AppError::Runtime(format!(
+            "Failed to read image tar file '{}': {}",
+            tar_path, e
+        ))
+    })?;
+
+    let options = ImportImageOptions { quiet: false };
+
+    let mut stream = docker.import_image(options, Bytes::from(file_bytes), None);
+    let mut loaded_images = Vec::new();
+
+    while let Some(result) = stream.next().await {
+        match result {
+            Ok(info) => {
+                if let Some(stream_msg) = info.stream {
+                    let trimmed = stream_msg.trim();
+                    if !trimmed.is_empty() {
+                        tracing::info!("docker load: {}", trimmed);
+                    }
+                    // Parse "Loaded image: name:tag" messages
+                    if let Some(name) = trimmed.strip_prefix("Loaded image: ") {
+                        loaded_images.push(name.to_string());
+                    }
+                }
+            }
+            Err(e) => {
+                return Err(AppError::Docker(e));
+            }
+        }
+    }
+
+    Ok(loaded_images)
+}
+
+/// Archives larger than this are refused for engines [`import_over_bollard`]
+/// has to serve, since bollard sends the request body from memory.
+const IMPORT_IN_MEMORY_LIMIT: u64 = 1 << 30;
+
+/// Create an image from a filesystem tarball such as one written by
+/// [`export`] (equivalent to `docker import`). Gzip archives are detected by
+/// the daemon. `changes` are validated like [`commit`]'s. Returns the new
+/// image ID.
+///
+/// The archive is streamed to Unix socket and plain TCP engines (the
+/// built-in runtime included). bollard cannot stream this request, so SSH
+/// and TLS engines get it from memory and archives over 1 GiB are refused
+/// for them.
+pub async fn image_import(
+    docker: &Docker,
+    tar_path: &str,
+    reference: Option<&str>,
+    changes: &[String],
+) -> Result<String, AppError> {
+    let path = std::path::Path::new(tar_path);
+    if !path.is_file() {
+        return Err(AppError::Validation(format!(
+            "Archive not found: {}",
+            tar_path
+        )));
+    }
+    for change in changes {
+        validate_commit_change(change)?;
+    }
+    let (repo, tag) = match reference.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(r) if r.contains('@') => {
+            return Err(AppError::Validation(
+                "Digest references are not supported for import".to_string(),
+            ))
+        }
+        Some(r) => split_repo_and_tag(r),
+        None => (String::new(), String::new()),
+    };
+
+    let changes: Vec<&str> = changes.iter().map(|c| c.trim()).collect();
+    let image_id = if crate::docker::raw_requests_supported() {
+        import_streamed(path, &repo, &tag, &changes).await?
+    } else {
+        import_over_bollard(docker, path, repo, tag, changes).await?
+    };
+    image_id.ok_or_else(|| {
+        AppError::Runtime(format!(
+            "Docker did not report an image ID for {}",
+            tar_path
+        ))
+    })
+}
+
+async fn import_streamed(
+    path: &std::path::Path,
+    repo: &str,
+    tag: &str,
+    changes: &[&str],
+) -> Result<Option<String>, AppError> {
+    let mut query = vec![("fromSrc", "-"), ("repo", repo), ("tag", tag)];
+    query.extend(changes.iter().map(|c| ("changes", *c)));
+    let query = serde_urlencoded::to_string(&query)
+        .map_err(|e| AppError::Runtime(format!("Invalid import options: {}", e)))?;
+    let uri = format!("/images/create?{}", query);
+
+    let file = tokio::fs::File::open(path).await?;
+    let (status, body) =
+        crate::docker::engine_upload(hyper::Method::POST, &uri, "application/x-tar", file).await?;
+    if !status.is_success() {
+        return Err(AppError::Runtime(format!(
+            "Image import failed ({}): {}",
+            status,
+            crate::docker::engine_error_message(&body)
+        )));
+    }
+    imported_image_id(&body)
+}
+
+/// The image ID in `POST /images/create` progress messages (one JSON
+/// object per line); an `error` message fails the import.
+fn imported_image_id(body: &[u8]) -> Result<Option<String>, AppError> {
+    let mut image_id = None;
+    for line in body.split(|b| *b == b'\n').filter(|l| !l.is_empty()) {
+        let message: serde_json::Value = serde_json::from_slice(line)?;
+        if let Some(error) = message["error"].as_str() {
+            return Err(AppError::Runtime(format!("Image import failed: {}", error)));
+        }
+        if let Some(status) = message["status"].as_str() {
+            if status.starts_with("sha256:") {
+                image_id = Some(status.to_string());
+            }
+        }
+    }
+    Ok(image_id)
+}
+
+async fn import_over_bollard(
+    docker: &Docker,
+    path: &std::path::Path,
+    repo: String,
+    tag: String,
+    changes: Vec<&str>,
+) -> Result<Option<String>, AppError> {
+    use bollard::image::CreateImageOptions;
+    use bytes::Bytes;
+
+    let size = tokio::fs::metadata(path).await?.len();
+    if size > IMPORT_IN_MEMORY_LIMIT {
+        return Err(AppError::Validation(format!(
+            "{} is {}; archives over {} cannot be imported into {}, run `docker import` against it instead",
+            path.display(),
+            format_bytes_human(size),
+            format_bytes_human(IMPORT_IN_MEMORY_LIMIT),
+            crate::docker::selected_engine()
+        )));
+    }
+    let file_bytes = tokio::fs::read(path).await?;
+    let options = CreateImageOptions {
+        from_src: "-".to_string(),
+        repo,
+        tag,
+        changes,
+        ..Default::default()
+    };
+    let mut stream = docker.create_image(Some(options), Some(Bytes::from(file_bytes)), None);
+
+    let mut image_id = None;
+    while let Some(info) = stream.next().await {
+        if let Some(status) = info?.status {
+            if status.starts_with("sha256:") {
+                image_id = Some(status);
+            }
+        }
+    }
+    Ok(image_id)
+}
+
+/// Progress callback for image save; receives the number of bytes written so far.
+pub type SaveProgressCallback = Arc<dyn Fn(u64) + Send + Sync + 'static>;
+
+/// Save one or more images to a tar archive (equivalent to `docker save`).
+///
+/// When `gzip` is set the archive is gzip-compressed; the result can be read
+/// back with [`image_load_from_tar`]. The archive is written to a temporary
+/// file next to `output_path` and renamed on success. Returns the archive
+/// size in bytes.
+pub async fn image_save(
+    docker: &Docker,
+    images: &[String],
+    output_path: &str,
+    gzip: bool,
+    on_progress: Option<SaveProgressCallback>,
+) -> Result<u64, AppError> {
+    if images.is_empty() || images.iter().any(|i| i.trim().is_empty()) {
+        return Err(AppError::Validation(
+            "At least one image reference is required".to_string(),
+        ));
+    }
+
+    let names: Vec<&str> = images.iter().map(|i| i.trim()).collect();
+    let size = write_archive(
+        docker.export_images(&names),
+        std::path::Path::new(output_path),
+        gzip,
+        on_progress,
+    )
+    .await?;
+    tracing::info!(
+        "Saved {} image(s) to {} ({})",
+        names.len(),
+        output_path,
+        format_bytes_human(size)
+    );
+    Ok(size)
+}
+
+/// Export a container's filesystem as a tar archive (equivalent to
+/// `docker export`). Written like [`image_save`]; the archive can be turned
+/// back into an image with [`image_import`]. Returns the archive size in
+/// bytes.
+pub async fn export(
+    docker: &Docker,
+    id: &str,
+    output_path: &str,
+    gzip: bool,
+    on_progress: Option<SaveProgressCallback>,
+) -> Result<u64, AppError> {
+    let size = write_archive(
+        docker.export_container(id),
+        std::path::Path::new(output_path),
+        gzip,
+        on_progress,
+    )
+    .await?;
+    tracing::info!(
+        "Exported container {} to {} ({})",
+        id,
+        output_path,
+        format_bytes_human(size)
+    );
+    Ok(size)
+}
+
+/// Chunks queued between the Docker stream and the archive writer.
+const ARCHIVE_WRITE_QUEUE: usize = 16;
+
+/// Message to the blocking archive writer.
+enum ArchiveChunk {
+    Data(bytes::Bytes),
+    /// The stream ended; finish and rename the file.
+    Done,
+}
+
+/// Stream a Docker archive to `output` via a `.partial` file that is renamed
+/// on success and removed on failure or when the future is dropped.
+///
+/// File writes and gzip compression run on a blocking thread, fed through a
+/// bounded queue so a slow disk slows the download instead of buffering it.
+async fn write_archive(
+    stream: impl futures_util::Stream<Item = Result<bytes::Bytes, bollard::errors::Error>>,
+    output: &std::path::Path,
+    gzip: bool,
+    on_progress: Option<SaveProgressCallback>,
+) -> Result<u64, AppError> {
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if !tokio::fs::metadata(parent).await.is_ok_and(|m| m.is_dir()) {
+            return Err(AppError::Validation(format!(
+                "Output directory does not exist: {}",
+                parent.display()
+            )));
+        }
+    }
+
+    let partial = output.with_extension("partial");
+    let file = tokio::fs::File::create(&partial).await?.into_std().await;
+    // Removes the file at once if this future is dropped; the writer then
+    // stops on the closed queue.
+    let mut cleanup = PartialFileGuard(Some(&partial));
+
+    let (tx, rx) = tokio::sync::mpsc::channel(ARCHIVE_WRITE_QUEUE);
+    let (partial_path, output_path) = (partial.clone(), output.to_path_buf());
+    let writer = tokio::task::spawn_blocking(move || {
+        write_archive_file(rx, file, &partial_path, &output_path, gzip)
+    });
+
+    let mut stream = std::pin::pin!(stream);
+    let mut written: u64 = 0;
+    let result: Result<(), AppError> = async {
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            let len = chunk.len() as u64;
+            // A closed queue means the writer failed; its error is returned below.
+            if tx.send(ArchiveChunk::Data(chunk)).await.is_err() {
+                return Ok(());
+            }
+            written += len;
+            if let Some(ref cb) = on_progress {
+                cb(written);
+            }
+        }
+        let _ = tx.send(ArchiveChunk::Done).await;
+        Ok(())
     }
+    .await;
+    // Without `Done` the writer removes the partial file.
+    drop(tx);
 
-    // Read the file into memory
-    let file_bytes = tokio::fs::read(tar_path).await.map_err(|e| {
-        AppError::Runtime(format!(
-            "Failed to read image tar file '{}': {}",
-            tar_path, e
+    let written = writer
+        .await
+        .map_err(|e| AppError::Runtime(format!("Task join error: {}", e)))?;
+    result?;
+    let size = written?;
+    cleanup.0 = None;
+    Ok(size)
+}
+
+/// Blocking half of [`write_archive`]: write `chunks` to `file` (at
+/// `partial`) and rename it to `output` once [`ArchiveChunk::Done`] arrives.
+/// Returns the file size.
+fn write_archive_file(
+    mut chunks: tokio::sync::mpsc::Receiver<ArchiveChunk>,
+    file: std::fs::File,
+    partial: &std::path::Path,
+    output: &std::path::Path,
+    gzip: bool,
+) -> Result<u64, AppError> {
+    // Declared before the writer so the file is closed before removal
+    // (Windows cannot remove it while open).
+    let mut cleanup = PartialFileGuard(Some(partial));
+
+    let file = std::io::BufWriter::new(file);
+    let mut writer = if gzip {
+        SaveWriter::Gzip(flate2::write::GzEncoder::new(
+            file,
+            flate2::Compression::default(),
         ))
-    })?;
+    } else {
+        SaveWriter::Plain(file)
+    };
+    loop {
+        match chunks.blocking_recv() {
+            Some(ArchiveChunk::Data(chunk)) => writer.write_all(&chunk)?,
+            Some(ArchiveChunk::Done) => break,
+            None => {
+                return Err(AppError::Cancelled(
+                    "The archive download stopped before it finished".to_string(),
+                ))
+            }
+        }
+    }
+    writer.finish()?;
+    std::fs::rename(partial, output)?;
+    cleanup.0 = None;
+    Ok(std::fs::metadata(output)?.len())
+}
 
-    let options = ImportImageOptions { quiet: false };
+/// Removes an unfinished `.partial` archive when dropped while still armed.
+struct PartialFileGuard<'a>(Option<&'a std::path::Path>);
 
-    let mut stream = docker.import_image(options, Bytes::from(file_bytes), None);
-    let mut loaded_images = Vec::new();
+impl Drop for PartialFileGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(path) = self.0 {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
 
-    while let Some(result) = stream.next().await {
-        match result {
-            Ok(info) => {
-                if let Some(stream_msg) = info.stream {
-                    let trimmed = stream_msg.trim();
-                    if !trimmed.is_empty() {
-                        tracing::info!("docker load: {}", trimmed);
-                    }
-                    // Parse "Loaded image: name:tag" messages
-                    if let Some(name) = trimmed.strip_prefix("Loaded image: ") {
-                        loaded_images.push(name.to_string());
-                    }
-                }
-            }
-            Err(e) => {
-                return Err(AppError::Docker(e));
-            }
+/// Output sink for [`write_archive`].
+enum SaveWriter {
+    Plain(std::io::BufWriter<std::fs::File>),
+    Gzip(flate2::write::GzEncoder<std::io::BufWriter<std::fs::File>>),
+}
+
+impl SaveWriter {
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        use std::io::Write;
+        match self {
+            SaveWriter::Plain(w) => w.write_all(buf),
+            SaveWriter::Gzip(w) => w.write_all(buf),
         }
     }
 
-    Ok(loaded_images)
+    /// Flush buffers (and write the gzip trailer).
+    fn finish(self) -> Result<(), AppError> {
+        use std::io::Write;
+        let mut file = match self {
+            SaveWriter::Plain(w) => w,
+            SaveWriter::Gzip(w) => w.finish()?,
+        };
+        file.flush()?;
+        Ok(())
+    }
 }
 
 /// List local Docker images.
@@ -1144,27 +2792,7 @@ async fn dockerhub_search(
 ) -> Result<Vec<ImageSearchResult>, AppError> {
     let page_size: u64 = limit.unwrap_or(25).clamp(1, 100);
 
-    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(8));
-    if let Ok(raw_proxy) = std::env::var("CRATEBAY_RUNTIME_HTTP_PROXY") {
-        let proxy = raw_proxy.trim();
-        if !proxy.is_empty() {
-            let proxy_url = if proxy.contains("://") {
-                proxy.to_string()
-            } else {
-                format!("http://{}", proxy)
-            };
-            builder = builder.proxy(reqwest::Proxy::all(&proxy_url).map_err(|e| {
-                AppError::Runtime(format!(
-                    "Invalid CRATEBAY_RUNTIME_HTTP_PROXY '{}': {}",
-                    proxy, e
-                ))
-            })?);
-        }
-    }
-
-    let client = builder
-        .build()
-        .map_err(|e| AppError::Runtime(format!("Failed to build HTTP client: {}", e)))?;
+    let client = crate::registry::registry_http_client("docker.io", Duration::from_secs(8))?;
 
     let resp = client
         .get("https://hub.docker.com/v2/search/repositories/")
@@ -1232,7 +2860,7 @@ async fn dockerhub_search(
     Ok(mapped)
 }
 
-fn format_reqwest_error(err: &reqwest::Error) -> String {
+pub(crate) fn format_reqwest_error(err: &reqwest::Error) -> String {
     let mut message = err.to_string();
     let mut source = err.source();
     while let Some(next) = source {
@@ -1280,6 +2908,58 @@ pub async fn image_inspect(docker: &Docker, id: &str) -> Result<ImageInspectInfo
     })
 }
 
+/// Layers of a local image, newest first, with their unpacked sizes.
+pub async fn image_history(docker: &Docker, reference: &str) -> Result<ImageLayers, AppError> {
+    let history = tokio::time::timeout(
+        DOCKER_IMAGE_INSPECT_TIMEOUT,
+        docker.image_history(reference),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Runtime(format!(
+            "Docker image history timed out after {:?}",
+            DOCKER_IMAGE_INSPECT_TIMEOUT
+        ))
+    })??;
+
+    let layers: Vec<ImageLayer> = history
+        .into_iter()
+        .map(|item| {
+            let size = item.size.max(0) as u64;
+            ImageLayer {
+                digest: (item.id != "<missing>" && !item.id.is_empty()).then_some(item.id),
+                created_by: clean_created_by(&item.created_by),
+                created: chrono::DateTime::from_timestamp(item.created, 0).map(|t| t.to_rfc3339()),
+                size,
+                empty: size == 0,
+                comment: (!item.comment.is_empty()).then_some(item.comment),
+            }
+        })
+        .collect();
+    Ok(ImageLayers {
+        reference: reference.to_string(),
+        source: "local".to_string(),
+        platform: None,
+        compressed: false,
+        total_size: layers.iter().map(|l| l.size).sum(),
+        layers,
+    })
+}
+
+/// Readable form of a history `created_by`: legacy builder entries
+/// (`/bin/sh -c #(nop)  CMD ["sh"]`, `/bin/sh -c apt-get ...`) become the
+/// Dockerfile instruction; BuildKit entries already are one.
+pub(crate) fn clean_created_by(created_by: &str) -> String {
+    let created_by = created_by.trim();
+    if let Some(rest) = created_by.strip_prefix("/bin/sh -c #(nop)") {
+        return rest.trim().to_string();
+    }
+    match created_by.strip_prefix("/bin/sh -c ") {
+        Some(command) => format!("RUN {}", command.trim()),
+        None => created_by.to_string(),
+    }
+}
+
 /// Remove a local image.
 pub async fn image_remove(docker: &Docker, id: &str, force: bool) -> Result<(), AppError> {
     let options = Some(RemoveImageOptions {
@@ -1329,6 +3009,164 @@ pub async fn image_tag(docker: &Docker, source: &str, target: &str) -> Result<()
     Ok(())
 }
 
+/// Commit a container's filesystem and config to a new image. Returns the
+/// new image ID.
+pub async fn commit(
+    docker: &Docker,
+    id: &str,
+    request: &ContainerCommitRequest,
+) -> Result<String, AppError> {
+    let target = request.target.trim();
+    if target.is_empty() {
+        return Err(AppError::Validation(
+            "Commit target must not be empty".to_string(),
+        ));
+    }
+    if target.contains('@') {
+        return Err(AppError::Validation(
+            "Digest targets are not supported for commit".to_string(),
+        ));
+    }
+    for change in &request.changes {
+        validate_commit_change(change)?;
+    }
+
+    let (repo, tag) = split_repo_and_tag(target);
+    let options = CommitContainerOptions {
+        container: id.to_string(),
+        repo,
+        tag,
+        comment: request.message.clone().unwrap_or_default(),
+        author: request.author.clone().unwrap_or_default(),
+        pause: request.pause.unwrap_or(true),
+        changes: (!request.changes.is_empty()).then(|| request.changes.join("\n")),
+    };
+    let commit = tokio::time::timeout(
+        DOCKER_COMMIT_TIMEOUT,
+        docker.commit_container(options, Config::<String>::default()),
+    )
+    .await
+    .map_err(|_| {
+        AppError::Runtime(format!(
+            "Docker commit timed out after {:?}",
+            DOCKER_COMMIT_TIMEOUT
+        ))
+    })??;
+    Ok(commit.id.unwrap_or_default())
+}
+
+/// Check one `--change` line: a single Dockerfile instruction Docker allows
+/// on commit.
+fn validate_commit_change(change: &str) -> Result<(), AppError> {
+    let change = change.trim();
+    if change.contains('\n') {
+        return Err(AppError::Validation(format!(
+            "Commit change must be a single line: {:?}",
+            change
+        )));
+    }
+    let instruction = change
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    if !COMMIT_CHANGE_INSTRUCTIONS.contains(&instruction.as_str())
+        || change.len() == instruction.len()
+    {
+        return Err(AppError::Validation(format!(
+            "Unsupported commit change '{}' (expected one of {} followed by arguments)",
+            change,
+            COMMIT_CHANGE_INSTRUCTIONS.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// Output callback for image builds; receives each build log line.
+pub type BuildOutputCallback = Arc<dyn Fn(String) + Send + Sync + 'static>;
+
+/// Build an image from a Dockerfile (equivalent to `docker build`).
+///
+/// The context directory is packed respecting `.dockerignore`. Build log
+/// lines are forwarded to `on_output`. Returns the built image ID. No overall
+/// timeout is applied: build steps may legitimately run for a long time.
+pub async fn image_build(
+    docker: &Docker,
+    request: &ImageBuildRequest,
+    on_output: Option<BuildOutputCallback>,
+) -> Result<String, AppError> {
+    use bollard::image::BuildImageOptions;
+
+    for tag in &request.tags {
+        if tag.trim().is_empty() || tag.contains('@') {
+            return Err(AppError::Validation(format!(
+                "Invalid image tag: '{}'",
+                tag
+            )));
+        }
+    }
+
+    let context_dir = std::path::PathBuf::from(&request.context_dir);
+    let dockerfile = request.dockerfile.as_ref().map(std::path::PathBuf::from);
+    let context = tokio::task::spawn_blocking(move || {
+        crate::build_context::create_build_context(&context_dir, dockerfile.as_deref())
+    })
+    .await
+    .map_err(|e| AppError::Runtime(format!("Build context task failed: {}", e)))??;
+
+    tracing::info!(
+        "Building image from {} ({} bytes context, tags: {:?})",
+        request.context_dir,
+        context.tar.len(),
+        request.tags
+    );
+
+    let options = BuildImageOptions {
+        dockerfile: context.dockerfile.clone(),
+        t: request.tags.first().cloned().unwrap_or_default(),
+        buildargs: request.build_args.clone(),
+        target: request.target.clone().unwrap_or_default(),
+        nocache: request.no_cache,
+        pull: request.pull,
+        rm: true,
+        ..Default::default()
+    };
+
+    let mut stream = docker.build_image(options, None, Some(context.tar.into()));
+    let mut image_id = None;
+
+    while let Some(result) = stream.next().await {
+        let info = result?;
+        if let Some(error) = info.error {
+            return Err(AppError::Runtime(format!(
+                "Image build failed: {}",
+                error.trim()
+            )));
+        }
+        if let Some(id) = info.aux.and_then(|aux| aux.id) {
+            image_id = Some(id);
+        }
+        if let Some(ref cb) = on_output {
+            let line = info.stream.or(info.status).unwrap_or_default();
+            let line = line.trim_end();
+            if !line.is_empty() {
+                cb(line.to_string());
+            }
+        }
+    }
+
+    let image_id = image_id.ok_or_else(|| {
+        AppError::Runtime("Image build finished without reporting an image ID".to_string())
+    })?;
+
+    // The build API accepts a single tag; apply the rest afterwards.
+    for tag in request.tags.iter().skip(1) {
+        image_tag(docker, &image_id, tag).await?;
+    }
+
+    Ok(image_id)
+}
+
 /// Progress callback for image pull operations.
 pub type PullProgressCallback = Arc<dyn Fn(PullProgress) + Send + Sync + 'static>;
 
@@ -1346,12 +3184,20 @@ pub struct PullProgress {
 /// If `mirror` is provided, rewrites Docker Hub images to use the mirror registry.
 /// Each pull attempt has a 30-second timeout to prevent infinite blocking.
 ///
+/// `platform` (`os/arch[/variant]`, e.g. `linux/amd64`) pulls that variant
+/// of a multi-arch image instead of the runtime's own.
+///
 /// The optional `on_progress` callback receives real-time layer download progress.
+///
+/// Cancelling `cancel` closes the pull stream; the daemon then aborts the
+/// pull and discards its partially downloaded layers.
 pub async fn image_pull(
     docker: &Docker,
     image: &str,
     mirror: Option<&str>,
+    platform: Option<&str>,
     on_progress: Option<PullProgressCallback>,
+    cancel: Option<&CancellationToken>,
 ) -> Result<(), AppError> {
     use bollard::image::CreateImageOptions;
 
@@ -1368,6 +3214,7 @@ pub async fn image_pull(
     let options = Some(CreateImageOptions {
         from_image: repo.as_str(),
         tag: tag.as_str(),
+        platform: platform.unwrap_or_default(),
         ..Default::default()
     });
 
@@ -1385,13 +3232,18 @@ pub async fn image_pull(
     let mut max_current_sent: u64 = 0;
 
     let chunk_timeout_secs = 120;
+    let what = format!("Pull of '{}'", image);
 
     loop {
-        let chunk_timeout = tokio::time::timeout(
-            std::time::Duration::from_secs(chunk_timeout_secs),
-            stream.next(),
+        let chunk_timeout = cancel::run(
+            cancel,
+            &what,
+            tokio::time::timeout(
+                std::time::Duration::from_secs(chunk_timeout_secs),
+                stream.next(),
+            ),
         )
-        .await;
+        .await?;
 
         match chunk_timeout {
             Ok(Some(Ok(info))) => {
@@ -1491,12 +3343,15 @@ pub async fn image_pull(
 /// Pull an image, trying a list of mirrors in order, falling back to direct pull.
 ///
 /// Returns Ok(()) on the first successful pull. If all mirrors fail, attempts
-/// a direct pull as final fallback.
+/// a direct pull as final fallback. A cancelled pull stops without trying
+/// the remaining mirrors.
 pub async fn image_pull_with_mirrors(
     docker: &Docker,
     image: &str,
     mirrors: &[String],
+    platform: Option<&str>,
     on_progress: Option<PullProgressCallback>,
+    cancel: Option<&CancellationToken>,
 ) -> Result<(), AppError> {
     // Try each mirror in order
     for (i, mirror) in mirrors.iter().enumerate() {
@@ -1518,7 +3373,16 @@ pub async fn image_pull_with_mirrors(
             });
         }
 
-        match image_pull(docker, image, Some(mirror), on_progress.clone()).await {
+        match image_pull(
+            docker,
+            image,
+            Some(mirror),
+            platform,
+            on_progress.clone(),
+            cancel,
+        )
+        .await
+        {
             Ok(()) => {
                 tracing::info!("Successfully pulled '{}' via mirror '{}'", image, mirror);
                 // Re-tag the mirror image to the original name and remove the mirror tag.
@@ -1538,6 +3402,7 @@ pub async fn image_pull_with_mirrors(
                 }
                 return Ok(());
             }
+            Err(e @ AppError::Cancelled(_)) => return Err(e),
             Err(e) => {
                 tracing::warn!("Mirror '{}' failed for '{}': {}", mirror, image, e);
                 if let Some(ref cb) = on_progress {
@@ -1563,27 +3428,94 @@ pub async fn image_pull_with_mirrors(
             total_bytes: 0,
         });
     }
-    image_pull(docker, image, None, on_progress).await
+    image_pull(docker, image, None, platform, on_progress, cancel).await
+}
+
+/// Push a local image to its registry through the Docker Engine API.
+///
+/// Works without a `docker` CLI on the host: the engine (built-in runtime
+/// or external) performs the upload. `auth` is forwarded as
+/// `X-Registry-Auth`; without it only anonymous-writable registries work.
+pub async fn image_push(
+    docker: &Docker,
+    image: &str,
+    auth: Option<&RegistryAuth>,
+    on_progress: Option<PullProgressCallback>,
+) -> Result<(), AppError> {
+    use bollard::auth::DockerCredentials;
+    use bollard::image::PushImageOptions;
+
+    let image = image.trim();
+    if image.is_empty() || image.contains('@') {
+        return Err(AppError::Validation(format!(
+            "Invalid image reference for push: '{}'",
+            image
+        )));
+    }
+
+    let (repo, tag) = split_repo_and_tag(image);
+    let credentials = auth.map(|a| DockerCredentials {
+        username: Some(a.username.clone()),
+        password: Some(a.password.clone()),
+        serveraddress: a.server_address.clone(),
+        ..Default::default()
+    });
+
+    tracing::info!("Pushing image: {}:{}", repo, tag);
+    let mut stream = docker.push_image(&repo, Some(PushImageOptions { tag }), credentials);
+
+    while let Some(result) = stream.next().await {
+        let info = result?;
+        if let Some(error) = info.error {
+            return Err(AppError::Runtime(format!(
+                "Image push failed for '{}': {}",
+                image, error
+            )));
+        }
+        if let Some(ref cb) = on_progress {
+            let (current, total) = info
+                .progress_detail
+                .as_ref()
+                .map(|d| (d.current.unwrap_or(0) as u64, d.total.unwrap_or(0) as u64))
+                .unwrap_or((0, 0));
+            cb(PullProgress {
+                status: info.status.unwrap_or_default(),
+                progress_detail: info.progress,
+                current_bytes: current,
+                total_bytes: total,
+            });
+        }
+    }
+
+    Ok(())
 }
 
-/// Rewrite a Docker Hub image reference to use a mirror registry.
+/// Rewrite an image reference to pull through a mirror registry.
 ///
-/// Rules:
+/// Rules (the mirror's `http(s)://` scheme is dropped):
 /// - "node:20-alpine" → "{mirror}/library/node:20-alpine" (official image)
 /// - "library/node:20-alpine" → "{mirror}/library/node:20-alpine"
 /// - "myuser/myapp:latest" → "{mirror}/myuser/myapp:latest"
-/// - "gcr.io/project/image:tag" → unchanged (non-Docker-Hub, has explicit registry)
+/// - "ghcr.io/org/app:tag" → "{mirror}/org/app:tag" (the mirror stands in
+///   for the explicit registry, so callers only pass mirrors configured for
+///   that registry; see [`crate::config::Config::mirrors_for_image`])
 fn rewrite_image_for_mirror(image: &str, mirror: &str) -> String {
-    let mirror = mirror.trim_end_matches('/');
+    let mirror = mirror
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
 
     // Check if image has an explicit registry (contains '.' or ':' before the first '/')
     // Examples with registry: "gcr.io/foo/bar", "registry.example.com:5000/foo"
     // Examples without: "node:20", "library/node:20", "myuser/myapp:latest"
-    if let Some(first_slash_pos) = image.find('/') {
-        let before_slash = &image[..first_slash_pos];
-        if before_slash.contains('.') || before_slash.contains(':') {
-            // Has explicit registry — don't rewrite
-            return image.to_string();
+    if let Some((before_slash, rest)) = image.split_once('/') {
+        if before_slash.contains('.') || before_slash.contains(':') || before_slash == "localhost" {
+            if rest.contains('/') || crate::config::canonical_registry(before_slash) != "docker.io"
+            {
+                return format!("{}/{}", mirror, rest);
+            }
+            // "docker.io/nginx" — official image with an explicit Docker Hub host.
+            return format!("{}/library/{}", mirror, rest);
         }
         // Has a namespace (e.g., "myuser/myapp:latest") — rewrite with namespace
         format!("{}/{}", mirror, image)
@@ -1603,7 +3535,8 @@ fn split_repo_and_tag(reference: &str) -> (String, String) {
     (reference.to_string(), "latest".to_string())
 }
 
-fn format_bytes_human(bytes: u64) -> String {
+/// Format a byte count as `B`/`KB`/`MB`/`GB` for display.
+pub fn format_bytes_human(bytes: u64) -> String {
     const KB: f64 = 1024.0;
     const MB: f64 = KB * 1024.0;
     const GB: f64 = MB * 1024.0;
@@ -1635,8 +3568,377 @@ pub async fn image_exists(docker: &Docker, image: &str) -> Result<bool, AppError
 pub async fn ensure_image(docker: &Docker, image: &str) -> Result<(), AppError> {
     if !image_exists(docker, image).await? {
         tracing::info!("Image '{}' not found locally, pulling...", image);
-        image_pull(docker, image, None, None).await?;
+        image_pull(docker, image, None, None, None, None).await?;
         tracing::info!("Image '{}' pulled successfully", image);
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specs(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn memory_swap_must_cover_memory() {
+        const MB: i64 = 1024 * 1024;
+        // Kept limits: unset or unlimited swap never blocks a memory change.
+        assert!(check_memory_swap(Some(512 * MB), None, false).is_ok());
+        assert!(check_memory_swap(Some(512 * MB), Some(0), false).is_ok());
+        assert!(check_memory_swap(Some(4096 * MB), Some(-1), false).is_ok());
+        assert!(check_memory_swap(Some(1024 * MB), Some(2048 * MB), false).is_ok());
+        let err = check_memory_swap(Some(4096 * MB), Some(2048 * MB), false).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("exceeds the container's memory+swap limit"));
+
+        assert!(check_memory_swap(Some(1024 * MB), Some(1024 * MB), true).is_ok());
+        assert!(check_memory_swap(Some(1024 * MB), Some(512 * MB), true).is_err());
+        assert!(check_memory_swap(Some(1024 * MB), Some(0), true).is_err());
+        assert!(check_memory_swap(Some(1024 * MB), Some(-2 * MB), true).is_err());
+        assert!(check_memory_swap(None, Some(1024 * MB), true).is_err());
+    }
+
+    #[test]
+    fn fractional_cpu_limits_round_up() {
+        assert_eq!(nano_cpus_to_cores(500_000_000), 1);
+        assert_eq!(nano_cpus_to_cores(1_000_000_000), 1);
+        assert_eq!(nano_cpus_to_cores(1_500_000_000), 2);
+    }
+
+    #[test]
+    fn reservations_sum_only_explicit_limits() {
+        let container = |labels: &[(&str, &str)]| {
+            container_info(bollard::models::ContainerSummary {
+                state: Some("exited".to_string()),
+                labels: Some(
+                    labels
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect(),
+                ),
+                ..Default::default()
+            })
+        };
+        let containers = [
+            container(&[
+                ("com.cratebay.cpu_cores", "2"),
+                ("com.cratebay.memory_mb", "512"),
+            ]),
+            container(&[("com.cratebay.memory_mb", "1024")]),
+            container(&[]),
+        ];
+        assert_eq!(sum_reservations(&containers), (2, 1536));
+        assert_eq!(sum_reservations(&[]), (0, 0));
+    }
+
+    #[test]
+    fn idempotency_window_covers_recent_creates() {
+        let window = IDEMPOTENCY_WINDOW.as_secs() as i64;
+        assert!(within_idempotency_window(1_000, 1_000));
+        assert!(within_idempotency_window(1_000, 1_000 + window));
+        assert!(!within_idempotency_window(1_000, 1_001 + window));
+    }
+
+    #[tokio::test]
+    async fn archive_write_renames_finished_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("image.tar");
+        let chunks: Vec<Result<bytes::Bytes, bollard::errors::Error>> = vec![
+            Ok(bytes::Bytes::from_static(b"layer-")),
+            Ok(bytes::Bytes::from_static(b"one")),
+        ];
+        let progress = Arc::new(std::sync::atomic::AtomicU64::new(0));
+        let seen = progress.clone();
+        let on_progress: SaveProgressCallback =
+            Arc::new(move |n| seen.store(n, std::sync::atomic::Ordering::SeqCst));
+
+        let size = write_archive(
+            futures_util::stream::iter(chunks),
+            &output,
+            false,
+            Some(on_progress),
+        )
+        .await
+        .unwrap();
+        assert_eq!(size, 9);
+        assert_eq!(std::fs::read(&output).unwrap(), b"layer-one");
+        assert_eq!(progress.load(std::sync::atomic::Ordering::SeqCst), 9);
+        assert!(!output.with_extension("partial").exists());
+    }
+
+    #[tokio::test]
+    async fn dropped_archive_write_removes_partial_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("image.tar");
+        let chunk: Result<bytes::Bytes, bollard::errors::Error> =
+            Ok(bytes::Bytes::from_static(b"layer"));
+        let stream = futures_util::stream::iter([chunk]).chain(futures_util::stream::pending());
+
+        let write = write_archive(stream, &output, false, None);
+        let timed_out = tokio::time::timeout(std::time::Duration::from_millis(50), write).await;
+        assert!(timed_out.is_err());
+        assert!(!output.with_extension("partial").exists());
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn rewrite_image_for_mirror_replaces_registry() {
+        let mirror = "https://mirror.example/";
+        assert_eq!(
+            rewrite_image_for_mirror("node:20", mirror),
+            "mirror.example/library/node:20"
+        );
+        assert_eq!(
+            rewrite_image_for_mirror("me/app:1", mirror),
+            "mirror.example/me/app:1"
+        );
+        assert_eq!(
+            rewrite_image_for_mirror("docker.io/nginx", mirror),
+            "mirror.example/library/nginx"
+        );
+        assert_eq!(
+            rewrite_image_for_mirror("ghcr.io/org/app:1", mirror),
+            "mirror.example/org/app:1"
+        );
+    }
+
+    #[test]
+    fn created_by_becomes_a_dockerfile_instruction() {
+        assert_eq!(
+            clean_created_by(r#"/bin/sh -c #(nop)  CMD ["nginx" "-g"]"#),
+            r#"CMD ["nginx" "-g"]"#
+        );
+        assert_eq!(
+            clean_created_by("/bin/sh -c apt-get update"),
+            "RUN apt-get update"
+        );
+        assert_eq!(
+            clean_created_by("COPY . /app # buildkit"),
+            "COPY . /app # buildkit"
+        );
+    }
+
+    #[test]
+    fn parse_list_filters_maps_to_docker_filters() {
+        let filters = parse_list_filters(&specs(&[
+            "name=web",
+            "label=env=prod",
+            "label=com.example.team",
+            "status=running",
+            "status=paused",
+            "ancestor=nginx:1.27",
+        ]))
+        .unwrap();
+        let mut docker = docker_list_filters(&filters);
+        docker.get_mut("label").unwrap().sort();
+
+        assert_eq!(docker["name"], vec!["web"]);
+        assert_eq!(docker["label"], vec!["com.example.team", "env=prod"]);
+        assert_eq!(docker["status"], vec!["running", "paused"]);
+        assert_eq!(docker["ancestor"], vec!["nginx:1.27"]);
+    }
+
+    #[test]
+    fn list_limit_applies_after_helpers_are_dropped() {
+        let summary = |id: &str, image: &str, internal: bool| bollard::models::ContainerSummary {
+            id: Some(id.to_string()),
+            image: Some(image.to_string()),
+            labels: internal
+                .then(|| HashMap::from([(INTERNAL_LABEL.to_string(), "stats".to_string())])),
+            ..Default::default()
+        };
+        // Newest first, as Docker lists them.
+        let containers = || {
+            vec![
+                summary("helper", "alpine:3.20", true),
+                summary("web", "nginx:1.27", false),
+                summary("db", "postgres:16", false),
+                summary("cache", "redis:7", false),
+            ]
+        };
+        let ids = |filters: ContainerListFilters| -> Vec<String> {
+            visible_containers(containers(), Some(&filters))
+                .into_iter()
+                .map(|c| c.id)
+                .collect()
+        };
+
+        let latest = ContainerListFilters {
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(ids(latest), vec!["web"]);
+        let by_image = ContainerListFilters {
+            image: Some("redis".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(ids(by_image), vec!["cache"]);
+    }
+
+    #[test]
+    fn parse_top_maps_columns_by_title() {
+        let titles = specs(&["UID", "PID", "PPID", "C", "STIME", "TTY", "TIME", "CMD"]);
+        let rows = vec![specs(&[
+            "root",
+            "4242",
+            "4200",
+            "3",
+            "10:00",
+            "?",
+            "00:00:01",
+            "nginx: master process nginx -g daemon off;",
+        ])];
+        let processes = parse_top(&titles, rows);
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].pid, 4242);
+        assert_eq!(processes[0].ppid, Some(4200));
+        assert_eq!(processes[0].user, "root");
+        assert_eq!(processes[0].cpu_percent, Some(3.0));
+        assert_eq!(
+            processes[0].command,
+            "nginx: master process nginx -g daemon off;"
+        );
+    }
+
+    #[test]
+    fn validate_commit_change_accepts_config_instructions() {
+        assert!(validate_commit_change("ENV APP_ENV=prod").is_ok());
+        assert!(validate_commit_change("cmd [\"nginx\", \"-g\", \"daemon off;\"]").is_ok());
+        assert!(validate_commit_change("LABEL org.example.team=infra").is_ok());
+
+        assert!(validate_commit_change("RUN rm -rf /").is_err());
+        assert!(validate_commit_change("ENV").is_err());
+        assert!(validate_commit_change("ENV A=1\nRUN id").is_err());
+    }
+
+    #[test]
+    fn parse_list_filters_rejects_bad_input() {
+        for bad in [
+            &["name"][..],
+            &["name="],
+            &["size=10"],
+            &["status=stopped"],
+            &["name=a", "name=b"],
+        ] {
+            assert!(
+                matches!(
+                    parse_list_filters(&specs(bad)),
+                    Err(AppError::Validation(_))
+                ),
+                "{:?} should be rejected",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn select_web_port_prefers_requested_then_web_ports() {
+        let mapping = |host_port, container_port, protocol: &str| PortMapping {
+            host_port,
+            container_port,
+            protocol: protocol.to_string(),
+        };
+        let ports = vec![
+            mapping(15432, 5432, "tcp"),
+            mapping(18080, 8080, "tcp"),
+            mapping(10053, 53, "udp"),
+        ];
+        assert_eq!(select_web_port(&ports, None).unwrap().host_port, 18080);
+        assert_eq!(
+            select_web_port(&ports, Some(5432)).unwrap().host_port,
+            15432
+        );
+        assert_eq!(
+            select_web_port(&ports, Some(18080)).unwrap().host_port,
+            18080
+        );
+        assert!(select_web_port(&ports, Some(53)).is_none());
+        assert_eq!(
+            select_web_port(&ports[..1], None).unwrap().container_port,
+            5432
+        );
+    }
+
+    #[test]
+    fn validate_create_flags_missing_volume_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let volumes = vec![
+            VolumeMount {
+                host_path: dir.path().display().to_string(),
+                container_path: "/data".to_string(),
+                read_only: None,
+            },
+            VolumeMount {
+                host_path: dir.path().join("missing").display().to_string(),
+                container_path: "data".to_string(),
+                read_only: None,
+            },
+        ];
+        let issues = volume_issues(&volumes);
+        assert_eq!(issues.len(), 2);
+        assert!(issues
+            .iter()
+            .all(|i| i.field == "volumes" && i.severity == "error"));
+    }
+
+    #[test]
+    fn validate_create_checks_limits_against_host() {
+        assert!(resource_issues(Some(2), Some(1024), Some(4), Some(8192)).is_empty());
+
+        let issues = resource_issues(Some(8), Some(16384), Some(4), Some(8192));
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].field, "cpuCores");
+        assert_eq!(issues[1].field, "memoryMb");
+        assert!(issues.iter().all(|i| i.severity == "error"));
+
+        let issues = resource_issues(None, Some(7000), None, Some(8192));
+        assert_eq!(issues[0].severity, "warning");
+
+        let issues = resource_issues(Some(0), Some(64), None, None);
+        assert_eq!(issues.len(), 2);
+    }
+
+    #[test]
+    fn run_platform_detects_emulation_and_missing_variants() {
+        let arm = "linux/arm64";
+        let multi = vec!["linux/amd64".to_string(), "linux/arm64/v8".to_string()];
+        let amd64_only = vec!["linux/amd64".to_string()];
+
+        assert_eq!(run_platform(arm, &multi, true, None), Ok(None));
+        assert_eq!(run_platform(arm, &[], false, None), Ok(None));
+        // Single-arch images run as what they are.
+        assert_eq!(
+            run_platform(arm, &amd64_only, false, None),
+            Ok(Some("linux/amd64".to_string()))
+        );
+        // Docker will not pick a foreign variant of an index by itself.
+        let err = run_platform(arm, &amd64_only, true, None).unwrap_err();
+        assert!(err.contains("--platform linux/amd64"), "{}", err);
+        // An explicit foreign platform is emulated if the image has it.
+        assert_eq!(
+            run_platform(arm, &multi, true, Some("linux/amd64")),
+            Ok(Some("linux/amd64".to_string()))
+        );
+        assert_eq!(
+            run_platform(arm, &multi, true, Some("linux/arm64")),
+            Ok(None)
+        );
+        assert!(run_platform(arm, &multi, true, Some("linux/s390x")).is_err());
+    }
+
+    #[test]
+    fn import_progress_yields_image_id_or_error() {
+        let body = b"{\"status\":\"Downloading\"}\n{\"status\":\"sha256:abc\"}\n";
+        assert_eq!(
+            imported_image_id(body).unwrap(),
+            Some("sha256:abc".to_string())
+        );
+        let body = b"{\"errorDetail\":{\"message\":\"bad tar\"},\"error\":\"bad tar\"}\n";
+        assert!(imported_image_id(body).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! Container list cache kept current by Docker events.
+//!
+//! The GUI lists containers on every page refresh and after every action.
+//! [`ContainerCache`] answers those calls from memory: the first call does a
+//! full Docker list, then container events from the event stream mark single
+//! containers stale, and the next call re-lists only those by id. Filters are
+//! applied in memory, except `ancestor`, which needs the image tree and goes
+//! to Docker.
+//!
+//! The cache is only trusted while the event stream is attached
+//! ([`set_live`](ContainerCache::set_live)); otherwise every call is a full
+//! Docker list, as before.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use bollard::Docker;
+
+use crate::container;
+use crate::error::AppError;
+use crate::models::{ContainerInfo, ContainerListFilters, DockerEvent};
+use crate::MutexExt;
+
+#[derive(Default)]
+struct CacheState {
+    /// Event stream attached; events are being applied.
+    live: bool,
+    /// All containers, newest first. `None` until the first full list.
+    containers: Option<Vec<ContainerInfo>>,
+    /// Containers changed since they were listed.
+    stale: HashSet<String>,
+    /// Bumped by [`ContainerCache::invalidate`] so an in-flight full list
+    /// does not store a result that predates it.
+    epoch: u64,
+}
+
+/// In-memory container list, see the module docs.
+#[derive(Default)]
+pub struct ContainerCache {
+    state: Mutex<CacheState>,
+}
+
+impl ContainerCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark whether the Docker event stream is attached. Attaching or
+    /// detaching drops the cached list, since events may have been missed.
+    pub fn set_live(&self, live: bool) {
+        if let Ok(mut state) = self.state.lock_or_recover() {
+            state.live = live;
+        }
+        self.invalidate();
+    }
+
+    /// Drop the cached list, e.g. after switching Docker hosts.
+    pub fn invalidate(&self) {
+        if let Ok(mut state) = self.state.lock_or_recover() {
+            state.containers = None;
+            state.stale.clear();
+            state.epoch += 1;
+        }
+    }
+
+    /// Apply a Docker event. Removed containers leave the cache at once;
+    /// other container events mark the container stale. Exec events do
+    /// not change the listing and are ignored.
+    pub fn apply_event(&self, event: &DockerEvent) {
+        if event.kind != "container" || event.action.starts_with("exec_") || event.id.is_empty() {
+            return;
+        }
+        let Ok(mut state) = self.state.lock_or_recover() else {
+            return;
+        };
+        if event.action == "destroy" {
+            if let Some(containers) = state.containers.as_mut() {
+                containers.retain(|c| c.id != event.id);
+            }
+            state.stale.remove(&event.id);
+        } else {
+            state.stale.insert(event.id.clone());
+        }
+    }
+
+    /// List containers (running or not), from the cache when possible.
+    pub async fn list(
+        &self,
+        docker: &Docker,
+        filters: Option<ContainerListFilters>,
+    ) -> Result<Vec<ContainerInfo>, AppError> {
+        let uncacheable = filters.as_ref().is_some_and(|f| f.ancestor.is_some());
+        let snapshot = {
+            let mut state = self.state.lock_or_recover()?;
+            // Events arriving while we list mark containers stale again.
+            (state.live && !uncacheable).then(|| {
+                let stale: Vec<String> = state.stale.drain().collect();
+                (state.epoch, state.containers.is_some(), stale)
+            })
+        };
+        let Some((epoch, cached, stale)) = snapshot else {
+            return container::list(docker, true, filters).await;
+        };
+
+        if !cached {
+            let containers = container::list(docker, true, None).await?;
+            let mut state = self.state.lock_or_recover()?;
+            if state.epoch == epoch {
+                state.containers = Some(containers.clone());
+            }
+            return Ok(filter(containers, filters.as_ref()));
+        }
+
+        if !stale.is_empty() {
+            match container::list_ids(docker, &stale).await {
+                Ok(fresh) => {
+                    let mut state = self.state.lock_or_recover()?;
+                    if state.epoch == epoch {
+                        if let Some(containers) = state.containers.as_mut() {
+                            merge(containers, &stale, fresh);
+                        }
+                    }
+                }
+                Err(e) => {
+                    // Retry these on the next call.
+                    let mut state = self.state.lock_or_recover()?;
+                    state.stale.extend(stale);
+                    return Err(e);
+                }
+            }
+        }
+
+        let containers = self.state.lock_or_recover()?.containers.clone();
+        match containers {
+            Some(containers) => Ok(filter(containers, filters.as_ref())),
+            // Invalidated while re-listing.
+            None => container::list(docker, true, filters).await,
+        }
+    }
+}
+
+/// Replace the `stale` containers in `containers` with `fresh`, dropping
+/// the ones that no longer exist, and keep newest-first order.
+fn merge(containers: &mut Vec<ContainerInfo>, stale: &[String], fresh: Vec<ContainerInfo>) {
+    containers.retain(|c| !stale.contains(&c.id));
+    containers.extend(fresh);
+    // RFC 3339 UTC timestamps sort chronologically as strings.
+    containers.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+}
+
+/// Apply list filters the way Docker does, except `ancestor`.
+fn filter(
+    mut containers: Vec<ContainerInfo>,
+    filters: Option<&ContainerListFilters>,
+) -> Vec<ContainerInfo> {
+    let Some(f) = filters else {
+        return containers;
+    };
+    containers.retain(|c| {
+        f.status.as_ref().is_none_or(|s| s.contains(&c.status))
+            && f.name.as_ref().is_none_or(|n| c.name.contains(n.as_str()))
+            && f.image
+                .as_ref()
+                .is_none_or(|i| c.image.contains(i.as_str()))
+            && f.label.as_ref().is_none_or(|labels| {
+                labels.iter().all(|(k, v)| {
+                    c.labels
+                        .get(k)
+                        .is_some_and(|actual| v.is_empty() || actual == v)
+                })
+            })
+    });
+    if let Some(limit) = f.limit {
+        containers.truncate(limit as usize);
+    }
+    containers
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::models::ContainerStatus;
+
+    fn info(id: &str, name: &str, status: ContainerStatus, created_at: &str) -> ContainerInfo {
+        ContainerInfo {
+            id: id.to_string(),
+            short_id: id.to_string(),
+            name: name.to_string(),
+            image: "nginx:latest".to_string(),
+            status,
+            state: String::new(),
+            created_at: created_at.to_string(),
+            ports: Vec::new(),
+            labels: HashMap::from([("app".to_string(), name.to_string())]),
+            cpu_cores: None,
+            memory_mb: None,
+        }
+    }
+
+    fn event(action: &str, id: &str) -> DockerEvent {
+        DockerEvent {
+            kind: "container".to_string(),
+            action: action.to_string(),
+            id: id.to_string(),
+            name: None,
+            time: 0,
+            attributes: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn events_mark_stale_and_destroy_removes() {
+        let cache = ContainerCache::new();
+        cache.set_live(true);
+        {
+            let mut state = cache.state.lock().unwrap();
+            state.containers = Some(vec![
+                info(
+                    "b",
+                    "web",
+                    ContainerStatus::Running,
+                    "2026-01-02T00:00:00+00:00",
+                ),
+                info(
+                    "a",
+                    "db",
+                    ContainerStatus::Exited,
+                    "2026-01-01T00:00:00+00:00",
+                ),
+            ]);
+        }
+
+        cache.apply_event(&event("exec_start: sh", "b"));
+        cache.apply_event(&event("stop", "b"));
+        cache.apply_event(&event("destroy", "a"));
+        let state = cache.state.lock().unwrap();
+        assert_eq!(state.stale, HashSet::from(["b".to_string()]));
+        let ids: Vec<_> = state
+            .containers
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|c| c.id.as_str())
+            .collect();
+        assert_eq!(ids, ["b"]);
+        drop(state);
+
+        cache.set_live(false);
+        assert!(cache.state.lock().unwrap().containers.is_none());
+    }
+
+    #[test]
+    fn merge_replaces_stale_and_keeps_newest_first() {
+        let mut containers = vec![
+            info(
+                "b",
+                "web",
+                ContainerStatus::Running,
+                "2026-01-02T00:00:00+00:00",
+            ),
+            info(
+                "a",
+                "db",
+                ContainerStatus::Running,
+                "2026-01-01T00:00:00+00:00",
+            ),
+        ];
+        let stale = ["a".to_string(), "c".to_string(), "b".to_string()];
+        merge(
+            &mut containers,
+            &stale,
+            vec![
+                info(
+                    "a",
+                    "db",
+                    ContainerStatus::Exited,
+                    "2026-01-01T00:00:00+00:00",
+                ),
+                info(
+                    "c",
+                    "new",
+                    ContainerStatus::Created,
+                    "2026-01-03T00:00:00+00:00",
+                ),
+            ],
+        );
+        let view: Vec<_> = containers
+            .iter()
+            .map(|c| (c.id.as_str(), c.status.clone()))
+            .collect();
+        assert_eq!(
+            view,
+            [
+                ("c", ContainerStatus::Created),
+                ("a", ContainerStatus::Exited)
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_apply_in_memory() {
+        let containers = vec![
+            info("c", "web-2", ContainerStatus::Running, "3"),
+            info("b", "web-1", ContainerStatus::Exited, "2"),
+            info("a", "db", ContainerStatus::Running, "1"),
+        ];
+        let running = ContainerListFilters {
+            status: Some(vec![ContainerStatus::Running]),
+            ..Default::default()
+        };
+        assert_eq!(filter(containers.clone(), Some(&running)).len(), 2);
+
+        let web = ContainerListFilters {
+            name: Some("web".to_string()),
+            label: Some(HashMap::from([("app".to_string(), String::new())])),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let listed = filter(containers.clone(), Some(&web));
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, "c");
+
+        let labelled = ContainerListFilters {
+            label: Some(HashMap::from([("app".to_string(), "db".to_string())])),
+            ..Default::default()
+        };
+        assert_eq!(filter(containers, Some(&labelled))[0].id, "a");
+    }
+}
@@ -0,0 +1,389 @@
+//! `dev watch`: restart or reload a container when host files change.
+//!
+//! Host paths are watched with `notify`, directories recursively. Single
+//! files are watched through their parent directory, because editors often
+//! save by writing a new file and renaming it over the old one, which ends
+//! a watch on the file itself.
+//!
+//! Changes are debounced: the action runs once nothing changed for
+//! [`WatchOptions::debounce`], so a save touching several files or a
+//! `git checkout` triggers it once. Changes made while the action runs
+//! trigger one more run afterwards. Paths below an ignored directory name
+//! (`.git`, `node_modules`, `target` unless replaced) and editor swap and
+//! backup files never trigger it; a container that writes into a watched
+//! directory through a bind mount needs that directory ignored, or each
+//! restart triggers the next.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use bollard::container::KillContainerOptions;
+use bollard::Docker;
+use notify::{EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::cancel::CancellationToken;
+use crate::container;
+use crate::error::AppError;
+use crate::models::ExecResult;
+
+/// Directory names ignored unless the caller passes its own list.
+pub const DEFAULT_IGNORE: &[&str] = &[".git", "node_modules", "target"];
+
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+const KILL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What happens to the container after a change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchAction {
+    /// Restart the container, giving it `timeout` seconds (default 10) to stop.
+    Restart { timeout: Option<u32> },
+    /// Send a signal to the main process, e.g. `HUP` for servers that
+    /// reload their configuration on it.
+    Signal(String),
+    /// Run a command in the container, e.g. a reload script.
+    Exec(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    pub container: String,
+    pub paths: Vec<PathBuf>,
+    pub action: WatchAction,
+    pub debounce: Duration,
+    /// Directory (or file) names that never trigger the action.
+    pub ignore: Vec<String>,
+}
+
+/// One run of the action.
+#[derive(Debug)]
+pub struct WatchReport {
+    /// Changed paths, sorted.
+    pub changed: Vec<PathBuf>,
+    pub elapsed: Duration,
+    /// The exec result for [`WatchAction::Exec`]. A failed action is
+    /// reported and watching goes on, so the next save can fix it.
+    pub outcome: Result<Option<ExecResult>, AppError>,
+}
+
+/// A watched path: a directory tree or a single file.
+#[derive(Debug, Clone, PartialEq)]
+struct Root {
+    path: PathBuf,
+    file: bool,
+}
+
+impl Root {
+    /// The path handed to the watcher and how.
+    fn watch_target(&self) -> (&Path, RecursiveMode) {
+        match (self.file, self.path.parent()) {
+            (true, Some(parent)) => (parent, RecursiveMode::NonRecursive),
+            _ => (&self.path, RecursiveMode::Recursive),
+        }
+    }
+}
+
+fn resolve_roots(paths: &[PathBuf]) -> Result<Vec<Root>, AppError> {
+    if paths.is_empty() {
+        return Err(AppError::Validation("No paths to watch".to_string()));
+    }
+    paths
+        .iter()
+        .map(|path| {
+            let resolved = path.canonicalize().map_err(|e| {
+                AppError::Validation(format!("Cannot watch '{}': {}", path.display(), e))
+            })?;
+            Ok(Root {
+                file: !resolved.is_dir(),
+                path: resolved,
+            })
+        })
+        .collect()
+}
+
+fn validate_action(action: &WatchAction) -> Result<(), AppError> {
+    match action {
+        WatchAction::Restart { .. } => Ok(()),
+        WatchAction::Signal(signal) => {
+            if !signal.is_empty() && signal.chars().all(|c| c.is_ascii_alphanumeric()) {
+                Ok(())
+            } else {
+                Err(AppError::Validation(format!(
+                    "Invalid signal '{}': use a name like HUP or SIGUSR1, or a number",
+                    signal
+                )))
+            }
+        }
+        WatchAction::Exec(cmd) => {
+            if cmd.first().is_some_and(|c| !c.trim().is_empty()) {
+                Ok(())
+            } else {
+                Err(AppError::Validation(
+                    "The reload command is empty".to_string(),
+                ))
+            }
+        }
+    }
+}
+
+/// Vim and Emacs swap, backup and lock files.
+fn editor_temp_file(name: &str) -> bool {
+    name.ends_with('~')
+        || name.ends_with(".swp")
+        || name.ends_with(".swx")
+        || name.starts_with(".#")
+        || name == "4913"
+}
+
+/// Whether a change of `path` triggers the action.
+fn triggers(path: &Path, roots: &[Root], ignore: &[String]) -> bool {
+    if path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(editor_temp_file)
+    {
+        return false;
+    }
+    roots.iter().any(|root| {
+        if root.file {
+            return path == root.path;
+        }
+        match path.strip_prefix(&root.path) {
+            Ok(relative) => !relative.components().any(|c| {
+                c.as_os_str()
+                    .to_str()
+                    .is_some_and(|name| ignore.iter().any(|i| i == name))
+            }),
+            Err(_) => false,
+        }
+    })
+}
+
+/// Add the paths of `event` that trigger the action to `pending`.
+fn collect(
+    event: notify::Event,
+    roots: &[Root],
+    ignore: &[String],
+    pending: &mut BTreeSet<PathBuf>,
+) {
+    // Reads and closes change nothing; writes also come as modify events.
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+    pending.extend(
+        event
+            .paths
+            .into_iter()
+            .filter(|path| triggers(path, roots, ignore)),
+    );
+}
+
+async fn apply(
+    docker: &Docker,
+    container_id: &str,
+    action: &WatchAction,
+) -> Result<Option<ExecResult>, AppError> {
+    match action {
+        WatchAction::Restart { timeout } => {
+            container::restart(docker, container_id, *timeout).await?;
+            Ok(None)
+        }
+        WatchAction::Signal(signal) => {
+            let options = Some(KillContainerOptions {
+                signal: signal.as_str(),
+            });
+            tokio::time::timeout(KILL_TIMEOUT, docker.kill_container(container_id, options))
+                .await
+                .map_err(|_| {
+                    AppError::Runtime(format!(
+                        "Signalling the container timed out after {:?}",
+                        KILL_TIMEOUT
+                    ))
+                })??;
+            Ok(None)
+        }
+        WatchAction::Exec(cmd) => Ok(Some(
+            container::exec(docker, container_id, cmd.clone(), None).await?,
+        )),
+    }
+}
+
+fn watch_error(e: notify::Error) -> AppError {
+    AppError::Runtime(format!("Watching files failed: {}", e))
+}
+
+/// Watch `options.paths` and apply `options.action` to the container after
+/// each batch of changes until `cancel` fires. `report` is called after
+/// every run.
+pub async fn run(
+    docker: &Docker,
+    options: &WatchOptions,
+    cancel: &CancellationToken,
+    mut report: impl FnMut(&WatchReport),
+) -> Result<(), AppError> {
+    validate_action(&options.action)?;
+    let roots = resolve_roots(&options.paths)?;
+    // Fail early on a typo rather than on the first save.
+    container::inspect(docker, &options.container).await?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })
+    .map_err(watch_error)?;
+    let mut targets = BTreeSet::new();
+    for root in &roots {
+        let (path, mode) = root.watch_target();
+        if targets.insert(path.to_path_buf()) {
+            watcher.watch(path, mode).map_err(watch_error)?;
+        }
+    }
+
+    let mut pending = BTreeSet::new();
+    loop {
+        let mut deadline = None;
+        loop {
+            let quiet = async {
+                match deadline {
+                    Some(at) => tokio::time::sleep_until(at).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                biased;
+                _ = cancel.cancelled() => return Ok(()),
+                _ = quiet => break,
+                event = rx.recv() => match event {
+                    Some(Ok(event)) => collect(event, &roots, &options.ignore, &mut pending),
+                    Some(Err(e)) => tracing::warn!("File watcher error: {}", e),
+                    None => return Err(AppError::Runtime("The file watcher stopped".to_string())),
+                },
+            }
+            if !pending.is_empty() {
+                deadline = Some(tokio::time::Instant::now() + options.debounce);
+            }
+        }
+
+        let changed: Vec<PathBuf> = std::mem::take(&mut pending).into_iter().collect();
+        let started = Instant::now();
+        let outcome = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Ok(()),
+            outcome = apply(docker, &options.container, &options.action) => outcome,
+        };
+        report(&WatchReport {
+            changed,
+            elapsed: started.elapsed(),
+            outcome,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::event::{AccessKind, ModifyKind};
+
+    use super::*;
+
+    fn ignore() -> Vec<String> {
+        DEFAULT_IGNORE.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn ignored_directories_and_editor_files_do_not_trigger() {
+        let roots = [
+            Root {
+                path: PathBuf::from("/work/app"),
+                file: false,
+            },
+            Root {
+                path: PathBuf::from("/work/config.toml"),
+                file: true,
+            },
+        ];
+        let ignore = ignore();
+        assert!(triggers(
+            Path::new("/work/app/src/main.rs"),
+            &roots,
+            &ignore
+        ));
+        assert!(triggers(Path::new("/work/config.toml"), &roots, &ignore));
+        assert!(!triggers(Path::new("/work/other.toml"), &roots, &ignore));
+        assert!(!triggers(
+            Path::new("/work/app/target/debug/app"),
+            &roots,
+            &ignore
+        ));
+        assert!(!triggers(
+            Path::new("/work/app/.git/index"),
+            &roots,
+            &ignore
+        ));
+        assert!(!triggers(
+            Path::new("/work/app/src/.main.rs.swp"),
+            &roots,
+            &ignore
+        ));
+        assert!(!triggers(
+            Path::new("/work/app/src/main.rs~"),
+            &roots,
+            &ignore
+        ));
+        // Only components below the watched root count.
+        let roots = [Root {
+            path: PathBuf::from("/home/me/target/app"),
+            file: false,
+        }];
+        assert!(triggers(
+            Path::new("/home/me/target/app/a.py"),
+            &roots,
+            &ignore
+        ));
+    }
+
+    #[test]
+    fn access_events_are_dropped_and_paths_deduplicated() {
+        let roots = [Root {
+            path: PathBuf::from("/work"),
+            file: false,
+        }];
+        let mut pending = BTreeSet::new();
+        let access = notify::Event::new(EventKind::Access(AccessKind::Any))
+            .add_path(PathBuf::from("/work/a.rs"));
+        collect(access, &roots, &ignore(), &mut pending);
+        assert!(pending.is_empty());
+
+        for _ in 0..2 {
+            let modify = notify::Event::new(EventKind::Modify(ModifyKind::Any))
+                .add_path(PathBuf::from("/work/a.rs"));
+            collect(modify, &roots, &ignore(), &mut pending);
+        }
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn actions_are_validated() {
+        assert!(validate_action(&WatchAction::Signal("SIGHUP".to_string())).is_ok());
+        assert!(validate_action(&WatchAction::Signal("HUP; rm".to_string())).is_err());
+        assert!(validate_action(&WatchAction::Exec(vec![" ".to_string()])).is_err());
+        assert!(validate_action(&WatchAction::Restart { timeout: None }).is_ok());
+    }
+
+    #[test]
+    fn files_are_watched_through_their_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.conf");
+        std::fs::write(&file, "x").unwrap();
+        let roots = resolve_roots(&[dir.path().to_path_buf(), file]).unwrap();
+        assert!(!roots[0].file);
+        assert_eq!(roots[0].watch_target().1, RecursiveMode::Recursive);
+        assert!(roots[1].file);
+        assert_eq!(
+            roots[1].watch_target(),
+            (roots[0].path.as_path(), RecursiveMode::NonRecursive)
+        );
+        assert!(resolve_roots(&[dir.path().join("missing")]).is_err());
+    }
+}
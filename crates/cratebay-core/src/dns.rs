@@ -0,0 +1,324 @@
+//! Host-side DNS for containers.
+//!
+//! Answers `A` queries for `<container>.docker.cratebay` with `127.0.0.1`
+//! for running containers that publish a port there, so host tools can
+//! reach containers by name. Container network IPs are not used: with the
+//! built-in runtime (and Docker Desktop-like engines) they live inside a VM
+//! the host cannot route to, while published ports reach the host's
+//! loopback on every platform. Engines on other machines are not covered.
+//! The records follow the Docker event stream: container events mark them
+//! stale through [`DnsRecords::apply_event`], and the owner re-lists running
+//! containers when [`DnsRecords::stale`] fires.
+//!
+//! The server only speaks the subset of RFC 1035 resolvers need for this:
+//! one question per UDP query, `A` answers, `NXDOMAIN` for unknown
+//! containers and `REFUSED` for names outside [`DNS_DOMAIN`].
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bollard::container::ListContainersOptions;
+use bollard::models::ContainerSummary;
+use bollard::Docker;
+use tokio::net::UdpSocket;
+use tokio::sync::Notify;
+
+use crate::cancel::CancellationToken;
+use crate::error::AppError;
+use crate::models::DockerEvent;
+use crate::MutexExt;
+
+/// Domain the records live under.
+pub const DNS_DOMAIN: &str = "docker.cratebay";
+
+/// Port the server listens on at `127.0.0.1` unless `CRATEBAY_DNS_PORT`
+/// says otherwise; 53 would need root.
+pub const DEFAULT_DNS_PORT: u16 = 5354;
+
+/// TTL of answers; short, since containers come and go.
+const RECORD_TTL_SECS: u32 = 5;
+
+const LIST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const TYPE_A: u16 = 1;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+const RCODE_NXDOMAIN: u16 = 3;
+const RCODE_REFUSED: u16 = 5;
+
+/// Listen port: `CRATEBAY_DNS_PORT`, else [`DEFAULT_DNS_PORT`].
+pub fn dns_port() -> u16 {
+    std::env::var("CRATEBAY_DNS_PORT")
+        .ok()
+        .and_then(|port| port.trim().parse().ok())
+        .filter(|port| *port != 0)
+        .unwrap_or(DEFAULT_DNS_PORT)
+}
+
+/// Container name → address, see the module docs.
+#[derive(Default)]
+pub struct DnsRecords {
+    names: Mutex<HashMap<String, Ipv4Addr>>,
+    stale: Notify,
+}
+
+impl DnsRecords {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the records with the running containers of `docker`.
+    /// Returns how many containers have a name and a published port.
+    pub async fn refresh(&self, docker: &Docker) -> Result<usize, AppError> {
+        let options = ListContainersOptions::<String> {
+            all: false,
+            ..Default::default()
+        };
+        let containers = tokio::time::timeout(LIST_TIMEOUT, docker.list_containers(Some(options)))
+            .await
+            .map_err(|_| {
+                AppError::Runtime(format!(
+                    "Docker container list timed out after {:?}",
+                    LIST_TIMEOUT
+                ))
+            })??;
+        let names = records_from(containers);
+        let count = names.len();
+        *self.names.lock_or_recover()? = names;
+        Ok(count)
+    }
+
+    /// Mark the records stale, e.g. after (re)attaching to the event stream.
+    pub fn invalidate(&self) {
+        self.stale.notify_one();
+    }
+
+    /// Mark the records stale when `event` can change a container's name
+    /// or presence.
+    pub fn apply_event(&self, event: &DockerEvent) {
+        let relevant = event.kind == "container"
+            && matches!(
+                event.action.as_str(),
+                "start" | "die" | "destroy" | "rename"
+            );
+        if relevant {
+            self.invalidate();
+        }
+    }
+
+    /// Wait until the records were marked stale.
+    pub async fn stale(&self) {
+        self.stale.notified().await
+    }
+
+    /// Address of `name`, a fully qualified `<container>.docker.cratebay`.
+    pub fn lookup(&self, name: &str) -> Option<Ipv4Addr> {
+        let container = container_label(name)?;
+        self.names.lock_or_recover().ok()?.get(&container).copied()
+    }
+}
+
+/// Lower-cased container name of `<container>.docker.cratebay[.]`.
+fn container_label(name: &str) -> Option<String> {
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    let container = name.strip_suffix(DNS_DOMAIN)?.strip_suffix('.')?;
+    (!container.is_empty()).then(|| container.to_string())
+}
+
+/// Names of listed containers that publish a port the host reaches on
+/// `127.0.0.1`: bound to all addresses or to loopback. Ports bound to
+/// another host address are not reachable there.
+fn records_from(containers: Vec<ContainerSummary>) -> HashMap<String, Ipv4Addr> {
+    let mut records = HashMap::new();
+    for container in containers {
+        let Some(name) = container
+            .names
+            .as_ref()
+            .and_then(|names| names.first())
+            .map(|name| name.trim_start_matches('/').to_ascii_lowercase())
+        else {
+            continue;
+        };
+        let published = container.ports.unwrap_or_default().iter().any(|port| {
+            port.public_port.is_some()
+                && matches!(
+                    port.ip.as_deref().unwrap_or(""),
+                    "" | "0.0.0.0" | "::" | "127.0.0.1" | "::1"
+                )
+        });
+        if published {
+            records.insert(name, Ipv4Addr::LOCALHOST);
+        }
+    }
+    records
+}
+
+/// Answer `records` queries on `socket` until `cancel` fires.
+pub async fn serve(
+    socket: UdpSocket,
+    records: &DnsRecords,
+    cancel: &CancellationToken,
+) -> Result<(), AppError> {
+    let mut buf = [0u8; 512];
+    loop {
+        let (len, peer) = tokio::select! {
+            _ = cancel.cancelled() => return Ok(()),
+            received = socket.recv_from(&mut buf) => received?,
+        };
+        let Some(reply) = respond(&buf[..len], |name| records.lookup(name)) else {
+            continue;
+        };
+        if let Err(e) = socket.send_to(&reply, peer).await {
+            tracing::debug!("DNS reply to {} failed: {}", peer, e);
+        }
+    }
+}
+
+/// Build the reply to one query; `None` for packets that are not a
+/// well-formed single-question query.
+fn respond(query: &[u8], lookup: impl Fn(&str) -> Option<Ipv4Addr>) -> Option<Vec<u8>> {
+    if query.len() < 12 {
+        return None;
+    }
+    let flags = u16::from_be_bytes([query[2], query[3]]);
+    let is_query = flags & 0x8000 == 0;
+    let opcode = (flags >> 11) & 0xf;
+    let questions = u16::from_be_bytes([query[4], query[5]]);
+    if !is_query || opcode != 0 || questions != 1 {
+        return None;
+    }
+
+    // QNAME is a sequence of labels; queries do not use compression.
+    let mut pos = 12;
+    let mut labels = Vec::new();
+    loop {
+        let len = *query.get(pos)? as usize;
+        pos += 1;
+        if len == 0 {
+            break;
+        }
+        if len > 63 {
+            return None;
+        }
+        labels.push(std::str::from_utf8(query.get(pos..pos + len)?).ok()?);
+        pos += len;
+    }
+    let qtype = u16::from_be_bytes([*query.get(pos)?, *query.get(pos + 1)?]);
+    let qclass = u16::from_be_bytes([*query.get(pos + 2)?, *query.get(pos + 3)?]);
+    let question = &query[12..pos + 4];
+    let name = labels.join(".");
+
+    let (rcode, answer) = if container_label(&name).is_none() {
+        (RCODE_REFUSED, None)
+    } else {
+        match lookup(&name) {
+            None => (RCODE_NXDOMAIN, None),
+            Some(ip) if qclass == CLASS_IN && matches!(qtype, TYPE_A | TYPE_ANY) => (0, Some(ip)),
+            // The name exists, just not with this record type.
+            Some(_) => (0, None),
+        }
+    };
+
+    // QR, AA, and RD copied from the query.
+    let reply_flags = 0x8000 | 0x0400 | (flags & 0x0100) | rcode;
+    let mut reply = Vec::with_capacity(12 + question.len() + 16);
+    reply.extend_from_slice(&query[0..2]);
+    reply.extend_from_slice(&reply_flags.to_be_bytes());
+    reply.extend_from_slice(&1u16.to_be_bytes());
+    reply.extend_from_slice(&u16::from(answer.is_some()).to_be_bytes());
+    reply.extend_from_slice(&[0, 0, 0, 0]);
+    reply.extend_from_slice(question);
+    if let Some(ip) = answer {
+        // Name: pointer to the question at offset 12.
+        reply.extend_from_slice(&[0xc0, 0x0c]);
+        reply.extend_from_slice(&TYPE_A.to_be_bytes());
+        reply.extend_from_slice(&CLASS_IN.to_be_bytes());
+        reply.extend_from_slice(&RECORD_TTL_SECS.to_be_bytes());
+        reply.extend_from_slice(&4u16.to_be_bytes());
+        reply.extend_from_slice(&ip.octets());
+    }
+    Some(reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bollard::models::Port;
+
+    fn query(name: &str, qtype: u16) -> Vec<u8> {
+        let mut q = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in name.split('.') {
+            q.push(label.len() as u8);
+            q.extend_from_slice(label.as_bytes());
+        }
+        q.push(0);
+        q.extend_from_slice(&qtype.to_be_bytes());
+        q.extend_from_slice(&CLASS_IN.to_be_bytes());
+        q
+    }
+
+    fn lookup(name: &str) -> Option<Ipv4Addr> {
+        (container_label(name)? == "web").then_some(Ipv4Addr::LOCALHOST)
+    }
+
+    #[test]
+    fn answers_a_queries_for_known_containers() {
+        let q = query("Web.docker.cratebay", TYPE_A);
+        let reply = respond(&q, lookup).unwrap();
+        assert_eq!(&reply[0..2], &[0x12, 0x34]);
+        // QR | AA | RD, NOERROR, one answer.
+        assert_eq!(&reply[2..4], &[0x85, 0x00]);
+        assert_eq!(&reply[6..8], &[0, 1]);
+        assert_eq!(&reply[reply.len() - 4..], &[127, 0, 0, 1]);
+    }
+
+    #[test]
+    fn rejects_unknown_and_foreign_names() {
+        let reply = respond(&query("db.docker.cratebay", TYPE_A), lookup).unwrap();
+        assert_eq!(reply[3] & 0x0f, RCODE_NXDOMAIN as u8);
+        assert_eq!(&reply[6..8], &[0, 0]);
+
+        let reply = respond(&query("example.com", TYPE_A), lookup).unwrap();
+        assert_eq!(reply[3] & 0x0f, RCODE_REFUSED as u8);
+
+        // AAAA for a known name: NOERROR without answers.
+        let reply = respond(&query("web.docker.cratebay", 28), lookup).unwrap();
+        assert_eq!((reply[3] & 0x0f, &reply[6..8]), (0, &[0u8, 0][..]));
+
+        assert!(respond(&[0x12, 0x34], lookup).is_none());
+        let truncated = query("web.docker.cratebay", TYPE_A);
+        assert!(respond(&truncated[..truncated.len() - 3], lookup).is_none());
+    }
+
+    #[test]
+    fn records_cover_containers_with_loopback_published_ports() {
+        let container = |name: &str, ports: Vec<Port>| ContainerSummary {
+            names: Some(vec![format!("/{}", name)]),
+            ports: Some(ports),
+            ..Default::default()
+        };
+        let port = |ip: Option<&str>, public_port: Option<u16>| Port {
+            ip: ip.map(str::to_string),
+            private_port: 80,
+            public_port,
+            ..Default::default()
+        };
+        let records = records_from(vec![
+            container("Web", vec![port(Some("0.0.0.0"), Some(8080))]),
+            container("local", vec![port(Some("127.0.0.1"), Some(8081))]),
+            // Exposed only, or published on another host address.
+            container("internal", vec![port(None, None)]),
+            container("lan", vec![port(Some("192.168.1.5"), Some(8082))]),
+            container("host-net", Vec::new()),
+        ]);
+        assert_eq!(
+            records,
+            HashMap::from([
+                ("web".to_string(), Ipv4Addr::LOCALHOST),
+                ("local".to_string(), Ipv4Addr::LOCALHOST),
+            ])
+        );
+    }
+}
@@ -2,12 +2,20 @@
 //!
 //! All Docker operations use `bollard` with `Arc<Docker>` for shared access.
 //! Supports multi-platform socket detection.
-//! Only connects to the CrateBay built-in runtime — external Docker daemons
-//! (Colima, Docker Desktop, OrbStack, Podman) are not attempted.
+//! Connects to the CrateBay built-in runtime unless a registered host
+//! (`cratebay host use`, `--host`), `DOCKER_HOST` or an active Docker
+//! context ([`crate::docker_context`]) names another engine.
+//! External Docker daemons (Colima, Docker Desktop, OrbStack, Podman) are
+//! never probed on their own.
 
 use bollard::Docker;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+use crate::config::{Config, HostSettings};
+use crate::docker_context;
+use crate::docker_ssh;
+use crate::docker_tls;
 use crate::error::AppError;
 use crate::runtime;
 
@@ -16,10 +24,13 @@ enum DockerHostTarget {
     UnixSocket(String),
     NamedPipe(String),
     Http(String),
+    /// `ssh://` URL, tunnelled by [`crate::docker_ssh`].
+    Ssh(String),
 }
 
 const DOCKER_PING_TIMEOUT_SECS: u64 = 5;
 const DOCKER_DEFAULT_TIMEOUT_SECS: u64 = 120;
+const DOCKER_SSH_PING_TIMEOUT_SECS: u64 = 20;
 
 fn parse_docker_host_target(raw: &str) -> Option<DockerHostTarget> {
     let host = raw.trim();
@@ -35,6 +46,10 @@ fn parse_docker_host_target(raw: &str) -> Option<DockerHostTarget> {
         return Some(DockerHostTarget::Http(format!("http://{}", path)));
     }
 
+    if host.starts_with("ssh://") {
+        return Some(DockerHostTarget::Ssh(host.to_string()));
+    }
+
     if host.starts_with("http://") || host.starts_with("https://") {
         return Some(DockerHostTarget::Http(host.to_string()));
     }
@@ -134,31 +149,129 @@ async fn try_connect_target(target: DockerHostTarget) -> Option<Docker> {
             )
             .ok()
         }
+        DockerHostTarget::Ssh(url) => {
+            // The first request also has to open the SSH session.
+            let docker = docker_ssh::connect(&url, DOCKER_SSH_PING_TIMEOUT_SECS)
+                .map_err(|e| tracing::warn!("{}", e))
+                .ok()?;
+            if let Err(e) = docker.ping().await {
+                tracing::warn!("Docker over SSH is not reachable: {}", e);
+                return None;
+            }
+            docker_ssh::connect(&url, DOCKER_DEFAULT_TIMEOUT_SECS).ok()
+        }
+    }
+}
+
+/// Where Docker commands go, as reported by `cratebay system status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EngineMode {
+    /// The CrateBay built-in runtime.
+    BuiltIn,
+    /// A registered host (`--host`, `CRATEBAY_HOST`, `active_host`).
+    Host,
+    /// `DOCKER_HOST` (or `--docker-host` / `docker_host` in config.toml).
+    DockerHost,
+    /// A non-default Docker context.
+    Context,
+}
+
+/// The engine selected by the current flags, environment and config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineSelection {
+    pub mode: EngineMode,
+    /// Host or context name.
+    pub name: Option<String>,
+    pub endpoint: Option<String>,
+}
+
+impl std::fmt::Display for EngineSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self.name.as_deref().unwrap_or("-");
+        match self.mode {
+            EngineMode::BuiltIn => f.write_str("built-in runtime")?,
+            EngineMode::Host => write!(f, "host '{}'", name)?,
+            EngineMode::DockerHost => f.write_str("DOCKER_HOST")?,
+            EngineMode::Context => write!(f, "Docker context '{}'", name)?,
+        }
+        match &self.endpoint {
+            Some(endpoint) => write!(f, " ({})", endpoint),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The engine [`connect`] uses, in the same order, without connecting.
+pub fn selected_engine() -> EngineSelection {
+    let config = Config::load_or_default();
+    if let Some(name) = config.active_host() {
+        let endpoint = config.host(&name).ok().map(|settings| settings.url.clone());
+        return EngineSelection {
+            mode: EngineMode::Host,
+            name: Some(name),
+            endpoint,
+        };
+    }
+    if let Ok(host) = std::env::var("DOCKER_HOST") {
+        if !host.trim().is_empty() {
+            return EngineSelection {
+                mode: EngineMode::DockerHost,
+                name: None,
+                endpoint: Some(host),
+            };
+        }
+    }
+    if let Ok(name) = docker_context::current() {
+        if name != docker_context::DEFAULT_CONTEXT {
+            return EngineSelection {
+                mode: EngineMode::Context,
+                endpoint: docker_context::active_host().ok().flatten(),
+                name: Some(name),
+            };
+        }
+    }
+    EngineSelection {
+        mode: EngineMode::BuiltIn,
+        name: None,
+        endpoint: Some(built_in_endpoint()),
+    }
+}
+
+/// Docker endpoint of the built-in runtime on this platform.
+fn built_in_endpoint() -> String {
+    #[cfg(target_os = "linux")]
+    {
+        runtime::linux::linux_docker_host()
+    }
+    #[cfg(target_os = "windows")]
+    {
+        runtime::windows::windows_docker_host()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        runtime::create_runtime_manager()
+            .docker_socket_path()
+            .to_string_lossy()
+            .to_string()
     }
 }
 
 /// Create a Docker client connection.
 ///
-/// Attempts connections in priority order:
-/// 1. `DOCKER_HOST` environment variable
-/// 2. Built-in runtime socket
-/// 3. Built-in runtime TCP (Linux/Windows)
-/// 4. Bollard local defaults as fallback
+/// Connects to the selected engine ([`connect_selected`]) when there is
+/// one, and fails if it is unreachable rather than silently switching to
+/// another engine. Otherwise attempts, in order:
+/// 1. Built-in runtime socket
+/// 2. Built-in runtime TCP (Linux/Windows)
+/// 3. Bollard local defaults as fallback
 pub async fn connect() -> Result<Docker, AppError> {
-    // 1. DOCKER_HOST environment variable (supports unix/tcp/http/npipe)
-    if let Ok(host) = std::env::var("DOCKER_HOST") {
-        if let Some(target) = parse_docker_host_target(&host) {
-            if let Some(docker) = try_connect_target(target).await {
-                tracing::info!("Connected via DOCKER_HOST");
-                return Ok(docker);
-            }
-            tracing::warn!("DOCKER_HOST is set but Docker is not reachable");
-        } else if !host.trim().is_empty() {
-            tracing::warn!("DOCKER_HOST is set but has an unsupported format");
-        }
+    if let Some(result) = connect_selected().await {
+        return result;
     }
 
-    // 2. Try built-in runtime socket
+    // 1. Try built-in runtime socket
     let runtime_mgr = runtime::create_runtime_manager();
     let runtime_socket = runtime_mgr.docker_socket_path();
     if runtime_socket.exists() {
@@ -185,7 +298,7 @@ pub async fn connect() -> Result<Docker, AppError> {
         }
     }
 
-    // 3. Try built-in runtime TCP endpoint (Linux/Windows)
+    // 2. Try built-in runtime TCP endpoint (Linux/Windows)
     //
     // On Linux and Windows the built-in runtime exposes Docker via a TCP
     // endpoint (hostfwd / WSL localhost forwarding). `docker_socket_path()`
@@ -221,7 +334,7 @@ pub async fn connect() -> Result<Docker, AppError> {
         }
     }
 
-    // 4. Try local defaults as a final fallback
+    // 3. Try local defaults as a final fallback
     tracing::debug!("Trying Docker local defaults");
     let docker = Docker::connect_with_local_defaults()?;
     if !crate::docker::is_available(&docker).await {
@@ -232,6 +345,121 @@ pub async fn connect() -> Result<Docker, AppError> {
     Ok(docker)
 }
 
+/// Connect to the engine the user selected: the active registered host,
+/// else `DOCKER_HOST` (local, remote or `ssh://`), else the active Docker
+/// context.
+///
+/// Returns `None` when none is set. The selection is an explicit choice,
+/// so failing to reach it is an error rather than a reason to fall back to
+/// the built-in runtime.
+pub async fn connect_selected() -> Option<Result<Docker, AppError>> {
+    if let Some(result) = connect_active_host().await {
+        return Some(result);
+    }
+    match std::env::var("DOCKER_HOST") {
+        Ok(host) if !host.trim().is_empty() => Some(connect_host(&host).await),
+        _ => connect_context().await,
+    }
+}
+
+/// Connect to the registered host called `name`.
+pub async fn connect_named(name: &str) -> Result<Docker, AppError> {
+    let config = Config::load()?;
+    let settings = config.host(name)?;
+    let docker = connect_registered(settings).await.map_err(|e| {
+        AppError::Runtime(format!(
+            "Host '{}' ({}) is not reachable: {}",
+            name, settings.url, e
+        ))
+    })?;
+    tracing::info!("Connected via host '{}'", name);
+    Ok(docker)
+}
+
+/// The active registered host; `None` when none is selected.
+async fn connect_active_host() -> Option<Result<Docker, AppError>> {
+    // An unreadable config still honors CRATEBAY_HOST; connect_named then
+    // reports the config error.
+    let name = Config::load_or_default().active_host()?;
+    Some(connect_named(&name).await)
+}
+
+async fn connect_registered(settings: &HostSettings) -> Result<Docker, AppError> {
+    let client = |timeout| {
+        if settings.url.starts_with("ssh://") {
+            let mut target = docker_ssh::SshTarget::parse(&settings.url)?;
+            target.identity_file = settings.identity_file.clone();
+            docker_ssh::connect_target(target, timeout)
+        } else {
+            docker_tls::connect(settings, timeout)
+        }
+    };
+    if !settings.url.starts_with("ssh://") && !settings.uses_tls() {
+        let target = parse_docker_host_target(&settings.url).ok_or_else(|| {
+            AppError::Validation(format!("Unsupported host url: {}", settings.url))
+        })?;
+        return try_connect_target(target)
+            .await
+            .ok_or_else(|| AppError::Runtime("Docker did not answer a ping".to_string()));
+    }
+    // The first request also has to open the SSH session or TLS handshake.
+    client(DOCKER_SSH_PING_TIMEOUT_SECS)?.ping().await?;
+    client(DOCKER_DEFAULT_TIMEOUT_SECS)
+}
+
+async fn connect_host(host: &str) -> Result<Docker, AppError> {
+    let target = parse_docker_host_target(host).ok_or_else(|| {
+        AppError::Validation(format!("DOCKER_HOST has an unsupported format: {}", host))
+    })?;
+    match try_connect_target(target).await {
+        Some(docker) => {
+            tracing::info!("Connected via DOCKER_HOST");
+            Ok(docker)
+        }
+        None => Err(AppError::Runtime(format!(
+            "DOCKER_HOST ({}) is not reachable",
+            host
+        ))),
+    }
+}
+
+/// Connect to the endpoint of the active Docker context; `None` when the
+/// `default` context is active or the Docker config cannot be read.
+async fn connect_context() -> Option<Result<Docker, AppError>> {
+    let name = docker_context::current()
+        .map_err(|e| tracing::debug!("Ignoring Docker contexts: {}", e))
+        .ok()?;
+    if name == docker_context::DEFAULT_CONTEXT {
+        return None;
+    }
+    let host = match docker_context::active_host() {
+        Ok(Some(host)) => host,
+        Ok(None) => {
+            return Some(Err(AppError::Validation(format!(
+                "Docker context '{}' has no Docker endpoint",
+                name
+            ))))
+        }
+        Err(e) => return Some(Err(e)),
+    };
+    let Some(target) = parse_docker_host_target(&host) else {
+        return Some(Err(AppError::Validation(format!(
+            "Docker context '{}' has an unsupported endpoint: {}",
+            name, host
+        ))));
+    };
+    Some(match try_connect_target(target).await {
+        Some(docker) => {
+            tracing::info!("Connected via Docker context '{}'", name);
+            Ok(docker)
+        }
+        None => Err(AppError::Runtime(format!(
+            "Docker context '{}' ({}) is not reachable",
+            name, host
+        ))),
+    })
+}
+
 /// Attempt to connect, returning None if Docker is not available.
 pub async fn try_connect() -> Option<Docker> {
     connect().await.ok()
@@ -245,6 +473,133 @@ pub async fn is_available(docker: &Docker) -> bool {
     )
 }
 
+/// The selected engine's endpoint when [`engine_request`] can reach it:
+/// Unix socket and plain TCP endpoints, which covers the built-in runtime.
+/// SSH and TLS hosts give `None`.
+fn raw_endpoint() -> Option<DockerHostTarget> {
+    let selection = selected_engine();
+    if selection.mode == EngineMode::Host {
+        let config = Config::load_or_default();
+        let name = selection.name.as_deref().unwrap_or_default();
+        if config.host(name).is_ok_and(|settings| settings.uses_tls()) {
+            return None;
+        }
+    }
+    match parse_docker_host_target(selection.endpoint.as_deref()?)? {
+        #[cfg(unix)]
+        target @ DockerHostTarget::UnixSocket(_) => Some(target),
+        DockerHostTarget::Http(url) if url.starts_with("http://") => {
+            Some(DockerHostTarget::Http(url))
+        }
+        _ => None,
+    }
+}
+
+/// Whether [`engine_request`] and [`engine_upload`] reach the selected engine.
+pub(crate) fn raw_requests_supported() -> bool {
+    raw_endpoint().is_some()
+}
+
+/// Send a Docker API request bollard has no method for (e.g. `POST
+/// /build/prune`) to the selected engine ([`selected_engine`]) and return
+/// the status and body. `body` is sent as JSON.
+///
+/// Only Unix socket and plain TCP endpoints are reachable this way, which
+/// covers the built-in runtime; SSH and TLS hosts fail with `Validation`.
+pub(crate) async fn engine_request(
+    method: hyper::Method,
+    path: &str,
+    body: Option<serde_json::Value>,
+) -> Result<(hyper::StatusCode, bytes::Bytes), AppError> {
+    let content_type = body.as_ref().map(|_| "application/json");
+    let body = body.map(|b| b.to_string()).unwrap_or_default();
+    engine_send(method, path, content_type, body).await
+}
+
+/// Stream `file` as the body of a Docker API request bollard can only send
+/// from memory (e.g. `POST /images/create?fromSrc=-`). Same endpoints as
+/// [`engine_request`].
+pub(crate) async fn engine_upload(
+    method: hyper::Method,
+    path: &str,
+    content_type: &str,
+    file: tokio::fs::File,
+) -> Result<(hyper::StatusCode, bytes::Bytes), AppError> {
+    use futures_util::stream;
+    use tokio::io::AsyncReadExt;
+
+    const CHUNK: usize = 256 * 1024;
+    let chunks = stream::try_unfold(file, |mut file| async move {
+        let mut buf = bytes::BytesMut::with_capacity(CHUNK);
+        let read = file.read_buf(&mut buf).await?;
+        Ok::<_, std::io::Error>((read > 0).then(|| (hyper::body::Frame::data(buf.freeze()), file)))
+    });
+    let body = http_body_util::StreamBody::new(chunks);
+    engine_send(method, path, Some(content_type), body).await
+}
+
+async fn engine_send<B>(
+    method: hyper::Method,
+    path: &str,
+    content_type: Option<&str>,
+    body: B,
+) -> Result<(hyper::StatusCode, bytes::Bytes), AppError>
+where
+    B: hyper::body::Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    use http_body_util::BodyExt;
+
+    let unsupported = || {
+        AppError::Validation(format!(
+            "{} is not supported for {}; run the docker CLI against it instead",
+            path,
+            selected_engine()
+        ))
+    };
+    let target = raw_endpoint().ok_or_else(unsupported)?;
+    let mut request = hyper::Request::builder()
+        .method(method)
+        .uri(format!("http://docker{}", path));
+    if let Some(content_type) = content_type {
+        request = request.header(hyper::header::CONTENT_TYPE, content_type);
+    }
+    let request = request
+        .body(body)
+        .map_err(|e| AppError::Runtime(format!("Invalid Docker API request: {}", e)))?;
+
+    let response = match target {
+        #[cfg(unix)]
+        DockerHostTarget::UnixSocket(socket) => {
+            let stream = tokio::net::UnixStream::connect(&socket).await?;
+            docker_ssh::send_over(stream, request, async {}).await?
+        }
+        DockerHostTarget::Http(url) => {
+            let authority = url["http://".len()..].split('/').next().unwrap_or_default();
+            let stream = tokio::net::TcpStream::connect(authority).await?;
+            docker_ssh::send_over(stream, request, async {}).await?
+        }
+        _ => return Err(unsupported()),
+    };
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| AppError::Runtime(format!("Reading Docker API response failed: {}", e)))?
+        .to_bytes();
+    Ok((status, body))
+}
+
+/// The `message` of a Docker API error body, or the body as text.
+pub(crate) fn engine_error_message(body: &[u8]) -> String {
+    serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| String::from_utf8_lossy(body).trim().to_string())
+}
+
 /// Get Docker version information.
 pub async fn version(docker: &Docker) -> Result<bollard::system::Version, AppError> {
     docker.version().await.map_err(AppError::Docker)
@@ -284,6 +639,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_docker_host_target_supports_ssh() {
+        let target = parse_docker_host_target("ssh://me@build.example");
+        assert!(
+            matches!(target, Some(DockerHostTarget::Ssh(url)) if url == "ssh://me@build.example")
+        );
+    }
+
+    #[test]
+    fn engine_selection_names_the_engine() {
+        let host = EngineSelection {
+            mode: EngineMode::Host,
+            name: Some("prod".to_string()),
+            endpoint: Some("ssh://deploy@prod".to_string()),
+        };
+        assert_eq!(host.to_string(), "host 'prod' (ssh://deploy@prod)");
+        let built_in = EngineSelection {
+            mode: EngineMode::BuiltIn,
+            name: None,
+            endpoint: None,
+        };
+        assert_eq!(built_in.to_string(), "built-in runtime");
+    }
+
     #[test]
     fn parse_docker_host_target_supports_npipe() {
         let target = parse_docker_host_target("npipe:////./pipe/docker_engine");
@@ -0,0 +1,257 @@
+//! Docker CLI contexts (`docker context`).
+//!
+//! Contexts live under the Docker config directory (`DOCKER_CONFIG`, or
+//! `~/.docker`): `contexts/meta/<sha256 of name>/meta.json` holds each
+//! context's endpoint, and `currentContext` in `config.json` names the
+//! active one. `DOCKER_CONTEXT` overrides it for one process. The implicit
+//! `default` context means "no context": CrateBay then uses `DOCKER_HOST`
+//! or its built-in runtime.
+//!
+//! Only the Docker endpoint's `Host` is used. Contexts that need TLS client
+//! certificates are listed but connecting to them fails.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::storage;
+
+/// Name of the implicit context.
+pub const DEFAULT_CONTEXT: &str = "default";
+
+/// A context as listed by `cratebay docker context ls`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerContext {
+    pub name: String,
+    pub description: Option<String>,
+    /// Docker endpoint; `None` for the default context.
+    pub host: Option<String>,
+    /// Whether this is the active context.
+    pub current: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContextMeta {
+    name: String,
+    #[serde(default)]
+    metadata: Option<ContextMetadata>,
+    #[serde(default)]
+    endpoints: std::collections::HashMap<String, Endpoint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContextMetadata {
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Endpoint {
+    #[serde(default)]
+    host: Option<String>,
+}
+
+/// Docker CLI config directory: `DOCKER_CONFIG`, else `~/.docker`.
+pub fn config_dir() -> Result<PathBuf, AppError> {
+    match std::env::var_os("DOCKER_CONFIG") {
+        Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir)),
+        _ => Ok(storage::home_dir()?.join(".docker")),
+    }
+}
+
+/// Name of the active context: `DOCKER_CONTEXT`, else `currentContext`
+/// in `config.json`, else `default`.
+pub fn current() -> Result<String, AppError> {
+    if let Ok(name) = std::env::var("DOCKER_CONTEXT") {
+        if !name.trim().is_empty() {
+            return Ok(name.trim().to_string());
+        }
+    }
+    current_in(&config_dir()?)
+}
+
+/// All contexts, `default` first, then by name.
+pub fn list() -> Result<Vec<DockerContext>, AppError> {
+    let current = current()?;
+    list_in(&config_dir()?, &current)
+}
+
+/// Endpoint of the active context, or `None` for `default`. Fails when
+/// the active context does not exist.
+pub fn active_host() -> Result<Option<String>, AppError> {
+    let name = current()?;
+    if name == DEFAULT_CONTEXT {
+        return Ok(None);
+    }
+    find(&config_dir()?, &name).map(|context| context.host)
+}
+
+/// Make `name` the active context by writing `currentContext` to
+/// `config.json`, keeping its other settings. `default` clears it.
+pub fn use_context(name: &str) -> Result<(), AppError> {
+    let dir = config_dir()?;
+    if name != DEFAULT_CONTEXT {
+        find(&dir, name)?;
+    }
+    set_current_in(&dir, name)
+}
+
+fn current_in(dir: &Path) -> Result<String, AppError> {
+    let config = read_config(dir)?;
+    Ok(config
+        .get("currentContext")
+        .and_then(|v| v.as_str())
+        .filter(|name| !name.is_empty())
+        .unwrap_or(DEFAULT_CONTEXT)
+        .to_string())
+}
+
+fn list_in(dir: &Path, current: &str) -> Result<Vec<DockerContext>, AppError> {
+    let mut contexts = vec![DockerContext {
+        name: DEFAULT_CONTEXT.to_string(),
+        description: Some("DOCKER_HOST or the CrateBay runtime".to_string()),
+        host: None,
+        current: current == DEFAULT_CONTEXT,
+    }];
+    let meta_dir = dir.join("contexts").join("meta");
+    let entries = match std::fs::read_dir(&meta_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(contexts),
+        Err(e) => return Err(e.into()),
+    };
+    let mut named = Vec::new();
+    for entry in entries {
+        let path = entry?.path().join("meta.json");
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let meta: ContextMeta = match serde_json::from_str(&content) {
+            Ok(meta) => meta,
+            Err(e) => {
+                tracing::warn!("Skipping Docker context {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        named.push(DockerContext {
+            current: meta.name == current,
+            description: meta
+                .metadata
+                .and_then(|m| m.description)
+                .filter(|d| !d.is_empty()),
+            host: meta
+                .endpoints
+                .get("docker")
+                .and_then(|e| e.host.clone())
+                .filter(|h| !h.is_empty()),
+            name: meta.name,
+        });
+    }
+    named.sort_by(|a, b| a.name.cmp(&b.name));
+    contexts.extend(named);
+    Ok(contexts)
+}
+
+fn find(dir: &Path, name: &str) -> Result<DockerContext, AppError> {
+    list_in(dir, name)?
+        .into_iter()
+        .find(|context| context.name == name)
+        .ok_or_else(|| AppError::NotFound {
+            entity: "Docker context".to_string(),
+            id: name.to_string(),
+        })
+}
+
+fn read_config(dir: &Path) -> Result<serde_json::Map<String, serde_json::Value>, AppError> {
+    let path = dir.join("config.json");
+    match std::fs::read_to_string(&path) {
+        Ok(content) if content.trim().is_empty() => Ok(Default::default()),
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| AppError::Validation(format!("Invalid {}: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Default::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn set_current_in(dir: &Path, name: &str) -> Result<(), AppError> {
+    let mut config = read_config(dir)?;
+    if name == DEFAULT_CONTEXT {
+        config.remove("currentContext");
+    } else {
+        config.insert(
+            "currentContext".to_string(),
+            serde_json::Value::String(name.to_string()),
+        );
+    }
+    let content = serde_json::to_vec_pretty(&config)?;
+    storage::write_atomic(&dir.join("config.json"), &content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_context(dir: &Path, hash: &str, meta: &str) {
+        let path = dir.join("contexts").join("meta").join(hash);
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("meta.json"), meta).unwrap();
+    }
+
+    #[test]
+    fn lists_contexts_from_meta_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_context(
+            dir.path(),
+            "b1",
+            r#"{"Name":"remote","Metadata":{},"Endpoints":{"docker":{"Host":"tcp://10.0.0.5:2375","SkipTLSVerify":false}}}"#,
+        );
+        write_context(
+            dir.path(),
+            "a2",
+            r#"{"Name":"colima","Metadata":{"Description":"colima"},"Endpoints":{"docker":{"Host":"unix:///Users/me/.colima/default/docker.sock"}}}"#,
+        );
+        write_context(dir.path(), "c3", "not json");
+
+        let contexts = list_in(dir.path(), "colima").unwrap();
+        let names: Vec<_> = contexts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["default", "colima", "remote"]);
+        assert!(contexts[1].current && !contexts[0].current);
+        assert_eq!(contexts[1].description.as_deref(), Some("colima"));
+        assert_eq!(contexts[2].host.as_deref(), Some("tcp://10.0.0.5:2375"));
+        assert_eq!(contexts[2].description, None);
+
+        assert!(matches!(
+            find(dir.path(), "missing"),
+            Err(AppError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn switching_context_keeps_other_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(current_in(dir.path()).unwrap(), DEFAULT_CONTEXT);
+
+        std::fs::write(
+            dir.path().join("config.json"),
+            r#"{"auths":{"ghcr.io":{}},"currentContext":"colima"}"#,
+        )
+        .unwrap();
+        assert_eq!(current_in(dir.path()).unwrap(), "colima");
+
+        set_current_in(dir.path(), "remote").unwrap();
+        assert_eq!(current_in(dir.path()).unwrap(), "remote");
+        set_current_in(dir.path(), DEFAULT_CONTEXT).unwrap();
+        assert_eq!(current_in(dir.path()).unwrap(), DEFAULT_CONTEXT);
+
+        let config = read_config(dir.path()).unwrap();
+        assert!(config.contains_key("auths"));
+        assert!(!config.contains_key("currentContext"));
+    }
+}
@@ -0,0 +1,413 @@
+//! Docker API over SSH (`DOCKER_HOST=ssh://user@host`).
+//!
+//! bollard has no SSH transport, so this plugs a custom one in: every
+//! request spawns `ssh <host> docker system dial-stdio` (the same helper
+//! the Docker CLI uses) and speaks HTTP/1.1 over the process's stdin and
+//! stdout. Hijacked connections (exec, attach) keep their process until the
+//! stream closes.
+//!
+//! Authentication is left to the system `ssh` client: keys, the agent and
+//! `~/.ssh/config` apply, plus the identity file of a registered host. It
+//! runs in batch mode, so hosts that need a password or an unknown host key
+//! prompt fail instead of hanging.
+//!
+//! On Unix the ssh processes share one connection per host through
+//! OpenSSH multiplexing (`ControlMaster=auto`): the first request opens
+//! it, later ones skip the handshake, and it stays up for a minute after
+//! the last one closes.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bollard::{BollardRequest, Docker};
+use hyper::body::{Body, Incoming};
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+use tokio::process::Command;
+
+use crate::error::AppError;
+
+/// `ConnectTimeout` passed to ssh, in seconds.
+const SSH_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// `ControlPersist` passed to ssh, in seconds.
+const SSH_CONTROL_PERSIST_SECS: u64 = 60;
+
+/// Longest control socket path: the 104-byte `sun_path` on macOS, less the
+/// terminating NUL.
+#[cfg(unix)]
+const MAX_CONTROL_PATH_LEN: usize = 103;
+
+/// Bytes a control socket adds to its directory: `/`, the 40-character
+/// `%C` hash and the `.<16 hex digits>` suffix ssh binds before renaming.
+#[cfg(unix)]
+const CONTROL_SOCKET_NAME_LEN: usize = 1 + 40 + 17;
+
+/// How long to wait for ssh's error output after a failed request.
+const SSH_STDERR_WAIT: Duration = Duration::from_secs(2);
+
+/// Where `ssh://[user@]host[:port][/socket]` points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshTarget {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Remote Docker socket, when not the remote default.
+    pub socket: Option<String>,
+    /// Private key passed with `-i`; only this key is offered.
+    pub identity_file: Option<PathBuf>,
+}
+
+impl SshTarget {
+    /// Parse an `ssh://` Docker host.
+    pub fn parse(raw: &str) -> Result<Self, AppError> {
+        let invalid = |reason: &str| {
+            AppError::Validation(format!("Invalid SSH Docker host '{}': {}", raw, reason))
+        };
+        let rest = raw
+            .trim()
+            .strip_prefix("ssh://")
+            .ok_or_else(|| invalid("expected ssh://[user@]host[:port]"))?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, ""),
+        };
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, host_port)) if !user.is_empty() => (Some(user.to_string()), host_port),
+            Some(_) => return Err(invalid("empty user")),
+            None => (None, authority),
+        };
+        let (host, port) = match host_port.rsplit_once(':') {
+            // Bracketed IPv6 literal without a port.
+            _ if host_port.starts_with('[') && host_port.ends_with(']') => (host_port, None),
+            Some((host, port)) => (
+                host,
+                Some(port.parse::<u16>().map_err(|_| invalid("bad port"))?),
+            ),
+            None => (host_port, None),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() || host.starts_with('-') {
+            return Err(invalid("missing host"));
+        }
+        Ok(Self {
+            user,
+            host: host.to_string(),
+            port,
+            socket: (!path.is_empty() && path != "/").then(|| path.to_string()),
+            identity_file: None,
+        })
+    }
+
+    /// Arguments for the `ssh` command.
+    fn ssh_args(&self) -> Vec<String> {
+        self.args_with(control_dir().as_deref())
+    }
+
+    /// Arguments for the `ssh` command, multiplexed through a control
+    /// socket in `control_dir` when given.
+    fn args_with(&self, control_dir: Option<&Path>) -> Vec<String> {
+        let mut args = vec![
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            format!("ConnectTimeout={}", SSH_CONNECT_TIMEOUT_SECS),
+            "-T".to_string(),
+        ];
+        if let Some(dir) = control_dir {
+            args.extend([
+                "-o".to_string(),
+                "ControlMaster=auto".to_string(),
+                "-o".to_string(),
+                // %C: hash of the local host, remote host, port and user.
+                format!("ControlPath={}", dir.join("%C").display()),
+                "-o".to_string(),
+                format!("ControlPersist={}", SSH_CONTROL_PERSIST_SECS),
+            ]);
+        }
+        if let Some(user) = &self.user {
+            args.extend(["-l".to_string(), user.clone()]);
+        }
+        if let Some(port) = self.port {
+            args.extend(["-p".to_string(), port.to_string()]);
+        }
+        if let Some(identity) = &self.identity_file {
+            args.extend([
+                "-i".to_string(),
+                identity.to_string_lossy().into_owned(),
+                "-o".to_string(),
+                "IdentitiesOnly=yes".to_string(),
+            ]);
+        }
+        args.extend([self.host.clone(), "--".to_string(), "docker".to_string()]);
+        // ssh joins the remote command into one string for the remote
+        // shell, so the socket path from the URL must not be parsed by it.
+        if let Some(socket) = &self.socket {
+            args.extend([
+                "--host".to_string(),
+                sh_quote(&format!("unix://{}", socket)),
+            ]);
+        }
+        args.extend(["system".to_string(), "dial-stdio".to_string()]);
+        args
+    }
+}
+
+/// Whether control sockets in `dir` fit in [`MAX_CONTROL_PATH_LEN`].
+#[cfg(unix)]
+fn control_path_fits(dir: &Path) -> bool {
+    dir.as_os_str().len() + CONTROL_SOCKET_NAME_LEN <= MAX_CONTROL_PATH_LEN
+}
+
+/// Quote `value` for a POSIX shell.
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Directory for ssh control sockets: `ssh` next to the runtime's host
+/// sockets, which follow `CRATEBAY_DATA_DIR` and the profile and stay short
+/// (`~/.cratebay/runtime/ssh` by default). `None` (no multiplexing) on
+/// Windows, whose OpenSSH has no control sockets, or when it cannot be
+/// created or its sockets would not fit in `sun_path`.
+fn control_dir() -> Option<PathBuf> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+
+        let dir = crate::runtime::common::host_docker_socket_path()
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| crate::storage::data_dir().join("runtime"))
+            .join("ssh");
+        if !control_path_fits(&dir) {
+            tracing::debug!("No ssh connection sharing: {} is too long", dir.display());
+            return None;
+        }
+        match std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(&dir)
+        {
+            Ok(()) => Some(dir),
+            Err(e) => {
+                tracing::debug!("No ssh connection sharing: {}: {}", dir.display(), e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        None
+    }
+}
+
+/// Docker client tunnelled through `ssh`. `timeout` is bollard's
+/// per-request timeout in seconds.
+pub fn connect(raw: &str, timeout: u64) -> Result<Docker, AppError> {
+    connect_target(SshTarget::parse(raw)?, timeout)
+}
+
+/// Like [`connect`], for an already parsed target.
+pub fn connect_target(target: SshTarget, timeout: u64) -> Result<Docker, AppError> {
+    let target = Arc::new(target);
+    let docker = Docker::connect_with_custom_transport(
+        move |request: BollardRequest| send(Arc::clone(&target), request),
+        Some("http://docker"),
+        timeout,
+        bollard::API_DEFAULT_VERSION,
+    )?;
+    Ok(docker)
+}
+
+/// Send one request over a fresh `ssh ... docker system dial-stdio`.
+async fn send<B>(
+    target: Arc<SshTarget>,
+    request: Request<B>,
+) -> Result<Response<Incoming>, bollard::errors::Error>
+where
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let mut child = Command::new("ssh")
+        .args(target.ssh_args())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| std::io::Error::new(e.kind(), format!("Failed to run ssh: {}", e)))?;
+    let (Some(stdin), Some(stdout), Some(mut stderr)) =
+        (child.stdin.take(), child.stdout.take(), child.stderr.take())
+    else {
+        return Err(std::io::Error::other("ssh stdio is not available").into());
+    };
+    // Drained for the life of the process so ssh never blocks on it, and
+    // kept to explain failures.
+    let stderr = tokio::spawn(async move {
+        let mut output = String::new();
+        let _ = stderr.read_to_string(&mut output).await;
+        output
+    });
+
+    let io = tokio::io::join(stdout, stdin);
+    let closed = async move {
+        let _ = child.wait().await;
+    };
+    match send_over(io, request, closed).await {
+        Ok(response) => Ok(response),
+        Err(e) => {
+            let detail = match tokio::time::timeout(SSH_STDERR_WAIT, stderr).await {
+                Ok(Ok(output)) => output.trim().to_string(),
+                _ => String::new(),
+            };
+            if detail.is_empty() {
+                Err(e)
+            } else {
+                Err(
+                    std::io::Error::other(format!("ssh to {} failed: {}", target.host, detail))
+                        .into(),
+                )
+            }
+        }
+    }
+}
+
+/// Send one request over `io` with HTTP/1.1 and run `closed` once the
+/// connection ends. Shared with the TLS transport.
+pub(crate) async fn send_over<S, B>(
+    io: S,
+    request: Request<B>,
+    closed: impl Future<Output = ()> + Send + 'static,
+) -> Result<Response<Incoming>, bollard::errors::Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(io)).await?;
+    tokio::spawn(async move {
+        // Resolves once the response is read or the connection is handed
+        // to a hijacked stream; the stream then owns the transport.
+        if let Err(e) = connection.with_upgrades().await {
+            tracing::debug!("Docker connection closed: {}", e);
+        }
+        closed.await;
+    });
+    Ok(sender.send_request(origin_form(request)?).await?)
+}
+
+/// bollard builds absolute URIs (`http://docker/v1.47/...`); send the path
+/// with a `Host` header instead.
+fn origin_form<B>(request: Request<B>) -> Result<Request<B>, bollard::errors::Error> {
+    let (mut parts, body) = request.into_parts();
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    parts.uri = path.parse().map_err(http_error)?;
+    parts.headers.insert(
+        hyper::header::HOST,
+        hyper::header::HeaderValue::from_static("docker"),
+    );
+    Ok(Request::from_parts(parts, body))
+}
+
+fn http_error(e: hyper::http::uri::InvalidUri) -> bollard::errors::Error {
+    bollard::errors::Error::HttpClientError { err: e.into() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ssh_hosts() {
+        assert_eq!(
+            SshTarget::parse("ssh://deploy@build.example:2222").unwrap(),
+            SshTarget {
+                user: Some("deploy".to_string()),
+                host: "build.example".to_string(),
+                port: Some(2222),
+                socket: None,
+                identity_file: None,
+            }
+        );
+        let target = SshTarget::parse("ssh://[fd00::1]/run/user/1000/docker.sock").unwrap();
+        assert_eq!(target.host, "fd00::1");
+        assert_eq!(target.user, None);
+        assert_eq!(target.socket.as_deref(), Some("/run/user/1000/docker.sock"));
+
+        assert!(SshTarget::parse("ssh://").is_err());
+        assert!(SshTarget::parse("ssh://@host").is_err());
+        assert!(SshTarget::parse("ssh://host:port").is_err());
+        assert!(SshTarget::parse("ssh://-oProxyCommand=x").is_err());
+        assert!(SshTarget::parse("tcp://host:2375").is_err());
+    }
+
+    #[test]
+    fn ssh_args_run_dial_stdio() {
+        let mut target = SshTarget::parse("ssh://me@host:22/var/run/docker.sock").unwrap();
+        let args = target.args_with(None);
+        assert_eq!(args[..2], ["-o", "BatchMode=yes"]);
+        assert_eq!(
+            args[5..].join(" "),
+            "-l me -p 22 host -- docker --host 'unix:///var/run/docker.sock' system dial-stdio"
+        );
+
+        target.identity_file = Some(PathBuf::from("/home/me/.ssh/lab"));
+        assert_eq!(
+            target.args_with(None)[9..13].join(" "),
+            "-i /home/me/.ssh/lab -o IdentitiesOnly=yes"
+        );
+    }
+
+    #[test]
+    fn ssh_args_quote_the_socket_for_the_remote_shell() {
+        let target = SshTarget::parse("ssh://host/tmp/d.sock;touch${IFS}x'y").unwrap();
+        let args = target.args_with(None);
+        let host = args.iter().position(|a| a == "--host").unwrap();
+        assert_eq!(args[host + 1], r"'unix:///tmp/d.sock;touch${IFS}x'\''y'");
+    }
+
+    #[test]
+    fn ssh_args_share_a_connection() {
+        let target = SshTarget::parse("ssh://host").unwrap();
+        let args = target.args_with(Some(Path::new("/home/me/.cratebay/runtime/ssh")));
+        assert_eq!(
+            args[5..11].join(" "),
+            format!(
+                "-o ControlMaster=auto -o ControlPath=/home/me/.cratebay/runtime/ssh/%C -o ControlPersist={}",
+                SSH_CONTROL_PERSIST_SECS
+            )
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn control_sockets_must_fit_sun_path() {
+        assert!(control_path_fits(Path::new(
+            "/Users/me/.cratebay/runtime/ssh"
+        )));
+        let temp = Path::new(
+            "/var/folders/ab/cdefghijklmnopqrstuvwxyz0123/T/cratebay-runtime-123456789/ssh",
+        );
+        assert!(!control_path_fits(temp));
+    }
+
+    #[test]
+    fn origin_form_strips_scheme_and_host() {
+        let request = Request::get("http://docker/v1.47/containers/json?all=true")
+            .body(())
+            .unwrap();
+        let request = origin_form(request).unwrap();
+        assert_eq!(request.uri().to_string(), "/v1.47/containers/json?all=true");
+        assert_eq!(request.headers()[hyper::header::HOST], "docker");
+    }
+}
@@ -0,0 +1,190 @@
+//! Docker API over TLS (`tcp://host:2376` with client certificates).
+//!
+//! bollard is built without its `ssl` feature, so registered hosts with
+//! `tls_ca` use this custom transport: each request opens a TCP connection,
+//! performs a rustls handshake trusting only the configured CA and, when
+//! set, presents the client certificate — the same material
+//! `docker --tlsverify` takes.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use bollard::{BollardRequest, Docker};
+use hyper::body::{Body, Incoming};
+use hyper::{Request, Response};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+
+use crate::config::HostSettings;
+use crate::docker_ssh;
+use crate::error::AppError;
+
+/// Default port of a TLS-protected Docker daemon.
+const DOCKER_TLS_PORT: u16 = 2376;
+
+struct TlsTarget {
+    host: String,
+    port: u16,
+    server_name: ServerName<'static>,
+    connector: TlsConnector,
+}
+
+/// Docker client for a host with `tls_ca` set. `timeout` is bollard's
+/// per-request timeout in seconds.
+pub fn connect(settings: &HostSettings, timeout: u64) -> Result<Docker, AppError> {
+    let (host, port) = host_port(&settings.url)?;
+    let server_name = ServerName::try_from(host.clone())
+        .map_err(|_| AppError::Validation(format!("Invalid TLS server name: {}", host)))?;
+    let target = Arc::new(TlsTarget {
+        host,
+        port,
+        server_name,
+        connector: TlsConnector::from(Arc::new(client_config(settings)?)),
+    });
+    let docker = Docker::connect_with_custom_transport(
+        move |request: BollardRequest| send(Arc::clone(&target), request),
+        Some("https://docker"),
+        timeout,
+        bollard::API_DEFAULT_VERSION,
+    )?;
+    Ok(docker)
+}
+
+/// `host` and `port` of `tcp://host[:port]` or `https://host[:port]`.
+fn host_port(url: &str) -> Result<(String, u16), AppError> {
+    let invalid = |reason: &str| {
+        AppError::Validation(format!("Invalid TLS Docker host '{}': {}", url, reason))
+    };
+    let rest = url
+        .trim()
+        .strip_prefix("tcp://")
+        .or_else(|| url.trim().strip_prefix("https://"))
+        .ok_or_else(|| invalid("expected tcp:// or https://"))?;
+    let authority = rest.split('/').next().unwrap_or_default();
+    let (host, port) = match authority.rsplit_once(':') {
+        _ if authority.starts_with('[') && authority.ends_with(']') => (authority, None),
+        Some((host, port)) => (
+            host,
+            Some(port.parse::<u16>().map_err(|_| invalid("bad port"))?),
+        ),
+        None => (authority, None),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err(invalid("missing host"));
+    }
+    Ok((host.to_string(), port.unwrap_or(DOCKER_TLS_PORT)))
+}
+
+/// rustls configuration from the host's CA, certificate and key.
+fn client_config(settings: &HostSettings) -> Result<rustls::ClientConfig, AppError> {
+    let ca = settings
+        .tls_ca
+        .as_deref()
+        .ok_or_else(|| AppError::Validation("TLS needs tls_ca".to_string()))?;
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in read_certs(ca)? {
+        roots.add(cert).map_err(|e| tls_error(ca, e))?;
+    }
+    let builder = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .map_err(|e| AppError::Runtime(format!("TLS setup failed: {}", e)))?
+    .with_root_certificates(roots);
+    match (settings.tls_cert.as_deref(), settings.tls_key.as_deref()) {
+        (Some(cert), Some(key)) => {
+            let chain = read_certs(cert)?;
+            let key = PrivateKeyDer::from_pem_file(key).map_err(|e| tls_error(key, e))?;
+            builder
+                .with_client_auth_cert(chain, key)
+                .map_err(|e| tls_error(cert, e))
+        }
+        _ => Ok(builder.with_no_client_auth()),
+    }
+}
+
+fn read_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, AppError> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| tls_error(path, e))?;
+    if certs.is_empty() {
+        return Err(AppError::Validation(format!(
+            "{} contains no certificates",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+fn tls_error(path: &Path, e: impl std::fmt::Display) -> AppError {
+    AppError::Validation(format!("Invalid TLS file {}: {}", path.display(), e))
+}
+
+/// Send one request over a fresh TLS connection.
+async fn send<B>(
+    target: Arc<TlsTarget>,
+    request: Request<B>,
+) -> Result<Response<Incoming>, bollard::errors::Error>
+where
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let tcp = TcpStream::connect((target.host.as_str(), target.port)).await?;
+    let stream = target
+        .connector
+        .connect(target.server_name.clone(), tcp)
+        .await
+        .map_err(|e| {
+            std::io::Error::new(
+                e.kind(),
+                format!("TLS handshake with {} failed: {}", target.host, e),
+            )
+        })?;
+    docker_ssh::send_over(stream, request, async {}).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tls_hosts() {
+        assert_eq!(
+            host_port("tcp://ci.example:2377").unwrap(),
+            ("ci.example".to_string(), 2377)
+        );
+        assert_eq!(
+            host_port("https://[fd00::1]").unwrap(),
+            ("fd00::1".to_string(), DOCKER_TLS_PORT)
+        );
+        assert!(host_port("tcp://:2376").is_err());
+        assert!(host_port("tcp://host:tls").is_err());
+        assert!(host_port("ssh://host").is_err());
+    }
+
+    #[test]
+    fn client_config_requires_readable_certificates() {
+        let dir = tempfile::tempdir().unwrap();
+        let ca = dir.path().join("ca.pem");
+        std::fs::write(&ca, "not a certificate").unwrap();
+        let settings = HostSettings {
+            url: "tcp://ci.example:2376".to_string(),
+            tls_ca: Some(ca),
+            ..Default::default()
+        };
+        assert!(matches!(
+            client_config(&settings),
+            Err(AppError::Validation(_))
+        ));
+
+        let settings = HostSettings {
+            tls_ca: Some(dir.path().join("missing.pem")),
+            ..settings
+        };
+        assert!(client_config(&settings).is_err());
+    }
+}
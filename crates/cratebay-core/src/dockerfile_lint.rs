@@ -0,0 +1,426 @@
+//! Dockerfile lint pass run before `image build`.
+//!
+//! A small parser splits the Dockerfile into instructions the way the
+//! builder does (`# escape=` directive, line continuations, comments inside
+//! continuations, `<<EOF` heredocs), and a few rules look for what makes
+//! images worse without failing the build:
+//!
+//! - `latest-tag`: a base image without a tag or with `:latest`
+//! - `missing-user` / `root-user`: the built stage runs as root
+//! - `apt-lists-not-cleaned`: `apt-get install` leaves the package lists in
+//!   the layer
+//!
+//! Unknown instructions and a missing `FROM` are errors, since the build
+//! would fail on them anyway.
+
+use std::path::PathBuf;
+
+use crate::error::AppError;
+use crate::models::{DockerfileLint, ImageBuildRequest, LintFinding, LintSeverity};
+
+const INSTRUCTIONS: &[&str] = &[
+    "ADD",
+    "ARG",
+    "CMD",
+    "COPY",
+    "ENTRYPOINT",
+    "ENV",
+    "EXPOSE",
+    "FROM",
+    "HEALTHCHECK",
+    "LABEL",
+    "MAINTAINER",
+    "ONBUILD",
+    "RUN",
+    "SHELL",
+    "STOPSIGNAL",
+    "USER",
+    "VOLUME",
+    "WORKDIR",
+];
+
+/// One logical instruction.
+#[derive(Debug)]
+struct Instruction {
+    /// 1-based line the instruction starts on.
+    line: usize,
+    /// Upper-cased keyword.
+    keyword: String,
+    /// Arguments with continuations joined and heredoc bodies appended.
+    args: String,
+}
+
+/// A build stage: its `FROM`, name and last `USER`.
+struct Stage<'a> {
+    from: &'a Instruction,
+    base: String,
+    name: Option<String>,
+    user: Option<&'a Instruction>,
+}
+
+fn parse(source: &str) -> Vec<Instruction> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut escape = '\\';
+    let mut i = 0;
+
+    // Parser directives are `# key=value` comments before anything else.
+    while let Some(rest) = lines.get(i).and_then(|l| l.trim().strip_prefix('#')) {
+        let Some((key, value)) = rest.split_once('=') else {
+            break;
+        };
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            break;
+        }
+        if key.eq_ignore_ascii_case("escape") {
+            if let Some(c) = value.trim().chars().next() {
+                escape = c;
+            }
+        }
+        i += 1;
+    }
+
+    let mut instructions = Vec::new();
+    while i < lines.len() {
+        let start = i;
+        let mut line = lines[i];
+        i += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let mut text = String::new();
+        loop {
+            let Some(head) = line.trim_end().strip_suffix(escape) else {
+                text.push_str(line);
+                break;
+            };
+            text.push_str(head);
+            text.push(' ');
+            // Comments and blank lines inside a continuation are dropped.
+            let next = lines[i.min(lines.len())..]
+                .iter()
+                .position(|l| !l.trim().is_empty() && !l.trim().starts_with('#'));
+            match next {
+                Some(offset) => {
+                    line = lines[i + offset];
+                    i += offset + 1;
+                }
+                None => {
+                    i = lines.len();
+                    break;
+                }
+            }
+        }
+
+        let text = text.trim();
+        let (keyword, args) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let mut args = args.trim().to_string();
+        for (word, strip_tabs) in heredoc_words(&args) {
+            while i < lines.len() {
+                let body = lines[i];
+                i += 1;
+                let cmp = if strip_tabs {
+                    body.trim_start_matches('\t')
+                } else {
+                    body
+                };
+                if cmp.trim_end() == word {
+                    break;
+                }
+                args.push('\n');
+                args.push_str(body);
+            }
+        }
+        instructions.push(Instruction {
+            line: start + 1,
+            keyword: keyword.to_ascii_uppercase(),
+            args,
+        });
+    }
+    instructions
+}
+
+/// Heredoc terminators opened by `args` (`<<EOF`, `<<-"EOF"`), and whether
+/// their bodies may indent with tabs.
+fn heredoc_words(args: &str) -> Vec<(String, bool)> {
+    let mut words = Vec::new();
+    for (pos, _) in args.match_indices("<<") {
+        let rest = &args[pos + 2..];
+        let (rest, strip_tabs) = match rest.strip_prefix('-') {
+            Some(rest) => (rest, true),
+            None => (rest, false),
+        };
+        let rest = rest.trim_start_matches(['"', '\'']);
+        let word: String = rest
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if word.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            words.push((word, strip_tabs));
+        }
+    }
+    words
+}
+
+/// Base image and stage name of a `FROM` (flags like `--platform` skipped).
+fn parse_from(args: &str) -> (String, Option<String>) {
+    let mut words = args.split_whitespace().filter(|w| !w.starts_with("--"));
+    let image = words.next().unwrap_or_default().to_string();
+    let name = match (words.next(), words.next()) {
+        (Some(kw), Some(name)) if kw.eq_ignore_ascii_case("as") => Some(name.to_ascii_lowercase()),
+        _ => None,
+    };
+    (image, name)
+}
+
+/// Why `image` is not pinned, if it is not.
+pub(crate) fn unpinned(image: &str) -> Option<&'static str> {
+    if image.contains(['$', '@']) || image.eq_ignore_ascii_case("scratch") {
+        return None;
+    }
+    let name = image.rsplit('/').next().unwrap_or(image);
+    match name.split_once(':') {
+        None => Some("has no tag, so it resolves to `latest`"),
+        Some((_, "latest")) => Some("uses the `latest` tag"),
+        Some(_) => None,
+    }
+}
+
+fn is_root(user: &str) -> bool {
+    let name = user.split(':').next().unwrap_or_default().trim();
+    name == "root" || name == "0"
+}
+
+/// Whether a `RUN` installs packages with apt.
+fn installs_apt_packages(args: &str) -> bool {
+    let words: Vec<&str> = args
+        .split(|c: char| c.is_whitespace() || matches!(c, ';' | '&' | '|' | '(' | ')'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    words.iter().enumerate().any(|(i, w)| {
+        (*w == "apt-get" || *w == "apt")
+            && words[i + 1..].iter().find(|w| !w.starts_with('-')) == Some(&"install")
+    })
+}
+
+fn finding(
+    findings: &mut Vec<LintFinding>,
+    ins: &Instruction,
+    rule: &str,
+    severity: LintSeverity,
+    message: String,
+) {
+    findings.push(LintFinding {
+        rule: rule.to_string(),
+        severity,
+        line: ins.line,
+        instruction: ins.keyword.clone(),
+        message,
+    });
+}
+
+/// Lint Dockerfile `source`. `target` picks the stage that is built (the
+/// last one by default) for the user rules.
+pub fn lint(source: &str, target: Option<&str>) -> Vec<LintFinding> {
+    let instructions = parse(source);
+    let mut findings = Vec::new();
+    let mut stages: Vec<Stage> = Vec::new();
+
+    for ins in &instructions {
+        match ins.keyword.as_str() {
+            "FROM" => {
+                let (base, name) = parse_from(&ins.args);
+                let is_stage = stages
+                    .iter()
+                    .any(|s| s.name.as_deref() == Some(&*base.to_ascii_lowercase()));
+                if let Some(reason) = unpinned(&base).filter(|_| !is_stage) {
+                    finding(
+                        &mut findings,
+                        ins,
+                        "latest-tag",
+                        LintSeverity::Warning,
+                        format!(
+                            "`{}` {}; pin a version so rebuilds are reproducible",
+                            base, reason
+                        ),
+                    );
+                }
+                stages.push(Stage {
+                    from: ins,
+                    base,
+                    name,
+                    user: None,
+                });
+            }
+            "USER" => {
+                if let Some(stage) = stages.last_mut() {
+                    stage.user = Some(ins);
+                }
+            }
+            // A cache mount on /var/lib/apt keeps the lists out of the layer too.
+            "RUN" if installs_apt_packages(&ins.args) && !ins.args.contains("/var/lib/apt") => {
+                finding(
+                    &mut findings,
+                    ins,
+                    "apt-lists-not-cleaned",
+                    LintSeverity::Warning,
+                    "`apt-get install` leaves /var/lib/apt/lists in the layer; end the same RUN with `&& rm -rf /var/lib/apt/lists/*`".to_string(),
+                )
+            }
+            keyword if !INSTRUCTIONS.contains(&keyword) => finding(
+                &mut findings,
+                ins,
+                "unknown-instruction",
+                LintSeverity::Error,
+                format!("Unknown instruction `{}`", keyword),
+            ),
+            _ => {}
+        }
+    }
+
+    let built = target
+        .map(str::to_ascii_lowercase)
+        .and_then(|t| stages.iter().position(|s| s.name.as_deref() == Some(&*t)))
+        .or_else(|| stages.len().checked_sub(1));
+    match built {
+        None => findings.push(LintFinding {
+            rule: "missing-from".to_string(),
+            severity: LintSeverity::Error,
+            line: instructions.first().map(|i| i.line).unwrap_or(1),
+            instruction: String::new(),
+            message: "The Dockerfile has no FROM instruction".to_string(),
+        }),
+        Some(mut index) => {
+            let from = stages[index].from;
+            // A stage built on an earlier stage inherits its USER.
+            let user = loop {
+                let stage = &stages[index];
+                if let Some(user) = stage.user {
+                    break Some(user);
+                }
+                let base = stage.base.to_ascii_lowercase();
+                match stages[..index]
+                    .iter()
+                    .rposition(|s| s.name.as_deref() == Some(&*base))
+                {
+                    Some(parent) => index = parent,
+                    None => break None,
+                }
+            };
+            match user {
+                None => finding(
+                    &mut findings,
+                    from,
+                    "missing-user",
+                    LintSeverity::Warning,
+                    "The image runs as root unless its base sets a user; add a USER instruction"
+                        .to_string(),
+                ),
+                Some(user) if is_root(&user.args) => finding(
+                    &mut findings,
+                    user,
+                    "root-user",
+                    LintSeverity::Warning,
+                    "The image runs as root; switch to an unprivileged USER at the end".to_string(),
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+
+    findings.sort_by_key(|f| f.line);
+    findings
+}
+
+/// Dockerfile of `request`: its `dockerfile` or `<context_dir>/Dockerfile`.
+pub fn dockerfile_path(request: &ImageBuildRequest) -> PathBuf {
+    match &request.dockerfile {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(&request.context_dir).join("Dockerfile"),
+    }
+}
+
+/// Lint the Dockerfile `request` would build.
+pub fn lint_build(request: &ImageBuildRequest) -> Result<DockerfileLint, AppError> {
+    let path = dockerfile_path(request);
+    let source = std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => {
+            AppError::Validation(format!("Dockerfile not found: {}", path.display()))
+        }
+        _ => AppError::Io(e),
+    })?;
+    Ok(DockerfileLint {
+        dockerfile: path.display().to_string(),
+        findings: lint(&source, request.target.as_deref()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(source: &str) -> Vec<(usize, String)> {
+        lint(source, None)
+            .into_iter()
+            .map(|f| (f.line, f.rule))
+            .collect()
+    }
+
+    #[test]
+    fn parser_joins_continuations_and_heredocs() {
+        let source = "# syntax=docker/dockerfile:1\n# escape=`\nFROM alpine:3.20\nRUN apk add `\n  # a comment\n  curl\nRUN <<EOF\napt-get install -y git\nEOF\nUSER app\n";
+        let instructions = parse(source);
+        let keywords: Vec<_> = instructions
+            .iter()
+            .map(|i| (i.line, i.keyword.as_str()))
+            .collect();
+        assert_eq!(
+            keywords,
+            [(3, "FROM"), (4, "RUN"), (7, "RUN"), (10, "USER")]
+        );
+        assert_eq!(instructions[1].args, "apk add    curl");
+        assert!(instructions[2].args.ends_with("\napt-get install -y git"));
+    }
+
+    #[test]
+    fn clean_dockerfile_has_no_findings() {
+        let source = "FROM debian:12-slim\nRUN apt-get update \\\n && apt-get install -y --no-install-recommends curl \\\n && rm -rf /var/lib/apt/lists/*\nUSER 1000:1000\n";
+        assert!(rules(source).is_empty());
+    }
+
+    #[test]
+    fn flags_unpinned_images_root_and_apt_lists() {
+        let source = "FROM golang AS build\nRUN apt-get -y install git\nFROM build AS test\nFROM node:latest\nUSER root\nFROM --platform=$BUILDPLATFORM alpine@sha256:abc\n";
+        assert_eq!(
+            rules(source),
+            [
+                (1, "latest-tag".to_string()),
+                (2, "apt-lists-not-cleaned".to_string()),
+                (4, "latest-tag".to_string()),
+                (6, "missing-user".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn user_rules_follow_the_target_stage_and_its_parents() {
+        let source =
+            "FROM rust:1.80 AS build\nUSER nobody\nFROM build AS dev\nFROM alpine:3.20\nUSER 0\n";
+        let built =
+            |target| -> Vec<String> { lint(source, target).into_iter().map(|f| f.rule).collect() };
+        assert_eq!(built(None), ["root-user"]);
+        assert!(built(Some("dev")).is_empty());
+    }
+
+    #[test]
+    fn unknown_instructions_and_missing_from_are_errors() {
+        let findings = lint("RUNN echo hi\n", None);
+        assert_eq!(findings.len(), 2);
+        assert!(findings
+            .iter()
+            .all(|f| f.severity == LintSeverity::Error && f.line == 1));
+        assert_eq!(lint("", None)[0].rule, "missing-from");
+    }
+}
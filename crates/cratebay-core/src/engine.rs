@@ -4,9 +4,11 @@
 //! CLI to ensure a responsive Docker client backed by the CrateBay built-in
 //! runtime.
 //!
+//! - Use `DOCKER_HOST` or the active Docker context when one is selected
 //! - Reuse an already-running built-in runtime first
 //! - Otherwise start/provision the built-in runtime
-//! - Use a cross-process lock to avoid concurrent provision/start (GUI + CLI)
+//! - Use a cross-process lock to avoid concurrent provision/start (GUI + CLI),
+//!   plus the runtime operation lock ([`crate::runtime::ops`])
 
 use std::fs::{File, OpenOptions};
 use std::path::{Path, PathBuf};
@@ -50,7 +52,9 @@ impl Default for EnsureOptions {
 /// Ensure a responsive Docker client, starting the built-in runtime if needed.
 ///
 /// Only the CrateBay built-in runtime is used — external Docker daemons
-/// (Colima, Docker Desktop, OrbStack, Podman, etc.) are not attempted.
+/// (Colima, Docker Desktop, OrbStack, Podman, etc.) are not attempted —
+/// unless `DOCKER_HOST` or a Docker context selects one
+/// ([`crate::docker::connect_selected`]).
 pub async fn ensure_docker(
     runtime: &dyn RuntimeManager,
     options: EnsureOptions,
@@ -64,6 +68,12 @@ pub async fn ensure_docker(
         on_provision_progress,
     } = options;
 
+    // 0. A selected engine (DOCKER_HOST, Docker context) replaces the
+    //    built-in runtime.
+    if let Some(result) = crate::docker::connect_selected().await {
+        return result.map(Arc::new);
+    }
+
     // 1. Fast path: try connecting to an already-running built-in runtime.
     if let Some(docker) = try_connect_builtin(runtime).await {
         return Ok(Arc::new(docker));
@@ -71,9 +81,12 @@ pub async fn ensure_docker(
 
     // 2. Acquire cross-process lock to avoid concurrent provision/start.
     let _lock = acquire_engine_lock(lock_wait_timeout).await?;
+    // Queue behind a start or provision from `cratebay runtime start` or
+    // the GUI; fail fast while the runtime is being stopped or resized.
+    let _op = runtime::ops::begin(runtime::ops::RuntimeOp::Start, lock_wait_timeout).await?;
 
-    // 3. TOCTOU: re-check after acquiring the lock — another process may have
-    //    started the runtime while we were waiting.
+    // 3. TOCTOU: re-check after acquiring the locks — another process may
+    //    have started the runtime while we were waiting.
     if let Some(docker) = try_connect_builtin(runtime).await {
         return Ok(Arc::new(docker));
     }
@@ -220,7 +233,9 @@ async fn wait_for_docker(
 // Cross-process lock
 // ---------------------------------------------------------------------------
 
-struct EngineLock {
+/// An exclusive lock on a file, released when dropped. Also used for the
+/// runtime operation lock ([`crate::runtime::ops`]).
+pub(crate) struct FileLock {
     #[allow(dead_code)]
     file: File,
     #[allow(dead_code)]
@@ -239,7 +254,7 @@ fn engine_lock_path_from_socket(socket_path: &Path) -> PathBuf {
     dir.join("engine.lock")
 }
 
-async fn acquire_engine_lock(timeout: Duration) -> Result<EngineLock, AppError> {
+async fn acquire_engine_lock(timeout: Duration) -> Result<FileLock, AppError> {
     let path = engine_lock_path();
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
@@ -247,7 +262,7 @@ async fn acquire_engine_lock(timeout: Duration) -> Result<EngineLock, AppError>
 
     let deadline = Instant::now() + timeout;
     loop {
-        match try_acquire_engine_lock(&path) {
+        match try_lock_file(&path) {
             Ok(lock) => return Ok(lock),
             Err(err) if is_lock_contended(&err) => {
                 if Instant::now() >= deadline {
@@ -264,7 +279,8 @@ async fn acquire_engine_lock(timeout: Duration) -> Result<EngineLock, AppError>
     }
 }
 
-fn is_lock_contended(err: &AppError) -> bool {
+/// Whether [`try_lock_file`] failed because another holder has the lock.
+pub(crate) fn is_lock_contended(err: &AppError) -> bool {
     match err {
         AppError::Io(io) => matches!(
             io.kind(),
@@ -275,7 +291,8 @@ fn is_lock_contended(err: &AppError) -> bool {
     }
 }
 
-fn try_acquire_engine_lock(path: &Path) -> Result<EngineLock, AppError> {
+/// Take an exclusive lock on `path` without waiting.
+pub(crate) fn try_lock_file(path: &Path) -> Result<FileLock, AppError> {
     #[cfg(windows)]
     {
         use std::os::windows::fs::OpenOptionsExt;
@@ -288,7 +305,7 @@ fn try_acquire_engine_lock(path: &Path) -> Result<EngineLock, AppError> {
             .share_mode(0);
 
         match opts.open(path) {
-            Ok(file) => Ok(EngineLock {
+            Ok(file) => Ok(FileLock {
                 file,
                 path: path.to_path_buf(),
             }),
@@ -310,7 +327,7 @@ fn try_acquire_engine_lock(path: &Path) -> Result<EngineLock, AppError> {
         let fd = file.as_raw_fd();
         let rc = unsafe { libc::flock(fd, libc::LOCK_EX | libc::LOCK_NB) };
         if rc == 0 {
-            Ok(EngineLock {
+            Ok(FileLock {
                 file,
                 path: path.to_path_buf(),
             })
@@ -1,3 +1,5 @@
+use bollard::errors::Error as BollardError;
+use serde::Serialize;
 use thiserror::Error;
 
 /// Unified error type for all CrateBay operations.
@@ -32,14 +34,171 @@ pub enum AppError {
 
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
+
+    #[error("Quota exceeded: {resource} would be {requested}, limit is {limit}")]
+    QuotaExceeded {
+        resource: String,
+        requested: u64,
+        limit: u64,
+    },
+
+    /// The operation was cancelled through its [`CancellationToken`](crate::cancel::CancellationToken);
+    /// holds what was cancelled, e.g. `Pull of 'nginx:latest'`.
+    #[error("{0} cancelled")]
+    Cancelled(String),
+
+    /// Rosetta translation is not available in the runtime VM.
+    #[error("Rosetta unavailable: {0}")]
+    RosettaUnavailable(String),
+
+    /// A host directory could not be shared with the runtime VM
+    /// (VirtioFS on macOS, drvfs bind mounts on Windows).
+    #[error("Shared directory error: {0}")]
+    SharedDirectory(String),
+
+    /// A runtime VM operation conflicts with one already in progress
+    /// ([`crate::runtime::ops`]).
+    #[error("Aborted: {0}")]
+    Aborted(String),
+
+    /// The change needs the runtime VM stopped first; holds what was
+    /// attempted, e.g. `changing CPU/memory`.
+    #[error("Requires restart: stop the runtime before {0}")]
+    RequiresRestart(String),
+}
+
+/// Stable, machine-readable error class. Clients branch on this instead of
+/// parsing messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    DockerUnreachable,
+    Docker,
+    Database,
+    LlmProxy,
+    Validation,
+    NotFound,
+    Mcp,
+    Runtime,
+    Io,
+    Serialization,
+    PermissionDenied,
+    QuotaExceeded,
+    Cancelled,
+    RosettaUnavailable,
+    SharedDirectory,
+    Aborted,
+    RequiresRestart,
+}
+
+/// Structured form of an [`AppError`], as returned to the GUI.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorDetail {
+    pub code: ErrorCode,
+    pub message: String,
+    /// The resource involved, e.g. `container/web` or `max_cpus`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// What the user can do about it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl AppError {
+    /// Error class. Docker and IO errors are split by cause: missing
+    /// objects are `NotFound`, refused access is `PermissionDenied` and
+    /// connection failures are `DockerUnreachable`.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::Docker(e) => docker_error_code(e),
+            AppError::Database(_) => ErrorCode::Database,
+            AppError::LlmProxy(_) => ErrorCode::LlmProxy,
+            AppError::Validation(_) => ErrorCode::Validation,
+            AppError::NotFound { .. } => ErrorCode::NotFound,
+            AppError::Mcp(_) => ErrorCode::Mcp,
+            AppError::Runtime(_) => ErrorCode::Runtime,
+            AppError::Io(e) => match e.kind() {
+                std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+                std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+                _ => ErrorCode::Io,
+            },
+            AppError::Serialization(_) => ErrorCode::Serialization,
+            AppError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            AppError::QuotaExceeded { .. } => ErrorCode::QuotaExceeded,
+            AppError::Cancelled(_) => ErrorCode::Cancelled,
+            AppError::RosettaUnavailable(_) => ErrorCode::RosettaUnavailable,
+            AppError::SharedDirectory(_) => ErrorCode::SharedDirectory,
+            AppError::Aborted(_) => ErrorCode::Aborted,
+            AppError::RequiresRestart(_) => ErrorCode::RequiresRestart,
+        }
+    }
+
+    /// Code, message, resource and an actionable hint.
+    pub fn detail(&self) -> ErrorDetail {
+        let code = self.code();
+        let resource = match self {
+            AppError::NotFound { entity, id } => Some(format!("{}/{}", entity, id)),
+            AppError::QuotaExceeded { resource, .. } => Some(resource.clone()),
+            _ => None,
+        };
+        let hint = match code {
+            ErrorCode::DockerUnreachable => {
+                Some("Start the CrateBay runtime, or check the selected Docker host")
+            }
+            ErrorCode::PermissionDenied => {
+                Some("Check file permissions and access to the Docker socket")
+            }
+            ErrorCode::QuotaExceeded => {
+                Some("Lower the request, or raise the max_* limit in config.toml")
+            }
+            ErrorCode::RosettaUnavailable => Some(
+                "Rosetta needs Apple Silicon; start the runtime with CRATEBAY_RUNTIME_ROSETTA=1",
+            ),
+            ErrorCode::SharedDirectory => Some(
+                "Check that the shared directory exists on a local disk (cratebay runtime mount list)",
+            ),
+            ErrorCode::Aborted => {
+                Some("Wait for the running runtime operation to finish, then retry")
+            }
+            ErrorCode::RequiresRestart => Some(
+                "Stop the runtime (cratebay runtime stop), apply the change, then start it again",
+            ),
+            _ => None,
+        };
+        ErrorDetail {
+            code,
+            message: self.to_string(),
+            resource,
+            hint: hint.map(str::to_string),
+        }
+    }
+}
+
+fn docker_error_code(err: &BollardError) -> ErrorCode {
+    match err {
+        BollardError::DockerResponseServerError { status_code, .. } => match status_code {
+            404 => ErrorCode::NotFound,
+            401 | 403 => ErrorCode::PermissionDenied,
+            _ => ErrorCode::Docker,
+        },
+        BollardError::SocketNotFoundError(_)
+        | BollardError::HyperLegacyError { .. }
+        | BollardError::RequestTimeoutError => ErrorCode::DockerUnreachable,
+        BollardError::IOError { err } => match err.kind() {
+            std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            _ => ErrorCode::DockerUnreachable,
+        },
+        _ => ErrorCode::Docker,
+    }
 }
 
-// For Tauri command compatibility — AppError must implement Serialize
-impl serde::Serialize for AppError {
+// Tauri commands return AppError, so it serializes as its ErrorDetail.
+impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        self.detail().serialize(serializer)
     }
 }
@@ -6,9 +6,18 @@
 //! Ported from master branch `images.rs` and adapted for the v2 error model
 //! (`AppError` instead of `ImageError`).
 
+use crate::cancel::{self, CancellationToken};
+use crate::error::AppError;
 use crate::storage;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Upper bound for downloading one image (all files).
+const IMAGE_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 
 // ---------------------------------------------------------------------------
 // Types
@@ -78,6 +87,9 @@ pub struct DownloadProgress {
     pub error: Option<String>,
 }
 
+/// Callback invoked with download progress.
+pub type DownloadProgressCallback = Arc<dyn Fn(DownloadProgress) + Send + Sync + 'static>;
+
 /// Paths to the downloaded image files on disk.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImagePaths {
@@ -94,7 +106,9 @@ pub struct ImagePaths {
 ///
 /// This is compiled into the binary.  The URLs below are placeholders that
 /// will be replaced with real CDN / GitHub Release asset URLs once the
-/// image build pipeline is ready.
+/// image build pipeline is ready. Entries without pinned `*_sha256` digests
+/// rely on `<url>.sha256` files published next to the assets; downloading
+/// fails without one.
 pub fn builtin_catalog() -> Vec<OsImageEntry> {
     vec![
         OsImageEntry {
@@ -177,31 +191,53 @@ pub fn image_paths(image_id: &str) -> ImagePaths {
 struct Meta {
     #[serde(default = "default_status")]
     status: ImageStatus,
+    /// Catalog version of a downloaded image. `None` for images installed
+    /// from bundled assets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
 }
 
 fn status_file(image_id: &str) -> PathBuf {
     image_dir(image_id).join("metadata.json")
 }
 
+fn save_meta(image_id: &str, meta: &Meta) -> Result<(), std::io::Error> {
+    let bytes = serde_json::to_vec_pretty(meta).map_err(std::io::Error::other)?;
+    storage::write_atomic(&status_file(image_id), &bytes)
+}
+
+fn load_meta(image_id: &str) -> Option<Meta> {
+    let bytes = std::fs::read(status_file(image_id)).ok()?;
+    serde_json::from_slice::<Meta>(&bytes).ok()
+}
+
 /// Persist the image status to a metadata file.
+///
+/// Clears the recorded catalog version: callers use this for images whose
+/// files did not come from [`download_image`].
 pub fn save_image_status(image_id: &str, status: &ImageStatus) -> Result<(), std::io::Error> {
-    let meta = Meta {
-        status: status.clone(),
-    };
-    let bytes = serde_json::to_vec_pretty(&meta).map_err(std::io::Error::other)?;
-    storage::write_atomic(&status_file(image_id), &bytes)
+    save_meta(
+        image_id,
+        &Meta {
+            status: status.clone(),
+            version: None,
+        },
+    )
 }
 
 /// Load the image status from its metadata file.
 pub fn load_image_status(image_id: &str) -> ImageStatus {
-    let path = status_file(image_id);
-    let Ok(bytes) = std::fs::read(&path) else {
-        return ImageStatus::NotDownloaded;
-    };
-    let Ok(meta) = serde_json::from_slice::<Meta>(&bytes) else {
-        return ImageStatus::NotDownloaded;
-    };
-    meta.status
+    load_meta(image_id)
+        .map(|meta| meta.status)
+        .unwrap_or(ImageStatus::NotDownloaded)
+}
+
+/// Catalog version of a downloaded image, if it was installed by
+/// [`download_image`].
+pub fn installed_version(image_id: &str) -> Option<String> {
+    load_meta(image_id)
+        .filter(|meta| meta.status == ImageStatus::Ready)
+        .and_then(|meta| meta.version)
 }
 
 // ---------------------------------------------------------------------------
@@ -247,6 +283,246 @@ pub fn is_image_ready(image_id: &str) -> bool {
     load_image_status(image_id) == ImageStatus::Ready
 }
 
+/// Download an image's files from the catalog, verifying SHA-256 checksums.
+///
+/// The expected checksum comes from the catalog entry, or from a
+/// `<url>.sha256` file published next to the asset when the catalog has
+/// none; a file with neither is not installed. Files are written to `*.part` and only moved into place once every
+/// file has been verified, so a failed or cancelled download (including one
+/// whose future is dropped) leaves the previous install untouched.
+pub async fn download_image(
+    image_id: &str,
+    on_progress: Option<DownloadProgressCallback>,
+    cancel: Option<&CancellationToken>,
+) -> Result<(), AppError> {
+    let entry = builtin_catalog()
+        .into_iter()
+        .find(|e| e.id == image_id)
+        .ok_or_else(|| AppError::NotFound {
+            entity: "image".into(),
+            id: image_id.to_string(),
+        })?;
+
+    let paths = image_paths(image_id);
+    let mut files = vec![
+        (
+            "kernel",
+            &entry.kernel_url,
+            &entry.kernel_sha256,
+            paths.kernel_path,
+        ),
+        (
+            "initrd",
+            &entry.initrd_url,
+            &entry.initrd_sha256,
+            paths.initrd_path,
+        ),
+    ];
+    if !entry.rootfs_url.trim().is_empty() {
+        files.push((
+            "rootfs",
+            &entry.rootfs_url,
+            &entry.rootfs_sha256,
+            paths.rootfs_path,
+        ));
+    }
+
+    std::fs::create_dir_all(image_dir(image_id))?;
+    let mut guard = DownloadGuard {
+        image_id,
+        previous: load_meta(image_id),
+        parts: Vec::new(),
+        armed: true,
+    };
+    save_image_status(image_id, &ImageStatus::Downloading)?;
+
+    let client = crate::registry::http_client(IMAGE_DOWNLOAD_TIMEOUT)?;
+    let mut progress = DownloadProgress {
+        image_id: image_id.to_string(),
+        current_file: String::new(),
+        bytes_downloaded: 0,
+        bytes_total: entry.size_bytes,
+        done: false,
+        error: None,
+    };
+
+    let what = format!("Download of {}", image_id);
+    let mut downloaded = Vec::new();
+    let mut result = Ok(());
+    for (name, url, sha256, dest) in files {
+        progress.current_file = name.to_string();
+        let part = part_path(&dest);
+        guard.parts.push(part.clone());
+        let file = download_file(&client, url, sha256, &part, &mut progress, &on_progress);
+        match cancel::run(cancel, &what, file).await.and_then(|r| r) {
+            Ok(()) => downloaded.push((part, dest)),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+
+    if result.is_ok() {
+        result = downloaded
+            .iter()
+            .try_for_each(|(part, dest)| std::fs::rename(part, dest))
+            .map_err(AppError::from);
+    }
+
+    match result {
+        Ok(()) => {
+            guard.armed = false;
+            save_meta(
+                image_id,
+                &Meta {
+                    status: ImageStatus::Ready,
+                    version: Some(entry.version.clone()),
+                },
+            )?;
+            progress.done = true;
+            if let Some(cb) = &on_progress {
+                cb(progress);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            drop(guard);
+            progress.error = Some(e.to_string());
+            if let Some(cb) = &on_progress {
+                cb(progress);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Undoes an unfinished [`download_image`] when dropped while armed: removes
+/// the `*.part` files and restores the previous metadata.
+struct DownloadGuard<'a> {
+    image_id: &'a str,
+    previous: Option<Meta>,
+    parts: Vec<PathBuf>,
+    armed: bool,
+}
+
+impl Drop for DownloadGuard<'_> {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        for part in &self.parts {
+            let _ = std::fs::remove_file(part);
+        }
+        let restored = self.previous.take().unwrap_or(Meta {
+            status: ImageStatus::NotDownloaded,
+            version: None,
+        });
+        if let Err(e) = save_meta(self.image_id, &restored) {
+            tracing::warn!("Failed to restore metadata for {}: {}", self.image_id, e);
+        }
+    }
+}
+
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Stream `url` into `dest`, checking the SHA-256 digest on the way.
+async fn download_file(
+    client: &reqwest::Client,
+    url: &str,
+    catalog_sha256: &str,
+    dest: &Path,
+    progress: &mut DownloadProgress,
+    on_progress: &Option<DownloadProgressCallback>,
+) -> Result<(), AppError> {
+    let expected = match catalog_sha256.trim() {
+        "" => fetch_sidecar_sha256(client, url).await?.ok_or_else(|| {
+            AppError::Runtime(format!(
+                "No SHA-256 checksum for {}: the catalog pins none and {}.sha256 is not published; refusing to install an unverified file",
+                url, url
+            ))
+        })?,
+        sha => sha.to_ascii_lowercase(),
+    };
+
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::Runtime(format!("Failed to download {}: {}", url, e)))?;
+
+    let mut file = tokio::fs::File::create(dest).await?;
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Runtime(format!("Failed to download {}: {}", url, e)))?
+    {
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+        progress.bytes_downloaded += chunk.len() as u64;
+        progress.bytes_total = progress.bytes_total.max(progress.bytes_downloaded);
+        if let Some(cb) = on_progress {
+            cb(progress.clone());
+        }
+    }
+    file.sync_all().await?;
+
+    verify_sha256(url, &hex_digest(hasher.finalize().as_slice()), &expected)
+}
+
+/// Read `<url>.sha256` (`sha256sum` format). `None` if it is not published.
+async fn fetch_sidecar_sha256(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<Option<String>, AppError> {
+    let sidecar = format!("{}.sha256", url);
+    let response = client
+        .get(&sidecar)
+        .send()
+        .await
+        .map_err(|e| AppError::Runtime(format!("Failed to fetch {}: {}", sidecar, e)))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let body = response
+        .error_for_status()
+        .map_err(|e| AppError::Runtime(format!("Failed to fetch {}: {}", sidecar, e)))?
+        .text()
+        .await
+        .map_err(|e| AppError::Runtime(format!("Failed to fetch {}: {}", sidecar, e)))?;
+    parse_sha256_file(&body)
+        .map(Some)
+        .ok_or_else(|| AppError::Runtime(format!("Malformed checksum file: {}", sidecar)))
+}
+
+/// Extract the digest from `sha256sum` output (`<hex>  <name>` or `<hex>`).
+fn parse_sha256_file(body: &str) -> Option<String> {
+    let digest = body.split_whitespace().next()?;
+    (digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| digest.to_ascii_lowercase())
+}
+
+fn hex_digest(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn verify_sha256(url: &str, actual: &str, expected: &str) -> Result<(), AppError> {
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(AppError::Runtime(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            url, expected, actual
+        )))
+    }
+}
+
 /// Create a VM disk image by copying the rootfs or creating a blank raw file.
 ///
 /// If the rootfs file exists it is used as the base; otherwise a sparse
@@ -383,6 +659,44 @@ mod tests {
         assert_eq!(default_status(), ImageStatus::NotDownloaded);
     }
 
+    #[test]
+    fn parse_sha256_file_accepts_sha256sum_format() {
+        let digest = "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+        assert_eq!(
+            parse_sha256_file(&format!("{}  vmlinuz-x86_64\n", digest)).as_deref(),
+            Some(digest.to_ascii_lowercase().as_str())
+        );
+        assert!(parse_sha256_file("not-a-digest vmlinuz").is_none());
+        assert!(parse_sha256_file("").is_none());
+    }
+
+    #[test]
+    fn verify_sha256_detects_mismatch() {
+        let empty = hex_digest(Sha256::digest(b"").as_slice());
+        assert_eq!(
+            empty,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert!(verify_sha256("u", &empty, &empty.to_ascii_uppercase()).is_ok());
+        assert!(matches!(
+            verify_sha256("u", &empty, &"0".repeat(64)),
+            Err(AppError::Runtime(_))
+        ));
+    }
+
+    #[test]
+    fn part_path_appends_suffix() {
+        let part = part_path(Path::new("/tmp/img/vmlinuz"));
+        assert_eq!(part, Path::new("/tmp/img/vmlinuz.part"));
+    }
+
+    #[test]
+    fn meta_without_version_deserializes() {
+        let meta: Meta = serde_json::from_str(r#"{"status":"ready"}"#).unwrap();
+        assert_eq!(meta.status, ImageStatus::Ready);
+        assert!(meta.version.is_none());
+    }
+
     #[test]
     fn builtin_catalog_contains_runtime_images() {
         let catalog = builtin_catalog();
@@ -4,18 +4,41 @@
 //! and Docker integration. Binary crates (gui, cli, mcp) depend on this.
 
 pub mod audit;
+pub mod autostart;
+pub mod browser;
+pub mod build_context;
+pub mod builder;
+pub mod bulk;
+pub mod cancel;
+pub mod checkpoint;
+pub mod compose;
+pub mod config;
 pub mod container;
+pub mod container_cache;
+pub mod dev_watch;
+pub mod dns;
 pub mod docker;
+pub mod docker_context;
+pub mod docker_ssh;
+pub mod docker_tls;
+pub mod dockerfile_lint;
 pub mod engine;
 pub mod error;
 pub mod fsutil;
 pub mod images;
 pub mod llm_proxy;
+pub mod logging;
 pub mod mcp;
 pub mod models;
+pub mod ports;
 pub mod proxy;
+pub mod prune;
+pub mod registry;
 pub mod runtime;
+pub mod status;
 pub mod storage;
+pub mod terminal;
+pub mod update;
 pub mod validation;
 
 // Re-export commonly used types
@@ -62,10 +85,39 @@ mod tests {
     }
 
     #[test]
-    fn app_error_serializes_as_string() {
+    fn app_error_serializes_as_detail() {
         let err = AppError::Validation("test".to_string());
-        let json = serde_json::to_string(&err).unwrap();
-        assert_eq!(json, "\"Validation error: test\"");
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"code": "VALIDATION", "message": "Validation error: test"})
+        );
+
+        let err = AppError::QuotaExceeded {
+            resource: "max_cpus".to_string(),
+            requested: 8,
+            limit: 4,
+        };
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "QUOTA_EXCEEDED");
+        assert_eq!(json["resource"], "max_cpus");
+        assert!(json["hint"].as_str().is_some_and(|h| h.contains("max_")));
+    }
+
+    #[test]
+    fn app_error_codes_split_docker_causes() {
+        let missing = AppError::Docker(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404,
+            message: "No such container".to_string(),
+        });
+        assert_eq!(missing.code(), error::ErrorCode::NotFound);
+        let socket = AppError::Docker(bollard::errors::Error::SocketNotFoundError(
+            "/var/run/docker.sock".to_string(),
+        ));
+        assert_eq!(socket.code(), error::ErrorCode::DockerUnreachable);
+        let rosetta = AppError::RosettaUnavailable("not enabled".to_string());
+        assert_eq!(rosetta.code(), error::ErrorCode::RosettaUnavailable);
+        assert!(rosetta.detail().hint.is_some());
     }
 
     #[test]
@@ -0,0 +1,442 @@
+//! Tracing setup shared by the binaries.
+//!
+//! [`init`] installs the global subscriber from the config file: the
+//! filter from [`Config::log_filter`] and either the usual text lines or,
+//! with `log_format = "json"`, one JSON object per line for systemd,
+//! launchd and log collectors. The filter sits behind a reload handle so
+//! [`set_filter`] can change verbosity while the process keeps running.
+//!
+//! Long-running processes also pass a log file, which gets every event the
+//! filter lets through (INFO and up by default), so the history before a
+//! crash can be retrieved. [`RollingFile`] rotates it by size and gzips the
+//! rotated files.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+use crate::config::Config;
+use crate::error::AppError;
+
+/// Log file name inside the data directory (`~/.cratebay/`).
+pub const LOG_FILE_NAME: &str = "cratebay.log";
+
+/// Size at which the log file is rotated.
+pub const LOG_FILE_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Rotated files kept (`cratebay.log.1.gz` is the newest).
+pub const LOG_FILE_KEEP: usize = 5;
+
+/// Reload handle of the installed filter, set by [`init`].
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Log line format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    /// Parse `text` or `json` (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Stream log lines are written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogOutput {
+    Stdout,
+    /// For processes whose stdout carries a protocol (the MCP server).
+    Stderr,
+}
+
+/// Default log file: [`LOG_FILE_NAME`] next to the database.
+pub fn default_log_file() -> Result<PathBuf, AppError> {
+    let db_path = crate::storage::default_db_path()?;
+    Ok(db_path.with_file_name(LOG_FILE_NAME))
+}
+
+/// Install the global subscriber. `default_filter` applies when neither
+/// `RUST_LOG` nor `log_level` is set. Events are also appended to `file`,
+/// rotated by size, when given. Does nothing if a subscriber is already
+/// installed.
+pub fn init(config: &Config, default_filter: &str, output: LogOutput, file: Option<&Path>) {
+    let directives = config.log_filter(default_filter);
+    let (filter, invalid_filter) = match EnvFilter::try_new(&directives) {
+        Ok(filter) => (filter, None),
+        Err(e) => (EnvFilter::new(default_filter), Some(e)),
+    };
+    let format = config.log_format();
+    let parsed_format = format.as_deref().map(LogFormat::parse);
+    let writer = match output {
+        LogOutput::Stdout => tracing_subscriber::fmt::writer::BoxMakeWriter::new(std::io::stdout),
+        LogOutput::Stderr => tracing_subscriber::fmt::writer::BoxMakeWriter::new(std::io::stderr),
+    };
+    let format_kind = parsed_format.flatten().unwrap_or(LogFormat::Text);
+    let fmt_layer = match format_kind {
+        LogFormat::Text => tracing_subscriber::fmt::layer().with_writer(writer).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .event_format(JsonFormat)
+            .with_writer(writer)
+            .boxed(),
+    };
+    let rolling = file.map(|path| RollingFile::open(path, LOG_FILE_MAX_BYTES, LOG_FILE_KEEP));
+    let (file_layer, file_error) = match rolling {
+        Some(Ok(rolling)) => {
+            let layer = match format_kind {
+                LogFormat::Text => tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(Mutex::new(rolling))
+                    .boxed(),
+                LogFormat::Json => tracing_subscriber::fmt::layer()
+                    .event_format(JsonFormat)
+                    .with_writer(Mutex::new(rolling))
+                    .boxed(),
+            };
+            (Some(layer), None)
+        }
+        Some(Err(e)) => (None, Some(e)),
+        None => (None, None),
+    };
+    let (filter, handle) = reload::Layer::new(filter);
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer)
+        .with(file_layer)
+        .try_init()
+        .is_err()
+    {
+        return;
+    }
+    let _ = FILTER.set(handle);
+
+    if let Some(e) = invalid_filter {
+        tracing::warn!(
+            "Ignoring invalid log filter '{}': {}; using '{}'",
+            directives,
+            e,
+            default_filter
+        );
+    }
+    if let (Some(format), Some(None)) = (format, parsed_format) {
+        tracing::warn!("Unknown log format '{}'; using text", format);
+    }
+    if let (Some(path), Some(e)) = (file, file_error) {
+        tracing::warn!("Not logging to {}: {}", path.display(), e);
+    }
+}
+
+/// Replace the active filter (e.g. `debug` or
+/// `info,cratebay_core::runtime=trace`) until the process exits. Returns
+/// the filter now in effect.
+pub fn set_filter(directives: &str) -> Result<String, AppError> {
+    let filter = EnvFilter::try_new(directives.trim())
+        .map_err(|e| AppError::Validation(format!("Invalid log filter '{}': {}", directives, e)))?;
+    let handle = FILTER
+        .get()
+        .ok_or_else(|| AppError::Runtime("Logging is not initialized".to_string()))?;
+    handle
+        .reload(filter)
+        .map_err(|e| AppError::Runtime(format!("Failed to change log filter: {}", e)))?;
+    tracing::info!("Log filter changed to '{}'", directives.trim());
+    current_filter().ok_or_else(|| AppError::Runtime("Logging is not initialized".to_string()))
+}
+
+/// The filter in effect, if [`init`] installed one.
+pub fn current_filter() -> Option<String> {
+    FILTER
+        .get()
+        .and_then(|handle| handle.with_current(|filter| filter.to_string()).ok())
+}
+
+/// Append-only log file rotated once it would grow past `max_bytes`:
+/// `<file>` moves to `<file>.1.gz` (compressed), older rotations shift up
+/// and only `keep` of them are kept.
+///
+/// Rotation compresses on the writing thread; at the default size this
+/// happens rarely enough not to matter.
+pub struct RollingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: File,
+    size: u64,
+}
+
+impl RollingFile {
+    /// Open `path` for appending, creating it and its directory.
+    pub fn open(path: &Path, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = Self::open_append(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            keep,
+            file,
+            size,
+        })
+    }
+
+    fn open_append(path: &Path) -> io::Result<File> {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+    }
+
+    /// `<file>.<index>.gz`.
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{}.gz", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.keep > 0 {
+            for index in (1..self.keep).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated_path(index + 1))?;
+                }
+            }
+            let mut source = File::open(&self.path)?;
+            let target = File::create(self.rotated_path(1))?;
+            let mut encoder = GzEncoder::new(target, Compression::default());
+            io::copy(&mut source, &mut encoder)?;
+            encoder.finish()?;
+        }
+        File::create(&self.path)?;
+        self.file = Self::open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            // Keep logging to the current file if rotation fails.
+            if let Err(e) = self.rotate() {
+                eprintln!("Failed to rotate {}: {}", self.path.display(), e);
+            }
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Formats each event as a single-line JSON object:
+/// `{"timestamp", "level", "target", "message", "fields", "spans"}`.
+/// `fields` and `spans` are omitted when empty.
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        let metadata = event.metadata();
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Value::String(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        );
+        line.insert(
+            "level".to_string(),
+            Value::String(metadata.level().to_string()),
+        );
+        line.insert(
+            "target".to_string(),
+            Value::String(metadata.target().to_string()),
+        );
+        if let Some(message) = fields.remove("message") {
+            line.insert("message".to_string(), message);
+        }
+        if !fields.is_empty() {
+            line.insert("fields".to_string(), Value::Object(fields));
+        }
+        if let Some(scope) = ctx.event_scope() {
+            let spans: Vec<Value> = scope
+                .from_root()
+                .map(|span| Value::String(span.name().to_string()))
+                .collect();
+            if !spans.is_empty() {
+                line.insert("spans".to_string(), Value::Array(spans));
+            }
+        }
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+/// Collects event fields into a JSON map, keeping numbers and booleans
+/// typed.
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(
+            field.name().to_string(),
+            Value::String(format!("{:?}", value)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn json_format_writes_one_object_per_event() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .event_format(JsonFormat)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("pull", image = "nginx");
+            let _guard = span.enter();
+            tracing::warn!(layers = 3u64, cached = true, "Pull \"slow\"");
+            tracing::info!("done");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "WARN");
+        assert_eq!(lines[0]["target"], module_path!());
+        assert_eq!(lines[0]["message"], "Pull \"slow\"");
+        assert_eq!(lines[0]["fields"]["layers"], 3);
+        assert_eq!(lines[0]["fields"]["cached"], true);
+        assert_eq!(lines[0]["spans"], serde_json::json!(["pull"]));
+        assert!(lines[0]["timestamp"].as_str().unwrap().ends_with('Z'));
+        assert!(lines[1].get("fields").is_none());
+    }
+
+    #[test]
+    fn rolling_file_rotates_and_compresses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs").join(LOG_FILE_NAME);
+        let mut file = RollingFile::open(&path, 16, 2).unwrap();
+        for line in ["first line\n", "second line\n", "third line\n", "4th\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        let gunzip = |index: usize| {
+            let rotated = PathBuf::from(format!("{}.{}.gz", path.display(), index));
+            let mut text = String::new();
+            io::Read::read_to_string(
+                &mut flate2::read::GzDecoder::new(File::open(&rotated).unwrap()),
+                &mut text,
+            )
+            .unwrap();
+            text
+        };
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "third line\n4th\n");
+        assert_eq!(gunzip(1), "second line\n");
+        assert_eq!(gunzip(2), "first line\n");
+
+        file.write_all(b"fifth line\n").unwrap();
+        assert_eq!(gunzip(1), "third line\n4th\n");
+        assert_eq!(gunzip(2), "second line\n");
+        assert!(!PathBuf::from(format!("{}.3.gz", path.display())).exists());
+
+        // Reopening continues the existing file and its size.
+        let mut file = RollingFile::open(&path, 16, 2).unwrap();
+        file.write_all(b"sixth line\n").unwrap();
+        assert_eq!(gunzip(1), "fifth line\n");
+    }
+
+    #[test]
+    fn parses_log_formats() {
+        assert_eq!(LogFormat::parse("JSON"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("text"), Some(LogFormat::Text));
+        assert_eq!(LogFormat::parse("xml"), None);
+    }
+
+    #[test]
+    fn set_filter_rejects_invalid_directives() {
+        assert!(matches!(
+            set_filter("cratebay=loud"),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
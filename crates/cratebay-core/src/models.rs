@@ -47,7 +47,12 @@ pub struct ContainerListFilters {
     pub status: Option<Vec<ContainerStatus>>,
     pub name: Option<String>,
     pub image: Option<String>,
+    /// Label matches; an empty value matches on the key alone.
     pub label: Option<HashMap<String, String>>,
+    /// Created from this image or one of its descendants (Docker `ancestor`).
+    pub ancestor: Option<String>,
+    /// Only the N most recently created containers.
+    pub limit: Option<u32>,
 }
 
 /// Request to create a new container.
@@ -66,6 +71,36 @@ pub struct ContainerCreateRequest {
     pub auto_start: Option<bool>,
     pub labels: Option<HashMap<String, String>>,
     pub template_id: Option<String>,
+    /// Client-chosen key that makes retries safe: replaying it within
+    /// [`IDEMPOTENCY_WINDOW`](crate::container::IDEMPOTENCY_WINDOW) returns
+    /// the container it created instead of creating another.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Platform to run, e.g. `linux/amd64` on an arm64 runtime (emulated).
+    /// Defaults to the runtime's own.
+    #[serde(default)]
+    pub platform: Option<String>,
+}
+
+/// A problem found by a dry run of a [`ContainerCreateRequest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunValidationIssue {
+    /// Request field the issue belongs to: `name`, `image`, `platform`,
+    /// `ports`, `volumes`, `cpuCores` or `memoryMb`.
+    pub field: String,
+    /// `error` blocks the run; `warning` is informational.
+    pub severity: String,
+    pub message: String,
+}
+
+/// Dry-run result for a [`ContainerCreateRequest`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunValidationReport {
+    /// True when there are no `error` issues.
+    pub ok: bool,
+    pub issues: Vec<RunValidationIssue>,
 }
 
 /// Result of a container exec command.
@@ -143,6 +178,19 @@ pub struct ContainerStats {
     pub memory_percent: f64,
 }
 
+/// One process running inside a container.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerProcess {
+    /// PID in the runtime VM's namespace.
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub user: String,
+    /// `%CPU` from `ps`; `None` when the daemon's `ps` does not report it.
+    pub cpu_percent: Option<f64>,
+    pub command: String,
+}
+
 /// Docker image information.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -178,6 +226,23 @@ pub struct ImageSearchResult {
     pub official: bool,
 }
 
+/// Merged results of a search across several registries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageSearchResponse {
+    /// Ranked by stars + pulls, at most `limit` entries.
+    pub results: Vec<ImageSearchResult>,
+    /// Sources that failed or timed out; their results are missing.
+    pub errors: Vec<ImageSearchSourceError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageSearchSourceError {
+    pub source: String,
+    pub error: String,
+}
+
 /// Docker image inspection info.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -192,6 +257,359 @@ pub struct ImageInspectInfo {
     pub layers: u32,
 }
 
+/// Layers of an image with the instruction that created each, newest
+/// first (as `docker history`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageLayers {
+    pub reference: String,
+    /// `local` (image store) or `remote` (registry manifest and config).
+    pub source: String,
+    /// Platform of a remote image, e.g. `linux/arm64`.
+    pub platform: Option<String>,
+    /// Whether sizes are compressed (remote) rather than unpacked (local).
+    pub compressed: bool,
+    pub total_size: u64,
+    pub layers: Vec<ImageLayer>,
+}
+
+/// One history entry of an image. Metadata-only instructions (`ENV`,
+/// `CMD`) are kept with size 0 and `empty` set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageLayer {
+    /// Layer digest (remote) or image id (local) when known.
+    pub digest: Option<String>,
+    /// Instruction, e.g. `RUN apt-get update` or `COPY . /app`.
+    pub created_by: String,
+    /// RFC 3339 creation time, if recorded.
+    pub created: Option<String>,
+    pub size: u64,
+    pub empty: bool,
+    pub comment: Option<String>,
+}
+
+/// Request to commit a container's filesystem to a new image.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerCommitRequest {
+    /// Target image reference, e.g. `myapp:snapshot`.
+    pub target: String,
+    pub author: Option<String>,
+    /// Commit message.
+    pub message: Option<String>,
+    /// Dockerfile instructions applied to the image config, e.g. `ENV A=1`.
+    #[serde(default)]
+    pub changes: Vec<String>,
+    /// Pause the container while committing (default: true).
+    pub pause: Option<bool>,
+}
+
+/// Request to build an image from a Dockerfile.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageBuildRequest {
+    /// Build context directory on the host.
+    pub context_dir: String,
+    /// Dockerfile path (defaults to `<context_dir>/Dockerfile`).
+    pub dockerfile: Option<String>,
+    /// Tags to apply, e.g. `["myapp:latest"]`.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// `--build-arg` values.
+    #[serde(default)]
+    pub build_args: HashMap<String, String>,
+    /// Multi-stage build target.
+    pub target: Option<String>,
+    /// Do not use the build cache.
+    #[serde(default)]
+    pub no_cache: bool,
+    /// Always attempt to pull newer base images.
+    #[serde(default)]
+    pub pull: bool,
+}
+
+/// How serious a Dockerfile lint finding is.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Info,
+    /// The build works, but the image is worse for it.
+    Warning,
+    /// The build would fail; `image build --lint-only` exits non-zero.
+    Error,
+}
+
+impl LintSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintSeverity::Info => "info",
+            LintSeverity::Warning => "warning",
+            LintSeverity::Error => "error",
+        }
+    }
+}
+
+/// One Dockerfile lint finding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFinding {
+    /// Rule ID, e.g. `latest-tag`.
+    pub rule: String,
+    pub severity: LintSeverity,
+    /// 1-based line of the instruction.
+    pub line: usize,
+    /// Instruction keyword, e.g. `FROM`.
+    pub instruction: String,
+    pub message: String,
+}
+
+/// Lint result for a Dockerfile, findings ordered by line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerfileLint {
+    pub dockerfile: String,
+    pub findings: Vec<LintFinding>,
+}
+
+/// A problem found in a compose file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeDiagnostic {
+    pub severity: LintSeverity,
+    /// Dotted path of the offending node, e.g. `services.web.ports[0]`.
+    pub path: String,
+    /// 1-based line, when the node (or its closest ancestor) was found.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Result of `compose validate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeValidation {
+    pub file: String,
+    /// Service names in file order.
+    pub services: Vec<String>,
+    pub diagnostics: Vec<ComposeDiagnostic>,
+}
+
+/// Result of `compose convert --to k8s`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComposeConversion {
+    pub file: String,
+    /// Generated objects as `Kind/name`; empty when the file has errors.
+    pub resources: Vec<String>,
+    /// Multi-document YAML for `kubectl apply -f`.
+    pub manifest: String,
+    pub diagnostics: Vec<ComposeDiagnostic>,
+}
+
+/// Registry credentials for push and registry API operations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryAuth {
+    pub username: String,
+    pub password: String,
+    /// Registry address, e.g. `ghcr.io` (Docker Hub when omitted).
+    pub server_address: Option<String>,
+}
+
+/// Image manifest fetched from a remote registry (no pull required).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteImageManifest {
+    /// Fully qualified reference, e.g. `registry-1.docker.io/library/nginx:latest`.
+    pub reference: String,
+    /// Digest of the top-level manifest (or manifest list).
+    pub digest: Option<String>,
+    pub media_type: String,
+    /// Whether the reference points at a manifest list / OCI index.
+    pub multi_arch: bool,
+    /// Platforms available for a multi-arch image (single entry otherwise).
+    pub platforms: Vec<RemotePlatform>,
+    /// Platform the layers and labels below describe, e.g. `linux/arm64/v8`.
+    pub platform: Option<String>,
+    pub layers: Vec<RemoteLayer>,
+    /// Sum of compressed layer sizes.
+    pub total_size: u64,
+    pub config_digest: Option<String>,
+    pub labels: HashMap<String, String>,
+    /// Manifest annotations (index and selected platform merged).
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+    /// What the manifest holds: a container image or another OCI artifact.
+    #[serde(default)]
+    pub artifact_type: ArtifactKind,
+    /// Artifacts attached to the manifest (signatures, SBOMs, attestations),
+    /// from the OCI referrers API.
+    #[serde(default)]
+    pub referrers: Vec<RemoteReferrer>,
+}
+
+/// Kind of content an OCI manifest holds, from its `artifactType`, config
+/// media type or layer media types.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactKind {
+    #[default]
+    Image,
+    HelmChart,
+    Wasm,
+    Sbom,
+    Signature,
+    Attestation,
+    Other,
+}
+
+impl ArtifactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Image => "image",
+            Self::HelmChart => "helm-chart",
+            Self::Wasm => "wasm",
+            Self::Sbom => "sbom",
+            Self::Signature => "signature",
+            Self::Attestation => "attestation",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// An artifact referring to a manifest through its `subject`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteReferrer {
+    pub digest: String,
+    /// Raw `artifactType`, e.g. `application/spdx+json`.
+    pub artifact_type: String,
+    pub kind: ArtifactKind,
+    pub size: u64,
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+}
+
+/// A platform entry in a manifest list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemotePlatform {
+    /// `os/architecture[/variant]`, e.g. `linux/amd64`.
+    pub platform: String,
+    pub digest: String,
+    pub size: u64,
+}
+
+/// One page of tags from a registry repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryTagList {
+    /// Fully qualified repository, e.g. `registry-1.docker.io/library/nginx`.
+    pub repository: String,
+    /// Tags on this page: `latest` first, then versions newest first.
+    pub tags: Vec<String>,
+    /// Artifact type of tags on this page that are not plain images. Always
+    /// covers cosign-style `sha256-<hex>.sig|.att|.sbom` tags; with
+    /// `artifacts` requested, every tag's manifest is read.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub artifact_types: HashMap<String, ArtifactKind>,
+    /// Number of tags matching the filter across all pages.
+    pub total: usize,
+    /// 1-based page number.
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+}
+
+/// One page of repositories from a registry's `/v2/_catalog`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryCatalog {
+    /// Registry host, e.g. `harbor.example.com`.
+    pub registry: String,
+    /// Repositories on this page, sorted.
+    pub repositories: Vec<String>,
+    /// This page's repositories grouped by path segment; only filled for
+    /// the GUI's registry browser.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree: Option<Vec<RegistryCatalogNode>>,
+    /// Number of repositories matching the filter across all pages.
+    pub total: usize,
+    /// 1-based page number.
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+}
+
+/// A path segment in [`RegistryCatalog::tree`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryCatalogNode {
+    /// Last path segment, e.g. `api`.
+    pub name: String,
+    /// Full repository path, e.g. `team/app/api`.
+    pub path: String,
+    /// Whether `path` itself is a repository (not just a namespace).
+    pub repository: bool,
+    pub children: Vec<RegistryCatalogNode>,
+}
+
+/// Description of a remote image, read before pulling it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDetails {
+    /// Fully qualified reference, e.g. `registry-1.docker.io/library/nginx:latest`.
+    pub reference: String,
+    /// Where the description came from: `dockerhub`, `quay` or `oci`.
+    pub source: String,
+    pub title: Option<String>,
+    /// One-line summary.
+    pub description: Option<String>,
+    /// Long description in Markdown (Docker Hub overview, Quay README).
+    pub readme: Option<String>,
+    /// Project homepage.
+    pub url: Option<String>,
+    /// Source repository URL.
+    pub source_url: Option<String>,
+    pub documentation: Option<String>,
+    /// SPDX license expression.
+    pub licenses: Option<String>,
+    pub stars: Option<u64>,
+    pub pulls: Option<u64>,
+    pub last_updated: Option<String>,
+    /// `org.opencontainers.image.*` annotations and labels of the image.
+    pub oci: HashMap<String, String>,
+    /// Lookups that failed while the others succeeded.
+    pub warnings: Vec<String>,
+}
+
+/// A layer in a remote image manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteLayer {
+    pub digest: String,
+    pub media_type: String,
+    /// Compressed size in bytes.
+    pub size: u64,
+}
+
+/// A Docker object event (container/image/network/volume).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerEvent {
+    /// Object type: `container`, `image`, `network` or `volume`.
+    pub kind: String,
+    /// Event action, e.g. `start`, `die`, `pull`, `destroy`.
+    pub action: String,
+    /// Object id (image reference for image events).
+    pub id: String,
+    /// Object name from the event attributes, when present.
+    pub name: Option<String>,
+    /// Unix timestamp (seconds).
+    pub time: i64,
+    pub attributes: HashMap<String, String>,
+}
+
 /// Container lifecycle status.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -415,8 +833,21 @@ pub enum AuditAction {
     ContainerCreate,
     ContainerStart,
     ContainerStop,
+    ContainerRestart,
+    ContainerPause,
+    ContainerUnpause,
+    ContainerRename,
+    ContainerUpdate,
     ContainerDelete,
     ContainerExec,
+    ContainerCheckpoint,
+    ContainerRestore,
+    ImagePush,
+    RuntimeStart,
+    RuntimeStop,
+    RuntimeExec,
+    RuntimeMountAdd,
+    RuntimeMountRemove,
     ApiKeySave,
     ApiKeyDelete,
     ProviderCreate,
@@ -437,8 +868,21 @@ impl AuditAction {
             AuditAction::ContainerCreate => "container.create",
             AuditAction::ContainerStart => "container.start",
             AuditAction::ContainerStop => "container.stop",
+            AuditAction::ContainerRestart => "container.restart",
+            AuditAction::ContainerPause => "container.pause",
+            AuditAction::ContainerUnpause => "container.unpause",
+            AuditAction::ContainerRename => "container.rename",
+            AuditAction::ContainerUpdate => "container.update",
             AuditAction::ContainerDelete => "container.delete",
             AuditAction::ContainerExec => "container.exec",
+            AuditAction::ContainerCheckpoint => "container.checkpoint",
+            AuditAction::ContainerRestore => "container.restore",
+            AuditAction::ImagePush => "image.push",
+            AuditAction::RuntimeStart => "runtime.start",
+            AuditAction::RuntimeStop => "runtime.stop",
+            AuditAction::RuntimeExec => "runtime.exec",
+            AuditAction::RuntimeMountAdd => "runtime.mount_add",
+            AuditAction::RuntimeMountRemove => "runtime.mount_remove",
             AuditAction::ApiKeySave => "api_key.save",
             AuditAction::ApiKeyDelete => "api_key.delete",
             AuditAction::ProviderCreate => "provider.create",
@@ -454,6 +898,32 @@ impl AuditAction {
     }
 }
 
+/// One row of the audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntry {
+    pub id: String,
+    /// UTC, `YYYY-MM-DDTHH:MM:SSZ`.
+    pub timestamp: String,
+    /// [`AuditAction::as_str`] value, e.g. `container.start`.
+    pub action: String,
+    pub target: String,
+    pub details: Option<String>,
+    pub user: String,
+}
+
+/// Filters for [`storage::query_audit_logs`](crate::storage::query_audit_logs).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogQuery {
+    pub action: Option<String>,
+    pub target: Option<String>,
+    /// Only entries at or after this UTC time (`YYYY-MM-DDTHH:MM:SSZ`); see
+    /// [`audit::parse_since`](crate::audit::parse_since).
+    pub since: Option<String>,
+    pub limit: u32,
+}
+
 /// Request to create a new LLM provider.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -623,3 +1093,39 @@ pub struct ResourceUsage {
     pub disk_total_gb: f32,
     pub container_count: u32,
 }
+
+/// Live runtime VM metrics sampled inside the guest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeMetrics {
+    pub read_at: String,
+    /// Busy time across all vCPUs over a 1s window (0-100).
+    pub cpu_percent: f32,
+    pub cpu_cores: u32,
+    pub memory_used_mb: u64,
+    /// Memory the guest sees; shrinks as the balloon inflates.
+    pub memory_total_mb: u64,
+    /// Memory the VM was configured with (`RuntimeConfig::memory_mb`).
+    pub memory_allocated_mb: u64,
+    /// Memory held by the balloon and returned to the host; the host backs
+    /// `memory_allocated_mb - memory_balloon_mb` at most.
+    pub memory_balloon_mb: u64,
+    /// Usage of the filesystem backing `/var/lib/docker`.
+    pub disk_used_gb: f32,
+    pub disk_total_gb: f32,
+    /// Cumulative counters since boot, excluding loopback and container veths.
+    pub net_rx_bytes: u64,
+    pub net_tx_bytes: u64,
+    pub container_count: u32,
+}
+
+/// Outcome of warming the Rosetta translation cache for one image.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RosettaWarmResult {
+    pub image: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
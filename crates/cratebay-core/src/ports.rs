@@ -0,0 +1,289 @@
+//! Host port conflict detection for published container ports.
+//!
+//! Docker only notices a taken host port when the container starts and then
+//! reports an opaque `bind: address already in use` from its proxy. CrateBay
+//! checks the ports up front instead and names whoever holds them: another
+//! container publishing the same port, or a host process.
+
+use std::net::{Ipv4Addr, TcpListener, UdpSocket};
+
+use bollard::container::InspectContainerOptions;
+use bollard::Docker;
+
+use crate::container;
+use crate::error::AppError;
+use crate::models::PortMapping;
+
+/// Who holds a host port that a container wants to publish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortOwner {
+    /// A running container already publishes the port.
+    Container { id: String, name: String },
+    /// A host process has the port bound; `None` when it cannot be identified.
+    Process(Option<String>),
+}
+
+/// A requested host port that is not available.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortConflict {
+    pub host_port: u16,
+    pub protocol: String,
+    pub owner: PortOwner,
+}
+
+impl std::fmt::Display for PortConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} is ", self.host_port, self.protocol)?;
+        match &self.owner {
+            PortOwner::Container { id, name } => write!(
+                f,
+                "published by container '{}' ({})",
+                name,
+                &id[..id.len().min(12)]
+            ),
+            PortOwner::Process(Some(process)) => write!(f, "in use by {}", process),
+            PortOwner::Process(None) => write!(f, "in use by another process"),
+        }
+    }
+}
+
+/// Find conflicts for `ports`. Containers are checked first, so a port
+/// published by a CrateBay container is reported as such rather than as
+/// the runtime process forwarding it. `exclude` skips the container being
+/// started itself.
+pub async fn find_conflicts(
+    docker: &Docker,
+    ports: &[PortMapping],
+    exclude: Option<&str>,
+) -> Result<Vec<PortConflict>, AppError> {
+    let ports: Vec<&PortMapping> = ports.iter().filter(|p| p.host_port != 0).collect();
+    if ports.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let running = container::list(docker, false, None).await?;
+    let mut conflicts = Vec::new();
+    for port in ports {
+        let protocol = normalize_protocol(&port.protocol);
+        let holder = running.iter().find(|c| {
+            Some(c.id.as_str()) != exclude
+                && c.ports.iter().any(|p| {
+                    p.host_port == port.host_port && normalize_protocol(&p.protocol) == protocol
+                })
+        });
+        let owner = match holder {
+            Some(c) => Some(PortOwner::Container {
+                id: c.id.clone(),
+                name: c.name.clone(),
+            }),
+            None if host_port_in_use(port.host_port, protocol) => Some(PortOwner::Process(
+                process_holding_port(port.host_port, protocol),
+            )),
+            None => None,
+        };
+        if let Some(owner) = owner {
+            conflicts.push(PortConflict {
+                host_port: port.host_port,
+                protocol: protocol.to_string(),
+                owner,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Fail with a [`AppError::Validation`] listing every conflict in `ports`.
+pub async fn ensure_available(
+    docker: &Docker,
+    ports: &[PortMapping],
+    exclude: Option<&str>,
+) -> Result<(), AppError> {
+    if let Some(duplicate) = duplicate_host_port(ports) {
+        return Err(AppError::Validation(format!(
+            "Host port {} is mapped more than once",
+            duplicate
+        )));
+    }
+    let conflicts = find_conflicts(docker, ports, exclude).await?;
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::Validation(format!(
+        "Host port{} not available: {}",
+        if conflicts.len() == 1 { "" } else { "s" },
+        conflicts
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    )))
+}
+
+/// Check the host ports published by an existing container before it is
+/// started. Running containers are left alone.
+pub async fn ensure_available_for_container(docker: &Docker, id: &str) -> Result<(), AppError> {
+    let detail = docker
+        .inspect_container(id, None::<InspectContainerOptions>)
+        .await?;
+    if detail
+        .state
+        .as_ref()
+        .and_then(|s| s.running)
+        .unwrap_or(false)
+    {
+        return Ok(());
+    }
+    let ports = detail
+        .host_config
+        .and_then(|h| h.port_bindings)
+        .map(|bindings| published_ports(&bindings))
+        .unwrap_or_default();
+    ensure_available(docker, &ports, detail.id.as_deref().or(Some(id))).await
+}
+
+/// Host ports of a Docker `PortBindings` map (`"80/tcp" -> [{HostPort}]`).
+fn published_ports(bindings: &bollard::models::PortMap) -> Vec<PortMapping> {
+    bindings
+        .iter()
+        .filter_map(|(key, hosts)| Some((key, hosts.as_ref()?)))
+        .flat_map(|(key, hosts)| {
+            let (container_port, protocol) = key.split_once('/').unwrap_or((key, "tcp"));
+            hosts.iter().filter_map(move |binding| {
+                Some(PortMapping {
+                    host_port: binding.host_port.as_deref()?.parse().ok()?,
+                    container_port: container_port.parse().ok()?,
+                    protocol: protocol.to_string(),
+                })
+            })
+        })
+        .collect()
+}
+
+fn normalize_protocol(protocol: &str) -> &'static str {
+    if protocol.eq_ignore_ascii_case("udp") {
+        "udp"
+    } else {
+        "tcp"
+    }
+}
+
+pub(crate) fn duplicate_host_port(ports: &[PortMapping]) -> Option<u16> {
+    let mut seen = std::collections::HashSet::new();
+    ports
+        .iter()
+        .filter(|p| p.host_port != 0)
+        .find(|p| !seen.insert((p.host_port, normalize_protocol(&p.protocol))))
+        .map(|p| p.host_port)
+}
+
+/// Whether binding `port` on the host fails because it is taken. Both the
+/// wildcard and loopback addresses are tried: on macOS a wildcard bind
+/// succeeds next to a listener on 127.0.0.1. Other failures (e.g. a
+/// privileged port) are not conflicts.
+fn host_port_in_use(port: u16, protocol: &str) -> bool {
+    [Ipv4Addr::UNSPECIFIED, Ipv4Addr::LOCALHOST]
+        .into_iter()
+        .any(|addr| {
+            let result = if protocol == "udp" {
+                UdpSocket::bind((addr, port)).map(drop)
+            } else {
+                TcpListener::bind((addr, port)).map(drop)
+            };
+            matches!(result, Err(e) if e.kind() == std::io::ErrorKind::AddrInUse)
+        })
+}
+
+/// Name and pid of the process holding `port`, via `lsof`.
+#[cfg(unix)]
+fn process_holding_port(port: u16, protocol: &str) -> Option<String> {
+    let mut cmd = std::process::Command::new("lsof");
+    cmd.arg("-nP").arg("-Fpc");
+    if protocol == "udp" {
+        cmd.arg(format!("-iUDP:{}", port));
+    } else {
+        cmd.arg(format!("-iTCP:{}", port)).arg("-sTCP:LISTEN");
+    }
+    let output = cmd.output().ok()?;
+    parse_lsof_process(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(unix))]
+fn process_holding_port(_port: u16, _protocol: &str) -> Option<String> {
+    None
+}
+
+/// First process of `lsof -F pc` output (`p<pid>` / `c<command>` lines).
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_lsof_process(output: &str) -> Option<String> {
+    let mut pid = None;
+    for line in output.lines() {
+        if let Some(value) = line.strip_prefix('p') {
+            pid = Some(value);
+        } else if let Some(command) = line.strip_prefix('c') {
+            return Some(match pid {
+                Some(pid) => format!("{} (pid {})", command, pid),
+                None => command.to_string(),
+            });
+        }
+    }
+    pid.map(|pid| format!("pid {}", pid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(host_port: u16, protocol: &str) -> PortMapping {
+        PortMapping {
+            host_port,
+            container_port: 80,
+            protocol: protocol.to_string(),
+        }
+    }
+
+    #[test]
+    fn detects_bound_host_port() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(host_port_in_use(port, "tcp"));
+        drop(listener);
+        assert!(!host_port_in_use(port, "tcp"));
+    }
+
+    #[test]
+    fn rejects_duplicate_host_ports() {
+        assert_eq!(
+            duplicate_host_port(&[mapping(8080, "tcp"), mapping(8080, "TCP")]),
+            Some(8080)
+        );
+        assert_eq!(
+            duplicate_host_port(&[mapping(53, "tcp"), mapping(53, "udp")]),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_lsof_field_output() {
+        assert_eq!(
+            parse_lsof_process("p4242\ncnode\nf23\n").as_deref(),
+            Some("node (pid 4242)")
+        );
+        assert_eq!(parse_lsof_process(""), None);
+    }
+
+    #[test]
+    fn conflict_messages_name_the_owner() {
+        let conflict = PortConflict {
+            host_port: 5432,
+            protocol: "tcp".to_string(),
+            owner: PortOwner::Container {
+                id: "0123456789abcdef".to_string(),
+                name: "db".to_string(),
+            },
+        };
+        assert_eq!(
+            conflict.to_string(),
+            "5432/tcp is published by container 'db' (0123456789ab)"
+        );
+    }
+}
@@ -0,0 +1,496 @@
+//! Removal previews and `system prune`.
+//!
+//! Destructive CLI commands can report what they would remove — IDs, names
+//! and the disk space reclaimed — before acting (`--dry-run`). The preview
+//! mirrors Docker's own rules: `system prune` removes stopped containers,
+//! networks and dangling images that nothing else uses, all unused images
+//! with `all_images`, and anonymous unused volumes with `volumes`.
+//!
+//! Sizes are what Docker reports per object. Image layers shared with
+//! images that stay are counted anyway, so image totals are upper bounds.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use bollard::container::{InspectContainerOptions, ListContainersOptions};
+use bollard::image::{ListImagesOptions, PruneImagesOptions};
+use bollard::models::{ContainerSummary, ImageSummary, Network, Volume};
+use bollard::network::ListNetworksOptions;
+use bollard::volume::ListVolumesOptions;
+use bollard::Docker;
+use serde::Serialize;
+
+use crate::error::AppError;
+
+const DOCKER_PRUNE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Networks every engine has; never removed.
+const PREDEFINED_NETWORKS: &[&str] = &["bridge", "host", "none"];
+
+/// Label Docker puts on volumes created without a name.
+const ANONYMOUS_VOLUME_LABEL: &str = "com.docker.volume.anonymous";
+
+/// Kind of a removed object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResourceKind {
+    Container,
+    Image,
+    Network,
+    Volume,
+    Mount,
+    /// A BuildKit cache record (`builder prune`).
+    #[serde(rename = "cache")]
+    BuildCache,
+}
+
+impl std::fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ResourceKind::Container => "container",
+            ResourceKind::Image => "image",
+            ResourceKind::Network => "network",
+            ResourceKind::Volume => "volume",
+            ResourceKind::Mount => "mount",
+            ResourceKind::BuildCache => "cache",
+        })
+    }
+}
+
+/// One object that is (or would be) removed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovalItem {
+    pub kind: ResourceKind,
+    pub id: String,
+    pub name: String,
+    pub size_bytes: Option<u64>,
+}
+
+/// Objects removed, or to be removed when `dry_run` is set.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovalReport {
+    pub dry_run: bool,
+    pub items: Vec<RemovalItem>,
+    /// Disk space freed (an upper bound for previews with images).
+    pub reclaimed_bytes: u64,
+}
+
+impl RemovalReport {
+    fn preview() -> Self {
+        Self {
+            dry_run: true,
+            ..Default::default()
+        }
+    }
+
+    /// Add an item, counting its size.
+    pub fn push(&mut self, item: RemovalItem) {
+        self.reclaimed_bytes += item.size_bytes.unwrap_or(0);
+        self.items.push(item);
+    }
+
+    /// Items of one kind.
+    pub fn count(&self, kind: ResourceKind) -> usize {
+        self.items.iter().filter(|item| item.kind == kind).count()
+    }
+}
+
+/// What `system prune` covers beyond stopped containers, unused networks
+/// and dangling images.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PruneOptions {
+    /// Remove every image no remaining container uses.
+    pub all_images: bool,
+    /// Remove anonymous volumes no remaining container uses.
+    pub volumes: bool,
+}
+
+/// Preview `container delete` of `ids`. Running containers need `force`,
+/// as the deletion itself would.
+pub async fn preview_container_delete(
+    docker: &Docker,
+    ids: &[String],
+    force: bool,
+) -> Result<RemovalReport, AppError> {
+    let mut report = RemovalReport::preview();
+    for id in ids {
+        let data = docker
+            .inspect_container(id, Some(InspectContainerOptions { size: true }))
+            .await?;
+        let running = data.state.as_ref().and_then(|s| s.running).unwrap_or(false);
+        if running && !force {
+            return Err(AppError::Validation(format!(
+                "Container {} is running; stop it or use --force",
+                id
+            )));
+        }
+        report.push(RemovalItem {
+            kind: ResourceKind::Container,
+            id: short_id(data.id.as_deref().unwrap_or(id)),
+            name: data
+                .name
+                .as_deref()
+                .unwrap_or(id)
+                .trim_start_matches('/')
+                .to_string(),
+            size_bytes: data.size_rw.and_then(|s| u64::try_from(s).ok()),
+        });
+    }
+    Ok(report)
+}
+
+/// Preview `image delete` of `id`.
+pub async fn preview_image_delete(docker: &Docker, id: &str) -> Result<RemovalReport, AppError> {
+    let data = docker.inspect_image(id).await?;
+    let mut report = RemovalReport::preview();
+    report.push(RemovalItem {
+        kind: ResourceKind::Image,
+        id: short_id(data.id.as_deref().unwrap_or(id)),
+        name: data
+            .repo_tags
+            .filter(|tags| !tags.is_empty())
+            .map(|tags| tags.join(", "))
+            .unwrap_or_else(|| "<none>".to_string()),
+        size_bytes: data.size.and_then(|s| u64::try_from(s).ok()),
+    });
+    Ok(report)
+}
+
+/// Preview `system prune`.
+pub async fn preview_system_prune(
+    docker: &Docker,
+    options: PruneOptions,
+) -> Result<RemovalReport, AppError> {
+    let containers = docker
+        .list_containers(Some(ListContainersOptions::<String> {
+            all: true,
+            size: true,
+            ..Default::default()
+        }))
+        .await?;
+    let images = docker
+        .list_images(Some(ListImagesOptions::<String> {
+            all: false,
+            ..Default::default()
+        }))
+        .await?;
+    let networks = docker
+        .list_networks(None::<ListNetworksOptions<String>>)
+        .await?;
+    let volumes = if options.volumes {
+        docker
+            .list_volumes(None::<ListVolumesOptions<String>>)
+            .await?
+            .volumes
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    Ok(plan_prune(
+        &containers,
+        &images,
+        &networks,
+        &volumes,
+        options,
+    ))
+}
+
+/// Run `system prune` and report what Docker removed.
+pub async fn system_prune(
+    docker: &Docker,
+    options: PruneOptions,
+) -> Result<RemovalReport, AppError> {
+    tokio::time::timeout(DOCKER_PRUNE_TIMEOUT, prune(docker, options))
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Docker prune timed out after {:?}",
+                DOCKER_PRUNE_TIMEOUT
+            ))
+        })?
+}
+
+async fn prune(docker: &Docker, options: PruneOptions) -> Result<RemovalReport, AppError> {
+    let mut report = RemovalReport::default();
+    let mut reclaimed = 0_i64;
+
+    let containers = docker
+        .prune_containers(None::<bollard::container::PruneContainersOptions<String>>)
+        .await?;
+    reclaimed += containers.space_reclaimed.unwrap_or(0);
+    for id in containers.containers_deleted.unwrap_or_default() {
+        report.items.push(deleted(ResourceKind::Container, &id));
+    }
+
+    let networks = docker
+        .prune_networks(None::<bollard::network::PruneNetworksOptions<String>>)
+        .await?;
+    for name in networks.networks_deleted.unwrap_or_default() {
+        report.items.push(RemovalItem {
+            kind: ResourceKind::Network,
+            id: String::new(),
+            name,
+            size_bytes: None,
+        });
+    }
+
+    let mut filters = HashMap::new();
+    if options.all_images {
+        filters.insert("dangling".to_string(), vec!["false".to_string()]);
+    }
+    let images = docker
+        .prune_images(Some(PruneImagesOptions { filters }))
+        .await?;
+    reclaimed += images.space_reclaimed.unwrap_or(0);
+    for item in images.images_deleted.unwrap_or_default() {
+        if let Some(id) = item.deleted {
+            report.items.push(deleted(ResourceKind::Image, &id));
+        }
+    }
+
+    if options.volumes {
+        let volumes = docker
+            .prune_volumes(None::<bollard::volume::PruneVolumesOptions<String>>)
+            .await?;
+        reclaimed += volumes.space_reclaimed.unwrap_or(0);
+        for name in volumes.volumes_deleted.unwrap_or_default() {
+            report.items.push(RemovalItem {
+                kind: ResourceKind::Volume,
+                id: String::new(),
+                name,
+                size_bytes: None,
+            });
+        }
+    }
+
+    report.reclaimed_bytes = u64::try_from(reclaimed).unwrap_or(0);
+    Ok(report)
+}
+
+fn deleted(kind: ResourceKind, id: &str) -> RemovalItem {
+    RemovalItem {
+        kind,
+        id: short_id(id),
+        name: String::new(),
+        size_bytes: None,
+    }
+}
+
+/// Apply Docker's prune rules to listed objects.
+fn plan_prune(
+    containers: &[ContainerSummary],
+    images: &[ImageSummary],
+    networks: &[Network],
+    volumes: &[Volume],
+    options: PruneOptions,
+) -> RemovalReport {
+    let mut report = RemovalReport::preview();
+    let stopped =
+        |c: &ContainerSummary| matches!(c.state.as_deref(), Some("exited" | "created" | "dead"));
+
+    let mut used_images = HashSet::new();
+    let mut used_networks = HashSet::new();
+    let mut used_volumes = HashSet::new();
+    for container in containers {
+        if stopped(container) {
+            report.push(RemovalItem {
+                kind: ResourceKind::Container,
+                id: short_id(container.id.as_deref().unwrap_or_default()),
+                name: container
+                    .names
+                    .as_ref()
+                    .and_then(|names| names.first())
+                    .map(|name| name.trim_start_matches('/').to_string())
+                    .unwrap_or_default(),
+                size_bytes: container.size_rw.and_then(|s| u64::try_from(s).ok()),
+            });
+            continue;
+        }
+        used_images.extend(container.image_id.clone());
+        if let Some(networks) = container
+            .network_settings
+            .as_ref()
+            .and_then(|s| s.networks.as_ref())
+        {
+            used_networks.extend(networks.keys().cloned());
+        }
+        for mount in container.mounts.iter().flatten() {
+            used_volumes.extend(mount.name.clone());
+        }
+    }
+
+    for network in networks {
+        let name = network.name.clone().unwrap_or_default();
+        if PREDEFINED_NETWORKS.contains(&name.as_str())
+            || network.scope.as_deref() == Some("swarm")
+            || used_networks.contains(&name)
+        {
+            continue;
+        }
+        report.push(RemovalItem {
+            kind: ResourceKind::Network,
+            id: short_id(network.id.as_deref().unwrap_or_default()),
+            name,
+            size_bytes: None,
+        });
+    }
+
+    for image in images {
+        let tags: Vec<_> = image
+            .repo_tags
+            .iter()
+            .filter(|tag| tag.as_str() != "<none>:<none>")
+            .cloned()
+            .collect();
+        let dangling = tags.is_empty();
+        if used_images.contains(&image.id) || !(dangling || options.all_images) {
+            continue;
+        }
+        report.push(RemovalItem {
+            kind: ResourceKind::Image,
+            id: short_id(&image.id),
+            name: if dangling {
+                "<none>".to_string()
+            } else {
+                tags.join(", ")
+            },
+            size_bytes: u64::try_from(image.size).ok(),
+        });
+    }
+
+    if options.volumes {
+        for volume in volumes {
+            let anonymous = volume.labels.contains_key(ANONYMOUS_VOLUME_LABEL);
+            if !anonymous || used_volumes.contains(&volume.name) {
+                continue;
+            }
+            report.push(RemovalItem {
+                kind: ResourceKind::Volume,
+                id: String::new(),
+                name: volume.name.clone(),
+                size_bytes: volume
+                    .usage_data
+                    .as_ref()
+                    .and_then(|u| u64::try_from(u.size).ok()),
+            });
+        }
+    }
+    report
+}
+
+/// `sha256:`-less 12-character ID.
+pub(crate) fn short_id(id: &str) -> String {
+    id.trim_start_matches("sha256:").chars().take(12).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bollard::models::{ContainerSummaryNetworkSettings, MountPoint};
+
+    use super::*;
+
+    fn container(id: &str, state: &str, image: &str, network: &str) -> ContainerSummary {
+        ContainerSummary {
+            id: Some(id.to_string()),
+            names: Some(vec![format!("/{}", id)]),
+            image_id: Some(image.to_string()),
+            state: Some(state.to_string()),
+            size_rw: Some(100),
+            network_settings: Some(ContainerSummaryNetworkSettings {
+                networks: Some(HashMap::from([(network.to_string(), Default::default())])),
+            }),
+            mounts: Some(vec![MountPoint {
+                name: Some(format!("{}-data", id)),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }
+    }
+
+    fn image(id: &str, tags: &[&str], size: i64) -> ImageSummary {
+        ImageSummary {
+            id: id.to_string(),
+            repo_tags: tags.iter().map(|t| t.to_string()).collect(),
+            size,
+            ..Default::default()
+        }
+    }
+
+    fn network(name: &str) -> Network {
+        Network {
+            name: Some(name.to_string()),
+            id: Some(format!("{}-id", name)),
+            scope: Some("local".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn volume(name: &str, anonymous: bool) -> Volume {
+        Volume {
+            name: name.to_string(),
+            labels: if anonymous {
+                HashMap::from([(ANONYMOUS_VOLUME_LABEL.to_string(), String::new())])
+            } else {
+                HashMap::new()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn prune_plan_follows_docker_rules() {
+        let containers = [
+            container("web", "running", "sha256:nginx", "app"),
+            container("job", "exited", "sha256:batch", "jobs"),
+        ];
+        let images = [
+            image("sha256:nginx", &["nginx:latest"], 1000),
+            image("sha256:batch", &["batch:1"], 500),
+            image("sha256:old", &["<none>:<none>"], 300),
+        ];
+        let networks = [network("bridge"), network("app"), network("jobs")];
+        let volumes = [
+            volume("web-data", true),
+            volume("job-data", true),
+            volume("named", false),
+        ];
+
+        let plan = plan_prune(
+            &containers,
+            &images,
+            &networks,
+            &volumes,
+            PruneOptions::default(),
+        );
+        let names: Vec<_> = plan
+            .items
+            .iter()
+            .map(|i| format!("{}:{}", i.kind, i.name))
+            .collect();
+        assert_eq!(names, ["container:job", "network:jobs", "image:<none>"]);
+        assert_eq!(plan.reclaimed_bytes, 400);
+        assert!(plan.dry_run);
+
+        let plan = plan_prune(
+            &containers,
+            &images,
+            &networks,
+            &volumes,
+            PruneOptions {
+                all_images: true,
+                volumes: true,
+            },
+        );
+        assert_eq!(plan.count(ResourceKind::Image), 2);
+        let volumes: Vec<_> = plan
+            .items
+            .iter()
+            .filter(|i| i.kind == ResourceKind::Volume)
+            .map(|i| i.name.as_str())
+            .collect();
+        assert_eq!(volumes, ["job-data"]);
+        assert_eq!(plan.reclaimed_bytes, 900);
+    }
+}
@@ -0,0 +1,2452 @@
+//! Container registry HTTP clients.
+//!
+//! Image search goes through [`RegistrySearchProvider`], one implementation
+//! per registry. Manifest inspection talks to the registry's distribution
+//! (v2) API via [`RegistryClient`]. All requests use the host network and
+//! never require a Docker daemon. Proxies come from the registry's
+//! `config.toml` entry, `CRATEBAY_RUNTIME_HTTP_PROXY` or `registry_proxy`
+//! (see [`registry_http_client`]).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, ACCEPT, CONTENT_TYPE, WWW_AUTHENTICATE};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::container::format_reqwest_error;
+use crate::error::AppError;
+use crate::models::{
+    ArtifactKind, ImageDetails, ImageLayer, ImageLayers, ImageSearchResponse, ImageSearchResult,
+    ImageSearchSourceError, RegistryAuth, RegistryCatalog, RegistryCatalogNode, RegistryTagList,
+    RemoteImageManifest, RemoteLayer, RemotePlatform, RemoteReferrer,
+};
+use crate::MutexExt;
+
+/// Default number of search results when no limit is given.
+const DEFAULT_SEARCH_LIMIT: u64 = 25;
+
+/// Timeout for registry HTTP requests.
+const REGISTRY_HTTP_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Timeout for registry API calls that may download blobs.
+const REGISTRY_API_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Docker Hub's registry API host.
+pub const DOCKER_HUB_REGISTRY: &str = "registry-1.docker.io";
+
+/// Manifest media types accepted when resolving a reference (indexes included).
+const MANIFEST_ACCEPT: &[&str] = &[
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+];
+
+/// Per-source timeout of [`search_sources`]; a slow registry only drops its
+/// own results.
+const SEARCH_SOURCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Search sources accepted by [`search_provider`].
+pub const SEARCH_SOURCES: &[&str] = &["dockerhub", "quay", "ghcr", "gitlab"];
+
+/// Sources searched when none is given. GHCR needs an owner and a token, so
+/// it is only searched on request.
+pub const DEFAULT_SEARCH_SOURCES: &[&str] = &["dockerhub", "quay"];
+
+/// A registry that supports searching for repositories.
+#[async_trait]
+pub trait RegistrySearchProvider: Send + Sync {
+    /// Source identifier reported in [`ImageSearchResult::source`].
+    fn source(&self) -> &'static str;
+
+    /// Search repositories matching `query`, returning at most `limit` results.
+    async fn search(&self, query: &str, limit: u64) -> Result<Vec<ImageSearchResult>, AppError>;
+}
+
+/// Resolve a search provider by source name (`dockerhub`, `quay`, `ghcr`, `gitlab`).
+pub fn search_provider(source: &str) -> Result<Box<dyn RegistrySearchProvider>, AppError> {
+    match source.trim().to_ascii_lowercase().as_str() {
+        "" | "dockerhub" | "docker.io" | "hub" => Ok(Box::new(DockerHubSearch)),
+        "quay" | "quay.io" => Ok(Box::new(QuaySearch)),
+        "ghcr" | "ghcr.io" | "github" => Ok(Box::new(GhcrSearch::from_env())),
+        "gitlab" | "registry.gitlab.com" => Ok(Box::new(GitLabSearch::from_env())),
+        other => Err(AppError::Validation(format!(
+            "Unknown image search source '{}' (expected one of: {})",
+            other,
+            SEARCH_SOURCES.join(", ")
+        ))),
+    }
+}
+
+/// Search `source` over HTTP.
+pub async fn search_images(
+    source: &str,
+    query: &str,
+    limit: Option<u64>,
+) -> Result<Vec<ImageSearchResult>, AppError> {
+    let term = query.trim();
+    if term.is_empty() {
+        return Err(AppError::Validation(
+            "Image search query cannot be empty".to_string(),
+        ));
+    }
+    let provider = search_provider(source)?;
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, 100);
+    let mut results = provider.search(term, limit).await?;
+    results.truncate(limit as usize);
+    Ok(results)
+}
+
+/// Search several sources concurrently and merge the results.
+///
+/// Each source runs under its own timeout. Failed sources are reported in
+/// [`ImageSearchResponse::errors`] next to the results of the others; the
+/// call only fails when every source does.
+pub async fn search_sources(
+    sources: &[&str],
+    query: &str,
+    limit: Option<u64>,
+) -> Result<ImageSearchResponse, AppError> {
+    let term = query.trim();
+    if term.is_empty() {
+        return Err(AppError::Validation(
+            "Image search query cannot be empty".to_string(),
+        ));
+    }
+    let providers = sources
+        .iter()
+        .map(|source| search_provider(source))
+        .collect::<Result<Vec<_>, _>>()?;
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, 100);
+
+    let searches = providers.iter().map(|provider| async move {
+        let outcome =
+            match tokio::time::timeout(SEARCH_SOURCE_TIMEOUT, provider.search(term, limit)).await {
+                Ok(result) => result,
+                Err(_) => Err(AppError::Runtime(format!(
+                    "Search timed out after {:?}",
+                    SEARCH_SOURCE_TIMEOUT
+                ))),
+            };
+        (provider.source(), outcome)
+    });
+
+    let mut response = ImageSearchResponse::default();
+    for (source, outcome) in futures_util::future::join_all(searches).await {
+        match outcome {
+            Ok(results) => response.results.extend(results),
+            Err(e) => response.errors.push(ImageSearchSourceError {
+                source: source.to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    if !providers.is_empty() && response.errors.len() == providers.len() {
+        let details = response
+            .errors
+            .iter()
+            .map(|e| format!("{}: {}", e.source, e.error))
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(AppError::Runtime(format!(
+            "Image search failed. {}",
+            details
+        )));
+    }
+
+    rank_search_results(&mut response.results);
+    response.results.truncate(limit as usize);
+    Ok(response)
+}
+
+/// Drop duplicate references and order by stars + pulls, highest first.
+/// Ties keep provider order.
+fn rank_search_results(results: &mut Vec<ImageSearchResult>) {
+    let mut seen = std::collections::HashSet::new();
+    results.retain(|r| seen.insert(r.reference.clone()));
+    results.sort_by_key(|r| {
+        std::cmp::Reverse(r.stars.unwrap_or(0).saturating_add(r.pulls.unwrap_or(0)))
+    });
+}
+
+/// Build an HTTP client, honouring `CRATEBAY_RUNTIME_HTTP_PROXY`.
+pub(crate) fn http_client(timeout: Duration) -> Result<reqwest::Client, AppError> {
+    build_http_client(timeout, env_token(&["CRATEBAY_RUNTIME_HTTP_PROXY"]), None)
+}
+
+/// Build an HTTP client for talking to `registry`.
+///
+/// The proxy is, in order: the registry's `[registries.<host>]` entry in
+/// `config.toml`, `CRATEBAY_RUNTIME_HTTP_PROXY`, then `registry_proxy`. The
+/// entry's `ca_file` is trusted in addition to the system roots.
+pub(crate) fn registry_http_client(
+    registry: &str,
+    timeout: Duration,
+) -> Result<reqwest::Client, AppError> {
+    configured_http_client(&Config::load_or_default(), registry, timeout)
+}
+
+fn configured_http_client(
+    config: &Config,
+    registry: &str,
+    timeout: Duration,
+) -> Result<reqwest::Client, AppError> {
+    let settings = config.registry(registry);
+    let proxy = settings
+        .and_then(|s| s.proxy.clone())
+        .or_else(|| env_token(&["CRATEBAY_RUNTIME_HTTP_PROXY"]))
+        .or_else(|| config.registry_proxy.clone());
+    build_http_client(timeout, proxy, settings.and_then(|s| s.ca_file.as_deref()))
+}
+
+fn build_http_client(
+    timeout: Duration,
+    proxy: Option<String>,
+    ca_file: Option<&Path>,
+) -> Result<reqwest::Client, AppError> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(timeout)
+        .user_agent(concat!("cratebay/", env!("CARGO_PKG_VERSION")));
+    if let Some(proxy) = proxy {
+        let proxy_url = if proxy.contains("://") {
+            proxy.clone()
+        } else {
+            format!("http://{}", proxy)
+        };
+        builder = builder.proxy(
+            reqwest::Proxy::all(&proxy_url)
+                .map_err(|e| AppError::Runtime(format!("Invalid proxy '{}': {}", proxy, e)))?,
+        );
+    }
+    if let Some(path) = ca_file {
+        let invalid = |e: &dyn fmt::Display| {
+            AppError::Validation(format!("Invalid CA bundle {}: {}", path.display(), e))
+        };
+        let pem = std::fs::read(path).map_err(|e| invalid(&e))?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| invalid(&e))?;
+        if certs.is_empty() {
+            return Err(invalid(&"no certificates found"));
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| AppError::Runtime(format!("Failed to build HTTP client: {}", e)))
+}
+
+fn env_token(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
+
+async fn send_json<T: serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    registry: &str,
+) -> Result<T, AppError> {
+    let resp = request.send().await.map_err(|e| {
+        AppError::Runtime(format!(
+            "{} request failed: {}",
+            registry,
+            format_reqwest_error(&e)
+        ))
+    })?;
+
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(AppError::Runtime(format!(
+            "{} API returned {}: {}",
+            registry, status, body
+        )));
+    }
+
+    resp.json()
+        .await
+        .map_err(|e| AppError::Runtime(format!("Failed to parse {} response: {}", registry, e)))
+}
+
+// ---------------------------------------------------------------------------
+// Docker Hub
+// ---------------------------------------------------------------------------
+
+/// Docker Hub search via `hub.docker.com/v2/search/repositories`.
+pub struct DockerHubSearch;
+
+#[async_trait]
+impl RegistrySearchProvider for DockerHubSearch {
+    fn source(&self) -> &'static str {
+        "dockerhub"
+    }
+
+    async fn search(&self, query: &str, limit: u64) -> Result<Vec<ImageSearchResult>, AppError> {
+        crate::container::image_search_dockerhub(query, Some(limit)).await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Quay
+// ---------------------------------------------------------------------------
+
+/// Quay.io search via `quay.io/api/v1/find/repositories` (public repositories).
+pub struct QuaySearch;
+
+#[derive(Debug, Deserialize)]
+struct QuayFindResponse {
+    #[serde(default)]
+    results: Vec<QuayFindResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QuayFindResult {
+    #[serde(default)]
+    kind: String,
+    name: String,
+    namespace: QuayNamespace,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    stars: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QuayNamespace {
+    name: String,
+}
+
+fn map_quay_results(response: QuayFindResponse) -> Vec<ImageSearchResult> {
+    response
+        .results
+        .into_iter()
+        .filter(|r| r.kind.is_empty() || r.kind == "repository")
+        .map(|r| ImageSearchResult {
+            source: "quay".to_string(),
+            reference: format!("quay.io/{}/{}", r.namespace.name, r.name),
+            description: r.description.unwrap_or_default(),
+            stars: r.stars,
+            pulls: None,
+            official: false,
+        })
+        .collect()
+}
+
+#[async_trait]
+impl RegistrySearchProvider for QuaySearch {
+    fn source(&self) -> &'static str {
+        "quay"
+    }
+
+    async fn search(&self, query: &str, limit: u64) -> Result<Vec<ImageSearchResult>, AppError> {
+        let client = registry_http_client("quay.io", REGISTRY_HTTP_TIMEOUT)?;
+        let response: QuayFindResponse = send_json(
+            client
+                .get("https://quay.io/api/v1/find/repositories")
+                .query(&[("query", query), ("page", "1")]),
+            "Quay",
+        )
+        .await?;
+        let mut results = map_quay_results(response);
+        results.truncate(limit as usize);
+        Ok(results)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// GitHub Container Registry
+// ---------------------------------------------------------------------------
+
+/// GHCR search via the GitHub packages API.
+///
+/// GitHub has no global package search, so the query names an owner
+/// (user or organization), optionally followed by `/<name filter>`:
+/// `my-org` lists all of the owner's container packages, `my-org/api`
+/// those whose name contains `api`. Requires a token with `read:packages`
+/// in `CRATEBAY_GHCR_TOKEN` or `GITHUB_TOKEN`.
+pub struct GhcrSearch {
+    api_base: String,
+    token: Option<String>,
+}
+
+impl GhcrSearch {
+    pub fn from_env() -> Self {
+        Self {
+            api_base: "https://api.github.com".to_string(),
+            token: env_token(&["CRATEBAY_GHCR_TOKEN", "GITHUB_TOKEN"]),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GhcrPackage {
+    name: String,
+    #[serde(default)]
+    visibility: Option<String>,
+    #[serde(default)]
+    repository: Option<GhcrRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhcrRepository {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    stargazers_count: Option<u64>,
+}
+
+/// Split a GHCR query into `(owner, name filter)`.
+fn parse_ghcr_query(query: &str) -> Result<(String, String), AppError> {
+    let query = query.trim().trim_start_matches("ghcr.io/");
+    let (owner, filter) = query.split_once('/').unwrap_or((query, ""));
+    if owner.is_empty() {
+        return Err(AppError::Validation(
+            "GHCR search needs an owner, e.g. 'my-org' or 'my-org/api'".to_string(),
+        ));
+    }
+    Ok((owner.to_ascii_lowercase(), filter.to_ascii_lowercase()))
+}
+
+fn map_ghcr_packages(
+    owner: &str,
+    filter: &str,
+    packages: Vec<GhcrPackage>,
+) -> Vec<ImageSearchResult> {
+    packages
+        .into_iter()
+        .filter(|pkg| pkg.name.to_ascii_lowercase().contains(filter))
+        .map(|pkg| {
+            let repository = pkg.repository.unwrap_or(GhcrRepository {
+                description: None,
+                stargazers_count: None,
+            });
+            let mut description = repository.description.unwrap_or_default();
+            if pkg.visibility.as_deref() == Some("private") {
+                description = format!("[private] {}", description).trim().to_string();
+            }
+            ImageSearchResult {
+                source: "ghcr".to_string(),
+                reference: format!("ghcr.io/{}/{}", owner, pkg.name.to_ascii_lowercase()),
+                description,
+                stars: repository.stargazers_count,
+                pulls: None,
+                official: false,
+            }
+        })
+        .collect()
+}
+
+#[async_trait]
+impl RegistrySearchProvider for GhcrSearch {
+    fn source(&self) -> &'static str {
+        "ghcr"
+    }
+
+    async fn search(&self, query: &str, limit: u64) -> Result<Vec<ImageSearchResult>, AppError> {
+        let (owner, filter) = parse_ghcr_query(query)?;
+        let Some(token) = self.token.as_deref() else {
+            return Err(AppError::Validation(
+                "GHCR search requires a GitHub token with read:packages \
+                 (set CRATEBAY_GHCR_TOKEN or GITHUB_TOKEN)"
+                    .to_string(),
+            ));
+        };
+
+        let client = registry_http_client("ghcr.io", REGISTRY_HTTP_TIMEOUT)?;
+        let mut last_err = None;
+        // The owner may be either an organization or a user.
+        for kind in ["orgs", "users"] {
+            let request = client
+                .get(format!("{}/{}/{}/packages", self.api_base, kind, owner))
+                .query(&[("package_type", "container"), ("per_page", "100")])
+                .bearer_auth(token)
+                .header("Accept", "application/vnd.github+json");
+            match send_json::<Vec<GhcrPackage>>(request, "GitHub").await {
+                Ok(packages) => {
+                    let mut results = map_ghcr_packages(&owner, &filter, packages);
+                    results.truncate(limit as usize);
+                    return Ok(results);
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            AppError::Runtime(format!("GHCR search failed for owner '{}'", owner))
+        }))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// GitLab Container Registry
+// ---------------------------------------------------------------------------
+
+/// GitLab registry search: finds projects matching the query and lists
+/// their container repositories.
+///
+/// Targets gitlab.com unless `CRATEBAY_GITLAB_URL` points at a self-managed
+/// instance. `GITLAB_TOKEN` is sent when set, which makes private projects
+/// visible.
+pub struct GitLabSearch {
+    base_url: String,
+    token: Option<String>,
+}
+
+impl GitLabSearch {
+    pub fn from_env() -> Self {
+        let base_url = env_token(&["CRATEBAY_GITLAB_URL"])
+            .unwrap_or_else(|| "https://gitlab.com".to_string())
+            .trim_end_matches('/')
+            .to_string();
+        Self {
+            base_url,
+            token: env_token(&["GITLAB_TOKEN"]),
+        }
+    }
+
+    /// API host, used to look up its proxy settings.
+    fn host(&self) -> &str {
+        let rest = self
+            .base_url
+            .split_once("://")
+            .map_or(self.base_url.as_str(), |(_, rest)| rest);
+        rest.split('/').next().unwrap_or(rest)
+    }
+
+    fn get(&self, client: &reqwest::Client, path: &str) -> reqwest::RequestBuilder {
+        let request = client.get(format!("{}/api/v4{}", self.base_url, path));
+        match self.token.as_deref() {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabProject {
+    id: u64,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    star_count: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabRegistryRepository {
+    location: String,
+}
+
+#[async_trait]
+impl RegistrySearchProvider for GitLabSearch {
+    fn source(&self) -> &'static str {
+        "gitlab"
+    }
+
+    async fn search(&self, query: &str, limit: u64) -> Result<Vec<ImageSearchResult>, AppError> {
+        let client = registry_http_client(self.host(), REGISTRY_HTTP_TIMEOUT)?;
+        let per_page = limit.to_string();
+        let projects: Vec<GitLabProject> = send_json(
+            self.get(&client, "/projects").query(&[
+                ("search", query),
+                ("simple", "true"),
+                ("order_by", "star_count"),
+                ("per_page", per_page.as_str()),
+            ]),
+            "GitLab",
+        )
+        .await?;
+
+        // Projects without a registry (or with it disabled) return 403/404;
+        // they are skipped rather than failing the whole search.
+        let lookups = projects.into_iter().map(|project| {
+            let request = self.get(
+                &client,
+                &format!("/projects/{}/registry/repositories", project.id),
+            );
+            async move {
+                let repos = send_json::<Vec<GitLabRegistryRepository>>(request, "GitLab")
+                    .await
+                    .unwrap_or_default();
+                (project, repos)
+            }
+        });
+
+        let mut results = Vec::new();
+        for (project, repos) in futures_util::future::join_all(lookups).await {
+            for repo in repos {
+                results.push(ImageSearchResult {
+                    source: "gitlab".to_string(),
+                    reference: repo.location,
+                    description: project.description.clone().unwrap_or_default(),
+                    stars: project.star_count,
+                    pulls: None,
+                    official: false,
+                });
+            }
+        }
+        Ok(results)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Image references
+// ---------------------------------------------------------------------------
+
+/// A parsed image reference: `[registry/]repository[:tag][@digest]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageReference {
+    /// Registry API host, e.g. `ghcr.io` or [`DOCKER_HUB_REGISTRY`].
+    pub registry: String,
+    /// Repository path, e.g. `library/nginx`.
+    pub repository: String,
+    pub tag: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl ImageReference {
+    /// Parse an image reference. A reference without a registry host refers
+    /// to Docker Hub; single-segment Hub repositories get `library/`.
+    pub fn parse(reference: &str) -> Result<Self, AppError> {
+        let reference = reference.trim();
+        let invalid = || AppError::Validation(format!("Invalid image reference: '{}'", reference));
+        if reference.is_empty() || reference.contains(char::is_whitespace) {
+            return Err(invalid());
+        }
+
+        let (name, digest) = match reference.split_once('@') {
+            Some((name, digest)) if digest.contains(':') => (name, Some(digest.to_string())),
+            Some(_) => return Err(invalid()),
+            None => (reference, None),
+        };
+        let (name, tag) = match name.rsplit_once(':') {
+            Some((repo, tag)) if !tag.contains('/') => {
+                if tag.is_empty() {
+                    return Err(invalid());
+                }
+                (repo, Some(tag.to_string()))
+            }
+            _ => (name, None),
+        };
+
+        let (registry, repository) = match name.split_once('/') {
+            Some((host, rest))
+                if host.contains('.') || host.contains(':') || host == "localhost" =>
+            {
+                (host.to_string(), rest.to_string())
+            }
+            _ => (DOCKER_HUB_REGISTRY.to_string(), name.to_string()),
+        };
+        let registry = match registry.as_str() {
+            "docker.io" | "index.docker.io" => DOCKER_HUB_REGISTRY.to_string(),
+            _ => registry,
+        };
+        let repository = if registry == DOCKER_HUB_REGISTRY && !repository.contains('/') {
+            format!("library/{}", repository)
+        } else {
+            repository
+        };
+
+        let valid_repo = !repository.is_empty()
+            && repository.split('/').all(|seg| !seg.is_empty())
+            && repository.chars().all(|c| {
+                c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '_' | '-' | '/')
+            });
+        if !valid_repo {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            registry,
+            repository,
+            tag,
+            digest,
+        })
+    }
+
+    /// Tag or digest to resolve (`latest` when neither is given).
+    pub fn reference(&self) -> &str {
+        self.digest
+            .as_deref()
+            .or(self.tag.as_deref())
+            .unwrap_or("latest")
+    }
+
+    /// Stand-in for registry-wide endpoints (`/v2/_catalog`): no repository.
+    fn catalog(registry: &str) -> Self {
+        Self {
+            registry: registry.to_string(),
+            repository: String::new(),
+            tag: None,
+            digest: None,
+        }
+    }
+
+    fn base_url(&self) -> String {
+        let scheme = if is_local_registry(&self.registry) {
+            "http"
+        } else {
+            "https"
+        };
+        format!("{}://{}/v2/{}", scheme, self.registry, self.repository)
+    }
+}
+
+impl fmt::Display for ImageReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.registry, self.repository)?;
+        match (&self.tag, &self.digest) {
+            (_, Some(digest)) => write!(f, "@{}", digest),
+            (Some(tag), None) => write!(f, ":{}", tag),
+            (None, None) => write!(f, ":latest"),
+        }
+    }
+}
+
+/// Registries reachable over plain HTTP (local development registries).
+fn is_local_registry(registry: &str) -> bool {
+    let host = registry.split(':').next().unwrap_or(registry);
+    matches!(host, "localhost" | "127.0.0.1")
+}
+
+// ---------------------------------------------------------------------------
+// Registry API client
+// ---------------------------------------------------------------------------
+
+/// Minimal client for the registry distribution (v2) API.
+///
+/// Handles `WWW-Authenticate` challenges: bearer tokens are fetched from the
+/// advertised realm (anonymously, or with `auth` when given) and reused for
+/// later requests; basic-auth registries get the credentials directly.
+pub struct RegistryClient {
+    http: reqwest::Client,
+    auth: Option<RegistryAuth>,
+    token: Mutex<Option<String>>,
+    /// `insecure = true` in the registry's config entry.
+    plain_http: bool,
+}
+
+impl RegistryClient {
+    pub fn new(registry: &str, auth: Option<RegistryAuth>) -> Result<Self, AppError> {
+        let config = Config::load_or_default();
+        Ok(Self {
+            http: configured_http_client(&config, registry, REGISTRY_API_TIMEOUT)?,
+            auth,
+            token: Mutex::new(None),
+            plain_http: config.registry(registry).is_some_and(|s| s.insecure),
+        })
+    }
+
+    /// `<scheme>://<registry>/v2/<repository>` for `image`.
+    fn base_url(&self, image: &ImageReference) -> String {
+        let url = image.base_url();
+        if self.plain_http {
+            url.replacen("https://", "http://", 1)
+        } else {
+            url
+        }
+    }
+
+    /// GET `url`, answering an authentication challenge once if needed.
+    async fn get(
+        &self,
+        image: &ImageReference,
+        url: &str,
+        accept: &[&str],
+    ) -> Result<reqwest::Response, AppError> {
+        let resp = self.send(url, accept, self.bearer()?.as_deref()).await?;
+        if resp.status() != StatusCode::UNAUTHORIZED {
+            return check_status(resp, image).await;
+        }
+
+        let challenge = resp
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|v| v.to_str().ok())
+            .map(parse_auth_challenge);
+        let resp = match challenge {
+            Some((scheme, params)) if scheme.eq_ignore_ascii_case("bearer") => {
+                let token = self.fetch_token(image, &params).await?;
+                *self.token.lock_or_recover()? = Some(token.clone());
+                self.send(url, accept, Some(&token)).await?
+            }
+            Some((scheme, _)) if scheme.eq_ignore_ascii_case("basic") && self.auth.is_some() => {
+                let auth = self.auth.as_ref().map(|a| (&a.username, &a.password));
+                let mut request = self.http.get(url).header(ACCEPT, accept.join(", "));
+                if let Some((username, password)) = auth {
+                    request = request.basic_auth(username, Some(password));
+                }
+                request.send().await.map_err(|e| request_error(image, &e))?
+            }
+            _ => resp,
+        };
+        check_status(resp, image).await
+    }
+
+    async fn send(
+        &self,
+        url: &str,
+        accept: &[&str],
+        token: Option<&str>,
+    ) -> Result<reqwest::Response, AppError> {
+        let mut request = self.http.get(url);
+        if !accept.is_empty() {
+            request = request.header(ACCEPT, accept.join(", "));
+        }
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.map_err(|e| {
+            AppError::Runtime(format!(
+                "Registry request to {} failed: {}",
+                url,
+                format_reqwest_error(&e)
+            ))
+        })
+    }
+
+    fn bearer(&self) -> Result<Option<String>, AppError> {
+        Ok(self.token.lock_or_recover()?.clone())
+    }
+
+    async fn fetch_token(
+        &self,
+        image: &ImageReference,
+        params: &HashMap<String, String>,
+    ) -> Result<String, AppError> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            token: Option<String>,
+            access_token: Option<String>,
+        }
+
+        let realm = params.get("realm").ok_or_else(|| {
+            AppError::Runtime(format!(
+                "Registry {} sent a bearer challenge without a realm",
+                image.registry
+            ))
+        })?;
+        let scope = params.get("scope").cloned().unwrap_or_else(|| {
+            if image.repository.is_empty() {
+                "registry:catalog:*".to_string()
+            } else {
+                format!("repository:{}:pull", image.repository)
+            }
+        });
+        let mut query = vec![("scope", scope)];
+        if let Some(service) = params.get("service") {
+            query.push(("service", service.clone()));
+        }
+
+        let mut request = self.http.get(realm).query(&query);
+        if let Some(auth) = &self.auth {
+            request = request.basic_auth(&auth.username, Some(&auth.password));
+        }
+        let resp = send_json::<TokenResponse>(request, "Registry token").await?;
+        resp.token.or(resp.access_token).ok_or_else(|| {
+            AppError::Runtime(format!(
+                "Registry {} returned no token for {}",
+                image.registry, image.repository
+            ))
+        })
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        image: &ImageReference,
+        url: &str,
+        accept: &[&str],
+    ) -> Result<(T, HeaderMap), AppError> {
+        let resp = self.get(image, url, accept).await?;
+        let headers = resp.headers().clone();
+        let body = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Runtime(format!("Failed to parse registry response: {}", e)))?;
+        Ok((body, headers))
+    }
+}
+
+async fn check_status(
+    resp: reqwest::Response,
+    image: &ImageReference,
+) -> Result<reqwest::Response, AppError> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    match status {
+        StatusCode::NOT_FOUND if image.repository.is_empty() => Err(AppError::NotFound {
+            entity: "registry catalog".to_string(),
+            id: image.registry.clone(),
+        }),
+        StatusCode::NOT_FOUND => Err(AppError::NotFound {
+            entity: "image".to_string(),
+            id: image.to_string(),
+        }),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN if image.repository.is_empty() => {
+            Err(AppError::PermissionDenied(format!(
+                "registry {} denied access to its catalog",
+                image.registry
+            )))
+        }
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            Err(AppError::PermissionDenied(format!(
+                "registry {} denied access to {}",
+                image.registry, image.repository
+            )))
+        }
+        _ => {
+            let body = resp.text().await.unwrap_or_default();
+            Err(AppError::Runtime(format!(
+                "Registry {} returned {}: {}",
+                image.registry, status, body
+            )))
+        }
+    }
+}
+
+fn request_error(image: &ImageReference, err: &reqwest::Error) -> AppError {
+    AppError::Runtime(format!(
+        "Registry request to {} failed: {}",
+        image.registry,
+        format_reqwest_error(err)
+    ))
+}
+
+/// Parse a `WWW-Authenticate` header into its scheme and parameters.
+fn parse_auth_challenge(header: &str) -> (String, HashMap<String, String>) {
+    let header = header.trim();
+    let (scheme, rest) = header.split_once(' ').unwrap_or((header, ""));
+    let mut params = HashMap::new();
+    let mut chars = rest.chars().peekable();
+
+    loop {
+        while matches!(chars.peek(), Some(c) if *c == ',' || c.is_whitespace()) {
+            chars.next();
+        }
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        if key.is_empty() {
+            break;
+        }
+        let value = if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut value = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => value.extend(chars.next()),
+                    '"' => break,
+                    c => value.push(c),
+                }
+            }
+            value
+        } else {
+            chars.by_ref().take_while(|c| *c != ',').collect()
+        };
+        params.insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+    (scheme.to_string(), params)
+}
+
+// ---------------------------------------------------------------------------
+// Manifests
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestDocument {
+    #[serde(default)]
+    media_type: Option<String>,
+    /// OCI 1.1 artifacts (Helm charts, SBOMs, signatures) may set this.
+    #[serde(default)]
+    artifact_type: Option<String>,
+    /// Present for manifest lists / OCI indexes.
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+    #[serde(default)]
+    config: Option<Descriptor>,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    #[serde(default)]
+    media_type: Option<String>,
+    digest: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    platform: Option<PlatformSpec>,
+    /// Set on entries of a referrers index.
+    #[serde(default)]
+    artifact_type: Option<String>,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlatformSpec {
+    os: String,
+    architecture: String,
+    #[serde(default)]
+    variant: Option<String>,
+}
+
+impl PlatformSpec {
+    fn display(&self) -> String {
+        match &self.variant {
+            Some(variant) => format!("{}/{}/{}", self.os, self.architecture, variant),
+            None => format!("{}/{}", self.os, self.architecture),
+        }
+    }
+
+    /// Whether `wanted` (`os/arch[/variant]`) selects this platform.
+    fn matches(&self, wanted: &str) -> bool {
+        let mut parts = wanted.split('/');
+        let os = parts.next().unwrap_or_default();
+        let arch = parts.next().unwrap_or_default();
+        let variant = parts.next();
+        self.os == os
+            && self.architecture == arch
+            && (variant.is_none() || variant == self.variant.as_deref())
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ImageConfigBlob {
+    #[serde(default)]
+    os: Option<String>,
+    #[serde(default)]
+    architecture: Option<String>,
+    #[serde(default)]
+    variant: Option<String>,
+    #[serde(default)]
+    config: Option<ImageConfigSection>,
+    #[serde(default)]
+    history: Option<Vec<HistoryEntry>>,
+}
+
+/// An entry of the config blob's `history`, oldest first.
+#[derive(Debug, Default, Deserialize)]
+struct HistoryEntry {
+    #[serde(default)]
+    created: Option<String>,
+    #[serde(default)]
+    created_by: Option<String>,
+    #[serde(default)]
+    comment: Option<String>,
+    /// Metadata-only instruction (`ENV`, `CMD`) without a layer.
+    #[serde(default)]
+    empty_layer: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ImageConfigSection {
+    #[serde(rename = "Labels", default)]
+    labels: Option<HashMap<String, String>>,
+}
+
+/// Artifact kind a media type (`artifactType`, config or layer) stands for,
+/// or None if it says nothing about it.
+fn media_type_kind(media_type: &str) -> Option<ArtifactKind> {
+    let media_type = media_type.to_ascii_lowercase();
+    let has = |needle: &str| media_type.contains(needle);
+    let kind = if media_type.starts_with("application/vnd.cncf.helm.") {
+        ArtifactKind::HelmChart
+    } else if has("wasm") {
+        ArtifactKind::Wasm
+    } else if has("spdx") || has("cyclonedx") || has("syft") || has("sbom") {
+        ArtifactKind::Sbom
+    } else if has("simplesigning") || has("sigstore.bundle") || has("notary.signature") {
+        ArtifactKind::Signature
+    } else if has("in-toto") || has("dsse.envelope") {
+        ArtifactKind::Attestation
+    } else if matches!(
+        media_type.as_str(),
+        "application/vnd.oci.image.config.v1+json"
+            | "application/vnd.docker.container.image.v1+json"
+    ) {
+        ArtifactKind::Image
+    } else {
+        return None;
+    };
+    Some(kind)
+}
+
+/// What a manifest holds. `artifactType` wins; otherwise a telling layer
+/// (cosign signatures and Wasm modules use an image config) or the config
+/// media type decides. Indexes are multi-arch images.
+fn manifest_kind(doc: &ManifestDocument) -> ArtifactKind {
+    if let Some(artifact_type) = &doc.artifact_type {
+        return media_type_kind(artifact_type).unwrap_or(ArtifactKind::Other);
+    }
+    if !doc.manifests.is_empty() {
+        return ArtifactKind::Image;
+    }
+    let layer_kind = doc
+        .layers
+        .iter()
+        .filter_map(|l| l.media_type.as_deref().and_then(media_type_kind))
+        .find(|kind| *kind != ArtifactKind::Image);
+    let config_type = doc.config.as_ref().and_then(|c| c.media_type.as_deref());
+    match (layer_kind, config_type) {
+        (Some(kind), _) => kind,
+        (None, None) => ArtifactKind::Image,
+        (None, Some(config_type)) => media_type_kind(config_type).unwrap_or(ArtifactKind::Other),
+    }
+}
+
+/// Artifact kind of cosign-style tags (`sha256-<hex>.sig`, `.att`, `.sbom`)
+/// and OCI referrers fallback tags (`sha256-<hex>`), told by name alone.
+fn tag_kind(tag: &str) -> Option<ArtifactKind> {
+    let rest = tag.strip_prefix("sha256-")?;
+    let (hex, suffix) = rest.split_once('.').unwrap_or((rest, ""));
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match suffix {
+        "sig" => Some(ArtifactKind::Signature),
+        "att" => Some(ArtifactKind::Attestation),
+        "sbom" => Some(ArtifactKind::Sbom),
+        "" => Some(ArtifactKind::Other),
+        _ => None,
+    }
+}
+
+/// Artifacts referring to `digest`, from the OCI referrers API or, on
+/// registries without it, the `sha256-<hex>` fallback tag. Best effort:
+/// failures give an empty list.
+async fn fetch_referrers(
+    client: &RegistryClient,
+    image: &ImageReference,
+    base: &str,
+    digest: &str,
+) -> Vec<RemoteReferrer> {
+    const INDEX_ACCEPT: &[&str] = &["application/vnd.oci.image.index.v1+json"];
+    let api = format!("{}/referrers/{}", base, digest);
+    let index = match client
+        .get_json::<ManifestDocument>(image, &api, INDEX_ACCEPT)
+        .await
+    {
+        Err(AppError::NotFound { .. }) => {
+            let tag = format!("{}/manifests/{}", base, digest.replacen(':', "-", 1));
+            client.get_json(image, &tag, INDEX_ACCEPT).await
+        }
+        other => other,
+    };
+    let index = match index {
+        Ok((index, _)) => index,
+        Err(e) => {
+            tracing::debug!("No referrers for {}@{}: {}", image, digest, e);
+            return Vec::new();
+        }
+    };
+    index
+        .manifests
+        .into_iter()
+        .map(|d| {
+            let artifact_type = d.artifact_type.unwrap_or_default();
+            RemoteReferrer {
+                kind: media_type_kind(&artifact_type).unwrap_or(ArtifactKind::Other),
+                artifact_type,
+                digest: d.digest,
+                size: d.size,
+                annotations: d.annotations,
+            }
+        })
+        .collect()
+}
+
+/// Platform of the engine VM: Linux on the host CPU architecture.
+pub fn host_platform() -> String {
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        other => other,
+    };
+    format!("linux/{}", arch)
+}
+
+/// Platforms listed in a manifest index (attestation entries excluded).
+fn index_platforms(manifests: &[Descriptor]) -> Vec<(&Descriptor, &PlatformSpec)> {
+    manifests
+        .iter()
+        .filter_map(|d| d.platform.as_ref().map(|p| (d, p)))
+        .filter(|(_, p)| p.os != "unknown" && p.architecture != "unknown")
+        .collect()
+}
+
+/// Fetch the manifest for `reference` from its registry.
+///
+/// For multi-arch images every platform is listed, and the layers and
+/// labels of `platform` (default: [`host_platform`]) are resolved. Other
+/// OCI artifacts (Helm charts, SBOMs, signatures) are reported by kind
+/// without platforms, and artifacts referring to the manifest are listed.
+pub async fn fetch_manifest(
+    reference: &str,
+    platform: Option<&str>,
+    auth: Option<RegistryAuth>,
+) -> Result<RemoteImageManifest, AppError> {
+    let image = ImageReference::parse(reference)?;
+    let client = RegistryClient::new(&image.registry, auth)?;
+    let (mut manifest, _) = resolve_manifest(&client, &image, platform).await?;
+    if let Some(digest) = &manifest.digest {
+        manifest.referrers =
+            fetch_referrers(&client, &image, &client.base_url(&image), digest).await;
+    }
+    Ok(manifest)
+}
+
+/// Layers of a remote image for `platform` (default: [`host_platform`]),
+/// newest first, from its manifest and config blob history. Sizes are
+/// compressed.
+pub async fn fetch_history(
+    reference: &str,
+    platform: Option<&str>,
+    auth: Option<RegistryAuth>,
+) -> Result<ImageLayers, AppError> {
+    let image = ImageReference::parse(reference)?;
+    let client = RegistryClient::new(&image.registry, auth)?;
+    let (manifest, history) = resolve_manifest(&client, &image, platform).await?;
+    if manifest.artifact_type != ArtifactKind::Image {
+        return Err(AppError::Validation(format!(
+            "{} is a {} artifact, not an image",
+            manifest.reference,
+            manifest.artifact_type.as_str()
+        )));
+    }
+    let layers = history_layers(&history, &manifest.layers);
+    Ok(ImageLayers {
+        reference: manifest.reference,
+        source: "remote".to_string(),
+        platform: manifest.platform,
+        compressed: true,
+        total_size: layers.iter().map(|l| l.size).sum(),
+        layers,
+    })
+}
+
+/// Pair config history entries (oldest first) with manifest layers: every
+/// entry without `empty_layer` owns the next layer. Images without history
+/// get one entry per layer. Returned newest first.
+fn history_layers(history: &[HistoryEntry], layers: &[RemoteLayer]) -> Vec<ImageLayer> {
+    let mut remaining = layers.iter();
+    let mut result: Vec<ImageLayer> = history
+        .iter()
+        .map(|entry| {
+            let layer = if entry.empty_layer {
+                None
+            } else {
+                remaining.next()
+            };
+            ImageLayer {
+                digest: layer.map(|l| l.digest.clone()),
+                created_by: crate::container::clean_created_by(
+                    entry.created_by.as_deref().unwrap_or_default(),
+                ),
+                created: entry.created.clone(),
+                size: layer.map_or(0, |l| l.size),
+                empty: layer.is_none(),
+                comment: entry.comment.clone().filter(|c| !c.is_empty()),
+            }
+        })
+        .collect();
+    // Layers the history does not account for (or no history at all).
+    result.extend(remaining.map(|l| ImageLayer {
+        digest: Some(l.digest.clone()),
+        created_by: String::new(),
+        created: None,
+        size: l.size,
+        empty: false,
+        comment: None,
+    }));
+    result.reverse();
+    result
+}
+
+/// Resolve `image` to a manifest (for `platform` if multi-arch) and the
+/// history of its config blob. Referrers are left empty.
+async fn resolve_manifest(
+    client: &RegistryClient,
+    image: &ImageReference,
+    platform: Option<&str>,
+) -> Result<(RemoteImageManifest, Vec<HistoryEntry>), AppError> {
+    let base = client.base_url(image);
+
+    let (doc, headers): (ManifestDocument, _) = client
+        .get_json(
+            image,
+            &format!("{}/manifests/{}", base, image.reference()),
+            MANIFEST_ACCEPT,
+        )
+        .await?;
+    let digest = header_str(&headers, "docker-content-digest");
+    let media_type = doc
+        .media_type
+        .clone()
+        .or_else(|| header_str(&headers, CONTENT_TYPE.as_str()))
+        .unwrap_or_default();
+
+    let top_kind = manifest_kind(&doc);
+    let multi_arch = !doc.manifests.is_empty() && top_kind == ArtifactKind::Image;
+    let mut annotations = doc.annotations.clone();
+    let (platforms, selected, manifest) = if multi_arch {
+        let available = index_platforms(&doc.manifests);
+        let platforms: Vec<RemotePlatform> = available
+            .iter()
+            .map(|(d, p)| RemotePlatform {
+                platform: p.display(),
+                digest: d.digest.clone(),
+                size: d.size,
+            })
+            .collect();
+
+        let wanted = platform.map(str::to_string).unwrap_or_else(host_platform);
+        let chosen = available
+            .iter()
+            .find(|(_, p)| p.matches(&wanted))
+            .or(if platform.is_none() {
+                available.first()
+            } else {
+                None
+            })
+            .ok_or_else(|| AppError::NotFound {
+                entity: "platform".to_string(),
+                id: format!(
+                    "{} for {} (available: {})",
+                    wanted,
+                    image,
+                    platforms
+                        .iter()
+                        .map(|p| p.platform.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            })?;
+        let (child, _): (ManifestDocument, _) = client
+            .get_json(
+                image,
+                &format!("{}/manifests/{}", base, chosen.0.digest),
+                MANIFEST_ACCEPT,
+            )
+            .await?;
+        (platforms, Some(chosen.1.display()), child)
+    } else {
+        (Vec::new(), None, doc)
+    };
+
+    let artifact_type = if multi_arch {
+        manifest_kind(&manifest)
+    } else {
+        top_kind
+    };
+
+    // Artifact configs need not be JSON (or may be the empty `{}`).
+    let config = match &manifest.config {
+        Some(config)
+            if config
+                .media_type
+                .as_deref()
+                .is_none_or(|t| t.ends_with("json")) =>
+        {
+            client
+                .get_json::<ImageConfigBlob>(
+                    image,
+                    &format!("{}/blobs/{}", base, config.digest),
+                    &[],
+                )
+                .await?
+                .0
+        }
+        _ => ImageConfigBlob::default(),
+    };
+
+    // Single-arch images report their platform via the config blob.
+    let (platforms, selected) = match (multi_arch, &config.os, &config.architecture) {
+        (false, Some(os), Some(arch)) => {
+            let spec = PlatformSpec {
+                os: os.clone(),
+                architecture: arch.clone(),
+                variant: config.variant.clone(),
+            };
+            let entry = RemotePlatform {
+                platform: spec.display(),
+                digest: digest.clone().unwrap_or_default(),
+                size: manifest.layers.iter().map(|l| l.size).sum(),
+            };
+            (vec![entry], Some(spec.display()))
+        }
+        _ => (platforms, selected),
+    };
+
+    // Annotations of the platform manifest win over the index's.
+    annotations.extend(manifest.annotations.clone());
+
+    let layers: Vec<RemoteLayer> = manifest
+        .layers
+        .iter()
+        .map(|l| RemoteLayer {
+            digest: l.digest.clone(),
+            media_type: l.media_type.clone().unwrap_or_default(),
+            size: l.size,
+        })
+        .collect();
+
+    let history = config.history.unwrap_or_default();
+    let manifest = RemoteImageManifest {
+        reference: image.to_string(),
+        digest,
+        media_type,
+        multi_arch,
+        platforms,
+        platform: selected,
+        total_size: layers.iter().map(|l| l.size).sum(),
+        layers,
+        config_digest: manifest.config.as_ref().map(|c| c.digest.clone()),
+        labels: config.config.and_then(|c| c.labels).unwrap_or_default(),
+        annotations,
+        artifact_type,
+        referrers: Vec::new(),
+    };
+    Ok((manifest, history))
+}
+
+fn header_str(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+// ---------------------------------------------------------------------------
+// Tags
+// ---------------------------------------------------------------------------
+
+/// Tags requested per registry page.
+const TAGS_PAGE_REQUEST: usize = 100;
+
+/// Upper bound on registry pages followed for one listing.
+const TAGS_MAX_PAGES: usize = 200;
+
+/// Default page size for [`list_tags`].
+pub const DEFAULT_TAGS_PAGE_SIZE: u32 = 50;
+
+/// Options for [`list_tags`].
+#[derive(Debug, Clone, Default)]
+pub struct TagListOptions {
+    /// Glob (`*`, `?`) a tag must match, e.g. `1.2*` or `*-alpine`.
+    pub filter: Option<String>,
+    /// 1-based page number (default 1).
+    pub page: Option<u32>,
+    /// Tags per page (default [`DEFAULT_TAGS_PAGE_SIZE`]).
+    pub page_size: Option<u32>,
+    /// Return every matching tag on a single page.
+    pub all: bool,
+    /// Read the manifest of every tag on the page to report non-image
+    /// artifacts in [`RegistryTagList::artifact_types`].
+    pub artifacts: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+/// List tags of a repository, following `Link` pagination headers.
+///
+/// The full tag set is fetched so it can be sorted: `latest` first, then
+/// version-like tags newest first (`v10` before `v9`), then the rest
+/// alphabetically. Filtering and paging apply to the sorted list.
+///
+/// Tags of signatures, attestations and SBOMs are recognized by name; with
+/// [`TagListOptions::artifacts`] the manifests of the page's tags are read
+/// too, so Helm charts and other artifacts sharing a repository show up.
+pub async fn list_tags(
+    reference: &str,
+    options: &TagListOptions,
+    auth: Option<RegistryAuth>,
+) -> Result<RegistryTagList, AppError> {
+    let image = ImageReference::parse(reference)?;
+    let client = RegistryClient::new(&image.registry, auth)?;
+    let base = client.base_url(&image);
+
+    let mut tags = Vec::new();
+    let mut next = Some(format!("{}/tags/list?n={}", base, TAGS_PAGE_REQUEST));
+    let mut pages = 0;
+    while let Some(url) = next.take() {
+        pages += 1;
+        if pages > TAGS_MAX_PAGES {
+            tracing::warn!(
+                "Stopped listing tags for {} after {} pages",
+                image,
+                TAGS_MAX_PAGES
+            );
+            break;
+        }
+        let (page, headers): (TagsResponse, _) = client.get_json(&image, &url, &[]).await?;
+        tags.extend(page.tags.unwrap_or_default());
+        next = headers
+            .get(reqwest::header::LINK)
+            .and_then(|v| v.to_str().ok())
+            .and_then(next_link)
+            .and_then(|link| resolve_link(&url, &link));
+    }
+
+    let mut tags: Vec<String> = match options.filter.as_deref().map(str::trim) {
+        Some(filter) if !filter.is_empty() => tags
+            .into_iter()
+            .filter(|tag| crate::build_context::wildcard_match(filter, tag))
+            .collect(),
+        _ => tags,
+    };
+    sort_tags(&mut tags);
+    tags.dedup();
+
+    let total = tags.len();
+    let repository = format!("{}/{}", image.registry, image.repository);
+    if options.all {
+        let artifact_types = tag_artifact_types(&client, &image, &base, &tags, options).await;
+        return Ok(RegistryTagList {
+            repository,
+            tags,
+            artifact_types,
+            total,
+            page: 1,
+            page_size: u32::try_from(total).unwrap_or(u32::MAX),
+            has_more: false,
+        });
+    }
+
+    let page = options.page.unwrap_or(1).max(1);
+    let page_size = options
+        .page_size
+        .unwrap_or(DEFAULT_TAGS_PAGE_SIZE)
+        .clamp(1, 1000);
+    let start = (page as usize - 1).saturating_mul(page_size as usize);
+    let tags: Vec<String> = tags
+        .into_iter()
+        .skip(start)
+        .take(page_size as usize)
+        .collect();
+    let artifact_types = tag_artifact_types(&client, &image, &base, &tags, options).await;
+    Ok(RegistryTagList {
+        repository,
+        has_more: start + tags.len() < total,
+        tags,
+        artifact_types,
+        total,
+        page,
+        page_size,
+    })
+}
+
+/// Manifests read at once by [`tag_artifact_types`].
+const ARTIFACT_LOOKUP_CONCURRENCY: usize = 8;
+
+/// Non-image tags among `tags`: by name, plus by manifest when
+/// [`TagListOptions::artifacts`] is set. Unreadable manifests are skipped.
+async fn tag_artifact_types(
+    client: &RegistryClient,
+    image: &ImageReference,
+    base: &str,
+    tags: &[String],
+    options: &TagListOptions,
+) -> HashMap<String, ArtifactKind> {
+    use futures_util::stream::{self, StreamExt};
+
+    let mut kinds: HashMap<String, ArtifactKind> = tags
+        .iter()
+        .filter_map(|tag| tag_kind(tag).map(|kind| (tag.clone(), kind)))
+        .collect();
+    if !options.artifacts {
+        return kinds;
+    }
+
+    let unknown: Vec<&String> = tags.iter().filter(|t| !kinds.contains_key(*t)).collect();
+    let lookups = stream::iter(unknown)
+        .map(|tag| async move {
+            let url = format!("{}/manifests/{}", base, tag);
+            match client
+                .get_json::<ManifestDocument>(image, &url, MANIFEST_ACCEPT)
+                .await
+            {
+                Ok((doc, _)) => Some((tag.clone(), manifest_kind(&doc))),
+                Err(e) => {
+                    tracing::debug!("Reading manifest of {}:{} failed: {}", image, tag, e);
+                    None
+                }
+            }
+        })
+        .buffer_unordered(ARTIFACT_LOOKUP_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+    kinds.extend(
+        lookups
+            .into_iter()
+            .flatten()
+            .filter(|(_, kind)| *kind != ArtifactKind::Image),
+    );
+    kinds
+}
+
+/// Extract the `rel="next"` target from a `Link` header.
+fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|part| {
+        let (target, params) = part.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|p| p.trim().replace(' ', "") == "rel=\"next\"" || p.trim() == "rel=next");
+        let target = target.trim().strip_prefix('<')?.strip_suffix('>')?;
+        is_next.then(|| target.to_string())
+    })
+}
+
+/// Resolve a (usually host-relative) `Link` target against the current URL.
+fn resolve_link(current: &str, link: &str) -> Option<String> {
+    reqwest::Url::parse(current)
+        .ok()?
+        .join(link)
+        .ok()
+        .map(|url| url.to_string())
+}
+
+/// Version key of a tag: numeric components plus whether it is a
+/// pre-release (`1.2.0-rc1`, `1.25-alpine`). None for non-version tags.
+fn version_key(tag: &str) -> Option<(Vec<u64>, bool)> {
+    let bare = tag.strip_prefix(['v', 'V']).unwrap_or(tag);
+    let (version, suffix) = match bare.split_once(['-', '+']) {
+        Some((version, suffix)) => (version, Some(suffix)),
+        None => (bare, None),
+    };
+    let parts = version
+        .split('.')
+        .map(|p| p.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some((parts, suffix.is_some()))
+}
+
+/// Sort tags: `latest` first, versions descending, then the rest ascending.
+fn sort_tags(tags: &mut [String]) {
+    use std::cmp::Ordering;
+
+    tags.sort_by(|a, b| {
+        let rank = |t: &str| match (t == "latest", version_key(t)) {
+            (true, _) => 0,
+            (false, Some(_)) => 1,
+            (false, None) => 2,
+        };
+        rank(a).cmp(&rank(b)).then_with(|| {
+            match (version_key(a), version_key(b)) {
+                (Some((va, pa)), Some((vb, pb))) => {
+                    let len = va.len().max(vb.len());
+                    let pad = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+                    (0..len)
+                        .map(|i| pad(&vb, i).cmp(&pad(&va, i)))
+                        .find(|o| *o != Ordering::Equal)
+                        // Releases before pre-releases of the same version.
+                        .unwrap_or_else(|| pa.cmp(&pb))
+                        // Then more specific (`1.2.0` before `1.2`), then by name.
+                        .then_with(|| vb.len().cmp(&va.len()))
+                        .then_with(|| a.cmp(b))
+                }
+                _ => a.cmp(b),
+            }
+        })
+    });
+}
+
+// ---------------------------------------------------------------------------
+// Catalog
+// ---------------------------------------------------------------------------
+
+/// Default page size for [`list_catalog`].
+pub const DEFAULT_CATALOG_PAGE_SIZE: u32 = 100;
+
+/// Options for [`list_catalog`].
+#[derive(Debug, Clone, Default)]
+pub struct CatalogOptions {
+    /// Glob (`*`, `?`) a repository must match, e.g. `team/*`.
+    pub filter: Option<String>,
+    /// 1-based page number (default 1).
+    pub page: Option<u32>,
+    /// Repositories per page (default [`DEFAULT_CATALOG_PAGE_SIZE`]).
+    pub page_size: Option<u32>,
+    /// Return every matching repository on a single page.
+    pub all: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CatalogResponse {
+    #[serde(default)]
+    repositories: Option<Vec<String>>,
+}
+
+/// Registry host from user input: `harbor.example.com`, `localhost:5000`
+/// or a URL like `https://harbor.example.com/`.
+pub fn parse_registry_host(input: &str) -> Result<String, AppError> {
+    let host = input.trim();
+    let host = host
+        .strip_prefix("https://")
+        .or_else(|| host.strip_prefix("http://"))
+        .unwrap_or(host)
+        .trim_end_matches('/');
+    let host = host.strip_suffix("/v2").unwrap_or(host);
+    if host.is_empty() || host.contains(['/', '@']) || host.contains(char::is_whitespace) {
+        return Err(AppError::Validation(format!(
+            "Invalid registry host: '{}'",
+            input.trim()
+        )));
+    }
+    if matches!(
+        host,
+        "docker.io" | "index.docker.io" | "hub.docker.com" | DOCKER_HUB_REGISTRY
+    ) {
+        return Err(AppError::Validation(
+            "Docker Hub does not expose its catalog; search it instead".to_string(),
+        ));
+    }
+    Ok(host.to_string())
+}
+
+/// List the repositories of a registry through `/v2/_catalog`, following
+/// `Link` pagination headers.
+///
+/// Meant for self-hosted registries (`registry:2`, Harbor, Nexus); public
+/// registries mostly deny catalog access. Bearer registries are asked for a
+/// `registry:catalog:*` token, which usually takes credentials. Filtering
+/// and paging apply to the sorted list.
+pub async fn list_catalog(
+    registry: &str,
+    options: &CatalogOptions,
+    auth: Option<RegistryAuth>,
+) -> Result<RegistryCatalog, AppError> {
+    let registry = parse_registry_host(registry)?;
+    let image = ImageReference::catalog(&registry);
+    let client = RegistryClient::new(&registry, auth)?;
+    let base = client.base_url(&image);
+
+    let mut repositories = Vec::new();
+    let mut next = Some(format!("{}_catalog?n={}", base, TAGS_PAGE_REQUEST));
+    let mut pages = 0;
+    while let Some(url) = next.take() {
+        pages += 1;
+        if pages > TAGS_MAX_PAGES {
+            tracing::warn!(
+                "Stopped listing the catalog of {} after {} pages",
+                registry,
+                TAGS_MAX_PAGES
+            );
+            break;
+        }
+        let (page, headers): (CatalogResponse, _) = client.get_json(&image, &url, &[]).await?;
+        repositories.extend(page.repositories.unwrap_or_default());
+        next = headers
+            .get(reqwest::header::LINK)
+            .and_then(|v| v.to_str().ok())
+            .and_then(next_link)
+            .and_then(|link| resolve_link(&url, &link));
+    }
+
+    let mut repositories: Vec<String> = match options.filter.as_deref().map(str::trim) {
+        Some(filter) if !filter.is_empty() => repositories
+            .into_iter()
+            .filter(|repo| crate::build_context::wildcard_match(filter, repo))
+            .collect(),
+        _ => repositories,
+    };
+    repositories.sort();
+    repositories.dedup();
+
+    let total = repositories.len();
+    if options.all {
+        return Ok(RegistryCatalog {
+            registry,
+            repositories,
+            tree: None,
+            total,
+            page: 1,
+            page_size: u32::try_from(total).unwrap_or(u32::MAX),
+            has_more: false,
+        });
+    }
+
+    let page = options.page.unwrap_or(1).max(1);
+    let page_size = options
+        .page_size
+        .unwrap_or(DEFAULT_CATALOG_PAGE_SIZE)
+        .clamp(1, 1000);
+    let start = (page as usize - 1).saturating_mul(page_size as usize);
+    let repositories: Vec<String> = repositories
+        .into_iter()
+        .skip(start)
+        .take(page_size as usize)
+        .collect();
+    Ok(RegistryCatalog {
+        registry,
+        has_more: start + repositories.len() < total,
+        repositories,
+        tree: None,
+        total,
+        page,
+        page_size,
+    })
+}
+
+/// Group repositories by path segment (`team/app/api` under `team`, then
+/// `app`) for a browser tree. Namespaces come before repositories at each
+/// level; a path can be both (`team/app` and `team/app/api`).
+pub fn catalog_tree(repositories: &[String]) -> Vec<RegistryCatalogNode> {
+    fn insert(nodes: &mut Vec<RegistryCatalogNode>, prefix: &str, segments: &[&str]) {
+        let Some((name, rest)) = segments.split_first() else {
+            return;
+        };
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", prefix, name)
+        };
+        let index = match nodes.iter().position(|n| n.name == *name) {
+            Some(index) => index,
+            None => {
+                nodes.push(RegistryCatalogNode {
+                    name: name.to_string(),
+                    path: path.clone(),
+                    repository: false,
+                    children: Vec::new(),
+                });
+                nodes.len() - 1
+            }
+        };
+        let node = &mut nodes[index];
+        if rest.is_empty() {
+            node.repository = true;
+        } else {
+            insert(&mut node.children, &path, rest);
+        }
+    }
+
+    fn sort(nodes: &mut [RegistryCatalogNode]) {
+        nodes.sort_by(|a, b| {
+            a.children
+                .is_empty()
+                .cmp(&b.children.is_empty())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        for node in nodes {
+            sort(&mut node.children);
+        }
+    }
+
+    let mut tree = Vec::new();
+    for repository in repositories {
+        let segments: Vec<&str> = repository.split('/').filter(|s| !s.is_empty()).collect();
+        insert(&mut tree, "", &segments);
+    }
+    sort(&mut tree);
+    tree
+}
+
+// ---------------------------------------------------------------------------
+// Image details
+// ---------------------------------------------------------------------------
+
+/// Prefix of the OCI pre-defined annotation keys.
+const OCI_ANNOTATION_PREFIX: &str = "org.opencontainers.image.";
+
+#[derive(Debug, Deserialize)]
+struct DockerHubRepository {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    full_description: Option<String>,
+    #[serde(default)]
+    star_count: Option<u64>,
+    #[serde(default)]
+    pull_count: Option<u64>,
+    #[serde(default)]
+    last_updated: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QuayRepository {
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Repository description from Docker Hub or Quay. None for registries
+/// without such an API.
+async fn fetch_repository_details(
+    image: &ImageReference,
+) -> Option<Result<ImageDetails, AppError>> {
+    match image.registry.as_str() {
+        DOCKER_HUB_REGISTRY => Some(dockerhub_details(image).await),
+        "quay.io" => Some(quay_details(image).await),
+        _ => None,
+    }
+}
+
+async fn dockerhub_details(image: &ImageReference) -> Result<ImageDetails, AppError> {
+    let client = registry_http_client("docker.io", REGISTRY_HTTP_TIMEOUT)?;
+    let repo: DockerHubRepository = send_json(
+        client.get(format!(
+            "https://hub.docker.com/v2/repositories/{}/",
+            image.repository
+        )),
+        "Docker Hub",
+    )
+    .await?;
+    let name = image
+        .repository
+        .strip_prefix("library/")
+        .unwrap_or(&image.repository);
+    let hub_path = if image.repository.starts_with("library/") {
+        format!("_/{}", name)
+    } else {
+        format!("r/{}", name)
+    };
+    Ok(ImageDetails {
+        source: "dockerhub".to_string(),
+        description: non_empty(repo.description),
+        readme: non_empty(repo.full_description),
+        url: Some(format!("https://hub.docker.com/{}", hub_path)),
+        stars: repo.star_count,
+        pulls: repo.pull_count,
+        last_updated: repo.last_updated,
+        ..Default::default()
+    })
+}
+
+async fn quay_details(image: &ImageReference) -> Result<ImageDetails, AppError> {
+    let client = registry_http_client("quay.io", REGISTRY_HTTP_TIMEOUT)?;
+    let repo: QuayRepository = send_json(
+        client.get(format!(
+            "https://quay.io/api/v1/repository/{}",
+            image.repository
+        )),
+        "Quay",
+    )
+    .await?;
+    // Quay keeps a single Markdown description, which doubles as the README.
+    let readme = non_empty(repo.description);
+    Ok(ImageDetails {
+        source: "quay".to_string(),
+        description: readme
+            .as_deref()
+            .and_then(|r| r.lines().map(str::trim).find(|l| !l.is_empty()))
+            .map(|l| l.trim_start_matches('#').trim().to_string()),
+        readme,
+        url: Some(format!("https://quay.io/repository/{}", image.repository)),
+        ..Default::default()
+    })
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// Fill fields the registry did not provide from OCI annotations/labels.
+fn apply_oci_annotations(details: &mut ImageDetails, oci: HashMap<String, String>) {
+    let get = |key: &str| {
+        non_empty(
+            oci.get(&format!("{}{}", OCI_ANNOTATION_PREFIX, key))
+                .cloned(),
+        )
+    };
+    details.title = details.title.take().or_else(|| get("title"));
+    details.description = details.description.take().or_else(|| get("description"));
+    details.url = details.url.take().or_else(|| get("url"));
+    details.source_url = details.source_url.take().or_else(|| get("source"));
+    details.documentation = details
+        .documentation
+        .take()
+        .or_else(|| get("documentation"));
+    details.licenses = details.licenses.take().or_else(|| get("licenses"));
+    details.oci = oci;
+}
+
+/// Describe `reference` before pulling it: the Docker Hub overview or Quay
+/// README where available, completed with the image's
+/// `org.opencontainers.image.*` annotations and labels.
+///
+/// Fails only when both the repository description and the manifest
+/// cannot be fetched.
+pub async fn fetch_image_details(
+    reference: &str,
+    auth: Option<RegistryAuth>,
+) -> Result<ImageDetails, AppError> {
+    let image = ImageReference::parse(reference)?;
+    let (repository, manifest) = tokio::join!(
+        fetch_repository_details(&image),
+        fetch_manifest(reference, None, auth)
+    );
+
+    let mut warnings = Vec::new();
+    let details = match repository {
+        Some(Ok(details)) => Some(details),
+        Some(Err(e)) => {
+            warnings.push(e.to_string());
+            None
+        }
+        None => None,
+    };
+    let oci = match manifest {
+        Ok(manifest) => manifest
+            .labels
+            .into_iter()
+            .chain(manifest.annotations)
+            .filter(|(key, _)| key.starts_with(OCI_ANNOTATION_PREFIX))
+            .collect(),
+        Err(e) if details.is_some() => {
+            warnings.push(e.to_string());
+            HashMap::new()
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut details = details.unwrap_or_else(|| ImageDetails {
+        source: "oci".to_string(),
+        ..Default::default()
+    });
+    details.reference = image.to_string();
+    apply_oci_annotations(&mut details, oci);
+    details.warnings = warnings;
+    Ok(details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_provider_resolves_known_sources() {
+        for source in SEARCH_SOURCES {
+            assert_eq!(search_provider(source).unwrap().source(), *source);
+        }
+        assert_eq!(search_provider("").unwrap().source(), "dockerhub");
+        assert!(matches!(
+            search_provider("ecr"),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn registry_ca_file_must_hold_certificates() {
+        let dir = tempfile::tempdir().unwrap();
+        let bogus = dir.path().join("ca.pem");
+        std::fs::write(&bogus, "not a certificate").unwrap();
+
+        let mut config = Config::default();
+        for ca_file in [bogus, dir.path().join("missing.pem")] {
+            config.registries.insert(
+                "harbor.lan".to_string(),
+                crate::config::RegistrySettings {
+                    ca_file: Some(ca_file),
+                    ..Default::default()
+                },
+            );
+            assert!(matches!(
+                configured_http_client(&config, "harbor.lan", REGISTRY_HTTP_TIMEOUT),
+                Err(AppError::Validation(_))
+            ));
+        }
+        assert!(configured_http_client(&config, "quay.io", REGISTRY_HTTP_TIMEOUT).is_ok());
+    }
+
+    #[test]
+    fn quay_results_map_to_references() {
+        let response: QuayFindResponse = serde_json::from_str(
+            r#"{"results":[
+                {"kind":"repository","name":"etcd","namespace":{"name":"coreos"},"description":"kv","stars":12},
+                {"kind":"application","name":"app","namespace":{"name":"x"}}
+            ]}"#,
+        )
+        .unwrap();
+        let results = map_quay_results(response);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].reference, "quay.io/coreos/etcd");
+        assert_eq!(results[0].source, "quay");
+        assert_eq!(results[0].stars, Some(12));
+    }
+
+    #[test]
+    fn search_results_rank_by_stars_and_pulls() {
+        let result = |reference: &str, stars: Option<u64>, pulls: Option<u64>| ImageSearchResult {
+            source: "dockerhub".to_string(),
+            reference: reference.to_string(),
+            description: String::new(),
+            stars,
+            pulls,
+            official: false,
+        };
+        let mut results = vec![
+            result("a", Some(5), None),
+            result("b", Some(1), Some(100)),
+            result("c", None, None),
+            result("a", Some(500), None),
+            result("d", Some(5), None),
+        ];
+        rank_search_results(&mut results);
+        let order: Vec<_> = results.iter().map(|r| r.reference.as_str()).collect();
+        assert_eq!(order, ["b", "a", "d", "c"]);
+    }
+
+    #[test]
+    fn ghcr_query_splits_owner_and_filter() {
+        assert_eq!(
+            parse_ghcr_query("My-Org").unwrap(),
+            ("my-org".to_string(), String::new())
+        );
+        assert_eq!(
+            parse_ghcr_query("ghcr.io/my-org/API").unwrap(),
+            ("my-org".to_string(), "api".to_string())
+        );
+        assert!(parse_ghcr_query("/api").is_err());
+    }
+
+    #[test]
+    fn ghcr_packages_map_to_references() {
+        let packages: Vec<GhcrPackage> = serde_json::from_str(
+            r#"[
+                {"name": "api-server", "visibility": "public",
+                 "repository": {"description": "API", "stargazers_count": 7}},
+                {"name": "Worker", "visibility": "private"},
+                {"name": "docs"}
+            ]"#,
+        )
+        .unwrap();
+
+        let results = map_ghcr_packages("my-org", "er", packages);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].reference, "ghcr.io/my-org/api-server");
+        assert_eq!(results[0].stars, Some(7));
+        assert_eq!(results[1].reference, "ghcr.io/my-org/worker");
+        assert_eq!(results[1].description, "[private]");
+        assert!(results.iter().all(|r| r.source == "ghcr"));
+    }
+
+    #[test]
+    fn image_reference_defaults_to_docker_hub_library() {
+        let image = ImageReference::parse("nginx").unwrap();
+        assert_eq!(image.registry, DOCKER_HUB_REGISTRY);
+        assert_eq!(image.repository, "library/nginx");
+        assert_eq!(image.reference(), "latest");
+        assert_eq!(
+            image.to_string(),
+            "registry-1.docker.io/library/nginx:latest"
+        );
+
+        let image = ImageReference::parse("docker.io/bitnami/redis:7.2").unwrap();
+        assert_eq!(image.registry, DOCKER_HUB_REGISTRY);
+        assert_eq!(image.repository, "bitnami/redis");
+        assert_eq!(image.reference(), "7.2");
+    }
+
+    #[test]
+    fn image_reference_parses_registry_port_and_digest() {
+        let image = ImageReference::parse("localhost:5000/team/app:v1").unwrap();
+        assert_eq!(image.registry, "localhost:5000");
+        assert_eq!(image.repository, "team/app");
+        assert_eq!(image.tag.as_deref(), Some("v1"));
+        assert!(image.base_url().starts_with("http://"));
+
+        let image = ImageReference::parse("ghcr.io/org/app@sha256:abc").unwrap();
+        assert_eq!(image.reference(), "sha256:abc");
+        assert_eq!(image.to_string(), "ghcr.io/org/app@sha256:abc");
+
+        assert!(ImageReference::parse("Bad/Upper").is_err());
+        assert!(ImageReference::parse("nginx:").is_err());
+        assert!(ImageReference::parse("").is_err());
+    }
+
+    #[test]
+    fn auth_challenge_parses_quoted_params() {
+        let (scheme, params) = parse_auth_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/nginx:pull,push""#,
+        );
+        assert_eq!(scheme, "Bearer");
+        assert_eq!(params["realm"], "https://auth.docker.io/token");
+        assert_eq!(params["service"], "registry.docker.io");
+        assert_eq!(params["scope"], "repository:library/nginx:pull,push");
+
+        let (scheme, params) = parse_auth_challenge(r#"Basic realm="Registry""#);
+        assert_eq!(scheme, "Basic");
+        assert_eq!(params["realm"], "Registry");
+    }
+
+    #[test]
+    fn index_platforms_skip_attestations_and_match_variants() {
+        let doc: ManifestDocument = serde_json::from_str(
+            r#"{
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "manifests": [
+                    {"digest": "sha256:a", "size": 1, "platform": {"os": "linux", "architecture": "amd64"}},
+                    {"digest": "sha256:b", "size": 2, "platform": {"os": "linux", "architecture": "arm64", "variant": "v8"}},
+                    {"digest": "sha256:c", "size": 3, "platform": {"os": "unknown", "architecture": "unknown"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let platforms = index_platforms(&doc.manifests);
+        assert_eq!(platforms.len(), 2);
+        assert_eq!(platforms[1].1.display(), "linux/arm64/v8");
+        assert!(platforms[1].1.matches("linux/arm64"));
+        assert!(platforms[1].1.matches("linux/arm64/v8"));
+        assert!(!platforms[1].1.matches("linux/arm64/v7"));
+        assert!(!platforms[0].1.matches("linux/arm64"));
+    }
+
+    #[test]
+    fn manifests_are_classified_by_artifact_type_config_and_layers() {
+        let kind = |json: &str| manifest_kind(&serde_json::from_str(json).unwrap());
+
+        let image = r#"{
+            "config": {"mediaType": "application/vnd.oci.image.config.v1+json", "digest": "sha256:c"},
+            "layers": [{"mediaType": "application/vnd.oci.image.layer.v1.tar+gzip", "digest": "sha256:l"}]
+        }"#;
+        assert_eq!(kind(image), ArtifactKind::Image);
+
+        let chart = r#"{
+            "config": {"mediaType": "application/vnd.cncf.helm.config.v1+json", "digest": "sha256:c"},
+            "layers": [{"mediaType": "application/vnd.cncf.helm.chart.content.v1.tar+gzip", "digest": "sha256:l"}]
+        }"#;
+        assert_eq!(kind(chart), ArtifactKind::HelmChart);
+
+        let sbom = r#"{
+            "artifactType": "application/spdx+json",
+            "config": {"mediaType": "application/vnd.oci.empty.v1+json", "digest": "sha256:c"},
+            "layers": [{"mediaType": "application/spdx+json", "digest": "sha256:l"}]
+        }"#;
+        assert_eq!(kind(sbom), ArtifactKind::Sbom);
+
+        // cosign keeps an image config and marks the layer instead.
+        let signature = r#"{
+            "config": {"mediaType": "application/vnd.oci.image.config.v1+json", "digest": "sha256:c"},
+            "layers": [{"mediaType": "application/vnd.dev.cosign.simplesigning.v1+json", "digest": "sha256:l"}]
+        }"#;
+        assert_eq!(kind(signature), ArtifactKind::Signature);
+
+        let unknown = r#"{"config": {"mediaType": "application/x-custom", "digest": "sha256:c"}}"#;
+        assert_eq!(kind(unknown), ArtifactKind::Other);
+    }
+
+    #[test]
+    fn history_entries_own_layers_unless_empty() {
+        let history: Vec<HistoryEntry> = serde_json::from_str(
+            r#"[
+                {"created_by": "/bin/sh -c #(nop) ADD file:abc in / "},
+                {"created_by": "/bin/sh -c #(nop)  CMD [\"sh\"]", "empty_layer": true},
+                {"created_by": "RUN /bin/sh -c apk add curl # buildkit"}
+            ]"#,
+        )
+        .unwrap();
+        let layer = |digest: &str, size| RemoteLayer {
+            digest: digest.to_string(),
+            media_type: String::new(),
+            size,
+        };
+        let layers = history_layers(
+            &history,
+            &[layer("sha256:base", 3000), layer("sha256:curl", 700)],
+        );
+
+        assert_eq!(layers.len(), 3);
+        assert_eq!(
+            layers[0].created_by,
+            "RUN /bin/sh -c apk add curl # buildkit"
+        );
+        assert_eq!(layers[0].size, 700);
+        assert_eq!(layers[1].created_by, r#"CMD ["sh"]"#);
+        assert!(layers[1].empty && layers[1].digest.is_none());
+        assert_eq!(layers[2].digest.as_deref(), Some("sha256:base"));
+
+        // No history: one unnamed entry per layer.
+        let layers = history_layers(&[], &[layer("sha256:a", 1), layer("sha256:b", 2)]);
+        assert_eq!(layers[0].digest.as_deref(), Some("sha256:b"));
+        assert!(layers[0].created_by.is_empty());
+    }
+
+    #[test]
+    fn cosign_tags_are_recognized_by_name() {
+        let digest = "a".repeat(64);
+        assert_eq!(
+            tag_kind(&format!("sha256-{}.sig", digest)),
+            Some(ArtifactKind::Signature)
+        );
+        assert_eq!(
+            tag_kind(&format!("sha256-{}.att", digest)),
+            Some(ArtifactKind::Attestation)
+        );
+        assert_eq!(
+            tag_kind(&format!("sha256-{}.sbom", digest)),
+            Some(ArtifactKind::Sbom)
+        );
+        assert_eq!(
+            tag_kind(&format!("sha256-{}", digest)),
+            Some(ArtifactKind::Other)
+        );
+        assert_eq!(tag_kind("sha256-abc.sig"), None);
+        assert_eq!(tag_kind("1.27-alpine"), None);
+    }
+
+    #[test]
+    fn tags_sort_latest_then_versions_descending() {
+        let mut tags: Vec<String> = [
+            "v9",
+            "alpine",
+            "latest",
+            "v10",
+            "1.2",
+            "1.2.0",
+            "1.10.1",
+            "1.2.0-rc1",
+            "edge",
+            "v1",
+        ]
+        .iter()
+        .map(|t| t.to_string())
+        .collect();
+        sort_tags(&mut tags);
+        assert_eq!(
+            tags,
+            [
+                "latest",
+                "v10",
+                "v9",
+                "1.10.1",
+                "1.2.0",
+                "1.2",
+                "1.2.0-rc1",
+                "v1",
+                "alpine",
+                "edge"
+            ]
+        );
+    }
+
+    #[test]
+    fn link_header_next_is_resolved_against_registry() {
+        let link =
+            next_link(r#"</v2/library/nginx/tags/list?last=1.25&n=100>; rel="next""#).unwrap();
+        assert_eq!(link, "/v2/library/nginx/tags/list?last=1.25&n=100");
+        assert_eq!(
+            resolve_link(
+                "https://registry-1.docker.io/v2/library/nginx/tags/list?n=100",
+                &link
+            )
+            .unwrap(),
+            "https://registry-1.docker.io/v2/library/nginx/tags/list?last=1.25&n=100"
+        );
+        assert!(next_link(r#"<https://example.com/x>; rel="prev""#).is_none());
+    }
+
+    #[test]
+    fn catalog_hosts_accept_urls_and_reject_docker_hub() {
+        for input in [
+            "harbor.example.com",
+            "https://harbor.example.com/",
+            "https://harbor.example.com/v2/",
+        ] {
+            assert_eq!(parse_registry_host(input).unwrap(), "harbor.example.com");
+        }
+        assert_eq!(
+            parse_registry_host("http://localhost:5000").unwrap(),
+            "localhost:5000"
+        );
+        assert_eq!(
+            ImageReference::catalog("localhost:5000").base_url(),
+            "http://localhost:5000/v2/"
+        );
+        for input in ["", "docker.io", "harbor.example.com/team", "user@host"] {
+            assert!(matches!(
+                parse_registry_host(input),
+                Err(AppError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn catalog_tree_groups_repositories_by_namespace() {
+        let repositories: Vec<String> = ["team/app", "busybox", "team/app/api", "team/web"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let tree = catalog_tree(&repositories);
+
+        let names = |nodes: &[RegistryCatalogNode]| {
+            nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&tree), ["team", "busybox"]);
+        assert!(!tree[0].repository);
+        assert!(tree[1].repository);
+
+        let team = &tree[0].children;
+        assert_eq!(names(team), ["app", "web"]);
+        assert!(team[0].repository, "team/app is a namespace and a repo");
+        assert_eq!(team[0].children[0].path, "team/app/api");
+    }
+
+    #[test]
+    fn docker_hub_references_share_one_tag_endpoint() {
+        let expected = "https://registry-1.docker.io/v2/library/nginx";
+        for reference in [
+            "nginx",
+            "library/nginx",
+            "docker.io/nginx",
+            "docker.io/library/nginx:1.27",
+            "index.docker.io/library/nginx",
+        ] {
+            let image = ImageReference::parse(reference).unwrap();
+            assert_eq!(image.base_url(), expected, "{}", reference);
+        }
+        assert_eq!(
+            ImageReference::parse("bitnami/redis").unwrap().base_url(),
+            "https://registry-1.docker.io/v2/bitnami/redis"
+        );
+    }
+
+    #[test]
+    fn oci_annotations_fill_missing_details() {
+        let oci: HashMap<String, String> = [
+            ("org.opencontainers.image.title", "Redis"),
+            ("org.opencontainers.image.description", "from labels"),
+            (
+                "org.opencontainers.image.source",
+                "https://github.com/redis/redis",
+            ),
+            ("org.opencontainers.image.licenses", "BSD-3-Clause"),
+            ("org.opencontainers.image.url", " "),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let mut details = ImageDetails {
+            source: "dockerhub".to_string(),
+            description: Some("from hub".to_string()),
+            ..Default::default()
+        };
+        apply_oci_annotations(&mut details, oci);
+        assert_eq!(details.title.as_deref(), Some("Redis"));
+        assert_eq!(details.description.as_deref(), Some("from hub"));
+        assert_eq!(
+            details.source_url.as_deref(),
+            Some("https://github.com/redis/redis")
+        );
+        assert_eq!(details.licenses.as_deref(), Some("BSD-3-Clause"));
+        assert_eq!(details.url, None);
+        assert_eq!(details.oci.len(), 5);
+    }
+}
@@ -0,0 +1,108 @@
+//! Memory balloon policy for the runtime VM (§7.4).
+//!
+//! The VM is started with its whole `memory_mb` allocation, and the guest
+//! keeps what it once touched (page cache, freed heap) until the host asks
+//! for it back. On macOS the VZ runner has a virtio balloon device;
+//! [`rebalance`] sizes it from guest metrics so the guest keeps what it uses
+//! plus [`HEADROOM_MB`] and returns the rest to the host. QEMU on Linux
+//! returns freed pages by itself (free page reporting) and needs no policy.
+
+use bollard::Docker;
+
+use super::{metrics, RuntimeManager};
+use crate::error::AppError;
+use crate::models::RuntimeMetrics;
+
+/// Memory the guest keeps on top of what it uses, for bursts between two
+/// rounds.
+pub const HEADROOM_MB: u64 = 512;
+
+/// The balloon never leaves the guest less than this.
+pub const MIN_TARGET_MB: u64 = 1024;
+
+/// Most memory taken back per round, so shrinking is gradual.
+const SHRINK_STEP_MB: u64 = 512;
+
+/// Smaller shrinks are not worth a balloon change.
+const MIN_SHRINK_MB: u64 = 128;
+
+/// Memory the guest needs: what it has minus what it could free.
+fn demand_mb(m: &RuntimeMetrics) -> u64 {
+    let available = m.memory_total_mb.saturating_sub(m.memory_used_mb);
+    m.memory_allocated_mb
+        .saturating_sub(m.memory_balloon_mb)
+        .saturating_sub(available)
+}
+
+/// Balloon target for a guest that currently has `current_mb` of its
+/// `allocated_mb` and needs `demand_mb`. Grows at once, shrinks by at most
+/// [`SHRINK_STEP_MB`].
+pub fn next_target_mb(allocated_mb: u64, current_mb: u64, demand_mb: u64) -> u64 {
+    let floor = MIN_TARGET_MB.min(allocated_mb);
+    let wanted = (demand_mb + HEADROOM_MB).clamp(floor, allocated_mb);
+    if wanted >= current_mb {
+        wanted
+    } else if current_mb - wanted < MIN_SHRINK_MB {
+        current_mb
+    } else {
+        wanted.max(current_mb - SHRINK_STEP_MB)
+    }
+}
+
+/// Run one policy round: sample the guest and move the balloon of
+/// `runtime` if needed. Returns the new target, or `None` if unchanged.
+pub async fn rebalance(
+    runtime: &dyn RuntimeManager,
+    docker: &Docker,
+) -> Result<Option<u64>, AppError> {
+    let m = metrics::collect(docker).await?;
+    let current = m.memory_allocated_mb.saturating_sub(m.memory_balloon_mb);
+    let target = next_target_mb(m.memory_allocated_mb, current, demand_mb(&m));
+    if target == current {
+        return Ok(None);
+    }
+    runtime.set_balloon_target(target).await?;
+    Ok(Some(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_guests_shrink_in_steps_down_to_the_floor() {
+        // 8 GB guest using 300 MB: give back 512 MB per round.
+        assert_eq!(next_target_mb(8192, 8192, 300), 7680);
+        assert_eq!(next_target_mb(8192, 1280, 300), 1024);
+        // Small guests are never ballooned below their allocation.
+        assert_eq!(next_target_mb(768, 768, 100), 768);
+        // Within MIN_SHRINK_MB of the target: leave it.
+        assert_eq!(next_target_mb(8192, 1100, 300), 1100);
+    }
+
+    #[test]
+    fn busy_guests_get_memory_back_at_once() {
+        assert_eq!(next_target_mb(8192, 1024, 3000), 3512);
+        assert_eq!(next_target_mb(8192, 1024, 9000), 8192);
+    }
+
+    #[test]
+    fn demand_ignores_ballooned_and_reclaimable_memory() {
+        let m = RuntimeMetrics {
+            read_at: String::new(),
+            cpu_percent: 0.0,
+            cpu_cores: 2,
+            // The guest sees 6 GB after a 2 GB balloon; 5 GB is available.
+            memory_used_mb: 1024,
+            memory_total_mb: 6144,
+            memory_allocated_mb: 8192,
+            memory_balloon_mb: 2048,
+            disk_used_gb: 0.0,
+            disk_total_gb: 0.0,
+            net_rx_bytes: 0,
+            net_tx_bytes: 0,
+            container_count: 0,
+        };
+        assert_eq!(demand_mb(&m), 1024);
+    }
+}
@@ -0,0 +1,112 @@
+//! Guest clock resync after host sleep (§3.5).
+//!
+//! The runtime VM is frozen while the host sleeps, and its clock resumes
+//! where it stopped: after a night with the lid closed the guest is hours
+//! behind, which breaks TLS, `make` and token expiry. [`WakeDetector`]
+//! notices host sleep, and [`sync_guest_clock`] sets the guest clock to the
+//! host's.
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bollard::Docker;
+
+use crate::container;
+use crate::error::AppError;
+
+/// How often [`WakeDetector::check`] should be called.
+pub const WAKE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wall-clock time gained over the monotonic clock that counts as sleep.
+const WAKE_THRESHOLD: Duration = Duration::from_secs(10);
+
+const SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Detects host sleep by comparing the wall clock with the monotonic clock,
+/// which stops while the host is suspended (macOS and Linux).
+pub struct WakeDetector {
+    wall: SystemTime,
+    mono: Instant,
+}
+
+impl Default for WakeDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WakeDetector {
+    pub fn new() -> Self {
+        Self {
+            wall: SystemTime::now(),
+            mono: Instant::now(),
+        }
+    }
+
+    /// How long the host slept since the previous call, if it did.
+    pub fn check(&mut self) -> Option<Duration> {
+        self.check_at(SystemTime::now(), Instant::now())
+    }
+
+    fn check_at(&mut self, wall: SystemTime, mono: Instant) -> Option<Duration> {
+        let wall_elapsed = wall.duration_since(self.wall).unwrap_or_default();
+        let mono_elapsed = mono.duration_since(self.mono);
+        self.wall = wall;
+        self.mono = mono;
+        let slept = wall_elapsed.saturating_sub(mono_elapsed);
+        (slept >= WAKE_THRESHOLD).then_some(slept)
+    }
+}
+
+/// Set the runtime VM's clock to the host's. Returns how far the guest was
+/// off in seconds (positive when it was ahead).
+///
+/// The time is passed in the command, so the guest ends up behind by the
+/// helper container's startup (well under a second).
+pub async fn sync_guest_clock(docker: &Docker) -> Result<i64, AppError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| AppError::Runtime(format!("Host clock is before 1970: {}", e)))?
+        .as_secs();
+    let script = format!("date +%s && date -u -s @{} >/dev/null", now);
+    let cmd = vec!["/bin/sh".to_string(), "-c".to_string(), script];
+    let result = container::runtime_exec(docker, cmd, Some(SYNC_TIMEOUT)).await?;
+    if result.exit_code != 0 {
+        return Err(AppError::Runtime(format!(
+            "Setting the runtime clock failed ({}): {}",
+            result.exit_code,
+            result.stderr.trim()
+        )));
+    }
+    let guest: i64 = result
+        .stdout
+        .trim()
+        .parse()
+        .map_err(|_| AppError::Runtime("Unexpected `date` output".to_string()))?;
+    Ok(guest - now as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wake_is_wall_time_the_monotonic_clock_missed() {
+        let wall = SystemTime::now();
+        let mono = Instant::now();
+        let mut detector = WakeDetector { wall, mono };
+
+        // Awake: both clocks advance together.
+        let step = Duration::from_secs(5);
+        assert_eq!(detector.check_at(wall + step, mono + step), None);
+
+        // Asleep for an hour between two polls.
+        let slept = Duration::from_secs(3600);
+        assert_eq!(
+            detector.check_at(wall + step * 2 + slept, mono + step * 2),
+            Some(slept)
+        );
+
+        // Wall clock set back: not a wake.
+        assert_eq!(detector.check_at(wall, mono + step * 3), None);
+    }
+}
@@ -15,6 +15,7 @@
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
+use super::RuntimeState;
 use crate::error::AppError;
 
 // ---------------------------------------------------------------------------
@@ -81,24 +82,59 @@ pub fn env_flag_enabled(name: &str) -> bool {
 
 /// The VM name CrateBay uses for its built-in container runtime.
 ///
-/// Override via `CRATEBAY_RUNTIME_VM_NAME`. Defaults to `"cratebay-runtime"`.
+/// Override via `CRATEBAY_RUNTIME_VM_NAME`. Defaults to `"cratebay-runtime"`,
+/// or `"cratebay-runtime-<profile>"` for a non-default profile. An override
+/// that is not a valid VM name is ignored with a warning.
 pub fn runtime_vm_name() -> &'static str {
     RUNTIME_VM_NAME
         .get_or_init(|| {
             std::env::var("CRATEBAY_RUNTIME_VM_NAME")
                 .ok()
-                .filter(|v| !v.trim().is_empty())
-                .unwrap_or_else(|| DEFAULT_RUNTIME_VM_NAME.to_string())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .filter(|v| match crate::validation::validate_vm_name(v) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        tracing::warn!("Ignoring CRATEBAY_RUNTIME_VM_NAME: {}", e);
+                        false
+                    }
+                })
+                .unwrap_or_else(|| match crate::storage::profile() {
+                    Some(profile) => format!("{}-{}", DEFAULT_RUNTIME_VM_NAME, profile),
+                    None => DEFAULT_RUNTIME_VM_NAME.to_string(),
+                })
         })
         .as_str()
 }
 
+/// Hash of the directory that isolates this runtime from others: an
+/// explicit `CRATEBAY_DATA_DIR`, else the data dir of a non-default
+/// profile. `None` for the shared default runtime.
+fn isolation_hash() -> Option<u32> {
+    let dir = std::env::var("CRATEBAY_DATA_DIR")
+        .ok()
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .or_else(|| {
+            crate::storage::profile()
+                .map(|_| crate::storage::data_dir().to_string_lossy().into_owned())
+        })?;
+    Some(dir.bytes().fold(0_u32, |acc, byte| {
+        acc.wrapping_mul(131).wrapping_add(byte as u32)
+    }))
+}
+
+/// Path of the persisted [`RuntimeConfig`](super::RuntimeConfig).
+pub fn runtime_config_path() -> PathBuf {
+    crate::storage::data_dir().join("runtime-config.json")
+}
+
 /// The guest port for the Docker API proxy inside the runtime VM.
 ///
 /// Override via `CRATEBAY_DOCKER_PROXY_PORT` or the legacy
-/// `CRATEBAY_DOCKER_VSOCK_PORT`. When `CRATEBAY_DATA_DIR` is set and no port
-/// override is provided, derive a deterministic high port from the data dir so
-/// isolated runtimes do not all collide on the global default port.
+/// `CRATEBAY_DOCKER_VSOCK_PORT`. When `CRATEBAY_DATA_DIR` or a profile is set
+/// and no port override is provided, derive a deterministic high port from the
+/// data dir so isolated runtimes do not all collide on the global default port.
 pub fn docker_proxy_port() -> u32 {
     *DOCKER_PROXY_PORT.get_or_init(|| {
         std::env::var("CRATEBAY_DOCKER_PROXY_PORT")
@@ -111,18 +147,7 @@ pub fn docker_proxy_port() -> u32 {
                     .and_then(|v| v.parse::<u32>().ok())
                     .filter(|v| *v > 0)
             })
-            .or_else(|| {
-                std::env::var("CRATEBAY_DATA_DIR").ok().and_then(|dir| {
-                    let dir = dir.trim();
-                    if dir.is_empty() {
-                        return None;
-                    }
-                    let hash = dir.bytes().fold(0_u32, |acc, byte| {
-                        acc.wrapping_mul(131).wrapping_add(byte as u32)
-                    });
-                    Some(42000 + (hash % 10000))
-                })
-            })
+            .or_else(|| isolation_hash().map(|hash| 42000 + (hash % 10000)))
             .unwrap_or(DEFAULT_DOCKER_PROXY_PORT)
     })
 }
@@ -130,7 +155,7 @@ pub fn docker_proxy_port() -> u32 {
 /// The host-side Docker-compatible Unix socket path exposed by CrateBay.
 ///
 /// Defaults to `$HOME/.cratebay/runtime/docker.sock`. When `CRATEBAY_DATA_DIR`
-/// is explicitly set, derive a short, isolated socket path under the system
+/// or a profile is set, derive a short, isolated socket path under the system
 /// temp directory so isolated runtimes do not share the global socket path and
 /// do not hit macOS Unix socket path length limits.
 ///
@@ -144,16 +169,10 @@ pub fn host_docker_socket_path() -> &'static Path {
                 }
             }
 
-            if let Ok(dir) = std::env::var("CRATEBAY_DATA_DIR") {
-                let dir = dir.trim();
-                if !dir.is_empty() {
-                    let hash = dir.bytes().fold(0_u32, |acc, byte| {
-                        acc.wrapping_mul(131).wrapping_add(byte as u32)
-                    });
-                    return std::env::temp_dir()
-                        .join(format!("cratebay-runtime-{}", hash))
-                        .join("docker.sock");
-                }
+            if let Some(hash) = isolation_hash() {
+                return std::env::temp_dir()
+                    .join(format!("cratebay-runtime-{}", hash))
+                    .join("docker.sock");
             }
 
             if let Ok(home) = std::env::var("HOME") {
@@ -175,27 +194,35 @@ pub fn host_docker_socket_path() -> &'static Path {
 /// Per-VM Docker socket path on the host.
 ///
 /// Located alongside `host_docker_socket_path()` and includes an additional
-/// suffix when `CRATEBAY_DATA_DIR` is explicitly set, so isolated runtimes do
+/// suffix when `CRATEBAY_DATA_DIR` or a profile is set, so isolated runtimes do
 /// not collide on the same `/tmp/docker-<vm>.sock` path.
 pub fn runtime_host_docker_socket_path(vm_id: &str) -> PathBuf {
+    runtime_host_socket_path("docker", vm_id)
+}
+
+/// Per-VM runner control socket path on the host.
+///
+/// Lives next to the Docker socket so it stays well under the 104-byte
+/// `sun_path` limit on macOS.
+pub fn runtime_control_socket_path(vm_id: &str) -> PathBuf {
+    runtime_host_socket_path("control", vm_id)
+}
+
+/// Per-VM interactive serial console socket path on the host.
+pub fn runtime_console_socket_path(vm_id: &str) -> PathBuf {
+    runtime_host_socket_path("console", vm_id)
+}
+
+/// `<socket dir>/<prefix>-<vm>[-<data dir hash>].sock`.
+fn runtime_host_socket_path(prefix: &str, vm_id: &str) -> PathBuf {
     let base = host_docker_socket_path()
         .parent()
         .map(Path::to_path_buf)
         .unwrap_or_else(|| crate::storage::data_dir().join("runtime"));
 
-    let suffix = std::env::var("CRATEBAY_DATA_DIR")
-        .ok()
-        .map(|dir| dir.trim().to_string())
-        .filter(|dir| !dir.is_empty())
-        .map(|dir| {
-            dir.bytes().fold(0_u32, |acc, byte| {
-                acc.wrapping_mul(131).wrapping_add(byte as u32)
-            })
-        });
-
-    match suffix {
-        Some(hash) => base.join(format!("docker-{}-{}.sock", vm_id, hash)),
-        None => base.join(format!("docker-{}.sock", vm_id)),
+    match isolation_hash() {
+        Some(hash) => base.join(format!("{}-{}-{}.sock", prefix, vm_id, hash)),
+        None => base.join(format!("{}-{}.sock", prefix, vm_id)),
     }
 }
 
@@ -566,12 +593,8 @@ pub fn runtime_image_assets_dir(image_id: &str) -> Option<PathBuf> {
 
         match bundled_assets_ready(image_id, &dir) {
             Some(true) => return Some(dir),
-            Some(false) => {
-                if placeholder_dir.is_none() {
-                    placeholder_dir = Some(dir);
-                }
-            }
-            None => {}
+            Some(false) if placeholder_dir.is_none() => placeholder_dir = Some(dir),
+            _ => {}
         }
     }
 
@@ -770,6 +793,95 @@ pub fn ensure_runtime_image_ready(image_id: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Whether usable (non-placeholder) bundled assets exist for `image_id`.
+pub fn bundled_runtime_assets_available(image_id: &str) -> bool {
+    runtime_image_assets_dir(image_id)
+        .is_some_and(|dir| bundled_assets_ready(image_id, &dir) == Some(true))
+}
+
+/// Ensure the runtime image is installed, downloading it from the image
+/// catalog when this build ships no usable bundled assets.
+///
+/// Bundled assets always win when present, so the desktop app keeps running
+/// the exact image it was released with.
+pub async fn ensure_runtime_image_ready_or_download(
+    image_id: &str,
+    on_progress: Option<crate::images::DownloadProgressCallback>,
+) -> Result<(), AppError> {
+    let id = image_id.to_string();
+    let needs_download = tokio::task::spawn_blocking(move || {
+        let installed = crate::images::is_image_ready(&id) && image_files_present(&id);
+        !(installed || bundled_runtime_assets_available(&id))
+    })
+    .await
+    .map_err(|e| AppError::Runtime(format!("Image check task panicked: {}", e)))?;
+
+    if needs_download {
+        tracing::info!(
+            "No bundled runtime assets for '{}'; downloading from the image catalog",
+            image_id
+        );
+        return crate::images::download_image(image_id, on_progress, None).await;
+    }
+
+    let id = image_id.to_string();
+    tokio::task::spawn_blocking(move || ensure_runtime_image_ready(&id))
+        .await
+        .map_err(|e| AppError::Runtime(format!("Image install task panicked: {}", e)))?
+}
+
+// ---------------------------------------------------------------------------
+// Shared directories
+// ---------------------------------------------------------------------------
+
+/// Guest directory under which shared host directories are mounted.
+pub const SHARED_DIR_GUEST_ROOT: &str = "/mnt/cratebay";
+
+/// Guest mount point for the shared directory tagged `tag`.
+pub fn shared_dir_guest_path(tag: &str) -> String {
+    format!("{}/{}", SHARED_DIR_GUEST_ROOT, tag)
+}
+
+/// Translate a Windows host path (`C:\Users\me`, or the `\\?\C:\...` form
+/// returned by `canonicalize`) into its WSL automount path
+/// (`/mnt/c/Users/me`). UNC shares have no automount path.
+pub fn wsl_automount_path(host_path: &str) -> Option<String> {
+    let path = host_path.strip_prefix(r"\\?\").unwrap_or(host_path);
+    let mut chars = path.chars();
+    let drive = chars.next().filter(|c| c.is_ascii_alphabetic())?;
+    if chars.next()? != ':' {
+        return None;
+    }
+    let rest = chars.as_str().replace('\\', "/");
+    Some(format!(
+        "/mnt/{}{}",
+        drive.to_ascii_lowercase(),
+        rest.trim_end_matches('/')
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Registry settings for the guest engine
+// ---------------------------------------------------------------------------
+
+/// Kernel arguments carrying Docker Hub mirrors and `registry_proxy` from
+/// `config.toml` into the guest, which writes them to `daemon.json`
+/// (`registry-mirrors` and `proxies`). dockerd has no per-registry proxies
+/// or mirrors for other registries, so only these two are forwarded.
+pub fn registry_cmdline_args(config: &crate::config::Config) -> String {
+    let mut args = String::new();
+    let mirrors = config.registry_mirrors_for("docker.io");
+    if !mirrors.is_empty() {
+        args.push_str(" cratebay_registry_mirrors=");
+        args.push_str(&mirrors.join(","));
+    }
+    if let Some(proxy) = &config.registry_proxy {
+        args.push_str(" cratebay_registry_proxy=");
+        args.push_str(proxy);
+    }
+    args
+}
+
 // ---------------------------------------------------------------------------
 // Docker TCP endpoint parsing
 // ---------------------------------------------------------------------------
@@ -875,6 +987,58 @@ pub fn wait_for_docker_tcp(host: &str, timeout: std::time::Duration) -> Result<(
     Err(last_error)
 }
 
+// ---------------------------------------------------------------------------
+// Disk resize
+// ---------------------------------------------------------------------------
+
+/// Ensure the runtime is stopped before an offline VM change, failing with
+/// [`AppError::RequiresRestart`] otherwise (`action` completes the sentence
+/// "stop the runtime before ...").
+pub fn ensure_runtime_stopped(state: &RuntimeState, action: &str) -> Result<(), AppError> {
+    match state {
+        RuntimeState::Starting | RuntimeState::Ready | RuntimeState::Stopping => {
+            Err(AppError::RequiresRestart(action.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Grow a raw (sparse) disk image to `new_gb` gigabytes.
+///
+/// Returns the previous size in bytes. Shrinking is rejected because the
+/// guest filesystem cannot be truncated safely from the host.
+pub fn grow_disk_image(path: &Path, new_gb: u32) -> Result<u64, AppError> {
+    if !path.exists() {
+        return Err(AppError::NotFound {
+            entity: "runtime disk".to_string(),
+            id: path.display().to_string(),
+        });
+    }
+
+    let current = std::fs::metadata(path)?.len();
+    let target = u64::from(new_gb) * 1024 * 1024 * 1024;
+    if target < current {
+        return Err(AppError::Validation(format!(
+            "Shrinking the runtime disk is not supported (current: {} GB, requested: {} GB)",
+            current / (1024 * 1024 * 1024),
+            new_gb
+        )));
+    }
+
+    if target > current {
+        let file = std::fs::OpenOptions::new().write(true).open(path)?;
+        file.set_len(target)?;
+        tracing::info!(
+            "Grew runtime disk {} from {} to {} bytes",
+            path.display(),
+            current,
+            target
+        );
+    }
+
+    Ok(current)
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -883,6 +1047,24 @@ pub fn wait_for_docker_tcp(host: &str, timeout: std::time::Duration) -> Result<(
 mod tests {
     use super::*;
 
+    #[test]
+    fn registry_cmdline_args_forward_hub_mirrors_and_proxy() {
+        let mut config = crate::config::Config::default();
+        assert_eq!(registry_cmdline_args(&config), "");
+
+        config
+            .set("registry_mirrors", "https://a.example,https://b.example")
+            .unwrap();
+        config
+            .set("registry_proxy", "socks5://10.0.0.1:1080")
+            .unwrap();
+        assert_eq!(
+            registry_cmdline_args(&config),
+            " cratebay_registry_mirrors=https://a.example,https://b.example \
+             cratebay_registry_proxy=socks5://10.0.0.1:1080"
+        );
+    }
+
     #[test]
     fn env_flag_truthy_values() {
         assert!(env_flag_truthy("1"));
@@ -949,6 +1131,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn runtime_control_socket_path_is_next_to_docker_socket() {
+        let control = runtime_control_socket_path("test-vm");
+        let docker = runtime_host_docker_socket_path("test-vm");
+        assert_eq!(control.parent(), docker.parent());
+        assert!(control.to_string_lossy().contains("control-test-vm"));
+
+        let console = runtime_console_socket_path("test-vm");
+        assert_eq!(console.parent(), docker.parent());
+        assert_ne!(console, control);
+    }
+
     #[test]
     fn runtime_os_image_id_not_empty() {
         let id = runtime_os_image_id();
@@ -987,6 +1181,26 @@ mod tests {
         assert_eq!(result, Some(("docker.local".to_string(), 2376)));
     }
 
+    #[test]
+    fn shared_dir_guest_path_uses_tag() {
+        assert_eq!(shared_dir_guest_path("code"), "/mnt/cratebay/code");
+    }
+
+    #[test]
+    fn wsl_automount_path_translates_drive_paths() {
+        assert_eq!(
+            wsl_automount_path(r"C:\Users\me\code").as_deref(),
+            Some("/mnt/c/Users/me/code")
+        );
+        assert_eq!(
+            wsl_automount_path(r"\\?\D:\work\").as_deref(),
+            Some("/mnt/d/work")
+        );
+        assert_eq!(wsl_automount_path(r"C:\").as_deref(), Some("/mnt/c"));
+        assert!(wsl_automount_path(r"\\server\share").is_none());
+        assert!(wsl_automount_path("/home/me").is_none());
+    }
+
     #[test]
     fn runtime_assets_root_candidates_not_empty() {
         // There should always be at least one candidate (the exe dir)
@@ -1082,4 +1296,55 @@ mod tests {
         std::fs::write(&b, b"a longer string").unwrap();
         assert!(!file_matches(&a, &b).unwrap());
     }
+
+    #[test]
+    fn grow_disk_image_extends_sparse_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("disk.raw");
+        std::fs::File::create(&path)
+            .unwrap()
+            .set_len(1024 * 1024 * 1024)
+            .unwrap();
+
+        let previous = grow_disk_image(&path, 2).unwrap();
+        assert_eq!(previous, 1024 * 1024 * 1024);
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len(),
+            2 * 1024 * 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn grow_disk_image_rejects_shrink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("disk.raw");
+        std::fs::File::create(&path)
+            .unwrap()
+            .set_len(2 * 1024 * 1024 * 1024)
+            .unwrap();
+
+        let err = grow_disk_image(&path, 1).unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len(),
+            2 * 1024 * 1024 * 1024
+        );
+    }
+
+    #[test]
+    fn grow_disk_image_missing_disk_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = grow_disk_image(&dir.path().join("missing.raw"), 10).unwrap_err();
+        assert!(matches!(err, AppError::NotFound { .. }));
+    }
+
+    #[test]
+    fn offline_change_requires_stopped_runtime() {
+        assert!(ensure_runtime_stopped(&RuntimeState::Stopped, "resizing").is_ok());
+        assert!(ensure_runtime_stopped(&RuntimeState::Provisioned, "resizing").is_ok());
+        assert!(matches!(
+            ensure_runtime_stopped(&RuntimeState::Ready, "resizing"),
+            Err(AppError::RequiresRestart(_))
+        ));
+    }
 }
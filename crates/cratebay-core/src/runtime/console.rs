@@ -0,0 +1,162 @@
+//! Interactive serial console of the runtime VM (§9.3).
+//!
+//! The runners expose the VM's serial port on a Unix socket next to the
+//! Docker socket ([`common::runtime_console_socket_path`]): the VZ runner
+//! relays its virtio console there, QEMU serves it as a `socket` chardev.
+//! Both still append guest output to the console log. [`Console`] connects
+//! the terminal to it until the user types [`DETACH_KEY`]. WSL2 has no
+//! serial console; `wsl -d <distro>` gives a shell instead.
+
+use std::path::PathBuf;
+
+use super::common;
+use crate::error::AppError;
+
+/// Ctrl-], as in telnet and `virsh console`.
+pub const DETACH_KEY: u8 = 0x1d;
+
+/// Console socket of the runtime VM.
+pub fn socket_path() -> PathBuf {
+    common::runtime_console_socket_path(common::runtime_vm_name())
+}
+
+/// Bytes of `input` to send to the guest, and whether it ends the session.
+#[cfg(any(unix, test))]
+fn split_detach(input: &[u8]) -> (&[u8], bool) {
+    match input.iter().position(|b| *b == DETACH_KEY) {
+        Some(pos) => (&input[..pos], true),
+        None => (input, false),
+    }
+}
+
+/// A connection to the runtime VM's serial console.
+#[cfg(unix)]
+pub struct Console {
+    stream: tokio::net::UnixStream,
+}
+
+#[cfg(unix)]
+impl Console {
+    pub async fn connect() -> Result<Self, AppError> {
+        let path = socket_path();
+        let stream = tokio::net::UnixStream::connect(&path).await.map_err(|e| {
+            AppError::Runtime(format!(
+                "Runtime console is not available at {} ({}); is the runtime running?",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self { stream })
+    }
+
+    /// Relay the terminal until [`DETACH_KEY`] is typed or the VM goes
+    /// away. The terminal is in raw mode meanwhile, so keys like Ctrl-C
+    /// reach the guest.
+    pub async fn attach(self) -> Result<(), AppError> {
+        relay(self.stream).await
+    }
+}
+
+#[cfg(unix)]
+async fn relay(stream: tokio::net::UnixStream) -> Result<(), AppError> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let (mut guest_read, mut guest_write) = stream.into_split();
+    let _raw = RawTerminal::enable();
+
+    let input = async {
+        let mut stdin = tokio::io::stdin();
+        let mut buf = [0u8; 1024];
+        loop {
+            let n = stdin.read(&mut buf).await?;
+            if n == 0 {
+                return Ok::<(), AppError>(());
+            }
+            let (send, detach) = split_detach(&buf[..n]);
+            guest_write.write_all(send).await?;
+            if detach {
+                return Ok(());
+            }
+        }
+    };
+    let output = async {
+        let mut stdout = tokio::io::stdout();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = guest_read.read(&mut buf).await?;
+            if n == 0 {
+                return Ok::<(), AppError>(());
+            }
+            stdout.write_all(&buf[..n]).await?;
+            stdout.flush().await?;
+        }
+    };
+    tokio::select! {
+        result = input => result,
+        result = output => result,
+    }
+}
+
+#[cfg(not(unix))]
+pub struct Console;
+
+#[cfg(not(unix))]
+impl Console {
+    pub async fn connect() -> Result<Self, AppError> {
+        Err(AppError::Runtime(
+            "The WSL2 runtime has no serial console; use `wsl -d <distro>` for a shell".to_string(),
+        ))
+    }
+
+    pub async fn attach(self) -> Result<(), AppError> {
+        Ok(())
+    }
+}
+
+/// Puts stdin in raw mode while alive, if it is a terminal.
+#[cfg(unix)]
+struct RawTerminal {
+    original: Option<libc::termios>,
+}
+
+#[cfg(unix)]
+impl RawTerminal {
+    fn enable() -> Self {
+        // SAFETY: termios is plain data; tcgetattr fills it or fails.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Self { original: None };
+        }
+        let mut raw = original;
+        unsafe {
+            libc::cfmakeraw(&mut raw);
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw);
+        }
+        Self {
+            original: Some(original),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        if let Some(original) = self.original.as_ref() {
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detach_key_ends_the_session_and_is_not_sent() {
+        assert_eq!(split_detach(b"ls\r"), (&b"ls\r"[..], false));
+        assert_eq!(split_detach(b"ab\x1dcd"), (&b"ab"[..], true));
+        assert_eq!(split_detach(b"\x1d"), (&b""[..], true));
+    }
+}
@@ -0,0 +1,354 @@
+//! Runner control channel protocol.
+//!
+//! Hypervisor runners (`cratebay-vz` today) listen on a Unix socket for
+//! JSON-RPC 2.0 requests, one JSON object per line in each direction. The
+//! types live in core so the macOS runtime and future Linux/Windows runners
+//! speak the same protocol; runners only supply the [`ControlCall`] dispatch.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// Bumped on incompatible changes to [`ControlCall`] or its results.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+const JSONRPC_VERSION: &str = "2.0";
+
+/// JSON-RPC error codes used on the control channel.
+pub mod error_codes {
+    pub const PARSE_ERROR: i32 = -32700;
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    pub const INVALID_PARAMS: i32 = -32602;
+    pub const INTERNAL_ERROR: i32 = -32603;
+    /// The runner understands the method but its hypervisor cannot do it.
+    pub const UNSUPPORTED: i32 = -32000;
+    /// The VM is not in a state that allows the call (e.g. resume while running).
+    pub const INVALID_STATE: i32 = -32001;
+}
+
+/// A control method and its parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum ControlCall {
+    /// Pause the vCPUs; guest memory stays resident.
+    Pause,
+    /// Resume a paused VM.
+    Resume,
+    /// Graceful (ACPI) shutdown. The runner replies first, then exits once
+    /// the VM is down.
+    Stop,
+    /// Share a host directory with the running VM.
+    AddShare {
+        tag: String,
+        host_path: String,
+        #[serde(default)]
+        read_only: bool,
+    },
+    /// Move the memory balloon so the guest keeps `target_mb`.
+    SetBalloon { target_mb: u64 },
+    /// Report [`RunnerStatus`].
+    State,
+}
+
+impl ControlCall {
+    fn method(&self) -> &'static str {
+        match self {
+            ControlCall::Pause => "pause",
+            ControlCall::Resume => "resume",
+            ControlCall::Stop => "stop",
+            ControlCall::AddShare { .. } => "add_share",
+            ControlCall::SetBalloon { .. } => "set_balloon",
+            ControlCall::State => "state",
+        }
+    }
+}
+
+const METHODS: &[&str] = &[
+    "pause",
+    "resume",
+    "stop",
+    "add_share",
+    "set_balloon",
+    "state",
+];
+
+/// A request line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlRequest {
+    pub jsonrpc: String,
+    pub id: u64,
+    #[serde(flatten)]
+    pub call: ControlCall,
+}
+
+impl ControlRequest {
+    pub fn new(call: ControlCall) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            call,
+        }
+    }
+}
+
+/// Error object of a failed call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl ControlError {
+    pub fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// A response line. Exactly one of `result` and `error` is set; `id` is
+/// `null` when the request could not be parsed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub jsonrpc: String,
+    pub id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ControlError>,
+}
+
+impl ControlResponse {
+    fn reply(id: Option<u64>, outcome: Result<serde_json::Value, ControlError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(value) => (Some(value), None),
+            Err(e) => (None, Some(e)),
+        };
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// Result of [`ControlCall::State`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerStatus {
+    /// Hypervisor state, e.g. `running`, `paused`, `stopping`.
+    pub state: String,
+    pub pid: u32,
+    pub protocol_version: u32,
+}
+
+/// Answer one request line. Runners call this for every line they read and
+/// write the serialized response back followed by `\n`.
+pub fn handle_line(
+    line: &str,
+    dispatch: impl FnOnce(ControlCall) -> Result<serde_json::Value, ControlError>,
+) -> ControlResponse {
+    let value: serde_json::Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(e) => {
+            return ControlResponse::reply(
+                None,
+                Err(ControlError::new(error_codes::PARSE_ERROR, e.to_string())),
+            )
+        }
+    };
+    let id = value.get("id").and_then(|v| v.as_u64());
+    let method = value
+        .get("method")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if !METHODS.contains(&method) {
+        return ControlResponse::reply(
+            id,
+            Err(ControlError::new(
+                error_codes::METHOD_NOT_FOUND,
+                format!("Unknown method '{}'", method),
+            )),
+        );
+    }
+    match serde_json::from_value::<ControlRequest>(value) {
+        Ok(request) => ControlResponse::reply(id, dispatch(request.call)),
+        Err(e) => ControlResponse::reply(
+            id,
+            Err(ControlError::new(
+                error_codes::INVALID_PARAMS,
+                e.to_string(),
+            )),
+        ),
+    }
+}
+
+/// Send `call` to the runner listening on `socket_path` and wait for the
+/// result.
+#[cfg(unix)]
+pub fn call(
+    socket_path: &std::path::Path,
+    call: ControlCall,
+    timeout: std::time::Duration,
+) -> Result<serde_json::Value, AppError> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let method = call.method();
+    let request = ControlRequest::new(call);
+    let mut stream = UnixStream::connect(socket_path)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut line = serde_json::to_string(&request)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.trim().is_empty() {
+        return Err(AppError::Runtime(format!(
+            "Runner closed the control socket without answering '{}'",
+            method
+        )));
+    }
+    let response: ControlResponse = serde_json::from_str(reply.trim())?;
+    if response.id != Some(request.id) {
+        return Err(AppError::Runtime(format!(
+            "Runner answered request {:?}, expected {}",
+            response.id, request.id
+        )));
+    }
+    match (response.result, response.error) {
+        (_, Some(e)) => Err(AppError::Runtime(format!(
+            "Runner rejected '{}' ({}): {}",
+            method, e.code, e.message
+        ))),
+        (Some(result), None) => Ok(result),
+        (None, None) => Ok(serde_json::Value::Null),
+    }
+}
+
+/// Query the runner's [`RunnerStatus`].
+#[cfg(unix)]
+pub fn status(
+    socket_path: &std::path::Path,
+    timeout: std::time::Duration,
+) -> Result<RunnerStatus, AppError> {
+    let value = call(socket_path, ControlCall::State, timeout)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_use_json_rpc_framing() {
+        let request = ControlRequest::new(ControlCall::AddShare {
+            tag: "src".to_string(),
+            host_path: "/Users/me/src".to_string(),
+            read_only: true,
+        });
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["jsonrpc"], "2.0");
+        assert_eq!(json["method"], "add_share");
+        assert_eq!(json["params"]["tag"], "src");
+        assert_eq!(json["params"]["read_only"], true);
+
+        let balloon = serde_json::to_value(ControlRequest::new(ControlCall::SetBalloon {
+            target_mb: 1024,
+        }))
+        .unwrap();
+        assert_eq!(balloon["method"], "set_balloon");
+        assert_eq!(balloon["params"]["target_mb"], 1024);
+
+        let pause = serde_json::to_value(ControlRequest::new(ControlCall::Pause)).unwrap();
+        assert_eq!(pause["method"], "pause");
+        assert!(pause.get("params").is_none());
+    }
+
+    #[test]
+    fn handle_line_dispatches_parsed_calls() {
+        let response = handle_line(r#"{"jsonrpc":"2.0","id":7,"method":"stop"}"#, |call| {
+            assert_eq!(call, ControlCall::Stop);
+            Ok(serde_json::Value::Null)
+        });
+        assert_eq!(response.id, Some(7));
+        assert!(response.error.is_none());
+
+        let response = handle_line(
+            r#"{"jsonrpc":"2.0","id":8,"method":"add_share","params":{"tag":"a","host_path":"/a"}}"#,
+            |call| {
+                assert!(matches!(
+                    call,
+                    ControlCall::AddShare {
+                        read_only: false,
+                        ..
+                    }
+                ));
+                Err(ControlError::new(error_codes::UNSUPPORTED, "no"))
+            },
+        );
+        assert_eq!(response.error.unwrap().code, error_codes::UNSUPPORTED);
+    }
+
+    #[test]
+    fn handle_line_reports_protocol_errors() {
+        let dispatch = |_| -> Result<serde_json::Value, ControlError> {
+            panic!("dispatch must not run for invalid requests")
+        };
+        let parse = handle_line("{not json", dispatch);
+        assert_eq!(parse.id, None);
+        assert_eq!(parse.error.unwrap().code, error_codes::PARSE_ERROR);
+
+        let unknown = handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"reboot"}"#, dispatch);
+        assert_eq!(unknown.id, Some(1));
+        assert_eq!(unknown.error.unwrap().code, error_codes::METHOD_NOT_FOUND);
+
+        let params = handle_line(
+            r#"{"jsonrpc":"2.0","id":2,"method":"add_share","params":{"tag":"a"}}"#,
+            dispatch,
+        );
+        assert_eq!(params.error.unwrap().code, error_codes::INVALID_PARAMS);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn call_round_trips_over_a_unix_socket() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixListener;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("control.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            BufReader::new(stream.try_clone().unwrap())
+                .read_line(&mut line)
+                .unwrap();
+            let response = handle_line(&line, |_| {
+                Ok(serde_json::to_value(RunnerStatus {
+                    state: "running".to_string(),
+                    pid: 42,
+                    protocol_version: PROTOCOL_VERSION,
+                })
+                .unwrap())
+            });
+            let mut out = serde_json::to_string(&response).unwrap();
+            out.push('\n');
+            (&stream).write_all(out.as_bytes()).unwrap();
+        });
+
+        let status = status(&path, std::time::Duration::from_secs(5)).unwrap();
+        server.join().unwrap();
+        assert_eq!(status.state, "running");
+        assert_eq!(status.pid, 42);
+    }
+}
@@ -0,0 +1,230 @@
+//! Host ports forwarded to ports on the runtime VM (§6.3).
+//!
+//! `cratebay runtime forward 8080:80` listens on `127.0.0.1:8080` and carries
+//! each connection to port 80 on the runtime VM, so servers inside the VM are
+//! reachable even when its network is NAT-only. Connections travel over the
+//! Docker API connection the runtime already exposes (vsock or TCP, §4.2):
+//! each one is an attached `nc` exec in a runtime helper container that
+//! shares the VM's network namespace. Only TCP is forwarded.
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use bollard::container::LogOutput;
+use bollard::exec::{CreateExecOptions, StartExecResults};
+use bollard::Docker;
+use futures_util::StreamExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{PortForward, Protocol};
+use crate::cancel::CancellationToken;
+use crate::container;
+use crate::error::AppError;
+
+/// Parse `HOST_PORT:GUEST_PORT[/tcp]`, or a single port forwarded to the
+/// same port.
+pub fn parse(spec: &str) -> Result<PortForward, AppError> {
+    let invalid = || {
+        AppError::Validation(format!(
+            "Invalid forward '{}': expected HOST_PORT:GUEST_PORT, e.g. 8080:80",
+            spec
+        ))
+    };
+    let (ports, protocol) = match spec.split_once('/') {
+        None => (spec, Protocol::Tcp),
+        Some((ports, "tcp")) => (ports, Protocol::Tcp),
+        Some((_, "udp")) => {
+            return Err(AppError::Validation(format!(
+                "Invalid forward '{}': only TCP ports can be forwarded",
+                spec
+            )))
+        }
+        Some(_) => return Err(invalid()),
+    };
+    let port = |s: &str| s.parse::<u16>().ok().filter(|p| *p != 0);
+    let (host_port, container_port) = match ports.split_once(':') {
+        Some((host, guest)) => (port(host), port(guest)),
+        None => (port(ports), port(ports)),
+    };
+    Ok(PortForward {
+        host_port: host_port.ok_or_else(invalid)?,
+        container_port: container_port.ok_or_else(invalid)?,
+        protocol,
+    })
+}
+
+/// Bound host listeners and the helper carrying their connections.
+pub struct Forwarder {
+    docker: Docker,
+    helper: String,
+    listeners: Vec<(TcpListener, u16)>,
+}
+
+impl Forwarder {
+    /// Listen on `127.0.0.1:<host_port>` for every forward and start the
+    /// runtime helper. Fails before starting anything if a port is taken.
+    pub async fn bind(docker: &Docker, forwards: &[PortForward]) -> Result<Self, AppError> {
+        let mut listeners = Vec::with_capacity(forwards.len());
+        for forward in forwards {
+            let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, forward.host_port));
+            let listener = TcpListener::bind(addr)
+                .await
+                .map_err(|e| AppError::Runtime(format!("Cannot listen on {}: {}", addr, e)))?;
+            listeners.push((listener, forward.container_port));
+        }
+        let helper = container::start_runtime_helper(docker, "forward").await?;
+        Ok(Self {
+            docker: docker.clone(),
+            helper,
+            listeners,
+        })
+    }
+
+    /// Host address and guest port of each forward.
+    pub fn addrs(&self) -> Vec<(SocketAddr, u16)> {
+        self.listeners
+            .iter()
+            .filter_map(|(listener, guest)| Some((listener.local_addr().ok()?, *guest)))
+            .collect()
+    }
+
+    /// Accept connections until `cancel` fires, then remove the helper.
+    pub async fn run(self, cancel: &CancellationToken) -> Result<(), AppError> {
+        let tasks: Vec<_> = self
+            .listeners
+            .into_iter()
+            .map(|(listener, guest_port)| {
+                tokio::spawn(accept_loop(
+                    self.docker.clone(),
+                    self.helper.clone(),
+                    listener,
+                    guest_port,
+                    cancel.clone(),
+                ))
+            })
+            .collect();
+        for task in tasks {
+            let _ = task.await;
+        }
+        // Removing the helper ends the execs of open connections.
+        container::delete(&self.docker, &self.helper, true).await
+    }
+}
+
+async fn accept_loop(
+    docker: Docker,
+    helper: String,
+    listener: TcpListener,
+    guest_port: u16,
+    cancel: CancellationToken,
+) {
+    loop {
+        let accepted = tokio::select! {
+            _ = cancel.cancelled() => return,
+            accepted = listener.accept() => accepted,
+        };
+        match accepted {
+            Ok((stream, peer)) => {
+                let docker = docker.clone();
+                let helper = helper.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = proxy(&docker, &helper, guest_port, stream).await {
+                        tracing::warn!("Forward from {} to port {}: {}", peer, guest_port, e);
+                    }
+                });
+            }
+            Err(e) => tracing::warn!("Accepting forwarded connection failed: {}", e),
+        }
+    }
+}
+
+/// Carry one connection to `guest_port` on the VM's loopback.
+async fn proxy(
+    docker: &Docker,
+    helper: &str,
+    guest_port: u16,
+    stream: TcpStream,
+) -> Result<(), AppError> {
+    let options = CreateExecOptions {
+        cmd: Some(vec![
+            "nc".to_string(),
+            "127.0.0.1".to_string(),
+            guest_port.to_string(),
+        ]),
+        attach_stdin: Some(true),
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        tty: Some(false),
+        ..Default::default()
+    };
+    let exec = docker.create_exec(helper, options).await?;
+    let StartExecResults::Attached {
+        mut output,
+        mut input,
+    } = docker.start_exec(&exec.id, None).await?
+    else {
+        return Err(AppError::Runtime(
+            "Docker did not attach to the forward exec".to_string(),
+        ));
+    };
+
+    let (mut client_read, mut client_write) = stream.into_split();
+    let upload = async move {
+        let _ = tokio::io::copy(&mut client_read, &mut input).await;
+        let _ = input.shutdown().await;
+    };
+    let download = async move {
+        while let Some(chunk) = output.next().await {
+            match chunk? {
+                LogOutput::StdOut { message } | LogOutput::Console { message } => {
+                    client_write.write_all(&message).await?
+                }
+                LogOutput::StdErr { message } => {
+                    tracing::debug!("nc: {}", String::from_utf8_lossy(&message).trim_end())
+                }
+                LogOutput::StdIn { .. } => {}
+            }
+        }
+        client_write.shutdown().await?;
+        Ok::<(), AppError>(())
+    };
+    tokio::pin!(download);
+
+    // The guest side closing ends the connection; the client closing its
+    // side only ends the upload, so the reply can still arrive.
+    tokio::select! {
+        result = &mut download => return result,
+        _ = upload => {}
+    }
+    download.await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_port_pairs_and_single_ports() {
+        let forward = parse("8080:80").unwrap();
+        assert_eq!((forward.host_port, forward.container_port), (8080, 80));
+        assert_eq!(forward.protocol, Protocol::Tcp);
+
+        let forward = parse("5432/tcp").unwrap();
+        assert_eq!((forward.host_port, forward.container_port), (5432, 5432));
+    }
+
+    #[test]
+    fn parse_rejects_udp_and_bad_ports() {
+        assert!(parse("53:53/udp")
+            .unwrap_err()
+            .to_string()
+            .contains("only TCP"));
+        for spec in ["", "0:80", "8080:", "8080:70000", "a:b", "80:80/sctp"] {
+            assert!(
+                matches!(parse(spec), Err(AppError::Validation(_))),
+                "{}",
+                spec
+            );
+        }
+    }
+}
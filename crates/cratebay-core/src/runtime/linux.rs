@@ -23,7 +23,8 @@
 //!
 //! # Lifecycle
 //!
-//! 1. **provision()** — Install runtime image from bundled assets via `common::ensure_runtime_image_ready()`
+//! 1. **provision()** — Install runtime image from bundled assets (or download it) via
+//!    `common::ensure_runtime_image_ready_or_download()`
 //! 2. **start()** — Spawn daemonized QEMU, write PID file, wait for Docker TCP readiness
 //! 3. **stop()** — SIGTERM → wait → SIGKILL → cleanup PID file
 //! 4. **detect()** — Check KVM, QEMU binary, image files, PID, Docker health
@@ -38,11 +39,14 @@ use std::time::Duration;
 use async_trait::async_trait;
 use tokio::sync::Mutex;
 
+use crate::config::{Config, QuotaResource};
 use crate::error::AppError;
 use crate::models::ResourceUsage;
 
 use super::common;
-use super::{HealthStatus, ProvisionProgress, RuntimeConfig, RuntimeManager, RuntimeState};
+use super::{
+    HealthStatus, HypervisorInfo, ProvisionProgress, RuntimeConfig, RuntimeManager, RuntimeState,
+};
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -115,13 +119,16 @@ fn kill_process(pid: u32, signal: i32) {
 
 /// Docker TCP port exposed by the QEMU VM on the host.
 ///
-/// Override via `CRATEBAY_LINUX_DOCKER_PORT`. Defaults to [`common::DEFAULT_LINUX_DOCKER_PORT`].
+/// Override via `CRATEBAY_LINUX_DOCKER_PORT`. Defaults to [`common::DEFAULT_LINUX_DOCKER_PORT`]
+/// plus the profile's [`crate::storage::profile_port_offset`].
 fn linux_docker_port() -> u16 {
     std::env::var("CRATEBAY_LINUX_DOCKER_PORT")
         .ok()
         .and_then(|v| v.parse::<u16>().ok())
         .filter(|port| *port > 0)
-        .unwrap_or(common::DEFAULT_LINUX_DOCKER_PORT)
+        .unwrap_or_else(|| {
+            common::DEFAULT_LINUX_DOCKER_PORT + crate::storage::profile_port_offset()
+        })
 }
 
 /// Docker host string: `tcp://127.0.0.1:<port>`.
@@ -143,6 +150,21 @@ fn kvm_available() -> bool {
         .is_ok()
 }
 
+/// First DRM render node, which virgl renders guest OpenGL through.
+fn render_node() -> Option<PathBuf> {
+    let mut nodes: Vec<PathBuf> = std::fs::read_dir("/dev/dri")
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("renderD"))
+        })
+        .collect();
+    nodes.sort();
+    nodes.into_iter().next()
+}
+
 // ---------------------------------------------------------------------------
 // QEMU binary discovery
 // ---------------------------------------------------------------------------
@@ -275,9 +297,13 @@ fn default_kernel_cmdline() -> &'static str {
 }
 
 /// Build the full kernel command line, including optional HTTP proxy.
-fn build_kernel_cmdline() -> String {
-    let mut cmdline = std::env::var("CRATEBAY_LINUX_RUNTIME_CMDLINE")
-        .ok()
+///
+/// `base` is the persisted override (§7.2.2); `CRATEBAY_LINUX_RUNTIME_CMDLINE`
+/// is still honoured when there is none.
+fn build_kernel_cmdline(base: Option<&str>) -> String {
+    let mut cmdline = base
+        .map(str::to_string)
+        .or_else(|| std::env::var("CRATEBAY_LINUX_RUNTIME_CMDLINE").ok())
         .filter(|v| !v.trim().is_empty())
         .unwrap_or_else(|| default_kernel_cmdline().to_string());
 
@@ -298,6 +324,11 @@ fn build_kernel_cmdline() -> String {
         }
     }
 
+    // Docker Hub mirrors and registry proxy for the guest daemon.json.
+    cmdline.push_str(&common::registry_cmdline_args(
+        &crate::config::Config::load_or_default(),
+    ));
+
     cmdline
 }
 
@@ -446,6 +477,7 @@ impl LinuxRuntime {
         disk_path: &Path,
         host_port: u16,
         guest_port: u32,
+        resources: &RuntimeConfig,
     ) -> Result<(), AppError> {
         let runtime_dir = runtime_dir();
         std::fs::create_dir_all(&runtime_dir)?;
@@ -461,9 +493,14 @@ impl LinuxRuntime {
             .write(true)
             .truncate(true)
             .open(&console_log)?;
+        let console_socket = common::runtime_console_socket_path(common::runtime_vm_name());
+        if let Some(parent) = console_socket.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let _ = std::fs::remove_file(&console_socket);
 
         let use_kvm = kvm_available();
-        let cmdline = build_kernel_cmdline();
+        let cmdline = build_kernel_cmdline(resources.boot.cmdline.as_deref());
 
         let machine = if cfg!(target_arch = "aarch64") {
             if use_kvm {
@@ -479,6 +516,20 @@ impl LinuxRuntime {
 
         let cpu = if use_kvm { "host" } else { "max" };
 
+        // A virgl GPU renders on the host GPU; without one there is no display.
+        let display = if resources.gpu {
+            let node = render_node().ok_or_else(|| {
+                AppError::Runtime(
+                    "GPU is enabled but no DRM render node (/dev/dri/renderD*) exists; \
+                     install the host GPU driver or run `cratebay runtime set --gpu false`"
+                        .to_string(),
+                )
+            })?;
+            format!("egl-headless,rendernode={}", node.display())
+        } else {
+            "none".to_string()
+        };
+
         let mut cmd = std::process::Command::new(qemu_path);
         cmd.arg("-name")
             .arg(common::runtime_vm_name())
@@ -487,9 +538,9 @@ impl LinuxRuntime {
             .arg("-cpu")
             .arg(cpu)
             .arg("-smp")
-            .arg("2")
+            .arg(resources.cpu_cores.to_string())
             .arg("-m")
-            .arg("2048")
+            .arg(resources.memory_mb.to_string())
             .arg("-kernel")
             .arg(&image_paths.kernel_path)
             .arg("-initrd")
@@ -503,13 +554,28 @@ impl LinuxRuntime {
                 "user,id=net0,hostfwd=tcp:127.0.0.1:{host_port}-:{guest_port}"
             ))
             .arg("-device")
-            .arg("virtio-net-pci,netdev=net0")
+            .arg(match &resources.mac_address {
+                Some(mac) => format!("virtio-net-pci,netdev=net0,mac={}", mac),
+                None => "virtio-net-pci,netdev=net0".to_string(),
+            })
             .arg("-device")
             .arg("virtio-rng-pci")
+            // Pages the guest frees go back to the host without a policy;
+            // the balloon gives way before the guest OOMs.
+            .arg("-device")
+            .arg("virtio-balloon-pci,free-page-reporting=on,deflate-on-oom=on")
+            // The serial console is a socket for `cratebay runtime attach`
+            // that QEMU also logs to the console log.
+            .arg("-chardev")
+            .arg(format!(
+                "socket,id=console0,path={},server=on,wait=off,logfile={}",
+                console_socket.display(),
+                console_log.display()
+            ))
             .arg("-serial")
-            .arg(format!("file:{}", console_log.display()))
+            .arg("chardev:console0")
             .arg("-display")
-            .arg("none")
+            .arg(&display)
             .arg("-monitor")
             .arg("none")
             .arg("-daemonize")
@@ -517,6 +583,10 @@ impl LinuxRuntime {
             .arg(&pid_file)
             .arg("-no-reboot");
 
+        if resources.gpu {
+            cmd.arg("-device").arg("virtio-gpu-gl-pci");
+        }
+
         // Add QEMU share directory for firmware files if available.
         if let Some(share_dir) = qemu_share_dir(qemu_path) {
             cmd.arg("-L").arg(share_dir);
@@ -689,8 +759,9 @@ impl RuntimeManager for LinuxRuntime {
     /// Provision the runtime — install runtime image from bundled assets (§8).
     ///
     /// Progress stages: checking → downloading → extracting → configuring → complete.
-    /// Uses `common::ensure_runtime_image_ready()` which handles bundled asset
-    /// discovery, placeholder detection, and file copying.
+    /// Uses `common::ensure_runtime_image_ready_or_download()` which handles
+    /// bundled asset discovery, placeholder detection, file copying, and the
+    /// checksum-verified catalog download when no assets are bundled.
     async fn provision(
         &self,
         on_progress: Box<dyn Fn(ProvisionProgress) + Send>,
@@ -737,11 +808,7 @@ impl RuntimeManager for LinuxRuntime {
             message: "Installing CrateBay Linux runtime image...".into(),
         });
 
-        // This is a blocking operation — run in spawn_blocking.
-        let id = image_id.clone();
-        tokio::task::spawn_blocking(move || common::ensure_runtime_image_ready(&id))
-            .await
-            .map_err(|e| AppError::Runtime(format!("Provision task panicked: {}", e)))??;
+        common::ensure_runtime_image_ready_or_download(&image_id, None).await?;
 
         on_progress(ProvisionProgress {
             stage: "extracting".into(),
@@ -814,16 +881,8 @@ impl RuntimeManager for LinuxRuntime {
         // Guard: Check current state.
         {
             let current = self.state.lock().await;
-            match &*current {
-                RuntimeState::Starting => {
-                    return Err(AppError::Runtime("Runtime is already starting".into()));
-                }
-                RuntimeState::Starting => {
-                    return Err(AppError::Runtime(
-                        "Runtime is currently being provisioned".into(),
-                    ));
-                }
-                _ => {} // Proceed.
+            if *current == RuntimeState::Starting {
+                return Err(AppError::Runtime("Runtime is already starting".into()));
             }
         }
 
@@ -884,10 +943,7 @@ impl RuntimeManager for LinuxRuntime {
 
         // Verify runtime image is ready.
         let image_id = common::runtime_os_image_id().to_string();
-        let id = image_id.clone();
-        tokio::task::spawn_blocking(move || common::ensure_runtime_image_ready(&id))
-            .await
-            .map_err(|e| AppError::Runtime(format!("Image check task panicked: {}", e)))??;
+        common::ensure_runtime_image_ready_or_download(&image_id, None).await?;
 
         // Check port availability.
         let host_for_port_check = host.clone();
@@ -904,17 +960,28 @@ impl RuntimeManager for LinuxRuntime {
             .await
             .map_err(|e| AppError::Runtime(format!("Disk ensure task panicked: {}", e)))??;
 
-        let image_paths = crate::images::image_paths(&image_id);
         let host_port = linux_docker_port();
         let guest_port = common::docker_proxy_port();
 
-        // Spawn QEMU.
+        // Spawn QEMU. CPU/memory and boot overrides come from the persisted
+        // config so `update_resources` takes effect on the next start.
+        let mut resources = RuntimeConfig::load();
+        resources.boot.validate()?;
+        resources.ensure_mac_address()?;
         let qp = qemu_path.clone();
-        let ip = image_paths.clone();
+        let ip = resources.boot.apply(crate::images::image_paths(&image_id));
         let dp = disk_path.clone();
-        tokio::task::spawn_blocking(move || Self::spawn_qemu(&qp, &ip, &dp, host_port, guest_port))
-            .await
-            .map_err(|e| AppError::Runtime(format!("QEMU spawn task panicked: {}", e)))??;
+        if !resources.shared_dirs.is_empty() {
+            tracing::warn!(
+                "Ignoring {} shared director(ies): the Linux runtime does not mount them yet",
+                resources.shared_dirs.len()
+            );
+        }
+        tokio::task::spawn_blocking(move || {
+            Self::spawn_qemu(&qp, &ip, &dp, host_port, guest_port, &resources)
+        })
+        .await
+        .map_err(|e| AppError::Runtime(format!("QEMU spawn task panicked: {}", e)))??;
 
         // Record start time.
         {
@@ -1075,6 +1142,15 @@ impl RuntimeManager for LinuxRuntime {
     /// On Linux the runtime uses TCP port forwarding, but the trait requires
     /// returning a `PathBuf`. We return a synthetic path that indicates TCP
     /// mode; the GUI layer should use `linux_docker_host()` for actual connection.
+    fn hypervisor(&self) -> HypervisorInfo {
+        HypervisorInfo {
+            backend: "kvm".to_string(),
+            available: kvm_available(),
+            rosetta_available: None,
+            gpu_available: Some(render_node().is_some()),
+        }
+    }
+
     fn docker_socket_path(&self) -> PathBuf {
         // Return the canonical host docker socket path.
         // The actual connection goes through TCP, but this provides a
@@ -1125,6 +1201,25 @@ impl RuntimeManager for LinuxRuntime {
             }
         }
     }
+
+    async fn set_balloon_target(&self, _target_mb: u64) -> Result<(), AppError> {
+        Err(AppError::Runtime(
+            "The KVM runtime returns freed guest memory through free page reporting; \
+             it has no balloon target"
+                .to_string(),
+        ))
+    }
+
+    /// Grow the runtime disk image (offline; the guest runs `resize2fs` on boot).
+    async fn resize_disk(&self, new_gb: u32) -> Result<(), AppError> {
+        common::ensure_runtime_stopped(&self.get_state().await?, "resizing its disk")?;
+        crate::validation::validate_disk_gb(new_gb)?;
+        Config::load()?.check_quota(QuotaResource::DiskGb, u64::from(new_gb))?;
+        tokio::task::spawn_blocking(move || common::grow_disk_image(&runtime_disk_path(), new_gb))
+            .await
+            .map_err(|e| AppError::Runtime(format!("Task join error: {}", e)))??;
+        Ok(())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1322,7 +1417,7 @@ mod tests {
 
     #[test]
     fn build_kernel_cmdline_contains_console() {
-        let cmdline = build_kernel_cmdline();
+        let cmdline = build_kernel_cmdline(None);
         assert!(
             cmdline.contains("console="),
             "cmdline should contain console=: {}",
@@ -1330,6 +1425,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_kernel_cmdline_uses_override() {
+        let cmdline = build_kernel_cmdline(Some("console=ttyS0 loglevel=7"));
+        assert!(
+            cmdline.starts_with("console=ttyS0 loglevel=7"),
+            "{}",
+            cmdline
+        );
+    }
+
     #[test]
     fn pid_alive_returns_false_for_nonexistent() {
         // PID 0 should not have a /proc entry (it's the kernel scheduler).
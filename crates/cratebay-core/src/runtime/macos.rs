@@ -24,7 +24,7 @@
 //!
 //! 1. **provision()** — Install runtime image from bundled assets or download
 //! 2. **start()** — Spawn VZ runner, wait for ready file, wait for Docker
-//! 3. **stop()** — SIGTERM → wait → SIGKILL → cleanup
+//! 3. **stop()** — control `stop` (SIGTERM fallback) → wait → SIGKILL → cleanup
 //! 4. **detect()** — Check macOS version, images, runner PID, Docker socket
 //!
 //! Ported from `master:crates/cratebay-core/src/macos.rs` (1749 lines) and
@@ -37,12 +37,16 @@ use std::time::{Duration, Instant, SystemTime};
 
 use async_trait::async_trait;
 
+use crate::config::{Config, QuotaResource};
 use crate::error::AppError;
 use crate::models::ResourceUsage;
 use crate::MutexExt;
 
 use super::common;
-use super::{HealthStatus, ProvisionProgress, RuntimeConfig, RuntimeManager, RuntimeState};
+use super::control::{self, ControlCall};
+use super::{
+    HealthStatus, HypervisorInfo, ProvisionProgress, RuntimeConfig, RuntimeManager, RuntimeState,
+};
 
 /// Minimum macOS version required for VZ.framework (macOS 13 Ventura).
 const MIN_MACOS_VERSION: u32 = 13;
@@ -66,6 +70,9 @@ const HEALTH_PING_CONNECT_TIMEOUT_SECS: u64 = 8;
 /// from `Ready` to `Starting`.
 const READY_DOWNGRADE_FAILURE_THRESHOLD: u8 = 3;
 
+/// Timeout for one request on the runner control socket.
+const CONTROL_CALL_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Grace period for SIGTERM before escalating to SIGKILL.
 const STOP_GRACE_PERIOD: Duration = Duration::from_secs(15);
 
@@ -562,10 +569,15 @@ impl MacOSRuntime {
     }
 
     /// Build kernel cmdline with DNS servers, host epoch and optional HTTP proxy.
-    fn build_cmdline(&self, runtime_http_proxy: Option<&RuntimeHttpProxyConfig>) -> String {
+    fn build_cmdline(
+        &self,
+        base: Option<&str>,
+        runtime_http_proxy: Option<&RuntimeHttpProxyConfig>,
+    ) -> String {
         let image_id = common::runtime_os_image_id();
-        let mut cmdline = crate::images::find_image(image_id)
-            .map(|e| e.default_cmdline)
+        let mut cmdline = base
+            .map(str::to_string)
+            .or_else(|| crate::images::find_image(image_id).map(|e| e.default_cmdline))
             .unwrap_or_else(|| "console=hvc0".to_string());
 
         // Inject runtime HTTP proxy for guest-side dockerd/apk egress
@@ -615,6 +627,11 @@ impl MacOSRuntime {
             cmdline.push_str(&common::docker_proxy_port().to_string());
         }
 
+        // Docker Hub mirrors and registry proxy for the guest daemon.json
+        cmdline.push_str(&common::registry_cmdline_args(
+            &crate::config::Config::load_or_default(),
+        ));
+
         tracing::debug!("VM boot cmdline: {}", cmdline);
         cmdline
     }
@@ -815,8 +832,13 @@ impl MacOSRuntime {
         // Ensure it has virtualization entitlements
         ensure_runner_entitlements(&runner_path)?;
 
+        // CPU/memory come from the persisted config so `update_resources`
+        // takes effect on the next start without recreating the manager.
+        let mut resources = RuntimeConfig::load();
+        resources.boot.validate()?;
+
         let image_id = common::runtime_os_image_id();
-        let paths = crate::images::image_paths(image_id);
+        let paths = resources.boot.apply(crate::images::image_paths(image_id));
 
         // Verify kernel exists
         if !paths.kernel_path.exists() {
@@ -853,7 +875,19 @@ impl MacOSRuntime {
         let console_err = console_file.try_clone()?;
 
         let runtime_http_proxy = Self::resolve_runtime_http_proxy_config();
-        let cmdline = self.build_cmdline(runtime_http_proxy.as_ref());
+        let mut cmdline = self.build_cmdline(
+            resources.boot.cmdline.as_deref(),
+            runtime_http_proxy.as_ref(),
+        );
+        let rosetta =
+            common::env_flag_enabled("CRATEBAY_RUNTIME_ROSETTA") && Self::rosetta_available();
+        if rosetta {
+            // Tells the guest init to mount the share and register binfmt.
+            cmdline.push_str(" cratebay_rosetta=1");
+        }
+        let mac_address = resources.ensure_mac_address()?;
+
+        let control_socket = common::runtime_control_socket_path(common::runtime_vm_name());
 
         let mut cmd = Command::new(&runner_path);
         cmd.arg("--kernel")
@@ -861,15 +895,23 @@ impl MacOSRuntime {
             .arg("--disk")
             .arg(&disk)
             .arg("--cpus")
-            .arg(self.config.cpu_cores.to_string())
+            .arg(resources.cpu_cores.to_string())
             .arg("--memory-mb")
-            .arg(self.config.memory_mb.to_string())
+            .arg(resources.memory_mb.to_string())
             .arg("--cmdline")
             .arg(&cmdline)
             .arg("--ready-file")
             .arg(&ready_file)
+            .arg("--control-socket")
+            .arg(&control_socket)
             .arg("--console-log")
-            .arg(&console_log);
+            .arg(&console_log)
+            .arg("--console-socket")
+            .arg(common::runtime_console_socket_path(
+                common::runtime_vm_name(),
+            ))
+            .arg("--mac")
+            .arg(&mac_address);
 
         // Set up initrd if present
         if paths.initrd_path.exists() {
@@ -957,12 +999,17 @@ impl MacOSRuntime {
         }
 
         // Rosetta support
-        if common::env_flag_enabled("CRATEBAY_RUNTIME_ROSETTA") && Self::rosetta_available() {
+        if rosetta {
             cmd.arg("--rosetta");
         }
 
-        // Shared directories
-        for share in &self.config.shared_dirs {
+        // virtio-gpu (persisted via `cratebay runtime set --gpu`)
+        if resources.gpu {
+            cmd.arg("--gpu");
+        }
+
+        // Shared directories (persisted via `cratebay runtime mount`)
+        for share in super::validate_shared_dirs(&resources.shared_dirs)? {
             cmd.arg("--share")
                 .arg(format!("{}:{}", share.tag, share.host_path));
         }
@@ -985,8 +1032,8 @@ impl MacOSRuntime {
             runner_path.display(),
             paths.kernel_path.display(),
             disk.display(),
-            self.config.cpu_cores,
-            self.config.memory_mb,
+            resources.cpu_cores,
+            resources.memory_mb,
         );
 
         let child = cmd.spawn().map_err(|e| {
@@ -1212,13 +1259,9 @@ impl RuntimeManager for MacOSRuntime {
         });
 
         let image_id = common::runtime_os_image_id();
-        // This copies from bundled assets — blocking I/O, wrap in spawn_blocking
-        tokio::task::spawn_blocking({
-            let image_id = image_id.to_string();
-            move || common::ensure_runtime_image_ready(&image_id)
-        })
-        .await
-        .map_err(|e| AppError::Runtime(format!("Task join error: {}", e)))??;
+        // Copies from bundled assets, or downloads the image when none ship
+        // with this build.
+        common::ensure_runtime_image_ready_or_download(image_id, None).await?;
 
         on_progress(ProvisionProgress {
             stage: "installing".into(),
@@ -1243,7 +1286,8 @@ impl RuntimeManager for MacOSRuntime {
         }
 
         if !disk_path.exists() {
-            let disk_bytes = (self.config.disk_gb as u64) * 1024 * 1024 * 1024;
+            // Sized from the persisted config, seeded by `default_vm_disk_gb`.
+            let disk_bytes = (RuntimeConfig::load().disk_gb as u64) * 1024 * 1024 * 1024;
             let image_id_owned = image_id.to_string();
             let disk_path_owned = disk_path.clone();
             tokio::task::spawn_blocking(move || {
@@ -1514,7 +1558,8 @@ impl RuntimeManager for MacOSRuntime {
     /// Stop the VZ.framework virtual machine gracefully.
     ///
     /// Flow:
-    /// 1. SIGTERM to the runner (triggers VZ graceful shutdown)
+    /// 1. `stop` over the runner control socket, or SIGTERM for runners
+    ///    started without one (both trigger VZ graceful shutdown)
     /// 2. Wait up to 15 seconds for the process to exit
     /// 3. SIGKILL if still alive
     /// 4. Clean up socket files and PID files
@@ -1530,11 +1575,23 @@ impl RuntimeManager for MacOSRuntime {
         // Get the runner PID
         let runner_pid = self.current_runner_pid();
 
-        // Phase 1: Send SIGTERM for graceful shutdown
+        // Phase 1: Request graceful shutdown
         if let Some(pid) = runner_pid {
-            tracing::info!("Sending SIGTERM to VZ runner (PID {})", pid);
-            unsafe {
-                libc::kill(pid as i32, libc::SIGTERM);
+            let control_socket = common::runtime_control_socket_path(common::runtime_vm_name());
+            match control::call(&control_socket, ControlCall::Stop, CONTROL_CALL_TIMEOUT) {
+                Ok(_) => {
+                    tracing::info!("Requested VZ runner stop over control socket (PID {})", pid)
+                }
+                Err(e) => {
+                    tracing::info!(
+                        "Runner control socket unavailable ({}), sending SIGTERM to VZ runner (PID {})",
+                        e,
+                        pid
+                    );
+                    unsafe {
+                        libc::kill(pid as i32, libc::SIGTERM);
+                    }
+                }
             }
 
             // Phase 2: Wait for graceful shutdown
@@ -1580,6 +1637,9 @@ impl RuntimeManager for MacOSRuntime {
         // Clean up files
         let _ = std::fs::remove_file(vm_runner_pid_path());
         let _ = std::fs::remove_file(vm_runner_ready_path());
+        let _ = std::fs::remove_file(common::runtime_control_socket_path(
+            common::runtime_vm_name(),
+        ));
 
         // Clean up stray processes
         cleanup_stray_runner_processes(None, "stop cleanup");
@@ -1666,6 +1726,16 @@ impl RuntimeManager for MacOSRuntime {
         common::host_docker_socket_path().to_path_buf()
     }
 
+    fn hypervisor(&self) -> HypervisorInfo {
+        HypervisorInfo {
+            backend: "virtualization.framework".to_string(),
+            available: Self::check_macos_version().unwrap_or(false),
+            rosetta_available: cfg!(target_arch = "aarch64").then(Self::rosetta_available),
+            // virtio-gpu exists since macOS 12; the minimum is 13.
+            gpu_available: Some(true),
+        }
+    }
+
     /// Get current resource usage of the VM.
     ///
     /// When Docker is responsive, queries container count via the API.
@@ -1703,6 +1773,28 @@ impl RuntimeManager for MacOSRuntime {
             container_count,
         })
     }
+
+    /// Ask the VZ runner over its control socket.
+    async fn set_balloon_target(&self, target_mb: u64) -> Result<(), AppError> {
+        let control_socket = common::runtime_control_socket_path(common::runtime_vm_name());
+        control::call(
+            &control_socket,
+            ControlCall::SetBalloon { target_mb },
+            CONTROL_CALL_TIMEOUT,
+        )?;
+        Ok(())
+    }
+
+    /// Grow the runtime disk image (offline; the guest runs `resize2fs` on boot).
+    async fn resize_disk(&self, new_gb: u32) -> Result<(), AppError> {
+        common::ensure_runtime_stopped(&self.get_state().await?, "resizing its disk")?;
+        crate::validation::validate_disk_gb(new_gb)?;
+        Config::load()?.check_quota(QuotaResource::DiskGb, u64::from(new_gb))?;
+        tokio::task::spawn_blocking(move || common::grow_disk_image(&vm_disk_path(), new_gb))
+            .await
+            .map_err(|e| AppError::Runtime(format!("Task join error: {}", e)))??;
+        Ok(())
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -1852,7 +1944,7 @@ mod tests {
     #[test]
     fn build_cmdline_contains_console() {
         let rt = test_runtime();
-        let cmdline = rt.build_cmdline(None);
+        let cmdline = rt.build_cmdline(None, None);
         assert!(
             cmdline.contains("console="),
             "cmdline should contain console=: {}",
@@ -1860,10 +1952,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn build_cmdline_uses_override() {
+        let rt = test_runtime();
+        let cmdline = rt.build_cmdline(Some("console=hvc0 loglevel=7"), None);
+        assert!(
+            cmdline.starts_with("console=hvc0 loglevel=7"),
+            "{}",
+            cmdline
+        );
+        assert!(cmdline.contains("cratebay_dns="), "{}", cmdline);
+    }
+
     #[test]
     fn build_cmdline_contains_epoch() {
         let rt = test_runtime();
-        let cmdline = rt.build_cmdline(None);
+        let cmdline = rt.build_cmdline(None, None);
         assert!(
             cmdline.contains("cratebay_host_epoch="),
             "cmdline should contain host epoch: {}",
@@ -1970,6 +2074,9 @@ mod tests {
                 host_path: "/Users/test".into(),
                 tag: "test".into(),
             }],
+            mac_address: None,
+            gpu: false,
+            boot: Default::default(),
         };
         let rt = MacOSRuntime::with_config(config);
         assert_eq!(rt.config.cpu_cores, 8);
@@ -2017,6 +2124,9 @@ mod tests {
             disk_gb: 50,
             auto_start: true,
             shared_dirs: vec![],
+            mac_address: None,
+            gpu: false,
+            boot: Default::default(),
         };
         let rt = MacOSRuntime::with_config(config);
         let usage = rt.resource_usage().await.unwrap();
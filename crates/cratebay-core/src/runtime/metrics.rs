@@ -0,0 +1,231 @@
+//! Live runtime VM metrics sampled inside the guest.
+//!
+//! The host-side [`RuntimeManager::resource_usage`](super::RuntimeManager::resource_usage)
+//! only sees the hypervisor process. This module reads `/proc` and `df`
+//! inside the VM from a runtime helper container, so the numbers are the
+//! same on every platform. Samples exec into one shared, idle helper
+//! (`cratebay-runtime-metrics`) instead of creating a container each time,
+//! which would flood the engine with container events.
+
+use bollard::Docker;
+
+use crate::container;
+use crate::error::AppError;
+use crate::models::RuntimeMetrics;
+
+use super::RuntimeConfig;
+
+/// Upper bound for one sample (exec + 1s CPU window), in seconds.
+const METRICS_TIMEOUT_SECS: u64 = 30;
+
+/// Kind of the shared helper: `cratebay-runtime-metrics`.
+const METRICS_HELPER: &str = "runtime-metrics";
+
+/// Shell script run in the guest; sections are introduced by `--name` lines.
+const METRICS_SCRIPT: &str = "\
+echo --stat; head -n1 /proc/stat; sleep 1; head -n1 /proc/stat; \
+echo --cpus; grep -c '^processor' /proc/cpuinfo; \
+echo --mem; grep -E '^(MemTotal|MemAvailable):' /proc/meminfo; \
+echo --balloon; getconf PAGESIZE 2>/dev/null; grep -E '^balloon_(inflate|deflate) ' /proc/vmstat; \
+echo --disk; df -kP /var/lib/docker 2>/dev/null || df -kP /; \
+echo --net; cat /proc/net/dev";
+
+/// Sample CPU, memory, disk and network usage of the runtime VM.
+pub async fn collect(docker: &Docker) -> Result<RuntimeMetrics, AppError> {
+    let cmd = container::host_namespace_cmd(vec![
+        "/bin/sh".to_string(),
+        "-c".to_string(),
+        METRICS_SCRIPT.to_string(),
+    ]);
+    let helper = container::shared_runtime_helper(docker, METRICS_HELPER).await?;
+    let result =
+        container::exec_with_timeout(docker, &helper, cmd, None, METRICS_TIMEOUT_SECS).await?;
+    if result.exit_code != 0 {
+        return Err(AppError::Runtime(format!(
+            "Runtime metrics script exited with {}: {}",
+            result.exit_code,
+            result.stderr.trim()
+        )));
+    }
+
+    let mut metrics = parse_metrics(&result.stdout)?;
+    metrics.memory_allocated_mb = RuntimeConfig::load().memory_mb;
+    metrics.container_count = container::list(docker, false, None)
+        .await
+        .map(|c| c.len() as u32)
+        .unwrap_or(0);
+    Ok(metrics)
+}
+
+/// Parse the output of [`METRICS_SCRIPT`].
+fn parse_metrics(output: &str) -> Result<RuntimeMetrics, AppError> {
+    let section = |name: &str| -> Vec<&str> {
+        let marker = format!("--{}", name);
+        output
+            .lines()
+            .skip_while(|l| l.trim() != marker)
+            .skip(1)
+            .take_while(|l| !l.starts_with("--"))
+            .collect()
+    };
+    let malformed = |what: &str| AppError::Runtime(format!("Unexpected {} output", what));
+
+    let stat = section("stat");
+    let (first, second) = match stat.as_slice() {
+        [a, b, ..] => (
+            parse_cpu_line(a).ok_or_else(|| malformed("/proc/stat"))?,
+            parse_cpu_line(b).ok_or_else(|| malformed("/proc/stat"))?,
+        ),
+        _ => return Err(malformed("/proc/stat")),
+    };
+    let total = second.0.saturating_sub(first.0);
+    let idle = second.1.saturating_sub(first.1);
+    let cpu_percent = if total == 0 {
+        0.0
+    } else {
+        (total.saturating_sub(idle) as f32 / total as f32) * 100.0
+    };
+
+    let cpu_cores = section("cpus")
+        .first()
+        .and_then(|l| l.trim().parse().ok())
+        .unwrap_or(0);
+
+    let meminfo_kb = |key: &str| -> u64 {
+        section("mem")
+            .iter()
+            .find(|l| l.starts_with(key))
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    };
+    let memory_total_kb = meminfo_kb("MemTotal:");
+    let memory_available_kb = meminfo_kb("MemAvailable:");
+
+    let balloon = section("balloon");
+    let page_size = balloon
+        .first()
+        .and_then(|l| l.trim().parse::<u64>().ok())
+        .unwrap_or(4096);
+    let vmstat = |key: &str| -> u64 {
+        balloon
+            .iter()
+            .filter_map(|l| l.split_once(' '))
+            .find(|(name, _)| *name == key)
+            .and_then(|(_, v)| v.trim().parse().ok())
+            .unwrap_or(0)
+    };
+    let balloon_pages = vmstat("balloon_inflate").saturating_sub(vmstat("balloon_deflate"));
+
+    let (disk_total_kb, disk_used_kb) = section("disk")
+        .last()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>())
+        .and_then(|f| Some((f.get(1)?.parse().ok()?, f.get(2)?.parse().ok()?)))
+        .unwrap_or((0u64, 0u64));
+
+    let (net_rx_bytes, net_tx_bytes) = parse_net_dev(&section("net"));
+
+    const KB_PER_GB: f32 = 1024.0 * 1024.0;
+    Ok(RuntimeMetrics {
+        read_at: chrono::Utc::now().to_rfc3339(),
+        cpu_percent,
+        cpu_cores,
+        memory_used_mb: memory_total_kb.saturating_sub(memory_available_kb) / 1024,
+        memory_total_mb: memory_total_kb / 1024,
+        memory_allocated_mb: 0,
+        memory_balloon_mb: balloon_pages * page_size / (1024 * 1024),
+        disk_used_gb: disk_used_kb as f32 / KB_PER_GB,
+        disk_total_gb: disk_total_kb as f32 / KB_PER_GB,
+        net_rx_bytes,
+        net_tx_bytes,
+        container_count: 0,
+    })
+}
+
+/// `(total, idle)` jiffies from the aggregate `cpu` line of `/proc/stat`.
+fn parse_cpu_line(line: &str) -> Option<(u64, u64)> {
+    let mut fields = line.split_whitespace();
+    if fields.next()? != "cpu" {
+        return None;
+    }
+    let values: Vec<u64> = fields.filter_map(|v| v.parse().ok()).collect();
+    if values.len() < 4 {
+        return None;
+    }
+    // idle + iowait count as idle time.
+    let idle = values[3] + values.get(4).copied().unwrap_or(0);
+    Some((values.iter().sum(), idle))
+}
+
+/// Sum rx/tx bytes over physical interfaces, skipping loopback and the
+/// virtual interfaces Docker creates for containers.
+fn parse_net_dev(lines: &[&str]) -> (u64, u64) {
+    lines
+        .iter()
+        .filter_map(|l| l.split_once(':'))
+        .filter(|(iface, _)| {
+            let iface = iface.trim();
+            !(iface == "lo"
+                || iface.starts_with("veth")
+                || iface.starts_with("docker")
+                || iface.starts_with("br-"))
+        })
+        .filter_map(|(_, rest)| {
+            let fields: Vec<u64> = rest
+                .split_whitespace()
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            Some((*fields.first()?, *fields.get(8)?))
+        })
+        .fold((0, 0), |(rx, tx), (r, t)| (rx + r, tx + t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+--stat
+cpu  100 0 100 800 0 0 0 0 0 0
+cpu  150 0 150 900 0 0 0 0 0 0
+--cpus
+2
+--mem
+MemTotal:        2048000 kB
+MemAvailable:    1024000 kB
+--balloon
+4096
+balloon_inflate 393216
+balloon_deflate 131072
+--disk
+Filesystem     1024-blocks    Used Available Capacity Mounted on
+/dev/vda          20971520 5242880  15728640      25% /var/lib/docker
+--net
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:    5000      10    0    0    0     0          0         0     5000      10    0    0    0     0       0          0
+  eth0: 1000000     800    0    0    0     0          0         0   200000     600    0    0    0     0       0          0
+vethab12:   777       7    0    0    0     0          0         0      777       7    0    0    0     0       0          0
+";
+
+    #[test]
+    fn parses_guest_metrics() {
+        let m = parse_metrics(SAMPLE).unwrap();
+        // 200 jiffies elapsed, 100 idle.
+        assert!((m.cpu_percent - 50.0).abs() < 0.01);
+        assert_eq!(m.cpu_cores, 2);
+        assert_eq!(m.memory_total_mb, 2000);
+        assert_eq!(m.memory_used_mb, 1000);
+        assert_eq!(m.memory_balloon_mb, 1024);
+        assert!((m.disk_total_gb - 20.0).abs() < 0.01);
+        assert!((m.disk_used_gb - 5.0).abs() < 0.01);
+        assert_eq!(m.net_rx_bytes, 1_000_000);
+        assert_eq!(m.net_tx_bytes, 200_000);
+    }
+
+    #[test]
+    fn rejects_missing_cpu_samples() {
+        let err = parse_metrics("--stat\ncpu 1 2 3 4\n--cpus\n1\n").unwrap_err();
+        assert!(matches!(err, AppError::Runtime(_)));
+    }
+}
@@ -5,7 +5,15 @@
 //! This module defines the platform-agnostic [`RuntimeManager`] trait and
 //! all supporting types for managing the built-in container runtime.
 
+pub mod balloon;
+pub mod clock;
 pub mod common;
+pub mod console;
+pub mod control;
+pub mod forward;
+pub mod metrics;
+pub mod ops;
+pub mod rosetta;
 
 #[cfg(target_os = "linux")]
 pub mod linux;
@@ -14,13 +22,14 @@ pub mod macos;
 #[cfg(target_os = "windows")]
 pub mod windows;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use crate::config::{Config, QuotaResource};
 use crate::error::AppError;
 use crate::models::ResourceUsage;
 
@@ -90,12 +99,31 @@ pub struct HealthStatus {
     pub docker_source: Option<String>,
 }
 
+/// Host virtualization backend used by the runtime.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HypervisorInfo {
+    /// Backend name: `virtualization.framework`, `kvm` or `wsl2`.
+    pub backend: String,
+    /// Whether the backend is usable on this host.
+    pub available: bool,
+    /// Rosetta x86_64 emulation (Apple Silicon only; None elsewhere).
+    pub rosetta_available: Option<bool>,
+    /// Whether [`RuntimeConfig::gpu`] can attach a virtio-gpu device (None
+    /// where the backend has no such setting).
+    pub gpu_available: Option<bool>,
+}
+
 // ---------------------------------------------------------------------------
 // Runtime Configuration (§7.2)
 // ---------------------------------------------------------------------------
 
 /// Configuration for the built-in container runtime VM.
+///
+/// Persisted to `runtime-config.json` under the data directory; missing
+/// fields fall back to the defaults.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RuntimeConfig {
     /// Number of CPU cores allocated to the VM.
     pub cpu_cores: u32,
@@ -107,6 +135,15 @@ pub struct RuntimeConfig {
     pub auto_start: bool,
     /// Shared directories (host → guest).
     pub shared_dirs: Vec<SharedDir>,
+    /// MAC address of the VM's NIC, generated on first start so DHCP hands
+    /// out the same lease across restarts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    /// Attach a virtio-gpu device to the VM (§7.2.1).
+    pub gpu: bool,
+    /// Kernel, initrd and command line overrides (§7.2.2).
+    #[serde(skip_serializing_if = "BootConfig::is_empty")]
+    pub boot: BootConfig,
 }
 
 impl Default for RuntimeConfig {
@@ -117,8 +154,207 @@ impl Default for RuntimeConfig {
             disk_gb: 20,
             auto_start: true,
             shared_dirs: vec![],
+            mac_address: None,
+            gpu: false,
+            boot: BootConfig::default(),
+        }
+    }
+}
+
+/// Boot overrides for the runtime VM (§7.2.2). Unset fields use the
+/// installed runtime image.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BootConfig {
+    /// Kernel command line replacing the image's default. CrateBay still
+    /// appends the `cratebay_*` arguments it needs unless they are given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmdline: Option<String>,
+    /// Kernel image to boot instead of the image's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kernel: Option<PathBuf>,
+    /// Initrd to boot instead of the image's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initrd: Option<PathBuf>,
+}
+
+impl BootConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check that the kernel and initrd overrides exist.
+    pub fn validate(&self) -> Result<(), AppError> {
+        for (what, path) in [("Kernel", &self.kernel), ("Initrd", &self.initrd)] {
+            if let Some(path) = path {
+                if !path.is_file() {
+                    return Err(AppError::Runtime(format!(
+                        "{} override {} does not exist",
+                        what,
+                        path.display()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `image` with the kernel and initrd overrides applied.
+    pub fn apply(&self, mut image: crate::images::ImagePaths) -> crate::images::ImagePaths {
+        if let Some(kernel) = &self.kernel {
+            image.kernel_path = kernel.clone();
+        }
+        if let Some(initrd) = &self.initrd {
+            image.initrd_path = initrd.clone();
+        }
+        image
+    }
+}
+
+impl RuntimeConfig {
+    /// Load the persisted runtime configuration (defaults if absent or
+    /// invalid). Before the first save, the `default_vm_*` keys of
+    /// `config.toml` override the built-in sizes.
+    pub fn load() -> Self {
+        let path = common::runtime_config_path();
+        if !path.exists() {
+            return Self::with_user_defaults(&Config::load_or_default());
+        }
+        Self::load_from(&path)
+    }
+
+    /// Built-in defaults with the `default_vm_*` sizes of `config` applied.
+    pub fn with_user_defaults(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            cpu_cores: config.default_vm_cpus.unwrap_or(defaults.cpu_cores),
+            memory_mb: config.default_vm_memory_mb.unwrap_or(defaults.memory_mb),
+            disk_gb: config.default_vm_disk_gb.unwrap_or(defaults.disk_gb),
+            ..defaults
+        }
+    }
+
+    /// Load a runtime configuration from `path` (defaults if absent or invalid).
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(bytes) = std::fs::read(path) else {
+            return Self::default();
+        };
+        serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid runtime config {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// Persist the runtime configuration.
+    pub fn save(&self) -> Result<(), AppError> {
+        self.save_to(&common::runtime_config_path())
+    }
+
+    /// Persist the runtime configuration to `path`.
+    pub fn save_to(&self, path: &Path) -> Result<(), AppError> {
+        let bytes = serde_json::to_vec_pretty(self)?;
+        crate::storage::write_atomic(path, &bytes)?;
+        Ok(())
+    }
+
+    /// Return the VM's MAC address, generating and persisting one on first
+    /// use.
+    pub fn ensure_mac_address(&mut self) -> Result<String, AppError> {
+        self.ensure_mac_address_at(&common::runtime_config_path())
+    }
+
+    fn ensure_mac_address_at(&mut self, path: &Path) -> Result<String, AppError> {
+        if let Some(mac) = self.mac_address.as_ref().filter(|m| is_valid_mac(m)) {
+            return Ok(mac.clone());
+        }
+        let mac = generate_mac_address();
+        self.mac_address = Some(mac.clone());
+        self.save_to(path)?;
+        Ok(mac)
+    }
+
+    /// Share `host_path` with the VM under `tag`. The host path is stored
+    /// canonicalized; the whole list is re-validated.
+    pub fn add_shared_dir(&mut self, host_path: &Path, tag: &str) -> Result<SharedDir, AppError> {
+        let mut dirs = self.shared_dirs.clone();
+        dirs.push(SharedDir {
+            host_path: host_path.to_string_lossy().to_string(),
+            tag: tag.to_string(),
+        });
+        self.shared_dirs = validate_shared_dirs(&dirs)?;
+        Ok(self.shared_dirs[self.shared_dirs.len() - 1].clone())
+    }
+
+    /// Stop sharing the directory mounted under `tag`.
+    pub fn remove_shared_dir(&mut self, tag: &str) -> Result<SharedDir, AppError> {
+        let index = self
+            .shared_dirs
+            .iter()
+            .position(|d| d.tag == tag)
+            .ok_or_else(|| AppError::NotFound {
+                entity: "shared directory".into(),
+                id: tag.to_string(),
+            })?;
+        Ok(self.shared_dirs.remove(index))
+    }
+}
+
+/// Validate and normalize shared directories before they reach the
+/// hypervisor: host paths are canonicalized, tags must be valid VirtioFS
+/// tags and unique, and no host directory may contain another.
+pub fn validate_shared_dirs(dirs: &[SharedDir]) -> Result<Vec<SharedDir>, AppError> {
+    let mut normalized: Vec<(SharedDir, PathBuf)> = Vec::with_capacity(dirs.len());
+    for dir in dirs {
+        crate::validation::validate_mount_tag(&dir.tag)?;
+        let host = crate::validation::canonical_host_dir(Path::new(&dir.host_path))?;
+
+        if let Some((other, _)) = normalized.iter().find(|(d, _)| d.tag == dir.tag) {
+            return Err(AppError::Validation(format!(
+                "Mount tag '{}' is already used for {}",
+                dir.tag, other.host_path
+            )));
+        }
+        if let Some((other, _)) = normalized
+            .iter()
+            .find(|(_, p)| p.starts_with(&host) || host.starts_with(p))
+        {
+            return Err(AppError::Validation(format!(
+                "Shared directory {} overlaps {} (tag '{}')",
+                host.display(),
+                other.host_path,
+                other.tag
+            )));
         }
+
+        normalized.push((
+            SharedDir {
+                host_path: host.to_string_lossy().to_string(),
+                tag: dir.tag.clone(),
+            },
+            host,
+        ));
     }
+    Ok(normalized.into_iter().map(|(d, _)| d).collect())
+}
+
+/// Random locally administered unicast MAC (`x2:xx:xx:xx:xx:xx`).
+fn generate_mac_address() -> String {
+    let mut bytes = [0u8; 6];
+    bytes.copy_from_slice(&uuid::Uuid::new_v4().as_bytes()[..6]);
+    bytes[0] = (bytes[0] & 0xfc) | 0x02;
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn is_valid_mac(mac: &str) -> bool {
+    let parts: Vec<&str> = mac.split(':').collect();
+    parts.len() == 6
+        && parts
+            .iter()
+            .all(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_hexdigit()))
 }
 
 // ---------------------------------------------------------------------------
@@ -194,8 +430,65 @@ pub trait RuntimeManager: Send + Sync {
     /// Get the Docker socket path for bollard connection.
     fn docker_socket_path(&self) -> PathBuf;
 
+    /// Describe the host virtualization backend and whether it is usable.
+    fn hypervisor(&self) -> HypervisorInfo;
+
     /// Get current resource usage of the runtime VM.
     async fn resource_usage(&self) -> Result<ResourceUsage, AppError>;
+
+    /// Change the CPU/memory allocation of the runtime VM.
+    ///
+    /// The runtime must be stopped; the new allocation is persisted and
+    /// applied on next start. The allocation must fit the host (see
+    /// [`validate_vm_resources`](crate::validation::validate_vm_resources)),
+    /// and the `max_cpus` / `max_memory_mb` quotas from `config.toml` apply.
+    /// Returns the updated configuration.
+    async fn update_resources(
+        &self,
+        cpu_cores: Option<u32>,
+        memory_mb: Option<u64>,
+    ) -> Result<RuntimeConfig, AppError> {
+        common::ensure_runtime_stopped(&self.get_state().await?, "changing CPU/memory")?;
+
+        let mut config = RuntimeConfig::load();
+        if let Some(cpu_cores) = cpu_cores {
+            config.cpu_cores = cpu_cores;
+        }
+        if let Some(memory_mb) = memory_mb {
+            config.memory_mb = memory_mb;
+        }
+        crate::validation::validate_vm_resources(
+            config.cpu_cores,
+            config.memory_mb,
+            crate::validation::HostResources::detect(),
+        )?;
+        let quotas = Config::load()?;
+        quotas.check_quota(QuotaResource::Cpus, u64::from(config.cpu_cores))?;
+        quotas.check_quota(QuotaResource::MemoryMb, config.memory_mb)?;
+        config.save()?;
+        Ok(config)
+    }
+
+    /// Grow the runtime VM disk to `new_gb` gigabytes.
+    ///
+    /// The runtime must be stopped. Shrinking is rejected, as is growing past
+    /// the `max_disk_gb` quota. The guest grows its filesystem to the new
+    /// size on next boot.
+    async fn resize_disk(&self, new_gb: u32) -> Result<(), AppError> {
+        let _ = new_gb;
+        Err(AppError::Runtime(
+            "Disk resize is not supported by this runtime".to_string(),
+        ))
+    }
+
+    /// Inflate or deflate the memory balloon of the running VM so the guest
+    /// keeps `target_mb` of its allocation (see [`balloon`]).
+    async fn set_balloon_target(&self, target_mb: u64) -> Result<(), AppError> {
+        let _ = target_mb;
+        Err(AppError::Runtime(
+            "Memory ballooning is not supported by this runtime".to_string(),
+        ))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -289,6 +582,79 @@ pub fn start_health_monitor(
 mod tests {
     use super::*;
 
+    #[test]
+    fn runtime_config_save_and_load_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runtime-config.json");
+        let config = RuntimeConfig {
+            cpu_cores: 6,
+            memory_mb: 8192,
+            ..RuntimeConfig::default()
+        };
+        config.save_to(&path).unwrap();
+
+        let loaded = RuntimeConfig::load_from(&path);
+        assert_eq!(loaded.cpu_cores, 6);
+        assert_eq!(loaded.memory_mb, 8192);
+        assert_eq!(loaded.disk_gb, 20);
+    }
+
+    #[test]
+    fn runtime_config_load_falls_back_to_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = RuntimeConfig::load_from(&dir.path().join("missing.json"));
+        assert_eq!(missing.cpu_cores, 2);
+
+        let partial = dir.path().join("partial.json");
+        std::fs::write(&partial, r#"{"cpu_cores": 4}"#).unwrap();
+        let loaded = RuntimeConfig::load_from(&partial);
+        assert_eq!(loaded.cpu_cores, 4);
+        assert_eq!(loaded.memory_mb, 2048);
+
+        let invalid = dir.path().join("invalid.json");
+        std::fs::write(&invalid, "not json").unwrap();
+        assert_eq!(RuntimeConfig::load_from(&invalid).memory_mb, 2048);
+    }
+
+    #[test]
+    fn user_defaults_seed_new_runtime_config() {
+        let mut config = Config::default();
+        config.set("default_vm_cpus", "6").unwrap();
+        config.set("default_vm_disk_gb", "64").unwrap();
+        let seeded = RuntimeConfig::with_user_defaults(&config);
+        assert_eq!(seeded.cpu_cores, 6);
+        assert_eq!(seeded.memory_mb, 2048);
+        assert_eq!(seeded.disk_gb, 64);
+    }
+
+    #[test]
+    fn mac_address_is_generated_once_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runtime-config.json");
+        let mut config = RuntimeConfig::default();
+
+        let mac = config.ensure_mac_address_at(&path).unwrap();
+        assert!(is_valid_mac(&mac));
+        let first = u8::from_str_radix(&mac[..2], 16).unwrap();
+        assert_eq!(first & 0x03, 0x02, "locally administered unicast");
+
+        let mut reloaded = RuntimeConfig::load_from(&path);
+        assert_eq!(reloaded.ensure_mac_address_at(&path).unwrap(), mac);
+    }
+
+    #[test]
+    fn invalid_mac_address_is_regenerated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("runtime-config.json");
+        let mut config = RuntimeConfig {
+            mac_address: Some("not-a-mac".into()),
+            ..RuntimeConfig::default()
+        };
+        let mac = config.ensure_mac_address_at(&path).unwrap();
+        assert_ne!(mac, "not-a-mac");
+        assert!(is_valid_mac(&mac));
+    }
+
     #[test]
     fn runtime_config_default_values() {
         let config = RuntimeConfig::default();
@@ -452,6 +818,40 @@ mod tests {
         assert_eq!(deserialized.tag, "workspace");
     }
 
+    #[test]
+    fn add_shared_dir_canonicalizes_and_rejects_conflicts() {
+        let root = tempfile::tempdir().unwrap();
+        let code = root.path().join("code");
+        std::fs::create_dir_all(code.join("nested")).unwrap();
+        let other = root.path().join("other");
+        std::fs::create_dir(&other).unwrap();
+
+        let mut config = RuntimeConfig::default();
+        let added = config
+            .add_shared_dir(&code.join("nested/.."), "code")
+            .unwrap();
+        assert_eq!(
+            Path::new(&added.host_path),
+            code.canonicalize().unwrap().as_path()
+        );
+
+        // Duplicate tag, nested and enclosing host paths are rejected.
+        assert!(config.add_shared_dir(&other, "code").is_err());
+        assert!(config
+            .add_shared_dir(&code.join("nested"), "nested")
+            .is_err());
+        assert!(config.add_shared_dir(root.path(), "root").is_err());
+        assert!(config.add_shared_dir(&other, "bad tag").is_err());
+        assert_eq!(config.shared_dirs.len(), 1);
+
+        config.add_shared_dir(&other, "other").unwrap();
+        assert_eq!(config.remove_shared_dir("code").unwrap().tag, "code");
+        assert!(matches!(
+            config.remove_shared_dir("code"),
+            Err(AppError::NotFound { .. })
+        ));
+    }
+
     #[test]
     fn protocol_deserializes() {
         let tcp: Protocol = serde_json::from_str("\"tcp\"").unwrap();
@@ -472,6 +872,13 @@ mod tests {
                 host_path: "/home/user/code".into(),
                 tag: "code".into(),
             }],
+            mac_address: None,
+            gpu: true,
+            boot: BootConfig {
+                cmdline: Some("console=hvc0 loglevel=7".into()),
+                kernel: Some("/tmp/vmlinuz".into()),
+                initrd: None,
+            },
         };
         let json = serde_json::to_string(&config).unwrap();
         let deserialized: RuntimeConfig = serde_json::from_str(&json).unwrap();
@@ -479,10 +886,32 @@ mod tests {
         assert_eq!(deserialized.memory_mb, 4096);
         assert_eq!(deserialized.disk_gb, 50);
         assert!(!deserialized.auto_start);
+        assert!(deserialized.gpu);
+        assert_eq!(deserialized.boot, config.boot);
         assert_eq!(deserialized.shared_dirs.len(), 1);
         assert_eq!(deserialized.shared_dirs[0].tag, "code");
     }
 
+    #[test]
+    fn boot_overrides_are_omitted_when_unset() {
+        let json = serde_json::to_string(&RuntimeConfig::default()).unwrap();
+        assert!(!json.contains("boot"), "{}", json);
+
+        let boot = BootConfig {
+            kernel: Some("/missing/vmlinuz".into()),
+            ..Default::default()
+        };
+        assert!(boot.validate().is_err());
+        let image = crate::images::ImagePaths {
+            kernel_path: "/image/vmlinuz".into(),
+            initrd_path: "/image/initramfs".into(),
+            rootfs_path: "/image/rootfs".into(),
+        };
+        let paths = boot.apply(image);
+        assert_eq!(paths.kernel_path, PathBuf::from("/missing/vmlinuz"));
+        assert_eq!(paths.initrd_path, PathBuf::from("/image/initramfs"));
+    }
+
     #[test]
     fn create_runtime_manager_returns_valid_manager() {
         let manager = create_runtime_manager();
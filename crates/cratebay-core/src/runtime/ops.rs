@@ -0,0 +1,205 @@
+//! Runtime VM operation locking.
+//!
+//! Provision, start, stop, resource and disk changes on a runtime VM hold
+//! `<vm>.op.lock` next to the Docker socket for their whole duration, so
+//! the GUI and CLI cannot interleave them (e.g. a stop during start's
+//! Docker wait). The holder records what it is doing in `<vm>.op.json`.
+//!
+//! A caller asking for the operation already running — or start and
+//! provision, which overlap — queues behind it and then usually finds
+//! nothing left to do; callers that must not block pass a zero wait. Any
+//! other combination fails fast with [`AppError::Aborted`].
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{is_lock_contended, try_lock_file, FileLock};
+use crate::error::AppError;
+
+/// How long a queued operation waits by default; provisioning downloads
+/// the runtime image, so this matches the engine lock.
+pub const DEFAULT_OP_WAIT: Duration = Duration::from_secs(10 * 60);
+
+/// An operation that changes the runtime VM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeOp {
+    Provision,
+    Start,
+    Stop,
+    UpdateResources,
+    ResizeDisk,
+}
+
+impl RuntimeOp {
+    /// Whether `self` waits for a running `other` instead of failing.
+    pub fn queues_behind(self, other: RuntimeOp) -> bool {
+        use RuntimeOp::*;
+        self == other || matches!((self, other), (Start, Provision) | (Provision, Start))
+    }
+}
+
+impl std::fmt::Display for RuntimeOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RuntimeOp::Provision => "provision",
+            RuntimeOp::Start => "start",
+            RuntimeOp::Stop => "stop",
+            RuntimeOp::UpdateResources => "resource update",
+            RuntimeOp::ResizeDisk => "disk resize",
+        })
+    }
+}
+
+/// The operation holding a VM's lock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpRecord {
+    pub op: RuntimeOp,
+    pub pid: u32,
+    pub started_at: String,
+}
+
+/// Held for the duration of an operation; releases the lock on drop.
+pub struct OpGuard {
+    _lock: FileLock,
+    record_path: PathBuf,
+}
+
+impl Drop for OpGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.record_path);
+    }
+}
+
+/// Begin `op` on the runtime VM, waiting up to `wait` behind an operation
+/// it queues behind.
+pub async fn begin(op: RuntimeOp, wait: Duration) -> Result<OpGuard, AppError> {
+    let dir = crate::runtime::common::host_docker_socket_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| crate::storage::data_dir().join("runtime"));
+    begin_in(&dir, crate::runtime::common::runtime_vm_name(), op, wait).await
+}
+
+async fn begin_in(
+    dir: &Path,
+    vm: &str,
+    op: RuntimeOp,
+    wait: Duration,
+) -> Result<OpGuard, AppError> {
+    std::fs::create_dir_all(dir)?;
+    let lock_path = dir.join(format!("{}.op.lock", vm));
+    let record_path = dir.join(format!("{}.op.json", vm));
+
+    let deadline = Instant::now() + wait;
+    loop {
+        match try_lock_file(&lock_path) {
+            Ok(lock) => {
+                let record = OpRecord {
+                    op,
+                    pid: std::process::id(),
+                    started_at: chrono::Utc::now().to_rfc3339(),
+                };
+                std::fs::write(&record_path, serde_json::to_vec(&record)?)?;
+                return Ok(OpGuard {
+                    _lock: lock,
+                    record_path,
+                });
+            }
+            Err(err) if is_lock_contended(&err) => {
+                // The record may be missing for a moment after the holder
+                // locks; treat that like a queueable operation.
+                let running = read_record(&record_path);
+                if let Some(running) = &running {
+                    if !op.queues_behind(running.op) {
+                        return Err(AppError::Aborted(format!(
+                            "Cannot {} runtime '{}': {} in progress (pid {}, since {})",
+                            op, vm, running.op, running.pid, running.started_at
+                        )));
+                    }
+                }
+                if Instant::now() >= deadline {
+                    let running = running.map_or_else(
+                        || "another operation".to_string(),
+                        |r| format!("{} (pid {})", r.op, r.pid),
+                    );
+                    let waited = if wait.is_zero() {
+                        String::new()
+                    } else {
+                        format!(" after waiting {:?}", wait)
+                    };
+                    return Err(AppError::Aborted(format!(
+                        "Cannot {} runtime '{}': {} still in progress{}",
+                        op, vm, running, waited
+                    )));
+                }
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn read_record(path: &Path) -> Option<OpRecord> {
+    serde_json::from_slice(&std::fs::read(path).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_and_provision_queue_behind_each_other() {
+        use RuntimeOp::*;
+        assert!(Start.queues_behind(Start));
+        assert!(Start.queues_behind(Provision));
+        assert!(Provision.queues_behind(Start));
+        assert!(!Stop.queues_behind(Start));
+        assert!(!Start.queues_behind(Stop));
+        assert!(!ResizeDisk.queues_behind(UpdateResources));
+    }
+
+    #[tokio::test]
+    async fn conflicting_ops_abort_and_same_ops_wait() {
+        let dir = tempfile::tempdir().unwrap();
+        let start = begin_in(dir.path(), "vm", RuntimeOp::Start, Duration::ZERO)
+            .await
+            .unwrap();
+        let record = read_record(&dir.path().join("vm.op.json")).unwrap();
+        assert_eq!(record.op, RuntimeOp::Start);
+
+        let err = begin_in(dir.path(), "vm", RuntimeOp::Stop, DEFAULT_OP_WAIT)
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, AppError::Aborted(_)));
+        assert!(err.to_string().contains("start in progress"));
+
+        let err = begin_in(
+            dir.path(),
+            "vm",
+            RuntimeOp::Start,
+            Duration::from_millis(300),
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("after waiting"));
+
+        // Other VMs are not affected.
+        drop(
+            begin_in(dir.path(), "other", RuntimeOp::Stop, Duration::ZERO)
+                .await
+                .unwrap(),
+        );
+
+        drop(start);
+        assert!(read_record(&dir.path().join("vm.op.json")).is_none());
+        begin_in(dir.path(), "vm", RuntimeOp::Stop, Duration::ZERO)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,126 @@
+//! Rosetta for Linux translation cache warmup.
+//!
+//! On Apple Silicon the runtime VM runs x86_64 binaries through Rosetta,
+//! which translates each binary the first time it executes. The guest keeps
+//! translations in a persistent cache (`rosettad` on the runtime disk), so
+//! running the common binaries of an image once up front removes most of the
+//! first-run translation cost.
+
+use std::time::{Duration, Instant};
+
+use bollard::container::Config;
+use bollard::Docker;
+
+use crate::container;
+use crate::error::AppError;
+use crate::models::RosettaWarmResult;
+
+/// Upper bound for warming one image.
+const WARM_TIMEOUT: Duration = Duration::from_secs(120);
+
+const AMD64_PLATFORM: &str = "linux/amd64";
+
+/// Executed in each image: touches the shell, coreutils and the dynamic
+/// loader so their translations land in the cache. Failures are ignored;
+/// minimal images lack some of these tools.
+const WARM_SCRIPT: &str = "\
+for c in 'ls /' 'cat /etc/os-release' 'env' 'id' 'uname -a' 'ps' 'grep -q x /etc/hostname' \
+'sed -n 1p /etc/hostname' 'sort /etc/hostname' 'head -n1 /etc/hostname' 'ldd --version'; do \
+$c >/dev/null 2>&1; done; true";
+
+/// Whether the runtime has the Rosetta binfmt handler registered.
+pub async fn is_enabled(docker: &Docker) -> Result<bool, AppError> {
+    let cmd = vec![
+        "/bin/sh".to_string(),
+        "-c".to_string(),
+        "test -e /proc/sys/fs/binfmt_misc/rosetta".to_string(),
+    ];
+    let result = container::runtime_exec(docker, cmd, Some(Duration::from_secs(30))).await?;
+    Ok(result.exit_code == 0)
+}
+
+/// Pre-translate common binaries of `images`, or of every local amd64
+/// image when `images` is empty.
+pub async fn warm(docker: &Docker, images: &[String]) -> Result<Vec<RosettaWarmResult>, AppError> {
+    if !is_enabled(docker).await? {
+        return Err(AppError::RosettaUnavailable(
+            "not enabled in the runtime (start it with CRATEBAY_RUNTIME_ROSETTA=1 on Apple Silicon)"
+                .to_string(),
+        ));
+    }
+
+    let targets = if images.is_empty() {
+        local_amd64_images(docker).await?
+    } else {
+        images.to_vec()
+    };
+
+    let mut results = Vec::with_capacity(targets.len());
+    for image in targets {
+        let started = Instant::now();
+        let error = match warm_image(docker, &image).await {
+            Ok(()) => None,
+            Err(e) => Some(e.to_string()),
+        };
+        results.push(RosettaWarmResult {
+            image,
+            ok: error.is_none(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            error,
+        });
+    }
+    Ok(results)
+}
+
+async fn warm_image(docker: &Docker, image: &str) -> Result<(), AppError> {
+    let config = Config {
+        image: Some(image.to_string()),
+        entrypoint: Some(vec![
+            "/bin/sh".to_string(),
+            "-c".to_string(),
+            WARM_SCRIPT.to_string(),
+        ]),
+        cmd: Some(Vec::new()),
+        ..Default::default()
+    };
+    let result = container::run_helper(
+        docker,
+        "rosetta-warm",
+        config,
+        Some(AMD64_PLATFORM),
+        WARM_TIMEOUT,
+    )
+    .await?;
+    if result.exit_code != 0 {
+        return Err(AppError::Runtime(format!(
+            "Warmup exited with {}: {}",
+            result.exit_code,
+            result.stderr.trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Tagged local images built for `amd64`.
+async fn local_amd64_images(docker: &Docker) -> Result<Vec<String>, AppError> {
+    let mut images = Vec::new();
+    for image in container::image_list(docker).await? {
+        let Some(tag) = image
+            .repo_tags
+            .iter()
+            .find(|t| t.as_str() != "<none>:<none>")
+        else {
+            continue;
+        };
+        match container::image_inspect(docker, &image.id).await {
+            Ok(info) if is_amd64(&info.architecture) => images.push(tag.clone()),
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Skipping {} for Rosetta warmup: {}", tag, e),
+        }
+    }
+    Ok(images)
+}
+
+fn is_amd64(architecture: &str) -> bool {
+    matches!(architecture, "amd64" | "x86_64")
+}
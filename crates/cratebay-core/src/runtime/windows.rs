@@ -26,7 +26,9 @@ use crate::error::AppError;
 use crate::models::ResourceUsage;
 
 use super::common;
-use super::{HealthStatus, ProvisionProgress, RuntimeConfig, RuntimeManager, RuntimeState};
+use super::{
+    HealthStatus, HypervisorInfo, ProvisionProgress, RuntimeConfig, RuntimeManager, RuntimeState,
+};
 
 // ---------------------------------------------------------------------------
 // Constants
@@ -35,9 +37,6 @@ use super::{HealthStatus, ProvisionProgress, RuntimeConfig, RuntimeManager, Runt
 /// Default WSL2 Docker TCP port (inside the guest, bound to 0.0.0.0).
 const DEFAULT_WSL_DOCKER_PORT: u16 = 2375;
 
-/// Default distro name for the CrateBay WSL2 runtime.
-const DEFAULT_DISTRO_NAME: &str = "cratebay-runtime";
-
 /// WSL2 asset subdirectory name inside the bundled runtime assets.
 const WSL_ASSETS_SUBDIR: &str = "runtime-wsl";
 
@@ -86,10 +85,7 @@ impl WindowsRuntime {
     /// Create a new Windows runtime manager with default configuration.
     pub fn new() -> Self {
         let data_dir = crate::storage::data_dir().join("runtime");
-        let distro_name = std::env::var("CRATEBAY_RUNTIME_VM_NAME")
-            .ok()
-            .filter(|v| !v.trim().is_empty())
-            .unwrap_or_else(|| DEFAULT_DISTRO_NAME.to_string());
+        let distro_name = common::runtime_vm_name().to_string();
 
         Self {
             config: RuntimeConfig::default(),
@@ -336,6 +332,27 @@ impl RuntimeManager for WindowsRuntime {
             )));
         }
 
+        // 1b) Bind-mount shared directories from the WSL drive automounts
+        let resources = RuntimeConfig::load();
+        if resources.gpu {
+            tracing::warn!("Ignoring gpu: WSL2 already exposes the host GPU through /dev/dxg");
+        }
+        if !resources.boot.is_empty() {
+            tracing::warn!(
+                "Ignoring boot overrides: WSL2 boots its own kernel (set `kernel=` in .wslconfig)"
+            );
+        }
+        let shared_dirs = super::validate_shared_dirs(&resources.shared_dirs)?;
+        if !shared_dirs.is_empty() {
+            let distro_c = distro.clone();
+            tokio::task::spawn_blocking(move || wsl_mount_shared_dirs(&distro_c, &shared_dirs))
+                .await
+                .map_err(|e| AppError::Runtime(format!("Join error: {}", e)))?
+                .map_err(|e| {
+                    AppError::SharedDirectory(format!("Failed to mount shared directories: {}", e))
+                })?;
+        }
+
         // 2) Start dockerd (3-layer fallback: OpenRC → detached → compatibility)
         let distro_c = distro.clone();
         tokio::task::spawn_blocking(move || wsl_start_dockerd(&distro_c, port))
@@ -530,6 +547,16 @@ impl RuntimeManager for WindowsRuntime {
         PathBuf::from(r"\\.\pipe\cratebay-docker")
     }
 
+    fn hypervisor(&self) -> HypervisorInfo {
+        HypervisorInfo {
+            backend: "wsl2".to_string(),
+            available: wsl2_available(),
+            rosetta_available: None,
+            // WSL2 exposes the host GPU to every distro through /dev/dxg.
+            gpu_available: None,
+        }
+    }
+
     async fn resource_usage(&self) -> Result<ResourceUsage, AppError> {
         let distro = self.distro_name.clone();
         let config_mem = self.config.memory_mb;
@@ -553,6 +580,18 @@ impl RuntimeManager for WindowsRuntime {
         .await
         .map_err(|e| AppError::Runtime(format!("Join error: {}", e)))?
     }
+
+    /// WSL2 resources are governed by the host `.wslconfig`, not per distro.
+    async fn update_resources(
+        &self,
+        _cpu_cores: Option<u32>,
+        _memory_mb: Option<u64>,
+    ) -> Result<RuntimeConfig, AppError> {
+        Err(AppError::Runtime(
+            "CPU/memory for the WSL2 runtime are configured in %UserProfile%\\.wslconfig"
+                .to_string(),
+        ))
+    }
 }
 
 // ===========================================================================
@@ -565,7 +604,7 @@ fn wsl_docker_port() -> u16 {
         .ok()
         .and_then(|v| v.parse::<u16>().ok())
         .filter(|p| *p > 0)
-        .unwrap_or(DEFAULT_WSL_DOCKER_PORT)
+        .unwrap_or_else(|| DEFAULT_WSL_DOCKER_PORT + crate::storage::profile_port_offset())
 }
 
 /// Check if WSL2 is available by running `wsl --status`.
@@ -688,6 +727,38 @@ fn wsl_exec_with_timeout(
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+// ---------------------------------------------------------------------------
+// Shared directories
+// ---------------------------------------------------------------------------
+
+/// Quote `value` for a POSIX shell.
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Bind-mount each shared directory's drvfs automount path (`/mnt/c/...`)
+/// onto its guest mount point. WSL already serves the drives over 9P, so no
+/// extra Plan 9 or SMB server is needed. Already-mounted points are skipped
+/// so restarts of a running distro are idempotent.
+fn wsl_mount_shared_dirs(distro: &str, dirs: &[super::SharedDir]) -> Result<(), String> {
+    let mut script = String::from("set -e");
+    for dir in dirs {
+        let source = common::wsl_automount_path(&dir.host_path).ok_or_else(|| {
+            format!(
+                "Shared directory '{}' is not on a local drive: {}",
+                dir.tag, dir.host_path
+            )
+        })?;
+        let target = sh_quote(&common::shared_dir_guest_path(&dir.tag));
+        script.push_str(&format!(
+            "; mkdir -p {t}; mountpoint -q {t} || mount --bind {s} {t}",
+            t = target,
+            s = sh_quote(&source)
+        ));
+    }
+    wsl_exec(distro, &script).map(|_| ())
+}
+
 // ---------------------------------------------------------------------------
 // WSL distro management
 // ---------------------------------------------------------------------------
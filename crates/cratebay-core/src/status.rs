@@ -0,0 +1,152 @@
+//! Unified system status snapshot.
+//!
+//! One structured model shared by `cratebay system status` and the GUI
+//! `system_status` command, so scripts and the UI read the same fields.
+
+use std::time::Duration;
+
+use bollard::Docker;
+use serde::{Deserialize, Serialize};
+
+use crate::docker::{self, EngineSelection};
+use crate::models::DockerStatus;
+use crate::runtime::{HypervisorInfo, RuntimeManager, RuntimeState};
+use crate::storage;
+
+/// Upper bound on runtime state detection for a status snapshot.
+const STATUS_RUNTIME_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Machine-readable status of CrateBay on this host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemStatus {
+    /// CrateBay version.
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    /// The engine Docker commands go to, reported even when unreachable.
+    pub engine: EngineSelection,
+    pub docker: DockerStatus,
+    pub runtime_state: RuntimeState,
+    pub hypervisor: HypervisorInfo,
+    pub config_dir: String,
+    pub data_dir: String,
+    pub log_dir: String,
+}
+
+/// Build a [`DockerStatus`] for an optional client.
+///
+/// `source` and `socket_path` describe where the client came from and are
+/// only reported when Docker answers.
+pub async fn docker_status(
+    client: Option<&Docker>,
+    source: &str,
+    socket_path: Option<String>,
+) -> DockerStatus {
+    let disconnected = DockerStatus {
+        connected: false,
+        version: None,
+        api_version: None,
+        os: None,
+        arch: None,
+        source: "none".to_string(),
+        socket_path: None,
+    };
+
+    let Some(client) = client else {
+        return disconnected;
+    };
+    if !docker::is_available(client).await {
+        return disconnected;
+    }
+
+    let version = docker::version(client).await.ok();
+    DockerStatus {
+        connected: true,
+        version: version.as_ref().and_then(|v| v.version.clone()),
+        api_version: version.as_ref().and_then(|v| v.api_version.clone()),
+        os: version.as_ref().and_then(|v| v.os.clone()),
+        arch: version.as_ref().and_then(|v| v.arch.clone()),
+        source: source.to_string(),
+        socket_path,
+    }
+}
+
+/// Collect a status snapshot. Never starts the runtime.
+pub async fn collect(runtime: &dyn RuntimeManager, docker: DockerStatus) -> SystemStatus {
+    let runtime_state =
+        match tokio::time::timeout(STATUS_RUNTIME_TIMEOUT, runtime.get_state()).await {
+            Ok(Ok(state)) => state,
+            Ok(Err(e)) => RuntimeState::Error(e.to_string()),
+            Err(_) => RuntimeState::Error(format!(
+                "Runtime state detection timed out after {:?}",
+                STATUS_RUNTIME_TIMEOUT
+            )),
+        };
+
+    SystemStatus {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        engine: docker::selected_engine(),
+        docker,
+        runtime_state,
+        hypervisor: runtime.hypervisor(),
+        config_dir: storage::config_dir().to_string_lossy().to_string(),
+        data_dir: storage::data_dir().to_string_lossy().to_string(),
+        log_dir: storage::log_dir().to_string_lossy().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::EngineMode;
+
+    #[tokio::test]
+    async fn docker_status_without_client_is_disconnected() {
+        let status = docker_status(None, "built-in", Some("/tmp/docker.sock".into())).await;
+        assert!(!status.connected);
+        assert_eq!(status.source, "none");
+        assert!(status.socket_path.is_none());
+    }
+
+    #[test]
+    fn system_status_serializes_camel_case() {
+        let status = SystemStatus {
+            version: "1.0.0".to_string(),
+            os: "linux".to_string(),
+            arch: "x86_64".to_string(),
+            engine: EngineSelection {
+                mode: EngineMode::DockerHost,
+                name: None,
+                endpoint: Some("tcp://10.0.0.5:2375".to_string()),
+            },
+            docker: DockerStatus {
+                connected: false,
+                version: None,
+                api_version: None,
+                os: None,
+                arch: None,
+                source: "none".to_string(),
+                socket_path: None,
+            },
+            runtime_state: RuntimeState::Stopped,
+            hypervisor: HypervisorInfo {
+                backend: "kvm".to_string(),
+                available: true,
+                rosetta_available: None,
+                gpu_available: Some(false),
+            },
+            config_dir: "/c".to_string(),
+            data_dir: "/d".to_string(),
+            log_dir: "/l".to_string(),
+        };
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["runtimeState"], "Stopped");
+        assert_eq!(json["hypervisor"]["backend"], "kvm");
+        assert_eq!(json["docker"]["connected"], false);
+        assert_eq!(json["engine"]["mode"], "docker-host");
+        assert!(json.get("configDir").is_some());
+    }
+}
@@ -9,8 +9,8 @@ use std::path::{Path, PathBuf};
 
 use crate::error::AppError;
 use crate::models::{
-    ApiFormat, ChatMessage, ConversationDetail, ConversationSummary, LlmModelInfo, LlmProvider,
-    McpServerConfig, McpServerStatus,
+    ApiFormat, AuditLogEntry, AuditLogQuery, ChatMessage, ConversationDetail, ConversationSummary,
+    LlmModelInfo, LlmProvider, McpServerConfig, McpServerStatus,
 };
 
 // ─── Migration System ───────────────────────────────────────────────
@@ -29,10 +29,10 @@ pub const MIGRATIONS: &[Migration] = &[Migration {
     sql: include_str!("../migrations/001_initial_schema.sql"),
 }];
 
-/// Get the default database path: `~/.cratebay/cratebay.db`
+/// Get the default database path: `~/.cratebay/cratebay.db`, under
+/// `profiles/<name>` for a non-default [`profile`].
 pub fn default_db_path() -> Result<PathBuf, AppError> {
-    let home = home_dir()?;
-    let db_dir = home.join(".cratebay");
+    let db_dir = cratebay_home()?;
     std::fs::create_dir_all(&db_dir)?;
     Ok(db_dir.join("cratebay.db"))
 }
@@ -794,52 +794,70 @@ pub fn list_audit_logs(
     target: Option<&str>,
     limit: u32,
 ) -> Result<Vec<serde_json::Value>, AppError> {
-    let (sql, params_vec): (String, Vec<Box<dyn rusqlite::types::ToSql>>) = match (action, target) {
-        (Some(a), Some(t)) => (
-            "SELECT id, timestamp, action, target, details, user
-                 FROM audit_log WHERE action = ?1 AND target = ?2
-                 ORDER BY timestamp DESC LIMIT ?3"
-                .to_string(),
-            vec![
-                Box::new(a.to_string()),
-                Box::new(t.to_string()),
-                Box::new(limit),
-            ],
-        ),
-        (Some(a), None) => (
-            "SELECT id, timestamp, action, target, details, user
-                 FROM audit_log WHERE action = ?1
-                 ORDER BY timestamp DESC LIMIT ?2"
-                .to_string(),
-            vec![Box::new(a.to_string()), Box::new(limit)],
-        ),
-        (None, Some(t)) => (
-            "SELECT id, timestamp, action, target, details, user
-                 FROM audit_log WHERE target = ?1
-                 ORDER BY timestamp DESC LIMIT ?2"
-                .to_string(),
-            vec![Box::new(t.to_string()), Box::new(limit)],
-        ),
-        (None, None) => (
-            "SELECT id, timestamp, action, target, details, user
-                 FROM audit_log ORDER BY timestamp DESC LIMIT ?1"
-                .to_string(),
-            vec![Box::new(limit)],
-        ),
+    let query = AuditLogQuery {
+        action: action.map(str::to_string),
+        target: target.map(str::to_string),
+        since: None,
+        limit,
     };
+    query_audit_logs(conn, &query)?
+        .into_iter()
+        .map(|entry| {
+            Ok(serde_json::json!({
+                "id": entry.id,
+                "timestamp": entry.timestamp,
+                "action": entry.action,
+                "target": entry.target,
+                "details": entry.details,
+                "user": entry.user,
+            }))
+        })
+        .collect()
+}
+
+/// Query audit logs, newest first.
+pub fn query_audit_logs(
+    conn: &Connection,
+    query: &AuditLogQuery,
+) -> Result<Vec<AuditLogEntry>, AppError> {
+    let mut clauses = Vec::new();
+    let mut values: Vec<&dyn rusqlite::types::ToSql> = Vec::new();
+    if let Some(action) = query.action.as_ref() {
+        values.push(action);
+        clauses.push(format!("action = ?{}", values.len()));
+    }
+    if let Some(target) = query.target.as_ref() {
+        values.push(target);
+        clauses.push(format!("target = ?{}", values.len()));
+    }
+    if let Some(since) = query.since.as_ref() {
+        values.push(since);
+        clauses.push(format!("timestamp >= ?{}", values.len()));
+    }
+    values.push(&query.limit);
+
+    let where_clause = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {} ", clauses.join(" AND "))
+    };
+    let sql = format!(
+        "SELECT id, timestamp, action, target, details, user
+             FROM audit_log {}ORDER BY timestamp DESC LIMIT ?{}",
+        where_clause,
+        values.len()
+    );
 
-    let params_refs: Vec<&dyn rusqlite::types::ToSql> =
-        params_vec.iter().map(|v| v.as_ref()).collect();
     let mut stmt = conn.prepare(&sql)?;
-    let rows = stmt.query_map(params_refs.as_slice(), |row| {
-        Ok(serde_json::json!({
-            "id": row.get::<_, String>(0)?,
-            "timestamp": row.get::<_, String>(1)?,
-            "action": row.get::<_, String>(2)?,
-            "target": row.get::<_, String>(3)?,
-            "details": row.get::<_, Option<String>>(4)?,
-            "user": row.get::<_, String>(5)?,
-        }))
+    let rows = stmt.query_map(values.as_slice(), |row| {
+        Ok(AuditLogEntry {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            action: row.get(2)?,
+            target: row.get(3)?,
+            details: row.get(4)?,
+            user: row.get(5)?,
+        })
     })?;
 
     let mut results = Vec::new();
@@ -855,9 +873,85 @@ pub fn list_audit_logs(
 // and logging subsystems.  These mirror the helpers from the v1 `store`
 // module and are the canonical way to locate CrateBay data on disk.
 
+/// Environment variable naming the active profile (set by `--profile`).
+pub const PROFILE_ENV: &str = "CRATEBAY_PROFILE";
+
+/// The profile that uses the unscoped directories.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Longest accepted profile name.
+const PROFILE_NAME_MAX_LEN: usize = 32;
+
+/// Check a profile name: 1-32 ASCII letters, digits, `-` or `_`.
+pub fn validate_profile(name: &str) -> Result<(), AppError> {
+    if name.is_empty()
+        || name.len() > PROFILE_NAME_MAX_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(AppError::Validation(format!(
+            "Invalid profile name '{}': use up to {} letters, digits, '-' or '_'",
+            name, PROFILE_NAME_MAX_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// The active profile from `CRATEBAY_PROFILE`; `None` for the default one.
+///
+/// A profile scopes the config, data and log directories, the database,
+/// the runtime VM name, its sockets and its default ports, so separate
+/// profiles never share state. An invalid name is ignored with a warning;
+/// entry points validate `--profile` before setting it.
+pub fn profile() -> Option<String> {
+    let name = std::env::var(PROFILE_ENV).ok()?;
+    let name = name.trim();
+    if name.is_empty() || name == DEFAULT_PROFILE {
+        return None;
+    }
+    match validate_profile(name) {
+        Ok(()) => Some(name.to_string()),
+        Err(e) => {
+            tracing::warn!("Ignoring {}: {}", PROFILE_ENV, e);
+            None
+        }
+    }
+}
+
+/// Offset added to default runtime ports so profiles do not collide:
+/// 0 for the default profile, else 1..=1000.
+pub fn profile_port_offset() -> u16 {
+    profile().map_or(0, |name| (profile_hash(&name) % 1000) as u16 + 1)
+}
+
+fn profile_hash(name: &str) -> u32 {
+    name.bytes().fold(0_u32, |acc, byte| {
+        acc.wrapping_mul(131).wrapping_add(byte as u32)
+    })
+}
+
+/// `base`, or `base/profiles/<name>` for a non-default profile.
+fn profile_scoped(base: PathBuf, profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) => base.join("profiles").join(name),
+        None => base,
+    }
+}
+
+/// `~/.cratebay`, scoped to the active [`profile`]. Holds the database,
+/// the GUI log and the default runtime socket.
+pub fn cratebay_home() -> Result<PathBuf, AppError> {
+    Ok(profile_scoped(
+        home_dir()?.join(".cratebay"),
+        profile().as_deref(),
+    ))
+}
+
 /// CrateBay configuration directory.
 ///
-/// Override with `CRATEBAY_CONFIG_DIR`.  Platform defaults:
+/// Override with `CRATEBAY_CONFIG_DIR`.  Platform defaults, under
+/// `profiles/<name>` for a non-default [`profile`]:
 /// - macOS:   `~/Library/Application Support/com.cratebay.app`
 /// - Linux:   `$XDG_CONFIG_HOME/cratebay` or `~/.config/cratebay`
 /// - Windows: `%APPDATA%\cratebay`
@@ -865,7 +959,10 @@ pub fn config_dir() -> PathBuf {
     if let Ok(dir) = std::env::var("CRATEBAY_CONFIG_DIR") {
         return PathBuf::from(dir);
     }
+    profile_scoped(base_config_dir(), profile().as_deref())
+}
 
+fn base_config_dir() -> PathBuf {
     #[cfg(target_os = "macos")]
     {
         if let Ok(home) = std::env::var("HOME") {
@@ -898,7 +995,8 @@ pub fn config_dir() -> PathBuf {
 
 /// CrateBay persistent data directory.
 ///
-/// Override with `CRATEBAY_DATA_DIR`.  Platform defaults:
+/// Override with `CRATEBAY_DATA_DIR`.  Platform defaults, under
+/// `profiles/<name>` for a non-default [`profile`]:
 /// - Linux: `$XDG_DATA_HOME/cratebay` or `~/.local/share/cratebay`
 /// - macOS / Windows: same as [`config_dir()`]
 pub fn data_dir() -> PathBuf {
@@ -908,18 +1006,22 @@ pub fn data_dir() -> PathBuf {
 
     #[cfg(target_os = "linux")]
     {
-        if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
-            return PathBuf::from(xdg).join("cratebay");
-        }
-        if let Ok(home) = std::env::var("HOME") {
-            return PathBuf::from(home)
-                .join(".local")
-                .join("share")
-                .join("cratebay");
+        let base = if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
+            Some(PathBuf::from(xdg).join("cratebay"))
+        } else {
+            std::env::var("HOME").ok().map(|home| {
+                PathBuf::from(home)
+                    .join(".local")
+                    .join("share")
+                    .join("cratebay")
+            })
+        };
+        if let Some(base) = base {
+            return profile_scoped(base, profile().as_deref());
         }
     }
 
-    // macOS / Windows default: same as config_dir.
+    // macOS / Windows default: same as config_dir (already scoped).
     config_dir()
 }
 
@@ -933,7 +1035,7 @@ pub fn log_dir() -> PathBuf {
 
     #[cfg(target_os = "linux")]
     {
-        return data_dir();
+        data_dir()
     }
 
     #[cfg(not(target_os = "linux"))]
@@ -993,7 +1095,7 @@ pub fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
 
 // ─── Helpers ────────────────────────────────────────────────────────
 
-fn home_dir() -> Result<PathBuf, AppError> {
+pub(crate) fn home_dir() -> Result<PathBuf, AppError> {
     std::env::var("HOME")
         .or_else(|_| std::env::var("USERPROFILE"))
         .map(PathBuf::from)
@@ -1026,6 +1128,22 @@ mod tests {
         conn
     }
 
+    #[test]
+    fn profiles_scope_directories() {
+        let base = PathBuf::from("/home/me/.config/cratebay");
+        assert_eq!(profile_scoped(base.clone(), None), base);
+        assert_eq!(
+            profile_scoped(base, Some("work")),
+            PathBuf::from("/home/me/.config/cratebay/profiles/work")
+        );
+
+        validate_profile("ci_sandbox-2").unwrap();
+        for bad in ["", "../x", "a/b", "work space", &"x".repeat(33)] {
+            assert!(validate_profile(bad).is_err(), "{:?}", bad);
+        }
+        assert_ne!(profile_hash("work"), profile_hash("personal"));
+    }
+
     #[test]
     fn test_migrations_apply_cleanly() {
         let conn = setup_db();
@@ -0,0 +1,279 @@
+//! Interactive terminal sessions for embedded terminals.
+//!
+//! A session is a `docker exec` with a TTY, attached in both directions.
+//! Shells on the runtime VM run in a privileged helper container that enters
+//! PID 1's namespaces, as [`container::runtime_exec`] does; that helper lives
+//! as long as the session and is removed by [`TerminalSession::close`].
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecOptions, StartExecResults};
+use bollard::Docker;
+use futures_util::StreamExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::container;
+use crate::error::AppError;
+
+/// Timeout for creating, starting and resizing the exec.
+const TERMINAL_SETUP_TIMEOUT: Duration = Duration::from_secs(12);
+
+/// Login shell used when none is given: bash where the image has it.
+const DEFAULT_SHELL: &str =
+    "if command -v bash >/dev/null 2>&1; then exec bash -l; else exec sh -l; fi";
+
+/// Where a terminal session runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminalTarget {
+    /// A running container (ID or name).
+    Container(String),
+    /// The runtime VM (the Docker host).
+    Runtime,
+}
+
+/// Output of a terminal session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminalEvent {
+    /// Terminal output, decoded as UTF-8.
+    Output(String),
+    /// The shell exited; the code is None when it could not be read.
+    Exit(Option<i64>),
+}
+
+/// An attached TTY exec. Output is delivered to the callback given to
+/// [`open`] from a background task until the shell exits.
+pub struct TerminalSession {
+    exec_id: String,
+    input: Mutex<Pin<Box<dyn AsyncWrite + Send>>>,
+    reader: tokio::task::JoinHandle<()>,
+    /// Runtime helper container to remove on close.
+    helper: Option<String>,
+}
+
+/// Open a shell in `target` with a `cols`×`rows` TTY. `shell` is run with
+/// `/bin/sh -c` (default: bash or sh as a login shell).
+pub async fn open(
+    docker: &Docker,
+    target: TerminalTarget,
+    shell: Option<String>,
+    cols: u16,
+    rows: u16,
+    on_event: impl Fn(TerminalEvent) + Send + 'static,
+) -> Result<TerminalSession, AppError> {
+    let shell = shell
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SHELL.to_string());
+    let cmd = vec!["/bin/sh".to_string(), "-c".to_string(), shell];
+
+    let (container, cmd, helper) = match target {
+        TerminalTarget::Container(id) => (id, cmd, None),
+        TerminalTarget::Runtime => {
+            let helper = container::start_runtime_helper(docker, "terminal").await?;
+            (
+                helper.clone(),
+                container::host_namespace_cmd(cmd),
+                Some(helper),
+            )
+        }
+    };
+
+    match attach(docker, &container, cmd, cols, rows, on_event).await {
+        Ok((exec_id, input, reader)) => Ok(TerminalSession {
+            exec_id,
+            input: Mutex::new(input),
+            reader,
+            helper,
+        }),
+        Err(e) => {
+            if let Some(helper) = &helper {
+                let _ = container::delete(docker, helper, true).await;
+            }
+            Err(e)
+        }
+    }
+}
+
+type Attached = (
+    String,
+    Pin<Box<dyn AsyncWrite + Send>>,
+    tokio::task::JoinHandle<()>,
+);
+
+async fn attach(
+    docker: &Docker,
+    container: &str,
+    cmd: Vec<String>,
+    cols: u16,
+    rows: u16,
+    on_event: impl Fn(TerminalEvent) + Send + 'static,
+) -> Result<Attached, AppError> {
+    let options = CreateExecOptions {
+        cmd: Some(cmd),
+        attach_stdin: Some(true),
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        tty: Some(true),
+        env: Some(vec!["TERM=xterm-256color".to_string()]),
+        ..Default::default()
+    };
+    let exec = setup_timeout("create", docker.create_exec(container, options)).await??;
+    let start = StartExecOptions {
+        detach: false,
+        tty: true,
+        output_capacity: None,
+    };
+    let StartExecResults::Attached { mut output, input } =
+        setup_timeout("start", docker.start_exec(&exec.id, Some(start))).await??
+    else {
+        return Err(AppError::Runtime(
+            "Docker did not attach to the terminal exec".to_string(),
+        ));
+    };
+    // The TTY only exists once the exec runs, so size it after starting.
+    if let Err(e) = resize_exec(docker, &exec.id, cols, rows).await {
+        tracing::warn!("Failed to size terminal {}: {}", exec.id, e);
+    }
+
+    let docker = docker.clone();
+    let exec_id = exec.id.clone();
+    let reader = tokio::spawn(async move {
+        let mut decoder = Utf8Decoder::default();
+        while let Some(chunk) = output.next().await {
+            let Ok(chunk) = chunk else { break };
+            let text = decoder.decode(&chunk.into_bytes());
+            if !text.is_empty() {
+                on_event(TerminalEvent::Output(text));
+            }
+        }
+        let exit_code = docker
+            .inspect_exec(&exec_id)
+            .await
+            .ok()
+            .and_then(|e| e.exit_code);
+        on_event(TerminalEvent::Exit(exit_code));
+    });
+    Ok((exec.id, input, reader))
+}
+
+impl TerminalSession {
+    /// Send keystrokes (or pasted text) to the shell.
+    pub async fn write(&self, data: &[u8]) -> Result<(), AppError> {
+        let mut input = self.input.lock().await;
+        input.write_all(data).await?;
+        input.flush().await?;
+        Ok(())
+    }
+
+    /// Resize the TTY, e.g. after the terminal pane changed size.
+    pub async fn resize(&self, docker: &Docker, cols: u16, rows: u16) -> Result<(), AppError> {
+        resize_exec(docker, &self.exec_id, cols, rows).await
+    }
+
+    /// Whether the shell has exited and all output was delivered.
+    pub fn is_finished(&self) -> bool {
+        self.reader.is_finished()
+    }
+
+    /// End the session. The shell gets a hangup when its input closes;
+    /// a runtime helper container is removed.
+    pub async fn close(&self, docker: &Docker) -> Result<(), AppError> {
+        let _ = self.input.lock().await.shutdown().await;
+        self.reader.abort();
+        if let Some(helper) = &self.helper {
+            container::delete(docker, helper, true).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn resize_exec(docker: &Docker, exec_id: &str, cols: u16, rows: u16) -> Result<(), AppError> {
+    if cols == 0 || rows == 0 {
+        return Err(AppError::Validation(format!(
+            "Invalid terminal size {}x{}",
+            cols, rows
+        )));
+    }
+    let options = ResizeExecOptions {
+        height: rows,
+        width: cols,
+    };
+    setup_timeout("resize", docker.resize_exec(exec_id, options)).await??;
+    Ok(())
+}
+
+async fn setup_timeout<T>(
+    step: &str,
+    future: impl std::future::Future<Output = T>,
+) -> Result<T, AppError> {
+    tokio::time::timeout(TERMINAL_SETUP_TIMEOUT, future)
+        .await
+        .map_err(|_| {
+            AppError::Runtime(format!(
+                "Terminal exec {} timed out after {:?}",
+                step, TERMINAL_SETUP_TIMEOUT
+            ))
+        })
+}
+
+/// Incremental UTF-8 decoding: a character split across output chunks is
+/// held back until its remaining bytes arrive.
+#[derive(Debug, Default)]
+struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    fn decode(&mut self, chunk: &[u8]) -> String {
+        self.pending.extend_from_slice(chunk);
+        let mut out = String::new();
+        loop {
+            match std::str::from_utf8(&self.pending) {
+                Ok(text) => {
+                    out.push_str(text);
+                    self.pending.clear();
+                    return out;
+                }
+                Err(e) => {
+                    let valid = e.valid_up_to();
+                    out.push_str(&String::from_utf8_lossy(&self.pending[..valid]));
+                    match e.error_len() {
+                        // Incomplete sequence at the end: wait for more bytes.
+                        None => {
+                            self.pending.drain(..valid);
+                            return out;
+                        }
+                        Some(len) => {
+                            out.push(char::REPLACEMENT_CHARACTER);
+                            self.pending.drain(..valid + len);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decoder_joins_characters_split_across_chunks() {
+        let bytes = "héllo ✓".as_bytes();
+        let mut decoder = Utf8Decoder::default();
+        // Split inside both the 2-byte 'é' and the 3-byte '✓'.
+        let mut out = decoder.decode(&bytes[..2]);
+        out += &decoder.decode(&bytes[2..8]);
+        out += &decoder.decode(&bytes[8..]);
+        assert_eq!(out, "héllo ✓");
+        assert!(decoder.pending.is_empty());
+    }
+
+    #[test]
+    fn decoder_replaces_invalid_bytes() {
+        let mut decoder = Utf8Decoder::default();
+        assert_eq!(decoder.decode(b"a\xffb"), "a\u{fffd}b");
+    }
+}
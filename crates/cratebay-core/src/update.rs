@@ -0,0 +1,471 @@
+//! Self-update of the `cratebay` CLI from GitHub releases.
+//!
+//! [`check`] compares the running version with the newest release.
+//! [`apply`] downloads this platform's binary (`cratebay-<os>-<arch>`),
+//! verifies it against the release's `SHA256SUMS.txt` and swaps it in place
+//! of the current executable. The previous binary is kept as `<exe>.old`
+//! until the new one has been seen to run, and restored if it does not.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+use crate::cancel::{self, CancellationToken};
+use crate::error::AppError;
+
+/// GitHub API endpoint listing CrateBay releases.
+pub const RELEASES_URL: &str = "https://api.github.com/repos/coder-hhx/CrateBay/releases";
+
+/// Checksum file attached to every release (`sha256sum` format).
+pub const CHECKSUMS_ASSET: &str = "SHA256SUMS.txt";
+
+const API_TIMEOUT: Duration = Duration::from_secs(30);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Result of an update check.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    pub prerelease: bool,
+    pub release_url: String,
+    pub published_at: Option<String>,
+    /// Release notes (Markdown).
+    pub notes: Option<String>,
+    /// CLI binary for this platform, e.g. `cratebay-linux-x86_64`.
+    pub asset_name: Option<String>,
+    /// Download URL of `asset_name`, if the release publishes it.
+    pub asset_url: Option<String>,
+    pub checksums_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Version of the running binaries.
+pub fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Release asset name of the CLI for this platform, or `None` where no
+/// binaries are published.
+pub fn cli_asset_name() -> Option<String> {
+    platform_asset_name(std::env::consts::OS, std::env::consts::ARCH)
+}
+
+fn platform_asset_name(os: &str, arch: &str) -> Option<String> {
+    if !matches!(os, "macos" | "linux" | "windows") || !matches!(arch, "x86_64" | "aarch64") {
+        return None;
+    }
+    let ext = if os == "windows" { ".exe" } else { "" };
+    Some(format!("cratebay-{}-{}{}", os, arch, ext))
+}
+
+/// Parse a release tag (`v1.2.3`, `1.2.3-beta.1`). Other tags, such as
+/// `runtime-v1.0.0` for runtime images, are not CrateBay releases.
+fn parse_version(tag: &str) -> Option<semver::Version> {
+    semver::Version::parse(tag.trim().strip_prefix('v').unwrap_or(tag.trim())).ok()
+}
+
+/// Query GitHub for the newest release. Pre-releases are only considered
+/// with `include_prerelease`.
+pub async fn check(include_prerelease: bool) -> Result<UpdateInfo, AppError> {
+    let client = crate::registry::http_client(API_TIMEOUT)?;
+    let url = format!("{}?per_page=30", RELEASES_URL);
+    let releases: Vec<Release> = client
+        .get(&url)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::Runtime(format!("Failed to query releases: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| AppError::Runtime(format!("Failed to parse releases: {}", e)))?;
+    latest_update(
+        releases,
+        current_version(),
+        include_prerelease,
+        cli_asset_name().as_deref(),
+    )
+}
+
+fn latest_update(
+    releases: Vec<Release>,
+    current: &str,
+    include_prerelease: bool,
+    asset_name: Option<&str>,
+) -> Result<UpdateInfo, AppError> {
+    let current_version = parse_version(current)
+        .ok_or_else(|| AppError::Runtime(format!("Invalid current version '{}'", current)))?;
+    let (version, release) = releases
+        .into_iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| Some((parse_version(&r.tag_name)?, r)))
+        .filter(|(v, r)| include_prerelease || (!r.prerelease && v.pre.is_empty()))
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .ok_or_else(|| AppError::NotFound {
+            entity: "release".to_string(),
+            id: RELEASES_URL.to_string(),
+        })?;
+
+    let asset_url = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.browser_download_url.clone())
+    };
+    let binary_url = asset_name.and_then(asset_url);
+    let checksums_url = asset_url(CHECKSUMS_ASSET);
+    Ok(UpdateInfo {
+        current_version: current.to_string(),
+        latest_version: version.to_string(),
+        update_available: version > current_version,
+        prerelease: release.prerelease,
+        release_url: release.html_url,
+        published_at: release.published_at,
+        notes: release.body,
+        asset_name: asset_name.map(str::to_string),
+        asset_url: binary_url,
+        checksums_url,
+    })
+}
+
+/// Install the release described by `info` over `exe` (normally
+/// `std::env::current_exe()`).
+///
+/// The binary must be listed in the release's `SHA256SUMS.txt`; unverified
+/// binaries are never installed. A cancelled or failed update leaves `exe`
+/// untouched.
+pub async fn apply(
+    info: &UpdateInfo,
+    exe: &Path,
+    cancel: Option<&CancellationToken>,
+) -> Result<(), AppError> {
+    let (Some(name), Some(url)) = (&info.asset_name, &info.asset_url) else {
+        return Err(AppError::NotFound {
+            entity: "release asset".to_string(),
+            id: format!(
+                "{} for {}-{}",
+                info.latest_version,
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            ),
+        });
+    };
+    let checksums_url = info.checksums_url.as_deref().ok_or_else(|| {
+        AppError::Validation(format!(
+            "Release {} publishes no {}; refusing to install an unverified binary",
+            info.latest_version, CHECKSUMS_ASSET
+        ))
+    })?;
+
+    let client = crate::registry::http_client(DOWNLOAD_TIMEOUT)?;
+    let what = format!("Update to {}", info.latest_version);
+    let checksums = cancel::run(cancel, &what, fetch_text(&client, checksums_url))
+        .await
+        .and_then(|r| r)?;
+    let expected = checksum_for(&checksums, name).ok_or_else(|| {
+        AppError::Validation(format!("{} has no checksum for {}", CHECKSUMS_ASSET, name))
+    })?;
+
+    let staged = StagedFile(sibling(exe, "new"));
+    let actual = cancel::run(cancel, &what, download(&client, url, &staged.0))
+        .await
+        .and_then(|r| r)?;
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err(AppError::Validation(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            name, expected, actual
+        )));
+    }
+    std::fs::set_permissions(&staged.0, std::fs::metadata(exe)?.permissions())?;
+    verify_runs(&staged.0, &info.latest_version)?;
+    swap(exe, &staged.0, &info.latest_version)
+}
+
+/// Removes a staged download unless it was moved into place.
+struct StagedFile(PathBuf);
+
+impl Drop for StagedFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// `<exe>.<suffix>` next to `exe`, so renames stay on one filesystem.
+fn sibling(exe: &Path, suffix: &str) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    exe.with_file_name(name)
+}
+
+async fn fetch_text(client: &reqwest::Client, url: &str) -> Result<String, AppError> {
+    client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::Runtime(format!("Failed to download {}: {}", url, e)))?
+        .text()
+        .await
+        .map_err(|e| AppError::Runtime(format!("Failed to download {}: {}", url, e)))
+}
+
+/// Stream `url` into `dest`, returning its SHA-256 digest.
+async fn download(client: &reqwest::Client, url: &str, dest: &Path) -> Result<String, AppError> {
+    let mut response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::Runtime(format!("Failed to download {}: {}", url, e)))?;
+    let mut file = tokio::fs::File::create(dest).await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::PermissionDenied {
+            AppError::PermissionDenied(format!(
+                "cannot write to {}; rerun with permission to replace the installed binary",
+                dest.parent().unwrap_or(dest).display()
+            ))
+        } else {
+            e.into()
+        }
+    })?;
+    let mut hasher = Sha256::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::Runtime(format!("Failed to download {}: {}", url, e)))?
+    {
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+    }
+    file.sync_all().await?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Digest of `name` in `sha256sum` output. Entries may carry a directory
+/// (`./cratebay-linux-x86_64/cratebay-linux-x86_64`) or the binary-mode `*`.
+fn checksum_for(sums: &str, name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (digest, path) = line.trim().split_once(char::is_whitespace)?;
+        let file = path.trim().trim_start_matches('*').rsplit('/').next()?;
+        (file == name && digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| digest.to_ascii_lowercase())
+    })
+}
+
+/// Check that `binary --version` runs and reports exactly `version`.
+fn verify_runs(binary: &Path, version: &str) -> Result<(), AppError> {
+    let output = Command::new(binary)
+        .arg("--version")
+        .output()
+        .map_err(|e| AppError::Runtime(format!("Updated binary failed to start: {}", e)))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let matches = reported_version(&stdout).is_some_and(|v| Some(v) == parse_version(version));
+    if !output.status.success() || !matches {
+        return Err(AppError::Runtime(format!(
+            "Updated binary did not report version {} (got '{}')",
+            version,
+            stdout.trim()
+        )));
+    }
+    Ok(())
+}
+
+/// The version in `--version` output (`cratebay 1.2.3`): the first word
+/// that parses as one.
+fn reported_version(output: &str) -> Option<semver::Version> {
+    output.split_whitespace().find_map(parse_version)
+}
+
+/// Replace `exe` with `staged`. The old binary is kept as `<exe>.old` until
+/// the installed one runs, and moved back on any failure.
+fn swap(exe: &Path, staged: &Path, version: &str) -> Result<(), AppError> {
+    let backup = sibling(exe, "old");
+    // A leftover from an update on Windows, where the running binary could
+    // not be deleted.
+    let _ = std::fs::remove_file(&backup);
+    std::fs::rename(exe, &backup)?;
+
+    let installed = std::fs::rename(staged, exe)
+        .map_err(AppError::from)
+        .and_then(|()| verify_runs(exe, version));
+    if let Err(e) = installed {
+        let _ = std::fs::remove_file(exe);
+        if let Err(restore) = std::fs::rename(&backup, exe) {
+            return Err(AppError::Runtime(format!(
+                "{}; restoring the previous binary from {} also failed: {}",
+                e,
+                backup.display(),
+                restore
+            )));
+        }
+        return Err(e);
+    }
+    let _ = std::fs::remove_file(&backup);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool, assets: &[&str]) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            html_url: format!("https://example.com/{}", tag),
+            body: None,
+            published_at: None,
+            prerelease,
+            draft: false,
+            assets: assets
+                .iter()
+                .map(|name| ReleaseAsset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://example.com/{}/{}", tag, name),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn asset_names_cover_released_platforms() {
+        assert_eq!(
+            platform_asset_name("linux", "x86_64").as_deref(),
+            Some("cratebay-linux-x86_64")
+        );
+        assert_eq!(
+            platform_asset_name("windows", "x86_64").as_deref(),
+            Some("cratebay-windows-x86_64.exe")
+        );
+        assert_eq!(platform_asset_name("freebsd", "x86_64"), None);
+    }
+
+    #[test]
+    fn latest_update_picks_newest_stable_release() {
+        let asset = "cratebay-linux-x86_64";
+        let releases = vec![
+            release("runtime-v9.0.0", false, &[]),
+            release("v1.1.0-beta.1", true, &[asset]),
+            release("v1.0.0", false, &[asset, CHECKSUMS_ASSET]),
+            release("v0.9.0", false, &[asset]),
+        ];
+        let info = latest_update(releases, "0.9.0", false, Some(asset)).unwrap();
+        assert_eq!(info.latest_version, "1.0.0");
+        assert!(info.update_available);
+        assert_eq!(
+            info.asset_url.as_deref(),
+            Some("https://example.com/v1.0.0/cratebay-linux-x86_64")
+        );
+        assert!(info.checksums_url.is_some());
+
+        let releases = vec![
+            release("v1.1.0-beta.1", true, &[]),
+            release("v1.0.0", false, &[]),
+        ];
+        let info = latest_update(releases, "1.0.0", true, Some(asset)).unwrap();
+        assert_eq!(info.latest_version, "1.1.0-beta.1");
+        assert!(info.asset_url.is_none());
+    }
+
+    #[test]
+    fn latest_update_reports_up_to_date() {
+        let releases = vec![release("v0.9.0", false, &[])];
+        let info = latest_update(releases, "0.9.0", false, None).unwrap();
+        assert!(!info.update_available);
+    }
+
+    #[test]
+    fn checksum_for_matches_file_name() {
+        let digest = "a".repeat(64);
+        let sums = format!(
+            "{}  ./cratebay-gui-linux-x86_64/CrateBay.deb\n{}  ./cratebay-linux-x86_64/cratebay-linux-x86_64\n",
+            "b".repeat(64),
+            digest
+        );
+        assert_eq!(
+            checksum_for(&sums, "cratebay-linux-x86_64").as_deref(),
+            Some(digest.as_str())
+        );
+        assert_eq!(checksum_for(&sums, "cratebay-macos-aarch64"), None);
+    }
+
+    #[test]
+    fn reported_version_must_match_exactly() {
+        let reports = |output: &str, version: &str| {
+            reported_version(output).is_some_and(|v| Some(v) == parse_version(version))
+        };
+        assert!(reports("cratebay 1.0.0\n", "1.0.0"));
+        assert!(reports("cratebay v1.0.0-rc1", "1.0.0-rc1"));
+        assert!(!reports("cratebay 11.0.0", "1.0.0"));
+        assert!(!reports("cratebay 1.0.0-rc1", "1.0.0"));
+        assert!(!reports("cratebay", "1.0.0"));
+    }
+
+    #[cfg(unix)]
+    fn script(path: &Path, body: &str) {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::write(path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn swap_installs_binary_that_runs() {
+        let tmp = tempfile::tempdir().unwrap();
+        let exe = tmp.path().join("cratebay");
+        let staged = tmp.path().join("cratebay.new");
+        script(&exe, "echo cratebay 0.9.0");
+        script(&staged, "echo cratebay 1.0.0");
+
+        swap(&exe, &staged, "1.0.0").unwrap();
+        assert!(std::fs::read_to_string(&exe).unwrap().contains("1.0.0"));
+        assert!(!staged.exists());
+        assert!(!sibling(&exe, "old").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn swap_rolls_back_when_new_binary_fails() {
+        let tmp = tempfile::tempdir().unwrap();
+        let exe = tmp.path().join("cratebay");
+        let staged = tmp.path().join("cratebay.new");
+        script(&exe, "echo cratebay 0.9.0");
+        script(&staged, "exit 1");
+
+        assert!(swap(&exe, &staged, "1.0.0").is_err());
+        assert!(std::fs::read_to_string(&exe).unwrap().contains("0.9.0"));
+        assert!(!sibling(&exe, "old").exists());
+    }
+}
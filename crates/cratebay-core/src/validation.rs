@@ -67,15 +67,222 @@ pub fn validate_path_within_root(path: &Path, root: &Path) -> Result<PathBuf, Ap
 
 /// Validate resource limits for containers.
 pub fn validate_resource_limits(cpu: u32, memory_mb: u64) -> Result<(), AppError> {
+    validate_cpu_cores(cpu)?;
+    validate_memory_mb(memory_mb)
+}
+
+/// Validate a CPU core limit (1-16).
+pub fn validate_cpu_cores(cpu: u32) -> Result<(), AppError> {
     if cpu == 0 || cpu > 16 {
         return Err(AppError::Validation("CPU cores must be 1-16".into()));
     }
+    Ok(())
+}
+
+/// Validate a memory limit in MB (256-65536).
+pub fn validate_memory_mb(memory_mb: u64) -> Result<(), AppError> {
     if !(256..=65536).contains(&memory_mb) {
         return Err(AppError::Validation("Memory must be 256-65536 MB".into()));
     }
     Ok(())
 }
 
+/// Maximum idempotency key length.
+pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 128;
+
+/// Validate an idempotency key: 1-128 printable ASCII characters without
+/// spaces (UUIDs, ULIDs and similar tokens).
+pub fn validate_idempotency_key(key: &str) -> Result<(), AppError> {
+    if key.is_empty()
+        || key.len() > MAX_IDEMPOTENCY_KEY_LEN
+        || !key.chars().all(|c| c.is_ascii_graphic())
+    {
+        return Err(AppError::Validation(format!(
+            "idempotency_key: must be 1-{} printable ASCII characters without spaces",
+            MAX_IDEMPOTENCY_KEY_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Maximum runtime VM name length.
+pub const MAX_VM_NAME_LEN: usize = 63;
+
+/// Largest runtime disk, in GB.
+pub const MAX_DISK_GB: u32 = 4096;
+
+/// Memory the host keeps for itself when sizing the runtime VM, in MB.
+pub const HOST_MEMORY_RESERVE_MB: u64 = 1024;
+
+/// Validate a runtime VM name: 1-63 ASCII letters, digits, `-`, `_` or
+/// `.`, starting with a letter or digit. Names become directory and distro
+/// names, so path separators are rejected.
+pub fn validate_vm_name(name: &str) -> Result<(), AppError> {
+    let valid_start = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric());
+    if !valid_start
+        || name.len() > MAX_VM_NAME_LEN
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(AppError::Validation(format!(
+            "name: {:?} must be 1-{} letters, digits, '-', '_' or '.', starting with a letter or digit",
+            name, MAX_VM_NAME_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// CPUs and memory of the machine running the runtime VM.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HostResources {
+    pub cpus: Option<u32>,
+    pub memory_mb: Option<u64>,
+}
+
+impl HostResources {
+    /// Detect this machine's resources; unknown values are `None` and
+    /// skip the matching checks.
+    pub fn detect() -> Self {
+        Self {
+            cpus: std::thread::available_parallelism()
+                .ok()
+                .and_then(|n| u32::try_from(n.get()).ok()),
+            memory_mb: host_memory_mb(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn host_memory_mb() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb / 1024)
+}
+
+#[cfg(target_os = "macos")]
+fn host_memory_mb() -> Option<u64> {
+    let mut bytes: u64 = 0;
+    let mut len = std::mem::size_of::<u64>();
+    // SAFETY: `hw.memsize` is a u64 and `bytes`/`len` describe a u64 buffer.
+    let rc = unsafe {
+        libc::sysctlbyname(
+            c"hw.memsize".as_ptr(),
+            (&mut bytes as *mut u64).cast(),
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (rc == 0).then_some(bytes / 1024 / 1024)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn host_memory_mb() -> Option<u64> {
+    None
+}
+
+/// Validate a runtime VM allocation: the static CPU and memory bounds, then
+/// the host — no more CPUs than it has, and memory that leaves
+/// [`HOST_MEMORY_RESERVE_MB`] for it. Every problem is reported, one
+/// `field: message` per line.
+pub fn validate_vm_resources(
+    cpu_cores: u32,
+    memory_mb: u64,
+    host: HostResources,
+) -> Result<(), AppError> {
+    let mut problems = Vec::new();
+    if let Err(AppError::Validation(e)) = validate_cpu_cores(cpu_cores) {
+        problems.push(format!("cpu_cores: {}", e));
+    } else if let Some(host_cpus) = host.cpus.filter(|&n| cpu_cores > n) {
+        problems.push(format!(
+            "cpu_cores: {} requested but the host has {}",
+            cpu_cores, host_cpus
+        ));
+    }
+    if let Err(AppError::Validation(e)) = validate_memory_mb(memory_mb) {
+        problems.push(format!("memory_mb: {}", e));
+    } else if let Some(host_mb) = host
+        .memory_mb
+        .filter(|&total| memory_mb + HOST_MEMORY_RESERVE_MB > total)
+    {
+        problems.push(format!(
+            "memory_mb: {} MB requested but the host has {} MB and keeps {} MB for itself",
+            memory_mb, host_mb, HOST_MEMORY_RESERVE_MB
+        ));
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Validation(problems.join("\n")))
+    }
+}
+
+/// Validate a runtime disk size in GB (1-[`MAX_DISK_GB`]).
+pub fn validate_disk_gb(disk_gb: u32) -> Result<(), AppError> {
+    if disk_gb == 0 || disk_gb > MAX_DISK_GB {
+        return Err(AppError::Validation(format!(
+            "disk_gb: must be 1-{} GB, got {}",
+            MAX_DISK_GB, disk_gb
+        )));
+    }
+    Ok(())
+}
+
+/// Maximum VirtioFS tag length in bytes (Linux `virtio_fs` limit).
+pub const MAX_MOUNT_TAG_LEN: usize = 36;
+
+/// Validate a VirtioFS mount tag: 1-36 bytes of ASCII letters, digits,
+/// `-`, `_` or `.`. Tags double as guest directory names, so `.` and `..`
+/// are rejected.
+pub fn validate_mount_tag(tag: &str) -> Result<(), AppError> {
+    if tag == "." || tag == ".." {
+        return Err(AppError::Validation(format!(
+            "Invalid mount tag: {:?}",
+            tag
+        )));
+    }
+    if tag.is_empty() || tag.len() > MAX_MOUNT_TAG_LEN {
+        return Err(AppError::Validation(format!(
+            "Mount tag must be 1-{} characters: {:?}",
+            MAX_MOUNT_TAG_LEN, tag
+        )));
+    }
+    if !tag
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(AppError::Validation(format!(
+            "Mount tag may only contain letters, digits, '-', '_' or '.': {:?}",
+            tag
+        )));
+    }
+    Ok(())
+}
+
+/// Resolve a host directory to share: relative paths and symlinks are
+/// resolved, and the target must be an existing directory.
+pub fn canonical_host_dir(path: &Path) -> Result<PathBuf, AppError> {
+    let canonical = path.canonicalize().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::NotFound {
+            entity: "directory".into(),
+            id: path.display().to_string(),
+        },
+        _ => AppError::Validation(format!("Invalid path {}: {}", path.display(), e)),
+    })?;
+    if !canonical.is_dir() {
+        return Err(AppError::Validation(format!(
+            "Not a directory: {}",
+            canonical.display()
+        )));
+    }
+    Ok(canonical)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,4 +323,85 @@ mod tests {
         assert!(validate_resource_limits(2, 128).is_err());
         assert!(validate_resource_limits(2, 70000).is_err());
     }
+
+    #[test]
+    fn test_validate_idempotency_key() {
+        assert!(validate_idempotency_key("6f1c2f4e-0d7b-4c2a-9a51-3c1e8f0b7d21").is_ok());
+        assert!(validate_idempotency_key("").is_err());
+        assert!(validate_idempotency_key("has space").is_err());
+        assert!(validate_idempotency_key(&"k".repeat(MAX_IDEMPOTENCY_KEY_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_vm_name() {
+        assert!(validate_vm_name("cratebay-runtime").is_ok());
+        assert!(validate_vm_name("runtime.v2_1").is_ok());
+        assert!(validate_vm_name("").is_err());
+        assert!(validate_vm_name("-runtime").is_err());
+        assert!(validate_vm_name("../etc").is_err());
+        assert!(validate_vm_name("a/b").is_err());
+        assert!(validate_vm_name("a\\b").is_err());
+        assert!(validate_vm_name(&"a".repeat(MAX_VM_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_vm_resources() {
+        let host = HostResources {
+            cpus: Some(8),
+            memory_mb: Some(16384),
+        };
+        assert!(validate_vm_resources(4, 8192, host).is_ok());
+        assert!(validate_vm_resources(8, 15360, host).is_ok());
+        assert!(validate_vm_resources(12, 8192, HostResources::default()).is_ok());
+
+        let Err(AppError::Validation(message)) = validate_vm_resources(0, 16000, host) else {
+            panic!("expected a validation error");
+        };
+        let lines: Vec<_> = message.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("cpu_cores: "));
+        assert!(lines[1].starts_with("memory_mb: ") && lines[1].contains("16384 MB"));
+
+        let err = validate_vm_resources(12, 0, host).unwrap_err().to_string();
+        assert!(err.contains("cpu_cores: 12 requested but the host has 8"));
+        assert!(err.contains("memory_mb: Memory must be"));
+
+        assert!(validate_disk_gb(64).is_ok());
+        assert!(validate_disk_gb(0).is_err());
+        assert!(validate_disk_gb(MAX_DISK_GB + 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_mount_tag() {
+        assert!(validate_mount_tag("workspace").is_ok());
+        assert!(validate_mount_tag("my-code_1.0").is_ok());
+        assert!(validate_mount_tag("").is_err());
+        assert!(validate_mount_tag("has:colon").is_err());
+        assert!(validate_mount_tag("has space").is_err());
+        assert!(validate_mount_tag("..").is_err());
+        assert!(validate_mount_tag(&"a".repeat(MAX_MOUNT_TAG_LEN)).is_ok());
+        assert!(validate_mount_tag(&"a".repeat(MAX_MOUNT_TAG_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_canonical_host_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let canonical = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        assert_eq!(
+            canonical_host_dir(&dir.path().join("sub/..")).unwrap(),
+            canonical
+        );
+
+        let file = dir.path().join("file.txt");
+        std::fs::write(&file, "x").unwrap();
+        assert!(matches!(
+            canonical_host_dir(&file),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            canonical_host_dir(&dir.path().join("missing")),
+            Err(AppError::NotFound { .. })
+        ));
+    }
 }
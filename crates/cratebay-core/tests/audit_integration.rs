@@ -6,7 +6,7 @@
 use rusqlite::Connection;
 
 use cratebay_core::audit;
-use cratebay_core::models::AuditAction;
+use cratebay_core::models::{AuditAction, AuditLogQuery};
 use cratebay_core::storage;
 
 fn setup_db() -> Connection {
@@ -45,8 +45,16 @@ fn audit_log_all_action_types() {
         (AuditAction::ContainerCreate, "container.create"),
         (AuditAction::ContainerStart, "container.start"),
         (AuditAction::ContainerStop, "container.stop"),
+        (AuditAction::ContainerRestart, "container.restart"),
+        (AuditAction::ContainerPause, "container.pause"),
+        (AuditAction::ContainerUnpause, "container.unpause"),
+        (AuditAction::ContainerRename, "container.rename"),
+        (AuditAction::ContainerUpdate, "container.update"),
         (AuditAction::ContainerDelete, "container.delete"),
         (AuditAction::ContainerExec, "container.exec"),
+        (AuditAction::ContainerCheckpoint, "container.checkpoint"),
+        (AuditAction::ContainerRestore, "container.restore"),
+        (AuditAction::RuntimeExec, "runtime.exec"),
         (AuditAction::ApiKeySave, "api_key.save"),
         (AuditAction::ApiKeyDelete, "api_key.delete"),
         (AuditAction::ProviderCreate, "provider.create"),
@@ -74,7 +82,7 @@ fn audit_log_all_action_types() {
     let count: u32 = conn
         .query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))
         .unwrap();
-    assert_eq!(count, 16);
+    assert_eq!(count, 24);
 }
 
 #[test]
@@ -190,6 +198,44 @@ fn list_audit_logs_respects_limit() {
     assert_eq!(logs.len(), 3);
 }
 
+#[test]
+fn query_audit_logs_filters_by_since() {
+    let conn = setup_db();
+
+    for (target, timestamp) in [
+        ("c1", "2025-01-01T00:00:00Z"),
+        ("c1", "2025-02-01T00:00:00Z"),
+        ("c2", "2025-03-01T00:00:00Z"),
+    ] {
+        audit::log_action_with_timestamp(
+            &conn,
+            &AuditAction::ContainerStart,
+            target,
+            None,
+            "user",
+            timestamp,
+        )
+        .unwrap();
+    }
+
+    let query = AuditLogQuery {
+        since: Some(audit::parse_since("2025-01-15").unwrap()),
+        limit: 100,
+        ..Default::default()
+    };
+    let logs = storage::query_audit_logs(&conn, &query).unwrap();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].timestamp, "2025-03-01T00:00:00Z");
+
+    let query = AuditLogQuery {
+        target: Some("c1".to_string()),
+        ..query
+    };
+    let logs = storage::query_audit_logs(&conn, &query).unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].action, "container.start");
+}
+
 // ─── Timestamp-based Insertion ──────────────────────────────────────
 
 #[test]
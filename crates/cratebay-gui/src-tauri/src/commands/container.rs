@@ -1,16 +1,20 @@
 //! Container management Tauri commands.
 
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 use crate::state::AppState;
+use crate::tasks::TaskKind;
 use cratebay_core::error::AppError;
 use cratebay_core::models::AuditAction;
 use cratebay_core::models::{
-    ContainerCreateRequest, ContainerDetail, ContainerInfo, ContainerListFilters, ContainerStats,
-    ExecResult, ImageInspectInfo, ImageSearchResult, LocalImageInfo, LogEntry, LogOptions,
+    ComposeConversion, ComposeValidation, ContainerCommitRequest, ContainerCreateRequest,
+    ContainerDetail, ContainerInfo, ContainerListFilters, ContainerProcess, ContainerStats,
+    DockerfileLint, ExecResult, ImageBuildRequest, ImageDetails, ImageInspectInfo, ImageLayers,
+    ImageSearchResponse, ImageSearchResult, LocalImageInfo, LogEntry, LogOptions, RegistryAuth,
+    RegistryCatalog, RegistryTagList, RemoteImageManifest, RunValidationReport,
 };
 use cratebay_core::MutexExt;
-use cratebay_core::{audit, container, storage, validation};
+use cratebay_core::{audit, checkpoint, compose, container, dockerfile_lint, storage, validation};
 
 /// List available container templates.
 #[tauri::command]
@@ -21,14 +25,15 @@ pub async fn container_templates(
     storage::list_templates(&db)
 }
 
-/// List all containers, optionally filtered.
+/// List all containers, optionally filtered. Served from the container
+/// cache while the Docker event stream is attached.
 #[tauri::command]
 pub async fn container_list(
     state: State<'_, AppState>,
     filters: Option<ContainerListFilters>,
 ) -> Result<Vec<ContainerInfo>, AppError> {
     let docker = state.ensure_docker_once().await?;
-    container::list(&docker, true, filters).await
+    state.containers.list(&docker, filters).await
 }
 
 /// Create a new container.
@@ -52,11 +57,28 @@ pub async fn container_create(
 
     // Audit
     let db = state.db.lock_or_recover()?;
-    audit::log_action(&db, &AuditAction::ContainerCreate, &result.id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerCreate,
+        &result.id,
+        None,
+        &audit::current_user(),
+    )?;
 
     Ok(result)
 }
 
+/// Dry-run a create/run request for the creation wizard. Every problem is
+/// returned as an issue instead of failing on the first one.
+#[tauri::command]
+pub async fn docker_run_validate(
+    state: State<'_, AppState>,
+    request: ContainerCreateRequest,
+) -> Result<RunValidationReport, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::validate_create(&docker, &request).await
+}
+
 /// Start a stopped container.
 #[tauri::command]
 pub async fn container_start(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
@@ -64,10 +86,32 @@ pub async fn container_start(state: State<'_, AppState>, id: String) -> Result<(
     container::start(&docker, &id).await?;
 
     let db = state.db.lock_or_recover()?;
-    audit::log_action(&db, &AuditAction::ContainerStart, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerStart,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
     Ok(())
 }
 
+/// Open a running container's published web port in the default browser.
+///
+/// `port` selects a container (or host) port; otherwise a well-known web
+/// port is used. Returns the opened URL.
+#[tauri::command]
+pub async fn open_in_browser(
+    state: State<'_, AppState>,
+    id: String,
+    port: Option<u16>,
+) -> Result<String, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    let url = container::web_url(&docker, &id, port).await?;
+    cratebay_core::browser::open_url(&url)?;
+    Ok(url)
+}
+
 /// Stop a running container.
 #[tauri::command]
 pub async fn container_stop(
@@ -79,7 +123,188 @@ pub async fn container_stop(
     container::stop(&docker, &id, timeout).await?;
 
     let db = state.db.lock_or_recover()?;
-    audit::log_action(&db, &AuditAction::ContainerStop, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerStop,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
+    Ok(())
+}
+
+/// Restart a container.
+#[tauri::command]
+pub async fn container_restart(
+    state: State<'_, AppState>,
+    id: String,
+    timeout: Option<u32>,
+) -> Result<(), AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::restart(&docker, &id, timeout).await?;
+
+    let db = state.db.lock_or_recover()?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerRestart,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
+    Ok(())
+}
+
+/// Pause a running container.
+#[tauri::command]
+pub async fn container_pause(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::pause(&docker, &id).await?;
+
+    let db = state.db.lock_or_recover()?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerPause,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
+    Ok(())
+}
+
+/// Resume a paused container.
+#[tauri::command]
+pub async fn container_unpause(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::unpause(&docker, &id).await?;
+
+    let db = state.db.lock_or_recover()?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerUnpause,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
+    Ok(())
+}
+
+/// Checkpoint a running container with CRIU. It stops unless
+/// `leave_running`.
+#[tauri::command]
+pub async fn container_checkpoint_create(
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+    leave_running: Option<bool>,
+) -> Result<(), AppError> {
+    state.ensure_docker_once().await?;
+    let leave_running = leave_running.unwrap_or(false);
+    checkpoint::create(&id, &name, leave_running).await?;
+
+    let db = state.db.lock_or_recover()?;
+    let details =
+        serde_json::json!({ "checkpoint": name, "leaveRunning": leave_running }).to_string();
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerCheckpoint,
+        &id,
+        Some(&details),
+        &audit::current_user(),
+    )?;
+    Ok(())
+}
+
+/// Names of a container's checkpoints.
+#[tauri::command]
+pub async fn container_checkpoint_list(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<String>, AppError> {
+    state.ensure_docker_once().await?;
+    checkpoint::list(&id).await
+}
+
+/// Start a stopped container from a checkpoint.
+#[tauri::command]
+pub async fn container_checkpoint_restore(
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+) -> Result<(), AppError> {
+    state.ensure_docker_once().await?;
+    checkpoint::restore(&id, &name).await?;
+
+    let db = state.db.lock_or_recover()?;
+    let details = serde_json::json!({ "checkpoint": name }).to_string();
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerRestore,
+        &id,
+        Some(&details),
+        &audit::current_user(),
+    )?;
+    Ok(())
+}
+
+/// Delete a checkpoint.
+#[tauri::command]
+pub async fn container_checkpoint_delete(
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+) -> Result<(), AppError> {
+    state.ensure_docker_once().await?;
+    checkpoint::remove(&id, &name).await
+}
+
+/// Rename a container.
+#[tauri::command]
+pub async fn container_rename(
+    state: State<'_, AppState>,
+    id: String,
+    name: String,
+) -> Result<(), AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::rename(&docker, &id, &name).await?;
+
+    let db = state.db.lock_or_recover()?;
+    let details = serde_json::json!({ "name": name }).to_string();
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerRename,
+        &id,
+        Some(&details),
+        &audit::current_user(),
+    )?;
+    Ok(())
+}
+
+/// Change CPU/memory limits of a container.
+#[tauri::command]
+pub async fn container_update(
+    state: State<'_, AppState>,
+    id: String,
+    cpu_cores: Option<u32>,
+    memory_mb: Option<u64>,
+    memory_swap_mb: Option<i64>,
+) -> Result<(), AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::update_resources(&docker, &id, cpu_cores, memory_mb, memory_swap_mb).await?;
+
+    let db = state.db.lock_or_recover()?;
+    let details = serde_json::json!({
+        "cpuCores": cpu_cores,
+        "memoryMb": memory_mb,
+        "memorySwapMb": memory_swap_mb,
+    })
+    .to_string();
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerUpdate,
+        &id,
+        Some(&details),
+        &audit::current_user(),
+    )?;
     Ok(())
 }
 
@@ -94,7 +319,13 @@ pub async fn container_delete(
     container::delete(&docker, &id, force.unwrap_or(false)).await?;
 
     let db = state.db.lock_or_recover()?;
-    audit::log_action(&db, &AuditAction::ContainerDelete, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerDelete,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
     Ok(())
 }
 
@@ -110,7 +341,13 @@ pub async fn container_exec(
     let result = container::exec(&docker, &id, cmd, working_dir).await?;
 
     let db = state.db.lock_or_recover()?;
-    audit::log_action(&db, &AuditAction::ContainerExec, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerExec,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
 
     Ok(result)
 }
@@ -146,6 +383,27 @@ pub async fn container_stats(
     container::stats(&docker, &id).await
 }
 
+/// Commit a container to a new image. Returns the image ID.
+#[tauri::command]
+pub async fn container_commit(
+    state: State<'_, AppState>,
+    id: String,
+    request: ContainerCommitRequest,
+) -> Result<String, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::commit(&docker, &id, &request).await
+}
+
+/// List processes running inside a container.
+#[tauri::command]
+pub async fn container_top(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<ContainerProcess>, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::top(&docker, &id).await
+}
+
 /// Execute a command with streaming output via Tauri Events.
 ///
 /// Output is emitted as events on `exec:stream:{channel_id}`.
@@ -170,7 +428,13 @@ pub async fn container_exec_stream(
 
     // Audit
     let db = state.db.lock_or_recover()?;
-    audit::log_action(&db, &AuditAction::ContainerExec, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ContainerExec,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
 
     Ok(())
 }
@@ -182,16 +446,62 @@ pub async fn image_list(state: State<'_, AppState>) -> Result<Vec<LocalImageInfo
     container::image_list(&docker).await
 }
 
-/// Search images from registry (Docker Hub via Docker API).
+/// Search images from registries.
+///
+/// Without `source`, Docker Hub and Quay are searched concurrently and the
+/// merged results carry per-source errors. A single `source` keeps the old
+/// behaviour (Docker Hub prefers the Docker Engine search API).
 #[tauri::command]
 pub async fn image_search(
     state: State<'_, AppState>,
     query: String,
     limit: Option<u32>,
-) -> Result<Vec<ImageSearchResult>, AppError> {
+    source: Option<String>,
+) -> Result<ImageSearchResponse, AppError> {
     let term = query.trim();
     let limit = limit.map(u64::from);
 
+    let Some(source) = source.filter(|s| !s.trim().is_empty()) else {
+        let merged = cratebay_core::registry::search_sources(
+            cratebay_core::registry::DEFAULT_SEARCH_SOURCES,
+            term,
+            limit,
+        )
+        .await;
+        return match merged {
+            Err(AppError::Runtime(e)) => match state.require_docker() {
+                // Registries unreachable from the host: the engine may still
+                // reach Docker Hub (e.g. through its own proxy settings).
+                Ok(docker) if cratebay_core::docker::is_available(&docker).await => {
+                    tracing::warn!("Registry search failed, using Docker Engine: {}", e);
+                    Ok(ImageSearchResponse {
+                        results: container::image_search(&docker, term, limit).await?,
+                        errors: Vec::new(),
+                    })
+                }
+                _ => Err(AppError::Runtime(e)),
+            },
+            other => other,
+        };
+    };
+
+    let results = if cratebay_core::registry::search_provider(&source)?.source() != "dockerhub" {
+        // GHCR / GitLab / Quay are searched over their HTTP APIs only.
+        cratebay_core::registry::search_images(&source, term, limit).await?
+    } else {
+        search_dockerhub(&state, term, limit).await?
+    };
+    Ok(ImageSearchResponse {
+        results,
+        errors: Vec::new(),
+    })
+}
+
+async fn search_dockerhub(
+    state: &AppState,
+    term: &str,
+    limit: Option<u64>,
+) -> Result<Vec<ImageSearchResult>, AppError> {
     // Prefer Docker Engine search when Docker is already reachable. Avoid
     // provisioning/starting the runtime just for image search — fallback to
     // Docker Hub HTTP API if Docker isn't available.
@@ -208,6 +518,72 @@ pub async fn image_search(
     container::image_search_dockerhub(term, limit).await
 }
 
+/// Fetch an image manifest from its registry without pulling.
+///
+/// Lists available platforms for multi-arch images and resolves layers and
+/// labels for `platform` (default: the engine VM's platform).
+#[tauri::command]
+pub async fn image_manifest(
+    reference: String,
+    platform: Option<String>,
+    auth: Option<RegistryAuth>,
+) -> Result<RemoteImageManifest, AppError> {
+    cratebay_core::registry::fetch_manifest(&reference, platform.as_deref(), auth).await
+}
+
+/// Describe a remote image before pulling: Docker Hub overview or Quay
+/// README plus its `org.opencontainers.image.*` annotations.
+#[tauri::command]
+pub async fn image_details(
+    reference: String,
+    auth: Option<RegistryAuth>,
+) -> Result<ImageDetails, AppError> {
+    cratebay_core::registry::fetch_image_details(&reference, auth).await
+}
+
+/// List tags of a repository in its registry, newest versions first.
+/// `artifacts` reads each tag's manifest so charts and SBOMs can be grouped.
+#[tauri::command]
+pub async fn image_tags(
+    reference: String,
+    filter: Option<String>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    all: Option<bool>,
+    artifacts: Option<bool>,
+) -> Result<RegistryTagList, AppError> {
+    let options = cratebay_core::registry::TagListOptions {
+        filter,
+        page,
+        page_size,
+        all: all.unwrap_or(false),
+        artifacts: artifacts.unwrap_or(false),
+    };
+    cratebay_core::registry::list_tags(&reference, &options, None).await
+}
+
+/// List the repositories of a self-hosted registry, grouped into a tree
+/// for the registry browser.
+#[tauri::command]
+pub async fn registry_catalog(
+    registry: String,
+    filter: Option<String>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    all: Option<bool>,
+    auth: Option<RegistryAuth>,
+) -> Result<RegistryCatalog, AppError> {
+    let options = cratebay_core::registry::CatalogOptions {
+        filter,
+        page,
+        page_size,
+        all: all.unwrap_or(false),
+    };
+    let mut catalog = cratebay_core::registry::list_catalog(&registry, &options, auth).await?;
+    catalog.tree = Some(cratebay_core::registry::catalog_tree(&catalog.repositories));
+    Ok(catalog)
+}
+
 /// Inspect a local image by id or reference.
 #[tauri::command]
 pub async fn image_inspect(
@@ -218,6 +594,24 @@ pub async fn image_inspect(
     container::image_inspect(&docker, &id).await
 }
 
+/// Layers of an image with the instruction and size of each, for the
+/// size treemap. `remote` reads them from the registry (compressed sizes)
+/// instead of the local image store.
+#[tauri::command]
+pub async fn image_layers(
+    state: State<'_, AppState>,
+    reference: String,
+    remote: Option<bool>,
+    platform: Option<String>,
+    auth: Option<RegistryAuth>,
+) -> Result<ImageLayers, AppError> {
+    if remote.unwrap_or(false) {
+        return cratebay_core::registry::fetch_history(&reference, platform.as_deref(), auth).await;
+    }
+    let docker = state.ensure_docker_once().await?;
+    container::image_history(&docker, &reference).await
+}
+
 /// Remove a local image.
 #[tauri::command]
 pub async fn image_remove(
@@ -245,7 +639,9 @@ pub async fn image_tag(
 /// Spawns the pull operation in the background so it doesn't block other Tauri commands.
 /// Progress and completion are reported via `image:pull:{channel_id}` events.
 ///
-/// Returns immediately with the channel_id for the frontend to listen on.
+/// Returns immediately with the channel_id for the frontend to listen on; the
+/// pull is also listed as a background task with the same id. `platform`
+/// (e.g. `linux/amd64`) pulls that variant instead of the runtime's own.
 #[tauri::command]
 pub async fn image_pull(
     app: tauri::AppHandle,
@@ -253,12 +649,28 @@ pub async fn image_pull(
     image: String,
     mirrors: Option<Vec<String>>,
     channel_id: Option<String>,
+    platform: Option<String>,
 ) -> Result<String, AppError> {
     let docker = state.ensure_docker_once().await?;
+    // Mirrors from the frontend are Docker Hub mirrors; other registries
+    // (and Docker Hub without frontend mirrors) use config.toml.
+    let is_docker_hub = cratebay_core::registry::ImageReference::parse(&image)
+        .map(|r| r.registry == cratebay_core::registry::DOCKER_HUB_REGISTRY)
+        .unwrap_or(true);
+    let mirrors = mirrors.filter(|_| is_docker_hub).or_else(|| {
+        let configured = cratebay_core::config::Config::load_or_default().mirrors_for_image(&image);
+        (!configured.is_empty()).then_some(configured)
+    });
     let channel_id = channel_id.unwrap_or_else(|| format!("pull-{}", uuid::Uuid::new_v4()));
     let ch_id = channel_id.clone();
     let app_handle = app.clone();
     let image_clone = image.clone();
+    let task = state.tasks.start(
+        &app,
+        channel_id.clone(),
+        TaskKind::ImagePull,
+        format!("Pull {}", image),
+    );
 
     // Emit start event
     let _ = app.emit(
@@ -279,10 +691,12 @@ pub async fn image_pull(
     tokio::spawn(async move {
         let app = app_handle;
         let event_name = crate::events::image_pull_progress_event(&ch_id);
+        let started = std::time::Instant::now();
 
         // Progress callback that emits Tauri events
         let app_for_progress = app.clone();
         let event_for_progress = event_name.clone();
+        let task_for_progress = task.clone();
         let progress_cb: container::PullProgressCallback = std::sync::Arc::new(move |progress| {
             let percent = if progress.total_bytes > 0 {
                 ((progress.current_bytes as f64 / progress.total_bytes as f64) * 100.0) as u32
@@ -290,6 +704,10 @@ pub async fn image_pull(
                 0
             };
             let status = translate_pull_status(&progress.status);
+            task_for_progress.progress(
+                (progress.total_bytes > 0).then_some(percent),
+                status.clone(),
+            );
             let _ = app_for_progress.emit(
                 &event_for_progress,
                 &crate::events::ImagePullProgress {
@@ -305,14 +723,41 @@ pub async fn image_pull(
             );
         });
 
-        let result = match mirrors {
-            Some(ref m) if !m.is_empty() => {
-                container::image_pull_with_mirrors(&docker, &image_clone, m, Some(progress_cb))
-                    .await
-            }
-            _ => container::image_pull(&docker, &image_clone, None, Some(progress_cb)).await,
-        };
+        let result = task
+            .run(async {
+                match mirrors {
+                    Some(ref m) if !m.is_empty() => {
+                        container::image_pull_with_mirrors(
+                            &docker,
+                            &image_clone,
+                            m,
+                            platform.as_deref(),
+                            Some(progress_cb),
+                            Some(task.cancel_token()),
+                        )
+                        .await
+                    }
+                    _ => {
+                        container::image_pull(
+                            &docker,
+                            &image_clone,
+                            None,
+                            platform.as_deref(),
+                            Some(progress_cb),
+                            Some(task.cancel_token()),
+                        )
+                        .await
+                    }
+                }
+            })
+            .await;
 
+        crate::notify::notify_operation_finished(
+            &app,
+            started,
+            format!("Pull of {} finished", image_clone),
+            result.as_ref().err().map(|e| e.to_string()),
+        );
         match result {
             Ok(()) => {
                 let _ = app.emit(
@@ -353,6 +798,296 @@ pub async fn image_pull(
     Ok(channel_id)
 }
 
+/// Push a local image to its registry (non-blocking).
+///
+/// Progress and completion are reported via `image:push:{channel_id}` events
+/// using the same payload as image pulls. Returns the channel_id immediately;
+/// it is also the id of the background task.
+#[tauri::command]
+pub async fn image_push(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    image: String,
+    auth: Option<RegistryAuth>,
+    channel_id: Option<String>,
+) -> Result<String, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    let channel_id = channel_id.unwrap_or_else(|| format!("push-{}", uuid::Uuid::new_v4()));
+    let event_name = crate::events::image_push_progress_event(&channel_id);
+    let task = state.tasks.start(
+        &app,
+        channel_id.clone(),
+        TaskKind::ImagePush,
+        format!("Push {}", image),
+    );
+
+    tokio::spawn(async move {
+        let started = std::time::Instant::now();
+        let app_for_progress = app.clone();
+        let event_for_progress = event_name.clone();
+        let task_for_progress = task.clone();
+        let progress_cb: container::PullProgressCallback = std::sync::Arc::new(move |progress| {
+            let percent = if progress.total_bytes > 0 {
+                ((progress.current_bytes as f64 / progress.total_bytes as f64) * 100.0) as u32
+            } else {
+                0
+            };
+            task_for_progress.progress(
+                (progress.total_bytes > 0).then_some(percent),
+                progress.status.clone(),
+            );
+            let _ = app_for_progress.emit(
+                &event_for_progress,
+                &crate::events::ImagePullProgress {
+                    current_layer: 0,
+                    total_layers: 0,
+                    progress_percent: percent,
+                    status: progress.status,
+                    complete: false,
+                    error: None,
+                    current_bytes: progress.current_bytes,
+                    total_bytes: progress.total_bytes,
+                },
+            );
+        });
+
+        let result = task
+            .run(container::image_push(
+                &docker,
+                &image,
+                auth.as_ref(),
+                Some(progress_cb),
+            ))
+            .await;
+        let (status, error) = match result {
+            Ok(()) => {
+                let state = app.state::<AppState>();
+                if let Ok(db) = state.db.lock_or_recover() {
+                    let _ = audit::log_action(
+                        &db,
+                        &AuditAction::ImagePush,
+                        &image,
+                        None,
+                        &audit::current_user(),
+                    );
+                }
+                (format!("镜像 {} 推送完成", &image), None)
+            }
+            Err(e) => {
+                tracing::error!("Image push failed for {}: {}", image, e);
+                (format!("镜像推送失败: {}", e), Some(e.to_string()))
+            }
+        };
+        crate::notify::notify_operation_finished(
+            &app,
+            started,
+            format!("Push of {} finished", image),
+            error.clone(),
+        );
+        let _ = app.emit(
+            &event_name,
+            &crate::events::ImagePullProgress {
+                current_layer: 0,
+                total_layers: 0,
+                progress_percent: if error.is_none() { 100 } else { 0 },
+                status,
+                complete: true,
+                error,
+                current_bytes: 0,
+                total_bytes: 0,
+            },
+        );
+    });
+
+    Ok(channel_id)
+}
+
+/// Progress callback reporting bytes written to an archive task.
+fn archive_progress(task: &crate::tasks::TaskHandle) -> container::SaveProgressCallback {
+    let task = task.clone();
+    std::sync::Arc::new(move |bytes| {
+        task.progress(
+            None,
+            format!("{:.1} MB written", bytes as f64 / 1_048_576.0),
+        )
+    })
+}
+
+/// Save images to a tar archive on the host. Returns the archive size in bytes.
+///
+/// Runs as a background task (`task_id`, default generated) so the save can
+/// be followed and cancelled from the jobs panel; a cancelled save leaves no
+/// partial archive behind.
+#[tauri::command]
+pub async fn image_save(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    images: Vec<String>,
+    output_path: String,
+    gzip: Option<bool>,
+    task_id: Option<String>,
+) -> Result<u64, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    let task = state.tasks.start(
+        &app,
+        task_id.unwrap_or_else(|| format!("save-{}", uuid::Uuid::new_v4())),
+        TaskKind::ImageSave,
+        format!("Save {}", images.join(", ")),
+    );
+    let on_progress = archive_progress(&task);
+    task.run(container::image_save(
+        &docker,
+        &images,
+        &output_path,
+        gzip.unwrap_or(false),
+        Some(on_progress),
+    ))
+    .await
+}
+
+/// Export a container's filesystem to a tar archive on the host. Returns the
+/// archive size in bytes. Runs as a background task like [`image_save`].
+#[tauri::command]
+pub async fn container_export(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    output_path: String,
+    gzip: Option<bool>,
+    task_id: Option<String>,
+) -> Result<u64, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    let task = state.tasks.start(
+        &app,
+        task_id.unwrap_or_else(|| format!("export-{}", uuid::Uuid::new_v4())),
+        TaskKind::ContainerExport,
+        format!("Export {}", id),
+    );
+    let on_progress = archive_progress(&task);
+    task.run(container::export(
+        &docker,
+        &id,
+        &output_path,
+        gzip.unwrap_or(false),
+        Some(on_progress),
+    ))
+    .await
+}
+
+/// Create an image from a filesystem tar archive. Returns the image ID.
+#[tauri::command]
+pub async fn image_import(
+    state: State<'_, AppState>,
+    path: String,
+    reference: Option<String>,
+    changes: Option<Vec<String>>,
+) -> Result<String, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    container::image_import(
+        &docker,
+        &path,
+        reference.as_deref(),
+        &changes.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Build an image from a Dockerfile.
+///
+/// Runs in the background; build output and completion are reported via
+/// `image:build:{channel_id}` events. Returns the channel_id immediately; it
+/// is also the id of the background task.
+#[tauri::command]
+pub async fn image_build(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    request: ImageBuildRequest,
+    channel_id: Option<String>,
+) -> Result<String, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    let channel_id = channel_id.unwrap_or_else(|| format!("build-{}", uuid::Uuid::new_v4()));
+    let event_name = crate::events::image_build_event(&channel_id);
+    let task = state.tasks.start(
+        &app,
+        channel_id.clone(),
+        TaskKind::ImageBuild,
+        format!(
+            "Build {}",
+            request.tags.first().unwrap_or(&request.context_dir)
+        ),
+    );
+
+    tokio::spawn(async move {
+        let started = std::time::Instant::now();
+        let app_for_output = app.clone();
+        let event_for_output = event_name.clone();
+        let task_for_output = task.clone();
+        let output_cb: container::BuildOutputCallback = std::sync::Arc::new(move |line| {
+            task_for_output.progress(None, line.trim_end());
+            let _ = app_for_output.emit(
+                &event_for_output,
+                &crate::events::ImageBuildLog {
+                    line: Some(line),
+                    complete: false,
+                    image_id: None,
+                    error: None,
+                },
+            );
+        });
+
+        let payload = match task
+            .run(container::image_build(&docker, &request, Some(output_cb)))
+            .await
+        {
+            Ok(image_id) => crate::events::ImageBuildLog {
+                line: None,
+                complete: true,
+                image_id: Some(image_id),
+                error: None,
+            },
+            Err(e) => {
+                tracing::error!("Image build failed for {}: {}", request.context_dir, e);
+                crate::events::ImageBuildLog {
+                    line: None,
+                    complete: true,
+                    image_id: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        crate::notify::notify_operation_finished(
+            &app,
+            started,
+            format!("Build of {} finished", request.context_dir),
+            payload.error.clone(),
+        );
+        let _ = app.emit(&event_name, &payload);
+    });
+
+    Ok(channel_id)
+}
+
+/// Lint the Dockerfile of a build request for the build dialog, before
+/// `image_build` is called. Needs no Docker connection.
+#[tauri::command]
+pub async fn image_build_lint(request: ImageBuildRequest) -> Result<DockerfileLint, AppError> {
+    dockerfile_lint::lint_build(&request)
+}
+
+/// Check a compose file. Problems are returned as diagnostics with line
+/// numbers; only an unreadable file is an error.
+#[tauri::command]
+pub async fn compose_validate(path: String) -> Result<ComposeValidation, AppError> {
+    compose::validate_file(std::path::Path::new(&path))
+}
+
+/// Convert a compose file to Kubernetes manifests (multi-document YAML).
+/// Nothing is converted when the file has errors.
+#[tauri::command]
+pub async fn compose_convert_k8s(path: String) -> Result<ComposeConversion, AppError> {
+    compose::convert_kubernetes_file(std::path::Path::new(&path))
+}
+
 /// Translate Docker pull status messages to Chinese.
 fn translate_pull_status(status: &str) -> String {
     // Docker API status messages are like "Downloading", "Extracting",
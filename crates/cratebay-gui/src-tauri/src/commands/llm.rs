@@ -60,7 +60,7 @@ pub async fn llm_provider_create(
         &AuditAction::ProviderCreate,
         &id,
         Some(&request.name),
-        "user",
+        &audit::current_user(),
     )?;
 
     storage::get_provider(&db, &id)
@@ -97,7 +97,13 @@ pub async fn llm_provider_update(
         storage::save_api_key(&db, &id, api_key.as_bytes(), &[0u8; 12], &hint)?;
     }
 
-    audit::log_action(&db, &AuditAction::ProviderUpdate, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ProviderUpdate,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
 
     storage::get_provider(&db, &id)
 }
@@ -107,7 +113,13 @@ pub async fn llm_provider_update(
 pub async fn llm_provider_delete(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
     let db = state.db.lock_or_recover()?;
     storage::delete_provider(&db, &id)?;
-    audit::log_action(&db, &AuditAction::ProviderDelete, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ProviderDelete,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
     Ok(())
 }
 
@@ -136,7 +148,7 @@ pub async fn llm_models_toggle(
         &AuditAction::ModelToggle,
         &format!("{}:{}", provider_id, model_id),
         Some(if enabled { "enabled" } else { "disabled" }),
-        "user",
+        &audit::current_user(),
     )?;
     Ok(())
 }
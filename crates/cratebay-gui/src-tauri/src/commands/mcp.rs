@@ -45,7 +45,7 @@ pub async fn mcp_server_add(
             &AuditAction::McpServerStart,
             &id,
             Some(&config.name),
-            "user",
+            &audit::current_user(),
         )?;
         result
     };
@@ -86,7 +86,13 @@ pub async fn mcp_server_remove(state: State<'_, AppState>, id: String) -> Result
     // Remove from SQLite
     let db = state.db.lock_or_recover()?;
     storage::remove_mcp_server(&db, &id)?;
-    audit::log_action(&db, &AuditAction::McpServerStop, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::McpServerStop,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
     Ok(())
 }
 
@@ -108,7 +114,7 @@ pub async fn mcp_server_start(
         &AuditAction::McpServerStart,
         &id,
         Some(&status.name),
-        "user",
+        &audit::current_user(),
     )?;
 
     Ok(status)
@@ -123,7 +129,13 @@ pub async fn mcp_server_stop(state: State<'_, AppState>, id: String) -> Result<(
 
     // Audit log
     let db = state.db.lock_or_recover()?;
-    audit::log_action(&db, &AuditAction::McpServerStop, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::McpServerStop,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
 
     Ok(())
 }
@@ -5,3 +5,5 @@ pub mod llm;
 pub mod mcp;
 pub mod storage;
 pub mod system;
+pub mod task;
+pub mod terminal;
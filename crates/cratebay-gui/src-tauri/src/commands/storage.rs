@@ -1,44 +1,100 @@
 //! Storage-related Tauri commands (settings, API keys, conversations).
 
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
+use crate::events::{event_names, SettingsChanged};
 use crate::state::AppState;
+use cratebay_core::config::{self, Config, CONFIG_KEYS};
 use cratebay_core::error::AppError;
 use cratebay_core::models::{
-    AuditAction, ConversationDetail, ConversationSummary, SaveMessageRequest,
+    AuditAction, AuditLogEntry, AuditLogQuery, ConversationDetail, ConversationSummary,
+    SaveMessageRequest,
 };
 use cratebay_core::MutexExt;
-use cratebay_core::{audit, storage};
+use cratebay_core::{audit, autostart, storage};
+
+/// Settings key that registers the app as a login item; its value is the
+/// registration state rather than a stored preference.
+const AUTOSTART_KEY: &str = "autostart";
 
 /// Get a setting value by key.
+///
+/// Keys of `config.toml` ([`CONFIG_KEYS`], e.g. `theme`, `docker_host`,
+/// `default_vm_cpus`) are read from the config file shared with the CLI,
+/// falling back to a value stored in the database before the key moved
+/// there. `autostart` reports whether the login item is registered. Other
+/// keys are GUI-only and live in the database.
 #[tauri::command]
 pub async fn settings_get(
     state: State<'_, AppState>,
     key: String,
 ) -> Result<Option<String>, AppError> {
+    if key == AUTOSTART_KEY {
+        return Ok(Some(autostart::is_enabled().to_string()));
+    }
+    if CONFIG_KEYS.contains(&key.as_str()) {
+        if let Some(value) = Config::load()?.get(&key)? {
+            return Ok(Some(value));
+        }
+    }
     let db = state.db.lock_or_recover()?;
     storage::get_setting(&db, &key)
 }
 
-/// Update a setting value.
+/// Set a setting value and emit `settings:changed` so every window can
+/// refresh. `config.toml` keys are validated and an empty value unsets
+/// them; `autostart=true` registers the app to start hidden at login. See
+/// [`settings_get`].
 #[tauri::command]
-pub async fn settings_update(
+pub async fn settings_set(
+    app: AppHandle,
     state: State<'_, AppState>,
     key: String,
     value: String,
 ) -> Result<(), AppError> {
-    let db = state.db.lock_or_recover()?;
-    storage::set_setting(&db, &key, &value)?;
-    audit::log_action(
-        &db,
-        &AuditAction::SettingsUpdate,
-        &key,
-        Some(&value),
-        "user",
-    )?;
+    let stored = if CONFIG_KEYS.contains(&key.as_str()) {
+        let mut config = Config::load()?;
+        config.set(&key, &value)?;
+        config.save()?;
+        config.get(&key)?
+    } else if key == AUTOSTART_KEY {
+        let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        let enabled = config::parse_bool(&key, value)?.unwrap_or(false);
+        autostart::set_enabled(enabled, &std::env::current_exe()?)?;
+        Some(enabled.to_string())
+    } else {
+        let db = state.db.lock_or_recover()?;
+        storage::set_setting(&db, &key, &value)?;
+        Some(value)
+    };
+    {
+        let db = state.db.lock_or_recover()?;
+        audit::log_action(
+            &db,
+            &AuditAction::SettingsUpdate,
+            &key,
+            stored.as_deref(),
+            &audit::current_user(),
+        )?;
+    }
+    let _ = app.emit(
+        event_names::SETTINGS_CHANGED,
+        &SettingsChanged { key, value: stored },
+    );
     Ok(())
 }
 
+/// Update a setting value. Deprecated alias of [`settings_set`].
+#[tauri::command]
+pub async fn settings_update(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    key: String,
+    value: String,
+) -> Result<(), AppError> {
+    settings_set(app, state, key, value).await
+}
+
 /// Save an encrypted API key for a provider.
 #[tauri::command]
 pub async fn api_key_save(
@@ -50,7 +106,13 @@ pub async fn api_key_save(
     let hint = storage::compute_key_hint(&api_key);
     // Store as plaintext bytes for now (real encryption with keyring to be added)
     storage::save_api_key(&db, &provider_id, api_key.as_bytes(), &[0u8; 12], &hint)?;
-    audit::log_action(&db, &AuditAction::ApiKeySave, &provider_id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ApiKeySave,
+        &provider_id,
+        None,
+        &audit::current_user(),
+    )?;
     Ok(())
 }
 
@@ -62,7 +124,13 @@ pub async fn api_key_delete(
 ) -> Result<(), AppError> {
     let db = state.db.lock_or_recover()?;
     storage::delete_api_key(&db, &provider_id)?;
-    audit::log_action(&db, &AuditAction::ApiKeyDelete, &provider_id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ApiKeyDelete,
+        &provider_id,
+        None,
+        &audit::current_user(),
+    )?;
     Ok(())
 }
 
@@ -103,7 +171,7 @@ pub async fn conversation_create(
         &AuditAction::ConversationCreate,
         &id,
         Some(&title),
-        "user",
+        &audit::current_user(),
     )?;
 
     storage::get_conversation(&db, &id)
@@ -114,7 +182,13 @@ pub async fn conversation_create(
 pub async fn conversation_delete(state: State<'_, AppState>, id: String) -> Result<(), AppError> {
     let db = state.db.lock_or_recover()?;
     storage::delete_conversation(&db, &id)?;
-    audit::log_action(&db, &AuditAction::ConversationDelete, &id, None, "user")?;
+    audit::log_action(
+        &db,
+        &AuditAction::ConversationDelete,
+        &id,
+        None,
+        &audit::current_user(),
+    )?;
     Ok(())
 }
 
@@ -170,3 +244,24 @@ pub async fn conversation_update_title(
     let db = state.db.lock_or_recover()?;
     storage::update_conversation_title(&db, &session_id, &title)
 }
+
+/// Query the audit log (lifecycle history), newest first.
+///
+/// `since` accepts the same values as `cratebay history --since`.
+#[tauri::command]
+pub async fn audit_log_list(
+    state: State<'_, AppState>,
+    target: Option<String>,
+    action: Option<String>,
+    since: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<AuditLogEntry>, AppError> {
+    let query = AuditLogQuery {
+        action,
+        target,
+        since: since.as_deref().map(audit::parse_since).transpose()?,
+        limit: limit.unwrap_or(100),
+    };
+    let db = state.db.lock_or_recover()?;
+    storage::query_audit_logs(&db, &query)
+}
@@ -4,13 +4,22 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use bollard::Docker;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 
+use crate::events;
 use crate::state::AppState;
-use cratebay_core::docker;
+use crate::tasks::{TaskHandle, TaskKind};
+use cratebay_core::config::{Config, HostEntry, LOCAL_HOST};
+use cratebay_core::docker::EngineMode;
 use cratebay_core::error::AppError;
-use cratebay_core::models::{DockerStatus, RuntimeStatusInfo, SystemInfo};
+use cratebay_core::models::{
+    AuditAction, DockerStatus, ExecResult, RuntimeMetrics, RuntimeStatusInfo, SystemInfo,
+};
+use cratebay_core::runtime::ops::{self, RuntimeOp};
 use cratebay_core::runtime::{RuntimeConfig, RuntimeState};
+use cratebay_core::status::{self, SystemStatus};
+use cratebay_core::update::{self, UpdateInfo};
+use cratebay_core::{audit, container, docker, engine};
 use cratebay_core::{storage, MutexExt};
 
 const SETTINGS_KEY_RUNTIME_HTTP_PROXY: &str = "runtimeHttpProxy";
@@ -28,7 +37,7 @@ pub async fn system_info(state: State<'_, AppState>) -> Result<SystemInfo, AppEr
 
     let db_size_bytes = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
 
-    let log_path = state.data_dir.join("cratebay.log");
+    let log_path = state.data_dir.join(cratebay_core::logging::LOG_FILE_NAME);
     let log_path_str = log_path.to_string_lossy().to_string();
 
     Ok(SystemInfo {
@@ -43,6 +52,60 @@ pub async fn system_info(state: State<'_, AppState>) -> Result<SystemInfo, AppEr
     })
 }
 
+/// Check GitHub for a newer CrateBay release.
+///
+/// The desktop app is updated through its installer; the frontend links to
+/// `release_url`. `cratebay update apply` updates the CLI in place.
+#[tauri::command]
+pub async fn update_check(include_prerelease: Option<bool>) -> Result<UpdateInfo, AppError> {
+    update::check(include_prerelease.unwrap_or(false)).await
+}
+
+/// Change the log filter of the running app (e.g. `debug` or
+/// `info,cratebay_core::runtime=trace`) without a restart. The change lasts
+/// until the app exits; `settings_set("log_level")` persists a level.
+/// Returns the filter now in effect.
+#[tauri::command]
+pub async fn log_set_level(filter: String) -> Result<String, AppError> {
+    cratebay_core::logging::set_filter(&filter)
+}
+
+/// List registered Docker hosts (`[hosts]` in config.toml), marking the
+/// active one.
+#[tauri::command]
+pub async fn host_list() -> Result<Vec<HostEntry>, AppError> {
+    Ok(Config::load()?.host_entries())
+}
+
+/// Switch the managed Docker engine to the registered host `name`, or back
+/// to the local engine when `name` is `None`. The selection is saved as
+/// `active_host` and the shared Docker client is replaced once the new
+/// engine answers; if it does not, the previous selection is restored.
+#[tauri::command]
+pub async fn host_use(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: Option<String>,
+) -> Result<String, AppError> {
+    let previous = Config::load()?;
+    let mut config = previous.clone();
+    config.set("active_host", name.as_deref().unwrap_or(LOCAL_HOST))?;
+    config.save()?;
+    let docker = match engine::ensure_docker(state.runtime.as_ref(), Default::default()).await {
+        Ok(docker) => docker,
+        Err(e) => {
+            previous.save()?;
+            return Err(e);
+        }
+    };
+    state.set_docker(Some(docker));
+    let _ = app.emit(events::event_names::DOCKER_CONNECTED, true);
+    Ok(match name {
+        Some(name) => format!("Connected to host '{}'", name),
+        None => "Connected to the local Docker engine".to_string(),
+    })
+}
+
 /// Get Docker connection status.
 ///
 /// Checks the current Docker connection in AppState (may have been
@@ -57,70 +120,21 @@ pub async fn docker_status(state: State<'_, AppState>) -> Result<DockerStatus, A
         guard.clone()
     };
 
-    match docker_opt {
-        Some(d) => {
-            let is_available = docker::is_available(&d).await;
-            if is_available {
-                let version_info = docker::version(&d).await.ok();
-                let source = "built-in".to_string();
-                let socket_path = Some(built_in_docker_endpoint(&state));
-                Ok(DockerStatus {
-                    connected: true,
-                    version: version_info.as_ref().and_then(|v| v.version.clone()),
-                    api_version: version_info.as_ref().and_then(|v| v.api_version.clone()),
-                    os: version_info.as_ref().and_then(|v| v.os.clone()),
-                    arch: version_info.as_ref().and_then(|v| v.arch.clone()),
-                    source,
-                    socket_path,
-                })
-            } else {
-                Ok(DockerStatus {
-                    connected: false,
-                    version: None,
-                    api_version: None,
-                    os: None,
-                    arch: None,
-                    source: "none".to_string(),
-                    socket_path: None,
-                })
-            }
-        }
-        None => Ok(DockerStatus {
-            connected: false,
-            version: None,
-            api_version: None,
-            os: None,
-            arch: None,
-            source: "none".to_string(),
-            socket_path: None,
-        }),
-    }
+    let engine = docker::selected_engine();
+    let source = match engine.mode {
+        EngineMode::BuiltIn => "built-in",
+        _ => "external",
+    };
+    Ok(status::docker_status(docker_opt.as_ref(), source, engine.endpoint).await)
 }
 
-fn built_in_docker_endpoint(state: &State<'_, AppState>) -> String {
-    #[cfg(target_os = "linux")]
-    {
-        cratebay_core::runtime::linux::linux_docker_host()
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        cratebay_core::runtime::windows::windows_docker_host()
-    }
-
-    #[cfg(all(unix, not(target_os = "linux")))]
-    {
-        state
-            .runtime
-            .docker_socket_path()
-            .to_string_lossy()
-            .to_string()
-    }
-
-    #[cfg(not(any(unix, windows)))]
-    {
-        "<unsupported>".to_string()
-    }
+/// Get a unified status snapshot (Docker, runtime, hypervisor, paths).
+///
+/// Same model as `cratebay system status --format json`.
+#[tauri::command]
+pub async fn system_status(state: State<'_, AppState>) -> Result<SystemStatus, AppError> {
+    let docker = docker_status(state.clone()).await?;
+    Ok(status::collect(state.runtime.as_ref(), docker).await)
 }
 
 /// Get built-in runtime status.
@@ -185,46 +199,126 @@ pub async fn runtime_status(state: State<'_, AppState>) -> Result<RuntimeStatusI
     })
 }
 
-/// Manually start the built-in runtime.
+/// Returned by [`runtime_start`] before the runtime has booted.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeStartTicket {
+    /// `Starting` while the background boot runs.
+    pub state: RuntimeState,
+    /// Id of the `runtimeStart` background task.
+    pub task_id: String,
+}
+
+/// Manually start the built-in runtime (non-blocking).
 ///
 /// This command allows the frontend to trigger runtime start
-/// (e.g., from Settings page or a retry button).
+/// (e.g., from Settings page or a retry button). It returns as soon as the
+/// runtime operation lock is held; provisioning, boot and the Docker wait
+/// run as a `runtimeStart` background task that reports progress with
+/// `task:update`, announces `runtime:state-change` and ends with
+/// `docker:connected` on success.
 #[tauri::command]
-pub async fn runtime_start(state: State<'_, AppState>) -> Result<String, AppError> {
+pub async fn runtime_start(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<RuntimeStartTicket, AppError> {
     tracing::info!("Manual runtime start requested");
 
     apply_runtime_http_proxy_env(&state)?;
+    // Fail fast instead of queueing: the command must not block.
+    let op = ops::begin(RuntimeOp::Start, Duration::ZERO).await?;
 
-    // Step 1: Detect
     let current = state.runtime.get_state().await?;
     tracing::info!("Runtime current state: {:?}", current);
 
-    // Step 2: Provision if needed
+    let task_id = format!("runtime-start-{}", uuid::Uuid::new_v4());
+    let task = state.tasks.start(
+        &app,
+        task_id.clone(),
+        TaskKind::RuntimeStart,
+        "Start runtime",
+    );
+    emit_runtime_state(&app, Some(current.clone()), RuntimeState::Starting);
+
+    tokio::spawn(async move {
+        let _op = op;
+        let started = std::time::Instant::now();
+        let result = task.run(boot_runtime(&app, &task, current)).await;
+        let next = match &result {
+            Ok(_) => None,
+            Err(AppError::Cancelled(_)) => app.state::<AppState>().runtime.get_state().await.ok(),
+            Err(e) => {
+                tracing::error!("Runtime start failed: {}", e);
+                Some(RuntimeState::Error(e.to_string()))
+            }
+        };
+        if let Some(next) = next {
+            emit_runtime_state(&app, Some(RuntimeState::Starting), next);
+        }
+        crate::notify::notify_operation_finished(
+            &app,
+            started,
+            "Runtime start finished",
+            result.err().map(|e| e.to_string()),
+        );
+    });
+
+    Ok(RuntimeStartTicket {
+        state: RuntimeState::Starting,
+        task_id,
+    })
+}
+
+/// Provision if needed, start the runtime and wait for Docker.
+async fn boot_runtime(
+    app: &AppHandle,
+    task: &TaskHandle,
+    current: RuntimeState,
+) -> Result<String, AppError> {
+    let state = app.state::<AppState>();
+
+    // Step 1: Provision if needed
     if current == RuntimeState::None {
         tracing::info!("Runtime needs provisioning...");
+        let task_for_progress = task.clone();
         state
             .runtime
-            .provision(Box::new(|progress| {
+            .provision(Box::new(move |progress| {
                 tracing::info!(
                     "Provision: {} - {:.1}% - {}",
                     progress.stage,
                     progress.percent,
                     progress.message
                 );
+                task_for_progress.progress(Some(progress.percent as u32), progress.message);
             }))
             .await?;
     }
 
-    // Step 3: Start
+    // Step 2: Start
+    task.progress(None, "Booting runtime VM");
     state.runtime.start().await?;
+    {
+        let db = state.db.lock_or_recover()?;
+        audit::log_action(
+            &db,
+            &AuditAction::RuntimeStart,
+            "runtime",
+            None,
+            &audit::current_user(),
+        )?;
+    }
     tracing::info!("Runtime started, waiting for Docker...");
 
-    // Step 4: Wait for Docker and update AppState
+    // Step 3: Wait for Docker and update AppState
+    task.progress(None, "Waiting for Docker");
     let deadline = std::time::Instant::now() + std::time::Duration::from_secs(45);
     while std::time::Instant::now() < deadline {
         if let Some(docker) = try_connect_runtime_docker(state.runtime.as_ref()).await {
             tracing::info!("Docker connected via built-in runtime");
             state.set_docker(Some(Arc::new(docker)));
+            emit_runtime_state(app, Some(RuntimeState::Starting), RuntimeState::Ready);
+            let _ = app.emit(events::event_names::DOCKER_CONNECTED, true);
             return Ok("Runtime started and Docker connected".to_string());
         }
         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
@@ -233,6 +327,19 @@ pub async fn runtime_start(state: State<'_, AppState>) -> Result<String, AppErro
     Ok("Runtime started but Docker not yet responsive".to_string())
 }
 
+/// Announce a runtime state transition caused by a command, in the same
+/// form as the health monitor's `runtime:state-change`.
+fn emit_runtime_state(app: &AppHandle, previous: Option<RuntimeState>, current: RuntimeState) {
+    let _ = app.emit(
+        events::event_names::RUNTIME_STATE_CHANGE,
+        &events::RuntimeStateChange {
+            previous,
+            current,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        },
+    );
+}
+
 async fn try_connect_runtime_docker(
     runtime: &dyn cratebay_core::runtime::RuntimeManager,
 ) -> Option<Docker> {
@@ -293,7 +400,18 @@ async fn try_connect_runtime_docker(
 #[tauri::command]
 pub async fn runtime_stop(state: State<'_, AppState>) -> Result<String, AppError> {
     tracing::info!("Manual runtime stop requested");
+    let _op = ops::begin(RuntimeOp::Stop, ops::DEFAULT_OP_WAIT).await?;
     state.runtime.stop().await?;
+    {
+        let db = state.db.lock_or_recover()?;
+        audit::log_action(
+            &db,
+            &AuditAction::RuntimeStop,
+            "runtime",
+            None,
+            &audit::current_user(),
+        )?;
+    }
 
     // Clear Docker connection since runtime is stopping
     state.set_docker(None);
@@ -301,6 +419,66 @@ pub async fn runtime_stop(state: State<'_, AppState>) -> Result<String, AppError
     Ok("Runtime stopped".to_string())
 }
 
+/// Change the built-in runtime CPU/memory. The runtime must be stopped;
+/// the new allocation applies on next start.
+#[tauri::command]
+pub async fn runtime_update_resources(
+    state: State<'_, AppState>,
+    cpu_cores: Option<u32>,
+    memory_mb: Option<u64>,
+) -> Result<RuntimeConfig, AppError> {
+    tracing::info!(
+        "Runtime resource update requested: cpus={:?}, memory_mb={:?}",
+        cpu_cores,
+        memory_mb
+    );
+    let _op = ops::begin(RuntimeOp::UpdateResources, ops::DEFAULT_OP_WAIT).await?;
+    state.runtime.update_resources(cpu_cores, memory_mb).await
+}
+
+/// Grow the built-in runtime disk. The runtime must be stopped.
+#[tauri::command]
+pub async fn runtime_resize_disk(
+    state: State<'_, AppState>,
+    disk_gb: u32,
+) -> Result<String, AppError> {
+    tracing::info!("Runtime disk resize requested: {} GB", disk_gb);
+    let _op = ops::begin(RuntimeOp::ResizeDisk, ops::DEFAULT_OP_WAIT).await?;
+    state.runtime.resize_disk(disk_gb).await?;
+    Ok(format!("Runtime disk resized to {} GB", disk_gb))
+}
+
+/// Sample live CPU, memory, disk and network usage inside the runtime VM.
+#[tauri::command]
+pub async fn runtime_metrics(state: State<'_, AppState>) -> Result<RuntimeMetrics, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    cratebay_core::runtime::metrics::collect(&docker).await
+}
+
+/// Run a command inside the built-in runtime VM and capture its output.
+#[tauri::command]
+pub async fn runtime_exec(
+    state: State<'_, AppState>,
+    cmd: Vec<String>,
+    timeout_secs: Option<u64>,
+) -> Result<ExecResult, AppError> {
+    let docker = state.ensure_docker_once().await?;
+    let result =
+        container::runtime_exec(&docker, cmd.clone(), timeout_secs.map(Duration::from_secs))
+            .await?;
+
+    let db = state.db.lock_or_recover()?;
+    audit::log_action(
+        &db,
+        &AuditAction::RuntimeExec,
+        "runtime",
+        Some(&serde_json::json!({ "cmd": cmd, "exitCode": result.exit_code }).to_string()),
+        &audit::current_user(),
+    )?;
+
+    Ok(result)
+}
+
 /// Convert a [`RuntimeState`] enum to its string representation for the API.
 fn format_runtime_state(state: &RuntimeState) -> String {
     match state {
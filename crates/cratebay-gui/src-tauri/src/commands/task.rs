@@ -0,0 +1,21 @@
+//! Background task commands for the jobs panel (see [`crate::tasks`]).
+
+use tauri::State;
+
+use cratebay_core::error::AppError;
+
+use crate::state::AppState;
+use crate::tasks::TaskInfo;
+
+/// List running and recently finished tasks, newest first.
+#[tauri::command]
+pub async fn task_list(state: State<'_, AppState>) -> Result<Vec<TaskInfo>, AppError> {
+    state.tasks.list()
+}
+
+/// Cancel a running task. Returns false when it had already finished; the
+/// final state arrives as a `task:update` event.
+#[tauri::command]
+pub async fn task_cancel(state: State<'_, AppState>, id: String) -> Result<bool, AppError> {
+    state.tasks.cancel(&id)
+}
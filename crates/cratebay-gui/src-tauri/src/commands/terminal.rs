@@ -0,0 +1,161 @@
+//! Embedded terminal commands (see [`cratebay_core::terminal`]).
+//!
+//! The frontend picks the session id, subscribes to
+//! `terminal:output:{session_id}` and then calls `terminal_open`, so no
+//! output is missed. A session is dropped when its shell exits or on
+//! `terminal_close`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter, State};
+
+use cratebay_core::error::AppError;
+use cratebay_core::terminal::{self, TerminalEvent, TerminalSession, TerminalTarget};
+use cratebay_core::MutexExt;
+
+use crate::events::{terminal_output_event, TerminalOutput};
+use crate::state::AppState;
+
+const DEFAULT_COLS: u16 = 80;
+const DEFAULT_ROWS: u16 = 24;
+
+fn session(state: &AppState, session_id: &str) -> Result<Arc<TerminalSession>, AppError> {
+    state
+        .terminals
+        .lock_or_recover()?
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound {
+            entity: "terminal session".to_string(),
+            id: session_id.to_string(),
+        })
+}
+
+/// Open a shell in `container`, or on the runtime VM when it is omitted.
+/// `shell` is run with `/bin/sh -c` (default: bash or sh).
+#[tauri::command]
+pub async fn terminal_open(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: String,
+    container: Option<String>,
+    shell: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+) -> Result<(), AppError> {
+    if state.terminals.lock_or_recover()?.contains_key(&session_id) {
+        return Err(AppError::Validation(format!(
+            "Terminal session '{}' already exists",
+            session_id
+        )));
+    }
+    let docker = state.ensure_docker_once().await?;
+    let target = match container.filter(|c| !c.trim().is_empty()) {
+        Some(id) => TerminalTarget::Container(id),
+        None => TerminalTarget::Runtime,
+    };
+
+    let event_name = terminal_output_event(&session_id);
+    let sessions = state.terminals.clone();
+    let exited_id = session_id.clone();
+    let exit_docker = docker.clone();
+    // Set before the exit handler looks the session up, so an exit that
+    // comes before the session is registered is seen below.
+    let exited = Arc::new(AtomicBool::new(false));
+    let exit_seen = exited.clone();
+    let session = terminal::open(
+        &docker,
+        target,
+        shell,
+        cols.unwrap_or(DEFAULT_COLS),
+        rows.unwrap_or(DEFAULT_ROWS),
+        move |event| {
+            let payload = match event {
+                TerminalEvent::Output(data) => TerminalOutput {
+                    data: Some(data),
+                    exited: false,
+                    exit_code: None,
+                },
+                TerminalEvent::Exit(exit_code) => {
+                    exit_seen.store(true, Ordering::SeqCst);
+                    // Release the session (and a runtime helper) once the shell is gone.
+                    let finished = sessions
+                        .lock_or_recover()
+                        .ok()
+                        .and_then(|mut s| s.remove(&exited_id));
+                    if let Some(session) = finished {
+                        let docker = exit_docker.clone();
+                        tauri::async_runtime::spawn(async move {
+                            let _ = session.close(&docker).await;
+                        });
+                    }
+                    TerminalOutput {
+                        data: None,
+                        exited: true,
+                        exit_code,
+                    }
+                }
+            };
+            let _ = app.emit(&event_name, &payload);
+        },
+    )
+    .await?;
+
+    let session = Arc::new(session);
+    state
+        .terminals
+        .lock_or_recover()?
+        .insert(session_id.clone(), session.clone());
+    // The shell may have exited before the session was registered; the
+    // exit handler then found nothing to release. Whichever side removes
+    // the session closes it.
+    if exited.load(Ordering::SeqCst) {
+        let finished = state.terminals.lock_or_recover()?.remove(&session_id);
+        if let Some(session) = finished {
+            session.close(&docker).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Send input (keystrokes or pasted text) to a terminal session.
+#[tauri::command]
+pub async fn terminal_write(
+    state: State<'_, AppState>,
+    session_id: String,
+    data: String,
+) -> Result<(), AppError> {
+    session(&state, &session_id)?.write(data.as_bytes()).await
+}
+
+/// Resize a terminal session's TTY.
+#[tauri::command]
+pub async fn terminal_resize(
+    state: State<'_, AppState>,
+    session_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), AppError> {
+    let docker = state.ensure_docker_once().await?;
+    session(&state, &session_id)?
+        .resize(&docker, cols, rows)
+        .await
+}
+
+/// Close a terminal session. Closing an unknown or already exited session
+/// is not an error.
+#[tauri::command]
+pub async fn terminal_close(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), AppError> {
+    let session = state.terminals.lock_or_recover()?.remove(&session_id);
+    match session {
+        Some(session) => {
+            let docker = state.ensure_docker_once().await?;
+            session.close(&docker).await
+        }
+        None => Ok(()),
+    }
+}
@@ -10,12 +10,42 @@ pub mod event_names {
     /// `image:pull:{channel_id}` where channel_id is unique per pull operation.
     pub const IMAGE_PULL_PROGRESS_PREFIX: &str = "image:pull";
 
+    /// Prefix for image push progress events. The full event name is
+    /// `image:push:{channel_id}`; payload is [`ImagePullProgress`](super::ImagePullProgress).
+    pub const IMAGE_PUSH_PROGRESS_PREFIX: &str = "image:push";
+
+    /// Prefix for image build log events. The full event name is
+    /// `image:build:{channel_id}`.
+    pub const IMAGE_BUILD_PREFIX: &str = "image:build";
+
     #[allow(dead_code)]
     pub const CONTAINER_STATUS_CHANGE: &str = "container:status-change";
     #[allow(dead_code)]
     pub const MCP_CONNECTION_CHANGE: &str = "mcp:connection-change";
     pub const RUNTIME_HEALTH: &str = "runtime:health";
+    /// Emitted only when the runtime state transitions (e.g. `Starting` →
+    /// `Ready`), so the frontend can refresh reactively instead of polling.
+    pub const RUNTIME_STATE_CHANGE: &str = "runtime:state-change";
     pub const RUNTIME_PROVISION: &str = "runtime:provision";
+    /// Docker container/image/network/volume events; payload is
+    /// [`cratebay_core::models::DockerEvent`].
+    pub const DOCKER_EVENT: &str = "docker:event";
+    /// Docker became available after runtime auto-start; payload is `true`.
+    pub const DOCKER_CONNECTED: &str = "docker:connected";
+    /// User-facing notification; payload is [`AppNotification`](super::AppNotification).
+    pub const APP_NOTIFICATION: &str = "app:notification";
+
+    /// Prefix for terminal output events. The full event name is
+    /// `terminal:output:{session_id}`; payload is [`TerminalOutput`](super::TerminalOutput).
+    pub const TERMINAL_OUTPUT_PREFIX: &str = "terminal:output";
+
+    /// A setting was changed by `settings_set`; payload is
+    /// [`SettingsChanged`](super::SettingsChanged).
+    pub const SETTINGS_CHANGED: &str = "settings:changed";
+
+    /// Background task registered, progressed or finished; payload is
+    /// [`TaskInfo`](crate::tasks::TaskInfo).
+    pub const TASK_UPDATE: &str = "task:update";
 }
 
 /// Build a scoped LLM stream event name.
@@ -28,6 +58,21 @@ pub fn image_pull_progress_event(channel_id: &str) -> String {
     format!("{}:{}", event_names::IMAGE_PULL_PROGRESS_PREFIX, channel_id)
 }
 
+/// Build a scoped image push progress event name.
+pub fn image_push_progress_event(channel_id: &str) -> String {
+    format!("{}:{}", event_names::IMAGE_PUSH_PROGRESS_PREFIX, channel_id)
+}
+
+/// Build a scoped image build log event name.
+pub fn image_build_event(channel_id: &str) -> String {
+    format!("{}:{}", event_names::IMAGE_BUILD_PREFIX, channel_id)
+}
+
+/// Build a scoped terminal output event name.
+pub fn terminal_output_event(session_id: &str) -> String {
+    format!("{}:{}", event_names::TERMINAL_OUTPUT_PREFIX, session_id)
+}
+
 /// Image pull progress update event payload.
 #[derive(serde::Serialize, Clone, Debug)]
 pub struct ImagePullProgress {
@@ -48,3 +93,62 @@ pub struct ImagePullProgress {
     /// Total bytes expected (current chunk/layer).
     pub total_bytes: u64,
 }
+
+/// Image build log event payload.
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageBuildLog {
+    /// Build output line (None on the completion event).
+    pub line: Option<String>,
+    /// Whether the build is finished (either success or failure).
+    pub complete: bool,
+    /// Built image ID on success.
+    pub image_id: Option<String>,
+    /// Error message if the build failed.
+    pub error: Option<String>,
+}
+
+/// Runtime state transition event payload.
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeStateChange {
+    /// State observed by the previous health check (None on first check).
+    pub previous: Option<cratebay_core::runtime::RuntimeState>,
+    /// Newly observed state.
+    pub current: cratebay_core::runtime::RuntimeState,
+    /// Timestamp of the transition (RFC 3339).
+    pub timestamp: String,
+}
+
+/// Notification event payload (see [`crate::notify`]).
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AppNotification {
+    /// `runtimeCrash`, `containerOom`, `diskFull` or `operationComplete`.
+    pub category: String,
+    /// Toast level: `info`, `success`, `warning` or `error`.
+    pub level: String,
+    pub title: String,
+    pub message: Option<String>,
+}
+
+/// Terminal output event payload.
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalOutput {
+    /// Output text (None on the exit event).
+    pub data: Option<String>,
+    /// Whether the shell exited; no further events follow.
+    pub exited: bool,
+    /// Exit code of the shell, when known.
+    pub exit_code: Option<i64>,
+}
+
+/// Settings change event payload.
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChanged {
+    pub key: String,
+    /// New value as `settings_get` returns it (None when unset).
+    pub value: Option<String>,
+}
@@ -4,7 +4,10 @@
 
 mod commands;
 mod events;
+mod notify;
 mod state;
+mod tasks;
+mod tray;
 
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -64,6 +67,9 @@ async fn get_responsive_shared_docker(
 ///    - If it responds, broadcast `Ready` immediately.
 /// 2. Only fall back to `runtime.health_check()` when the shared client is
 ///    unresponsive or absent.
+///
+/// Every tick emits `runtime:health`; `runtime:state-change` is emitted
+/// additionally whenever the observed state differs from the previous tick.
 fn start_runtime_health_monitor(
     app_handle: tauri::AppHandle,
     runtime: Arc<dyn cratebay_core::runtime::RuntimeManager>,
@@ -71,6 +77,7 @@ fn start_runtime_health_monitor(
     tauri::async_runtime::spawn(async move {
         // 20-second interval — faster feedback without excessive overhead.
         let mut interval = tokio::time::interval(Duration::from_secs(20));
+        let mut last_state: Option<cratebay_core::runtime::RuntimeState> = None;
         loop {
             interval.tick().await;
 
@@ -86,6 +93,7 @@ fn start_runtime_health_monitor(
                     docker_source: Some("builtin".to_string()),
                 };
                 let _ = app_handle.emit(events::event_names::RUNTIME_HEALTH, &health);
+                emit_runtime_state_change(&app_handle, &mut last_state, &health);
                 continue;
             }
 
@@ -114,6 +122,244 @@ fn start_runtime_health_monitor(
             }
 
             let _ = app_handle.emit(events::event_names::RUNTIME_HEALTH, &health);
+            emit_runtime_state_change(&app_handle, &mut last_state, &health);
+        }
+    });
+}
+
+/// Emit `runtime:state-change` if `health` reports a different state than
+/// the last one observed by the monitor.
+fn emit_runtime_state_change(
+    app_handle: &tauri::AppHandle,
+    last_state: &mut Option<cratebay_core::runtime::RuntimeState>,
+    health: &cratebay_core::runtime::HealthStatus,
+) {
+    if last_state.as_ref() == Some(&health.runtime_state) {
+        return;
+    }
+
+    let change = events::RuntimeStateChange {
+        previous: last_state.replace(health.runtime_state.clone()),
+        current: health.runtime_state.clone(),
+        timestamp: health.last_check.clone(),
+    };
+    tracing::debug!(
+        "Runtime state changed: {:?} -> {:?}",
+        change.previous,
+        change.current
+    );
+    if let (
+        Some(cratebay_core::runtime::RuntimeState::Ready),
+        cratebay_core::runtime::RuntimeState::Error(reason),
+    ) = (&change.previous, &change.current)
+    {
+        notify::notify(
+            app_handle,
+            notify::NotificationCategory::RuntimeCrash,
+            "error",
+            "The container runtime stopped unexpectedly",
+            Some(reason.clone()),
+        );
+    }
+    let _ = app_handle.emit(events::event_names::RUNTIME_STATE_CHANGE, &change);
+}
+
+/// Forward Docker object events to the frontend as `docker:event`.
+///
+/// Waits until a shared Docker client exists, then follows its event stream;
+/// when the stream drops (runtime restart, reconnect) it retries with the
+/// current client.
+fn start_docker_event_forwarder(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let docker = app_handle.state::<AppState>().require_docker().ok();
+            let Some(docker) = docker else {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                continue;
+            };
+
+            let emit_handle = app_handle.clone();
+            let cache = Arc::clone(&app_handle.state::<AppState>().containers);
+            cache.set_live(true);
+            let events_cache = Arc::clone(&cache);
+            // Events may have been missed while detached.
+            let dns = Arc::clone(&app_handle.state::<AppState>().dns);
+            dns.invalidate();
+            let result = cratebay_core::container::watch_events(&docker, move |event| {
+                events_cache.apply_event(&event);
+                dns.apply_event(&event);
+                notify::notify_docker_event(&emit_handle, &event);
+                let _ = emit_handle.emit(events::event_names::DOCKER_EVENT, &event);
+            })
+            .await;
+            cache.set_live(false);
+            if let Err(e) = result {
+                tracing::debug!("Docker event stream ended: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    });
+}
+
+/// Serve `<container>.docker.cratebay` on `127.0.0.1:<dns_port>`.
+///
+/// Records are re-listed whenever the event forwarder marks them stale.
+/// Another process holding the port (e.g. `cratebay dns serve`) only
+/// disables the GUI's server.
+fn start_container_dns(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let port = cratebay_core::dns::dns_port();
+        let socket = match tokio::net::UdpSocket::bind((std::net::Ipv4Addr::LOCALHOST, port)).await
+        {
+            Ok(socket) => socket,
+            Err(e) => {
+                tracing::warn!("Container DNS disabled: cannot bind port {}: {}", port, e);
+                return;
+            }
+        };
+        let records = Arc::clone(&app_handle.state::<AppState>().dns);
+        let refresh = Arc::clone(&records);
+        tauri::async_runtime::spawn(async move {
+            loop {
+                refresh.stale().await;
+                let Ok(docker) = app_handle.state::<AppState>().require_docker() else {
+                    continue;
+                };
+                if let Err(e) = refresh.refresh(&docker).await {
+                    tracing::debug!("Refreshing container DNS records failed: {}", e);
+                }
+            }
+        });
+        let cancel = cratebay_core::cancel::CancellationToken::new();
+        if let Err(e) = cratebay_core::dns::serve(socket, &records, &cancel).await {
+            tracing::warn!("Container DNS stopped: {}", e);
+        }
+    });
+}
+
+/// Warn when the runtime disk fills up.
+///
+/// Samples guest metrics every 5 minutes while a Docker client exists and
+/// notifies once per crossing of [`notify::DISK_FULL_PERCENT`]; the warning
+/// re-arms after usage drops 5 points below the threshold.
+fn start_disk_usage_monitor(app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(300));
+        let mut warned = false;
+        loop {
+            interval.tick().await;
+            let Ok(docker) = app_handle.state::<AppState>().require_docker() else {
+                continue;
+            };
+            let metrics = match cratebay_core::runtime::metrics::collect(&docker).await {
+                Ok(metrics) => metrics,
+                Err(e) => {
+                    tracing::debug!("Disk usage sample failed: {}", e);
+                    continue;
+                }
+            };
+            if metrics.disk_total_gb <= 0.0 {
+                continue;
+            }
+            let percent = metrics.disk_used_gb / metrics.disk_total_gb * 100.0;
+            if percent >= notify::DISK_FULL_PERCENT && !warned {
+                warned = true;
+                notify::notify(
+                    &app_handle,
+                    notify::NotificationCategory::DiskFull,
+                    "warning",
+                    format!("Runtime disk is {:.0}% full", percent),
+                    Some(format!(
+                        "{:.1} of {:.1} GB used. Remove unused images or grow the disk with `cratebay runtime resize`.",
+                        metrics.disk_used_gb, metrics.disk_total_gb
+                    )),
+                );
+            } else if percent < notify::DISK_FULL_PERCENT - 5.0 {
+                warned = false;
+            }
+        }
+    });
+}
+
+/// Return idle runtime VM memory to the host.
+///
+/// Runs a [`balloon`](cratebay_core::runtime::balloon) policy round every
+/// minute while the built-in runtime is ready and serves Docker. Only the
+/// VZ runtime has a balloon target; QEMU returns freed memory by itself.
+fn start_balloon_policy(app_handle: tauri::AppHandle) {
+    if !cfg!(target_os = "macos") {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if cratebay_core::docker::selected_engine().mode
+                != cratebay_core::docker::EngineMode::BuiltIn
+            {
+                continue;
+            }
+            let state = app_handle.state::<AppState>();
+            if !matches!(
+                state.runtime.get_state().await,
+                Ok(cratebay_core::runtime::RuntimeState::Ready)
+            ) {
+                continue;
+            }
+            let Ok(docker) = state.require_docker() else {
+                continue;
+            };
+            match cratebay_core::runtime::balloon::rebalance(state.runtime.as_ref(), &docker).await
+            {
+                Ok(Some(target_mb)) => {
+                    tracing::debug!("Runtime balloon target set to {} MB", target_mb)
+                }
+                Ok(None) => {}
+                Err(e) => tracing::debug!("Balloon policy round failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Recover from host sleep.
+///
+/// On wake, re-checks the Docker connection (the runner's forwards may
+/// have dropped it) and sets the runtime clock, which stood still while
+/// the host slept, to the host's.
+fn start_wake_watcher(app_handle: tauri::AppHandle) {
+    use cratebay_core::runtime::clock;
+
+    tauri::async_runtime::spawn(async move {
+        let mut wake = clock::WakeDetector::new();
+        let mut interval = tokio::time::interval(clock::WAKE_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(slept) = wake.check() else {
+                continue;
+            };
+            tracing::info!("Host woke after ~{}s asleep", slept.as_secs());
+            let state = app_handle.state::<AppState>();
+            state.recheck_docker();
+            if cratebay_core::docker::selected_engine().mode
+                != cratebay_core::docker::EngineMode::BuiltIn
+                || !matches!(
+                    state.runtime.get_state().await,
+                    Ok(cratebay_core::runtime::RuntimeState::Ready)
+                )
+            {
+                continue;
+            }
+            let docker = match state.ensure_docker_once().await {
+                Ok(docker) => docker,
+                Err(e) => {
+                    tracing::warn!("Runtime clock not synced after wake: {}", e);
+                    continue;
+                }
+            };
+            match clock::sync_guest_clock(&docker).await {
+                Ok(offset) => tracing::info!("Runtime clock synced (was off by {}s)", offset),
+                Err(e) => tracing::warn!("Runtime clock not synced after wake: {}", e),
+            }
         }
     });
 }
@@ -230,14 +476,50 @@ fn apply_runtime_http_proxy_env(app_handle: &tauri::AppHandle) {
     );
 }
 
+/// Value of `--profile <name>` / `--profile=<name>` on the command line.
+fn profile_arg() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next();
+        }
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
 fn main() {
-    // Initialize tracing
-    let env_filter = tracing_subscriber::EnvFilter::from_default_env();
-    let env_filter = match "cratebay=info".parse() {
-        Ok(directive) => env_filter.add_directive(directive),
-        Err(_) => env_filter,
-    };
-    tracing_subscriber::fmt().with_env_filter(env_filter).init();
+    // The profile scopes every config and data path, so it comes first.
+    if let Some(profile) = profile_arg() {
+        if let Err(e) = cratebay_core::storage::validate_profile(&profile) {
+            eprintln!("Fatal: {}", e);
+            std::process::exit(1);
+        }
+        std::env::set_var(cratebay_core::storage::PROFILE_ENV, profile);
+    }
+
+    // Load config.toml before anything reads RUST_LOG / DOCKER_HOST.
+    let config = cratebay_core::config::Config::load();
+
+    // Initialize tracing (RUST_LOG wins over config log_level). The log
+    // file keeps INFO history for bug reports after a crash.
+    let log_file = cratebay_core::logging::default_log_file().ok();
+    cratebay_core::logging::init(
+        config.as_ref().unwrap_or(&Default::default()),
+        "cratebay=info",
+        cratebay_core::logging::LogOutput::Stdout,
+        log_file.as_deref(),
+    );
+
+    let config = config.unwrap_or_else(|e| {
+        tracing::warn!("Ignoring config file: {}", e);
+        Default::default()
+    });
+    if let Some(host) = config.docker_host() {
+        std::env::set_var("DOCKER_HOST", host);
+    }
 
     // Initialize database
     let db_path = match cratebay_core::storage::default_db_path() {
@@ -370,10 +652,15 @@ fn main() {
 
     let app_state = AppState {
         docker: Arc::new(Mutex::new(docker.clone())),
-        docker_init: Arc::new(tokio::sync::OnceCell::new()),
+        docker_connect: Arc::new(tokio::sync::Mutex::new(None)),
+        docker_checked_at: Arc::new(Mutex::new(None)),
         db: Arc::new(Mutex::new(conn)),
         data_dir,
         llm_cancel_tokens: Arc::new(Mutex::new(std::collections::HashMap::new())),
+        terminals: Arc::new(Mutex::new(std::collections::HashMap::new())),
+        tasks: Arc::new(tasks::TaskManager::default()),
+        containers: Arc::new(cratebay_core::container_cache::ContainerCache::new()),
+        dns: Arc::new(cratebay_core::dns::DnsRecords::new()),
         runtime: runtime.clone(),
         mcp_manager,
     };
@@ -396,12 +683,32 @@ fn main() {
             // can use the configured proxy without requiring a manual runtime restart.
             apply_runtime_http_proxy_env(app.handle());
 
+            // Launched at login: stay in the tray. The runtime auto-start
+            // below still runs, so Docker and containers with a restart
+            // policy come up without opening the window.
+            if std::env::args().any(|arg| arg == cratebay_core::autostart::HIDDEN_ARG) {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+            }
+
             // Start periodic health monitor (every 30s)
             let app_handle = app.handle().clone();
             let health_runtime = runtime.clone();
             start_runtime_health_monitor(app_handle, health_runtime);
             tracing::info!("Runtime health monitor started");
 
+            start_docker_event_forwarder(app.handle().clone());
+            start_container_dns(app.handle().clone());
+            start_disk_usage_monitor(app.handle().clone());
+            start_balloon_policy(app.handle().clone());
+            start_wake_watcher(app.handle().clone());
+
+            match tray::create_tray(app.handle()) {
+                Ok(()) => tray::start_tray_sync(app.handle().clone()),
+                Err(e) => tracing::warn!("Failed to create tray icon: {}", e),
+            }
+
             // ── Runtime auto-start (background, non-blocking) ────────
             // If Docker is not yet connected, try to start the built-in
             // runtime and then reconnect Docker through the runtime socket.
@@ -470,7 +777,7 @@ fn main() {
                                 tracing::info!("Docker connected via ensured container engine");
                                 let state = auto_start_handle.state::<AppState>();
                                 state.set_docker(Some(docker.clone()));
-                                let _ = auto_start_handle.emit("docker:connected", true);
+                                let _ = auto_start_handle.emit(events::event_names::DOCKER_CONNECTED, true);
 
                                 // Preload bundle images in background
                                 let resource_dir = auto_start_handle
@@ -565,20 +872,46 @@ fn main() {
             commands::container::container_templates,
             commands::container::container_list,
             commands::container::container_create,
+            commands::container::docker_run_validate,
             commands::container::container_start,
             commands::container::container_stop,
+            commands::container::open_in_browser,
+            commands::container::container_restart,
+            commands::container::container_pause,
+            commands::container::container_unpause,
+            commands::container::container_checkpoint_create,
+            commands::container::container_checkpoint_list,
+            commands::container::container_checkpoint_restore,
+            commands::container::container_checkpoint_delete,
+            commands::container::container_rename,
+            commands::container::container_update,
             commands::container::container_delete,
             commands::container::container_exec,
             commands::container::container_exec_stream,
             commands::container::container_logs,
             commands::container::container_inspect,
             commands::container::container_stats,
+            commands::container::container_top,
+            commands::container::container_commit,
             commands::container::image_list,
             commands::container::image_search,
+            commands::container::image_manifest,
+            commands::container::image_details,
+            commands::container::image_tags,
+            commands::container::registry_catalog,
             commands::container::image_inspect,
+            commands::container::image_layers,
             commands::container::image_remove,
             commands::container::image_tag,
             commands::container::image_pull,
+            commands::container::image_push,
+            commands::container::image_build,
+            commands::container::image_build_lint,
+            commands::container::compose_validate,
+            commands::container::compose_convert_k8s,
+            commands::container::image_save,
+            commands::container::image_import,
+            commands::container::container_export,
             // LLM
             commands::llm::llm_proxy_stream,
             commands::llm::llm_proxy_cancel,
@@ -592,6 +925,7 @@ fn main() {
             commands::llm::llm_models_toggle,
             // Storage
             commands::storage::settings_get,
+            commands::storage::settings_set,
             commands::storage::settings_update,
             commands::storage::api_key_save,
             commands::storage::api_key_delete,
@@ -601,6 +935,7 @@ fn main() {
             commands::storage::conversation_delete,
             commands::storage::conversation_save_message,
             commands::storage::conversation_update_title,
+            commands::storage::audit_log_list,
             // MCP
             commands::mcp::mcp_server_list,
             commands::mcp::mcp_server_add,
@@ -612,10 +947,26 @@ fn main() {
             commands::mcp::mcp_export_client_config,
             // System
             commands::system::system_info,
+            commands::system::update_check,
+            commands::system::log_set_level,
+            commands::system::host_list,
+            commands::system::host_use,
             commands::system::docker_status,
+            commands::system::system_status,
             commands::system::runtime_status,
             commands::system::runtime_start,
             commands::system::runtime_stop,
+            commands::system::runtime_update_resources,
+            commands::system::runtime_resize_disk,
+            commands::system::runtime_metrics,
+            commands::system::runtime_exec,
+            // Terminal commands
+            commands::terminal::terminal_open,
+            commands::terminal::terminal_write,
+            commands::terminal::terminal_resize,
+            commands::terminal::terminal_close,
+            commands::task::task_list,
+            commands::task::task_cancel,
             // Debug
             #[cfg(debug_assertions)]
             commands::system::webview_debug_report,
@@ -0,0 +1,122 @@
+//! User-facing notifications for runtime and long-running operation events.
+//!
+//! Notifications are emitted as `app:notification` and rendered by the
+//! frontend toast system. Each category can be muted from Settings; the
+//! toggle is stored under its settings key (`"false"` disables it).
+
+use std::time::{Duration, Instant};
+
+use tauri::{Emitter, Manager};
+
+use cratebay_core::models::DockerEvent;
+use cratebay_core::{storage, MutexExt};
+
+use crate::events::{self, AppNotification};
+use crate::state::AppState;
+
+/// Operations shorter than this finish without a notification.
+pub const LONG_OPERATION_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Runtime disk usage (percent) that raises a disk-full warning.
+pub const DISK_FULL_PERCENT: f32 = 90.0;
+
+/// Notification categories, each with its own settings toggle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationCategory {
+    RuntimeCrash,
+    ContainerOom,
+    DiskFull,
+    OperationComplete,
+}
+
+impl NotificationCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationCategory::RuntimeCrash => "runtimeCrash",
+            NotificationCategory::ContainerOom => "containerOom",
+            NotificationCategory::DiskFull => "diskFull",
+            NotificationCategory::OperationComplete => "operationComplete",
+        }
+    }
+
+    /// Settings key holding the toggle, e.g. `notifyRuntimeCrash`.
+    fn setting_key(self) -> &'static str {
+        match self {
+            NotificationCategory::RuntimeCrash => "notifyRuntimeCrash",
+            NotificationCategory::ContainerOom => "notifyContainerOom",
+            NotificationCategory::DiskFull => "notifyDiskFull",
+            NotificationCategory::OperationComplete => "notifyOperationComplete",
+        }
+    }
+}
+
+/// Whether `category` is enabled (default: enabled).
+fn is_enabled(app: &tauri::AppHandle, category: NotificationCategory) -> bool {
+    let state = app.state::<AppState>();
+    let Ok(db) = state.db.lock_or_recover() else {
+        return true;
+    };
+    !matches!(
+        storage::get_setting(&db, category.setting_key())
+            .ok()
+            .flatten()
+            .as_deref()
+            .map(str::trim),
+        Some("false" | "0" | "off")
+    )
+}
+
+/// Emit a notification unless its category is muted.
+pub fn notify(
+    app: &tauri::AppHandle,
+    category: NotificationCategory,
+    level: &str,
+    title: impl Into<String>,
+    message: Option<String>,
+) {
+    if !is_enabled(app, category) {
+        return;
+    }
+    let notification = AppNotification {
+        category: category.as_str().to_string(),
+        level: level.to_string(),
+        title: title.into(),
+        message,
+    };
+    let _ = app.emit(events::event_names::APP_NOTIFICATION, &notification);
+}
+
+/// Notify that an operation started at `started` finished, if it ran for
+/// at least [`LONG_OPERATION_THRESHOLD`].
+pub fn notify_operation_finished(
+    app: &tauri::AppHandle,
+    started: Instant,
+    title: impl Into<String>,
+    error: Option<String>,
+) {
+    if started.elapsed() < LONG_OPERATION_THRESHOLD {
+        return;
+    }
+    let level = if error.is_some() { "error" } else { "success" };
+    notify(
+        app,
+        NotificationCategory::OperationComplete,
+        level,
+        title,
+        error,
+    );
+}
+
+/// Notification for a Docker event, if it is one users should hear about.
+pub fn notify_docker_event(app: &tauri::AppHandle, event: &DockerEvent) {
+    if event.kind == "container" && event.action == "oom" {
+        let name = event.name.clone().unwrap_or_else(|| event.id.clone());
+        notify(
+            app,
+            NotificationCategory::ContainerOom,
+            "error",
+            format!("Container {} ran out of memory", name),
+            Some("The kernel OOM killer stopped a process in this container.".to_string()),
+        );
+    }
+}
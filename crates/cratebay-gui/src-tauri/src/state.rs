@@ -5,14 +5,30 @@ use rusqlite::Connection;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tokio::sync::OnceCell;
+use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 
+use cratebay_core::container_cache::ContainerCache;
+use cratebay_core::dns::DnsRecords;
 use cratebay_core::engine::EnsureOptions;
 use cratebay_core::error::AppError;
 use cratebay_core::mcp::McpManager;
 use cratebay_core::runtime::RuntimeManager;
+use cratebay_core::terminal::TerminalSession;
+use cratebay_core::MutexExt;
+
+use crate::tasks::TaskManager;
+
+/// A stored Docker client is pinged before use when it has not been
+/// checked for this long.
+pub const DOCKER_HEALTH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Timeout of that ping.
+const DOCKER_PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// After a failed connect, commands fail fast with its error for this long
+/// before the next attempt.
+pub const DOCKER_RETRY_BACKOFF: Duration = Duration::from_secs(5);
 
 /// Shared application state accessible from all Tauri commands.
 pub struct AppState {
@@ -20,14 +36,17 @@ pub struct AppState {
     /// Wrapped in Mutex so it can be updated after runtime starts.
     pub docker: Arc<Mutex<Option<Arc<Docker>>>>,
 
-    /// In-process single-flight guard for Docker initialisation.
+    /// In-process single-flight guard for Docker connection, holding the
+    /// time and error of the last failed attempt.
     ///
-    /// The first call to `ensure_docker_once()` that finds Docker unavailable
-    /// will execute `engine::ensure_docker()` exactly once; all concurrent
-    /// callers await that same future instead of each spawning a separate
-    /// start sequence. Once resolved (Ok or Err) the result is cached so
-    /// subsequent calls for the same session return immediately.
-    pub docker_init: Arc<OnceCell<Result<Arc<Docker>, String>>>,
+    /// A call to `ensure_docker_once()` that finds Docker unavailable runs
+    /// `engine::ensure_docker()` under this lock; concurrent callers wait
+    /// and reuse the client it stores instead of each spawning a separate
+    /// start sequence.
+    pub docker_connect: Arc<tokio::sync::Mutex<Option<(Instant, String)>>>,
+
+    /// When the stored Docker client last answered a ping.
+    pub docker_checked_at: Arc<Mutex<Option<Instant>>>,
 
     /// SQLite database connection.
     pub db: Arc<Mutex<Connection>>,
@@ -39,6 +58,20 @@ pub struct AppState {
     /// Key is the channel_id used to identify the streaming session.
     pub llm_cancel_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
 
+    /// Open embedded terminal sessions, keyed by session id.
+    pub terminals: Arc<Mutex<HashMap<String, Arc<TerminalSession>>>>,
+
+    /// Long-running operations shown in the jobs panel.
+    pub tasks: Arc<TaskManager>,
+
+    /// Container list served to `container_list`, kept current by the
+    /// Docker event forwarder.
+    pub containers: Arc<ContainerCache>,
+
+    /// `<container>.docker.cratebay` records served on 127.0.0.1, kept
+    /// current by the Docker event forwarder.
+    pub dns: Arc<DnsRecords>,
+
     /// Built-in container runtime manager (platform-specific).
     pub runtime: Arc<dyn RuntimeManager>,
 
@@ -64,48 +97,97 @@ impl AppState {
 
     /// Ensure Docker is available, with in-process single-flight deduplication.
     ///
-    /// When Docker is not yet connected, only the **first** concurrent caller
-    /// runs the full `engine::ensure_docker()` start sequence. All other
-    /// concurrent callers await that same future.
+    /// When Docker is not connected, only **one** concurrent caller runs the
+    /// full `engine::ensure_docker()` start sequence; the others wait for it
+    /// and reuse its client. A failed attempt is remembered for
+    /// [`DOCKER_RETRY_BACKOFF`], so commands fail fast instead of each paying
+    /// the connect timeout, and retried after that.
     ///
     /// # Fast path
-    /// If the shared Docker client is already present, it is returned
-    /// **immediately without a ping** to avoid per-command serialisation.
+    /// A stored client is returned without a round trip, except that it is
+    /// pinged once per [`DOCKER_HEALTH_INTERVAL`]; a client that fails the
+    /// ping is dropped and the connect path runs.
     pub async fn ensure_docker_once(&self) -> Result<Arc<Docker>, AppError> {
-        // Fast path: a client is already stored — return it immediately.
+        if let Some(docker) = self.checked_docker().await {
+            return Ok(docker);
+        }
+
+        let mut last_failure = self.docker_connect.lock().await;
+        // Another caller may have connected while we waited.
         if let Ok(docker) = self.require_docker() {
             return Ok(docker);
         }
+        if let Some((at, message)) = last_failure.as_ref() {
+            if at.elapsed() < DOCKER_RETRY_BACKOFF {
+                return Err(AppError::Runtime(message.clone()));
+            }
+        }
 
-        // Single-flight init: exactly one concurrent caller runs ensure_docker.
-        let result = self
-            .docker_init
-            .get_or_init(|| async {
-                let options = EnsureOptions {
-                    lock_wait_timeout: Duration::from_secs(60),
-                    ..Default::default()
-                };
-                match cratebay_core::engine::ensure_docker(self.runtime.as_ref(), options).await {
-                    Ok(docker) => {
-                        self.set_docker(Some(docker.clone()));
-                        Ok(docker)
-                    }
-                    Err(e) => Err(e.to_string()),
-                }
-            })
-            .await;
+        let options = EnsureOptions {
+            lock_wait_timeout: Duration::from_secs(60),
+            ..Default::default()
+        };
+        match cratebay_core::engine::ensure_docker(self.runtime.as_ref(), options).await {
+            Ok(docker) => {
+                *last_failure = None;
+                self.set_docker(Some(docker.clone()));
+                Ok(docker)
+            }
+            Err(e) => {
+                *last_failure = Some((Instant::now(), e.to_string()));
+                Err(AppError::Runtime(e.to_string()))
+            }
+        }
+    }
 
-        match result {
-            Ok(docker) => Ok(docker.clone()),
-            Err(msg) => Err(AppError::Runtime(msg.clone())),
+    /// The stored client, pinged first when its last check is older than
+    /// [`DOCKER_HEALTH_INTERVAL`]. Only one caller pings; the others keep
+    /// using the client meanwhile.
+    async fn checked_docker(&self) -> Option<Arc<Docker>> {
+        let docker = self.require_docker().ok()?;
+        {
+            let mut checked_at = self.docker_checked_at.lock_or_recover().ok()?;
+            if checked_at.is_some_and(|at| at.elapsed() < DOCKER_HEALTH_INTERVAL) {
+                return Some(docker);
+            }
+            *checked_at = Some(Instant::now());
+        }
+        if let Ok(Ok(_)) = tokio::time::timeout(DOCKER_PING_TIMEOUT, docker.ping()).await {
+            return Some(docker);
         }
+
+        tracing::info!("Docker client failed its health check, reconnecting");
+        if let Ok(mut guard) = self.docker.lock_or_recover() {
+            // Keep a client another caller stored in the meantime.
+            if guard.as_ref().is_some_and(|d| Arc::ptr_eq(d, &docker)) {
+                *guard = None;
+            }
+        }
+        self.containers.invalidate();
+        None
     }
 
     /// Update the Docker client (e.g., after runtime starts).
     pub fn set_docker(&self, docker: Option<Arc<Docker>>) {
+        if let Ok(mut checked_at) = self.docker_checked_at.lock_or_recover() {
+            *checked_at = docker.as_ref().map(|_| Instant::now());
+        }
         if let Ok(mut guard) = self.docker.lock() {
             *guard = docker;
         }
+        self.containers.invalidate();
+        self.dns.invalidate();
+    }
+
+    /// Ping the stored client on next use and drop cached listings, e.g.
+    /// after host sleep, when connections may be dead and
+    /// [`DOCKER_HEALTH_INTERVAL`] has not elapsed on the monotonic clock.
+    pub fn recheck_docker(&self) {
+        if let Ok(mut checked_at) = self.docker_checked_at.lock_or_recover() {
+            *checked_at = None;
+        }
+        self.containers.invalidate();
+        self.dns.invalidate();
     }
 
     /// Check if Docker is currently available.
@@ -0,0 +1,249 @@
+//! Background task registry for long-running operations.
+//!
+//! Pulls, pushes, builds, exports and runtime start register a task
+//! so the frontend can show them in a jobs panel and cancel them. Every
+//! change is emitted as `task:update` with the full [`TaskInfo`]. Finished
+//! tasks stay listed (up to [`FINISHED_TASK_LIMIT`]) until the app exits.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::Emitter;
+
+use cratebay_core::cancel::{self, CancellationToken};
+use cratebay_core::error::AppError;
+use cratebay_core::MutexExt;
+
+use crate::events::event_names;
+
+/// Finished tasks kept for the jobs panel; older ones are dropped.
+pub const FINISHED_TASK_LIMIT: usize = 50;
+
+/// Minimum interval between progress events of one task, unless the
+/// percentage changes.
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What a task does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskKind {
+    ImagePull,
+    ImagePush,
+    ImageBuild,
+    ImageSave,
+    ContainerExport,
+    RuntimeStart,
+}
+
+/// Lifecycle of a task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskStatus {
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+/// A task as listed by `task_list` and sent with `task:update`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub id: String,
+    pub kind: TaskKind,
+    pub title: String,
+    pub status: TaskStatus,
+    /// Progress percentage (0-100), when the operation reports one.
+    pub progress: Option<u32>,
+    /// Latest status message.
+    pub message: Option<String>,
+    /// Operation result on success (e.g. the built image ID).
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    /// RFC 3339 timestamps.
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+struct Task {
+    info: TaskInfo,
+    cancel: CancellationToken,
+}
+
+/// Registry of running and recently finished tasks, held in
+/// [`AppState`](crate::state::AppState).
+#[derive(Default)]
+pub struct TaskManager {
+    tasks: Mutex<Vec<Task>>,
+}
+
+impl TaskManager {
+    /// Register a running task and announce it. `id` is the operation's
+    /// channel id where it has one, so events can be correlated.
+    pub fn start(
+        self: &Arc<Self>,
+        app: &tauri::AppHandle,
+        id: String,
+        kind: TaskKind,
+        title: impl Into<String>,
+    ) -> TaskHandle {
+        let cancel = CancellationToken::new();
+        let title = title.into();
+        let info = TaskInfo {
+            id: id.clone(),
+            kind,
+            title: title.clone(),
+            status: TaskStatus::Running,
+            progress: None,
+            message: None,
+            result: None,
+            error: None,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+        };
+        if let Ok(mut tasks) = self.tasks.lock_or_recover() {
+            // A reused channel id replaces the earlier task.
+            tasks.retain(|t| t.info.id != id);
+            tasks.push(Task {
+                info: info.clone(),
+                cancel: cancel.clone(),
+            });
+        }
+        let _ = app.emit(event_names::TASK_UPDATE, &info);
+        TaskHandle {
+            id,
+            title,
+            manager: Arc::clone(self),
+            app: app.clone(),
+            cancel,
+            last_emit: Arc::new(Mutex::new((None, Instant::now()))),
+        }
+    }
+
+    /// All tasks, newest first.
+    pub fn list(&self) -> Result<Vec<TaskInfo>, AppError> {
+        let tasks = self.tasks.lock_or_recover()?;
+        Ok(tasks.iter().rev().map(|t| t.info.clone()).collect())
+    }
+
+    /// Request cancellation of a running task. Returns false when the task
+    /// has already finished.
+    pub fn cancel(&self, id: &str) -> Result<bool, AppError> {
+        let tasks = self.tasks.lock_or_recover()?;
+        let task = tasks
+            .iter()
+            .find(|t| t.info.id == id)
+            .ok_or_else(|| AppError::NotFound {
+                entity: "task".to_string(),
+                id: id.to_string(),
+            })?;
+        if task.info.status != TaskStatus::Running {
+            return Ok(false);
+        }
+        task.cancel.cancel();
+        Ok(true)
+    }
+
+    /// Apply `f` to a task and return the updated info.
+    fn update(&self, id: &str, f: impl FnOnce(&mut TaskInfo)) -> Option<TaskInfo> {
+        let mut tasks = self.tasks.lock_or_recover().ok()?;
+        let task = tasks.iter_mut().find(|t| t.info.id == id)?;
+        f(&mut task.info);
+        let info = task.info.clone();
+        if info.status != TaskStatus::Running {
+            prune_finished(&mut tasks);
+        }
+        Some(info)
+    }
+}
+
+/// Drop the oldest finished tasks beyond [`FINISHED_TASK_LIMIT`].
+fn prune_finished(tasks: &mut Vec<Task>) {
+    let finished = tasks
+        .iter()
+        .filter(|t| t.info.status != TaskStatus::Running)
+        .count();
+    let mut excess = finished.saturating_sub(FINISHED_TASK_LIMIT);
+    tasks.retain(|t| {
+        if excess > 0 && t.info.status != TaskStatus::Running {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+/// Handle to a registered task, used to report progress and run the
+/// operation. Cheap to clone into progress callbacks.
+#[derive(Clone)]
+pub struct TaskHandle {
+    id: String,
+    title: String,
+    manager: Arc<TaskManager>,
+    app: tauri::AppHandle,
+    cancel: CancellationToken,
+    /// Last emitted percentage and time, for throttling.
+    last_emit: Arc<Mutex<(Option<u32>, Instant)>>,
+}
+
+impl TaskHandle {
+    /// Token cancelled by `task_cancel`, for operations that clean up on
+    /// cancellation (see [`cratebay_core::cancel`]).
+    pub fn cancel_token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    /// Report progress. Events are throttled to one per
+    /// [`PROGRESS_EMIT_INTERVAL`] unless the percentage changes.
+    pub fn progress(&self, percent: Option<u32>, message: impl Into<String>) {
+        let message = message.into();
+        let Some(info) = self.manager.update(&self.id, |info| {
+            info.progress = percent.map(|p| p.min(100)).or(info.progress);
+            info.message = Some(message);
+        }) else {
+            return;
+        };
+        if let Ok(mut last) = self.last_emit.lock_or_recover() {
+            if last.0 == info.progress && last.1.elapsed() < PROGRESS_EMIT_INTERVAL {
+                return;
+            }
+            *last = (info.progress, Instant::now());
+        }
+        let _ = self.app.emit(event_names::TASK_UPDATE, &info);
+    }
+
+    /// Run `operation` until it completes or the task is cancelled, then
+    /// record the outcome. Operations that do not watch
+    /// [`cancel_token`](Self::cancel_token) are dropped on cancellation,
+    /// which aborts their in-flight Docker or download stream.
+    pub async fn run<T, F>(&self, operation: F) -> Result<T, AppError>
+    where
+        T: Serialize,
+        F: Future<Output = Result<T, AppError>>,
+    {
+        let result = cancel::run(Some(&self.cancel), &self.title, operation)
+            .await
+            .and_then(|r| r);
+        let info = self.manager.update(&self.id, |info| {
+            info.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            match &result {
+                Ok(value) => {
+                    info.status = TaskStatus::Succeeded;
+                    info.progress = Some(100);
+                    info.result = serde_json::to_value(value).ok().filter(|v| !v.is_null());
+                }
+                Err(AppError::Cancelled(_)) => info.status = TaskStatus::Cancelled,
+                Err(e) => {
+                    info.status = TaskStatus::Failed;
+                    info.error = Some(e.to_string());
+                }
+            }
+        });
+        if let Some(info) = info {
+            let _ = self.app.emit(event_names::TASK_UPDATE, &info);
+        }
+        result
+    }
+}
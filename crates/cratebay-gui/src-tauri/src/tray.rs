@@ -0,0 +1,310 @@
+//! System tray (menubar on macOS) with quick actions.
+//!
+//! The menu is built from live state: runtime status with start/stop, the
+//! most recently created containers with start/stop, "Stop All Containers"
+//! and "Open CrateBay". [`start_tray_sync`] rebuilds it whenever the
+//! runtime state changes, Docker connects or a Docker event arrives.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::menu::{Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Listener, Manager};
+use tokio::sync::Notify;
+
+use cratebay_core::error::AppError;
+use cratebay_core::models::{ContainerInfo, ContainerStatus};
+use cratebay_core::runtime::RuntimeState;
+
+use crate::commands;
+use crate::events::event_names;
+use crate::notify::{self, NotificationCategory};
+use crate::state::AppState;
+
+const TRAY_ID: &str = "cratebay";
+
+/// Containers listed in the menu, most recently created first.
+const RECENT_CONTAINER_LIMIT: usize = 8;
+
+/// Bursts of events (e.g. a compose project starting) are coalesced into
+/// one rebuild.
+const SYNC_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Rebuild even without events, to catch changes nothing reports (e.g. an
+/// external Docker daemon going away).
+const SYNC_FALLBACK_INTERVAL: Duration = Duration::from_secs(60);
+
+const MENU_RUNTIME_STATUS: &str = "runtime-status";
+const MENU_RUNTIME_START: &str = "runtime-start";
+const MENU_RUNTIME_STOP: &str = "runtime-stop";
+const MENU_STOP_ALL: &str = "stop-all";
+const MENU_OPEN: &str = "open";
+const MENU_QUIT: &str = "quit";
+/// Prefixes of per-container items; the container ID follows.
+const MENU_CONTAINER_START: &str = "container-start:";
+const MENU_CONTAINER_STOP: &str = "container-stop:";
+
+/// Wakes the sync loop; managed by Tauri once [`start_tray_sync`] runs.
+struct TraySync(Arc<Notify>);
+
+/// Live state the menu is built from.
+#[derive(Default)]
+struct TraySnapshot {
+    runtime: Option<RuntimeState>,
+    /// All containers, newest first; `None` when Docker is unavailable.
+    containers: Option<Vec<ContainerInfo>>,
+}
+
+impl TraySnapshot {
+    async fn collect(app: &AppHandle) -> Self {
+        let state = app.state::<AppState>();
+        let runtime = state.runtime.get_state().await.ok();
+        let containers = match state.require_docker() {
+            Ok(docker) => cratebay_core::container::list(&docker, true, None)
+                .await
+                .ok()
+                .map(|mut containers| {
+                    containers.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+                    containers
+                }),
+            Err(_) => None,
+        };
+        Self {
+            runtime,
+            containers,
+        }
+    }
+
+    fn running_count(&self) -> usize {
+        self.containers
+            .iter()
+            .flatten()
+            .filter(|c| is_up(&c.status))
+            .count()
+    }
+}
+
+/// Whether a container can be stopped.
+fn is_up(status: &ContainerStatus) -> bool {
+    matches!(
+        status,
+        ContainerStatus::Running | ContainerStatus::Paused | ContainerStatus::Restarting
+    )
+}
+
+fn runtime_label(state: Option<&RuntimeState>) -> String {
+    let status = match state {
+        None => "Unknown",
+        Some(RuntimeState::None) => "Not installed",
+        Some(RuntimeState::Provisioned) | Some(RuntimeState::Stopped) => "Stopped",
+        Some(RuntimeState::Starting) => "Starting…",
+        Some(RuntimeState::Ready) => "Running",
+        Some(RuntimeState::Stopping) => "Stopping…",
+        Some(RuntimeState::Error(_)) => "Error",
+    };
+    format!("Runtime: {}", status)
+}
+
+fn build_menu(app: &AppHandle, snapshot: &TraySnapshot) -> tauri::Result<Menu<tauri::Wry>> {
+    let (can_start, can_stop) = match snapshot.runtime {
+        Some(RuntimeState::Ready) | Some(RuntimeState::Starting) => (false, true),
+        Some(RuntimeState::Stopping) => (false, false),
+        _ => (true, false),
+    };
+    let status = MenuItemBuilder::with_id(
+        MENU_RUNTIME_STATUS,
+        runtime_label(snapshot.runtime.as_ref()),
+    )
+    .enabled(false)
+    .build(app)?;
+    let start = MenuItemBuilder::with_id(MENU_RUNTIME_START, "Start Runtime")
+        .enabled(can_start)
+        .build(app)?;
+    let stop = MenuItemBuilder::with_id(MENU_RUNTIME_STOP, "Stop Runtime")
+        .enabled(can_stop)
+        .build(app)?;
+    let mut menu = MenuBuilder::new(app)
+        .item(&status)
+        .item(&start)
+        .item(&stop)
+        .separator();
+
+    match &snapshot.containers {
+        Some(containers) if !containers.is_empty() => {
+            for container in containers.iter().take(RECENT_CONTAINER_LIMIT) {
+                let up = is_up(&container.status);
+                let (marker, action, label) = if up {
+                    ("●", MENU_CONTAINER_STOP, "Stop")
+                } else {
+                    ("○", MENU_CONTAINER_START, "Start")
+                };
+                let submenu = SubmenuBuilder::new(app, format!("{} {}", marker, container.name))
+                    .text(format!("{}{}", action, container.id), label)
+                    .build()?;
+                menu = menu.item(&submenu);
+            }
+        }
+        containers => {
+            let text = if containers.is_some() {
+                "No containers"
+            } else {
+                "Docker is not available"
+            };
+            let placeholder = MenuItemBuilder::new(text).enabled(false).build(app)?;
+            menu = menu.item(&placeholder);
+        }
+    }
+
+    let running = snapshot.running_count();
+    let stop_all =
+        MenuItemBuilder::with_id(MENU_STOP_ALL, format!("Stop All Containers ({})", running))
+            .enabled(running > 0)
+            .build(app)?;
+    menu.item(&stop_all)
+        .separator()
+        .text(MENU_OPEN, "Open CrateBay")
+        .text(MENU_QUIT, "Quit CrateBay")
+        .build()
+}
+
+/// Create the tray icon. Its menu starts empty of live state and is filled
+/// in by [`start_tray_sync`].
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app, &TraySnapshot::default())?;
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("CrateBay")
+        .menu(&menu)
+        .on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Keep the tray menu in sync with the runtime and containers.
+///
+/// Listens to `runtime:state-change`, `docker:connected` and `docker:event`,
+/// then rebuilds the menu after [`SYNC_DEBOUNCE`].
+pub fn start_tray_sync(app: AppHandle) {
+    let wake = Arc::new(Notify::new());
+    app.manage(TraySync(Arc::clone(&wake)));
+    for event in [
+        event_names::RUNTIME_STATE_CHANGE,
+        event_names::DOCKER_CONNECTED,
+        event_names::DOCKER_EVENT,
+    ] {
+        let wake = Arc::clone(&wake);
+        app.listen_any(event, move |_| wake.notify_one());
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            refresh(&app).await;
+            let _ = tokio::time::timeout(SYNC_FALLBACK_INTERVAL, wake.notified()).await;
+            tokio::time::sleep(SYNC_DEBOUNCE).await;
+        }
+    });
+}
+
+/// Ask the sync loop to rebuild the menu, for changes that emit no event
+/// right away (the health monitor reports runtime state on its own tick).
+fn request_sync(app: &AppHandle) {
+    if let Some(sync) = app.try_state::<TraySync>() {
+        sync.0.notify_one();
+    }
+}
+
+async fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let snapshot = TraySnapshot::collect(app).await;
+    match build_menu(app, &snapshot) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                tracing::warn!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to build tray menu: {}", e),
+    }
+    let tooltip = match snapshot.containers {
+        Some(_) => format!("CrateBay — {} running", snapshot.running_count()),
+        None => "CrateBay".to_string(),
+    };
+    let _ = tray.set_tooltip(Some(tooltip));
+}
+
+fn on_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        MENU_OPEN => show_main_window(app),
+        MENU_QUIT => app.exit(0),
+        _ => {
+            let app = app.clone();
+            let id = id.to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = run_action(&app, &id).await {
+                    tracing::warn!("Tray action {} failed: {}", id, e);
+                    notify::notify(
+                        &app,
+                        NotificationCategory::OperationComplete,
+                        "error",
+                        "Tray action failed",
+                        Some(e.to_string()),
+                    );
+                }
+                request_sync(&app);
+            });
+        }
+    }
+}
+
+async fn run_action(app: &AppHandle, id: &str) -> Result<(), AppError> {
+    if let Some(container) = id.strip_prefix(MENU_CONTAINER_START) {
+        return commands::container::container_start(app.state(), container.to_string()).await;
+    }
+    if let Some(container) = id.strip_prefix(MENU_CONTAINER_STOP) {
+        return commands::container::container_stop(app.state(), container.to_string(), None).await;
+    }
+    match id {
+        MENU_RUNTIME_START => commands::system::runtime_start(app.clone(), app.state())
+            .await
+            .map(drop),
+        MENU_RUNTIME_STOP => commands::system::runtime_stop(app.state()).await.map(drop),
+        MENU_STOP_ALL => stop_all(app).await,
+        _ => Ok(()),
+    }
+}
+
+/// Stop every running container, continuing past failures.
+async fn stop_all(app: &AppHandle) -> Result<(), AppError> {
+    let docker = app.state::<AppState>().require_docker()?;
+    let running = cratebay_core::container::list(&docker, false, None).await?;
+    let mut failed = Vec::new();
+    for container in running {
+        if let Err(e) =
+            commands::container::container_stop(app.state(), container.id.clone(), None).await
+        {
+            tracing::warn!("Failed to stop {}: {}", container.name, e);
+            failed.push(container.name);
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::Runtime(format!(
+            "Failed to stop {}",
+            failed.join(", ")
+        )))
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+}
@@ -3,7 +3,9 @@ import { useAppStore } from "@/stores/appStore";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useChatStore } from "@/stores/chatStore";
 import { useMcpStore } from "@/stores/mcpStore";
+import { useContainerStore } from "@/stores/containerStore";
 import { invoke, listen } from "@/lib/tauri";
+import type { SettingsChangedEvent } from "@/types/settings";
 import { AppLayout } from "@/components/layout/AppLayout";
 import { ToastContainer } from "@/components/common/Toast";
 import { ChatPage } from "@/pages/ChatPage";
@@ -221,6 +223,107 @@ function App() {
     };
   }, []);
 
+  // Listen for runtime:state-change (emitted only on transitions, also by
+  // the background boot started with runtime_start) so a failed start shows
+  // without waiting for the next health check, and the lists refresh once
+  // the runtime is ready.
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    void listen<{ current: string | Record<string, string> }>(
+      "runtime:state-change",
+      (payload) => {
+        const next = mapRuntimeState(payload.current);
+        // docker:connected refreshes the status after a successful start,
+        // and runtime:health decides whether a connected runtime is really
+        // going down.
+        if (next === "running") {
+          const store = useContainerStore.getState();
+          void store.fetchContainers();
+          void store.fetchImages();
+          return;
+        }
+        if (next === "starting" && useAppStore.getState().dockerConnected) return;
+        setEngineState(next, false);
+      },
+    ).then((unsub) => {
+      unlisten = unsub;
+    });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  // Listen for settings:changed so settings edited in another window (or
+  // via config.toml keys) are reflected here.
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    void listen<SettingsChangedEvent>("settings:changed", () => {
+      void useSettingsStore.getState().fetchSettings();
+    }).then((unsub) => {
+      unlisten = unsub;
+    });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
+  // Listen for docker:event (container/image changes) and refresh lists
+  // instead of waiting for a manual refresh. Bursts are coalesced.
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    const timers: Record<string, number | undefined> = {};
+    const schedule = (key: string, refresh: () => Promise<void>) => {
+      window.clearTimeout(timers[key]);
+      timers[key] = window.setTimeout(() => void refresh(), 300);
+    };
+
+    void listen<{ kind: string; action: string }>("docker:event", (event) => {
+      const store = useContainerStore.getState();
+      // Execs do not change what the lists show.
+      if (event.payload.action.startsWith("exec_")) return;
+      if (event.payload.kind === "container") {
+        schedule("containers", store.fetchContainers);
+      } else if (event.payload.kind === "image") {
+        schedule("images", store.fetchImages);
+      }
+    }).then((unsub) => {
+      unlisten = unsub;
+    });
+
+    return () => {
+      unlisten?.();
+      Object.values(timers).forEach((t) => window.clearTimeout(t));
+    };
+  }, []);
+
+  // Surface backend notifications (runtime crash, OOM, disk full, long
+  // operations finishing) as toasts. Muted categories are filtered in Rust.
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    void listen<{
+      level: "info" | "success" | "warning" | "error";
+      title: string;
+      message?: string | null;
+    }>("app:notification", (event) => {
+      useAppStore.getState().addNotification({
+        type: event.payload.level,
+        title: event.payload.title,
+        message: event.payload.message ?? undefined,
+        dismissable: true,
+      });
+    }).then((unsub) => {
+      unlisten = unsub;
+    });
+
+    return () => {
+      unlisten?.();
+    };
+  }, []);
+
   useEffect(() => {
     const state = useAppStore.getState();
     if (state.runtimeStatus === "running" && state.dockerConnected) {
@@ -247,12 +247,12 @@ describe("containerTools — tool execution", () => {
 // Tool registry tests
 // ---------------------------------------------------------------------------
 describe("tool registry", () => {
-  it("containerTools contains 8 tools", () => {
-    expect(containerTools).toHaveLength(8);
+  it("containerTools contains 9 tools", () => {
+    expect(containerTools).toHaveLength(9);
   });
 
   it("builtinTools contains all registered tools from all categories", () => {
-    // containerTools(8) + filesystemTools(3) + shellTools(1) + mcpTools(2) + systemTools(3)
+    // containerTools(9) + filesystemTools(3) + shellTools(1) + mcpTools(2) + systemTools(3)
     expect(builtinTools.length).toBeGreaterThanOrEqual(8);
 
     // Check container tools are all present
@@ -265,6 +265,7 @@ describe("tool registry", () => {
     expect(names).toContain("container_exec");
     expect(names).toContain("container_logs");
     expect(names).toContain("container_inspect");
+    expect(names).toContain("container_commit");
   });
 
   it("each tool has name, label, description, parameters, and execute", () => {
@@ -23,6 +23,8 @@ import {
 import { Plus, ChevronDown, Loader2 } from "lucide-react";
 import { cn } from "@/lib/utils";
 
+const PLATFORMS = ["linux/amd64", "linux/arm64"];
+
 /**
  * Format bytes to human-readable size string.
  */
@@ -47,6 +49,7 @@ export function ContainerCreate() {
   const [templateId, setTemplateId] = useState("");
   const [cpuCores, setCpuCores] = useState(2);
   const [memoryMb, setMemoryMb] = useState(2048);
+  const [platform, setPlatform] = useState("");
 
   const imageInputRef = useRef<HTMLInputElement>(null);
   const dropdownRef = useRef<HTMLDivElement>(null);
@@ -99,6 +102,7 @@ export function ContainerCreate() {
     setTemplateId("");
     setCpuCores(2);
     setMemoryMb(2048);
+    setPlatform("");
   }, []);
 
   const selectedTemplate = templates.find((tmpl) => tmpl.id === templateId);
@@ -129,6 +133,9 @@ export function ContainerCreate() {
       memoryMb,
       autoStart: true,
     };
+    if (platform) {
+      req.platform = platform;
+    }
     if (selectedTemplate?.defaultCommand?.trim()) {
       req.command = selectedTemplate.defaultCommand.trim();
     }
@@ -136,7 +143,7 @@ export function ContainerCreate() {
     void createContainer(req);
     resetForm();
     setOpen(false);
-  }, [canCreate, templateId, name, image, cpuCores, memoryMb, selectedTemplate, createContainer, resetForm]);
+  }, [canCreate, templateId, name, image, cpuCores, memoryMb, platform, selectedTemplate, createContainer, resetForm]);
 
   return (
     <Dialog open={open} onOpenChange={(v) => { setOpen(v); if (!v) resetForm(); }}>
@@ -285,6 +292,27 @@ export function ContainerCreate() {
               />
             </div>
           </div>
+
+          {/* Platform — another architecture runs under emulation */}
+          <div className="flex flex-col gap-1.5">
+            <Label>{t("containers", "platform")}</Label>
+            <Select
+              value={platform || "default"}
+              onValueChange={(v) => setPlatform(v === "default" ? "" : v)}
+            >
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="default">{t("containers", "platformDefault")}</SelectItem>
+                {PLATFORMS.map((p) => (
+                  <SelectItem key={p} value={p}>
+                    <span className="font-mono text-xs">{p}</span>
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
         </div>
 
         <DialogFooter>
@@ -2,10 +2,11 @@ import { useState, useEffect, useCallback } from "react";
 import { createPortal } from "react-dom";
 import { useContainerStore, type ContainerInfo } from "@/stores/containerStore";
 import { useI18n } from "@/lib/i18n";
+import { invoke } from "@/lib/tauri";
 import { useAppStore } from "@/stores/appStore";
 import { Badge } from "@/components/ui/badge";
 import { cn } from "@/lib/utils";
-import { Play, Square, Trash2, Copy, Check, X, Terminal } from "lucide-react";
+import { Play, Square, Trash2, Copy, Check, X, Terminal, ExternalLink } from "lucide-react";
 
 /**
  * Container detail panel — fixed-positioned overlay on the right side.
@@ -235,7 +236,23 @@ function DetailContent({ container }: { container: ContainerInfo }) {
                     {port.protocol}
                   </span>
                 </div>
-                <CopyButton value={`${port.hostPort}:${port.containerPort}`} />
+                <div className="flex items-center gap-0.5">
+                  {isRunning && port.protocol === "tcp" && (
+                    <button
+                      onClick={() =>
+                        void invoke("open_in_browser", {
+                          id: container.id,
+                          port: port.containerPort,
+                        }).catch(() => undefined)
+                      }
+                      className="flex h-6 w-6 items-center justify-center rounded text-muted-foreground transition-colors hover:bg-muted hover:text-foreground"
+                      title={t("containers", "openInBrowser")}
+                    >
+                      <ExternalLink className="h-3 w-3" />
+                    </button>
+                  )}
+                  <CopyButton value={`${port.hostPort}:${port.containerPort}`} />
+                </div>
               </div>
             ))}
           </div>
@@ -1,5 +1,6 @@
 import * as React from "react";
 
+import { errorMessage } from "@/lib/errors";
 import { invoke } from "@/lib/tauri";
 import { cn } from "@/lib/utils";
 import { useI18n } from "@/lib/i18n";
@@ -28,7 +29,7 @@ export function ContainerLogs({ containerId, tail = 200 }: { containerId: string
       });
       setEntries(Array.isArray(logs) ? logs : []);
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setError(message);
     } finally {
       setLoading(false);
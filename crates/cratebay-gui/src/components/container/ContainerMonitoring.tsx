@@ -1,5 +1,6 @@
 import * as React from "react";
 
+import { errorMessage } from "@/lib/errors";
 import { invoke } from "@/lib/tauri";
 import { useI18n } from "@/lib/i18n";
 import { Button } from "@/components/ui/button";
@@ -45,7 +46,7 @@ export function ContainerMonitoring({
       const next = await fetchContainerStats(containerId);
       setStats(next);
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       setStats(null);
       setError(message);
     } finally {
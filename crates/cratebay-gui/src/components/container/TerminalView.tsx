@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 
+import { errorMessage } from "@/lib/errors";
 import { invoke, listen } from "@/lib/tauri";
 import { cn } from "@/lib/utils";
 import { Button } from "@/components/ui/button";
@@ -135,7 +136,7 @@ export function TerminalView({ containerId, onClose }: TerminalViewProps) {
           new Promise<void>((resolve) => window.setTimeout(resolve, 5000)),
         ]);
       } catch (err) {
-        const message = err instanceof Error ? err.message : String(err);
+        const message = errorMessage(err);
         if (mountedRef.current) {
           setLines((prev) => [
             ...prev,
@@ -6,6 +6,7 @@ import {
   type ApiFormat,
   type ProviderTestResult,
 } from "@/stores/settingsStore";
+import { errorMessage } from "@/lib/errors";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
@@ -71,7 +72,7 @@ export function ProviderCard({ provider }: ProviderCardProps) {
       const result = await testProvider(provider.id);
       setTestResult(result);
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = errorMessage(error);
       setTestResult({
         success: false,
         latencyMs: 0,
@@ -88,7 +89,7 @@ export function ProviderCard({ provider }: ProviderCardProps) {
       await deleteProvider(provider.id);
       setDeleteConfirmOpen(false);
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = errorMessage(error);
       setTestResult({
         success: false,
         latencyMs: 0,
@@ -102,7 +103,7 @@ export function ProviderCard({ provider }: ProviderCardProps) {
     try {
       await fetchModels(provider.id);
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = errorMessage(error);
       setTestResult({
         success: false,
         latencyMs: 0,
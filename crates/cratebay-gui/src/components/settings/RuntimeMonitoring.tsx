@@ -0,0 +1,156 @@
+import * as React from "react";
+
+import { errorMessage } from "@/lib/errors";
+import { invoke } from "@/lib/tauri";
+import { useI18n } from "@/lib/i18n";
+import { Button } from "@/components/ui/button";
+
+export type RuntimeMetrics = {
+  readAt: string;
+  cpuPercent: number;
+  cpuCores: number;
+  memoryUsedMb: number;
+  memoryTotalMb: number;
+  memoryAllocatedMb: number;
+  memoryBalloonMb: number;
+  diskUsedGb: number;
+  diskTotalGb: number;
+  netRxBytes: number;
+  netTxBytes: number;
+  containerCount: number;
+};
+
+/** Samples kept for the sparklines (15s interval → 15 minutes). */
+const HISTORY_SIZE = 60;
+const POLL_INTERVAL_MS = 15000;
+
+export async function fetchRuntimeMetrics(): Promise<RuntimeMetrics> {
+  return invoke<RuntimeMetrics>("runtime_metrics");
+}
+
+function formatBytes(bytes: number): string {
+  const units = ["B", "KB", "MB", "GB", "TB"];
+  let value = bytes;
+  let unit = 0;
+  while (value >= 1024 && unit < units.length - 1) {
+    value /= 1024;
+    unit += 1;
+  }
+  return `${value.toFixed(unit === 0 ? 0 : 1)} ${units[unit]}`;
+}
+
+function Sparkline({ values, max = 100 }: { values: number[]; max?: number }) {
+  if (values.length < 2) {
+    return <div className="h-8" />;
+  }
+  const width = 120;
+  const height = 32;
+  const step = width / (HISTORY_SIZE - 1);
+  const offset = (HISTORY_SIZE - values.length) * step;
+  const points = values
+    .map((v, i) => {
+      const x = offset + i * step;
+      const y = height - (Math.min(Math.max(v, 0), max) / max) * height;
+      return `${x.toFixed(1)},${y.toFixed(1)}`;
+    })
+    .join(" ");
+  return (
+    <svg viewBox={`0 0 ${width} ${height}`} className="h-8 w-full text-primary" preserveAspectRatio="none">
+      <polyline points={points} fill="none" stroke="currentColor" strokeWidth={1.5} />
+    </svg>
+  );
+}
+
+export function RuntimeMonitoring({ enabled = true }: { enabled?: boolean }) {
+  const { t } = useI18n();
+  const [history, setHistory] = React.useState<RuntimeMetrics[]>([]);
+  const [loading, setLoading] = React.useState(false);
+  const [error, setError] = React.useState<string | null>(null);
+  const inFlightRef = React.useRef(false);
+
+  const refresh = React.useCallback(async () => {
+    if (inFlightRef.current) return;
+    inFlightRef.current = true;
+    setLoading(true);
+    setError(null);
+    try {
+      const next = await fetchRuntimeMetrics();
+      setHistory((prev) => [...prev, next].slice(-HISTORY_SIZE));
+    } catch (err) {
+      const message = errorMessage(err);
+      setError(message);
+    } finally {
+      setLoading(false);
+      inFlightRef.current = false;
+    }
+  }, []);
+
+  React.useEffect(() => {
+    if (!enabled) return;
+    void refresh();
+    const id = window.setInterval(() => void refresh(), POLL_INTERVAL_MS);
+    return () => window.clearInterval(id);
+  }, [enabled, refresh]);
+
+  const latest = history.length > 0 ? history[history.length - 1] : null;
+  const memPercent = (m: RuntimeMetrics) =>
+    m.memoryTotalMb > 0 ? (m.memoryUsedMb / m.memoryTotalMb) * 100 : 0;
+
+  return (
+    <div className="rounded-lg border border-border bg-card p-3">
+      <div className="flex items-center justify-between">
+        <span className="text-sm font-medium">{t("settings", "runtimeMetrics")}</span>
+        <Button variant="ghost" size="xs" onClick={() => void refresh()} disabled={loading || !enabled}>
+          {t("common", "refresh")}
+        </Button>
+      </div>
+
+      {!enabled ? (
+        <div className="mt-2 text-sm text-muted-foreground">{t("settings", "runtimeMetricsUnavailable")}</div>
+      ) : latest ? (
+        <div className="mt-2 grid grid-cols-2 gap-x-6 gap-y-3 text-sm">
+          <div>
+            <div className="text-[10px] uppercase tracking-wider text-muted-foreground">CPU</div>
+            <div className="font-mono">
+              {latest.cpuPercent.toFixed(1)}% ({latest.cpuCores} cores)
+            </div>
+            <Sparkline values={history.map((m) => m.cpuPercent)} />
+          </div>
+          <div>
+            <div className="text-[10px] uppercase tracking-wider text-muted-foreground">MEM</div>
+            <div className="font-mono">
+              {latest.memoryUsedMb} / {latest.memoryTotalMb} MB ({memPercent(latest).toFixed(1)}%)
+            </div>
+            {latest.memoryBalloonMb > 0 && (
+              <div className="font-mono text-[10px] text-muted-foreground">
+                host {latest.memoryAllocatedMb - latest.memoryBalloonMb} /{" "}
+                {latest.memoryAllocatedMb} MB
+              </div>
+            )}
+            <Sparkline values={history.map(memPercent)} />
+          </div>
+          <div>
+            <div className="text-[10px] uppercase tracking-wider text-muted-foreground">DISK</div>
+            <div className="font-mono">
+              {latest.diskUsedGb.toFixed(1)} / {latest.diskTotalGb.toFixed(1)} GB
+            </div>
+          </div>
+          <div>
+            <div className="text-[10px] uppercase tracking-wider text-muted-foreground">NET</div>
+            <div className="font-mono">
+              ↓ {formatBytes(latest.netRxBytes)} ↑ {formatBytes(latest.netTxBytes)}
+            </div>
+          </div>
+          <div className="col-span-2 text-[10px] uppercase tracking-wider text-muted-foreground">
+            {latest.containerCount} containers ·{" "}
+            {new Date(latest.readAt).toLocaleTimeString("zh-CN", { hour12: false })}
+          </div>
+        </div>
+      ) : (
+        <div className="mt-2 text-sm text-muted-foreground">
+          {error ? `${t("common", "error")}: ${error}` : t("common", "loading")}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,62 @@
+/**
+ * Errors returned by Tauri commands.
+ *
+ * The backend rejects with an `ErrorDetail` object (`code`, `message`,
+ * optional `resource` and `hint`); these helpers turn it into an `Error`
+ * and into text for notifications.
+ */
+
+import type { ErrorCode, ErrorDetail } from "@/types/error";
+
+/**
+ * A failed Tauri command. Carries the backend's error code so callers can
+ * branch on it, e.g. to offer enabling Rosetta.
+ */
+export class CommandError extends Error implements ErrorDetail {
+  code: ErrorCode;
+  resource?: string;
+  hint?: string;
+
+  constructor(detail: ErrorDetail) {
+    super(detail.message);
+    this.name = "CommandError";
+    this.code = detail.code;
+    this.resource = detail.resource;
+    this.hint = detail.hint;
+  }
+
+  toString(): string {
+    return this.message;
+  }
+}
+
+function isErrorDetail(value: unknown): value is ErrorDetail {
+  return (
+    value !== null &&
+    typeof value === "object" &&
+    typeof (value as Record<string, unknown>).code === "string" &&
+    typeof (value as Record<string, unknown>).message === "string"
+  );
+}
+
+/** Wrap a structured backend error in a `CommandError`. */
+export function toCommandError(err: unknown): unknown {
+  return isErrorDetail(err) && !(err instanceof Error) ? new CommandError(err) : err;
+}
+
+/**
+ * User-facing text for a caught error, with the backend's hint appended
+ * when there is one.
+ */
+export function errorMessage(err: unknown): string {
+  if (err instanceof CommandError && err.hint) {
+    return `${err.message}. ${err.hint}`;
+  }
+  if (err instanceof Error) {
+    return err.message;
+  }
+  if (isErrorDetail(err)) {
+    return errorMessage(new CommandError(err));
+  }
+  return String(err);
+}
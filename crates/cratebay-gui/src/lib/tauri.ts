@@ -10,6 +10,7 @@
 
 import { invoke as tauriInvoke } from "@tauri-apps/api/core";
 import { listen as tauriListen } from "@tauri-apps/api/event";
+import { toCommandError } from "@/lib/errors";
 
 declare global {
   interface Window {
@@ -30,17 +31,26 @@ export function isTauri(): boolean {
 
 /**
  * Invoke a Tauri command. Falls back to a console warning and empty result
- * when Tauri is not available (browser-only development).
+ * when Tauri is not available (browser-only development). Structured
+ * backend errors are rethrown as `CommandError`.
  */
 export async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   if (isTauri()) {
-    return tauriInvoke<T>(cmd, args);
+    try {
+      return await tauriInvoke<T>(cmd, args);
+    } catch (err) {
+      throw toCommandError(err);
+    }
   }
 
   const mockInvoke =
     typeof window !== "undefined" ? window.__MOCK_TAURI_INVOKE__ : undefined;
   if (typeof mockInvoke === "function") {
-    return (await mockInvoke(cmd, args)) as T;
+    try {
+      return (await mockInvoke(cmd, args)) as T;
+    } catch (err) {
+      throw toCommandError(err);
+    }
   }
 
   throw new Error(
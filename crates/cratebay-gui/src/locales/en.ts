@@ -116,6 +116,7 @@ const en: Translations = {
     viewDetails: "View details",
     overview: "Overview",
     ports: "Ports",
+    openInBrowser: "Open in browser",
     terminal: "Terminal",
     logs: "Logs",
     error: "Error",
@@ -126,6 +127,8 @@ const en: Translations = {
     cpuCores: "CPU Cores",
     memoryMb: "Memory (MB)",
     selectTemplate: "Select a template...",
+    platform: "Platform",
+    platformDefault: "Runtime default",
     builtinRuntimeNotReady: "Built-in runtime not started. Go to Settings > Runtime to start the engine",
   },
   mcp: {
@@ -189,6 +192,7 @@ const en: Translations = {
     pull: "Pull",
     searchError: "Docker is not connected. Please start Docker to search images.",
     searchProxyHint: "Image search failed due to network restrictions. Configure Runtime HTTP Proxy in Settings > Runtime, then restart Runtime.",
+    searchPartial: "Some registries could not be searched: {sources}",
   },
   settings: {
     title: "Settings",
@@ -233,6 +237,16 @@ const en: Translations = {
     maxHistoryDesc: "Maximum number of messages to keep in context.",
     confirmDestructive: "Confirm Destructive Operations",
     confirmDestructiveDesc: "Show a confirmation dialog before delete/stop operations.",
+    autostart: "Start at Login",
+    autostartDesc: "Launch CrateBay hidden in the tray at login and start the runtime, so containers with a restart policy come up.",
+    notifyRuntimeCrash: "Notify on Runtime Crash",
+    notifyRuntimeCrashDesc: "Alert when the container runtime stops unexpectedly.",
+    notifyContainerOom: "Notify on Container OOM",
+    notifyContainerOomDesc: "Alert when a container runs out of memory.",
+    notifyDiskFull: "Notify on Low Disk Space",
+    notifyDiskFullDesc: "Alert when the runtime disk is more than 90% full.",
+    notifyOperationComplete: "Notify When Long Operations Finish",
+    notifyOperationCompleteDesc: "Alert when an image pull, push or build that took over 30 seconds finishes.",
     aboutSubtitle: "AI-Native Development Control Plane",
     builtWith: "Built with",
     license: "License",
@@ -245,6 +259,8 @@ const en: Translations = {
     runtimeControl: "Runtime Control",
     runtimeControlDesc: "Start or stop the built-in container runtime",
     runtimeRestart: "Restart Runtime",
+    runtimeMetrics: "Runtime Usage",
+    runtimeMetricsUnavailable: "Start the runtime to see live usage.",
     runtimeHttpProxy: "Runtime HTTP Proxy",
     runtimeHttpProxyDesc: "Optional proxy endpoint used by the runtime VM for image pulls/search (host:port or URL).",
     runtimeHttpProxyBridge: "Enable Proxy Bridge (macOS)",
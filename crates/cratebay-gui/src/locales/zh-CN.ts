@@ -116,6 +116,7 @@ const zhCN: Translations = {
     viewDetails: "查看详情",
     overview: "概览",
     ports: "端口",
+    openInBrowser: "在浏览器中打开",
     terminal: "终端",
     logs: "日志",
     error: "错误",
@@ -126,6 +127,8 @@ const zhCN: Translations = {
     cpuCores: "CPU 核心数",
     memoryMb: "内存 (MB)",
     selectTemplate: "选择模板...",
+    platform: "平台",
+    platformDefault: "运行时默认",
     builtinRuntimeNotReady: "自研 Runtime 未启动，请在 Settings > Runtime 中启动引擎",
   },
   mcp: {
@@ -189,6 +192,7 @@ const zhCN: Translations = {
     pull: "拉取",
     searchError: "Docker 未连接，请先启动 Docker 才能搜索镜像。",
     searchProxyHint: "镜像搜索失败，当前网络可能需要代理。请在 设置 > Runtime 配置 HTTP Proxy，并重启 Runtime 后重试。",
+    searchPartial: "部分镜像仓库搜索失败：{sources}",
   },
   settings: {
     title: "设置",
@@ -233,6 +237,16 @@ const zhCN: Translations = {
     maxHistoryDesc: "上下文中保留的最大消息数量。",
     confirmDestructive: "确认危险操作",
     confirmDestructiveDesc: "在执行删除/停止操作前显示确认对话框。",
+    autostart: "登录时启动",
+    autostartDesc: "登录时在托盘中隐藏启动 CrateBay 并启动运行时，使设置了重启策略的容器自动运行。",
+    notifyRuntimeCrash: "运行时崩溃通知",
+    notifyRuntimeCrashDesc: "容器运行时意外停止时提醒。",
+    notifyContainerOom: "容器内存耗尽通知",
+    notifyContainerOomDesc: "容器发生 OOM 时提醒。",
+    notifyDiskFull: "磁盘空间不足通知",
+    notifyDiskFullDesc: "运行时磁盘使用超过 90% 时提醒。",
+    notifyOperationComplete: "长时间操作完成通知",
+    notifyOperationCompleteDesc: "耗时超过 30 秒的镜像拉取、推送或构建完成时提醒。",
     aboutSubtitle: "AI 原生开发控制平面",
     builtWith: "构建技术",
     license: "开源许可",
@@ -245,6 +259,8 @@ const zhCN: Translations = {
     runtimeControl: "运行时控制",
     runtimeControlDesc: "启动或停止内置容器运行时",
     runtimeRestart: "重启 Runtime",
+    runtimeMetrics: "运行时资源占用",
+    runtimeMetricsUnavailable: "启动运行时后可查看实时占用。",
     runtimeHttpProxy: "Runtime HTTP 代理",
     runtimeHttpProxyDesc: "可选的运行时代理地址（host:port 或 URL），用于 VM 内镜像拉取/搜索。",
     runtimeHttpProxyBridge: "启用代理桥接（macOS）",
@@ -21,6 +21,7 @@ import { PullTaskList } from "@/components/images/PullTaskList";
 import type {
   LocalImageInfo,
   ImageSearchResult,
+  ImageSearchResponse,
   ImageInspectInfo,
 } from "@/types/image";
 import { Button } from "@/components/ui/button";
@@ -619,20 +620,26 @@ function SearchImagesTab({ onToolbar }: { onToolbar: (node: React.ReactNode) =>
   const [results, setResults] = useState<ImageSearchResult[]>([]);
   const [searching, setSearching] = useState(false);
   const [searchError, setSearchError] = useState<string | null>(null);
+  const [searchWarning, setSearchWarning] = useState<string | null>(null);
   const startPull = usePullStore((s) => s.startPull);
 
   const handleSearch = useCallback(async () => {
     if (query.trim().length === 0) return;
     setSearching(true);
     setSearchError(null);
+    setSearchWarning(null);
     try {
       const data = await Promise.race([
-        invoke<ImageSearchResult[]>("image_search", { query: query.trim() }),
-        new Promise<ImageSearchResult[]>((_, reject) =>
+        invoke<ImageSearchResponse>("image_search", { query: query.trim() }),
+        new Promise<ImageSearchResponse>((_, reject) =>
           window.setTimeout(() => reject(new Error("Image search timeout (15s)")), 15000),
         ),
       ]);
-      setResults(data);
+      setResults(data.results);
+      if (data.errors.length > 0) {
+        const sources = data.errors.map((e) => `${e.source} (${e.error})`).join(", ");
+        setSearchWarning(t("images", "searchPartial").replace("{sources}", sources));
+      }
     } catch (err) {
       setResults([]);
       const message = err instanceof Error ? err.message : String(err);
@@ -705,6 +712,11 @@ function SearchImagesTab({ onToolbar }: { onToolbar: (node: React.ReactNode) =>
           {searchError}
         </div>
       )}
+      {searchWarning !== null && (
+        <div className="mb-4 rounded-md border border-border bg-muted px-3 py-2 text-xs text-muted-foreground">
+          {searchWarning}
+        </div>
+      )}
 
       {/* Results */}
       {results.length === 0 ? (
@@ -1,5 +1,6 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "@/lib/errors";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useAppStore } from "@/stores/appStore";
 import { useI18n } from "@/lib/i18n";
@@ -17,6 +18,7 @@ import { Button } from "@/components/ui/button";
 import { ProviderForm } from "@/components/settings/ProviderForm";
 import { ProviderCard } from "@/components/settings/ProviderCard";
 import { ReasoningEffort } from "@/components/settings/ReasoningEffort";
+import { RuntimeMonitoring } from "@/components/settings/RuntimeMonitoring";
 import {
   Moon,
   Sun,
@@ -355,7 +357,10 @@ function RuntimeTab() {
   const handleRuntimeStart = async () => {
     try {
       setRuntimeLoading(true);
+      // Returns once the boot is running in the background; completion
+      // arrives as runtime:state-change / docker:connected.
       await invoke("runtime_start");
+      useAppStore.getState().setRuntimeStatus("starting");
       addNotification({
         type: "success",
         title: t("settings", "runtimeStarting"),
@@ -363,7 +368,7 @@ function RuntimeTab() {
         dismissable: true,
       });
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = errorMessage(error);
       addNotification({
         type: "error",
         title: t("common", "error"),
@@ -386,7 +391,7 @@ function RuntimeTab() {
         dismissable: true,
       });
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = errorMessage(error);
       addNotification({
         type: "error",
         title: t("common", "error"),
@@ -405,6 +410,7 @@ function RuntimeTab() {
         await invoke("runtime_stop");
       }
       await invoke("runtime_start");
+      useAppStore.getState().setRuntimeStatus("starting");
       addNotification({
         type: "success",
         title: t("settings", "runtimeRestart"),
@@ -412,7 +418,7 @@ function RuntimeTab() {
         dismissable: true,
       });
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = errorMessage(error);
       addNotification({
         type: "error",
         title: t("common", "error"),
@@ -435,7 +441,7 @@ function RuntimeTab() {
         dismissable: true,
       });
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = errorMessage(error);
       addNotification({
         type: "error",
         title: t("common", "error"),
@@ -516,6 +522,10 @@ function RuntimeTab() {
         </div>
       </SettingRow>
 
+      <div className="py-3 border-b border-border">
+        <RuntimeMonitoring enabled={dockerConnected} />
+      </div>
+
       <SettingRow
         label={t("settings", "runtimeHttpProxy")}
         description={t("settings", "runtimeHttpProxyDesc")}
@@ -709,6 +719,14 @@ function RegistryMirrorsSection() {
 
 /* ---------- Advanced Tab ---------- */
 
+/** Settings keys of the `app:notification` category toggles. */
+const NOTIFICATION_TOGGLES = [
+  "notifyRuntimeCrash",
+  "notifyContainerOom",
+  "notifyDiskFull",
+  "notifyOperationComplete",
+] as const;
+
 function AdvancedTab() {
   const { t } = useI18n();
   const settings = useSettingsStore((s) => s.settings);
@@ -761,6 +779,25 @@ function AdvancedTab() {
           onCheckedChange={(v) => void updateSettings({ confirmDestructiveOps: v })}
         />
       </SettingRow>
+
+      <SettingRow
+        label={t("settings", "autostart")}
+        description={t("settings", "autostartDesc")}
+      >
+        <Switch
+          checked={settings.autostart}
+          onCheckedChange={(v) => void updateSettings({ autostart: v })}
+        />
+      </SettingRow>
+
+      {NOTIFICATION_TOGGLES.map((key) => (
+        <SettingRow key={key} label={t("settings", key)} description={t("settings", `${key}Desc`)}>
+          <Switch
+            checked={settings[key]}
+            onCheckedChange={(v) => void updateSettings({ [key]: v })}
+          />
+        </SettingRow>
+      ))}
     </div>
   );
 }
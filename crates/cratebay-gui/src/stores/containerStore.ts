@@ -1,4 +1,5 @@
 import { create } from "zustand";
+import { CommandError, errorMessage } from "@/lib/errors";
 import { invoke, listen } from "@/lib/tauri";
 import { useAppStore } from "@/stores/appStore";
 import { useSettingsStore } from "@/stores/settingsStore";
@@ -8,6 +9,7 @@ import type {
   ContainerTemplate,
   ContainerFilter,
   DockerImageInfo,
+  RunValidationReport,
 } from "@/types/container";
 import type { LocalImageInfo } from "@/types/image";
 
@@ -49,6 +51,11 @@ interface ContainerState {
   filteredContainers: () => ContainerInfo[];
 }
 
+/** A backend timeout, after which Docker may still have finished the work. */
+function isTimeout(err: unknown): boolean {
+  return err instanceof CommandError && err.code === "RUNTIME" && err.message.includes("timed out");
+}
+
 export const useContainerStore = create<ContainerState>()((set, get) => ({
   containers: [],
   loading: false,
@@ -105,7 +112,7 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
       if (requestController.signal.aborted) {
         console.warn("[containerStore] fetchContainers aborted");
       } else {
-        const message = err instanceof Error ? err.message : String(err);
+        const message = errorMessage(err);
         console.warn("[containerStore] fetchContainers failed:", message);
         if (get()._fetchAbortController === requestController) {
           set({
@@ -131,7 +138,7 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
       const images = await invoke<LocalImageInfo[]>("image_list");
       set({ images, imagesLoading: false });
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       console.error("[containerStore] fetchImages failed:", message);
       set({ imagesLoading: false });
     }
@@ -170,17 +177,20 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
     };
 
     try {
-      // Step 1: Check if image exists locally
-      let needsPull = false;
-      try {
-        const images = await invoke<LocalImageInfo[]>("image_list");
-        const imageTag = req.image;
-        needsPull = !images.some((img) =>
-          img.repoTags.some((tag) => tag === imageTag),
-        );
-      } catch {
-        // If image_list fails, try to create directly
-        needsPull = false;
+      // Step 1: Check if image exists locally. An explicit platform always
+      // pulls: the local image may be another platform's variant.
+      let needsPull = req.platform !== undefined;
+      if (!needsPull) {
+        try {
+          const images = await invoke<LocalImageInfo[]>("image_list");
+          const imageTag = req.image;
+          needsPull = !images.some((img) =>
+            img.repoTags.some((tag) => tag === imageTag),
+          );
+        } catch {
+          // If image_list fails, try to create directly
+          needsPull = false;
+        }
       }
 
       // Step 2: Pull image if needed (non-blocking backend, event-driven)
@@ -205,6 +215,7 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
           image: req.image,
           mirrors: hasMirrors ? mirrors : null,
           channel_id: pullChannelId,
+          platform: req.platform ?? null,
         });
 
         // Wait for pull completion via event
@@ -268,7 +279,17 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
 
       // Step 3: Create container (image already available)
       updatePlaceholder("正在创建容器...");
-      const container = await invoke<ContainerInfo>("container_create", { request: req });
+      // One key per create: a retry after a backend timeout returns the
+      // container the first attempt made instead of creating a duplicate.
+      const request = { ...req, idempotencyKey: req.idempotencyKey ?? crypto.randomUUID() };
+      let container: ContainerInfo;
+      try {
+        container = await invoke<ContainerInfo>("container_create", { request });
+      } catch (err) {
+        if (!isTimeout(err)) throw err;
+        updatePlaceholder("正在创建容器...");
+        container = await invoke<ContainerInfo>("container_create", { request });
+      }
       // Replace placeholder with real container
       set((state) => ({
         containers: state.containers.map((c) =>
@@ -276,6 +297,21 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
         ),
       }));
 
+      // An image for another architecture runs emulated, or exits with
+      // "exec format error" when the runtime has no emulator; say which.
+      void invoke<RunValidationReport>("docker_run_validate", { request: req })
+        .then((report) => {
+          for (const issue of report.issues.filter((i) => i.field === "platform")) {
+            notify({
+              type: "warning",
+              title: `镜像 ${req.image} 架构与运行时不一致`,
+              message: issue.message,
+              dismissable: true,
+            });
+          }
+        })
+        .catch(() => {});
+
       // Check if auto-start succeeded
       if (container.state === "created" || container.status === "created") {
         // Container was created but auto-start failed (e.g. invalid CMD for the image)
@@ -302,7 +338,7 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
       }
       return container;
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       console.error("[containerStore] createContainer failed:", message);
       // Remove the placeholder on failure
       set((state) => ({
@@ -349,7 +385,7 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
         });
       }
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       console.error("[containerStore] startContainer failed:", message);
       set({ error: message });
       notify({
@@ -374,7 +410,7 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
       // Refresh from backend to get real status
       await get().fetchContainers();
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       console.error("[containerStore] stopContainer failed:", message);
       set({ error: message });
       notify({
@@ -394,7 +430,7 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
         selectedContainerId: state.selectedContainerId === id ? null : state.selectedContainerId,
       }));
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       console.error("[containerStore] deleteContainer failed:", message);
       set({ error: message });
     }
@@ -406,7 +442,7 @@ export const useContainerStore = create<ContainerState>()((set, get) => ({
       const templates = await invoke<ContainerTemplate[]>("container_templates");
       set({ templates });
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       console.error("[containerStore] fetchTemplates failed:", message);
       // Provide built-in templates as fallback (these are part of the app, not mock)
       set({
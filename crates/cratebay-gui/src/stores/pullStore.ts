@@ -1,4 +1,5 @@
 import { create } from "zustand";
+import { errorMessage } from "@/lib/errors";
 import { invoke, listen } from "@/lib/tauri";
 import { useSettingsStore } from "@/stores/settingsStore";
 
@@ -141,7 +142,7 @@ export const usePullStore = create<PullState>()((set, get) => ({
         },
       );
     } catch (err) {
-      const message = err instanceof Error ? err.message : String(err);
+      const message = errorMessage(err);
       set((state) => ({
         tasks: state.tasks.map((t) =>
           t.id === tempId || (!t.complete && t.image === trimmedImage)
@@ -67,6 +67,7 @@ const defaultSettings: AppSettings = {
   maxConversationHistory: 50,
   containerDefaultTtlHours: 8,
   confirmDestructiveOps: true,
+  autostart: false,
   reasoningEffort: "medium",
   registryMirrors: DEFAULT_REGISTRY_MIRRORS,
   runtimeHttpProxy: "",
@@ -74,6 +75,10 @@ const defaultSettings: AppSettings = {
   runtimeHttpProxyBindHost: "0.0.0.0",
   runtimeHttpProxyBindPort: 3128,
   runtimeHttpProxyGuestHost: "192.168.64.1",
+  notifyRuntimeCrash: true,
+  notifyContainerOom: true,
+  notifyDiskFull: true,
+  notifyOperationComplete: true,
 };
 
 let providerIdCounter = 0;
@@ -87,7 +92,7 @@ function shouldUseMockFallback(): boolean {
 
 async function persistSelectionSetting(key: string, value: string | null): Promise<void> {
   if (!isTauri()) return;
-  await invoke("settings_update", { key, value: value ?? "" });
+  await invoke("settings_set", { key, value: value ?? "" });
 }
 
 export const useSettingsStore = create<SettingsState>()((set, get) => ({
@@ -335,9 +340,11 @@ export const useSettingsStore = create<SettingsState>()((set, get) => ({
       const keys: (keyof AppSettings)[] = [
         "language", "theme", "sendOnEnter", "showAgentThinking",
         "maxConversationHistory", "containerDefaultTtlHours",
-        "confirmDestructiveOps", "reasoningEffort", "registryMirrors",
+        "confirmDestructiveOps", "autostart", "reasoningEffort", "registryMirrors",
         "runtimeHttpProxy", "runtimeHttpProxyBridge", "runtimeHttpProxyBindHost",
         "runtimeHttpProxyBindPort", "runtimeHttpProxyGuestHost",
+        "notifyRuntimeCrash", "notifyContainerOom", "notifyDiskFull",
+        "notifyOperationComplete",
       ];
       const fetched: Partial<AppSettings> = {};
       for (const key of keys) {
@@ -397,10 +404,10 @@ export const useSettingsStore = create<SettingsState>()((set, get) => ({
     const newSettings = { ...get().settings, ...patch };
     set({ settings: newSettings });
     try {
-      // settings_update takes (key, value) pairs, so we update each changed key
+      // settings_set takes (key, value) pairs, so we update each changed key
       for (const [key, value] of Object.entries(patch)) {
         const serialized = Array.isArray(value) ? JSON.stringify(value) : String(value);
-        await invoke("settings_update", { key, value: serialized });
+        await invoke("settings_set", { key, value: serialized });
       }
     } catch (err) {
       if (!shouldUseMockFallback()) {
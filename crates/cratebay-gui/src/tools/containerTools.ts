@@ -67,6 +67,12 @@ const ContainerCreateParams = Type.Object({
       maximum: 65536,
     }),
   ),
+  platform: Type.Optional(
+    Type.String({
+      description:
+        "Platform to run, e.g. 'linux/amd64' (emulated on arm64); pull the image for it first",
+    }),
+  ),
   env: Type.Optional(
     Type.Union([
       Type.Array(Type.String(), {
@@ -155,6 +161,24 @@ const ContainerLogsParams = Type.Object({
   ),
 });
 
+const ContainerCommitParams = Type.Object({
+  containerId: Type.String({ description: "Container ID or name", minLength: 1 }),
+  target: Type.String({
+    description: "New image reference in repo:tag format",
+    minLength: 1,
+  }),
+  author: Type.Optional(Type.String({ description: "Image author" })),
+  message: Type.Optional(Type.String({ description: "Commit message" })),
+  changes: Type.Optional(
+    Type.Array(Type.String(), {
+      description: "Dockerfile instructions to apply, e.g. 'ENV APP_ENV=prod' or 'CMD [\"app\"]'",
+    }),
+  ),
+  pause: Type.Optional(
+    Type.Boolean({ description: "Pause the container while committing (default true)" }),
+  ),
+});
+
 type ContainerSummary = {
   id: string;
   shortId?: string;
@@ -457,6 +481,7 @@ export const containerCreateTool: AgentTool<typeof ContainerCreateParams> = {
 
     const memoryMb = params.memoryMb ?? params.memory_mb;
     if (memoryMb !== undefined) request.memoryMb = memoryMb;
+    if (params.platform !== undefined) request.platform = params.platform;
 
     if (params.ttlHours !== undefined) {
       request.labels = {
@@ -576,6 +601,26 @@ export const containerLogsTool: AgentTool<typeof ContainerLogsParams> = {
   },
 };
 
+export const containerCommitTool: AgentTool<typeof ContainerCommitParams> = {
+  name: "container_commit",
+  label: "Commit Container",
+  description: "Save a container's filesystem and config as a new local image.",
+  parameters: ContainerCommitParams,
+  execute: async (_toolCallId, params) => {
+    const imageId = await invoke<string>("container_commit", {
+      id: params.containerId,
+      request: {
+        target: params.target,
+        author: params.author,
+        message: params.message,
+        changes: params.changes ?? [],
+        pause: params.pause,
+      },
+    });
+    return textResult(`Committed ${params.containerId} as ${params.target} (${imageId}).`);
+  },
+};
+
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 export const containerTools: AgentTool<any>[] = [
   containerListTool,
@@ -586,4 +631,5 @@ export const containerTools: AgentTool<any>[] = [
   containerDeleteTool,
   containerExecTool,
   containerLogsTool,
+  containerCommitTool,
 ];
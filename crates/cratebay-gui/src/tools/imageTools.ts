@@ -9,7 +9,7 @@ import type {
   AgentToolUpdateCallback,
 } from "@mariozechner/pi-agent-core";
 import { invoke } from "@/lib/tauri";
-import type { ImageInspectInfo, ImageSearchResult, LocalImageInfo } from "@/types/image";
+import type { ImageInspectInfo, ImageSearchResponse, LocalImageInfo } from "@/types/image";
 
 const EmptyParams = Type.Object({});
 
@@ -25,6 +25,15 @@ const ImageSearchParams = Type.Object({
       maximum: 50,
     }),
   ),
+  source: Type.Optional(
+    Type.Union(
+      [Type.Literal("dockerhub"), Type.Literal("quay"), Type.Literal("ghcr"), Type.Literal("gitlab")],
+      {
+        description:
+          "Registry to search (default: Docker Hub and Quay together). For ghcr, query is '<owner>' or '<owner>/<name filter>'",
+      },
+    ),
+  ),
 });
 
 const ImagePullParams = Type.Object({
@@ -37,6 +46,11 @@ const ImagePullParams = Type.Object({
       description: "Optional registry mirror URLs",
     }),
   ),
+  platform: Type.Optional(
+    Type.String({
+      description: "Variant to pull, e.g. 'linux/amd64' or 'linux/arm64'",
+    }),
+  ),
 });
 
 const ImageRemoveParams = Type.Object({
@@ -110,18 +124,21 @@ export const imageSearchTool: AgentTool<typeof ImageSearchParams> = {
   description: "Search Docker registries for images by keyword.",
   parameters: ImageSearchParams,
   execute: async (_toolCallId, params) => {
-    const results = await Promise.race([
-      invoke<ImageSearchResult[]>("image_search", {
+    const response = await Promise.race([
+      invoke<ImageSearchResponse>("image_search", {
         query: params.query,
         limit: params.limit ?? 10,
+        source: params.source,
       }),
-      new Promise<ImageSearchResult[]>((_, reject) =>
+      new Promise<ImageSearchResponse>((_, reject) =>
         window.setTimeout(() => reject(new Error("Image search timeout (15s)")), 15000),
       ),
     ]);
+    const results = response.results;
+    const failed = response.errors.map((e) => `\n(${e.source} unavailable: ${e.error})`).join("");
 
     if (!Array.isArray(results) || results.length === 0) {
-      return textResult(`No image results found for "${params.query}".`);
+      return textResult(`No image results found for "${params.query}".${failed}`);
     }
 
     const lines = results.map((result) => {
@@ -130,7 +147,7 @@ export const imageSearchTool: AgentTool<typeof ImageSearchParams> = {
       return `- **${result.reference}** (source: ${result.source}, stars: ${stars}, pulls: ${pulls})`;
     });
 
-    return textResult(`Found ${results.length} result(s):\n${lines.join("\n")}`);
+    return textResult(`Found ${results.length} result(s):\n${lines.join("\n")}${failed}`);
   },
 };
 
@@ -154,6 +171,7 @@ export const imagePullTool: AgentTool<typeof ImagePullParams> = {
     const channelId = await invoke<string>("image_pull", {
       image: params.image,
       mirrors: params.mirrors,
+      platform: params.platform,
     });
 
     onUpdate?.({
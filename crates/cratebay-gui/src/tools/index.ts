@@ -53,6 +53,7 @@ export const toolRiskLevels: Record<string, RiskLevel> = {
   container_delete: "high",
   container_exec: "medium",
   container_logs: "low",
+  container_commit: "medium",
   image_list: "low",
   image_search: "low",
   image_pull: "medium",
@@ -0,0 +1,24 @@
+import type { LintSeverity } from "./image";
+
+/** A problem in a compose file; `path` is e.g. `services.web.ports[0]`. */
+export interface ComposeDiagnostic {
+  severity: LintSeverity;
+  path: string;
+  line?: number;
+  message: string;
+}
+
+export interface ComposeValidation {
+  file: string;
+  services: string[];
+  diagnostics: ComposeDiagnostic[];
+}
+
+export interface ComposeConversion {
+  file: string;
+  /** Generated objects as `Kind/name`; empty when the file has errors. */
+  resources: string[];
+  /** Multi-document YAML for `kubectl apply -f`. */
+  manifest: string;
+  diagnostics: ComposeDiagnostic[];
+}
@@ -34,6 +34,25 @@ export interface ContainerCreateRequest {
   cpuCores?: number;
   memoryMb?: number;
   autoStart?: boolean;
+  /** Replaying a key within 24h returns the container it created. */
+  idempotencyKey?: string;
+  /** e.g. `linux/amd64`; defaults to the runtime's own platform. */
+  platform?: string;
+}
+
+/**
+ * Issue found by `docker_run_validate`.
+ */
+export interface RunValidationIssue {
+  field: "name" | "image" | "platform" | "ports" | "volumes" | "cpuCores" | "memoryMb";
+  severity: "error" | "warning";
+  message: string;
+}
+
+export interface RunValidationReport {
+  /** No `error` issues; warnings may remain. */
+  ok: boolean;
+  issues: RunValidationIssue[];
 }
 
 /**
@@ -88,3 +107,15 @@ export interface ContainerLogEvent {
   stream: "stdout" | "stderr";
   timestamp: string;
 }
+
+/**
+ * Terminal output event, emitted as `terminal:output:{sessionId}` for
+ * sessions opened with `terminal_open`.
+ */
+export interface TerminalOutputEvent {
+  /** Output text; absent on the exit event. */
+  data?: string;
+  /** The shell exited; no further events follow. */
+  exited: boolean;
+  exitCode?: number;
+}
@@ -0,0 +1,34 @@
+/**
+ * Structured command error type definitions.
+ *
+ * Mirrors `cratebay_core::error::ErrorDetail`: every Tauri command rejects
+ * with one of these, and `invoke` rethrows it as a `CommandError`.
+ */
+
+export type ErrorCode =
+  | "DOCKER_UNREACHABLE"
+  | "DOCKER"
+  | "DATABASE"
+  | "LLM_PROXY"
+  | "VALIDATION"
+  | "NOT_FOUND"
+  | "MCP"
+  | "RUNTIME"
+  | "IO"
+  | "SERIALIZATION"
+  | "PERMISSION_DENIED"
+  | "QUOTA_EXCEEDED"
+  | "CANCELLED"
+  | "ROSETTA_UNAVAILABLE"
+  | "SHARED_DIRECTORY"
+  | "ABORTED"
+  | "REQUIRES_RESTART";
+
+export interface ErrorDetail {
+  code: ErrorCode;
+  message: string;
+  /** The resource involved, e.g. `container/web` or `max_cpus`. */
+  resource?: string;
+  /** What the user can do about it. */
+  hint?: string;
+}
@@ -121,6 +121,7 @@ export interface Translations {
     viewDetails: string;
     overview: string;
     ports: string;
+    openInBrowser: string;
     terminal: string;
     logs: string;
     error: string;
@@ -131,6 +132,8 @@ export interface Translations {
     cpuCores: string;
     memoryMb: string;
     selectTemplate: string;
+    platform: string;
+    platformDefault: string;
     builtinRuntimeNotReady: string;
   };
   mcp: {
@@ -194,6 +197,7 @@ export interface Translations {
     pull: string;
     searchError: string;
     searchProxyHint: string;
+    searchPartial: string;
   };
   settings: {
     title: string;
@@ -238,6 +242,16 @@ export interface Translations {
     maxHistoryDesc: string;
     confirmDestructive: string;
     confirmDestructiveDesc: string;
+    autostart: string;
+    autostartDesc: string;
+    notifyRuntimeCrash: string;
+    notifyRuntimeCrashDesc: string;
+    notifyContainerOom: string;
+    notifyContainerOomDesc: string;
+    notifyDiskFull: string;
+    notifyDiskFullDesc: string;
+    notifyOperationComplete: string;
+    notifyOperationCompleteDesc: string;
     aboutSubtitle: string;
     builtWith: string;
     license: string;
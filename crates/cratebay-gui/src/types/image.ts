@@ -2,7 +2,8 @@
  * Image-related types for CrateBay.
  *
  * Matches the Tauri commands: image_list, image_search, image_pull,
- * image_remove, image_inspect, image_tag.
+ * image_remove, image_inspect, image_tag, image_manifest,
+ * image_details, image_tags, image_layers, registry_catalog.
  */
 
 export interface LocalImageInfo {
@@ -22,6 +23,12 @@ export interface ImageSearchResult {
   official: boolean;
 }
 
+/** Merged multi-registry search; `errors` lists sources that failed. */
+export interface ImageSearchResponse {
+  results: ImageSearchResult[];
+  errors: { source: string; error: string }[];
+}
+
 export interface ImageInspectInfo {
   id: string;
   repoTags: string[];
@@ -32,3 +39,137 @@ export interface ImageInspectInfo {
   dockerVersion: string;
   layers: number;
 }
+
+/** Result of image_layers, newest layer first. */
+export interface ImageLayers {
+  reference: string;
+  source: "local" | "remote";
+  platform?: string;
+  /** Remote sizes are compressed, local ones unpacked. */
+  compressed: boolean;
+  totalSize: number;
+  layers: ImageLayer[];
+}
+
+export interface ImageLayer {
+  digest?: string;
+  /** Instruction, e.g. `RUN apt-get update`. */
+  createdBy: string;
+  created?: string;
+  size: number;
+  /** Metadata-only instruction (ENV, CMD) without a layer. */
+  empty: boolean;
+  comment?: string;
+}
+
+export interface RemotePlatform {
+  platform: string; // os/arch[/variant]
+  digest: string;
+  size: number;
+}
+
+export interface RemoteLayer {
+  digest: string;
+  mediaType: string;
+  size: number;
+}
+
+export interface RemoteImageManifest {
+  reference: string;
+  digest?: string;
+  mediaType: string;
+  multiArch: boolean;
+  platforms: RemotePlatform[];
+  platform?: string;
+  layers: RemoteLayer[];
+  totalSize: number;
+  configDigest?: string;
+  labels: Record<string, string>;
+  annotations: Record<string, string>;
+  artifactType: ArtifactKind;
+  referrers: RemoteReferrer[];
+}
+
+export type ArtifactKind =
+  | "image"
+  | "helm-chart"
+  | "wasm"
+  | "sbom"
+  | "signature"
+  | "attestation"
+  | "other";
+
+/** An artifact (signature, SBOM, attestation) attached to a manifest. */
+export interface RemoteReferrer {
+  digest: string;
+  artifactType: string;
+  kind: ArtifactKind;
+  size: number;
+  annotations: Record<string, string>;
+}
+
+export interface ImageDetails {
+  reference: string;
+  source: "dockerhub" | "quay" | "oci";
+  title?: string;
+  description?: string;
+  /** Long description in Markdown. */
+  readme?: string;
+  url?: string;
+  sourceUrl?: string;
+  documentation?: string;
+  licenses?: string;
+  stars?: number;
+  pulls?: number;
+  lastUpdated?: string;
+  /** `org.opencontainers.image.*` annotations and labels. */
+  oci: Record<string, string>;
+  warnings: string[];
+}
+
+export interface RegistryTagList {
+  repository: string;
+  tags: string[];
+  /** Kind of the tags on this page that are not plain images. */
+  artifactTypes?: Record<string, ArtifactKind>;
+  total: number;
+  page: number;
+  pageSize: number;
+  hasMore: boolean;
+}
+
+/** A path segment of a registry catalog; `repository` if pullable itself. */
+export interface RegistryCatalogNode {
+  name: string;
+  path: string;
+  repository: boolean;
+  children: RegistryCatalogNode[];
+}
+
+export interface RegistryCatalog {
+  registry: string;
+  repositories: string[];
+  /** This page's repositories grouped by path segment. */
+  tree?: RegistryCatalogNode[];
+  total: number;
+  page: number;
+  pageSize: number;
+  hasMore: boolean;
+}
+
+export type LintSeverity = "info" | "warning" | "error";
+
+/** A Dockerfile lint finding shown in the build dialog. */
+export interface LintFinding {
+  /** Rule ID, e.g. `latest-tag`. */
+  rule: string;
+  severity: LintSeverity;
+  line: number;
+  instruction: string;
+  message: string;
+}
+
+export interface DockerfileLint {
+  dockerfile: string;
+  findings: LintFinding[];
+}
@@ -23,13 +23,25 @@ export type { ChatSession, ChatMessage, ToolCallInfo, LlmTokenEvent } from "./ch
 export type {
   ContainerInfo,
   ContainerCreateRequest,
+  RunValidationIssue,
+  RunValidationReport,
   ContainerTemplate,
   ContainerFilter,
   PortMapping,
   ContainerStatusEvent,
   ContainerLogEvent,
+  TerminalOutputEvent,
 } from "./container";
 
+// Compose types
+export type { ComposeDiagnostic, ComposeValidation, ComposeConversion } from "./compose";
+
+// Command error types
+export type { ErrorCode, ErrorDetail } from "./error";
+
+// Background task types
+export type { TaskInfo, TaskKind, TaskStatus } from "./task";
+
 // MCP types
 export type { McpServerInfo, McpServerConfig, McpToolInfo } from "./mcp";
 
@@ -41,7 +53,11 @@ export type {
   LlmProviderUpdateRequest,
   LlmModelInfo,
   AppSettings,
+  SettingsChangedEvent,
 } from "./settings";
 
+// Update types
+export type { UpdateInfo } from "./update";
+
 // i18n types
 export type { Translations } from "./i18n";
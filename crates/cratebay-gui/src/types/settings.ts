@@ -76,6 +76,7 @@ export interface AppSettings {
   maxConversationHistory: number;
   containerDefaultTtlHours: number;
   confirmDestructiveOps: boolean;
+  autostart: boolean; // Start hidden in the tray at login (reflects the login item)
   reasoningEffort: "low" | "medium" | "high"; // Global reasoning effort preference
   registryMirrors: string[]; // Docker registry mirror URLs
   runtimeHttpProxy: string;
@@ -83,6 +84,19 @@ export interface AppSettings {
   runtimeHttpProxyBindHost: string;
   runtimeHttpProxyBindPort: number;
   runtimeHttpProxyGuestHost: string;
+  notifyRuntimeCrash: boolean;
+  notifyContainerOom: boolean;
+  notifyDiskFull: boolean;
+  notifyOperationComplete: boolean;
+}
+
+/**
+ * Payload of the `settings:changed` event, emitted by `settings_set`.
+ * `value` is absent when the key was unset.
+ */
+export interface SettingsChangedEvent {
+  key: string;
+  value?: string | null;
 }
 
 /**
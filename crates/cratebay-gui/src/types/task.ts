@@ -0,0 +1,38 @@
+/**
+ * Background task type definitions for the jobs panel.
+ *
+ * Mirrors `tasks::TaskInfo` in the Tauri backend; updates arrive as
+ * `task:update` events.
+ */
+
+export type TaskKind =
+  | "imagePull"
+  | "imagePush"
+  | "imageBuild"
+  | "imageSave"
+  | "containerExport"
+  | "runtimeStart";
+
+export type TaskStatus = "running" | "succeeded" | "failed" | "cancelled";
+
+/**
+ * A long-running operation, as returned by `task_list` and sent with
+ * `task:update`.
+ */
+export interface TaskInfo {
+  /** Task id; equals the channel id for pulls, pushes and builds. */
+  id: string;
+  kind: TaskKind;
+  title: string;
+  status: TaskStatus;
+  /** Progress percentage (0-100), when the operation reports one. */
+  progress?: number;
+  /** Latest status message. */
+  message?: string;
+  /** Operation result on success, e.g. the built image ID. */
+  result?: unknown;
+  error?: string;
+  /** RFC 3339 timestamps. */
+  startedAt: string;
+  finishedAt?: string;
+}
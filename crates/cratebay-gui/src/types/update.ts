@@ -0,0 +1,16 @@
+/**
+ * Release update check, mirroring `cratebay_core::update::UpdateInfo`
+ * returned by `update_check`.
+ */
+export interface UpdateInfo {
+  currentVersion: string;
+  latestVersion: string;
+  updateAvailable: boolean;
+  prerelease: boolean;
+  releaseUrl: string;
+  publishedAt: string | null;
+  notes: string | null; // Markdown release notes
+  assetName: string | null; // CLI binary for this platform
+  assetUrl: string | null;
+  checksumsUrl: string | null;
+}
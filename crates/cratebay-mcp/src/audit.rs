@@ -28,9 +28,12 @@ pub enum AuditResult {
     Error(String),
 }
 
-/// Get the audit log file path: `~/.cratebay/logs/mcp-audit.jsonl`.
+/// Get the audit log file path: `~/.cratebay/logs/mcp-audit.jsonl` (scoped
+/// to the active profile).
 fn audit_log_path() -> Option<PathBuf> {
-    dirs::home_dir().map(|h| h.join(".cratebay").join("logs").join("mcp-audit.jsonl"))
+    cratebay_core::storage::cratebay_home()
+        .ok()
+        .map(|h| h.join("logs").join("mcp-audit.jsonl"))
 }
 
 /// Write an audit entry to the JSONL log file.
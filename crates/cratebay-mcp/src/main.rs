@@ -40,13 +40,12 @@ struct McpState {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize tracing — write to stderr so stdout is reserved for JSON-RPC
-    tracing_subscriber::fmt()
-        .with_writer(std::io::stderr)
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .init();
+    cratebay_core::logging::init(
+        &cratebay_core::config::Config::load().unwrap_or_default(),
+        "info",
+        cratebay_core::logging::LogOutput::Stderr,
+        None,
+    );
 
     tracing::info!("CrateBay MCP Server starting...");
 
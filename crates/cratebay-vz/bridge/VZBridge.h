@@ -44,12 +44,25 @@ typedef struct {
     bool read_only;
 } VZSharedDir;
 
+// ---------------------------------------------------------------------------
+// Additional disk descriptor (passed from Rust to Swift).
+// ---------------------------------------------------------------------------
+
+typedef struct {
+    const char *path;
+    bool read_only;
+} VZDiskSpec;
+
 // ---------------------------------------------------------------------------
 // VM configuration (passed from Rust to Swift).
 // ---------------------------------------------------------------------------
 
+/// Boot loader selection for VZVMConfig.boot_mode.
+#define VZ_BOOT_MODE_LINUX 0   // VZLinuxBootLoader with an external kernel
+#define VZ_BOOT_MODE_EFI   1   // VZEFIBootLoader (macOS 13+), boots the disk
+
 typedef struct {
-    const char *kernel_path;
+    const char *kernel_path;   // Linux boot only; ignored for EFI
     const char *initrd_path;   // NULL if not used
     const char *cmdline;       // NULL => "console=hvc0"
     const char *disk_path;
@@ -60,6 +73,15 @@ typedef struct {
     bool enable_vsock;
     const VZSharedDir *shared_dirs;
     uint32_t shared_dirs_count;
+    uint32_t boot_mode;                  // VZ_BOOT_MODE_*
+    const char *efi_variable_store_path; // EFI only; created if missing
+    const char *iso_path;                // NULL => no installer media
+    const VZDiskSpec *extra_disks;       // attached after disk_path
+    uint32_t extra_disks_count;
+    const char *mac_address;             // "xx:xx:xx:xx:xx:xx"; NULL => random
+    bool gpu;                            // attach a virtio-gpu device
+    int32_t console_read_fd;             // serial input pipe; -1 => none
+    int32_t console_write_fd;            // serial output pipe; -1 => console_log_path
 } VZVMConfig;
 
 // ---------------------------------------------------------------------------
@@ -79,6 +101,11 @@ VZVMHandle vz_create_and_start_vm(const VZVMConfig *config,
 int32_t vz_stop_vm(VZVMHandle handle, double timeout_secs,
                     VZErrorString *out_error);
 
+/// Set the memory balloon target: the guest memory size in MB the balloon
+/// inflates or deflates towards. Returns 0 on success.
+int32_t vz_set_balloon_target(VZVMHandle handle, uint64_t target_mb,
+                              VZErrorString *out_error);
+
 /// Destroy the VM handle and free all associated resources. The VM must
 /// already be stopped. Returns 0 on success.
 int32_t vz_destroy_vm(VZVMHandle handle, VZErrorString *out_error);
@@ -196,12 +196,17 @@ public func vz_create_and_start_vm(
     }
     let cfg = configPtr.pointee
 
-    // --- Kernel path ---
-    guard let kernelCStr = cfg.kernel_path else {
-        setError(outError, "kernel_path is NULL")
-        return nil
+    let efiBoot = cfg.boot_mode == 1
+
+    // --- Kernel path (Linux boot only) ---
+    var kernelPath = ""
+    if !efiBoot {
+        guard let kernelCStr = cfg.kernel_path else {
+            setError(outError, "kernel_path is NULL")
+            return nil
+        }
+        kernelPath = String(cString: kernelCStr)
     }
-    let kernelPath = String(cString: kernelCStr)
 
     // --- Disk path ---
     guard let diskCStr = cfg.disk_path else {
@@ -230,17 +235,49 @@ public func vz_create_and_start_vm(
     vzConfig.platform = VZGenericPlatformConfiguration()
 
     // Boot loader
-    let kernelURL = URL(fileURLWithPath: kernelPath)
-    let bootLoader = VZLinuxBootLoader(kernelURL: kernelURL)
-    bootLoader.commandLine = cmdline
-    if let initrdPath = initrdPath {
-        bootLoader.initialRamdiskURL = URL(fileURLWithPath: initrdPath)
+    if efiBoot {
+        guard #available(macOS 13.0, *) else {
+            setError(outError, "EFI boot requires macOS 13.0 or later")
+            return nil
+        }
+        guard let storeCStr = cfg.efi_variable_store_path else {
+            setError(outError, "efi_variable_store_path is NULL")
+            return nil
+        }
+        let storeURL = URL(fileURLWithPath: String(cString: storeCStr))
+        let store: VZEFIVariableStore
+        do {
+            if FileManager.default.fileExists(atPath: storeURL.path) {
+                store = VZEFIVariableStore(url: storeURL)
+            } else {
+                try FileManager.default.createDirectory(
+                    at: storeURL.deletingLastPathComponent(),
+                    withIntermediateDirectories: true
+                )
+                store = try VZEFIVariableStore(creatingVariableStoreAt: storeURL)
+            }
+        } catch {
+            setError(outError, "Failed to open EFI variable store: \(describeNSError(error))")
+            return nil
+        }
+        let bootLoader = VZEFIBootLoader()
+        bootLoader.variableStore = store
+        vzConfig.bootLoader = bootLoader
+    } else {
+        let kernelURL = URL(fileURLWithPath: kernelPath)
+        let bootLoader = VZLinuxBootLoader(kernelURL: kernelURL)
+        bootLoader.commandLine = cmdline
+        if let initrdPath = initrdPath {
+            bootLoader.initialRamdiskURL = URL(fileURLWithPath: initrdPath)
+        }
+        vzConfig.bootLoader = bootLoader
     }
-    vzConfig.bootLoader = bootLoader
 
-    // CPU + Memory
+    // CPU + Memory. The balloon starts deflated; the host policy inflates
+    // it through vz_set_balloon_target to take idle guest memory back.
     vzConfig.cpuCount = cpus
     vzConfig.memorySize = memoryBytes
+    vzConfig.memoryBalloonDevices = [VZVirtioTraditionalMemoryBalloonDeviceConfiguration()]
 
     // --- Storage (virtio-blk) ---
     let diskURL = URL(fileURLWithPath: diskPath)
@@ -254,6 +291,46 @@ public func vz_create_and_start_vm(
     let blockDevice = VZVirtioBlockDeviceConfiguration(attachment: diskAttachment)
     vzConfig.storageDevices = [blockDevice]
 
+    // --- Additional disks (virtio-blk, in order: /dev/vdb, /dev/vdc, ...) ---
+    if cfg.extra_disks_count > 0, let disks = cfg.extra_disks {
+        for i in 0..<Int(cfg.extra_disks_count) {
+            let spec = disks[i]
+            guard let pathCStr = spec.path else {
+                continue
+            }
+            let path = String(cString: pathCStr)
+            do {
+                let attachment = try VZDiskImageStorageDeviceAttachment(
+                    url: URL(fileURLWithPath: path),
+                    readOnly: spec.read_only
+                )
+                vzConfig.storageDevices.append(VZVirtioBlockDeviceConfiguration(attachment: attachment))
+            } catch {
+                setError(outError, "Failed to attach disk \(path): \(error.localizedDescription)")
+                return nil
+            }
+        }
+    }
+
+    // --- Installer ISO (read-only; listed first so EFI firmware boots it) ---
+    if let isoCStr = cfg.iso_path {
+        let isoURL = URL(fileURLWithPath: String(cString: isoCStr))
+        let isoAttachment: VZDiskImageStorageDeviceAttachment
+        do {
+            isoAttachment = try VZDiskImageStorageDeviceAttachment(url: isoURL, readOnly: true)
+        } catch {
+            setError(outError, "Failed to attach ISO: \(error.localizedDescription)")
+            return nil
+        }
+        if #available(macOS 13.0, *) {
+            let cdrom = VZUSBMassStorageDeviceConfiguration(attachment: isoAttachment)
+            vzConfig.storageDevices.insert(cdrom, at: 0)
+        } else {
+            let cdrom = VZVirtioBlockDeviceConfiguration(attachment: isoAttachment)
+            vzConfig.storageDevices.insert(cdrom, at: 0)
+        }
+    }
+
     // --- Network (default NAT; bridged is explicit opt-in) ---
     let networkDevice = VZVirtioNetworkDeviceConfiguration()
     let requestedMode = ProcessInfo.processInfo.environment["CRATEBAY_VZ_NETWORK_MODE"]?
@@ -264,6 +341,14 @@ public func vz_create_and_start_vm(
     } else {
         networkDevice.attachment = VZNATNetworkDeviceAttachment()
     }
+    if let macCStr = cfg.mac_address {
+        let macString = String(cString: macCStr)
+        guard let mac = VZMACAddress(string: macString) else {
+            setError(outError, "Invalid MAC address: \(macString)")
+            return nil
+        }
+        networkDevice.macAddress = mac
+    }
     vzConfig.networkDevices = [networkDevice]
 
     // --- Virtio socket (vsock) ---
@@ -271,7 +356,7 @@ public func vz_create_and_start_vm(
         vzConfig.socketDevices = [VZVirtioSocketDeviceConfiguration()]
     }
 
-    // --- Serial console (file or stdout) ---
+    // --- Serial console (runner pipes, file or stdout) ---
     let serialPort = VZVirtioConsoleDeviceSerialPortConfiguration()
 
     var consoleLogPath: String? = nil
@@ -280,7 +365,14 @@ public func vz_create_and_start_vm(
     }
     let consoleReadHandle = FileHandle(forReadingAtPath: "/dev/null") ?? FileHandle.standardInput
 
-    if let logPath = consoleLogPath {
+    if cfg.console_read_fd >= 0 && cfg.console_write_fd >= 0 {
+        // The runner relays these to its console socket and appends the
+        // output to consoleLogPath itself.
+        serialPort.attachment = VZFileHandleSerialPortAttachment(
+            fileHandleForReading: FileHandle(fileDescriptor: cfg.console_read_fd, closeOnDealloc: false),
+            fileHandleForWriting: FileHandle(fileDescriptor: cfg.console_write_fd, closeOnDealloc: false)
+        )
+    } else if let logPath = consoleLogPath {
         let logURL = URL(fileURLWithPath: logPath)
         let logDir = logURL.deletingLastPathComponent()
         try? FileManager.default.createDirectory(at: logDir, withIntermediateDirectories: true)
@@ -337,6 +429,14 @@ public func vz_create_and_start_vm(
             if VZLinuxRosettaDirectoryShare.availability == .installed {
                 do {
                     let rosettaShare = try VZLinuxRosettaDirectoryShare()
+                    // Ahead-of-time translation cache: the guest runs
+                    // `rosettad daemon` on this abstract socket so translated
+                    // binaries survive container restarts. Best-effort.
+                    if #available(macOS 14.0, *) {
+                        if let caching = try? VZLinuxRosettaAbstractSocketCachingOptions(name: "rosetta") {
+                            rosettaShare.options = caching
+                        }
+                    }
                     let rosettaFS = VZVirtioFileSystemDeviceConfiguration(tag: "rosetta")
                     rosettaFS.share = rosettaShare
                     fileSystems.append(rosettaFS)
@@ -362,6 +462,17 @@ public func vz_create_and_start_vm(
         vzConfig.directorySharingDevices = fileSystems
     }
 
+    // --- GPU ---
+    // virtio-gpu with one headless scanout. Virtualization.framework only
+    // offers 2D for Linux guests; there is no host GPU acceleration.
+    if cfg.gpu {
+        let graphics = VZVirtioGraphicsDeviceConfiguration()
+        graphics.scanouts = [
+            VZVirtioGraphicsScanoutConfiguration(widthInPixels: 1280, heightInPixels: 800)
+        ]
+        vzConfig.graphicsDevices = [graphics]
+    }
+
     // --- Validate ---
     do {
         try vzConfig.validate()
@@ -503,6 +614,97 @@ public func vz_stop_vm(
     return 0
 }
 
+/// Pause (`resume == false`) or resume the VM, waiting up to 10 seconds.
+private func setPaused(
+    _ handle: UnsafeMutableRawPointer?,
+    resume: Bool,
+    _ outError: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?
+) -> Int32 {
+    guard let instance = lookupVM(handle) else {
+        setError(outError, "Invalid VM handle")
+        return -1
+    }
+
+    let semaphore = DispatchSemaphore(value: 0)
+    var opError: String? = nil
+    let verb = resume ? "resume" : "pause"
+
+    instance.queue.async {
+        let allowed = resume ? instance.vm.canResume : instance.vm.canPause
+        if !allowed {
+            opError = "VM cannot \(verb) in its current state"
+            semaphore.signal()
+            return
+        }
+        let completion: (Result<Void, Error>) -> Void = { result in
+            if case .failure(let error) = result {
+                opError = "VZ \(verb) failed: \(describeNSError(error))"
+            }
+            semaphore.signal()
+        }
+        if resume {
+            instance.vm.resume(completionHandler: completion)
+        } else {
+            instance.vm.pause(completionHandler: completion)
+        }
+    }
+
+    if semaphore.wait(timeout: .now() + 10) == .timedOut {
+        setError(outError, "Timed out waiting for VM to \(verb) (10s)")
+        return -1
+    }
+    if let err = opError {
+        setError(outError, err)
+        return -1
+    }
+    return 0
+}
+
+@_cdecl("vz_pause_vm")
+public func vz_pause_vm(
+    _ handle: UnsafeMutableRawPointer?,
+    _ outError: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?
+) -> Int32 {
+    return setPaused(handle, resume: false, outError)
+}
+
+@_cdecl("vz_resume_vm")
+public func vz_resume_vm(
+    _ handle: UnsafeMutableRawPointer?,
+    _ outError: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?
+) -> Int32 {
+    return setPaused(handle, resume: true, outError)
+}
+
+@_cdecl("vz_set_balloon_target")
+public func vz_set_balloon_target(
+    _ handle: UnsafeMutableRawPointer?,
+    _ targetMB: UInt64,
+    _ outError: UnsafeMutablePointer<UnsafeMutablePointer<CChar>?>?
+) -> Int32 {
+    guard let instance = lookupVM(handle) else {
+        setError(outError, "Invalid VM handle")
+        return -1
+    }
+
+    var opError: String? = nil
+    instance.queue.sync {
+        guard let balloon = instance.vm.memoryBalloonDevices.first
+            as? VZVirtioTraditionalMemoryBalloonDevice
+        else {
+            opError = "VM has no memory balloon device"
+            return
+        }
+        // VZ rounds to whole MiB and clamps to the configured memory size.
+        balloon.targetVirtualMachineMemorySize = targetMB * 1024 * 1024
+    }
+    if let err = opError {
+        setError(outError, err)
+        return -1
+    }
+    return 0
+}
+
 @_cdecl("vz_destroy_vm")
 public func vz_destroy_vm(
     _ handle: UnsafeMutableRawPointer?,
@@ -29,6 +29,13 @@ pub struct VZSharedDir {
     pub read_only: bool,
 }
 
+/// Descriptor for an additional disk passed to the bridge.
+#[repr(C)]
+pub struct VZDiskSpec {
+    pub path: *const c_char,
+    pub read_only: bool,
+}
+
 /// VM configuration passed to the bridge.
 #[repr(C)]
 pub struct VZVMConfig {
@@ -43,6 +50,15 @@ pub struct VZVMConfig {
     pub enable_vsock: bool,
     pub shared_dirs: *const VZSharedDir,
     pub shared_dirs_count: u32,
+    pub boot_mode: u32,
+    pub efi_variable_store_path: *const c_char,
+    pub iso_path: *const c_char,
+    pub extra_disks: *const VZDiskSpec,
+    pub extra_disks_count: u32,
+    pub mac_address: *const c_char,
+    pub gpu: bool,
+    pub console_read_fd: i32,
+    pub console_write_fd: i32,
 }
 
 // ---------------------------------------------------------------------------
@@ -80,6 +96,16 @@ extern "C" {
 
     pub fn vz_stop_vm(handle: VZVMHandle, timeout_secs: f64, out_error: *mut *mut c_char) -> i32;
 
+    pub fn vz_pause_vm(handle: VZVMHandle, out_error: *mut *mut c_char) -> i32;
+
+    pub fn vz_resume_vm(handle: VZVMHandle, out_error: *mut *mut c_char) -> i32;
+
+    pub fn vz_set_balloon_target(
+        handle: VZVMHandle,
+        target_mb: u64,
+        out_error: *mut *mut c_char,
+    ) -> i32;
+
     pub fn vz_destroy_vm(handle: VZVMHandle, out_error: *mut *mut c_char) -> i32;
 
     pub fn vz_vm_state(handle: VZVMHandle) -> i32;
@@ -140,6 +166,21 @@ impl VzState {
             _ => Self::Unknown,
         }
     }
+
+    /// Lower-case name reported over the control socket.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stopped => "stopped",
+            Self::Running => "running",
+            Self::Paused => "paused",
+            Self::Error => "error",
+            Self::Starting => "starting",
+            Self::Pausing => "pausing",
+            Self::Resuming => "resuming",
+            Self::Stopping => "stopping",
+            Self::Unknown => "unknown",
+        }
+    }
 }
 
 /// Owned handle to a bridge VM.  Calls `vz_destroy_vm` on drop.
@@ -181,6 +222,37 @@ impl VmHandle {
         Ok(())
     }
 
+    /// Pause the VM's vCPUs.
+    pub fn pause(&self) -> Result<(), String> {
+        let mut err: *mut c_char = ptr::null_mut();
+        let rc = unsafe { vz_pause_vm(self.raw, &mut err) };
+        if rc != 0 {
+            return Err(take_error(err).unwrap_or_else(|| "unknown pause error".into()));
+        }
+        Ok(())
+    }
+
+    /// Resume a paused VM.
+    pub fn resume(&self) -> Result<(), String> {
+        let mut err: *mut c_char = ptr::null_mut();
+        let rc = unsafe { vz_resume_vm(self.raw, &mut err) };
+        if rc != 0 {
+            return Err(take_error(err).unwrap_or_else(|| "unknown resume error".into()));
+        }
+        Ok(())
+    }
+
+    /// Inflate or deflate the memory balloon towards `target_mb` of guest
+    /// memory.
+    pub fn set_balloon_target(&self, target_mb: u64) -> Result<(), String> {
+        let mut err: *mut c_char = ptr::null_mut();
+        let rc = unsafe { vz_set_balloon_target(self.raw, target_mb, &mut err) };
+        if rc != 0 {
+            return Err(take_error(err).unwrap_or_else(|| "unknown balloon error".into()));
+        }
+        Ok(())
+    }
+
     /// Read console output starting at `offset`. Returns bytes read.
     /// The console output comes from the serial port log file configured
     /// when the VM was created.
@@ -261,13 +333,64 @@ pub struct SharedDirFFI {
     pub read_only: bool,
 }
 
+/// How the VM boots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootMode {
+    /// `VZLinuxBootLoader` with an external kernel + initrd.
+    Linux,
+    /// `VZEFIBootLoader` with an NVRAM variable store; boots the disk's
+    /// bootloader (GRUB, systemd-boot), so stock cloud images work as-is.
+    Efi,
+}
+
+impl BootMode {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw {
+            "linux" => Ok(Self::Linux),
+            "efi" => Ok(Self::Efi),
+            other => Err(format!(
+                "Invalid --boot-mode '{}', expected 'linux' or 'efi'",
+                other
+            )),
+        }
+    }
+
+    fn as_raw(self) -> u32 {
+        match self {
+            Self::Linux => 0,
+            Self::Efi => 1,
+        }
+    }
+}
+
+/// Additional disk, holding an owned CString like [`SharedDirFFI`].
+pub struct DiskSpecFFI {
+    pub path: CString,
+    pub read_only: bool,
+}
+
 /// Configuration for creating a VM via the bridge.
 pub struct VmCreateConfig {
-    pub kernel_path: String,
+    pub boot_mode: BootMode,
+    /// Required for [`BootMode::Linux`].
+    pub kernel_path: Option<String>,
+    /// Required for [`BootMode::Efi`]; created on first boot.
+    pub efi_variable_store_path: Option<String>,
+    /// Installer ISO attached read-only as a USB mass storage device.
+    pub iso_path: Option<String>,
+    /// Disks attached after `disk_path`.
+    pub extra_disks: Vec<DiskSpecFFI>,
+    /// Fixed NIC MAC address; random when `None`.
+    pub mac_address: Option<String>,
+    /// Attach a virtio-gpu device.
+    pub gpu: bool,
     pub initrd_path: Option<String>,
     pub cmdline: String,
     pub disk_path: String,
     pub console_log_path: Option<String>,
+    /// Serial console `(guest reads, guest writes)` descriptors; the console
+    /// goes to `console_log_path` when `None`.
+    pub console_fds: Option<(i32, i32)>,
     pub cpus: u32,
     pub memory_mb: u64,
     pub rosetta: bool,
@@ -277,8 +400,37 @@ pub struct VmCreateConfig {
 
 /// Create and start a VM through the Swift bridge.
 pub fn create_and_start_vm(cfg: &VmCreateConfig) -> Result<VmHandle, String> {
-    let kernel = CString::new(cfg.kernel_path.as_str())
+    let kernel = cfg
+        .kernel_path
+        .as_ref()
+        .map(|s| CString::new(s.as_str()))
+        .transpose()
         .map_err(|e| format!("invalid kernel_path: {}", e))?;
+    let efi_vars = cfg
+        .efi_variable_store_path
+        .as_ref()
+        .map(|s| CString::new(s.as_str()))
+        .transpose()
+        .map_err(|e| format!("invalid efi_variable_store_path: {}", e))?;
+    let iso = cfg
+        .iso_path
+        .as_ref()
+        .map(|s| CString::new(s.as_str()))
+        .transpose()
+        .map_err(|e| format!("invalid iso_path: {}", e))?;
+    let mac = cfg
+        .mac_address
+        .as_ref()
+        .map(|s| CString::new(s.as_str()))
+        .transpose()
+        .map_err(|e| format!("invalid mac_address: {}", e))?;
+    match cfg.boot_mode {
+        BootMode::Linux if kernel.is_none() => return Err("Linux boot requires a kernel".into()),
+        BootMode::Efi if efi_vars.is_none() => {
+            return Err("EFI boot requires a variable store path".into())
+        }
+        _ => {}
+    }
     let initrd = cfg
         .initrd_path
         .as_ref()
@@ -307,8 +459,17 @@ pub fn create_and_start_vm(cfg: &VmCreateConfig) -> Result<VmHandle, String> {
         })
         .collect();
 
+    let c_disks: Vec<VZDiskSpec> = cfg
+        .extra_disks
+        .iter()
+        .map(|d| VZDiskSpec {
+            path: d.path.as_ptr(),
+            read_only: d.read_only,
+        })
+        .collect();
+
     let c_config = VZVMConfig {
-        kernel_path: kernel.as_ptr(),
+        kernel_path: kernel.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
         initrd_path: initrd.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
         cmdline: cmdline.as_ptr(),
         disk_path: disk.as_ptr(),
@@ -323,6 +484,19 @@ pub fn create_and_start_vm(cfg: &VmCreateConfig) -> Result<VmHandle, String> {
             c_dirs.as_ptr()
         },
         shared_dirs_count: c_dirs.len() as u32,
+        boot_mode: cfg.boot_mode.as_raw(),
+        efi_variable_store_path: efi_vars.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+        iso_path: iso.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+        extra_disks: if c_disks.is_empty() {
+            ptr::null()
+        } else {
+            c_disks.as_ptr()
+        },
+        extra_disks_count: c_disks.len() as u32,
+        mac_address: mac.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+        gpu: cfg.gpu,
+        console_read_fd: cfg.console_fds.map_or(-1, |(read, _)| read),
+        console_write_fd: cfg.console_fds.map_or(-1, |(_, write)| write),
     };
 
     let mut err: *mut c_char = ptr::null_mut();
@@ -42,15 +42,30 @@ fn main() {
 #[cfg(target_os = "macos")]
 #[derive(Debug, Clone)]
 struct Args {
-    kernel: std::path::PathBuf,
+    boot_mode: ffi::BootMode,
+    kernel: Option<std::path::PathBuf>,
+    /// EFI variable store; defaults to `<disk>.efivars`.
+    efi_vars: Option<std::path::PathBuf>,
+    /// Installer ISO attached read-only for interactive OS installs.
+    iso: Option<std::path::PathBuf>,
+    /// Additional disks in "path[:ro]" format.
+    extra_disks: Vec<String>,
+    /// Fixed NIC MAC address.
+    mac: Option<String>,
     initrd: Option<std::path::PathBuf>,
     disk: std::path::PathBuf,
     cpus: u32,
     memory_mb: u64,
     cmdline: String,
     ready_file: Option<std::path::PathBuf>,
+    /// Unix socket for the JSON-RPC control channel.
+    control_socket: Option<std::path::PathBuf>,
     console_log: Option<std::path::PathBuf>,
+    /// Unix socket relaying the serial console interactively.
+    console_socket: Option<std::path::PathBuf>,
     rosetta: bool,
+    /// Attach a virtio-gpu device.
+    gpu: bool,
     /// Shared directories in "tag:host_path[:ro]" format.
     shared_dirs: Vec<String>,
     /// Vsock forwards in "guest_port:unix_socket_path" format.
@@ -68,9 +83,10 @@ struct Args {
 #[cfg(target_os = "macos")]
 impl Args {
     fn usage() -> &'static str {
-        "Usage:\n  cratebay-vz --kernel <path> --disk <path> --cpus <n> --memory-mb <n> \
+        "Usage:\n  cratebay-vz [--boot-mode linux|efi] [--kernel <path>] [--efi-vars <path>] \
+         --disk <path> --cpus <n> --memory-mb <n> [--iso <path>] [--extra-disk path[:ro]] [--mac xx:xx:xx:xx:xx:xx] \
          [--initrd <path>] [--cmdline <str>] [--ready-file <path>] \
-         [--console-log <path>] [--rosetta] [--share tag:host_path[:ro]] \
+         [--control-socket <path>] [--console-log <path>] [--console-socket <path>] [--rosetta] [--gpu] [--share tag:host_path[:ro]] \
          [--vsock-forward guest_port:unix_socket_path] \
          [--tcp-forward guest_port:unix_socket_path] \
          [--reverse-tcp-forward bind_host:bind_port=unix_socket_path] \
@@ -79,15 +95,23 @@ impl Args {
     }
 
     fn parse() -> Result<Self, String> {
+        let mut boot_mode = ffi::BootMode::Linux;
         let mut kernel: Option<std::path::PathBuf> = None;
+        let mut efi_vars: Option<std::path::PathBuf> = None;
+        let mut iso: Option<std::path::PathBuf> = None;
+        let mut extra_disks: Vec<String> = Vec::new();
+        let mut mac: Option<String> = None;
         let mut initrd: Option<std::path::PathBuf> = None;
         let mut disk: Option<std::path::PathBuf> = None;
         let mut cpus: Option<u32> = None;
         let mut memory_mb: Option<u64> = None;
         let mut cmdline: Option<String> = None;
         let mut ready_file: Option<std::path::PathBuf> = None;
+        let mut control_socket: Option<std::path::PathBuf> = None;
         let mut console_log: Option<std::path::PathBuf> = None;
+        let mut console_socket: Option<std::path::PathBuf> = None;
         let mut rosetta = false;
+        let mut gpu = false;
         let mut shared_dirs: Vec<String> = Vec::new();
         let mut vsock_forwards: Vec<String> = Vec::new();
         let mut tcp_forwards: Vec<String> = Vec::new();
@@ -101,6 +125,38 @@ impl Args {
                 "--help" | "-h" => {
                     return Err(Self::usage().to_string());
                 }
+                "--boot-mode" => {
+                    boot_mode = ffi::BootMode::parse(
+                        &it.next()
+                            .ok_or_else(|| "--boot-mode requires a value".to_string())?,
+                    )?;
+                }
+                "--efi-vars" => {
+                    efi_vars = Some(
+                        it.next()
+                            .ok_or_else(|| "--efi-vars requires a value".to_string())?
+                            .into(),
+                    );
+                }
+                "--iso" => {
+                    iso = Some(
+                        it.next()
+                            .ok_or_else(|| "--iso requires a value".to_string())?
+                            .into(),
+                    );
+                }
+                "--extra-disk" => {
+                    extra_disks.push(
+                        it.next()
+                            .ok_or_else(|| "--extra-disk requires a value".to_string())?,
+                    );
+                }
+                "--mac" => {
+                    mac = Some(
+                        it.next()
+                            .ok_or_else(|| "--mac requires a value".to_string())?,
+                    );
+                }
                 "--kernel" => {
                     kernel = Some(
                         it.next()
@@ -153,6 +209,13 @@ impl Args {
                             .into(),
                     );
                 }
+                "--control-socket" => {
+                    control_socket = Some(
+                        it.next()
+                            .ok_or_else(|| "--control-socket requires a value".to_string())?
+                            .into(),
+                    );
+                }
                 "--console-log" => {
                     console_log = Some(
                         it.next()
@@ -160,9 +223,19 @@ impl Args {
                             .into(),
                     );
                 }
+                "--console-socket" => {
+                    console_socket = Some(
+                        it.next()
+                            .ok_or_else(|| "--console-socket requires a value".to_string())?
+                            .into(),
+                    );
+                }
                 "--rosetta" => {
                     rosetta = true;
                 }
+                "--gpu" => {
+                    gpu = true;
+                }
                 "--share" => {
                     shared_dirs.push(
                         it.next()
@@ -203,22 +276,36 @@ impl Args {
             }
         }
 
-        let kernel = kernel.ok_or_else(|| "Missing --kernel".to_string())?;
+        if boot_mode == ffi::BootMode::Linux && kernel.is_none() {
+            return Err("Missing --kernel".to_string());
+        }
         let disk = disk.ok_or_else(|| "Missing --disk".to_string())?;
+        let efi_vars = match boot_mode {
+            ffi::BootMode::Efi => Some(efi_vars.unwrap_or_else(|| disk.with_extension("efivars"))),
+            ffi::BootMode::Linux => None,
+        };
         let cpus = cpus.ok_or_else(|| "Missing --cpus".to_string())?;
         let memory_mb = memory_mb.ok_or_else(|| "Missing --memory-mb".to_string())?;
         let cmdline = cmdline.unwrap_or_else(|| "console=hvc0".to_string());
 
         Ok(Self {
+            boot_mode,
             kernel,
+            efi_vars,
+            iso,
+            extra_disks,
+            mac,
             initrd,
             disk,
             cpus,
             memory_mb,
             cmdline,
             ready_file,
+            control_socket,
             console_log,
+            console_socket,
             rosetta,
+            gpu,
             shared_dirs,
             vsock_forwards,
             tcp_forwards,
@@ -232,7 +319,7 @@ impl Args {
 #[cfg(target_os = "macos")]
 fn parse_shared_dir(spec: &str) -> Result<ffi::SharedDirFFI, String> {
     // Format: "tag:host_path" or "tag:host_path:ro"
-    // Tag is guaranteed to not contain colons (validated by mount_virtiofs).
+    // Tag is guaranteed to not contain colons (validated by `runtime::validate_shared_dirs`).
     // We split on the first colon to get the tag, then check if the remainder
     // ends with ":ro" to determine read-only mode.
     let first_colon = spec.find(':').ok_or_else(|| {
@@ -268,16 +355,77 @@ fn parse_shared_dir(spec: &str) -> Result<ffi::SharedDirFFI, String> {
     })
 }
 
+#[cfg(target_os = "macos")]
+fn parse_extra_disk(spec: &str) -> Result<ffi::DiskSpecFFI, String> {
+    // Format: "path" or "path:ro"
+    let (path, read_only) = match spec.strip_suffix(":ro") {
+        Some(stripped) => (stripped, true),
+        None => (spec, false),
+    };
+    if path.is_empty() {
+        return Err(format!(
+            "Invalid --extra-disk format '{}', expected 'path[:ro]'",
+            spec
+        ));
+    }
+    let path = std::ffi::CString::new(path).map_err(|e| format!("invalid disk path: {}", e))?;
+    Ok(ffi::DiskSpecFFI { path, read_only })
+}
+
+/// A pipe as `(read end, write end)`.
+#[cfg(target_os = "macos")]
+fn console_pipe() -> Result<(std::fs::File, std::fs::File), String> {
+    use std::os::fd::FromRawFd;
+
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(format!(
+            "Failed to create console pipe: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    // SAFETY: pipe() returned two fresh descriptors we now own.
+    Ok(unsafe {
+        (
+            std::fs::File::from_raw_fd(fds[0]),
+            std::fs::File::from_raw_fd(fds[1]),
+        )
+    })
+}
+
 #[cfg(target_os = "macos")]
 fn run(args: Args) -> Result<(), String> {
+    use std::os::fd::AsRawFd;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
 
     let kernel_path = args
         .kernel
-        .to_str()
-        .ok_or_else(|| "Kernel path is not valid UTF-8".to_string())?
-        .to_string();
+        .as_ref()
+        .map(|p| {
+            p.to_str()
+                .ok_or_else(|| "Kernel path is not valid UTF-8".to_string())
+                .map(|s| s.to_string())
+        })
+        .transpose()?;
+    let efi_variable_store_path = args
+        .efi_vars
+        .as_ref()
+        .map(|p| {
+            p.to_str()
+                .ok_or_else(|| "EFI variable store path is not valid UTF-8".to_string())
+                .map(|s| s.to_string())
+        })
+        .transpose()?;
+    let iso_path = args
+        .iso
+        .as_ref()
+        .map(|p| {
+            p.to_str()
+                .ok_or_else(|| "ISO path is not valid UTF-8".to_string())
+                .map(|s| s.to_string())
+        })
+        .transpose()?;
     let disk_path = args
         .disk
         .to_str()
@@ -309,12 +457,41 @@ fn run(args: Args) -> Result<(), String> {
         .map(|s| parse_shared_dir(s))
         .collect::<Result<Vec<_>, _>>()?;
 
+    let extra_disks: Vec<ffi::DiskSpecFFI> = args
+        .extra_disks
+        .iter()
+        .map(|s| parse_extra_disk(s))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Interactive console: the guest uses one end of each pipe and the
+    // console server the other. The guest ends must outlive the VM.
+    let (guest_console, host_console) = match args.console_socket {
+        Some(_) => {
+            let (guest_read, host_write) = console_pipe()?;
+            let (host_read, guest_write) = console_pipe()?;
+            (
+                Some((guest_read, guest_write)),
+                Some((host_read, host_write)),
+            )
+        }
+        None => (None, None),
+    };
+
     let config = ffi::VmCreateConfig {
+        boot_mode: args.boot_mode,
         kernel_path,
+        efi_variable_store_path,
+        iso_path,
+        extra_disks,
+        mac_address: args.mac.clone(),
+        gpu: args.gpu,
         initrd_path,
         cmdline: args.cmdline.clone(),
         disk_path,
         console_log_path,
+        console_fds: guest_console
+            .as_ref()
+            .map(|(read, write)| (read.as_raw_fd(), write.as_raw_fd())),
         cpus: args.cpus,
         memory_mb: args.memory_mb,
         rosetta: args.rosetta,
@@ -398,6 +575,23 @@ fn run(args: Args) -> Result<(), String> {
         }
     }
 
+    if let Some(path) = args.control_socket.clone() {
+        let thread = start_control_server(handle.clone(), path, shutdown_requested.clone())?;
+        forward_threads.push(thread);
+    }
+
+    if let (Some(path), Some((host_read, host_write))) = (args.console_socket.clone(), host_console)
+    {
+        let thread = start_console_server(
+            host_read,
+            host_write,
+            args.console_log.clone(),
+            path,
+            shutdown_requested.clone(),
+        )?;
+        forward_threads.push(thread);
+    }
+
     // Signal readiness after forwards are bound.
     if let Some(path) = args.ready_file.as_ref() {
         let _ = std::fs::create_dir_all(path.parent().unwrap_or_else(|| std::path::Path::new(".")));
@@ -405,10 +599,10 @@ fn run(args: Args) -> Result<(), String> {
             .map_err(|e| format!("Failed to write ready file: {}", e))?;
     }
 
-    // Wait for SIGTERM or VM to stop on its own.
+    // Wait for SIGTERM / a control `stop`, or the VM to stop on its own.
     loop {
         if shutdown_requested.load(Ordering::SeqCst) {
-            tracing::info!("SIGTERM received, initiating graceful ACPI shutdown...");
+            tracing::info!("Shutdown requested, initiating graceful ACPI shutdown...");
             match handle.stop(15.0) {
                 Ok(()) => tracing::info!("VM stopped gracefully"),
                 Err(e) => tracing::warn!("VM stop error: {}", e),
@@ -661,6 +855,239 @@ fn start_vsock_forward(
     }))
 }
 
+/// Serve the JSON-RPC control channel defined in
+/// `cratebay_core::runtime::control`, one request per line.
+#[cfg(target_os = "macos")]
+fn start_control_server(
+    handle: std::sync::Arc<ffi::VmHandle>,
+    sock_path: std::path::PathBuf,
+    shutdown_requested: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) -> Result<std::thread::JoinHandle<()>, String> {
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixListener;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    use cratebay_core::runtime::control::{
+        self, error_codes, ControlCall, ControlError, RunnerStatus,
+    };
+
+    if let Some(parent) = sock_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create socket dir {}: {}", parent.display(), e))?;
+    }
+    let _ = std::fs::remove_file(&sock_path);
+
+    let listener = UnixListener::bind(&sock_path)
+        .map_err(|e| format!("Failed to bind unix socket {}: {}", sock_path.display(), e))?;
+    // Only the owning user may pause or stop the VM.
+    std::fs::set_permissions(&sock_path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict {}: {}", sock_path.display(), e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to set socket nonblocking: {}", e))?;
+
+    tracing::info!("control socket enabled: {}", sock_path.display());
+
+    let stop_flag = shutdown_requested.clone();
+    let dispatch = move |call: ControlCall| -> Result<serde_json::Value, ControlError> {
+        let invalid_state = |e: String| ControlError::new(error_codes::INVALID_STATE, e);
+        match call {
+            ControlCall::Pause => handle.pause().map_err(invalid_state)?,
+            ControlCall::Resume => handle.resume().map_err(invalid_state)?,
+            ControlCall::Stop => shutdown_requested.store(true, Ordering::SeqCst),
+            ControlCall::SetBalloon { target_mb } => handle
+                .set_balloon_target(target_mb)
+                .map_err(invalid_state)?,
+            ControlCall::AddShare { .. } => {
+                // VZ fixes the directory sharing devices at configuration
+                // time; a share can only be added by restarting the runner.
+                return Err(ControlError::new(
+                    error_codes::UNSUPPORTED,
+                    "Virtualization.framework cannot add shares to a running VM",
+                ));
+            }
+            ControlCall::State => {
+                let status = RunnerStatus {
+                    state: handle.state().as_str().to_string(),
+                    pid: std::process::id(),
+                    protocol_version: control::PROTOCOL_VERSION,
+                };
+                return serde_json::to_value(status)
+                    .map_err(|e| ControlError::new(error_codes::INTERNAL_ERROR, e.to_string()));
+            }
+        }
+        Ok(serde_json::Value::Null)
+    };
+
+    Ok(std::thread::spawn(move || {
+        while !stop_flag.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _addr)) => {
+                    if stream.set_nonblocking(false).is_err() {
+                        continue;
+                    }
+                    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+                    let mut line = String::new();
+                    let Ok(reader) = stream.try_clone() else {
+                        continue;
+                    };
+                    if BufReader::new(reader).read_line(&mut line).is_err() || line.is_empty() {
+                        continue;
+                    }
+                    let response = control::handle_line(line.trim(), &dispatch);
+                    if let Ok(mut out) = serde_json::to_string(&response) {
+                        out.push('\n');
+                        let _ = (&stream).write_all(out.as_bytes());
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "control socket accept failed on {}: {}",
+                        sock_path.display(),
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+
+        let _ = std::fs::remove_file(&sock_path);
+    }))
+}
+
+/// Relay the serial console between the VM and `sock_path`, one client at
+/// a time, and append guest output to `console_log`. Typing into the
+/// console is only possible through the socket.
+#[cfg(target_os = "macos")]
+fn start_console_server(
+    guest_output: std::fs::File,
+    guest_input: std::fs::File,
+    console_log: Option<std::path::PathBuf>,
+    sock_path: std::path::PathBuf,
+    shutdown_requested: std::sync::Arc<std::sync::atomic::AtomicBool>,
+) -> Result<std::thread::JoinHandle<()>, String> {
+    use std::io::{self, Read, Write};
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    if let Some(parent) = sock_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create socket dir {}: {}", parent.display(), e))?;
+    }
+    let _ = std::fs::remove_file(&sock_path);
+
+    let listener = UnixListener::bind(&sock_path)
+        .map_err(|e| format!("Failed to bind unix socket {}: {}", sock_path.display(), e))?;
+    // The console is a root shell on most images; keep it to the owner.
+    std::fs::set_permissions(&sock_path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict {}: {}", sock_path.display(), e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to set socket nonblocking: {}", e))?;
+
+    tracing::info!("console socket enabled: {}", sock_path.display());
+
+    // The attached client, tagged so a leaving client does not detach its
+    // successor.
+    let client: Arc<Mutex<Option<(u64, UnixStream)>>> = Arc::new(Mutex::new(None));
+
+    let output_client = client.clone();
+    let mut log = console_log.and_then(|path| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .ok()
+    });
+    std::thread::spawn(move || {
+        let mut guest_output = guest_output;
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = match guest_output.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            if let Some(log) = log.as_mut() {
+                let _ = log.write_all(&buf[..n]);
+            }
+            let mut slot = output_client.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some((_, stream)) = slot.as_mut() {
+                if stream.write_all(&buf[..n]).is_err() {
+                    *slot = None;
+                }
+            }
+        }
+    });
+
+    let guest_input = Arc::new(Mutex::new(guest_input));
+    Ok(std::thread::spawn(move || {
+        let mut next_id = 0u64;
+        while !shutdown_requested.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _addr)) => {
+                    if stream.set_nonblocking(false).is_err() {
+                        continue;
+                    }
+                    let mut slot = client.lock().unwrap_or_else(|e| e.into_inner());
+                    if slot.is_some() {
+                        let _ = (&stream).write_all(b"Console is attached elsewhere.\r\n");
+                        continue;
+                    }
+                    let Ok(writer) = stream.try_clone() else {
+                        continue;
+                    };
+                    next_id += 1;
+                    let id = next_id;
+                    *slot = Some((id, writer));
+                    drop(slot);
+
+                    let client = client.clone();
+                    let guest_input = guest_input.clone();
+                    std::thread::spawn(move || {
+                        let mut stream = stream;
+                        let mut buf = [0u8; 1024];
+                        loop {
+                            let n = match stream.read(&mut buf) {
+                                Ok(0) | Err(_) => break,
+                                Ok(n) => n,
+                            };
+                            let mut input = guest_input.lock().unwrap_or_else(|e| e.into_inner());
+                            if input.write_all(&buf[..n]).is_err() {
+                                break;
+                            }
+                        }
+                        let mut slot = client.lock().unwrap_or_else(|e| e.into_inner());
+                        if slot.as_ref().is_some_and(|(current, _)| *current == id) {
+                            *slot = None;
+                        }
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "console socket accept failed on {}: {}",
+                        sock_path.display(),
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+
+        let _ = std::fs::remove_file(&sock_path);
+    }))
+}
+
 #[cfg(target_os = "macos")]
 fn start_tcp_forward(
     guest_port: u32,
@@ -377,7 +377,7 @@ else
 fi
 
 echo ""
-echo "== Resolve Alpine package dependencies (docker-engine + e2fsprogs) =="
+echo "== Resolve Alpine package dependencies (docker-engine + e2fsprogs + e2fsprogs-extra + criu) =="
 python3 - "$alpine_version" "$arch" >"$tmp_dir/pkglist.txt" <<'PY'
 import io
 import os
@@ -442,7 +442,7 @@ def resolve(tok: str):
         return sorted(provides[t])[0]
     return None
 
-roots = ["docker-engine", "e2fsprogs", "containerd-ctr"]
+roots = ["docker-engine", "e2fsprogs", "e2fsprogs-extra", "containerd-ctr", "criu"]
 want = set()
 stack = list(roots)
 
@@ -745,6 +745,32 @@ if [ -b /dev/vda ]; then
     mkfs.ext4 -F /dev/vda >/dev/null 2>&1 || mkfs.ext4 /dev/vda >/dev/null 2>&1 || true
     mount -t ext4 -o rw,noatime /dev/vda /var/lib/docker 2>/dev/null || true
   fi
+  # Grow the filesystem if the host resized disk.raw (`cratebay runtime resize`).
+  # resize2fs is in e2fsprogs-extra; a no-op when the size is unchanged.
+  if ! resize_out="$(resize2fs /dev/vda 2>&1)"; then
+    log "resize2fs /dev/vda failed: $(printf '%s' "$resize_out" | tail -n 1)"
+  fi
+fi
+
+# Rosetta for Linux (macOS hosts started with `--rosetta`): run x86_64
+# binaries through the host-provided translator, and keep its ahead-of-time
+# translation cache on the persistent disk so it survives reboots.
+if [ "$(cmdline_value cratebay_rosetta || true)" = "1" ]; then
+  mkdir -p /mnt/rosetta
+  if mount -t virtiofs rosetta /mnt/rosetta 2>/dev/null; then
+    mount -t binfmt_misc binfmt_misc /proc/sys/fs/binfmt_misc 2>/dev/null || true
+    if [ ! -e /proc/sys/fs/binfmt_misc/rosetta ]; then
+      printf '%s' ':rosetta:M::\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00\x02\x00\x3e\x00:\xff\xff\xff\xff\xff\xfe\xfe\x00\xff\xff\xff\xff\xff\xff\xff\xff\xfe\xff\xff\xff:/mnt/rosetta/rosetta:CF' \
+        >/proc/sys/fs/binfmt_misc/register 2>/dev/null || log "WARN: failed to register rosetta binfmt"
+    fi
+    if [ -x /mnt/rosetta/rosettad ]; then
+      mkdir -p /var/lib/docker/rosettad
+      /mnt/rosetta/rosettad daemon /var/lib/docker/rosettad >/dev/null 2>&1 &
+    fi
+    log "rosetta enabled"
+  else
+    log "WARN: rosetta share not available"
+  fi
 fi
 
 # Ensure CA bundle exists (generated by the package's triggers on a real install).
@@ -777,19 +803,29 @@ export DOCKER_RAMDISK=1
 
 mkdir -p /etc/docker
 daemon_config=/etc/docker/daemon.json
-if [ -n "$runtime_http_proxy" ]; then
-  cat >"$daemon_config" <<EOF
-{
-  "proxies": {
-    "http-proxy": "http://${runtime_http_proxy}",
-    "https-proxy": "http://${runtime_http_proxy}",
-    "no-proxy": "127.0.0.1,localhost,::1"
-  }
-}
-EOF
-else
-  printf '{}\n' >"$daemon_config"
+# Docker Hub mirrors and registry proxy from the host's config.toml. An
+# explicit registry proxy wins over the runtime HTTP proxy bridge.
+# Experimental features enable the checkpoint API (CRIU).
+registry_mirrors="$(cmdline_value cratebay_registry_mirrors || true)"
+daemon_proxy="$(cmdline_value cratebay_registry_proxy || true)"
+if [ -z "$daemon_proxy" ] && [ -n "$runtime_http_proxy" ]; then
+  daemon_proxy="http://${runtime_http_proxy}"
 fi
+{
+  printf '{\n'
+  printf '  "experimental": true'
+  if [ -n "$registry_mirrors" ]; then
+    printf ',\n  "registry-mirrors": ["%s"]' "$(printf '%s' "$registry_mirrors" | sed 's/,/", "/g')"
+  fi
+  if [ -n "$daemon_proxy" ]; then
+    printf ',\n  "proxies": {\n'
+    printf '    "http-proxy": "%s",\n' "$daemon_proxy"
+    printf '    "https-proxy": "%s",\n' "$daemon_proxy"
+    printf '    "no-proxy": "127.0.0.1,localhost,::1"\n'
+    printf '  }'
+  fi
+  printf '\n}\n'
+} >"$daemon_config"
 
 set -- dockerd \
   --config-file="$daemon_config" \
@@ -142,6 +142,7 @@ roots = [
     "procps-ng",
     "util-linux",
     "ca-certificates",
+    "criu",
 ]
 
 want = set()
@@ -212,6 +213,7 @@ mkdir -p \
 
 cat >"$rootfs_dir/etc/docker/daemon.json" <<'JSON'
 {
+  "experimental": true,
   "features": {
     "containerd-snapshotter": false
   }